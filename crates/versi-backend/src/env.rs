@@ -0,0 +1,82 @@
+use std::ffi::OsString;
+
+/// Variables in Versi's own environment that another version manager sets
+/// up, sorted. Versi inherits them when launched from a shell where that
+/// manager is initialized, and they can make `backend` (`fnm` or `nvm`)
+/// look in the wrong place.
+pub fn conflicting_env_vars(backend: &str) -> Vec<String> {
+    conflicting_in(backend, std::env::vars_os().map(|(key, _)| key))
+}
+
+/// Whether `key` can be exported from a POSIX shell: a letter or
+/// underscore followed by letters, digits and underscores.
+pub fn is_valid_env_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn conflicting_in(backend: &str, keys: impl Iterator<Item = OsString>) -> Vec<String> {
+    let other_prefix = match backend {
+        "fnm" => "NVM_",
+        "nvm" => "FNM_",
+        _ => return Vec::new(),
+    };
+    let mut vars: Vec<String> = keys
+        .filter_map(|key| key.into_string().ok())
+        .filter(|key| key.starts_with(other_prefix) || key == "VOLTA_HOME")
+        .collect();
+    vars.sort();
+    vars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(keys: &[&str]) -> impl Iterator<Item = OsString> {
+        keys.iter()
+            .map(OsString::from)
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn finds_the_other_managers_variables() {
+        let env = [
+            "PATH",
+            "NVM_DIR",
+            "NVM_BIN",
+            "FNM_DIR",
+            "VOLTA_HOME",
+            "HOME",
+        ];
+        assert_eq!(
+            conflicting_in("fnm", keys(&env)),
+            ["NVM_BIN", "NVM_DIR", "VOLTA_HOME"]
+        );
+        assert_eq!(conflicting_in("nvm", keys(&env)), ["FNM_DIR", "VOLTA_HOME"]);
+        assert!(conflicting_in("volta", keys(&env)).is_empty());
+    }
+
+    #[test]
+    fn validates_env_keys() {
+        for key in ["PATH", "_private", "npm_config_cache", "A1"] {
+            assert!(is_valid_env_key(key), "{key}");
+        }
+        for key in [
+            "",
+            "1ABC",
+            "A-B",
+            "A B",
+            "A=B",
+            "X;rm -rf ~",
+            "$(id)",
+            "ÄPFEL",
+        ] {
+            assert!(!is_valid_env_key(key), "{key}");
+        }
+    }
+}
@@ -1,10 +1,21 @@
+//! Backend-agnostic traits and types for managing Node.js versions.
+//!
+//! A [`BackendProvider`] detects a version manager such as fnm or nvm and
+//! creates [`VersionManager`]s for it, natively or inside a WSL distro.
+
+mod env;
 mod error;
 mod traits;
 mod types;
+mod update;
 
+pub use env::{conflicting_env_vars, is_valid_env_key};
 pub use error::BackendError;
 pub use traits::{
-    BackendDetection, BackendInfo, BackendProvider, BackendUpdate, ManagerCapabilities,
-    ShellInitOptions, VersionManager,
+    BackendDetection, BackendInfo, BackendProvider, BackendUpdate, CD_HOOK_END, CD_HOOK_START,
+    ManagerCapabilities, ShellInitOptions, VersionManager,
+};
+pub use types::{
+    InstallProgress, InstalledVersion, NodeVersion, RemoteVersion, VersionGroup, VersionParseError,
 };
-pub use types::{InstalledVersion, NodeVersion, RemoteVersion, VersionGroup, VersionParseError};
+pub use update::{BackendUpdateChannel, check_github_update};
@@ -1,9 +1,12 @@
 use async_trait::async_trait;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use tokio::sync::mpsc;
 
 use crate::error::BackendError;
-use crate::types::{InstalledVersion, NodeVersion, RemoteVersion};
+use crate::types::{InstallProgress, InstalledVersion, NodeVersion, RemoteVersion};
+use crate::update::BackendUpdateChannel;
 
+/// What [`BackendProvider::detect`] found on this machine.
 #[derive(Debug, Clone)]
 pub struct BackendDetection {
     pub found: bool,
@@ -11,6 +14,38 @@ pub struct BackendDetection {
     pub version: Option<String>,
     pub in_path: bool,
     pub data_dir: Option<PathBuf>,
+    /// Where the binary was found, e.g. `PATH` or `Homebrew`.
+    pub source: Option<String>,
+}
+
+impl BackendDetection {
+    /// A backend known to be installed at `path`, for callers that already
+    /// know where it lives and skip [`BackendProvider::detect`].
+    pub fn found(path: impl Into<PathBuf>) -> Self {
+        Self {
+            found: true,
+            path: Some(path.into()),
+            version: None,
+            in_path: false,
+            data_dir: None,
+            source: None,
+        }
+    }
+
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    pub fn with_data_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.data_dir = Some(dir.into());
+        self
+    }
+
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -20,29 +55,82 @@ pub struct BackendUpdate {
     pub release_url: String,
 }
 
+/// Finds, installs and creates [`VersionManager`]s for one backend, such as
+/// fnm or nvm.
 #[async_trait]
 pub trait BackendProvider: Send + Sync {
+    /// Short identifier, e.g. `fnm`.
     fn name(&self) -> &'static str;
+    /// Name shown to users, e.g. `fnm (Fast Node Manager)`.
     fn display_name(&self) -> &'static str;
+    /// Text that identifies this backend's init line in a shell config.
     fn shell_config_marker(&self) -> &str;
+    /// Comment written above the init line added to a shell config.
     fn shell_config_label(&self) -> &str;
+    /// Looks for the backend on this machine, honouring any configured
+    /// binary or data directory.
     async fn detect(&self) -> BackendDetection;
+    /// Installs the backend itself with its official installer.
     async fn install_backend(&self) -> Result<(), BackendError>;
+    /// Returns the release `channel` offers when it is newer than
+    /// `current_version`.
     async fn check_for_update(
         &self,
-        client: &reqwest::Client,
+        github: &versi_core::GitHubClient,
         current_version: &str,
+        channel: &BackendUpdateChannel,
     ) -> Result<Option<BackendUpdate>, String>;
+    /// Upgrades the native install to `version` the way it was installed,
+    /// and says how. Output is sent to `progress` a line at a time where
+    /// the upgrade can show it.
+    async fn update_backend(
+        &self,
+        _version: &str,
+        _progress: mpsc::Sender<String>,
+    ) -> Result<String, BackendError> {
+        Err(BackendError::Unsupported(format!(
+            "updating {} from Versi",
+            self.name()
+        )))
+    }
+    /// Upgrades the backend at `backend_path` inside a WSL distro to
+    /// `version`, and says how.
+    async fn update_backend_in_wsl(
+        &self,
+        _distro: &str,
+        _backend_path: &str,
+        _version: &str,
+        _progress: mpsc::Sender<String>,
+    ) -> Result<String, BackendError> {
+        Err(BackendError::Unsupported(format!(
+            "updating {} inside WSL",
+            self.name()
+        )))
+    }
+    /// Creates a manager for a native install from a detection result.
     fn create_manager(&self, detection: &BackendDetection) -> Box<dyn VersionManager>;
+    /// Creates a manager that runs `backend_path` inside a WSL distro.
     fn create_manager_for_wsl(
         &self,
         distro: String,
         backend_path: String,
     ) -> Box<dyn VersionManager>;
+    /// Creates a manager that runs `backend_path` through the bash of Git
+    /// Bash or MSYS2 at `bash`.
+    fn create_manager_for_msys(
+        &self,
+        bash: String,
+        backend_path: String,
+    ) -> Box<dyn VersionManager>;
 
+    /// Paths to probe for the backend inside WSL distros.
     fn wsl_search_paths(&self) -> Vec<&'static str> {
         vec![]
     }
+    /// Paths to probe for the backend from Git Bash or MSYS2.
+    fn msys_search_paths(&self) -> Vec<&'static str> {
+        vec![]
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -53,6 +141,8 @@ pub struct ManagerCapabilities {
     pub supports_auto_switch: bool,
     pub supports_corepack: bool,
     pub supports_resolve_engines: bool,
+    /// Can install versions into a directory shared by every user.
+    pub supports_system_wide: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -62,15 +152,39 @@ pub struct BackendInfo {
     pub version: Option<String>,
     pub data_dir: Option<PathBuf>,
     pub in_path: bool,
+    /// Where the binary was found, e.g. `PATH` or `Homebrew`.
+    pub source: Option<String>,
 }
 
+/// Comment lines around the shell code a backend adds to switch versions on
+/// `cd`, so the block can be found again to replace or remove it.
+pub const CD_HOOK_START: &str = "# >>> versi: switch Node.js versions on cd >>>";
+pub const CD_HOOK_END: &str = "# <<< versi: switch Node.js versions on cd <<<";
+
 #[derive(Debug, Clone, Default)]
 pub struct ShellInitOptions {
+    /// Switch to the project's version on `cd`: fnm's `--use-on-cd`, or a
+    /// hook block for backends without one.
     pub use_on_cd: bool,
     pub resolve_engines: bool,
     pub corepack_enabled: bool,
 }
 
+impl ShellInitOptions {
+    /// The options an existing shell configuration was set up with.
+    pub fn detect(content: &str) -> Self {
+        Self {
+            use_on_cd: content.contains("--use-on-cd") || content.contains(CD_HOOK_START),
+            resolve_engines: content.contains("--resolve-engines"),
+            corepack_enabled: content.contains("--corepack-enabled"),
+        }
+    }
+}
+
+/// Manages the Node.js versions of one backend in one environment.
+///
+/// Versions are passed as the backend prints them, e.g. `v22.11.0`.
+/// Operations a backend cannot perform return [`BackendError::Unsupported`].
 #[async_trait]
 pub trait VersionManager: Send + Sync + VersionManagerClone {
     fn name(&self) -> &'static str;
@@ -81,14 +195,28 @@ pub trait VersionManager: Send + Sync + VersionManagerClone {
 
     async fn list_installed(&self) -> Result<Vec<InstalledVersion>, BackendError>;
 
+    /// Every version available to install.
     async fn list_remote(&self) -> Result<Vec<RemoteVersion>, BackendError>;
 
+    /// Version active in the most recently used shell, which differs from the
+    /// default after `use` or use-on-cd. `None` when the backend cannot tell.
     async fn current_version(&self) -> Result<Option<NodeVersion>, BackendError>;
 
+    /// Version new shells start with, if one is set.
     async fn default_version(&self) -> Result<Option<NodeVersion>, BackendError>;
 
     async fn install(&self, version: &str) -> Result<(), BackendError>;
 
+    /// Like [`install`](Self::install), sending download progress to
+    /// `progress` when the backend reports it.
+    async fn install_with_progress(
+        &self,
+        version: &str,
+        _progress: mpsc::Sender<InstallProgress>,
+    ) -> Result<(), BackendError> {
+        self.install(version).await
+    }
+
     async fn uninstall(&self, version: &str) -> Result<(), BackendError>;
 
     async fn set_default(&self, version: &str) -> Result<(), BackendError>;
@@ -97,6 +225,39 @@ pub trait VersionManager: Send + Sync + VersionManagerClone {
         Err(BackendError::Unsupported("use_version".to_string()))
     }
 
+    /// Runs `command` with the given installed version on `PATH` and returns its stdout.
+    async fn exec(&self, _version: &str, _command: &[&str]) -> Result<String, BackendError> {
+        Err(BackendError::Unsupported("exec".to_string()))
+    }
+
+    /// Like [`exec`](Self::exec), but runs `command` from the `dir` directory.
+    async fn exec_in(
+        &self,
+        _version: &str,
+        _command: &[&str],
+        _dir: &Path,
+    ) -> Result<String, BackendError> {
+        Err(BackendError::Unsupported("exec_in".to_string()))
+    }
+
+    /// Path of the `node` executable of an installed version, when it lives
+    /// on the local filesystem.
+    fn node_binary(&self, _version: &str) -> Option<PathBuf> {
+        None
+    }
+
+    /// Directory shared by every user that versions are installed into,
+    /// when the backend runs in system-wide mode.
+    fn system_wide_dir(&self) -> Option<&Path> {
+        None
+    }
+
+    /// Whether installing, removing or changing the default version has to
+    /// ask for administrator rights.
+    fn needs_elevation(&self) -> bool {
+        false
+    }
+
     async fn list_remote_lts(&self) -> Result<Vec<RemoteVersion>, BackendError> {
         let all = self.list_remote().await?;
         Ok(all
@@ -105,7 +266,12 @@ pub trait VersionManager: Send + Sync + VersionManagerClone {
             .collect())
     }
 
+    /// Line that sets the backend up in `shell` (e.g. `zsh`), or `None` when
+    /// the shell is not supported.
     fn shell_init_command(&self, shell: &str, options: &ShellInitOptions) -> Option<String>;
+
+    /// Extra environment variables passed to every backend command.
+    fn set_env_overrides(&mut self, vars: Vec<(String, String)>);
 }
 
 pub trait VersionManagerClone: Send + Sync {
@@ -100,6 +100,14 @@ pub struct RemoteVersion {
     pub is_latest: bool,
 }
 
+/// How far a version's download has got, as the backend reports it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InstallProgress {
+    pub downloaded: u64,
+    /// `None` when the backend doesn't know the download's size.
+    pub total: Option<u64>,
+}
+
 #[derive(Debug, Clone)]
 pub struct VersionGroup {
     pub major: u32,
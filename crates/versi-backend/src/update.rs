@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+use versi_core::{GitHubClient, is_newer_version};
+
+use crate::BackendUpdate;
+
+/// Which releases of a backend are offered as updates.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BackendUpdateChannel {
+    /// The latest stable release.
+    #[default]
+    Stable,
+    /// The latest release, prereleases included.
+    Prerelease,
+    /// Stay on this version: nothing newer is offered.
+    Pinned(String),
+}
+
+impl BackendUpdateChannel {
+    /// A short description for the settings and About views.
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Stable => "stable releases".to_string(),
+            Self::Prerelease => "prereleases".to_string(),
+            Self::Pinned(version) => format!("pinned to {}", version.trim_start_matches('v')),
+        }
+    }
+}
+
+/// The release of `repo` that `channel` offers over `current_version`, if
+/// any.
+pub async fn check_github_update(
+    github: &GitHubClient,
+    repo: &str,
+    current_version: &str,
+    channel: &BackendUpdateChannel,
+) -> Result<Option<BackendUpdate>, String> {
+    let current = current_version.trim_start_matches('v');
+    let release = match channel {
+        BackendUpdateChannel::Stable => github.latest_release(repo).await?,
+        BackendUpdateChannel::Prerelease => github
+            .releases(repo)
+            .await?
+            .into_iter()
+            .find(|release| !release.draft),
+        BackendUpdateChannel::Pinned(pinned) => {
+            let pinned = pinned.trim_start_matches('v');
+            if !is_newer_version(pinned, current) {
+                return Ok(None);
+            }
+            // Below the pin, e.g. after a downgrade: offer the pinned version.
+            let release = github
+                .releases(repo)
+                .await?
+                .into_iter()
+                .find(|release| release.tag_name.trim_start_matches('v') == pinned)
+                .ok_or_else(|| format!("Release {} was not found", pinned))?;
+            Some(release)
+        }
+    };
+    let Some(release) = release else {
+        return Ok(None);
+    };
+
+    let latest = release.tag_name.trim_start_matches('v');
+    Ok(is_newer_version(latest, current).then(|| BackendUpdate {
+        current_version: current.to_string(),
+        latest_version: latest.to_string(),
+        release_url: release.html_url.clone(),
+    }))
+}
@@ -1,9 +1,12 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
 use tokio::io::AsyncWriteExt;
 use tokio::sync::mpsc;
 
+use crate::update::Packaging;
+
 #[derive(Debug, Clone)]
 pub enum UpdateProgress {
     Downloading { downloaded: u64, total: u64 },
@@ -19,30 +22,64 @@ pub enum ApplyResult {
     ExitForInstaller,
 }
 
+/// Downloads the update, picking up where an interrupted download of the
+/// same file left off, then applies it. `expected_size` is the asset size
+/// the release lists, checked once the download completes, and
+/// `expected_sha256` its digest, checked before anything is applied.
 pub async fn download_and_apply(
     client: &reqwest::Client,
     download_url: &str,
+    expected_size: Option<u64>,
+    expected_sha256: Option<&str>,
     progress: mpsc::Sender<UpdateProgress>,
 ) -> Result<ApplyResult, String> {
+    let expected_sha256 = expected_sha256.ok_or_else(|| {
+        "The release lists no checksum for this download; install the update from the release page"
+            .to_string()
+    })?;
     let cache_dir = versi_platform::AppPaths::new()?.cache_dir;
-    std::fs::create_dir_all(&cache_dir)
+    let partial_dir = cache_dir.join(PARTIAL_DIR);
+    std::fs::create_dir_all(&partial_dir)
         .map_err(|e| format!("Failed to create cache directory: {e}"))?;
 
     let temp_dir = tempfile::tempdir_in(&cache_dir)
         .map_err(|e| format!("Failed to create temp directory: {e}"))?;
 
     let file_name = download_url.rsplit('/').next().unwrap_or("update-download");
-    let download_path = temp_dir.path().join(file_name);
+    let partial_path = partial_dir.join(format!("{file_name}.part"));
+    remove_stale_partials(&partial_dir, &partial_path);
 
     info!("Downloading update from {download_url}");
-    download_file(client, download_url, &download_path, &progress).await?;
-
-    let is_msi = file_name.ends_with(".msi");
-
-    if is_msi {
-        let _ = progress.send(UpdateProgress::Applying).await;
-        let _ = temp_dir.keep();
-        return apply_msi(&download_path);
+    download_file(
+        client,
+        download_url,
+        &partial_path,
+        expected_size,
+        &progress,
+    )
+    .await?;
+    verify_download(&partial_path, expected_sha256).await?;
+    let download_path = temp_dir.path().join(file_name);
+    std::fs::rename(&partial_path, &download_path)
+        .map_err(|e| format!("Failed to move the download: {e}"))?;
+
+    match Packaging::of(file_name) {
+        Some(Packaging::Msi) => {
+            let _ = progress.send(UpdateProgress::Applying).await;
+            let _ = temp_dir.keep();
+            return apply_msi(&download_path);
+        }
+        Some(Packaging::AppImage) => {
+            let _ = progress.send(UpdateProgress::Applying).await;
+            return apply_appimage(&download_path);
+        }
+        Some(Packaging::Deb) => {
+            let _ = progress.send(UpdateProgress::Applying).await;
+            // Kept so the package can be installed by hand if dpkg fails.
+            let _ = temp_dir.keep();
+            return apply_deb(&download_path);
+        }
+        _ => {}
     }
 
     let _ = progress.send(UpdateProgress::Extracting).await;
@@ -55,51 +92,228 @@ pub async fn download_and_apply(
     apply_update(&extract_dir)
 }
 
+/// Where unfinished update downloads are kept between attempts, inside the
+/// cache directory.
+const PARTIAL_DIR: &str = "update-downloads";
+
+/// What a partial download was fetched from, saved next to it so a later
+/// attempt only resumes the same file.
+#[derive(Debug, Serialize, Deserialize)]
+struct PartialDownload {
+    url: String,
+    /// A strong `ETag` or `Last-Modified`, sent as `If-Range` so the server
+    /// sends the whole file again if it changed.
+    validator: Option<String>,
+}
+
+fn metadata_path(partial: &Path) -> PathBuf {
+    let mut name = partial.as_os_str().to_os_string();
+    name.push(".json");
+    PathBuf::from(name)
+}
+
+fn discard_partial(partial: &Path) {
+    let _ = std::fs::remove_file(partial);
+    let _ = std::fs::remove_file(metadata_path(partial));
+}
+
+/// Removes partial downloads of other files, such as an older release.
+fn remove_stale_partials(dir: &Path, keep: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let keep_metadata = metadata_path(keep);
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path != keep && path != keep_metadata {
+            debug!("Removing stale partial download {}", path.display());
+            let _ = std::fs::remove_file(&path);
+        }
+    }
+}
+
+/// The part of the download already on disk that can be resumed: its
+/// length and validator, when it came from `url`.
+fn resumable(partial: &Path, url: &str) -> Option<(u64, Option<String>)> {
+    let len = std::fs::metadata(partial).ok()?.len();
+    let metadata: PartialDownload =
+        serde_json::from_str(&std::fs::read_to_string(metadata_path(partial)).ok()?).ok()?;
+    (len > 0 && metadata.url == url).then_some((len, metadata.validator))
+}
+
+/// The first byte and full length from a `Content-Range` header, such as
+/// `bytes 1000-4999/5000` or `bytes */5000`.
+fn parse_content_range(value: &str) -> Option<(Option<u64>, Option<u64>)> {
+    let (range, total) = value.strip_prefix("bytes ")?.split_once('/')?;
+    let start = match range {
+        "*" => None,
+        range => Some(range.split_once('-')?.0.trim().parse().ok()?),
+    };
+    Some((start, total.trim().parse().ok()))
+}
+
+/// Downloads `url` to `dest`, resuming with an HTTP range request when an
+/// earlier attempt left part of it there. An interrupted download is kept
+/// for the next attempt; one that completes with the wrong size is
+/// discarded.
 async fn download_file(
     client: &reqwest::Client,
     url: &str,
     dest: &Path,
+    expected_size: Option<u64>,
     progress: &mpsc::Sender<UpdateProgress>,
 ) -> Result<(), String> {
     use futures_util::StreamExt;
+    use reqwest::StatusCode;
+    use reqwest::header::{CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+
+    let mut resume = resumable(dest, url);
+    let (response, offset, total) = loop {
+        let mut request = client.get(url);
+        if let Some((offset, validator)) = &resume {
+            request = request.header(RANGE, format!("bytes={offset}-"));
+            if let Some(validator) = validator {
+                request = request.header(IF_RANGE, validator);
+            }
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Download request failed: {e}"))?;
+        let content_range = response
+            .headers()
+            .get(CONTENT_RANGE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_content_range);
+
+        match (response.status(), resume.take()) {
+            (StatusCode::PARTIAL_CONTENT, Some((offset, _)))
+                if content_range.is_some_and(|(start, _)| start == Some(offset)) =>
+            {
+                let total = content_range.and_then(|(_, total)| total).unwrap_or(0);
+                info!("Resuming download at {offset} of {total} bytes");
+                break (response, offset, total);
+            }
+            (StatusCode::RANGE_NOT_SATISFIABLE, Some((offset, _)))
+                if content_range.and_then(|(_, total)| total) == Some(offset) =>
+            {
+                info!("Download was already complete: {offset} bytes");
+                let _ = progress
+                    .send(UpdateProgress::Downloading {
+                        downloaded: offset,
+                        total: offset,
+                    })
+                    .await;
+                return finish_download(dest, offset, expected_size);
+            }
+            (StatusCode::PARTIAL_CONTENT | StatusCode::RANGE_NOT_SATISFIABLE, Some(_)) => {
+                // The partial file no longer matches what the server has.
+                warn!("Could not resume the download, starting over");
+                discard_partial(dest);
+            }
+            (status, _) if !status.is_success() => {
+                return Err(format!("Download failed with status {status}"));
+            }
+            (_, _) => {
+                let total = response.content_length().unwrap_or(0);
+                break (response, 0, total);
+            }
+        }
+    };
 
-    let response = client
-        .get(url)
-        .send()
-        .await
-        .map_err(|e| format!("Download request failed: {e}"))?;
-
-    if !response.status().is_success() {
-        return Err(format!("Download failed with status {}", response.status()));
-    }
-
-    let total = response.content_length().unwrap_or(0);
-    let mut downloaded: u64 = 0;
-
-    let mut file = tokio::fs::File::create(dest)
-        .await
-        .map_err(|e| format!("Failed to create download file: {e}"))?;
+    let mut file = if offset > 0 {
+        tokio::fs::OpenOptions::new()
+            .append(true)
+            .open(dest)
+            .await
+            .map_err(|e| format!("Failed to open partial download: {e}"))?
+    } else {
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v: &reqwest::header::HeaderValue| v.to_str().ok())
+                .map(str::to_string)
+        };
+        // Weak ETags can't be used in If-Range.
+        let validator = header(ETAG)
+            .filter(|etag| !etag.starts_with("W/"))
+            .or_else(|| header(LAST_MODIFIED));
+        let metadata = PartialDownload {
+            url: url.to_string(),
+            validator,
+        };
+        if let Ok(json) = serde_json::to_string(&metadata) {
+            let _ = std::fs::write(metadata_path(dest), json);
+        }
+        tokio::fs::File::create(dest)
+            .await
+            .map_err(|e| format!("Failed to create download file: {e}"))?
+    };
 
+    let mut downloaded = offset;
     let mut stream = response.bytes_stream();
     while let Some(chunk) = stream.next().await {
-        let chunk = chunk.map_err(|e| format!("Download stream error: {e}"))?;
+        let chunk = chunk.map_err(|e| {
+            format!(
+                "Download interrupted after {} MB; it will resume from there: {e}",
+                downloaded / 1_000_000
+            )
+        })?;
         file.write_all(&chunk)
             .await
             .map_err(|e| format!("Failed to write download data: {e}"))?;
         downloaded += chunk.len() as u64;
         let _ = progress
-            .send(UpdateProgress::Downloading { downloaded, total })
+            .send(UpdateProgress::Downloading {
+                downloaded,
+                total: total.max(downloaded),
+            })
             .await;
     }
 
     file.flush()
         .await
         .map_err(|e| format!("Failed to flush download file: {e}"))?;
+    drop(file);
 
+    let expected_size = expected_size.or((total > 0).then_some(total));
+    finish_download(dest, downloaded, expected_size)
+}
+
+/// Checks a finished download is as long as the release says. Archives are
+/// checked further as they are extracted, since every zip entry carries a
+/// CRC.
+fn finish_download(dest: &Path, downloaded: u64, expected_size: Option<u64>) -> Result<(), String> {
+    let _ = std::fs::remove_file(metadata_path(dest));
+    if let Some(expected) = expected_size
+        && downloaded != expected
+    {
+        discard_partial(dest);
+        return Err(format!(
+            "Download is incomplete: got {downloaded} of {expected} bytes"
+        ));
+    }
     info!("Download complete: {} bytes", downloaded);
     Ok(())
 }
 
+/// Checks a finished download against the digest the release lists. A
+/// resumed download is pieced together across sessions in a writable cache
+/// directory, so nothing is applied, let alone with elevated rights, until
+/// the whole file hashes correctly. A mismatch discards the download.
+async fn verify_download(dest: &Path, expected_sha256: &str) -> Result<(), String> {
+    let actual = crate::sha256_file(dest).await?;
+    if !actual.eq_ignore_ascii_case(expected_sha256) {
+        discard_partial(dest);
+        return Err(format!(
+            "Download does not match the release checksum (SHA-256 {actual}, expected {expected_sha256}); it was discarded"
+        ));
+    }
+    info!("Verified the download, SHA-256 {actual}");
+    Ok(())
+}
+
 fn extract_zip(zip_path: &Path, dest: &Path) -> Result<(), String> {
     let file =
         std::fs::File::open(zip_path).map_err(|e| format!("Failed to open zip file: {e}"))?;
@@ -301,6 +515,71 @@ fn apply_update_with_pkexec(new_binary: &Path, target: &Path) -> Result<ApplyRes
     Ok(ApplyResult::RestartRequired)
 }
 
+/// Swaps the AppImage Versi was started from for `appimage`. The running
+/// copy stays mounted, so it can be replaced in place.
+#[cfg(target_os = "linux")]
+fn apply_appimage(appimage: &Path) -> Result<ApplyResult, String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let target = std::env::var_os("APPIMAGE")
+        .map(std::path::PathBuf::from)
+        .ok_or_else(|| "Versi is not running from an AppImage".to_string())?;
+    let mut staged = target.clone().into_os_string();
+    staged.push(".new");
+    let staged = std::path::PathBuf::from(staged);
+
+    info!("Replacing AppImage {}", target.display());
+    let replaced = std::fs::copy(appimage, &staged)
+        .and_then(|_| std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o755)))
+        .and_then(|()| std::fs::rename(&staged, &target));
+    match replaced {
+        Ok(()) => {
+            info!("AppImage update applied successfully");
+            Ok(ApplyResult::RestartRequired)
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+            let _ = std::fs::remove_file(&staged);
+            info!("Permission denied, trying pkexec for elevated replacement");
+            apply_update_with_pkexec(appimage, &target)
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(&staged);
+            Err(format!("Failed to replace the AppImage: {e}"))
+        }
+    }
+}
+
+/// Installs the update package with dpkg, asking for elevation.
+#[cfg(target_os = "linux")]
+fn apply_deb(deb: &Path) -> Result<ApplyResult, String> {
+    info!("Installing {} with dpkg", deb.display());
+    let status = std::process::Command::new("pkexec")
+        .args(["dpkg", "-i", &deb.to_string_lossy()])
+        .status()
+        .map_err(|e| format!("Failed to run pkexec: {e}"))?;
+
+    if !status.success() {
+        return Err(format!(
+            "Installing the update package failed.\n\
+             To update manually, run:\n  sudo dpkg -i {}",
+            deb.display()
+        ));
+    }
+
+    info!("Linux update applied via dpkg");
+    Ok(ApplyResult::RestartRequired)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn apply_appimage(_appimage: &Path) -> Result<ApplyResult, String> {
+    Err("AppImage updates are only supported on Linux".to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn apply_deb(_deb: &Path) -> Result<ApplyResult, String> {
+    Err(".deb updates are only supported on Linux".to_string())
+}
+
 #[cfg(target_os = "windows")]
 fn apply_update(_extract_dir: &Path) -> Result<ApplyResult, String> {
     unreachable!("Windows uses MSI path, not extract+apply")
@@ -366,8 +645,12 @@ pub fn restart_app() -> Result<(), String> {
     // On Linux, after self_replace, /proc/self/exe points to the old deleted inode
     // and current_exe() returns a path with " (deleted)" appended.
     // Strip it to get the actual path where the new binary was placed.
+    // An AppImage runs from a mount that goes away on exit, so the image
+    // itself is started again.
     #[cfg(target_os = "linux")]
-    let exe = {
+    let exe = if let Some(appimage) = std::env::var_os("APPIMAGE") {
+        std::path::PathBuf::from(appimage)
+    } else {
         let path_str = exe.to_string_lossy();
         if path_str.ends_with(" (deleted)") {
             let fixed = std::path::PathBuf::from(path_str.trim_end_matches(" (deleted)"));
@@ -384,3 +667,54 @@ pub fn restart_app() -> Result<(), String> {
         .map_err(|e| format!("Failed to restart app: {e}"))?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_content_ranges() {
+        assert_eq!(
+            parse_content_range("bytes 1000-4999/5000"),
+            Some((Some(1000), Some(5000)))
+        );
+        assert_eq!(
+            parse_content_range("bytes */5000"),
+            Some((None, Some(5000)))
+        );
+        assert_eq!(parse_content_range("bytes 0-99/*"), Some((Some(0), None)));
+        assert_eq!(parse_content_range("items 0-1/2"), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn discards_downloads_that_do_not_match_the_release() {
+        let dir = tempfile::tempdir().unwrap();
+        let partial = dir.path().join("Versi.AppImage.part");
+        std::fs::write(&partial, b"abc").unwrap();
+        let abc = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+        assert!(verify_download(&partial, abc).await.is_ok());
+        std::fs::write(&partial, b"abd").unwrap();
+        assert!(verify_download(&partial, abc).await.is_err());
+        assert!(!partial.exists());
+    }
+
+    #[test]
+    fn resumes_only_downloads_of_the_same_url() {
+        let dir = tempfile::tempdir().unwrap();
+        let partial = dir.path().join("Versi.zip.part");
+        std::fs::write(&partial, b"partial").unwrap();
+        std::fs::write(
+            metadata_path(&partial),
+            r#"{"url":"https://example.com/Versi.zip","validator":"\"abc\""}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            resumable(&partial, "https://example.com/Versi.zip"),
+            Some((7, Some("\"abc\"".to_string())))
+        );
+        assert_eq!(resumable(&partial, "https://example.com/Other.zip"), None);
+    }
+}
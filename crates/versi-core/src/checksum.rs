@@ -0,0 +1,88 @@
+use std::io::Read;
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+/// The SHA-256 of the file at `path`, in lowercase hex.
+pub async fn sha256_file(path: &Path) -> Result<String, String> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || sha256_reader(std::fs::File::open(&path)?))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+fn sha256_reader(mut reader: impl Read) -> std::io::Result<String> {
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+/// The digest from a `sha256sum` line, `<hex>  <file>`, a bare digest in
+/// either case, or a `sha256:<hex>` digest as GitHub lists for release
+/// assets.
+pub fn parse_sha256(output: &str) -> Option<String> {
+    let digest = output.split_whitespace().next()?;
+    let digest = digest.strip_prefix("sha256:").unwrap_or(digest);
+    (digest.len() == 64 && digest.chars().all(|c| c.is_ascii_hexdigit()))
+        .then(|| digest.to_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_files_in_process() {
+        assert_eq!(
+            sha256_reader(&b"test"[..]).unwrap(),
+            "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"
+        );
+        assert_eq!(
+            sha256_reader(std::io::empty()).unwrap(),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_missing_files() {
+        assert!(
+            sha256_file(Path::new("/nonexistent/versi/archive.zip"))
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn parses_sha256sum_output() {
+        let digest = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+        assert_eq!(
+            parse_sha256(&format!("{digest}  /tmp/install.sh\n")),
+            Some(digest.to_string())
+        );
+        assert_eq!(
+            parse_sha256("sha256sum: /tmp/install.sh: No such file"),
+            None
+        );
+        assert_eq!(parse_sha256(""), None);
+        assert_eq!(
+            parse_sha256(&format!("{}\r\n", digest.to_ascii_uppercase())),
+            Some(digest.to_string())
+        );
+        assert_eq!(
+            parse_sha256(&format!("sha256:{digest}")),
+            Some(digest.to_string())
+        );
+    }
+}
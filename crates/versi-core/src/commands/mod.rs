@@ -1 +1,3 @@
-pub use versi_platform::HideWindow;
+pub use versi_platform::{
+    Elevation, HideWindow, is_writable, run_streamed, run_streamed_redraws, run_tracked,
+};
@@ -0,0 +1,156 @@
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const DEFAULT_DIST_URL: &str = "https://nodejs.org/dist";
+
+#[derive(Debug, Deserialize)]
+struct DistEntry {
+    version: String,
+    date: String,
+    #[serde(default)]
+    files: Vec<String>,
+}
+
+/// Release dates and published binaries per version, from a dist `index.json`.
+#[derive(Debug, Clone, Default)]
+pub struct ReleaseIndex {
+    pub dates: HashMap<String, NaiveDate>,
+    pub files: HashMap<String, Vec<String>>,
+}
+
+pub async fn fetch_release_index(
+    client: &reqwest::Client,
+    mirror: Option<&str>,
+) -> Result<ReleaseIndex, String> {
+    let base = mirror.unwrap_or(DEFAULT_DIST_URL).trim_end_matches('/');
+    let url = format!("{}/index.json", base);
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch release index: {}", e))?;
+
+    let entries: Vec<DistEntry> = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse release index: {}", e))?;
+
+    Ok(parse_release_index(entries))
+}
+
+fn parse_release_index(entries: Vec<DistEntry>) -> ReleaseIndex {
+    let mut index = ReleaseIndex::default();
+    for entry in entries {
+        if let Ok(date) = NaiveDate::parse_from_str(&entry.date, "%Y-%m-%d") {
+            index.dates.insert(entry.version.clone(), date);
+        }
+        if !entry.files.is_empty() {
+            index.files.insert(entry.version, entry.files);
+        }
+    }
+    index
+}
+
+pub fn format_release_age(date: NaiveDate, today: NaiveDate) -> String {
+    let days = (today - date).num_days();
+
+    let age = match days {
+        ..=0 => "today".to_string(),
+        1 => "yesterday".to_string(),
+        2..=30 => format!("{} days ago", days),
+        31..=364 => {
+            let months = days / 30;
+            if months == 1 {
+                "1 month ago".to_string()
+            } else {
+                format!("{} months ago", months)
+            }
+        }
+        _ => {
+            let years = days / 365;
+            if years == 1 {
+                "1 year ago".to_string()
+            } else {
+                format!("{} years ago", years)
+            }
+        }
+    };
+
+    format!("{}, {}", date.format("%Y-%m-%d"), age)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_parse_release_dates_skips_invalid() {
+        let entries = vec![
+            DistEntry {
+                version: "v22.1.0".to_string(),
+                date: "2024-04-24".to_string(),
+                files: Vec::new(),
+            },
+            DistEntry {
+                version: "v22.0.0".to_string(),
+                date: "not-a-date".to_string(),
+                files: Vec::new(),
+            },
+        ];
+        let index = parse_release_index(entries);
+        assert_eq!(index.dates.len(), 1);
+        assert_eq!(index.dates.get("v22.1.0"), Some(&date("2024-04-24")));
+    }
+
+    #[test]
+    fn test_parse_release_index_keeps_files() {
+        let entries = vec![
+            DistEntry {
+                version: "v22.1.0".to_string(),
+                date: "2024-04-24".to_string(),
+                files: vec!["linux-x64".to_string(), "osx-arm64-tar".to_string()],
+            },
+            DistEntry {
+                version: "v0.1.14".to_string(),
+                date: "2011-08-26".to_string(),
+                files: Vec::new(),
+            },
+        ];
+        let index = parse_release_index(entries);
+        assert_eq!(index.files["v22.1.0"], vec!["linux-x64", "osx-arm64-tar"]);
+        assert!(!index.files.contains_key("v0.1.14"));
+    }
+
+    #[test]
+    fn test_format_release_age_days() {
+        let today = date("2024-04-10");
+        assert_eq!(format_release_age(today, today), "2024-04-10, today");
+        assert_eq!(
+            format_release_age(date("2024-04-09"), today),
+            "2024-04-09, yesterday"
+        );
+        assert_eq!(
+            format_release_age(date("2024-04-01"), today),
+            "2024-04-01, 9 days ago"
+        );
+    }
+
+    #[test]
+    fn test_format_release_age_months_and_years() {
+        let today = date("2024-11-26");
+        assert_eq!(
+            format_release_age(date("2024-03-26"), today),
+            "2024-03-26, 8 months ago"
+        );
+        assert_eq!(
+            format_release_age(date("2022-11-01"), today),
+            "2022-11-01, 2 years ago"
+        );
+    }
+}
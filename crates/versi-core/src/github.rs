@@ -0,0 +1,221 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use log::{debug, warn};
+use reqwest::StatusCode;
+use reqwest::header::{ACCEPT, AUTHORIZATION, ETAG, HeaderMap, IF_NONE_MATCH, RETRY_AFTER};
+
+use crate::update::GitHubRelease;
+
+const API_BASE: &str = "https://api.github.com";
+
+#[derive(Default)]
+struct ClientState {
+    /// Last `ETag` and body per URL, so unchanged responses can be served
+    /// from a `304 Not Modified`, which does not count against the rate limit.
+    cached: HashMap<String, (String, String)>,
+    rate_limited_until: Option<SystemTime>,
+}
+
+/// GitHub REST client shared by the app and backend update checks.
+///
+/// Clones share the response cache and rate-limit state.
+#[derive(Clone)]
+pub struct GitHubClient {
+    http: reqwest::Client,
+    token: Option<String>,
+    state: Arc<Mutex<ClientState>>,
+}
+
+impl GitHubClient {
+    pub fn new(http: reqwest::Client, token: Option<String>) -> Self {
+        Self {
+            http,
+            token: token.filter(|t| !t.trim().is_empty()),
+            state: Arc::new(Mutex::new(ClientState::default())),
+        }
+    }
+
+    /// This client with another HTTP client or token, keeping the response
+    /// cache and rate-limit state.
+    pub fn reconfigure(&self, http: reqwest::Client, token: Option<String>) -> Self {
+        Self {
+            http,
+            token: token.filter(|t| !t.trim().is_empty()),
+            state: Arc::clone(&self.state),
+        }
+    }
+
+    /// Time left until GitHub accepts requests again after a rate limit.
+    pub fn retry_after(&self) -> Option<Duration> {
+        let until = self.lock().rate_limited_until?;
+        until.duration_since(SystemTime::now()).ok()
+    }
+
+    /// The latest release of `repo` (`owner/name`), or `None` when it has none.
+    pub async fn latest_release(&self, repo: &str) -> Result<Option<GitHubRelease>, String> {
+        let Some(body) = self
+            .get(&format!("/repos/{}/releases/latest", repo))
+            .await?
+        else {
+            return Ok(None);
+        };
+        serde_json::from_str(&body)
+            .map(Some)
+            .map_err(|e| format!("Failed to parse release of {}: {}", repo, e))
+    }
+
+    /// The most recent releases of `repo`, newest first, prereleases
+    /// included.
+    pub async fn releases(&self, repo: &str) -> Result<Vec<GitHubRelease>, String> {
+        let Some(body) = self
+            .get(&format!("/repos/{}/releases?per_page=30", repo))
+            .await?
+        else {
+            return Ok(Vec::new());
+        };
+        serde_json::from_str(&body)
+            .map_err(|e| format!("Failed to parse releases of {}: {}", repo, e))
+    }
+
+    async fn get(&self, path: &str) -> Result<Option<String>, String> {
+        if let Some(wait) = self.retry_after() {
+            return Err(format!(
+                "GitHub rate limit reached, retrying in {}s",
+                wait.as_secs()
+            ));
+        }
+
+        let url = format!("{}{}", API_BASE, path);
+        let cached = self.lock().cached.get(&url).cloned();
+
+        let mut request = self
+            .http
+            .get(&url)
+            .header(ACCEPT, "application/vnd.github+json");
+        if let Some(token) = &self.token {
+            request = request.header(AUTHORIZATION, format!("Bearer {}", token));
+        }
+        if let Some((etag, _)) = &cached {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+
+        let response = request.send().await.map_err(|e| e.to_string())?;
+        let status = response.status();
+
+        if let Some(until) = rate_limit_reset(status, response.headers(), SystemTime::now()) {
+            warn!("GitHub rate limit reached for {}", url);
+            self.lock().rate_limited_until = Some(until);
+            return Err("GitHub rate limit reached".to_string());
+        }
+
+        match status {
+            StatusCode::NOT_MODIFIED => {
+                debug!("{} not modified", url);
+                Ok(cached.map(|(_, body)| body))
+            }
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => {
+                let etag = response
+                    .headers()
+                    .get(ETAG)
+                    .and_then(|v| v.to_str().ok())
+                    .map(str::to_string);
+                let body = response.text().await.map_err(|e| e.to_string())?;
+                if let Some(etag) = etag {
+                    self.lock().cached.insert(url, (etag, body.clone()));
+                }
+                Ok(Some(body))
+            }
+            status => Err(format!("GitHub returned {}", status)),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ClientState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// When a response signals a rate limit, the time requests may resume,
+/// taken from `Retry-After` or `X-RateLimit-Reset`.
+fn rate_limit_reset(
+    status: StatusCode,
+    headers: &HeaderMap,
+    now: SystemTime,
+) -> Option<SystemTime> {
+    if status != StatusCode::FORBIDDEN && status != StatusCode::TOO_MANY_REQUESTS {
+        return None;
+    }
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+    };
+
+    if let Some(secs) = header(RETRY_AFTER.as_str()) {
+        return Some(now + Duration::from_secs(secs));
+    }
+    if header("x-ratelimit-remaining") == Some(0) {
+        let reset = header("x-ratelimit-reset")
+            .map(|epoch| UNIX_EPOCH + Duration::from_secs(epoch))
+            .filter(|reset| *reset > now)
+            .unwrap_or(now + Duration::from_secs(60));
+        return Some(reset);
+    }
+    (status == StatusCode::TOO_MANY_REQUESTS).then(|| now + Duration::from_secs(60))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, HeaderValue::from_static(value));
+        }
+        map
+    }
+
+    #[test]
+    fn reads_reset_from_rate_limit_headers() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_000);
+        let limited = headers(&[
+            ("x-ratelimit-remaining", "0"),
+            ("x-ratelimit-reset", "1600"),
+        ]);
+        assert_eq!(
+            rate_limit_reset(StatusCode::FORBIDDEN, &limited, now),
+            Some(UNIX_EPOCH + Duration::from_secs(1_600))
+        );
+
+        let retry = headers(&[("retry-after", "30")]);
+        assert_eq!(
+            rate_limit_reset(StatusCode::TOO_MANY_REQUESTS, &retry, now),
+            Some(now + Duration::from_secs(30))
+        );
+    }
+
+    #[test]
+    fn reconfigured_client_keeps_rate_limit() {
+        let client = GitHubClient::new(reqwest::Client::new(), None);
+        client.lock().rate_limited_until = Some(SystemTime::now() + Duration::from_secs(600));
+
+        let with_token = client.reconfigure(reqwest::Client::new(), Some("ghp_x".to_string()));
+        assert!(with_token.retry_after().is_some());
+    }
+
+    #[test]
+    fn ignores_other_failures() {
+        let now = SystemTime::now();
+        let remaining = headers(&[("x-ratelimit-remaining", "12")]);
+        assert_eq!(
+            rate_limit_reset(StatusCode::FORBIDDEN, &remaining, now),
+            None
+        );
+        assert_eq!(rate_limit_reset(StatusCode::OK, &headers(&[]), now), None);
+    }
+}
@@ -1,8 +1,32 @@
+//! Shared services for Versi and its backends: running commands, GitHub and
+//! Node.js release metadata, proxy detection, and retries.
+//!
+//! App self-updates live behind the default `self-update` feature.
+
+#[cfg(feature = "self-update")]
 pub mod auto_update;
+mod checksum;
 pub mod commands;
+mod dist;
+mod github;
+mod pac;
+mod proxy;
+mod retry;
 mod schedule;
 mod update;
 
-pub use commands::HideWindow;
-pub use schedule::{ReleaseSchedule, fetch_release_schedule};
-pub use update::{AppUpdate, GitHubRelease, check_for_update, is_newer_version};
+pub use checksum::{parse_sha256, sha256_file};
+pub use commands::{
+    Elevation, HideWindow, is_writable, run_streamed, run_streamed_redraws, run_tracked,
+};
+pub use dist::{ReleaseIndex, fetch_release_index, format_release_age};
+pub use github::GitHubClient;
+pub use pac::{PacScript, first_proxy};
+pub use proxy::{SystemProxy, detect_system_proxy, fetch_pac_script};
+pub use retry::{BackoffStrategy, RetryPolicy};
+pub use schedule::{
+    EolCountdown, ReleaseSchedule, SCHEDULE_TTL, VersionSchedule, fetch_release_schedule,
+};
+pub use update::{
+    AppUpdate, GitHubRelease, InstallChannel, Packaging, check_for_update, is_newer_version,
+};
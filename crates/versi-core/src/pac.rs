@@ -0,0 +1,806 @@
+//! A small evaluator for proxy auto-config files.
+//!
+//! PAC files are JavaScript, but nearly all of them are a single
+//! `FindProxyForURL` made of `if`/`else`, `return`, local variables, string
+//! comparisons and the standard PAC helpers. That subset is interpreted
+//! here; anything else is rejected when the file is parsed, so a file that
+//! cannot be evaluated is reported up front rather than guessed at.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, ToSocketAddrs, UdpSocket};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// How deeply statements and expressions may nest. PAC files are fetched
+/// over the network, so a crafted one must not overflow the stack.
+const MAX_DEPTH: usize = 64;
+
+/// How long `dnsResolve` and friends wait for a lookup. They run while a
+/// request picks its proxy, so a slow resolver must not hold it up.
+const DNS_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A parsed `FindProxyForURL`.
+#[derive(Debug, Clone)]
+pub struct PacScript {
+    params: (String, String),
+    body: Vec<Stmt>,
+    /// What `myIpAddress` returns, found when the file is parsed.
+    my_ip: Ipv4Addr,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Num(f64),
+    Punct(&'static str),
+}
+
+#[derive(Debug, Clone)]
+enum Stmt {
+    If(Expr, Vec<Stmt>, Vec<Stmt>),
+    Return(Expr),
+    Let(String, Expr),
+    Assign(String, Expr),
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Str(String),
+    Num(f64),
+    Bool(bool),
+    Var(String),
+    Not(Box<Expr>),
+    Neg(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Eq(Box<Expr>, Box<Expr>, bool),
+    /// `<`, `<=`, `>` or `>=`, true when the operands compare as one of the
+    /// given orderings.
+    Compare(Box<Expr>, Box<Expr>, &'static [Ordering]),
+    Concat(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+    Method(Box<Expr>, String, Vec<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Str(String),
+    Num(f64),
+    Bool(bool),
+}
+
+impl Value {
+    fn truthy(&self) -> bool {
+        match self {
+            Self::Str(s) => !s.is_empty(),
+            Self::Num(n) => *n != 0.0,
+            Self::Bool(b) => *b,
+        }
+    }
+
+    fn to_number(&self) -> f64 {
+        match self {
+            Self::Str(s) if s.trim().is_empty() => 0.0,
+            Self::Str(s) => s.trim().parse().unwrap_or(f64::NAN),
+            Self::Num(n) => *n,
+            Self::Bool(b) => f64::from(u8::from(*b)),
+        }
+    }
+
+    fn into_string(self) -> String {
+        match self {
+            Self::Str(s) => s,
+            Self::Num(n) => n.to_string(),
+            Self::Bool(b) => b.to_string(),
+        }
+    }
+}
+
+const PUNCTUATION: &[&str] = &[
+    "===", "!==", "==", "!=", "<=", ">=", "&&", "||", "(", ")", "{", "}", ",", ";", "!", "+", "-",
+    "<", ">", "=", ".",
+];
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = source.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let rest: String = chars[i..chars.len().min(i + 3)].iter().collect();
+        if c.is_whitespace() {
+            i += 1;
+        } else if rest.starts_with("//") {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if rest.starts_with("/*") {
+            i += 2;
+            while i + 1 < chars.len() && !(chars[i] == '*' && chars[i + 1] == '/') {
+                i += 1;
+            }
+            i += 2;
+        } else if c == '"' || c == '\'' {
+            let mut value = String::new();
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                if chars[i] == '\\' && i + 1 < chars.len() {
+                    i += 1;
+                }
+                value.push(chars[i]);
+                i += 1;
+            }
+            if i == chars.len() {
+                return Err("Unterminated string".to_string());
+            }
+            i += 1;
+            tokens.push(Token::Str(value));
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let number: String = chars[start..i].iter().collect();
+            tokens.push(Token::Num(
+                number
+                    .parse()
+                    .map_err(|_| format!("Invalid number {}", number))?,
+            ));
+        } else if c.is_alphabetic() || c == '_' || c == '$' {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$')
+            {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else if let Some(punct) = PUNCTUATION.iter().find(|p| rest.starts_with(**p)) {
+            i += punct.len();
+            tokens.push(Token::Punct(punct));
+        } else {
+            return Err(format!("Unsupported character '{}'", c));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    /// Runs `parse` one level deeper, failing past [`MAX_DEPTH`].
+    fn nested<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, String>,
+    ) -> Result<T, String> {
+        if self.depth >= MAX_DEPTH {
+            return Err("Nested too deeply".to_string());
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, punct: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Punct(p)) if *p == punct) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, punct: &str) -> Result<(), String> {
+        if self.eat(punct) {
+            Ok(())
+        } else {
+            Err(format!("Expected '{}' but found {:?}", punct, self.peek()))
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Ident(name)) if name == keyword) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, String> {
+        match self.next() {
+            Some(Token::Ident(name)) => Ok(name),
+            other => Err(format!("Expected a name but found {:?}", other)),
+        }
+    }
+
+    fn block(&mut self) -> Result<Vec<Stmt>, String> {
+        if !self.eat("{") {
+            return Ok(self.statement()?.into_iter().collect());
+        }
+        let mut statements = Vec::new();
+        while !self.eat("}") {
+            if self.peek().is_none() {
+                return Err("Missing '}'".to_string());
+            }
+            statements.extend(self.statement()?);
+        }
+        Ok(statements)
+    }
+
+    fn statement(&mut self) -> Result<Option<Stmt>, String> {
+        self.nested(Self::statement_inner)
+    }
+
+    fn statement_inner(&mut self) -> Result<Option<Stmt>, String> {
+        if self.eat(";") {
+            return Ok(None);
+        }
+        if self.eat_keyword("if") {
+            self.expect("(")?;
+            let condition = self.expression()?;
+            self.expect(")")?;
+            let then = self.block()?;
+            let otherwise = if self.eat_keyword("else") {
+                self.block()?
+            } else {
+                Vec::new()
+            };
+            return Ok(Some(Stmt::If(condition, then, otherwise)));
+        }
+        if self.eat_keyword("return") {
+            let value = self.expression()?;
+            self.eat(";");
+            return Ok(Some(Stmt::Return(value)));
+        }
+        if self.eat_keyword("var") || self.eat_keyword("let") || self.eat_keyword("const") {
+            let name = self.ident()?;
+            self.expect("=")?;
+            let value = self.expression()?;
+            self.eat(";");
+            return Ok(Some(Stmt::Let(name, value)));
+        }
+        let name = self.ident()?;
+        self.expect("=")?;
+        let value = self.expression()?;
+        self.eat(";");
+        Ok(Some(Stmt::Assign(name, value)))
+    }
+
+    fn expression(&mut self) -> Result<Expr, String> {
+        self.nested(Self::or)
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut left = self.and()?;
+        while self.eat("||") {
+            left = Expr::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut left = self.comparison()?;
+        while self.eat("&&") {
+            left = Expr::And(Box::new(left), Box::new(self.comparison()?));
+        }
+        Ok(left)
+    }
+
+    fn comparison(&mut self) -> Result<Expr, String> {
+        let left = self.relational()?;
+        for (punct, equal) in [("===", true), ("==", true), ("!==", false), ("!=", false)] {
+            if self.eat(punct) {
+                return Ok(Expr::Eq(
+                    Box::new(left),
+                    Box::new(self.relational()?),
+                    equal,
+                ));
+            }
+        }
+        Ok(left)
+    }
+
+    fn relational(&mut self) -> Result<Expr, String> {
+        let left = self.concat()?;
+        let operators: [(&str, &'static [Ordering]); 4] = [
+            ("<=", &[Ordering::Less, Ordering::Equal]),
+            (">=", &[Ordering::Greater, Ordering::Equal]),
+            ("<", &[Ordering::Less]),
+            (">", &[Ordering::Greater]),
+        ];
+        for (punct, orderings) in operators {
+            if self.eat(punct) {
+                return Ok(Expr::Compare(
+                    Box::new(left),
+                    Box::new(self.concat()?),
+                    orderings,
+                ));
+            }
+        }
+        Ok(left)
+    }
+
+    fn concat(&mut self) -> Result<Expr, String> {
+        let mut left = self.unary()?;
+        loop {
+            if self.eat("+") {
+                left = Expr::Concat(Box::new(left), Box::new(self.unary()?));
+            } else if self.eat("-") {
+                left = Expr::Sub(Box::new(left), Box::new(self.unary()?));
+            } else {
+                return Ok(left);
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat("!") {
+            return self.nested(|parser| Ok(Expr::Not(Box::new(parser.unary()?))));
+        }
+        if self.eat("-") {
+            return self.nested(|parser| Ok(Expr::Neg(Box::new(parser.unary()?))));
+        }
+        let mut expr = self.primary()?;
+        while self.eat(".") {
+            let method = self.ident()?;
+            self.expect("(")?;
+            expr = Expr::Method(Box::new(expr), method, self.arguments()?);
+        }
+        Ok(expr)
+    }
+
+    fn arguments(&mut self) -> Result<Vec<Expr>, String> {
+        let mut args = Vec::new();
+        if self.eat(")") {
+            return Ok(args);
+        }
+        loop {
+            args.push(self.expression()?);
+            if self.eat(")") {
+                return Ok(args);
+            }
+            self.expect(",")?;
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Str(value)) => Ok(Expr::Str(value)),
+            Some(Token::Num(value)) => Ok(Expr::Num(value)),
+            Some(Token::Punct("(")) => {
+                let expr = self.expression()?;
+                self.expect(")")?;
+                Ok(expr)
+            }
+            Some(Token::Ident(name)) if name == "true" || name == "false" => {
+                Ok(Expr::Bool(name == "true"))
+            }
+            Some(Token::Ident(name)) => {
+                if self.eat("(") {
+                    Ok(Expr::Call(name, self.arguments()?))
+                } else {
+                    Ok(Expr::Var(name))
+                }
+            }
+            other => Err(format!("Unexpected {:?}", other)),
+        }
+    }
+}
+
+impl PacScript {
+    /// Parses the `FindProxyForURL` function of a PAC file.
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let tokens = tokenize(source)?;
+        let start = tokens
+            .windows(2)
+            .position(|pair| {
+                pair[0] == Token::Ident("function".to_string())
+                    && pair[1] == Token::Ident("FindProxyForURL".to_string())
+            })
+            .ok_or_else(|| "No FindProxyForURL function found".to_string())?;
+        let mut parser = Parser {
+            tokens,
+            pos: start + 2,
+            depth: 0,
+        };
+        parser.expect("(")?;
+        let url = parser.ident()?;
+        parser.expect(",")?;
+        let host = parser.ident()?;
+        parser.expect(")")?;
+        if parser.peek() != Some(&Token::Punct("{")) {
+            return Err("Expected the function body".to_string());
+        }
+        let body = parser
+            .block()
+            .map_err(|e| format!("Unsupported PAC file: {}", e))?;
+        Ok(Self {
+            params: (url, host),
+            body,
+            my_ip: local_ipv4().unwrap_or(Ipv4Addr::LOCALHOST),
+        })
+    }
+
+    /// The PAC result for `url`, such as `"PROXY proxy:8080; DIRECT"`.
+    /// Resolving host names may block for up to [`DNS_TIMEOUT`] per lookup.
+    pub fn find_proxy(&self, url: &str, host: &str) -> Result<String, String> {
+        let mut vars = HashMap::from([
+            (self.params.0.clone(), Value::Str(url.to_string())),
+            (self.params.1.clone(), Value::Str(host.to_string())),
+        ]);
+        match run(&self.body, &mut vars, self.my_ip)? {
+            Some(value) => Ok(value.into_string()),
+            None => Ok("DIRECT".to_string()),
+        }
+    }
+}
+
+fn run(
+    statements: &[Stmt],
+    vars: &mut HashMap<String, Value>,
+    my_ip: Ipv4Addr,
+) -> Result<Option<Value>, String> {
+    for statement in statements {
+        match statement {
+            Stmt::If(condition, then, otherwise) => {
+                let branch = if eval(condition, vars, my_ip)?.truthy() {
+                    then
+                } else {
+                    otherwise
+                };
+                if let Some(value) = run(branch, vars, my_ip)? {
+                    return Ok(Some(value));
+                }
+            }
+            Stmt::Return(value) => return eval(value, vars, my_ip).map(Some),
+            Stmt::Let(name, value) | Stmt::Assign(name, value) => {
+                let value = eval(value, vars, my_ip)?;
+                vars.insert(name.clone(), value);
+            }
+        }
+    }
+    Ok(None)
+}
+
+fn eval(expr: &Expr, vars: &HashMap<String, Value>, my_ip: Ipv4Addr) -> Result<Value, String> {
+    Ok(match expr {
+        Expr::Str(s) => Value::Str(s.clone()),
+        Expr::Num(n) => Value::Num(*n),
+        Expr::Bool(b) => Value::Bool(*b),
+        Expr::Var(name) => vars
+            .get(name)
+            .cloned()
+            .ok_or_else(|| format!("Unknown variable {}", name))?,
+        Expr::Not(inner) => Value::Bool(!eval(inner, vars, my_ip)?.truthy()),
+        Expr::Neg(inner) => Value::Num(-eval(inner, vars, my_ip)?.to_number()),
+        Expr::And(left, right) => {
+            Value::Bool(eval(left, vars, my_ip)?.truthy() && eval(right, vars, my_ip)?.truthy())
+        }
+        Expr::Or(left, right) => {
+            Value::Bool(eval(left, vars, my_ip)?.truthy() || eval(right, vars, my_ip)?.truthy())
+        }
+        Expr::Eq(left, right, equal) => {
+            Value::Bool((eval(left, vars, my_ip)? == eval(right, vars, my_ip)?) == *equal)
+        }
+        Expr::Compare(left, right, orderings) => {
+            let ordering = match (eval(left, vars, my_ip)?, eval(right, vars, my_ip)?) {
+                (Value::Str(a), Value::Str(b)) => Some(a.cmp(&b)),
+                (a, b) => a.to_number().partial_cmp(&b.to_number()),
+            };
+            Value::Bool(ordering.is_some_and(|ordering| orderings.contains(&ordering)))
+        }
+        Expr::Sub(left, right) => {
+            Value::Num(eval(left, vars, my_ip)?.to_number() - eval(right, vars, my_ip)?.to_number())
+        }
+        Expr::Concat(left, right) => match (eval(left, vars, my_ip)?, eval(right, vars, my_ip)?) {
+            (Value::Num(a), Value::Num(b)) => Value::Num(a + b),
+            (a, b) => Value::Str(a.into_string() + &b.into_string()),
+        },
+        Expr::Method(target, method, args) => {
+            let target = eval(target, vars, my_ip)?.into_string();
+            let args = args
+                .iter()
+                .map(|arg| eval(arg, vars, my_ip))
+                .collect::<Result<Vec<_>, _>>()?;
+            method_call(&target, method, &args)?
+        }
+        Expr::Call(name, args) => {
+            let args = args
+                .iter()
+                .map(|arg| eval(arg, vars, my_ip).map(Value::into_string))
+                .collect::<Result<Vec<_>, _>>()?;
+            call(name, &args, my_ip)?
+        }
+    })
+}
+
+fn method_call(target: &str, method: &str, args: &[Value]) -> Result<Value, String> {
+    let index = |i: usize| match args.get(i) {
+        Some(Value::Num(n)) => Some(*n as usize),
+        _ => None,
+    };
+    Ok(match method {
+        "toLowerCase" => Value::Str(target.to_lowercase()),
+        "toUpperCase" => Value::Str(target.to_uppercase()),
+        "indexOf" => {
+            let needle = args
+                .first()
+                .cloned()
+                .map(Value::into_string)
+                .unwrap_or_default();
+            Value::Num(target.find(&needle).map_or(-1.0, |i| i as f64))
+        }
+        "substring" => {
+            let chars: Vec<char> = target.chars().collect();
+            let start = index(0).unwrap_or(0).min(chars.len());
+            let end = index(1).unwrap_or(chars.len()).min(chars.len());
+            Value::Str(chars[start.min(end)..end.max(start)].iter().collect())
+        }
+        _ => return Err(format!("Unsupported method {}", method)),
+    })
+}
+
+fn call(name: &str, args: &[String], my_ip: Ipv4Addr) -> Result<Value, String> {
+    let arg = |i: usize| args.get(i).map(String::as_str).unwrap_or_default();
+    Ok(match name {
+        "isPlainHostName" => Value::Bool(!arg(0).contains('.')),
+        "dnsDomainIs" => Value::Bool(arg(0).to_lowercase().ends_with(&arg(1).to_lowercase())),
+        "localHostOrDomainIs" => {
+            let host = arg(0).to_lowercase();
+            let full = arg(1).to_lowercase();
+            Value::Bool(
+                host == full || (!host.contains('.') && full.starts_with(&format!("{host}."))),
+            )
+        }
+        "shExpMatch" => Value::Bool(glob_match(arg(1), arg(0))),
+        "dnsDomainLevels" => Value::Num(arg(0).matches('.').count() as f64),
+        "isResolvable" => Value::Bool(resolve(arg(0)).is_some()),
+        "dnsResolve" => Value::Str(resolve(arg(0)).map(|ip| ip.to_string()).unwrap_or_default()),
+        "myIpAddress" => Value::Str(my_ip.to_string()),
+        "isInNet" => Value::Bool(is_in_net(arg(0), arg(1), arg(2))),
+        _ => return Err(format!("Unsupported function {}", name)),
+    })
+}
+
+fn resolve(host: &str) -> Option<IpAddr> {
+    if let Ok(ip) = host.parse() {
+        return Some(ip);
+    }
+    let host = host.to_string();
+    with_timeout(DNS_TIMEOUT, move || {
+        (host.as_str(), 0)
+            .to_socket_addrs()
+            .ok()?
+            .map(|addr| addr.ip())
+            .find(IpAddr::is_ipv4)
+    })
+}
+
+/// Runs a blocking lookup on its own thread, giving up after `timeout`.
+/// The system resolver can't be cancelled, so a lookup that hangs is left
+/// to finish in the background.
+fn with_timeout(
+    timeout: Duration,
+    lookup: impl FnOnce() -> Option<IpAddr> + Send + 'static,
+) -> Option<IpAddr> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(lookup());
+    });
+    rx.recv_timeout(timeout).ok().flatten()
+}
+
+/// The address this machine uses to reach other hosts. Connecting a UDP
+/// socket only picks the route; nothing is sent.
+fn local_ipv4() -> Option<Ipv4Addr> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).ok()?;
+    socket.connect((Ipv4Addr::new(192, 0, 2, 1), 53)).ok()?;
+    match socket.local_addr().ok()?.ip() {
+        IpAddr::V4(ip) if !ip.is_unspecified() => Some(ip),
+        _ => None,
+    }
+}
+
+fn is_in_net(host: &str, pattern: &str, mask: &str) -> bool {
+    let (Some(IpAddr::V4(ip)), Ok(pattern), Ok(mask)) = (
+        resolve(host),
+        pattern.parse::<Ipv4Addr>(),
+        mask.parse::<Ipv4Addr>(),
+    ) else {
+        return false;
+    };
+    let mask = u32::from(mask);
+    u32::from(ip) & mask == u32::from(pattern) & mask
+}
+
+/// Shell-style matching with `*` and `?`, as `shExpMatch` does.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// The first usable proxy in a PAC result as a URL, or `None` when the
+/// result starts with `DIRECT`. SOCKS entries are skipped.
+pub fn first_proxy(result: &str) -> Option<String> {
+    for entry in result.split(';').map(str::trim) {
+        let mut parts = entry.split_whitespace();
+        match (parts.next().map(str::to_uppercase).as_deref(), parts.next()) {
+            (Some("DIRECT"), _) => return None,
+            (Some("PROXY" | "HTTP"), Some(address)) => return Some(format!("http://{}", address)),
+            (Some("HTTPS"), Some(address)) => return Some(format!("https://{}", address)),
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCRIPT: &str = r#"
+        // Corporate proxy rules
+        function FindProxyForURL(url, host) {
+            var lower = host.toLowerCase();
+            if (isPlainHostName(lower) || dnsDomainIs(lower, ".corp.example.com"))
+                return "DIRECT";
+            if (shExpMatch(host, "10.*") && isInNet(host, "10.0.0.0", "255.0.0.0")) {
+                return 'DIRECT';
+            } else if (shExpMatch(url, "https://*.nodejs.org/*") && url.substring(0, 6) == "https:") {
+                return "PROXY node-proxy:3128; DIRECT";
+            }
+            return "PROXY " + "proxy.example.com:8080";
+        }
+    "#;
+
+    #[test]
+    fn evaluates_common_pac_rules() {
+        let script = PacScript::parse(SCRIPT).unwrap();
+        let find = |url: &str, host: &str| script.find_proxy(url, host).unwrap();
+        assert_eq!(find("http://intranet/", "intranet"), "DIRECT");
+        assert_eq!(
+            find("https://wiki.corp.example.com/", "wiki.CORP.example.com"),
+            "DIRECT"
+        );
+        assert_eq!(find("http://10.1.2.3/", "10.1.2.3"), "DIRECT");
+        assert_eq!(
+            find("https://dist.nodejs.org/index.json", "dist.nodejs.org"),
+            "PROXY node-proxy:3128; DIRECT"
+        );
+        assert_eq!(
+            find("https://github.com/", "github.com"),
+            "PROXY proxy.example.com:8080"
+        );
+    }
+
+    #[test]
+    fn compares_indexes_and_negative_numbers() {
+        let script = PacScript::parse(
+            r#"function FindProxyForURL(url, host) {
+                if (url.indexOf("/registry/") >= 0 && host.indexOf("npm") != -1) return "PROXY npm:1";
+                if (url.indexOf("nodejs") > -1 || dnsDomainLevels(host) < 1) return "DIRECT";
+                if (5 - 7 <= -2) return "PROXY sub:1";
+                return "PROXY other:1";
+            }"#,
+        )
+        .unwrap();
+        let find = |url: &str, host: &str| script.find_proxy(url, host).unwrap();
+        assert_eq!(
+            find("https://npm.example/registry/x", "npm.example"),
+            "PROXY npm:1"
+        );
+        assert_eq!(find("https://nodejs.org/dist/", "nodejs.org"), "DIRECT");
+        assert_eq!(find("http://intranet/", "intranet"), "DIRECT");
+        assert_eq!(find("https://github.com/", "github.com"), "PROXY sub:1");
+    }
+
+    #[test]
+    fn my_ip_address_is_the_local_address() {
+        let mut script = PacScript::parse(
+            r#"function FindProxyForURL(url, host) {
+                if (isInNet(myIpAddress(), "10.0.0.0", "255.0.0.0")) return "PROXY office:8080";
+                return "DIRECT";
+            }"#,
+        )
+        .unwrap();
+        script.my_ip = Ipv4Addr::new(10, 1, 2, 3);
+        assert_eq!(
+            script.find_proxy("https://a/", "a").unwrap(),
+            "PROXY office:8080"
+        );
+        script.my_ip = Ipv4Addr::new(192, 168, 1, 5);
+        assert_eq!(script.find_proxy("https://a/", "a").unwrap(), "DIRECT");
+    }
+
+    #[test]
+    fn rejects_deeply_nested_scripts() {
+        let parens = format!(
+            "function FindProxyForURL(url, host) {{ return {}1{}; }}",
+            "(".repeat(100_000),
+            ")".repeat(100_000)
+        );
+        assert!(PacScript::parse(&parens).is_err());
+        let nots = format!(
+            "function FindProxyForURL(url, host) {{ return {}true; }}",
+            "!".repeat(100_000)
+        );
+        assert!(PacScript::parse(&nots).is_err());
+        let ifs = format!(
+            "function FindProxyForURL(url, host) {{ {} return 1; }}",
+            "if (true) ".repeat(100_000)
+        );
+        assert!(PacScript::parse(&ifs).is_err());
+    }
+
+    #[test]
+    fn gives_up_on_slow_lookups() {
+        let started = std::time::Instant::now();
+        let slow = with_timeout(Duration::from_millis(50), || {
+            std::thread::sleep(Duration::from_secs(5));
+            Some(IpAddr::V4(Ipv4Addr::LOCALHOST))
+        });
+        assert_eq!(slow, None);
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert_eq!(
+            with_timeout(Duration::from_secs(1), || Some(IpAddr::V4(
+                Ipv4Addr::LOCALHOST
+            ))),
+            Some(IpAddr::V4(Ipv4Addr::LOCALHOST))
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_scripts() {
+        assert!(PacScript::parse("var x = 1;").is_err());
+        assert!(
+            PacScript::parse("function FindProxyForURL(url, host) { while (true) {} }").is_err()
+        );
+    }
+
+    #[test]
+    fn picks_the_first_http_proxy() {
+        assert_eq!(
+            first_proxy("SOCKS s:1080; PROXY p:8080; DIRECT"),
+            Some("http://p:8080".to_string())
+        );
+        assert_eq!(first_proxy("DIRECT; PROXY p:8080"), None);
+        assert!(glob_match("*.nodejs.org", "dist.nodejs.org"));
+        assert!(!glob_match("*.nodejs.org", "nodejs.org.evil.com"));
+    }
+}
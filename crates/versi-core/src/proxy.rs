@@ -0,0 +1,254 @@
+//! The proxy configured for the system: WinHTTP/Internet Settings on
+//! Windows, System Configuration on macOS, and the usual environment
+//! variables everywhere.
+
+use std::path::Path;
+
+/// Proxies from the operating system's network settings.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SystemProxy {
+    /// `host:port` or a URL, for plain HTTP requests.
+    pub http: Option<String>,
+    /// `host:port` or a URL, for HTTPS requests.
+    pub https: Option<String>,
+    /// Hosts that bypass the proxy.
+    pub bypass: Vec<String>,
+    /// Where the proxy auto-config file is, when one is configured.
+    pub pac_url: Option<String>,
+}
+
+impl SystemProxy {
+    pub fn is_empty(&self) -> bool {
+        self.http.is_none() && self.https.is_none() && self.pac_url.is_none()
+    }
+}
+
+/// Reads the system proxy settings.
+pub async fn detect_system_proxy() -> SystemProxy {
+    let mut proxy = detect_native().await;
+    let env = |keys: [&str; 2]| {
+        keys.iter()
+            .find_map(|key| std::env::var(key).ok().filter(|value| !value.is_empty()))
+    };
+    proxy.http = proxy.http.or_else(|| env(["HTTP_PROXY", "http_proxy"]));
+    proxy.https = proxy
+        .https
+        .or_else(|| env(["HTTPS_PROXY", "https_proxy"]))
+        .or_else(|| env(["ALL_PROXY", "all_proxy"]));
+    if proxy.bypass.is_empty()
+        && let Some(no_proxy) = env(["NO_PROXY", "no_proxy"])
+    {
+        proxy.bypass = split_list(&no_proxy, ',');
+    }
+    proxy
+}
+
+#[cfg(windows)]
+async fn detect_native() -> SystemProxy {
+    use crate::HideWindow;
+
+    let output = tokio::process::Command::new("reg")
+        .args([
+            "query",
+            r"HKCU\Software\Microsoft\Windows\CurrentVersion\Internet Settings",
+        ])
+        .hide_window()
+        .output()
+        .await;
+    match output {
+        Ok(output) => parse_internet_settings(&String::from_utf8_lossy(&output.stdout)),
+        Err(e) => {
+            log::debug!("Could not read Internet Settings: {}", e);
+            SystemProxy::default()
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+async fn detect_native() -> SystemProxy {
+    let output = tokio::process::Command::new("scutil")
+        .arg("--proxy")
+        .output()
+        .await;
+    match output {
+        Ok(output) => parse_scutil(&String::from_utf8_lossy(&output.stdout)),
+        Err(e) => {
+            log::debug!("Could not read system proxy settings: {}", e);
+            SystemProxy::default()
+        }
+    }
+}
+
+/// Linux desktops have no single proxy setting; the environment variables
+/// are what command-line tools use.
+#[cfg(not(any(windows, target_os = "macos")))]
+async fn detect_native() -> SystemProxy {
+    SystemProxy::default()
+}
+
+/// Parses `reg query` of the Internet Settings key, which WinHTTP and
+/// browsers share.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_internet_settings(output: &str) -> SystemProxy {
+    let value = |name: &str| {
+        output.lines().find_map(|line| {
+            let mut parts = line.split_whitespace();
+            (parts.next() == Some(name)).then(|| {
+                let _kind = parts.next();
+                parts.collect::<Vec<_>>().join(" ")
+            })
+        })
+    };
+
+    let mut proxy = SystemProxy {
+        pac_url: value("AutoConfigURL").filter(|url| !url.is_empty()),
+        ..SystemProxy::default()
+    };
+    if value("ProxyEnable").as_deref() != Some("0x1") {
+        return proxy;
+    }
+    if let Some(server) = value("ProxyServer") {
+        // Either one proxy for everything or "http=a:80;https=b:443".
+        if server.contains('=') {
+            for entry in split_list(&server, ';') {
+                match entry.split_once('=') {
+                    Some(("http", address)) => proxy.http = Some(address.to_string()),
+                    Some(("https", address)) => proxy.https = Some(address.to_string()),
+                    _ => {}
+                }
+            }
+        } else {
+            proxy.http = Some(server.clone());
+            proxy.https = Some(server);
+        }
+    }
+    if let Some(overrides) = value("ProxyOverride") {
+        proxy.bypass = split_list(&overrides, ';')
+            .into_iter()
+            .map(|host| match host.as_str() {
+                "<local>" => "localhost".to_string(),
+                _ => host.trim_start_matches("*.").to_string(),
+            })
+            .collect();
+    }
+    proxy
+}
+
+/// Parses `scutil --proxy`, the settings System Configuration applies.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_scutil(output: &str) -> SystemProxy {
+    let value = |name: &str| {
+        output.lines().find_map(|line| {
+            let (key, value) = line.split_once(" : ")?;
+            (key.trim() == name).then(|| value.trim().to_string())
+        })
+    };
+    let server = |enable: &str, host: &str, port: &str| {
+        if value(enable).as_deref() != Some("1") {
+            return None;
+        }
+        let host = value(host)?;
+        Some(match value(port) {
+            Some(port) => format!("{}:{}", host, port),
+            None => host,
+        })
+    };
+
+    let pac_url = value("ProxyAutoConfigURLString")
+        .filter(|_| value("ProxyAutoConfigEnable").as_deref() == Some("1"));
+    let mut bypass = Vec::new();
+    let mut in_exceptions = false;
+    for line in output.lines() {
+        let line = line.trim();
+        if line.starts_with("ExceptionsList") {
+            in_exceptions = true;
+        } else if in_exceptions && line == "}" {
+            in_exceptions = false;
+        } else if in_exceptions && let Some((_, host)) = line.split_once(" : ") {
+            bypass.push(host.trim_start_matches("*.").to_string());
+        }
+    }
+    SystemProxy {
+        http: server("HTTPEnable", "HTTPProxy", "HTTPPort"),
+        https: server("HTTPSEnable", "HTTPSProxy", "HTTPSPort"),
+        bypass,
+        pac_url,
+    }
+}
+
+fn split_list(list: &str, separator: char) -> Vec<String> {
+    list.split(separator)
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Loads a PAC file from a URL or a local path.
+pub async fn fetch_pac_script(client: &reqwest::Client, location: &str) -> Result<String, String> {
+    if let Some(path) = location.strip_prefix("file://") {
+        return tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| format!("Failed to read PAC file: {}", e));
+    }
+    if Path::new(location).is_absolute() {
+        return tokio::fs::read_to_string(location)
+            .await
+            .map_err(|e| format!("Failed to read PAC file: {}", e));
+    }
+    client
+        .get(location)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|e| format!("Failed to fetch PAC file: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("Failed to fetch PAC file: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_windows_internet_settings() {
+        let output = r"
+HKEY_CURRENT_USER\Software\Microsoft\Windows\CurrentVersion\Internet Settings
+    ProxyEnable    REG_DWORD    0x1
+    ProxyServer    REG_SZ    http=proxy:80;https=secure:443
+    ProxyOverride    REG_SZ    *.corp.example.com;<local>
+    AutoConfigURL    REG_SZ    http://wpad/proxy.pac
+";
+        assert_eq!(
+            parse_internet_settings(output),
+            SystemProxy {
+                http: Some("proxy:80".to_string()),
+                https: Some("secure:443".to_string()),
+                bypass: vec!["corp.example.com".to_string(), "localhost".to_string()],
+                pac_url: Some("http://wpad/proxy.pac".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn parses_scutil_output() {
+        let output = "<dictionary> {
+  ExceptionsList : <array> {
+    0 : *.local
+    1 : 169.254/16
+  }
+  HTTPEnable : 0
+  HTTPSEnable : 1
+  HTTPSPort : 8443
+  HTTPSProxy : proxy.example.com
+  ProxyAutoConfigEnable : 0
+  ProxyAutoConfigURLString : http://wpad/proxy.pac
+}";
+        let proxy = parse_scutil(output);
+        assert_eq!(proxy.http, None);
+        assert_eq!(proxy.https.as_deref(), Some("proxy.example.com:8443"));
+        assert_eq!(proxy.bypass, ["local", "169.254/16"]);
+        assert_eq!(proxy.pac_url, None);
+    }
+}
@@ -0,0 +1,146 @@
+use std::future::Future;
+use std::time::Duration;
+
+use log::debug;
+use serde::{Deserialize, Serialize};
+
+const MAX_DELAY_SECS: u64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackoffStrategy {
+    Fixed,
+    Linear,
+    Exponential,
+}
+
+impl BackoffStrategy {
+    pub const ALL: [BackoffStrategy; 3] = [
+        BackoffStrategy::Fixed,
+        BackoffStrategy::Linear,
+        BackoffStrategy::Exponential,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Fixed => "Fixed",
+            Self::Linear => "Linear",
+            Self::Exponential => "Exponential",
+        }
+    }
+}
+
+/// How network requests are retried: how often, how long to wait between
+/// attempts, and how long a single attempt may take.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    pub retries: u32,
+    pub backoff: BackoffStrategy,
+    pub base_delay_secs: u64,
+    pub timeout_secs: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            retries: 3,
+            backoff: BackoffStrategy::Exponential,
+            base_delay_secs: 2,
+            timeout_secs: 30,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Wait before the given retry, counting from 1, capped at a minute.
+    pub fn delay(&self, retry: u32) -> Duration {
+        let secs = match self.backoff {
+            BackoffStrategy::Fixed => self.base_delay_secs,
+            BackoffStrategy::Linear => self.base_delay_secs.saturating_mul(u64::from(retry)),
+            BackoffStrategy::Exponential => self
+                .base_delay_secs
+                .saturating_mul(2u64.saturating_pow(retry.saturating_sub(1))),
+        };
+        Duration::from_secs(secs.min(MAX_DELAY_SECS))
+    }
+
+    /// Runs `attempt` until it succeeds or the retries are used up, returning
+    /// the last error. Each attempt is bounded by the policy's timeout.
+    pub async fn run<T, F, Fut>(&self, label: &str, mut attempt: F) -> Result<T, String>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, String>>,
+    {
+        let timeout = Duration::from_secs(self.timeout_secs);
+        let mut last_err = String::new();
+        for retry in 0..=self.retries {
+            if retry > 0 {
+                tokio::time::sleep(self.delay(retry)).await;
+            }
+            match tokio::time::timeout(timeout, attempt()).await {
+                Ok(Ok(value)) => return Ok(value),
+                Ok(Err(e)) => last_err = e,
+                Err(_) => last_err = "Request timed out".to_string(),
+            }
+            debug!("{} attempt {} failed: {}", label, retry + 1, last_err);
+        }
+        Err(last_err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delays_follow_strategy() {
+        let mut policy = RetryPolicy {
+            base_delay_secs: 2,
+            ..RetryPolicy::default()
+        };
+        let delays = |policy: &RetryPolicy| -> Vec<u64> {
+            (1..=4).map(|retry| policy.delay(retry).as_secs()).collect()
+        };
+
+        policy.backoff = BackoffStrategy::Fixed;
+        assert_eq!(delays(&policy), vec![2, 2, 2, 2]);
+        policy.backoff = BackoffStrategy::Linear;
+        assert_eq!(delays(&policy), vec![2, 4, 6, 8]);
+        policy.backoff = BackoffStrategy::Exponential;
+        assert_eq!(delays(&policy), vec![2, 4, 8, 16]);
+        assert_eq!(policy.delay(10).as_secs(), MAX_DELAY_SECS);
+    }
+
+    #[tokio::test]
+    async fn run_retries_until_success() {
+        let policy = RetryPolicy {
+            retries: 2,
+            base_delay_secs: 0,
+            ..RetryPolicy::default()
+        };
+        let mut calls = 0;
+        let result = policy
+            .run("test", || {
+                calls += 1;
+                let outcome = if calls < 3 {
+                    Err(format!("failure {}", calls))
+                } else {
+                    Ok(calls)
+                };
+                async move { outcome }
+            })
+            .await;
+        assert_eq!(result, Ok(3));
+
+        let mut calls = 0;
+        let result: Result<(), String> = policy
+            .run("test", || {
+                calls += 1;
+                let outcome = Err(format!("failure {}", calls));
+                async move { outcome }
+            })
+            .await;
+        assert_eq!(result, Err("failure 3".to_string()));
+    }
+}
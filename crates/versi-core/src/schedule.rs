@@ -1,9 +1,22 @@
-use chrono::NaiveDate;
+use chrono::{Months, NaiveDate};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::time::Duration;
 
 const SCHEDULE_URL: &str = "https://raw.githubusercontent.com/nodejs/Release/main/schedule.json";
 
+/// The only host the schedule is accepted from, even after redirects. EOL
+/// dates drive bulk uninstalls, so data from anywhere else is refused.
+const SCHEDULE_HOST: &str = "raw.githubusercontent.com";
+
+/// How long a fetched schedule is used before it is fetched again. It only
+/// changes a few times a year.
+pub const SCHEDULE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Release lines below this are long gone; a schedule without them is not
+/// the real one.
+const MIN_MAJORS: usize = 10;
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct VersionSchedule {
     pub start: String,
@@ -16,12 +29,126 @@ pub struct VersionSchedule {
     pub codename: Option<String>,
 }
 
+/// Where a release line stands against its end-of-life date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EolCountdown {
+    /// Support ended on this date.
+    Ended(NaiveDate),
+    /// Support ends on `end`, `days` from today, which is `months` whole
+    /// calendar months away.
+    Ending {
+        end: NaiveDate,
+        days: i64,
+        months: u32,
+    },
+}
+
+impl EolCountdown {
+    /// The countdown for a line whose support ends on `end`.
+    pub fn new(end: NaiveDate, today: NaiveDate) -> Self {
+        if end <= today {
+            return Self::Ended(end);
+        }
+        let months = (1..)
+            .take_while(|&n| {
+                today
+                    .checked_add_months(Months::new(n))
+                    .is_some_and(|date| date <= end)
+            })
+            .last()
+            .unwrap_or(0);
+        Self::Ending {
+            end,
+            days: (end - today).num_days(),
+            months,
+        }
+    }
+
+    pub fn end(&self) -> NaiveDate {
+        match *self {
+            Self::Ended(end) | Self::Ending { end, .. } => end,
+        }
+    }
+
+    /// "EOL since 2023-09-11", "EOL in 12 days (2025-04-30)" or
+    /// "EOL in 3 months (2025-04-30)".
+    pub fn label(&self) -> String {
+        let (days, months) = match *self {
+            Self::Ended(end) => return format!("EOL since {}", end.format("%Y-%m-%d")),
+            Self::Ending { days, months, .. } => (days, months),
+        };
+        let when = match (days, months) {
+            (1, _) => "tomorrow".to_string(),
+            (days, months) if days < 60 || months < 2 => format!("in {} days", days),
+            (_, months) if months < 24 => format!("in {} months", months),
+            (_, months) => format!("in {} years", months / 12),
+        };
+        format!("EOL {} ({})", when, self.end().format("%Y-%m-%d"))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReleaseSchedule {
     pub versions: HashMap<u32, VersionSchedule>,
 }
 
 impl ReleaseSchedule {
+    /// Parses `schedule.json`, rejecting malformed keys or dates and end
+    /// dates before the start. Fields added upstream later are ignored.
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let raw: HashMap<String, VersionSchedule> = serde_json::from_str(json)
+            .map_err(|e| format!("Failed to parse release schedule: {}", e))?;
+
+        let mut versions = HashMap::new();
+        for (key, value) in raw {
+            let Some(line) = key.strip_prefix('v') else {
+                return Err(format!("Unexpected release line in schedule: {}", key));
+            };
+            // 0.x lines predate semver majors and are not tracked.
+            if let Some(minor) = line.strip_prefix("0.")
+                && minor.parse::<u32>().is_ok()
+            {
+                continue;
+            }
+            let major: u32 = line
+                .parse()
+                .map_err(|_| format!("Unexpected release line in schedule: {}", key))?;
+            versions.insert(major, value);
+        }
+
+        let schedule = Self { versions };
+        schedule.validate()?;
+        Ok(schedule)
+    }
+
+    /// Checks that every date parses and each line ends after it starts.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.versions.len() < MIN_MAJORS {
+            return Err(format!(
+                "Release schedule lists only {} release lines",
+                self.versions.len()
+            ));
+        }
+        let date = |major: u32, value: &str| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map_err(|_| format!("Invalid date for Node {} in schedule: {}", major, value))
+        };
+        for (&major, schedule) in &self.versions {
+            let start = date(major, &schedule.start)?;
+            let end = date(major, &schedule.end)?;
+            for value in [&schedule.lts, &schedule.maintenance].into_iter().flatten() {
+                date(major, value)?;
+            }
+            if end < start {
+                return Err(format!("Node {} ends before it starts in schedule", major));
+            }
+            if schedule.codename.as_deref().is_some_and(str::is_empty) {
+                return Err(format!("Empty codename for Node {} in schedule", major));
+            }
+        }
+        Ok(())
+    }
+
     pub fn is_active(&self, major: u32) -> bool {
         let Some(schedule) = self.versions.get(&major) else {
             return major >= 18;
@@ -35,6 +162,38 @@ impl ReleaseSchedule {
         end_date > today
     }
 
+    pub fn end_date(&self, major: u32) -> Option<NaiveDate> {
+        let schedule = self.versions.get(&major)?;
+        NaiveDate::parse_from_str(&schedule.end, "%Y-%m-%d").ok()
+    }
+
+    /// The countdown for `major` once it has ended or ends within
+    /// `warn_within_days` of `today`.
+    pub fn eol_countdown(
+        &self,
+        major: u32,
+        today: NaiveDate,
+        warn_within_days: u32,
+    ) -> Option<EolCountdown> {
+        let countdown = EolCountdown::new(self.end_date(major)?, today);
+        match countdown {
+            EolCountdown::Ending { days, .. } if days > i64::from(warn_within_days) => None,
+            countdown => Some(countdown),
+        }
+    }
+
+    /// When the line became LTS, if it is an LTS line.
+    pub fn lts_date(&self, major: u32) -> Option<NaiveDate> {
+        let schedule = self.versions.get(&major)?;
+        NaiveDate::parse_from_str(schedule.lts.as_deref()?, "%Y-%m-%d").ok()
+    }
+
+    /// When the line moved to maintenance, receiving only critical fixes.
+    pub fn maintenance_date(&self, major: u32) -> Option<NaiveDate> {
+        let schedule = self.versions.get(&major)?;
+        NaiveDate::parse_from_str(schedule.maintenance.as_deref()?, "%Y-%m-%d").ok()
+    }
+
     pub fn is_lts(&self, major: u32) -> bool {
         self.versions
             .get(&major)
@@ -72,20 +231,19 @@ pub async fn fetch_release_schedule(client: &reqwest::Client) -> Result<ReleaseS
         .await
         .map_err(|e| format!("Failed to fetch release schedule: {}", e))?;
 
-    let raw: HashMap<String, VersionSchedule> = response
-        .json()
+    let url = response.url();
+    if url.scheme() != "https" || url.host_str() != Some(SCHEDULE_HOST) {
+        return Err(format!("Refusing release schedule served from {}", url));
+    }
+    let response = response
+        .error_for_status()
+        .map_err(|e| format!("Failed to fetch release schedule: {}", e))?;
+    let body = response
+        .text()
         .await
-        .map_err(|e| format!("Failed to parse release schedule: {}", e))?;
-
-    let versions: HashMap<u32, VersionSchedule> = raw
-        .into_iter()
-        .filter_map(|(key, value)| {
-            let major = key.trim_start_matches('v').parse().ok()?;
-            Some((major, value))
-        })
-        .collect();
+        .map_err(|e| format!("Failed to fetch release schedule: {}", e))?;
 
-    Ok(ReleaseSchedule { versions })
+    ReleaseSchedule::parse(&body)
 }
 
 #[cfg(test)]
@@ -193,6 +351,39 @@ mod tests {
         assert!(!schedule.is_active(16));
     }
 
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_eol_countdown_labels() {
+        let end = date("2025-04-30");
+        let label = |today: &str| EolCountdown::new(end, date(today)).label();
+        assert_eq!(label("2025-05-01"), "EOL since 2025-04-30");
+        assert_eq!(label("2025-04-30"), "EOL since 2025-04-30");
+        assert_eq!(label("2025-04-29"), "EOL tomorrow (2025-04-30)");
+        assert_eq!(label("2025-03-01"), "EOL in 60 days (2025-04-30)");
+        assert_eq!(label("2025-01-30"), "EOL in 3 months (2025-04-30)");
+        assert_eq!(label("2025-02-01"), "EOL in 2 months (2025-04-30)");
+        assert_eq!(label("2022-04-30"), "EOL in 3 years (2025-04-30)");
+    }
+
+    #[test]
+    fn test_eol_countdown_threshold() {
+        let schedule = create_test_schedule();
+        let today = date("2025-01-30");
+        assert_eq!(
+            schedule.eol_countdown(16, today, 0),
+            Some(EolCountdown::Ended(date("2023-09-11")))
+        );
+        assert!(matches!(
+            schedule.eol_countdown(18, today, 90),
+            Some(EolCountdown::Ending { days: 90, .. })
+        ));
+        assert_eq!(schedule.eol_countdown(18, today, 89), None);
+        assert_eq!(schedule.eol_countdown(99, today, 365), None);
+    }
+
     #[test]
     fn test_active_lts_versions() {
         let schedule = create_test_schedule();
@@ -201,4 +392,62 @@ mod tests {
         assert!(!active_lts.contains(&23));
         assert!(!active_lts.contains(&16));
     }
+
+    fn schedule_json(extra: &str) -> String {
+        let mut entries: Vec<String> = (8..=24)
+            .map(|major| {
+                format!(
+                    r#""v{major}": {{"start": "2017-05-30", "end": "2030-04-30", "codename": "Line{major}"}}"#
+                )
+            })
+            .collect();
+        entries.push(r#""v0.12": {"start": "2015-02-06", "end": "2016-12-31"}"#.to_string());
+        if !extra.is_empty() {
+            entries.push(extra.to_string());
+        }
+        format!("{{{}}}", entries.join(","))
+    }
+
+    #[test]
+    fn test_parse_accepts_upstream_format() {
+        let schedule = ReleaseSchedule::parse(&schedule_json("")).unwrap();
+        assert_eq!(schedule.versions.len(), 17);
+        assert_eq!(schedule.codename(22), Some("Line22"));
+    }
+
+    #[test]
+    fn test_parse_ignores_new_fields() {
+        let schedule = ReleaseSchedule::parse(&schedule_json(
+            r#""v25": {"start": "2025-04-22", "end": "2028-04-30", "eol": true}"#,
+        ))
+        .unwrap();
+        assert_eq!(schedule.end_date(25), NaiveDate::from_ymd_opt(2028, 4, 30));
+    }
+
+    #[test]
+    fn test_parse_rejects_unexpected_data() {
+        for extra in [
+            r#""latest": {"start": "2025-04-22", "end": "2028-04-30"}"#,
+            r#""v25": {"start": "2025-04-22", "end": "soon"}"#,
+            r#""v25": {"start": "2025-04-22", "end": "2020-01-01"}"#,
+        ] {
+            assert!(
+                ReleaseSchedule::parse(&schedule_json(extra)).is_err(),
+                "accepted {}",
+                extra
+            );
+        }
+        assert!(
+            ReleaseSchedule::parse(r#"{"v22": {"start": "2024-04-24", "end": "2027-04-30"}}"#)
+                .is_err()
+        );
+        assert!(ReleaseSchedule::parse("[]").is_err());
+    }
+
+    #[test]
+    fn test_end_date() {
+        let schedule = create_test_schedule();
+        assert_eq!(schedule.end_date(18), NaiveDate::from_ymd_opt(2025, 4, 30));
+        assert_eq!(schedule.end_date(99), None);
+    }
 }
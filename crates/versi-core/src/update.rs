@@ -1,5 +1,9 @@
+use std::path::Path;
+
 use serde::Deserialize;
 
+use crate::github::GitHubClient;
+
 const GITHUB_REPO: &str = "almeidx/versi";
 
 #[derive(Debug, Clone)]
@@ -10,6 +14,15 @@ pub struct AppUpdate {
     pub release_notes: Option<String>,
     pub download_url: Option<String>,
     pub download_size: Option<u64>,
+    /// SHA-256 of the download from the release, checked before the update
+    /// is applied.
+    pub download_sha256: Option<String>,
+    /// Share of installs the release is offered to, from a
+    /// `<!-- rollout: 25 -->` comment in the release notes. `None` offers it
+    /// to everyone.
+    pub rollout_percentage: Option<u8>,
+    /// How this copy was installed, on Windows.
+    pub channel: Option<InstallChannel>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -17,6 +30,9 @@ pub struct GitHubAsset {
     pub name: String,
     pub browser_download_url: String,
     pub size: u64,
+    /// `sha256:<hex>`, which GitHub computes when the asset is uploaded.
+    #[serde(default)]
+    pub digest: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -26,49 +42,254 @@ pub struct GitHubRelease {
     pub body: Option<String>,
     #[serde(default)]
     pub assets: Vec<GitHubAsset>,
+    #[serde(default)]
+    pub prerelease: bool,
+    #[serde(default)]
+    pub draft: bool,
 }
 
-pub fn asset_name(version: &str) -> Option<String> {
-    let name = if cfg!(target_os = "macos") && cfg!(target_arch = "aarch64") {
-        format!("versi-{version}-macos-arm64.zip")
-    } else if cfg!(target_os = "macos") && cfg!(target_arch = "x86_64") {
-        format!("versi-{version}-macos-x64.zip")
-    } else if cfg!(target_os = "linux") && cfg!(target_arch = "x86_64") {
-        format!("versi-{version}-linux-x64.zip")
-    } else if cfg!(target_os = "linux") && cfg!(target_arch = "aarch64") {
-        format!("versi-{version}-linux-arm64.zip")
-    } else if cfg!(target_os = "windows") && cfg!(target_arch = "x86_64") {
-        format!("versi-{version}-windows-x64.msi")
-    } else {
-        return None;
+/// How a release asset is packaged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Packaging {
+    Zip,
+    Msi,
+    Dmg,
+    AppImage,
+    Deb,
+}
+
+impl Packaging {
+    const ALL: [Self; 5] = [Self::Zip, Self::Msi, Self::Dmg, Self::AppImage, Self::Deb];
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Zip => "zip",
+            Self::Msi => "msi",
+            Self::Dmg => "dmg",
+            Self::AppImage => "appimage",
+            Self::Deb => "deb",
+        }
+    }
+
+    /// The packaging of an asset, from its file name.
+    pub fn of(file_name: &str) -> Option<Self> {
+        let extension = file_name.rsplit_once('.')?.1.to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|packaging| packaging.extension() == extension)
+    }
+
+    /// Packagings the running install can update itself from, best first.
+    /// An AppImage or a `.deb` install is updated in the same format, so the
+    /// update lands where the install lives. Installs a package manager owns
+    /// update through it instead.
+    pub fn for_this_install(channel: Option<InstallChannel>) -> Vec<Self> {
+        if let Some(channel) = channel {
+            match channel {
+                InstallChannel::Msi => vec![Self::Msi, Self::Zip],
+                InstallChannel::Portable => vec![Self::Zip],
+                InstallChannel::Winget | InstallChannel::Scoop | InstallChannel::Msix => Vec::new(),
+            }
+        } else if cfg!(target_os = "macos") {
+            vec![Self::Zip]
+        } else if std::env::var_os("APPIMAGE").is_some() {
+            vec![Self::AppImage]
+        } else if installed_from_deb() {
+            vec![Self::Deb, Self::Zip]
+        } else {
+            vec![Self::Zip]
+        }
+    }
+}
+
+/// How Versi was installed on Windows. Package managers track the version
+/// they installed, so installs they own must not replace themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallChannel {
+    /// The MSI from the release page.
+    Msi,
+    /// `winget install`, either of the MSI or of the zip.
+    Winget,
+    Scoop,
+    /// An MSIX package from the Store or App Installer.
+    Msix,
+    /// An unpacked zip anywhere else.
+    Portable,
+}
+
+impl InstallChannel {
+    /// Where the running binary came from, or `None` off Windows.
+    pub async fn detect() -> Option<Self> {
+        if !cfg!(target_os = "windows") {
+            return None;
+        }
+        let exe = std::env::current_exe().ok()?;
+        let local_app_data = std::env::var_os("LOCALAPPDATA").map(std::path::PathBuf::from);
+        let install_source = read_install_source().await;
+        let channel =
+            Self::from_install(&exe, local_app_data.as_deref(), install_source.as_deref());
+        log::debug!("Install channel: {:?}", channel);
+        Some(channel)
+    }
+
+    /// Classifies an install by where the binary lives. The MSI records in
+    /// the registry whether winget ran it.
+    fn from_install(
+        exe: &Path,
+        local_app_data: Option<&Path>,
+        install_source: Option<&str>,
+    ) -> Self {
+        let normalize = |path: &Path| {
+            path.to_string_lossy()
+                .to_ascii_lowercase()
+                .replace('/', "\\")
+        };
+        let path = normalize(exe);
+        let msi_exe = local_app_data.map(|dir| format!("{}\\versi\\versi.exe", normalize(dir)));
+        if path.contains("\\windowsapps\\") {
+            Self::Msix
+        } else if path.contains("\\scoop\\apps\\") {
+            Self::Scoop
+        } else if path.contains("\\winget\\packages\\") || path.contains("\\winget\\links\\") {
+            Self::Winget
+        } else if msi_exe.is_some_and(|msi_exe| path == msi_exe) {
+            if install_source.is_some_and(|source| source.eq_ignore_ascii_case("winget")) {
+                Self::Winget
+            } else {
+                Self::Msi
+            }
+        } else {
+            Self::Portable
+        }
+    }
+
+    /// Whether the app may download and apply updates itself.
+    pub fn self_updates(self) -> bool {
+        matches!(self, Self::Msi | Self::Portable)
+    }
+
+    /// Who updates the install when the app does not.
+    pub fn package_manager(self) -> Option<&'static str> {
+        match self {
+            Self::Winget => Some("winget"),
+            Self::Scoop => Some("Scoop"),
+            Self::Msix => Some("the Microsoft Store"),
+            Self::Msi | Self::Portable => None,
+        }
+    }
+
+    /// The command that updates the install, for channels that have one.
+    pub fn update_command(self) -> Option<&'static str> {
+        match self {
+            Self::Winget => Some("winget upgrade --id almeidx.Versi --exact"),
+            Self::Scoop => Some("scoop update versi"),
+            Self::Msix | Self::Msi | Self::Portable => None,
+        }
+    }
+}
+
+/// The `InstallSource` value the MSI writes, `winget` when winget passed it.
+async fn read_install_source() -> Option<String> {
+    use crate::HideWindow;
+
+    let output = tokio::process::Command::new("reg")
+        .args(["query", r"HKCU\Software\Versi", "/v", "InstallSource"])
+        .hide_window()
+        .output()
+        .await
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| {
+            let mut parts = line.split_whitespace();
+            if parts.next() != Some("InstallSource") {
+                return None;
+            }
+            parts.nth(1).map(str::to_string)
+        })
+}
+
+/// Whether the running binary was installed by dpkg.
+fn installed_from_deb() -> bool {
+    let Ok(exe) = std::env::current_exe() else {
+        return false;
     };
-    Some(name)
+    std::fs::read_to_string("/var/lib/dpkg/info/versi.list")
+        .is_ok_and(|files| files.lines().any(|file| exe == std::path::Path::new(file)))
+}
+
+fn os_names(os: &str) -> &'static [&'static str] {
+    match os {
+        "macos" => &["macos", "darwin"],
+        "linux" => &["linux"],
+        "windows" => &["windows", "win"],
+        _ => &[],
+    }
+}
+
+fn arch_names(arch: &str) -> &'static [&'static str] {
+    match arch {
+        "x86_64" => &["x64", "x86_64", "amd64"],
+        "aarch64" => &["arm64", "aarch64"],
+        _ => &[],
+    }
+}
+
+/// Whether `name` is the asset for `os` and `arch` packaged as `packaging`,
+/// e.g. `versi-1.2.0-linux-x64.zip` or `versi_1.2.0_amd64.deb`. AppImages
+/// and `.deb` packages only exist for Linux, so they may leave the OS out.
+fn asset_matches(name: &str, os: &str, arch: &str, packaging: Packaging) -> bool {
+    let name = name.to_ascii_lowercase();
+    let Some(stem) = name.strip_suffix(&format!(".{}", packaging.extension())) else {
+        return false;
+    };
+    if !stem.starts_with("versi") {
+        return false;
+    }
+    // Names like `x86_64` contain separators, so they are matched as
+    // substrings bounded by separators rather than as split tokens.
+    let is_separator = |c: Option<char>| c.is_none_or(|c| matches!(c, '-' | '_' | '.'));
+    let has = |names: &[&str]| {
+        names.iter().any(|part| {
+            stem.match_indices(part).any(|(start, _)| {
+                is_separator(stem[..start].chars().next_back())
+                    && is_separator(stem[start + part.len()..].chars().next())
+            })
+        })
+    };
+    let os_matches = match packaging {
+        Packaging::AppImage | Packaging::Deb => os == "linux",
+        _ => has(os_names(os)),
+    };
+    os_matches && has(arch_names(arch))
+}
+
+/// The asset to update from: the first packaging in `packagings` that the
+/// release has an asset for on `os` and `arch`.
+pub fn select_asset<'a>(
+    assets: &'a [GitHubAsset],
+    os: &str,
+    arch: &str,
+    packagings: &[Packaging],
+) -> Option<&'a GitHubAsset> {
+    packagings.iter().find_map(|&packaging| {
+        assets
+            .iter()
+            .find(|asset| asset_matches(&asset.name, os, arch, packaging))
+    })
 }
 
 pub async fn check_for_update(
-    client: &reqwest::Client,
+    github: &GitHubClient,
     current_version: &str,
 ) -> Result<Option<AppUpdate>, String> {
-    let url = format!(
-        "https://api.github.com/repos/{}/releases/latest",
-        GITHUB_REPO
-    );
-
-    let response = client
-        .get(&url)
-        .header("User-Agent", "versi")
-        .send()
+    let Some(release) = github
+        .latest_release(GITHUB_REPO)
         .await
-        .map_err(|e| format!("Failed to check for app update: {}", e))?;
-
-    if !response.status().is_success() {
+        .map_err(|e| format!("Failed to check for app update: {}", e))?
+    else {
         return Ok(None);
-    }
-
-    let release: GitHubRelease = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse app update response: {}", e))?;
+    };
 
     let latest = release
         .tag_name
@@ -77,29 +298,50 @@ pub async fn check_for_update(
     let current = current_version.strip_prefix('v').unwrap_or(current_version);
 
     if is_newer_version(latest, current) {
-        let (download_url, download_size) = asset_name(latest)
-            .and_then(|expected| {
-                release
-                    .assets
-                    .iter()
-                    .find(|a| a.name == expected)
-                    .map(|a| (Some(a.browser_download_url.clone()), Some(a.size)))
-            })
-            .unwrap_or((None, None));
+        let channel = InstallChannel::detect().await;
+        let asset = select_asset(
+            &release.assets,
+            std::env::consts::OS,
+            std::env::consts::ARCH,
+            &Packaging::for_this_install(channel),
+        );
+        let download_url = asset.map(|a| a.browser_download_url.clone());
+        let download_size = asset.map(|a| a.size);
+        let download_sha256 = asset
+            .and_then(|a| a.digest.as_deref())
+            .and_then(crate::parse_sha256);
 
+        let rollout_percentage = release.body.as_deref().and_then(rollout_percentage);
         Ok(Some(AppUpdate {
             current_version: current.to_string(),
             latest_version: latest.to_string(),
             release_url: release.html_url,
+            rollout_percentage,
             release_notes: release.body,
             download_url,
             download_size,
+            download_sha256,
+            channel,
         }))
     } else {
         Ok(None)
     }
 }
 
+/// Reads the staged rollout percentage from release notes, where it is kept
+/// in an HTML comment so it does not show on the release page.
+fn rollout_percentage(notes: &str) -> Option<u8> {
+    notes.lines().find_map(|line| {
+        let comment = line.trim().strip_prefix("<!--")?.strip_suffix("-->")?;
+        let (key, value) = comment.split_once(':')?;
+        if !key.trim().eq_ignore_ascii_case("rollout") {
+            return None;
+        }
+        let percentage: u8 = value.trim().trim_end_matches('%').trim().parse().ok()?;
+        Some(percentage.min(100))
+    })
+}
+
 pub fn is_newer_version(latest: &str, current: &str) -> bool {
     let parse_version = |v: &str| -> Option<(u32, u32, u32)> {
         let parts: Vec<&str> = v.split('.').collect();
@@ -139,4 +381,101 @@ mod tests {
         assert!(!is_newer_version("1.0.0", "1.0.1"));
         assert!(!is_newer_version("0.9.0", "1.0.0"));
     }
+
+    #[test]
+    fn reads_the_rollout_percentage_from_release_notes() {
+        assert_eq!(
+            rollout_percentage("## Changes\n<!-- rollout: 25 -->\n- Fixes"),
+            Some(25)
+        );
+        assert_eq!(rollout_percentage("<!-- Rollout: 150% -->"), Some(100));
+        assert_eq!(rollout_percentage("<!-- released by CI -->"), None);
+        assert_eq!(rollout_percentage("rollout: 10"), None);
+    }
+
+    #[test]
+    fn selects_the_asset_for_the_platform_and_packaging() {
+        let assets: Vec<GitHubAsset> = [
+            "versi-1.2.0-macos-arm64.zip",
+            "versi-1.2.0-linux-x64.zip",
+            "versi-1.2.0-linux-arm64.zip",
+            "versi-1.2.0-x86_64.AppImage",
+            "versi_1.2.0_amd64.deb",
+            "versi-1.2.0-windows-x64.msi",
+            "versi-1.2.0-windows-x64.zip",
+        ]
+        .into_iter()
+        .map(|name| GitHubAsset {
+            name: name.to_string(),
+            browser_download_url: format!("https://example.com/{name}"),
+            size: 1,
+            digest: None,
+        })
+        .collect();
+        let select = |os, arch, packagings: &[Packaging]| {
+            select_asset(&assets, os, arch, packagings).map(|a| a.name.as_str())
+        };
+
+        assert_eq!(
+            select("linux", "x86_64", &[Packaging::AppImage]),
+            Some("versi-1.2.0-x86_64.AppImage")
+        );
+        assert_eq!(
+            select("linux", "x86_64", &[Packaging::Deb, Packaging::Zip]),
+            Some("versi_1.2.0_amd64.deb")
+        );
+        assert_eq!(
+            select("linux", "aarch64", &[Packaging::Deb, Packaging::Zip]),
+            Some("versi-1.2.0-linux-arm64.zip")
+        );
+        assert_eq!(select("linux", "aarch64", &[Packaging::AppImage]), None);
+        assert_eq!(
+            select("windows", "x86_64", &[Packaging::Msi, Packaging::Zip]),
+            Some("versi-1.2.0-windows-x64.msi")
+        );
+        assert_eq!(select("macos", "x86_64", &[Packaging::Zip]), None);
+        assert_eq!(
+            Packaging::of("versi-1.2.0-x86_64.AppImage"),
+            Some(Packaging::AppImage)
+        );
+    }
+
+    #[test]
+    fn detects_the_install_channel_from_the_binary_path() {
+        let local = Path::new(r"C:\Users\me\AppData\Local");
+        let channel =
+            |exe: &str, source| InstallChannel::from_install(Path::new(exe), Some(local), source);
+
+        assert_eq!(
+            channel(
+                r"C:\Program Files\WindowsApps\Versi_1.2.0_x64__abc\versi.exe",
+                None
+            ),
+            InstallChannel::Msix
+        );
+        assert_eq!(
+            channel(r"C:\Users\me\scoop\apps\versi\current\versi.exe", None),
+            InstallChannel::Scoop
+        );
+        assert_eq!(
+            channel(
+                r"C:\Users\me\AppData\Local\Microsoft\WinGet\Packages\almeidx.Versi_abc\versi.exe",
+                None
+            ),
+            InstallChannel::Winget
+        );
+        assert_eq!(
+            channel(r"C:\Users\me\AppData\Local\Versi\versi.exe", Some("winget")),
+            InstallChannel::Winget
+        );
+        assert_eq!(
+            channel(r"C:\Users\me\AppData\Local\Versi\versi.exe", Some("msi")),
+            InstallChannel::Msi
+        );
+        assert_eq!(
+            channel(r"D:\Tools\versi\versi.exe", Some("winget")),
+            InstallChannel::Portable
+        );
+        assert!(Packaging::for_this_install(Some(InstallChannel::Scoop)).is_empty());
+    }
 }
@@ -1,21 +1,34 @@
 use async_trait::async_trait;
-use log::{debug, error, info, trace};
-use std::path::PathBuf;
+use log::{debug, error, info, trace, warn};
+use std::path::{Path, PathBuf};
 use tokio::process::Command;
+use tokio::sync::mpsc;
 
-use versi_core::HideWindow;
+use versi_core::{Elevation, HideWindow};
 
 use versi_backend::{
-    BackendError, BackendInfo, InstalledVersion, ManagerCapabilities, NodeVersion, RemoteVersion,
-    ShellInitOptions, VersionManager,
+    BackendError, BackendInfo, InstallProgress, InstalledVersion, ManagerCapabilities, NodeVersion,
+    RemoteVersion, ShellInitOptions, VersionManager, is_valid_env_key,
 };
 
-use crate::version::{parse_installed_versions, parse_remote_versions};
+use crate::version::{parse_install_progress, parse_installed_versions, parse_remote_versions};
+
+/// First fnm release with `--progress`, which draws the download progress
+/// bar even when stderr is not a terminal.
+const PROGRESS_SINCE: &str = "1.36.0";
 
 #[derive(Debug, Clone)]
 pub enum Environment {
     Native,
-    Wsl { distro: String, fnm_path: String },
+    Wsl {
+        distro: String,
+        fnm_path: String,
+    },
+    /// Runs through the bash of Git Bash or MSYS2.
+    Msys {
+        bash: String,
+        fnm_path: String,
+    },
 }
 
 #[derive(Clone)]
@@ -23,7 +36,16 @@ pub struct FnmBackend {
     info: BackendInfo,
     fnm_dir: Option<PathBuf>,
     node_dist_mirror: Option<String>,
+    env_overrides: Vec<(String, String)>,
+    /// Inherited variables left out of native commands, see
+    /// [`versi_backend::conflicting_env_vars`].
+    env_removals: Vec<String>,
     environment: Environment,
+    /// `fnm_dir` is the directory shared by every user.
+    system_wide: bool,
+    /// Whether `fnm_dir` was read-only for the current user when the backend
+    /// was set up.
+    needs_elevation: bool,
 }
 
 impl FnmBackend {
@@ -35,10 +57,15 @@ impl FnmBackend {
                 version,
                 data_dir: fnm_dir.clone(),
                 in_path: true,
+                source: None,
             },
             fnm_dir,
             node_dist_mirror: None,
+            env_overrides: Vec::new(),
+            env_removals: Vec::new(),
             environment: Environment::Native,
+            system_wide: false,
+            needs_elevation: false,
         }
     }
 
@@ -48,11 +75,30 @@ impl FnmBackend {
         self
     }
 
+    /// Installs versions into `dir`, shared by every user of the machine.
+    /// Commands that write there ask for administrator rights when the
+    /// current user cannot.
+    pub fn with_system_wide_dir(mut self, dir: PathBuf) -> Self {
+        self.needs_elevation = !versi_core::is_writable(&dir);
+        self.system_wide = true;
+        self.with_fnm_dir(dir)
+    }
+
+    pub fn with_source(mut self, source: Option<String>) -> Self {
+        self.info.source = source;
+        self
+    }
+
     pub fn with_node_dist_mirror(mut self, mirror: String) -> Self {
         self.node_dist_mirror = Some(mirror);
         self
     }
 
+    pub fn with_env_removals(mut self, vars: Vec<String>) -> Self {
+        self.env_removals = vars;
+        self
+    }
+
     pub fn with_wsl(distro: String, fnm_path: String) -> Self {
         Self {
             info: BackendInfo {
@@ -61,10 +107,35 @@ impl FnmBackend {
                 version: None,
                 data_dir: None,
                 in_path: true,
+                source: None,
             },
             fnm_dir: None,
             node_dist_mirror: None,
+            env_overrides: Vec::new(),
+            env_removals: Vec::new(),
             environment: Environment::Wsl { distro, fnm_path },
+            system_wide: false,
+            needs_elevation: false,
+        }
+    }
+
+    pub fn with_msys(bash: String, fnm_path: String) -> Self {
+        Self {
+            info: BackendInfo {
+                name: "fnm",
+                path: PathBuf::from(&fnm_path),
+                version: None,
+                data_dir: None,
+                in_path: true,
+                source: None,
+            },
+            fnm_dir: None,
+            node_dist_mirror: None,
+            env_overrides: Vec::new(),
+            env_removals: Vec::new(),
+            environment: Environment::Msys { bash, fnm_path },
+            system_wide: false,
+            needs_elevation: false,
         }
     }
 
@@ -80,6 +151,10 @@ impl FnmBackend {
                 let mut cmd = Command::new(&self.info.path);
                 cmd.args(args);
 
+                for key in &self.env_removals {
+                    cmd.env_remove(key);
+                }
+
                 if let Some(dir) = &self.fnm_dir {
                     debug!("Setting FNM_DIR={:?}", dir);
                     cmd.env("FNM_DIR", dir);
@@ -90,6 +165,11 @@ impl FnmBackend {
                     cmd.env("FNM_NODE_DIST_MIRROR", mirror);
                 }
 
+                for (key, value) in &self.env_overrides {
+                    debug!("Setting {} from environment overrides", key);
+                    cmd.env(key, value);
+                }
+
                 cmd.hide_window();
                 cmd
             }
@@ -102,18 +182,111 @@ impl FnmBackend {
                 );
 
                 let mut cmd = Command::new("wsl.exe");
-                cmd.args(["-d", distro, "--", fnm_path]);
+                cmd.args(["-d", distro, "--"]);
+                if !self.env_overrides.is_empty() {
+                    cmd.arg("env");
+                    cmd.args(
+                        self.env_overrides
+                            .iter()
+                            .map(|(key, value)| format!("{key}={value}")),
+                    );
+                }
+                cmd.arg(fnm_path);
                 cmd.args(args);
                 cmd.hide_window();
                 cmd
             }
+            Environment::Msys { bash, fnm_path } => {
+                debug!(
+                    "Building MSYS fnm command: {} -c {} {}",
+                    bash,
+                    fnm_path,
+                    args.join(" ")
+                );
+
+                let exports: String = self
+                    .env_overrides
+                    .iter()
+                    .map(|(key, value)| format!("export {}={}; ", key, shell_quote(value)))
+                    .collect();
+                let command = std::iter::once(fnm_path.as_str())
+                    .chain(args.iter().copied())
+                    .map(shell_quote)
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let mut cmd = Command::new(bash);
+                cmd.args(["-c", &format!("{exports}exec {command}")]);
+                cmd.hide_window();
+                cmd
+            }
         }
     }
 
+    fn draws_progress(&self) -> bool {
+        self.info
+            .version
+            .as_deref()
+            .is_some_and(|version| !versi_core::is_newer_version(PROGRESS_SINCE, version))
+    }
+
     async fn execute(&self, args: &[&str]) -> Result<String, BackendError> {
+        self.run(self.build_command(args), args, None).await
+    }
+
+    async fn execute_writing(&self, args: &[&str]) -> Result<String, BackendError> {
+        self.execute_writing_streamed(args, None).await
+    }
+
+    /// Runs a command that writes to `fnm_dir`, as an administrator when the
+    /// system-wide directory is not writable by the current user. Each line
+    /// it prints goes to `lines` as it appears.
+    async fn execute_writing_streamed(
+        &self,
+        args: &[&str],
+        lines: Option<&mpsc::Sender<String>>,
+    ) -> Result<String, BackendError> {
+        let cmd = self.build_command(args);
+        let dir = match &self.fnm_dir {
+            Some(dir) if self.system_wide && !versi_core::is_writable(dir) => dir,
+            _ => return self.run(cmd, args, lines).await,
+        };
+
+        let Some(elevation) = Elevation::available() else {
+            return Err(BackendError::BackendSpecific(format!(
+                "{} is not writable by this user. Start Versi as an administrator to change system-wide versions",
+                dir.display()
+            )));
+        };
+        info!(
+            "Asking for administrator rights through {} to write to {}",
+            elevation.label(),
+            dir.display()
+        );
+        self.run(elevation.wrap(&cmd), args, lines)
+            .await
+            .map_err(|e| match e {
+                BackendError::CommandFailed { stderr } if elevation.was_cancelled(&stderr) => {
+                    warn!("Administrator authentication was cancelled");
+                    BackendError::BackendSpecific(
+                        "Administrator authentication was cancelled".to_string(),
+                    )
+                }
+                e => e,
+            })
+    }
+
+    async fn run(
+        &self,
+        mut cmd: Command,
+        args: &[&str],
+        lines: Option<&mpsc::Sender<String>>,
+    ) -> Result<String, BackendError> {
         info!("Executing fnm command: {}", args.join(" "));
 
-        let output = self.build_command(args).output().await?;
+        let output = match lines {
+            Some(lines) => versi_core::run_streamed_redraws(&mut cmd, lines).await?,
+            None => versi_core::run_tracked(&mut cmd).await?,
+        };
 
         debug!("fnm command exit status: {:?}", output.status);
         trace!("fnm stdout: {}", String::from_utf8_lossy(&output.stdout));
@@ -134,6 +307,19 @@ impl FnmBackend {
     }
 }
 
+/// fnm takes the newest releases as flags rather than aliases.
+fn install_target(version: &str) -> &str {
+    match version.trim().to_lowercase().as_str() {
+        "latest" | "current" | "node" => "--latest",
+        "lts" | "lts/*" => "--lts",
+        _ => version,
+    }
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
 #[async_trait]
 impl VersionManager for FnmBackend {
     fn name(&self) -> &'static str {
@@ -148,6 +334,7 @@ impl VersionManager for FnmBackend {
             supports_auto_switch: true,
             supports_corepack: true,
             supports_resolve_engines: true,
+            supports_system_wide: matches!(self.environment, Environment::Native),
         }
     }
 
@@ -171,6 +358,15 @@ impl VersionManager for FnmBackend {
     }
 
     async fn current_version(&self) -> Result<Option<NodeVersion>, BackendError> {
+        // `fnm current` only sees Versi's own environment, not the user's
+        // shells, so their links are read first.
+        if matches!(self.environment, Environment::Native)
+            && let Ok(Some(version)) =
+                tokio::task::spawn_blocking(crate::multishell::last_shell_version).await
+        {
+            return Ok(Some(version));
+        }
+
         let output = self.execute(&["current"]).await?;
         let output = output.trim();
 
@@ -193,17 +389,46 @@ impl VersionManager for FnmBackend {
     }
 
     async fn install(&self, version: &str) -> Result<(), BackendError> {
-        self.execute(&["install", version]).await?;
+        self.execute_writing(&["install", install_target(version)])
+            .await?;
+        Ok(())
+    }
+
+    async fn install_with_progress(
+        &self,
+        version: &str,
+        progress: mpsc::Sender<InstallProgress>,
+    ) -> Result<(), BackendError> {
+        if !self.draws_progress() {
+            return self.install(version).await;
+        }
+
+        let (lines, mut received) = mpsc::channel(32);
+        let install = async move {
+            let args = ["install", "--progress", "always", install_target(version)];
+            let result = self.execute_writing_streamed(&args, Some(&lines)).await;
+            drop(lines);
+            result
+        };
+        let forward = async {
+            while let Some(line) = received.recv().await {
+                if let Some(update) = parse_install_progress(&line) {
+                    let _ = progress.send(update).await;
+                }
+            }
+        };
+        let (result, ()) = tokio::join!(install, forward);
+        result?;
         Ok(())
     }
 
     async fn uninstall(&self, version: &str) -> Result<(), BackendError> {
-        self.execute(&["uninstall", version]).await?;
+        self.execute_writing(&["uninstall", version]).await?;
         Ok(())
     }
 
     async fn set_default(&self, version: &str) -> Result<(), BackendError> {
-        self.execute(&["default", version]).await?;
+        self.execute_writing(&["default", version]).await?;
         Ok(())
     }
 
@@ -212,9 +437,64 @@ impl VersionManager for FnmBackend {
         Ok(())
     }
 
+    async fn exec(&self, version: &str, command: &[&str]) -> Result<String, BackendError> {
+        let using = format!("--using={}", version);
+        let mut args = vec!["exec", using.as_str(), "--"];
+        args.extend_from_slice(command);
+        self.execute(&args).await
+    }
+
+    async fn exec_in(
+        &self,
+        version: &str,
+        command: &[&str],
+        dir: &Path,
+    ) -> Result<String, BackendError> {
+        let using = format!("--using={}", version);
+        let mut args = vec!["exec", using.as_str(), "--"];
+        args.extend_from_slice(command);
+        // wsl.exe starts in the translated Windows working directory too.
+        let mut cmd = self.build_command(&args);
+        cmd.current_dir(dir);
+        self.run(cmd, &args, None).await
+    }
+
+    fn node_binary(&self, version: &str) -> Option<PathBuf> {
+        if !matches!(self.environment, Environment::Native) {
+            return None;
+        }
+        let installation = self
+            .fnm_dir
+            .as_ref()?
+            .join("node-versions")
+            .join(version)
+            .join("installation");
+        if cfg!(windows) {
+            Some(installation.join("node.exe"))
+        } else {
+            Some(installation.join("bin").join("node"))
+        }
+    }
+
+    fn system_wide_dir(&self) -> Option<&Path> {
+        self.fnm_dir.as_deref().filter(|_| self.system_wide)
+    }
+
+    fn needs_elevation(&self) -> bool {
+        self.needs_elevation
+    }
+
     fn shell_init_command(&self, shell: &str, options: &ShellInitOptions) -> Option<String> {
         let mut flags = Vec::new();
 
+        // Shells would otherwise look for versions in the user's own FNM_DIR.
+        let fnm_dir = self
+            .system_wide_dir()
+            .map(|dir| format!("--fnm-dir \"{}\"", dir.display()));
+        if let Some(fnm_dir) = &fnm_dir {
+            flags.push(fnm_dir.as_str());
+        }
+
         if options.use_on_cd {
             flags.push("--use-on-cd");
         }
@@ -241,4 +521,18 @@ impl VersionManager for FnmBackend {
             _ => None,
         }
     }
+
+    fn set_env_overrides(&mut self, vars: Vec<(String, String)>) {
+        // Keys are exported from shell scripts in WSL and MSYS.
+        self.env_overrides = vars
+            .into_iter()
+            .filter(|(key, _)| {
+                let valid = is_valid_env_key(key);
+                if !valid {
+                    warn!("Skipping environment variable with invalid name {:?}", key);
+                }
+                valid
+            })
+            .collect();
+    }
 }
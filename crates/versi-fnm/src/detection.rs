@@ -1,4 +1,6 @@
-use std::path::PathBuf;
+use log::{debug, warn};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
 use tokio::process::Command;
 use which::which;
 
@@ -11,43 +13,52 @@ pub struct FnmDetection {
     pub version: Option<String>,
     pub in_path: bool,
     pub fnm_dir: Option<PathBuf>,
+    /// Where the binary was found, e.g. `PATH` or `Homebrew`.
+    pub source: Option<String>,
 }
 
-pub(crate) async fn detect_fnm() -> FnmDetection {
+/// Detects fnm, trying `binary` first when the user configured one.
+pub(crate) async fn detect_fnm(binary: Option<&Path>) -> FnmDetection {
     let fnm_dir = detect_fnm_dir();
 
-    if let Ok(path) = which("fnm") {
+    let configured = match binary {
+        Some(path) if path.is_file() => Some((path.to_path_buf(), "Settings", false)),
+        Some(path) => {
+            warn!("Configured fnm binary {:?} does not exist", path);
+            None
+        }
+        None => None,
+    };
+
+    let found = configured
+        .or_else(|| which("fnm").ok().map(|path| (path, "PATH", true)))
+        .or_else(|| {
+            fnm_candidates(dirs::home_dir().as_deref(), |key| std::env::var_os(key))
+                .into_iter()
+                .find(|(path, _)| path.is_file())
+                .map(|(path, source)| (path, source, false))
+        });
+
+    if let Some((path, source, in_path)) = found {
+        debug!("Found fnm at {:?} via {}", path, source);
         let version = get_fnm_version(&path).await;
         return FnmDetection {
             found: true,
             path: Some(path),
             version,
-            in_path: true,
+            in_path,
             fnm_dir,
+            source: Some(source.to_string()),
         };
     }
 
-    let common_paths = get_common_fnm_paths();
-
-    for path in common_paths {
-        if path.exists() {
-            let version = get_fnm_version(&path).await;
-            return FnmDetection {
-                found: true,
-                path: Some(path),
-                version,
-                in_path: false,
-                fnm_dir,
-            };
-        }
-    }
-
     FnmDetection {
         found: false,
         path: None,
         version: None,
         in_path: false,
         fnm_dir,
+        source: None,
     }
 }
 
@@ -87,32 +98,73 @@ fn get_fnm_dir_candidates() -> Vec<PathBuf> {
     paths
 }
 
-fn get_common_fnm_paths() -> Vec<PathBuf> {
+/// Install locations of package managers and installers that fnm is
+/// commonly set up with, for when a GUI launch doesn't see the shell's
+/// `PATH`. Each path comes with a label for where it was found.
+fn fnm_candidates(
+    home: Option<&Path>,
+    env: impl Fn(&str) -> Option<OsString>,
+) -> Vec<(PathBuf, &'static str)> {
+    let binary = if cfg!(windows) { "fnm.exe" } else { "fnm" };
     let mut paths = Vec::new();
 
-    if let Some(home) = dirs::home_dir() {
-        paths.push(home.join(".fnm").join("fnm"));
-        paths.push(home.join(".local").join("bin").join("fnm"));
-        paths.push(home.join(".cargo").join("bin").join("fnm"));
+    if let Some(cargo_home) = env("CARGO_HOME") {
+        paths.push((PathBuf::from(cargo_home).join("bin").join(binary), "Cargo"));
+    }
+    if let Some(home) = home {
+        paths.push((home.join(".cargo").join("bin").join(binary), "Cargo"));
+    }
 
-        #[cfg(target_os = "macos")]
-        {
-            paths.push(PathBuf::from("/opt/homebrew/bin/fnm"));
+    if cfg!(windows) {
+        if let Some(scoop) = env("SCOOP") {
+            paths.push((PathBuf::from(scoop).join("shims").join(binary), "Scoop"));
         }
-
-        #[cfg(unix)]
-        {
-            paths.push(PathBuf::from("/usr/local/bin/fnm"));
-            paths.push(PathBuf::from("/usr/bin/fnm"));
+        if let Some(home) = home {
+            paths.push((home.join("scoop").join("shims").join(binary), "Scoop"));
         }
-
-        #[cfg(target_os = "windows")]
+        if let Some(global) = env("SCOOP_GLOBAL")
+            .or_else(|| env("ProgramData").map(|p| PathBuf::from(p).join("scoop").into_os_string()))
         {
-            if let Some(local_app_data) = dirs::data_local_dir() {
-                paths.push(local_app_data.join("fnm").join("fnm.exe"));
-            }
+            paths.push((PathBuf::from(global).join("shims").join(binary), "Scoop"));
         }
+        if let Some(local_app_data) = env("LOCALAPPDATA") {
+            paths.push((
+                PathBuf::from(local_app_data).join("fnm").join(binary),
+                "fnm installer",
+            ));
+        }
+        return paths;
+    }
+
+    if let Some(prefix) = env("HOMEBREW_PREFIX") {
+        paths.push((PathBuf::from(prefix).join("bin").join(binary), "Homebrew"));
+    }
+    if let Some(home) = home {
+        paths.push((home.join(".fnm").join(binary), "fnm installer"));
+        paths.push((
+            home.join(".local").join("share").join("fnm").join(binary),
+            "fnm installer",
+        ));
+        paths.push((home.join(".local").join("bin").join(binary), "~/.local/bin"));
+    }
+    paths.push((PathBuf::from("/opt/homebrew/bin").join(binary), "Homebrew"));
+    paths.push((
+        PathBuf::from("/home/linuxbrew/.linuxbrew/bin").join(binary),
+        "Linuxbrew",
+    ));
+    if let Some(home) = home {
+        paths.push((
+            home.join(".linuxbrew").join("bin").join(binary),
+            "Linuxbrew",
+        ));
     }
+    let usr_local = if cfg!(target_os = "macos") {
+        "Homebrew"
+    } else {
+        "/usr/local/bin"
+    };
+    paths.push((PathBuf::from("/usr/local/bin").join(binary), usr_local));
+    paths.push((PathBuf::from("/usr/bin").join(binary), "/usr/bin"));
 
     paths
 }
@@ -139,30 +191,6 @@ async fn get_fnm_version(path: &PathBuf) -> Option<String> {
     Some(version)
 }
 
-pub(crate) async fn install_fnm() -> Result<(), crate::FnmError> {
-    #[cfg(unix)]
-    let status = Command::new("bash")
-        .args(["-c", "curl -fsSL https://fnm.vercel.app/install | bash"])
-        .hide_window()
-        .status()
-        .await?;
-
-    #[cfg(windows)]
-    let status = Command::new("powershell")
-        .args(["-Command", "irm https://fnm.vercel.app/install | iex"])
-        .hide_window()
-        .status()
-        .await?;
-
-    if status.success() {
-        Ok(())
-    } else {
-        Err(crate::FnmError::InstallFailed(
-            "fnm installation script failed".to_string(),
-        ))
-    }
-}
-
 pub async fn _check_fnm_update(current_version: &str) -> Option<String> {
     let output = Command::new("curl")
         .args([
@@ -190,3 +218,43 @@ pub async fn _check_fnm_update(current_version: &str) -> Option<String> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn probes_package_manager_prefixes() {
+        let home = Path::new("/home/user");
+        let candidates = fnm_candidates(Some(home), |key| match key {
+            "HOMEBREW_PREFIX" => Some("/opt/brew".into()),
+            _ => None,
+        });
+        let source = |path: &str| {
+            candidates
+                .iter()
+                .find(|(candidate, _)| candidate == Path::new(path))
+                .map(|(_, source)| *source)
+        };
+
+        assert_eq!(source("/home/user/.cargo/bin/fnm"), Some("Cargo"));
+        assert_eq!(source("/opt/brew/bin/fnm"), Some("Homebrew"));
+        assert_eq!(source("/opt/homebrew/bin/fnm"), Some("Homebrew"));
+        assert_eq!(
+            source("/home/linuxbrew/.linuxbrew/bin/fnm"),
+            Some("Linuxbrew")
+        );
+        assert_eq!(source("/home/user/.fnm/fnm"), Some("fnm installer"));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn probes_scoop_shims() {
+        let home = Path::new("C:\\Users\\user");
+        let candidates = fnm_candidates(Some(home), |_| None);
+        assert!(candidates.iter().any(|(path, source)| {
+            *source == "Scoop" && path == &home.join("scoop").join("shims").join("fnm.exe")
+        }));
+    }
+}
@@ -0,0 +1,468 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use log::info;
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+use tokio::sync::mpsc;
+use versi_core::{HideWindow, is_newer_version, run_streamed, sha256_file};
+
+use crate::error::FnmError;
+
+/// The fnm release onboarding installs and the SHA-256 of each of its
+/// assets, written by `scripts/pin-fnm.sh <version>` in a reviewed commit
+/// and checked again when a Versi release is prepared.
+static PINNED: LazyLock<PinnedRelease> = LazyLock::new(|| {
+    serde_json::from_str(include_str!("../fnm-release.json")).expect("fnm-release.json is valid")
+});
+
+#[derive(Debug, Deserialize)]
+struct PinnedRelease {
+    version: String,
+    /// Asset file name to lowercase hex SHA-256.
+    assets: BTreeMap<String, String>,
+}
+
+/// How fnm was installed, kept so update checks can say how to upgrade it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FnmInstallMethod {
+    Homebrew,
+    /// A pinned fnm release, checked against its SHA-256 before it is
+    /// unpacked. Settings from before pinning call it `script`.
+    #[serde(alias = "script")]
+    Release,
+}
+
+impl FnmInstallMethod {
+    /// The methods that work on this system, in order of preference.
+    pub fn available() -> Vec<Self> {
+        let mut methods = Vec::new();
+        if brew_path().is_some() {
+            methods.push(Self::Homebrew);
+        }
+        methods.push(Self::Release);
+        methods
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Homebrew => "Homebrew",
+            Self::Release => "a verified download",
+        }
+    }
+
+    /// How to upgrade an fnm installed this way.
+    pub fn update_hint(&self) -> &'static str {
+        match self {
+            Self::Homebrew => "Installed with Homebrew. Upgrade with `brew upgrade fnm`.",
+            Self::Release => {
+                "Installed from a verified fnm release. Replace it with a newer release to upgrade."
+            }
+        }
+    }
+
+    /// Installs fnm, sending each line of output to `progress` as it is
+    /// printed.
+    pub async fn install(&self, progress: mpsc::Sender<String>) -> Result<(), FnmError> {
+        info!("Installing fnm with {}", self.display_name());
+        match self {
+            Self::Homebrew => {
+                let brew = brew_path()
+                    .ok_or_else(|| FnmError::InstallFailed("Homebrew was not found".to_string()))?;
+                let mut command = Command::new(brew);
+                command.args(["install", "fnm"]).env("NONINTERACTIVE", "1");
+                run(&mut command, &progress).await
+            }
+            Self::Release => install_release(&PINNED, &progress).await,
+        }
+    }
+}
+
+/// Installs fnm with the first available method, for installs that don't
+/// show progress.
+pub(crate) async fn install_fnm() -> Result<(), FnmError> {
+    let method = FnmInstallMethod::available()
+        .into_iter()
+        .next()
+        .unwrap_or(FnmInstallMethod::Release);
+    let (tx, mut rx) = mpsc::channel(32);
+    let drain = tokio::spawn(async move { while rx.recv().await.is_some() {} });
+    let result = method.install(tx).await;
+    let _ = drain.await;
+    result
+}
+
+/// Upgrades the fnm at `path` the way it was installed: Homebrew or cargo
+/// when it lives under theirs, otherwise the pinned release when that is
+/// newer than `current`.
+pub(crate) async fn upgrade_fnm(path: &Path, current: Option<&str>) -> Result<String, FnmError> {
+    let (tx, mut rx) = mpsc::channel(32);
+    let drain = tokio::spawn(async move { while rx.recv().await.is_some() {} });
+    let location = path.to_string_lossy();
+    let result = if location.contains("/Cellar/") || location.contains("brew/") {
+        let brew = brew_path()
+            .ok_or_else(|| FnmError::InstallFailed("Homebrew was not found".to_string()))?;
+        let mut command = Command::new(brew);
+        command.args(["upgrade", "fnm"]).env("NONINTERACTIVE", "1");
+        run(&mut command, &tx)
+            .await
+            .map(|()| "Upgraded with Homebrew".to_string())
+    } else if location.contains(".cargo") {
+        run(Command::new("cargo").args(["install", "fnm"]), &tx)
+            .await
+            .map(|()| "Rebuilt with cargo install".to_string())
+    } else if install_dir().is_some_and(|dir| path.parent() == Some(dir.as_path())) {
+        let current = current
+            .map(|v| v.trim_start_matches('v'))
+            .unwrap_or("0.0.0");
+        if is_newer_version(&PINNED.version, current) {
+            install_release(&PINNED, &tx)
+                .await
+                .map(|()| format!("Installed the verified fnm {} release", PINNED.version))
+        } else {
+            Err(FnmError::InstallFailed(format!(
+                "fnm {} is the newest release this version of Versi can verify",
+                PINNED.version
+            )))
+        }
+    } else {
+        Err(FnmError::InstallFailed(format!(
+            "fnm at {} was installed outside Versi; upgrade it with the tool that installed it",
+            path.display()
+        )))
+    };
+    drop(tx);
+    let _ = drain.await;
+    result
+}
+
+/// Release assets a WSL distro can run, by `uname -m`.
+const WSL_ASSETS: [(&str, &str); 3] = [
+    ("x86_64", "fnm-linux.zip"),
+    ("aarch64", "fnm-arm64.zip"),
+    ("armv7l", "fnm-arm32.zip"),
+];
+
+/// The script that upgrades the fnm at `backend_path` inside a WSL distro,
+/// chosen by where it is installed.
+pub(crate) fn wsl_upgrade_script(backend_path: &str, version: &str) -> Result<String, FnmError> {
+    if backend_path.contains("linuxbrew") {
+        Ok("brew upgrade fnm".to_string())
+    } else if backend_path.contains("/.cargo/") {
+        Ok("cargo install fnm".to_string())
+    } else if backend_path.starts_with("/usr/") {
+        // Package managers need root; `sudo -n` fails instead of prompting.
+        Ok([
+            "if command -v apt-get >/dev/null; then sudo -n apt-get install -y --only-upgrade fnm",
+            "elif command -v dnf >/dev/null; then sudo -n dnf upgrade -y fnm",
+            "elif command -v pacman >/dev/null; then sudo -n pacman -S --noconfirm fnm",
+            "elif command -v zypper >/dev/null; then sudo -n zypper --non-interactive update fnm",
+            "else echo 'No supported package manager found' >&2; exit 1; fi",
+        ]
+        .join("; "))
+    } else {
+        let dir = backend_path
+            .rsplit_once('/')
+            .map_or(backend_path, |(dir, _)| dir);
+        wsl_release_script(&PINNED, dir, version)
+    }
+}
+
+/// Downloads the pinned release asset for the distro's architecture into
+/// `dir`, checking it against the pinned SHA-256 with `sha256sum -c` before
+/// anything is unpacked. Only the pinned version can be installed this way.
+fn wsl_release_script(
+    release: &PinnedRelease,
+    dir: &str,
+    version: &str,
+) -> Result<String, FnmError> {
+    let version = version.trim_start_matches('v');
+    if version != release.version {
+        return Err(FnmError::InstallFailed(format!(
+            "fnm {} is the only release this version of Versi can verify, not {}",
+            release.version, version
+        )));
+    }
+    let cases: Vec<String> = WSL_ASSETS
+        .iter()
+        .filter_map(|(arch, asset)| {
+            let sum = release.assets.get(*asset)?;
+            Some(format!("  {arch}) asset={asset}; sum={sum} ;;"))
+        })
+        .collect();
+    if cases.is_empty() {
+        return Err(FnmError::InstallFailed(format!(
+            "This build of Versi has no checksum pinned for the Linux assets of fnm {}",
+            release.version
+        )));
+    }
+    let dir = format!("'{}'", dir.replace('\'', "'\\''"));
+
+    let mut script = vec!["set -e".to_string(), "case \"$(uname -m)\" in".to_string()];
+    script.extend(cases);
+    script.extend([
+        "  *) echo \"No verified fnm release for $(uname -m)\" >&2; exit 1 ;;".to_string(),
+        "esac".to_string(),
+        "tmp=\"$(mktemp -d)\"; trap 'rm -rf \"$tmp\"' EXIT".to_string(),
+        format!(
+            "curl -fsSL -o \"$tmp/$asset\" \"https://github.com/Schniz/fnm/releases/download/v{}/$asset\"",
+            release.version
+        ),
+        "echo \"$sum  $tmp/$asset\" | sha256sum -c --quiet -".to_string(),
+        "unzip -j -o -q \"$tmp/$asset\" -d \"$tmp/out\"".to_string(),
+        format!("mkdir -p {dir} && install -m 755 \"$tmp/out/fnm\" {dir}/fnm"),
+    ]);
+    Ok(script.join("\n"))
+}
+
+/// Runs `script` with bash inside `distro`.
+pub(crate) async fn run_in_wsl(distro: &str, script: &str) -> Result<(), FnmError> {
+    let (tx, mut rx) = mpsc::channel(32);
+    let drain = tokio::spawn(async move { while rx.recv().await.is_some() {} });
+    let result = run(
+        Command::new("wsl.exe").args(["-d", distro, "--", "bash", "-lc", script]),
+        &tx,
+    )
+    .await;
+    drop(tx);
+    let _ = drain.await;
+    result
+}
+
+async fn run(command: &mut Command, progress: &mpsc::Sender<String>) -> Result<(), FnmError> {
+    let output = run_streamed(command.hide_window(), progress).await?;
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let detail = stderr
+        .lines()
+        .rev()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map_or_else(|| output.status.to_string(), str::to_string);
+    Err(FnmError::InstallFailed(detail))
+}
+
+/// Homebrew only installs fnm on macOS; elsewhere the script is used.
+fn brew_path() -> Option<PathBuf> {
+    if !cfg!(target_os = "macos") {
+        return None;
+    }
+    which::which("brew").ok().or_else(|| {
+        ["/opt/homebrew/bin/brew", "/usr/local/bin/brew"]
+            .into_iter()
+            .map(PathBuf::from)
+            .find(|path| path.is_file())
+    })
+}
+
+/// The release asset built for this platform.
+fn release_asset() -> Option<&'static str> {
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("windows", _) => Some("fnm-windows.zip"),
+        ("macos", _) => Some("fnm-macos.zip"),
+        ("linux", "x86_64") => Some("fnm-linux.zip"),
+        ("linux", "aarch64") => Some("fnm-arm64.zip"),
+        ("linux", "arm") => Some("fnm-arm32.zip"),
+        _ => None,
+    }
+}
+
+/// Where the official installer puts fnm, which detection already checks.
+fn install_dir() -> Option<PathBuf> {
+    if cfg!(windows) {
+        dirs::data_local_dir().map(|dir| dir.join("fnm"))
+    } else {
+        dirs::home_dir().map(|home| home.join(".local").join("share").join("fnm"))
+    }
+}
+
+/// Downloads the pinned release for this platform, checks it against the
+/// pinned SHA-256 and unpacks the binary. Nothing downloaded runs before it
+/// is verified.
+async fn install_release(
+    release: &PinnedRelease,
+    progress: &mpsc::Sender<String>,
+) -> Result<(), FnmError> {
+    let asset = release_asset().ok_or_else(|| {
+        FnmError::InstallFailed("fnm has no release for this platform".to_string())
+    })?;
+    let expected = release.assets.get(asset).ok_or_else(|| {
+        FnmError::InstallFailed(format!(
+            "This build of Versi has no checksum pinned for {asset} of fnm {}",
+            release.version
+        ))
+    })?;
+    let dir = install_dir().ok_or_else(|| {
+        FnmError::InstallFailed("Could not find a directory to install fnm in".to_string())
+    })?;
+
+    let _ = progress
+        .send(format!("Downloading fnm {}...", release.version))
+        .await;
+    let archive = std::env::temp_dir().join(format!("versi-{}-{asset}", std::process::id()));
+    let url = format!(
+        "https://github.com/Schniz/fnm/releases/download/v{}/{asset}",
+        release.version
+    );
+
+    let result = async {
+        download(&url, &archive).await?;
+        let actual = sha256_file(&archive)
+            .await
+            .map_err(FnmError::InstallFailed)?;
+        if actual != *expected {
+            return Err(FnmError::InstallFailed(format!(
+                "{asset} of fnm {} does not match the pinned checksum \
+                 (SHA-256 {actual}, expected {expected})",
+                release.version
+            )));
+        }
+        info!(
+            "Verified {asset} of fnm {}, SHA-256 {actual}",
+            release.version
+        );
+        let _ = progress
+            .send(format!("Verified the download (SHA-256 {actual})"))
+            .await;
+
+        let archive = archive.clone();
+        let dir = dir.clone();
+        tokio::task::spawn_blocking(move || unpack_binary(&archive, &dir))
+            .await
+            .map_err(|e| FnmError::InstallFailed(e.to_string()))?
+    }
+    .await;
+
+    let _ = std::fs::remove_file(&archive);
+    let binary = result?;
+    let _ = progress
+        .send(format!("Installed fnm to {}", binary.display()))
+        .await;
+    Ok(())
+}
+
+/// Extracts the fnm binary from a release archive into `dir`.
+fn unpack_binary(archive: &Path, dir: &Path) -> Result<PathBuf, FnmError> {
+    let name = if cfg!(windows) { "fnm.exe" } else { "fnm" };
+    let file = std::fs::File::open(archive)?;
+    let mut zip = zip::ZipArchive::new(file).map_err(|e| {
+        FnmError::InstallFailed(format!("Failed to read {}: {e}", archive.display()))
+    })?;
+    let index = (0..zip.len())
+        .find(|&i| {
+            zip.by_index(i).is_ok_and(|entry| {
+                Path::new(entry.name()).file_name() == Some(std::ffi::OsStr::new(name))
+            })
+        })
+        .ok_or_else(|| FnmError::InstallFailed(format!("The release has no {name}")))?;
+    let mut entry = zip
+        .by_index(index)
+        .map_err(|e| FnmError::InstallFailed(e.to_string()))?;
+
+    std::fs::create_dir_all(dir)?;
+    let binary = dir.join(name);
+    let mut out = std::fs::File::create(&binary)?;
+    std::io::copy(&mut entry, &mut out)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(binary)
+}
+
+async fn download(url: &str, path: &Path) -> Result<(), FnmError> {
+    let output = Command::new("curl")
+        .args(["-fsSL", "-o"])
+        .arg(path)
+        .arg(url)
+        .hide_window()
+        .output()
+        .await?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(FnmError::NetworkError(format!(
+            "Failed to download {url}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use versi_core::parse_sha256;
+
+    #[test]
+    fn picks_the_wsl_upgrade_by_install_location() {
+        assert_eq!(
+            wsl_upgrade_script("/home/linuxbrew/.linuxbrew/bin/fnm", "1.38.1").unwrap(),
+            "brew upgrade fnm"
+        );
+        assert!(
+            wsl_upgrade_script("/usr/bin/fnm", "1.38.1")
+                .unwrap()
+                .contains("sudo -n apt-get")
+        );
+    }
+
+    #[test]
+    fn verifies_the_wsl_release_before_unpacking() {
+        let release = PinnedRelease {
+            version: "1.38.1".to_string(),
+            assets: BTreeMap::from([("fnm-linux.zip".to_string(), "ab".repeat(32))]),
+        };
+        let script = wsl_release_script(&release, "/home/me/.local/share/fnm", "v1.38.1").unwrap();
+        assert!(!script.contains("fnm.vercel.app"));
+        assert!(script.contains(&format!(
+            "x86_64) asset=fnm-linux.zip; sum={}",
+            "ab".repeat(32)
+        )));
+        assert!(!script.contains("aarch64)"));
+        let verify = script.find("sha256sum -c").unwrap();
+        assert!(script.find("curl -fsSL").unwrap() < verify);
+        assert!(verify < script.find("unzip").unwrap());
+        assert!(script.ends_with("'/home/me/.local/share/fnm'/fnm"));
+
+        assert!(wsl_release_script(&release, "/opt/fnm", "1.39.0").is_err());
+        let unpinned = PinnedRelease {
+            version: "1.38.1".to_string(),
+            assets: BTreeMap::new(),
+        };
+        assert!(wsl_release_script(&unpinned, "/opt/fnm", "1.38.1").is_err());
+    }
+
+    #[test]
+    fn pinned_checksums_are_sha256() {
+        assert!(!PINNED.version.is_empty());
+        let script = include_str!("../../../scripts/pin-fnm.sh");
+        let assets = script
+            .lines()
+            .find_map(|line| line.strip_prefix("ASSETS=(")?.strip_suffix(')'))
+            .expect("pin-fnm.sh lists the release assets");
+        if let Some(asset) = release_asset() {
+            assert!(assets.split_whitespace().any(|a| a == asset));
+        }
+        for asset in assets.split_whitespace() {
+            assert!(
+                PINNED.assets.contains_key(asset),
+                "{asset} of fnm {} is not pinned; run scripts/pin-fnm.sh {0}",
+                PINNED.version
+            );
+        }
+        for (asset, digest) in &PINNED.assets {
+            assert_eq!(
+                parse_sha256(digest).as_ref(),
+                Some(digest),
+                "checksum of {asset}"
+            );
+        }
+    }
+}
@@ -1,11 +1,14 @@
 mod backend;
 mod detection;
 mod error;
+mod install;
+mod multishell;
 mod provider;
 mod update;
 mod version;
 
 pub use backend::{Environment, FnmBackend};
 pub use error::FnmError;
+pub use install::FnmInstallMethod;
 pub use provider::FnmProvider;
 pub use version::{parse_installed_versions, parse_remote_versions};
@@ -0,0 +1,72 @@
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+
+use versi_backend::NodeVersion;
+
+const MULTISHELL_DIR: &str = "fnm_multishells";
+
+/// The version the most recently used shell runs. Each shell `fnm env` sets
+/// up gets its own link in `fnm_multishells`, re-pointed by `fnm use` and
+/// use-on-cd, so the newest link is the last shell that changed versions.
+pub(crate) fn last_shell_version() -> Option<NodeVersion> {
+    let newest = multishell_dirs()
+        .into_iter()
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let modified = entry.path().symlink_metadata().ok()?.modified().ok()?;
+            Some((modified, entry.path()))
+        })
+        .max_by_key(|(modified, _): &(SystemTime, PathBuf)| *modified)?;
+
+    // Shells started on the default point at the alias, which links on to
+    // the installation itself.
+    let installation = std::fs::canonicalize(newest.1).ok()?;
+    version_from_installation(&installation)
+}
+
+/// Where fnm keeps the links, which has moved between releases: the runtime
+/// directory, then the cache directory, and the temporary directory before
+/// that.
+fn multishell_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = [
+        std::env::var_os("XDG_RUNTIME_DIR").map(PathBuf::from),
+        dirs::cache_dir(),
+        dirs::home_dir().map(|home| home.join(".cache")),
+        Some(std::env::temp_dir()),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|dir| dir.join(MULTISHELL_DIR))
+    .collect();
+    dirs.dedup();
+    dirs
+}
+
+/// Reads the version from a path inside an fnm installation, such as
+/// `~/.local/share/fnm/node-versions/v22.11.0/installation`.
+fn version_from_installation(path: &Path) -> Option<NodeVersion> {
+    let mut components = path.components();
+    components.find(|c| *c == Component::Normal("node-versions".as_ref()))?;
+    components.next()?.as_os_str().to_str()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_version_from_an_installation_path() {
+        assert_eq!(
+            version_from_installation(Path::new(
+                "/home/dev/.local/share/fnm/node-versions/v22.11.0/installation"
+            )),
+            Some(NodeVersion::new(22, 11, 0))
+        );
+        assert_eq!(
+            version_from_installation(Path::new("/home/dev/.local/share/fnm/aliases/default")),
+            None
+        );
+    }
+}
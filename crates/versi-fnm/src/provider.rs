@@ -1,19 +1,49 @@
 use async_trait::async_trait;
+use std::path::PathBuf;
 
+use log::info;
+use tokio::sync::mpsc;
 use versi_backend::{
-    BackendDetection, BackendError, BackendProvider, BackendUpdate, VersionManager,
+    BackendDetection, BackendError, BackendProvider, BackendUpdate, BackendUpdateChannel,
+    VersionManager, conflicting_env_vars,
 };
 
 use crate::backend::FnmBackend;
-use crate::detection::{detect_fnm, detect_fnm_dir, install_fnm};
+use crate::detection::{detect_fnm, detect_fnm_dir};
+use crate::install::{install_fnm, run_in_wsl, upgrade_fnm, wsl_upgrade_script};
 use crate::update::check_for_fnm_update;
 
 #[derive(Default)]
-pub struct FnmProvider;
+pub struct FnmProvider {
+    binary: Option<PathBuf>,
+    system_wide_dir: Option<PathBuf>,
+    sanitize_env: bool,
+}
 
 impl FnmProvider {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// A provider that uses `binary` as fnm before probing elsewhere.
+    pub fn with_binary(binary: Option<PathBuf>) -> Self {
+        Self {
+            binary,
+            ..Self::default()
+        }
+    }
+
+    /// Installs versions into `dir`, shared by every user, instead of the
+    /// user's own FNM_DIR.
+    pub fn with_system_wide_dir(mut self, dir: Option<PathBuf>) -> Self {
+        self.system_wide_dir = dir;
+        self
+    }
+
+    /// Leaves variables set up by nvm or Volta out of fnm commands.
+    pub fn with_sanitized_env(mut self, enabled: bool) -> Self {
+        self.sanitize_env = enabled;
+        self
     }
 }
 
@@ -36,13 +66,14 @@ impl BackendProvider for FnmProvider {
     }
 
     async fn detect(&self) -> BackendDetection {
-        let detection = detect_fnm().await;
+        let detection = detect_fnm(self.binary.as_deref()).await;
         BackendDetection {
             found: detection.found,
             path: detection.path,
             version: detection.version,
             in_path: detection.in_path,
             data_dir: detection.fnm_dir,
+            source: detection.source,
         }
     }
 
@@ -54,10 +85,39 @@ impl BackendProvider for FnmProvider {
 
     async fn check_for_update(
         &self,
-        client: &reqwest::Client,
+        github: &versi_core::GitHubClient,
         current_version: &str,
+        channel: &BackendUpdateChannel,
     ) -> Result<Option<BackendUpdate>, String> {
-        check_for_fnm_update(client, current_version).await
+        check_for_fnm_update(github, current_version, channel).await
+    }
+
+    async fn update_backend(
+        &self,
+        _version: &str,
+        _progress: mpsc::Sender<String>,
+    ) -> Result<String, BackendError> {
+        let detection = self.detect().await;
+        let path = detection.path.ok_or(BackendError::NotFound)?;
+        upgrade_fnm(&path, detection.version.as_deref())
+            .await
+            .map_err(|e| BackendError::InstallFailed(e.to_string()))
+    }
+
+    async fn update_backend_in_wsl(
+        &self,
+        distro: &str,
+        backend_path: &str,
+        version: &str,
+        _progress: mpsc::Sender<String>,
+    ) -> Result<String, BackendError> {
+        let script = wsl_upgrade_script(backend_path, version)
+            .map_err(|e| BackendError::InstallFailed(e.to_string()))?;
+        info!("Upgrading fnm in {}: {}", distro, script);
+        run_in_wsl(distro, &script)
+            .await
+            .map(|()| format!("Upgraded to fnm {}", version.trim_start_matches('v')))
+            .map_err(|e| BackendError::InstallFailed(e.to_string()))
     }
 
     fn create_manager(&self, detection: &BackendDetection) -> Box<dyn VersionManager> {
@@ -66,8 +126,18 @@ impl BackendProvider for FnmProvider {
             .clone()
             .unwrap_or_else(|| std::path::PathBuf::from("fnm"));
         let data_dir = detection.data_dir.clone().or_else(detect_fnm_dir);
-        let backend = FnmBackend::new(path, detection.version.clone(), data_dir.clone());
-        let backend = if let Some(dir) = data_dir {
+        let mut backend = FnmBackend::new(path, detection.version.clone(), data_dir.clone())
+            .with_source(detection.source.clone());
+        if self.sanitize_env {
+            let removed = conflicting_env_vars(self.name());
+            if !removed.is_empty() {
+                info!("Leaving {} out of fnm commands", removed.join(", "));
+                backend = backend.with_env_removals(removed);
+            }
+        }
+        let backend = if let Some(dir) = &self.system_wide_dir {
+            backend.with_system_wide_dir(dir.clone())
+        } else if let Some(dir) = data_dir {
             backend.with_fnm_dir(dir)
         } else {
             backend
@@ -83,6 +153,14 @@ impl BackendProvider for FnmProvider {
         Box::new(FnmBackend::with_wsl(distro, backend_path))
     }
 
+    fn create_manager_for_msys(
+        &self,
+        bash: String,
+        backend_path: String,
+    ) -> Box<dyn VersionManager> {
+        Box::new(FnmBackend::with_msys(bash, backend_path))
+    }
+
     fn wsl_search_paths(&self) -> Vec<&'static str> {
         vec![
             "$HOME/.local/share/fnm/fnm",
@@ -92,4 +170,13 @@ impl BackendProvider for FnmProvider {
             "$HOME/.fnm/fnm",
         ]
     }
+
+    fn msys_search_paths(&self) -> Vec<&'static str> {
+        vec![
+            "$LOCALAPPDATA/Microsoft/WinGet/Links/fnm.exe",
+            "$HOME/scoop/shims/fnm.exe",
+            "$HOME/.cargo/bin/fnm.exe",
+            "/usr/bin/fnm",
+        ]
+    }
 }
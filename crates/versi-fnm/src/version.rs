@@ -1,4 +1,4 @@
-use versi_backend::{InstalledVersion, RemoteVersion};
+use versi_backend::{InstallProgress, InstalledVersion, RemoteVersion};
 
 pub fn parse_installed_versions(output: &str) -> Vec<InstalledVersion> {
     output
@@ -63,6 +63,36 @@ pub fn parse_remote_versions(output: &str) -> Vec<RemoteVersion> {
         .collect()
 }
 
+/// Reads the byte counts from a redraw of fnm's download progress bar, which
+/// shows them as `12.50 MiB/45.20 MiB`.
+pub fn parse_install_progress(line: &str) -> Option<InstallProgress> {
+    let (before, after) = line.split_once('/')?;
+    let mut before = before.split_whitespace().rev();
+    let unit = before.next()?;
+    let downloaded = parse_bytes(before.next()?, unit)?;
+    let mut after = after.split_whitespace();
+    let total = match (after.next(), after.next()) {
+        (Some(amount), Some(unit)) => parse_bytes(amount, unit).filter(|&total| total > 0),
+        _ => None,
+    };
+    Some(InstallProgress { downloaded, total })
+}
+
+fn parse_bytes(amount: &str, unit: &str) -> Option<u64> {
+    let amount: f64 = amount.parse().ok()?;
+    let scale = match unit.trim_end_matches(|c: char| !c.is_ascii_alphabetic()) {
+        "B" => 1.0,
+        "KiB" => 1024.0,
+        "MiB" => 1024.0 * 1024.0,
+        "GiB" => 1024.0 * 1024.0 * 1024.0,
+        "kB" | "KB" => 1e3,
+        "MB" => 1e6,
+        "GB" => 1e9,
+        _ => return None,
+    };
+    Some((amount * scale) as u64)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -141,4 +171,16 @@ mod tests {
         assert!(versions[0].lts_codename.is_none());
         assert!(versions[1].lts_codename.is_none());
     }
+
+    #[test]
+    fn test_parse_install_progress() {
+        let progress =
+            parse_install_progress("[00:00:02] ██████▇      12.50 MiB/45.00 MiB (6.25 MiB/s, 5s)")
+                .unwrap();
+        assert_eq!(progress.downloaded, 13_107_200);
+        assert_eq!(progress.total, Some(47_185_920));
+
+        assert!(parse_install_progress("Installing Node v20.11.0 (x64)").is_none());
+        assert!(parse_install_progress("Downloading https://nodejs.org/dist").is_none());
+    }
 }
@@ -1,10 +1,10 @@
 use async_trait::async_trait;
 use log::{debug, info};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use versi_backend::{
-    BackendError, BackendInfo, InstalledVersion, ManagerCapabilities, NodeVersion, RemoteVersion,
-    ShellInitOptions, VersionManager,
+    BackendError, BackendInfo, CD_HOOK_END, CD_HOOK_START, InstalledVersion, ManagerCapabilities,
+    NodeVersion, RemoteVersion, ShellInitOptions, VersionManager,
 };
 
 use crate::client::{NvmClient, NvmEnvironment};
@@ -22,7 +22,7 @@ impl NvmBackend {
             NvmEnvironment::Windows { nvm_exe } => {
                 (nvm_exe.clone(), nvm_exe.parent().map(|p| p.to_path_buf()))
             }
-            NvmEnvironment::Wsl { nvm_dir, .. } => (
+            NvmEnvironment::Wsl { nvm_dir, .. } | NvmEnvironment::Msys { nvm_dir, .. } => (
                 PathBuf::from(nvm_dir).join("nvm.sh"),
                 Some(PathBuf::from(nvm_dir)),
             ),
@@ -35,10 +35,16 @@ impl NvmBackend {
                 version,
                 data_dir,
                 in_path: true,
+                source: None,
             },
             client,
         }
     }
+
+    pub fn with_source(mut self, source: Option<String>) -> Self {
+        self.info.source = source;
+        self
+    }
 }
 
 impl std::fmt::Debug for NvmBackend {
@@ -61,9 +67,10 @@ impl VersionManager for NvmBackend {
             supports_lts_filter: true,
             supports_use_version: true,
             supports_shell_integration: supports_shell,
-            supports_auto_switch: false,
+            supports_auto_switch: supports_shell,
             supports_corepack: false,
             supports_resolve_engines: false,
+            supports_system_wide: false,
         }
     }
 
@@ -102,6 +109,12 @@ impl VersionManager for NvmBackend {
     }
 
     async fn current_version(&self) -> Result<Option<NodeVersion>, BackendError> {
+        // nvm.sh switches versions through each shell's own PATH, so a new
+        // shell only ever reports the default. nvm-windows switches one
+        // global link instead.
+        if !self.client.is_windows() {
+            return Ok(None);
+        }
         debug!("nvm: getting current version");
         self.client
             .current()
@@ -161,19 +174,108 @@ impl VersionManager for NvmBackend {
             })
     }
 
-    fn shell_init_command(&self, _shell: &str, _options: &ShellInitOptions) -> Option<String> {
+    async fn exec(&self, version: &str, command: &[&str]) -> Result<String, BackendError> {
+        if self.client.is_windows() {
+            return Err(BackendError::Unsupported("exec".to_string()));
+        }
+        self.client
+            .exec(version, command)
+            .await
+            .map_err(|e| BackendError::CommandFailed {
+                stderr: e.to_string(),
+            })
+    }
+
+    async fn exec_in(
+        &self,
+        version: &str,
+        command: &[&str],
+        dir: &Path,
+    ) -> Result<String, BackendError> {
+        if self.client.is_windows() {
+            return Err(BackendError::Unsupported("exec_in".to_string()));
+        }
+        self.client
+            .exec_in(version, command, dir)
+            .await
+            .map_err(|e| BackendError::CommandFailed {
+                stderr: e.to_string(),
+            })
+    }
+
+    fn node_binary(&self, version: &str) -> Option<PathBuf> {
         match &self.client.environment {
-            NvmEnvironment::Unix { nvm_dir } => Some(format!(
-                "export NVM_DIR=\"{}\" && [ -s \"$NVM_DIR/nvm.sh\" ] && \\. \"$NVM_DIR/nvm.sh\"",
-                nvm_dir.display()
-            )),
-            NvmEnvironment::Wsl { nvm_dir, .. } => Some(format!(
-                "export NVM_DIR=\"{}\" && [ -s \"$NVM_DIR/nvm.sh\" ] && \\. \"$NVM_DIR/nvm.sh\"",
+            NvmEnvironment::Unix { nvm_dir } => Some(
                 nvm_dir
-            )),
-            NvmEnvironment::Windows { .. } => None,
+                    .join("versions")
+                    .join("node")
+                    .join(version)
+                    .join("bin")
+                    .join("node"),
+            ),
+            NvmEnvironment::Windows { nvm_exe } => {
+                Some(nvm_exe.parent()?.join(version).join("node.exe"))
+            }
+            NvmEnvironment::Wsl { .. } | NvmEnvironment::Msys { .. } => None,
+        }
+    }
+
+    fn shell_init_command(&self, shell: &str, options: &ShellInitOptions) -> Option<String> {
+        let nvm_dir = match &self.client.environment {
+            NvmEnvironment::Unix { nvm_dir } => nvm_dir.display().to_string(),
+            NvmEnvironment::Wsl { nvm_dir, .. } | NvmEnvironment::Msys { nvm_dir, .. } => {
+                nvm_dir.clone()
+            }
+            NvmEnvironment::Windows { .. } => return None,
+        };
+        let mut command = format!(
+            "export NVM_DIR=\"{}\" && [ -s \"$NVM_DIR/nvm.sh\" ] && \\. \"$NVM_DIR/nvm.sh\"",
+            nvm_dir
+        );
+        if options.use_on_cd
+            && let Some(hook) = cd_hook(shell)
+        {
+            command.push('\n');
+            command.push_str(&hook);
         }
+        Some(command)
     }
+
+    fn set_env_overrides(&mut self, vars: Vec<(String, String)>) {
+        self.client.env_overrides = vars;
+    }
+}
+
+/// Uses the version from the nearest `.nvmrc`, installing it first when
+/// needed, and goes back to the default outside projects. Adapted from the
+/// hook in nvm's README.
+const LOAD_NVMRC: &str = r#"load_nvmrc() {
+  local nvmrc_path nvmrc_version
+  nvmrc_path="$(nvm_find_nvmrc)"
+  if [ -n "$nvmrc_path" ]; then
+    nvmrc_version="$(nvm version "$(cat "$nvmrc_path")")"
+    if [ "$nvmrc_version" = "N/A" ]; then
+      nvm install
+    elif [ "$nvmrc_version" != "$(nvm version)" ]; then
+      nvm use
+    fi
+  elif [ "$(nvm version)" != "$(nvm version default)" ]; then
+    nvm use default
+  fi
+}"#;
+
+/// nvm has no use-on-cd of its own, so shells get a function that runs on
+/// every directory change. Only bash and zsh can load nvm at all.
+fn cd_hook(shell: &str) -> Option<String> {
+    let hook = match shell {
+        "bash" => r#"cd() { builtin cd "$@" && load_nvmrc; }"#,
+        "zsh" => "autoload -U add-zsh-hook\nadd-zsh-hook chpwd load_nvmrc",
+        _ => return None,
+    };
+    Some(format!(
+        "{}\n{}\n{}\nload_nvmrc\n{}",
+        CD_HOOK_START, LOAD_NVMRC, hook, CD_HOOK_END
+    ))
 }
 
 #[cfg(test)]
@@ -196,11 +298,36 @@ mod tests {
         assert!(caps.supports_shell_integration);
         assert!(caps.supports_lts_filter);
         assert!(caps.supports_use_version);
-        assert!(!caps.supports_auto_switch);
+        assert!(caps.supports_auto_switch);
         assert!(!caps.supports_corepack);
         assert!(!caps.supports_resolve_engines);
     }
 
+    #[test]
+    fn use_on_cd_adds_a_hook_for_bash_and_zsh() {
+        let backend = unix_backend();
+        let options = ShellInitOptions {
+            use_on_cd: true,
+            ..ShellInitOptions::default()
+        };
+
+        let bash = backend.shell_init_command("bash", &options).unwrap();
+        assert!(bash.starts_with("export NVM_DIR=\"/home/user/.nvm\""));
+        assert!(bash.contains(CD_HOOK_START) && bash.ends_with(CD_HOOK_END));
+        assert!(bash.contains("builtin cd"));
+
+        let zsh = backend.shell_init_command("zsh", &options).unwrap();
+        assert!(zsh.contains("add-zsh-hook chpwd load_nvmrc"));
+
+        let fish = backend.shell_init_command("fish", &options).unwrap();
+        assert!(!fish.contains(CD_HOOK_START));
+
+        let off = backend
+            .shell_init_command("bash", &ShellInitOptions::default())
+            .unwrap();
+        assert_eq!(off.lines().count(), 1);
+    }
+
     #[test]
     fn windows_capabilities_no_shell_integration() {
         let caps = windows_backend().capabilities();
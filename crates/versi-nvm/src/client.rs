@@ -1,7 +1,7 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tokio::process::Command;
 
-use versi_backend::{InstalledVersion, NodeVersion, RemoteVersion};
+use versi_backend::{InstalledVersion, NodeVersion, RemoteVersion, is_valid_env_key};
 use versi_platform::HideWindow;
 
 use crate::error::NvmError;
@@ -12,32 +12,62 @@ use crate::version::{
 
 #[derive(Debug, Clone)]
 pub enum NvmEnvironment {
-    Unix { nvm_dir: PathBuf },
-    Windows { nvm_exe: PathBuf },
-    Wsl { distro: String, nvm_dir: String },
+    Unix {
+        nvm_dir: PathBuf,
+    },
+    Windows {
+        nvm_exe: PathBuf,
+    },
+    Wsl {
+        distro: String,
+        nvm_dir: String,
+    },
+    /// nvm-sh run through the bash of Git Bash or MSYS2.
+    Msys {
+        bash: String,
+        nvm_dir: String,
+    },
 }
 
 #[derive(Clone)]
 pub struct NvmClient {
     pub environment: NvmEnvironment,
+    pub env_overrides: Vec<(String, String)>,
+    /// Inherited variables left out of native commands, see
+    /// [`versi_backend::conflicting_env_vars`].
+    pub env_removals: Vec<String>,
 }
 
 impl NvmClient {
     pub fn unix(nvm_dir: PathBuf) -> Self {
         Self {
             environment: NvmEnvironment::Unix { nvm_dir },
+            env_overrides: Vec::new(),
+            env_removals: Vec::new(),
         }
     }
 
     pub fn windows(nvm_exe: PathBuf) -> Self {
         Self {
             environment: NvmEnvironment::Windows { nvm_exe },
+            env_overrides: Vec::new(),
+            env_removals: Vec::new(),
         }
     }
 
     pub fn wsl(distro: String, nvm_dir: String) -> Self {
         Self {
             environment: NvmEnvironment::Wsl { distro, nvm_dir },
+            env_overrides: Vec::new(),
+            env_removals: Vec::new(),
+        }
+    }
+
+    pub fn msys(bash: String, nvm_dir: String) -> Self {
+        Self {
+            environment: NvmEnvironment::Msys { bash, nvm_dir },
+            env_overrides: Vec::new(),
+            env_removals: Vec::new(),
         }
     }
 
@@ -45,18 +75,53 @@ impl NvmClient {
         matches!(self.environment, NvmEnvironment::Windows { .. })
     }
 
+    /// The overrides whose names are safe to pass on. Keys end up in shell
+    /// scripts, so anything else is left out.
+    fn valid_overrides(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.env_overrides.iter().filter_map(|(key, value)| {
+            if is_valid_env_key(key) {
+                Some((key, value))
+            } else {
+                log::warn!("Skipping environment variable with invalid name {:?}", key);
+                None
+            }
+        })
+    }
+
+    fn export_overrides(&self) -> String {
+        self.valid_overrides()
+            .map(|(key, value)| format!("export {}={}; ", key, shell_quote(value)))
+            .collect()
+    }
+
+    /// A bash script that loads nvm from `nvm_dir` with the overrides
+    /// exported, then runs `nvm_args`.
+    fn sourced_script(&self, nvm_dir: &str, nvm_args: &str) -> String {
+        format!(
+            "{}export NVM_DIR=\"{}\"; [ -s \"$NVM_DIR/nvm.sh\" ] && \\. \"$NVM_DIR/nvm.sh\"; {}",
+            self.export_overrides(),
+            nvm_dir,
+            nvm_args
+        )
+    }
+
     fn build_nvm_command(&self, nvm_args: &str) -> Command {
         match &self.environment {
             NvmEnvironment::Unix { nvm_dir } => {
                 let script = format!(
-                    "export NVM_DIR=\"{}\"; [ -s \"$NVM_DIR/nvm.sh\" ] && \\. \"$NVM_DIR/nvm.sh\"; {}",
-                    nvm_dir.display(),
+                    "export NVM_DIR={}; [ -s \"$NVM_DIR/nvm.sh\" ] && \\. \"$NVM_DIR/nvm.sh\"; {}",
+                    shell_quote(&nvm_dir.to_string_lossy()),
                     nvm_args
                 );
                 let mut cmd = Command::new("bash");
                 cmd.args(["-c", &script]);
+                cmd.env("NVM_DIR", nvm_dir);
                 cmd.env("TERM", "dumb");
                 cmd.env("NO_COLOR", "1");
+                for key in &self.env_removals {
+                    cmd.env_remove(key);
+                }
+                cmd.envs(self.valid_overrides());
                 cmd.hide_window();
                 cmd
             }
@@ -69,24 +134,36 @@ impl NvmClient {
                 };
                 let mut cmd = Command::new(nvm_exe);
                 cmd.args(args);
+                for key in &self.env_removals {
+                    cmd.env_remove(key);
+                }
+                cmd.envs(self.valid_overrides());
                 cmd.hide_window();
                 cmd
             }
             NvmEnvironment::Wsl { distro, nvm_dir } => {
-                let script = format!(
-                    "export NVM_DIR=\"{}\"; [ -s \"$NVM_DIR/nvm.sh\" ] && \\. \"$NVM_DIR/nvm.sh\"; {}",
-                    nvm_dir, nvm_args
-                );
+                let script = self.sourced_script(nvm_dir, nvm_args);
                 let mut cmd = Command::new("wsl.exe");
                 cmd.args(["-d", distro, "--", "bash", "-c", &script]);
                 cmd.hide_window();
                 cmd
             }
+            NvmEnvironment::Msys { bash, nvm_dir } => {
+                let script = self.sourced_script(nvm_dir, nvm_args);
+                let mut cmd = Command::new(bash);
+                cmd.args(["-c", &script]);
+                cmd.hide_window();
+                cmd
+            }
         }
     }
 
     async fn execute(&self, nvm_args: &str) -> Result<String, NvmError> {
-        let output = self.build_nvm_command(nvm_args).output().await?;
+        self.run(self.build_nvm_command(nvm_args)).await
+    }
+
+    async fn run(&self, mut cmd: Command) -> Result<String, NvmError> {
+        let output = versi_platform::run_tracked(&mut cmd).await?;
 
         if output.status.success() {
             let stdout = String::from_utf8_lossy(&output.stdout).to_string();
@@ -205,6 +282,21 @@ impl NvmClient {
         Ok(())
     }
 
+    pub async fn exec(&self, version: &str, command: &[&str]) -> Result<String, NvmError> {
+        self.execute(&exec_args(version, command)).await
+    }
+
+    pub async fn exec_in(
+        &self,
+        version: &str,
+        command: &[&str],
+        dir: &Path,
+    ) -> Result<String, NvmError> {
+        let mut cmd = self.build_nvm_command(&exec_args(version, command));
+        cmd.current_dir(dir);
+        self.run(cmd).await
+    }
+
     pub async fn version(&self) -> Result<String, NvmError> {
         if self.is_windows() {
             let output = self.execute("nvm version").await?;
@@ -216,6 +308,19 @@ impl NvmClient {
     }
 }
 
+fn exec_args(version: &str, command: &[&str]) -> String {
+    let quoted: Vec<String> = command.iter().map(|arg| shell_quote(arg)).collect();
+    format!(
+        "nvm exec --silent {} {}",
+        shell_quote(version),
+        quoted.join(" ")
+    )
+}
+
+pub(crate) fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -247,4 +352,50 @@ mod tests {
             if distro == "Debian" && nvm_dir == "/home/user/.nvm"
         ));
     }
+
+    #[test]
+    fn msys_commands_run_through_its_bash() {
+        let bash = r"C:\Program Files\Git\bin\bash.exe";
+        let client = NvmClient::msys(bash.to_string(), "/c/Users/me/.nvm".to_string());
+        let cmd = client.build_nvm_command("nvm list");
+        let cmd = cmd.as_std();
+        assert_eq!(cmd.get_program(), bash);
+        let args: Vec<_> = cmd.get_args().collect();
+        assert_eq!(args[0], "-c");
+        assert!(
+            args[1]
+                .to_string_lossy()
+                .starts_with("export NVM_DIR=\"/c/Users/me/.nvm\";")
+        );
+    }
+
+    #[test]
+    fn export_overrides_quotes_values() {
+        let mut client = NvmClient::wsl("Ubuntu".to_string(), "/home/user/.nvm".to_string());
+        client.env_overrides = vec![
+            ("HTTPS_PROXY".to_string(), "http://proxy:8080".to_string()),
+            ("NOTE".to_string(), "it's".to_string()),
+        ];
+        assert_eq!(
+            client.export_overrides(),
+            "export HTTPS_PROXY='http://proxy:8080'; export NOTE='it'\\''s'; "
+        );
+    }
+
+    #[test]
+    fn export_overrides_skips_invalid_names() {
+        let mut client = NvmClient::wsl("Ubuntu".to_string(), "/home/user/.nvm".to_string());
+        client.env_overrides = vec![
+            ("X; touch /tmp/pwned; Y".to_string(), "1".to_string()),
+            ("1ST".to_string(), "1".to_string()),
+            (
+                "NVM_NODEJS_ORG_MIRROR".to_string(),
+                "https://mirror".to_string(),
+            ),
+        ];
+        assert_eq!(
+            client.export_overrides(),
+            "export NVM_NODEJS_ORG_MIRROR='https://mirror'; "
+        );
+    }
 }
@@ -1,4 +1,5 @@
-use std::path::PathBuf;
+use log::{debug, warn};
+use std::path::{Path, PathBuf};
 use tokio::process::Command;
 
 use versi_platform::HideWindow;
@@ -21,8 +22,36 @@ pub enum NvmVariant {
     NotFound,
 }
 
-pub async fn detect_nvm() -> NvmDetection {
-    if let Some(detection) = detect_unix_nvm().await {
+/// How a Unix nvm was put in place, which decides how it is updated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NvmInstallMethod {
+    /// A git checkout, made by hand or by the install script when git is
+    /// available.
+    Git,
+    /// Plain files downloaded by the install script with `METHOD=script`.
+    Script,
+}
+
+impl NvmInstallMethod {
+    pub fn of(nvm_dir: &Path) -> Self {
+        if nvm_dir.join(".git").exists() {
+            Self::Git
+        } else {
+            Self::Script
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Git => "git clone",
+            Self::Script => "install script",
+        }
+    }
+}
+
+/// Detects nvm, looking in `nvm_dir` first when the user configured one.
+pub async fn detect_nvm(nvm_dir: Option<&Path>) -> NvmDetection {
+    if let Some(detection) = detect_unix_nvm(nvm_dir).await {
         return detection;
     }
 
@@ -39,8 +68,8 @@ pub async fn detect_nvm() -> NvmDetection {
     }
 }
 
-async fn detect_unix_nvm() -> Option<NvmDetection> {
-    let nvm_dir = find_unix_nvm_dir()?;
+async fn detect_unix_nvm(nvm_dir: Option<&Path>) -> Option<NvmDetection> {
+    let nvm_dir = find_unix_nvm_dir(nvm_dir)?;
 
     let nvm_sh = nvm_dir.join("nvm.sh");
     if !nvm_sh.exists() {
@@ -59,7 +88,14 @@ async fn detect_unix_nvm() -> Option<NvmDetection> {
     })
 }
 
-fn find_unix_nvm_dir() -> Option<PathBuf> {
+fn find_unix_nvm_dir(configured: Option<&Path>) -> Option<PathBuf> {
+    if let Some(dir) = configured {
+        if dir.join("nvm.sh").exists() {
+            return Some(dir.to_path_buf());
+        }
+        warn!("Configured NVM_DIR {:?} has no nvm.sh", dir);
+    }
+
     if let Ok(dir) = std::env::var("NVM_DIR") {
         let path = PathBuf::from(&dir);
         if path.join("nvm.sh").exists() {
@@ -68,6 +104,11 @@ fn find_unix_nvm_dir() -> Option<PathBuf> {
     }
 
     if let Some(home) = dirs::home_dir() {
+        if let Some(path) = nvm_dir_from_shell_configs(&home) {
+            debug!("Found NVM_DIR {:?} in shell config", path);
+            return Some(path);
+        }
+
         let default = home.join(".nvm");
         if default.join("nvm.sh").exists() {
             return Some(default);
@@ -84,6 +125,50 @@ fn find_unix_nvm_dir() -> Option<PathBuf> {
     None
 }
 
+/// GUI apps don't inherit variables exported from shell startup files, so
+/// an `NVM_DIR` set there has to be read from the files themselves.
+fn nvm_dir_from_shell_configs(home: &Path) -> Option<PathBuf> {
+    const CONFIGS: [&str; 5] = [
+        ".zshrc",
+        ".bashrc",
+        ".bash_profile",
+        ".profile",
+        ".config/fish/config.fish",
+    ];
+
+    CONFIGS
+        .iter()
+        .filter_map(|name| std::fs::read_to_string(home.join(name)).ok())
+        .filter_map(|content| parse_nvm_dir(&content, home))
+        .find(|dir| dir.join("nvm.sh").exists())
+}
+
+/// The last plain `NVM_DIR` assignment in a shell config, with `~` and
+/// `$HOME` expanded. Assignments computed by commands are skipped.
+fn parse_nvm_dir(content: &str, home: &Path) -> Option<PathBuf> {
+    content.lines().rev().find_map(|line| {
+        let line = line.trim();
+        let value = line
+            .strip_prefix("export NVM_DIR=")
+            .or_else(|| line.strip_prefix("NVM_DIR="))
+            .or_else(|| line.strip_prefix("set -gx NVM_DIR "))
+            .or_else(|| line.strip_prefix("set -x NVM_DIR "))?;
+        let value = value.split(" #").next()?.trim().trim_end_matches(';');
+        let value = value.trim_matches(|c| c == '"' || c == '\'');
+        if value.is_empty() || value.contains("$(") || value.contains('`') {
+            return None;
+        }
+
+        let home = home.to_string_lossy();
+        let expanded = if let Some(rest) = value.strip_prefix('~') {
+            format!("{}{}", home, rest)
+        } else {
+            value.replace("${HOME}", &home).replace("$HOME", &home)
+        };
+        (!expanded.contains('$')).then(|| PathBuf::from(expanded))
+    })
+}
+
 async fn detect_windows_nvm() -> Option<NvmDetection> {
     if let Ok(path) = which::which("nvm") {
         let version = get_windows_nvm_version(&path).await;
@@ -113,9 +198,19 @@ async fn detect_windows_nvm() -> Option<NvmDetection> {
     None
 }
 
+/// Where nvm-windows installs to. `PATH` and `NVM_HOME` are only read at
+/// startup, so an install made while Versi is running is found here.
 fn get_windows_nvm_paths() -> Vec<PathBuf> {
     let mut paths = Vec::new();
 
+    if let Ok(home) = std::env::var("NVM_HOME") {
+        paths.push(PathBuf::from(&home).join("nvm.exe"));
+    }
+
+    if let Ok(local) = std::env::var("LOCALAPPDATA") {
+        paths.push(PathBuf::from(&local).join("nvm").join("nvm.exe"));
+    }
+
     if let Ok(appdata) = std::env::var("APPDATA") {
         paths.push(PathBuf::from(&appdata).join("nvm").join("nvm.exe"));
     }
@@ -181,16 +276,23 @@ pub async fn install_nvm() -> Result<(), crate::NvmError> {
 
     #[cfg(windows)]
     {
-        Err(crate::NvmError::InstallFailed(
-            "Automatic nvm-windows installation is not supported. Please install manually from https://github.com/coreybutler/nvm-windows/releases".to_string(),
-        ))
+        let Some(package_manager) = crate::WindowsPackageManager::available().into_iter().next()
+        else {
+            return Err(crate::NvmError::InstallFailed(
+                "Neither winget nor Chocolatey was found. Please install nvm-windows manually from https://github.com/coreybutler/nvm-windows/releases".to_string(),
+            ));
+        };
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(32);
+        let drain = tokio::spawn(async move { while rx.recv().await.is_some() {} });
+        let result = package_manager.install_nvm(tx).await;
+        let _ = drain.await;
+        result
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::path::Path;
-
     use super::*;
 
     #[test]
@@ -246,4 +348,29 @@ mod tests {
         };
         assert!(detect_nvm_environment(&detection).is_none());
     }
+
+    #[test]
+    fn parses_nvm_dir_from_shell_configs() {
+        let home = Path::new("/home/user");
+        assert_eq!(
+            parse_nvm_dir("export NVM_DIR=\"/mnt/data/nvm\"\n", home),
+            Some(PathBuf::from("/mnt/data/nvm"))
+        );
+        assert_eq!(
+            parse_nvm_dir("export NVM_DIR=\"$HOME/tools/nvm\"", home),
+            Some(PathBuf::from("/home/user/tools/nvm"))
+        );
+        assert_eq!(
+            parse_nvm_dir("set -gx NVM_DIR ~/nvm", home),
+            Some(PathBuf::from("/home/user/nvm"))
+        );
+        assert_eq!(
+            parse_nvm_dir(
+                "export NVM_DIR=\"$([ -z \"${XDG_CONFIG_HOME-}\" ] && printf %s \"${HOME}/.nvm\")\"",
+                home
+            ),
+            None
+        );
+        assert_eq!(parse_nvm_dir("# nothing here", home), None);
+    }
 }
@@ -2,14 +2,16 @@ mod backend;
 mod client;
 mod detection;
 mod error;
+mod package_manager;
 mod provider;
 mod update;
 mod version;
 
 pub use backend::NvmBackend;
 pub use client::{NvmClient, NvmEnvironment};
-pub use detection::{NvmDetection, NvmVariant};
+pub use detection::{NvmDetection, NvmInstallMethod, NvmVariant};
 pub use error::NvmError;
+pub use package_manager::WindowsPackageManager;
 pub use provider::NvmProvider;
 
 pub use versi_backend::{
@@ -0,0 +1,119 @@
+use log::info;
+use tokio::process::Command;
+use tokio::sync::mpsc;
+use versi_platform::{HideWindow, run_streamed};
+
+use crate::error::NvmError;
+
+const WINGET_PACKAGE: &str = "CoreyButler.NVMforWindows";
+const CHOCOLATEY_PACKAGE: &str = "nvm";
+
+/// A Windows package manager that can install nvm-windows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowsPackageManager {
+    Winget,
+    Chocolatey,
+}
+
+impl WindowsPackageManager {
+    const ALL: [Self; 2] = [Self::Winget, Self::Chocolatey];
+
+    /// The package managers found on `PATH`, in order of preference. Always
+    /// empty outside Windows.
+    pub fn available() -> Vec<Self> {
+        if !cfg!(windows) {
+            return Vec::new();
+        }
+        Self::ALL
+            .into_iter()
+            .filter(|pm| which::which(pm.program()).is_ok())
+            .collect()
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Winget => "winget",
+            Self::Chocolatey => "Chocolatey",
+        }
+    }
+
+    fn program(&self) -> &'static str {
+        match self {
+            Self::Winget => "winget",
+            Self::Chocolatey => "choco",
+        }
+    }
+
+    /// Chocolatey only installs from an elevated prompt, so it is started
+    /// through PowerShell with a UAC request and its output is not visible.
+    /// `verb` is `install` or `upgrade`, which both package managers share.
+    fn command(&self, verb: &str) -> Command {
+        match self {
+            Self::Winget => {
+                let mut command = Command::new("winget");
+                command.args([
+                    verb,
+                    "--id",
+                    WINGET_PACKAGE,
+                    "--exact",
+                    "--silent",
+                    "--accept-package-agreements",
+                    "--accept-source-agreements",
+                ]);
+                command
+            }
+            Self::Chocolatey => {
+                let mut command = Command::new("powershell");
+                command.args([
+                    "-NoProfile",
+                    "-Command",
+                    &format!(
+                        "$p = Start-Process choco -ArgumentList '{verb} {CHOCOLATEY_PACKAGE} -y' \
+                         -Verb RunAs -Wait -PassThru; exit $p.ExitCode"
+                    ),
+                ]);
+                command
+            }
+        }
+    }
+
+    /// Installs nvm-windows, sending each line of output to `progress` as it
+    /// is printed.
+    pub async fn install_nvm(&self, progress: mpsc::Sender<String>) -> Result<(), NvmError> {
+        info!("Installing nvm-windows with {}", self.display_name());
+        self.run("install", progress).await
+    }
+
+    /// Upgrades an nvm-windows this package manager installed.
+    pub async fn upgrade_nvm(&self, progress: mpsc::Sender<String>) -> Result<(), NvmError> {
+        info!("Upgrading nvm-windows with {}", self.display_name());
+        self.run("upgrade", progress).await
+    }
+
+    async fn run(&self, verb: &str, progress: mpsc::Sender<String>) -> Result<(), NvmError> {
+        if *self == Self::Chocolatey {
+            let _ = progress
+                .send("Waiting for administrator approval...".to_string())
+                .await;
+        }
+
+        let output = run_streamed(self.command(verb).hide_window(), &progress).await?;
+        if output.status.success() {
+            return Ok(());
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let detail = stderr
+            .lines()
+            .rev()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .map_or_else(|| output.status.to_string(), str::to_string);
+        Err(NvmError::InstallFailed(format!(
+            "{} could not {} nvm-windows: {}",
+            self.display_name(),
+            verb,
+            detail
+        )))
+    }
+}
@@ -1,23 +1,32 @@
 use async_trait::async_trait;
 use std::path::PathBuf;
 
+use log::info;
+use tokio::sync::mpsc;
 use versi_backend::{
-    BackendDetection, BackendError, BackendProvider, BackendUpdate, VersionManager,
+    BackendDetection, BackendError, BackendProvider, BackendUpdate, BackendUpdateChannel,
+    VersionManager, conflicting_env_vars,
 };
 
 use crate::backend::NvmBackend;
 use crate::client::{NvmClient, NvmEnvironment};
-use crate::detection::{NvmVariant, detect_nvm, detect_nvm_environment, install_nvm};
-use crate::update::check_for_nvm_update;
+use crate::detection::{
+    NvmInstallMethod, NvmVariant, detect_nvm, detect_nvm_environment, install_nvm,
+};
+use crate::update::{check_for_nvm_update, run_upgrade_script, upgrade_script, upgrade_summary};
 
 pub struct NvmProvider {
     variant: std::sync::Mutex<NvmVariant>,
+    nvm_dir: Option<PathBuf>,
+    sanitize_env: bool,
 }
 
 impl Default for NvmProvider {
     fn default() -> Self {
         Self {
             variant: std::sync::Mutex::new(NvmVariant::NotFound),
+            nvm_dir: None,
+            sanitize_env: false,
         }
     }
 }
@@ -26,6 +35,20 @@ impl NvmProvider {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// A provider that looks for nvm in `nvm_dir` before the usual places.
+    pub fn with_nvm_dir(nvm_dir: Option<PathBuf>) -> Self {
+        Self {
+            nvm_dir,
+            ..Self::default()
+        }
+    }
+
+    /// Leaves variables set up by fnm or Volta out of nvm commands.
+    pub fn with_sanitized_env(mut self, enabled: bool) -> Self {
+        self.sanitize_env = enabled;
+        self
+    }
 }
 
 #[async_trait]
@@ -47,11 +70,19 @@ impl BackendProvider for NvmProvider {
     }
 
     async fn detect(&self) -> BackendDetection {
-        let detection = detect_nvm().await;
+        let detection = detect_nvm(self.nvm_dir.as_deref()).await;
 
         *self.variant.lock().unwrap_or_else(|e| e.into_inner()) = detection.variant.clone();
 
         let path = detection.nvm_dir.clone().or(detection.nvm_exe.clone());
+        let source = match detection.variant {
+            NvmVariant::Unix => detection
+                .nvm_dir
+                .as_deref()
+                .map(|dir| NvmInstallMethod::of(dir).label()),
+            NvmVariant::Windows => Some("nvm-windows"),
+            NvmVariant::NotFound => None,
+        };
 
         BackendDetection {
             found: detection.found,
@@ -59,6 +90,7 @@ impl BackendProvider for NvmProvider {
             version: detection.version,
             in_path: detection.found,
             data_dir: detection.nvm_dir,
+            source: source.map(str::to_string),
         }
     }
 
@@ -70,15 +102,71 @@ impl BackendProvider for NvmProvider {
 
     async fn check_for_update(
         &self,
-        client: &reqwest::Client,
+        github: &versi_core::GitHubClient,
         current_version: &str,
+        channel: &BackendUpdateChannel,
     ) -> Result<Option<BackendUpdate>, String> {
         let variant = self
             .variant
             .lock()
             .unwrap_or_else(|e| e.into_inner())
             .clone();
-        check_for_nvm_update(client, current_version, &variant).await
+        check_for_nvm_update(github, current_version, &variant, channel).await
+    }
+
+    async fn update_backend(
+        &self,
+        version: &str,
+        progress: mpsc::Sender<String>,
+    ) -> Result<String, BackendError> {
+        let detection = self.detect().await;
+        let variant = self
+            .variant
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        let result = if variant == NvmVariant::Windows {
+            let package_manager = crate::WindowsPackageManager::available()
+                .into_iter()
+                .next()
+                .ok_or_else(|| {
+                    BackendError::InstallFailed(
+                        "Neither winget nor Chocolatey was found".to_string(),
+                    )
+                })?;
+            package_manager
+                .upgrade_nvm(progress)
+                .await
+                .map(|()| format!("Upgraded with {}", package_manager.display_name()))
+        } else {
+            let method = detection.data_dir.as_deref().map(NvmInstallMethod::of);
+            let nvm_dir = detection
+                .data_dir
+                .as_ref()
+                .map(|dir| dir.to_string_lossy().to_string());
+            let script = upgrade_script(method, nvm_dir.as_deref(), version);
+            info!("Upgrading nvm: {}", script);
+            run_upgrade_script(None, &script, &progress)
+                .await
+                .map(|()| upgrade_summary(method, version))
+        };
+        result.map_err(|e| BackendError::InstallFailed(e.to_string()))
+    }
+
+    async fn update_backend_in_wsl(
+        &self,
+        distro: &str,
+        backend_path: &str,
+        version: &str,
+        progress: mpsc::Sender<String>,
+    ) -> Result<String, BackendError> {
+        let nvm_dir = backend_path.strip_suffix("/nvm.sh").unwrap_or(backend_path);
+        let script = upgrade_script(None, Some(nvm_dir), version);
+        info!("Upgrading nvm in {}: {}", distro, script);
+        run_upgrade_script(Some(distro), &script, &progress)
+            .await
+            .map(|()| upgrade_summary(None, version))
+            .map_err(|e| BackendError::InstallFailed(e.to_string()))
     }
 
     fn create_manager(&self, detection: &BackendDetection) -> Box<dyn VersionManager> {
@@ -106,12 +194,28 @@ impl BackendProvider for NvmProvider {
                     .data_dir
                     .clone()
                     .or_else(|| detection.path.clone())
-                    .unwrap_or_else(|| PathBuf::from("~/.nvm")),
+                    .or_else(|| self.nvm_dir.clone())
+                    .or_else(|| dirs::home_dir().map(|home| home.join(".nvm")))
+                    .unwrap_or_else(|| PathBuf::from(".nvm")),
             });
 
-        let client = NvmClient { environment };
+        let mut env_removals = Vec::new();
+        if self.sanitize_env {
+            env_removals = conflicting_env_vars(self.name());
+            if !env_removals.is_empty() {
+                info!("Leaving {} out of nvm commands", env_removals.join(", "));
+            }
+        }
+        let client = NvmClient {
+            environment,
+            env_overrides: Vec::new(),
+            env_removals,
+        };
 
-        Box::new(NvmBackend::new(client, detection.version.clone()))
+        Box::new(
+            NvmBackend::new(client, detection.version.clone())
+                .with_source(detection.source.clone()),
+        )
     }
 
     fn create_manager_for_wsl(
@@ -119,20 +223,32 @@ impl BackendProvider for NvmProvider {
         distro: String,
         backend_path: String,
     ) -> Box<dyn VersionManager> {
-        let nvm_dir = if backend_path.ends_with("nvm.sh") {
-            backend_path
-                .strip_suffix("/nvm.sh")
-                .unwrap_or(&backend_path)
-                .to_string()
-        } else {
-            backend_path
-        };
+        let client = NvmClient::wsl(distro, nvm_dir_of(backend_path));
+        Box::new(NvmBackend::new(client, None))
+    }
 
-        let client = NvmClient::wsl(distro, nvm_dir);
+    fn create_manager_for_msys(
+        &self,
+        bash: String,
+        backend_path: String,
+    ) -> Box<dyn VersionManager> {
+        let client = NvmClient::msys(bash, nvm_dir_of(backend_path));
         Box::new(NvmBackend::new(client, None))
     }
 
     fn wsl_search_paths(&self) -> Vec<&'static str> {
         vec!["$HOME/.nvm/nvm.sh"]
     }
+
+    fn msys_search_paths(&self) -> Vec<&'static str> {
+        vec!["$HOME/.nvm/nvm.sh"]
+    }
+}
+
+/// The nvm directory of a detected `nvm.sh`.
+fn nvm_dir_of(backend_path: String) -> String {
+    match backend_path.strip_suffix("/nvm.sh") {
+        Some(dir) => dir.to_string(),
+        None => backend_path,
+    }
 }
@@ -1,89 +1,152 @@
-use serde::Deserialize;
-use versi_backend::BackendUpdate;
+use tokio::process::Command;
+use tokio::sync::mpsc;
+use versi_backend::{BackendUpdate, BackendUpdateChannel, check_github_update};
+use versi_core::GitHubClient;
+use versi_platform::{HideWindow, run_streamed};
 
-use crate::detection::NvmVariant;
+use crate::NvmError;
+use crate::client::shell_quote;
+use crate::detection::{NvmInstallMethod, NvmVariant};
 
 const NVM_UNIX_REPO: &str = "nvm-sh/nvm";
 const NVM_WINDOWS_REPO: &str = "coreybutler/nvm-windows";
 
-#[derive(Deserialize)]
-struct GitHubRelease {
-    tag_name: String,
-    html_url: String,
-}
-
 pub async fn check_for_nvm_update(
-    client: &reqwest::Client,
+    github: &GitHubClient,
     current_version: &str,
     variant: &NvmVariant,
+    channel: &BackendUpdateChannel,
 ) -> Result<Option<BackendUpdate>, String> {
     let repo = match variant {
         NvmVariant::Unix | NvmVariant::NotFound => NVM_UNIX_REPO,
         NvmVariant::Windows => NVM_WINDOWS_REPO,
     };
 
-    let url = format!("https://api.github.com/repos/{}/releases/latest", repo);
-
-    let response = client
-        .get(&url)
-        .header("User-Agent", "versi")
-        .send()
+    check_github_update(github, repo, current_version, channel)
         .await
-        .map_err(|e| format!("Failed to check for nvm update: {}", e))?;
+        .map_err(|e| format!("Failed to check for nvm update: {}", e))
+}
 
-    if !response.status().is_success() {
-        return Ok(None);
-    }
+/// A script that updates the install in `nvm_dir` to `version` the way it
+/// was installed. When `method` is unknown, as inside WSL, the script checks
+/// for a git checkout itself.
+pub(crate) fn upgrade_script(
+    method: Option<NvmInstallMethod>,
+    nvm_dir: Option<&str>,
+    version: &str,
+) -> String {
+    let nvm_dir = nvm_dir.map_or_else(|| "\"$HOME/.nvm\"".to_string(), shell_quote);
+    let version = version.trim_start_matches('v');
+    let steps = match method {
+        Some(NvmInstallMethod::Git) => git_upgrade(version),
+        Some(NvmInstallMethod::Script) => script_upgrade(version),
+        None => format!(
+            "if [ -d \"$NVM_DIR/.git\" ]; then {}; else {}; fi",
+            git_upgrade(version),
+            script_upgrade(version)
+        ),
+    };
+    format!("export NVM_DIR={}; {}", nvm_dir, steps)
+}
 
-    let release: GitHubRelease = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse nvm update response: {}", e))?;
-
-    let latest = release
-        .tag_name
-        .strip_prefix('v')
-        .unwrap_or(&release.tag_name);
-    let current = current_version.strip_prefix('v').unwrap_or(current_version);
-
-    if is_newer_version(latest, current) {
-        Ok(Some(BackendUpdate {
-            current_version: current.to_string(),
-            latest_version: latest.to_string(),
-            release_url: release.html_url,
-        }))
-    } else {
-        Ok(None)
+/// Checks out the release tag, as nvm's manual upgrade instructions do.
+fn git_upgrade(version: &str) -> String {
+    format!(
+        "cd \"$NVM_DIR\" && git fetch --tags --progress origin && git -c advice.detachedHead=false checkout v{}",
+        version
+    )
+}
+
+/// Reruns the installer over the existing files. `METHOD=script` keeps it
+/// from turning them into a git checkout, and `PROFILE=/dev/null` from
+/// adding its lines to shell startup files again.
+fn script_upgrade(version: &str) -> String {
+    format!(
+        "curl -fsSL https://raw.githubusercontent.com/nvm-sh/nvm/v{}/install.sh | METHOD=script PROFILE=/dev/null bash",
+        version
+    )
+}
+
+/// How an upgrade was done, for the result shown once it finishes.
+pub(crate) fn upgrade_summary(method: Option<NvmInstallMethod>, version: &str) -> String {
+    let version = version.trim_start_matches('v');
+    match method {
+        Some(NvmInstallMethod::Git) => format!("Checked out nvm {} with git", version),
+        Some(NvmInstallMethod::Script) => {
+            format!("Upgraded to nvm {} with the install script", version)
+        }
+        None => format!("Upgraded to nvm {}", version),
     }
 }
 
-fn is_newer_version(latest: &str, current: &str) -> bool {
-    let parse_version = |v: &str| -> Option<(u32, u32, u32)> {
-        let parts: Vec<&str> = v.split('.').collect();
-        if parts.len() >= 3 {
-            Some((
-                parts[0].parse().ok()?,
-                parts[1].parse().ok()?,
-                parts[2].parse().ok()?,
-            ))
-        } else if parts.len() == 2 {
-            Some((parts[0].parse().ok()?, parts[1].parse().ok()?, 0))
-        } else if parts.len() == 1 {
-            Some((parts[0].parse().ok()?, 0, 0))
-        } else {
-            None
+/// Runs an upgrade script with bash, inside `distro` when one is given,
+/// sending its output to `progress` a line at a time.
+pub(crate) async fn run_upgrade_script(
+    distro: Option<&str>,
+    script: &str,
+    progress: &mpsc::Sender<String>,
+) -> Result<(), NvmError> {
+    let mut command = match distro {
+        Some(distro) => {
+            let mut command = Command::new("wsl.exe");
+            command.args(["-d", distro, "--", "bash", "-c", script]);
+            command
+        }
+        None => {
+            let mut command = Command::new("bash");
+            command.args(["-c", script]);
+            command
         }
     };
-
-    match (parse_version(latest), parse_version(current)) {
-        (Some(l), Some(c)) => l > c,
-        _ => latest != current,
+    let output = run_streamed(command.hide_window(), progress).await?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(NvmError::InstallFailed(
+            stderr
+                .lines()
+                .rev()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .map_or_else(|| output.status.to_string(), str::to_string),
+        ))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use versi_core::is_newer_version;
+
+    #[test]
+    fn upgrade_script_keeps_the_existing_nvm_dir() {
+        assert_eq!(
+            upgrade_script(
+                Some(NvmInstallMethod::Script),
+                Some("/home/me/.nvm"),
+                "v0.40.3"
+            ),
+            "export NVM_DIR='/home/me/.nvm'; curl -fsSL \
+             https://raw.githubusercontent.com/nvm-sh/nvm/v0.40.3/install.sh | METHOD=script PROFILE=/dev/null bash"
+        );
+        assert!(
+            upgrade_script(Some(NvmInstallMethod::Script), None, "0.40.3")
+                .starts_with("export NVM_DIR=\"$HOME/.nvm\";")
+        );
+    }
+
+    #[test]
+    fn upgrade_script_follows_the_install_method() {
+        let git = upgrade_script(Some(NvmInstallMethod::Git), Some("/home/me/.nvm"), "0.40.3");
+        assert!(git.contains("git fetch --tags --progress origin"));
+        assert!(git.ends_with("checkout v0.40.3"));
+        assert!(!git.contains("install.sh"));
+
+        let unknown = upgrade_script(None, Some("/home/me/.nvm"), "0.40.3");
+        assert!(unknown.contains("if [ -d \"$NVM_DIR/.git\" ]; then cd"));
+        assert!(unknown.contains("else curl"));
+    }
 
     #[test]
     fn newer_version_returns_true() {
@@ -0,0 +1,156 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Native id of the window announcements come from; `0` until one opens.
+/// Only Windows needs the id itself, elsewhere it just marks the app ready.
+static WINDOW: AtomicU64 = AtomicU64::new(0);
+
+/// Sets the window screen readers attribute announcements to, given its
+/// native id. Nothing is announced before this is called.
+pub fn set_announcement_window(raw_window_id: u64) {
+    WINDOW.store(raw_window_id, Ordering::Relaxed);
+}
+
+/// Has the screen reader read `message` out without moving focus, like a
+/// live region: an `NSAccessibility` announcement on macOS, a UI Automation
+/// notification on Windows and, on Linux, speech-dispatcher while AT-SPI
+/// reports a screen reader running. Does nothing when no screen reader is
+/// listening.
+pub fn announce(message: &str) {
+    let raw_window_id = WINDOW.load(Ordering::Relaxed);
+    if raw_window_id == 0 || message.is_empty() {
+        return;
+    }
+    #[cfg(target_os = "macos")]
+    macos::announce(message);
+    #[cfg(target_os = "windows")]
+    win32::announce(raw_window_id, message);
+    #[cfg(target_os = "linux")]
+    linux::announce(message);
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use objc2::MainThreadMarker;
+    use objc2::runtime::AnyObject;
+    use objc2_app_kit::{
+        NSAccessibilityAnnouncementKey, NSAccessibilityAnnouncementRequestedNotification,
+        NSAccessibilityPostNotificationWithUserInfo, NSAccessibilityPriorityKey,
+        NSAccessibilityPriorityLevel, NSApplication,
+    };
+    use objc2_foundation::{NSDictionary, NSNumber, NSString};
+
+    pub(super) fn announce(message: &str) {
+        // AppKit has to be called from the main thread, where Versi updates.
+        let Some(mtm) = MainThreadMarker::new() else {
+            return;
+        };
+        let app = NSApplication::sharedApplication(mtm);
+        let text = NSString::from_str(message);
+        let priority = NSNumber::new_isize(NSAccessibilityPriorityLevel::High.0);
+        // SAFETY: the keys are AppKit constants, the values the types
+        // documented for them, and the element is the shared application.
+        unsafe {
+            let values: [&AnyObject; 2] = [&text, &priority];
+            let info = NSDictionary::from_slices(
+                &[NSAccessibilityAnnouncementKey, NSAccessibilityPriorityKey],
+                &values,
+            );
+            NSAccessibilityPostNotificationWithUserInfo(
+                &app,
+                NSAccessibilityAnnouncementRequestedNotification,
+                Some(&info),
+            );
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod win32 {
+    use std::ffi::c_void;
+
+    use windows_sys::Win32::Foundation::{HWND, SysAllocString, SysFreeString};
+    use windows_sys::Win32::UI::Accessibility::{
+        NotificationKind_ActionCompleted, NotificationProcessing_ImportantMostRecent,
+        UiaClientsAreListening, UiaHostProviderFromHwnd, UiaRaiseNotificationEvent,
+    };
+
+    /// The `IUnknown` part of a COM object's vtable.
+    #[repr(C)]
+    struct UnknownVtbl {
+        query_interface: usize,
+        add_ref: usize,
+        release: unsafe extern "system" fn(*mut c_void) -> u32,
+    }
+
+    pub(super) fn announce(raw_window_id: u64, message: &str) {
+        let hwnd = raw_window_id as usize as HWND;
+        let text: Vec<u16> = message.encode_utf16().chain(std::iter::once(0)).collect();
+        let activity: Vec<u16> = "VersiStatus"
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .collect();
+
+        // SAFETY: the provider comes from UI Automation for a live window
+        // and is released once; the strings are freed after the call.
+        unsafe {
+            if UiaClientsAreListening() == 0 {
+                return;
+            }
+            let mut provider: *mut c_void = std::ptr::null_mut();
+            if UiaHostProviderFromHwnd(hwnd, &mut provider) != 0 || provider.is_null() {
+                return;
+            }
+            let text = SysAllocString(text.as_ptr());
+            let activity = SysAllocString(activity.as_ptr());
+            UiaRaiseNotificationEvent(
+                provider,
+                NotificationKind_ActionCompleted,
+                NotificationProcessing_ImportantMostRecent,
+                text,
+                activity,
+            );
+            SysFreeString(text);
+            SysFreeString(activity);
+            let vtbl = *(provider as *const *const UnknownVtbl);
+            ((*vtbl).release)(provider);
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::process::Command;
+
+    pub(super) fn announce(message: &str) {
+        let message = message.to_string();
+        std::thread::spawn(move || {
+            if !screen_reader_enabled() {
+                return;
+            }
+            // Orca speaks through speech-dispatcher too, so this comes out
+            // of the same voice and queue.
+            if let Err(e) = Command::new("spd-say")
+                .args(["--application-name", "Versi", "--priority", "message"])
+                .arg(&message)
+                .status()
+            {
+                log::debug!("Failed to announce through spd-say: {}", e);
+            }
+        });
+    }
+
+    /// AT-SPI's own flag for a running screen reader, which Orca sets.
+    fn screen_reader_enabled() -> bool {
+        Command::new("busctl")
+            .args([
+                "--user",
+                "get-property",
+                "org.a11y.Bus",
+                "/org/a11y/bus",
+                "org.a11y.Status",
+                "ScreenReaderEnabled",
+            ])
+            .output()
+            .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).trim() == "b true")
+    }
+}
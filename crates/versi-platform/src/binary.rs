@@ -0,0 +1,163 @@
+use std::io::Read;
+use std::path::Path;
+
+const MH_MAGIC_64: u32 = 0xfeed_facf;
+const FAT_MAGIC: u32 = 0xcafe_babe;
+const FAT_MAGIC_64: u32 = 0xcafe_babf;
+const CPU_TYPE_X86_64: u32 = 0x0100_0007;
+const CPU_TYPE_ARM64: u32 = 0x0100_000c;
+
+/// Bytes read from the start of a binary; enough for the fat header and a
+/// handful of architectures.
+const HEADER_LEN: u64 = 256;
+
+/// CPU architecture a Mach-O executable was built for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BinaryArch {
+    Arm64,
+    X64,
+    /// Contains both `arm64` and `x86_64` slices.
+    Universal,
+}
+
+impl BinaryArch {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Arm64 => "arm64",
+            Self::X64 => "x86_64",
+            Self::Universal => "universal",
+        }
+    }
+
+    /// Whether a binary of this architecture runs natively on `host`.
+    pub fn runs_natively_on(self, host: BinaryArch) -> bool {
+        self == host || self == Self::Universal
+    }
+}
+
+/// Reads the architecture of the Mach-O executable at `path`. `None` when
+/// the file cannot be read or is not a 64-bit Mach-O binary.
+pub fn binary_arch(path: &Path) -> Option<BinaryArch> {
+    let mut header = Vec::new();
+    std::fs::File::open(path)
+        .ok()?
+        .take(HEADER_LEN)
+        .read_to_end(&mut header)
+        .ok()?;
+    parse_mach_header(&header)
+}
+
+/// The architecture of the machine itself. On Apple Silicon this is `arm64`
+/// even when Versi runs under Rosetta 2.
+pub fn host_arch() -> Option<BinaryArch> {
+    #[cfg(target_os = "macos")]
+    {
+        let output = std::process::Command::new("sysctl")
+            .args(["-n", "hw.optional.arm64"])
+            .output()
+            .ok()?;
+        if String::from_utf8_lossy(&output.stdout).trim() == "1" {
+            return Some(BinaryArch::Arm64);
+        }
+    }
+    match std::env::consts::ARCH {
+        "aarch64" => Some(BinaryArch::Arm64),
+        "x86_64" => Some(BinaryArch::X64),
+        _ => None,
+    }
+}
+
+fn parse_mach_header(header: &[u8]) -> Option<BinaryArch> {
+    let word = |offset: usize, big_endian: bool| -> Option<u32> {
+        let bytes: [u8; 4] = header.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    };
+
+    match word(0, true)? {
+        magic @ (FAT_MAGIC | FAT_MAGIC_64) => {
+            let entry_len = if magic == FAT_MAGIC { 20 } else { 32 };
+            let count = word(4, true)? as usize;
+            let (mut arm64, mut x64) = (false, false);
+            for i in 0..count {
+                match word(8 + i * entry_len, true) {
+                    Some(CPU_TYPE_ARM64) => arm64 = true,
+                    Some(CPU_TYPE_X86_64) => x64 = true,
+                    Some(_) => {}
+                    None => break,
+                }
+            }
+            match (arm64, x64) {
+                (true, true) => Some(BinaryArch::Universal),
+                (true, false) => Some(BinaryArch::Arm64),
+                (false, true) => Some(BinaryArch::X64),
+                (false, false) => None,
+            }
+        }
+        _ if word(0, false)? == MH_MAGIC_64 => match word(4, false)? {
+            CPU_TYPE_ARM64 => Some(BinaryArch::Arm64),
+            CPU_TYPE_X86_64 => Some(BinaryArch::X64),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thin(cpu_type: u32) -> Vec<u8> {
+        let mut header = MH_MAGIC_64.to_le_bytes().to_vec();
+        header.extend(cpu_type.to_le_bytes());
+        header.extend([0; 24]);
+        header
+    }
+
+    fn fat(cpu_types: &[u32]) -> Vec<u8> {
+        let mut header = FAT_MAGIC.to_be_bytes().to_vec();
+        header.extend((cpu_types.len() as u32).to_be_bytes());
+        for cpu_type in cpu_types {
+            header.extend(cpu_type.to_be_bytes());
+            header.extend([0; 16]);
+        }
+        header
+    }
+
+    #[test]
+    fn reads_thin_and_fat_headers() {
+        assert_eq!(
+            parse_mach_header(&thin(CPU_TYPE_ARM64)),
+            Some(BinaryArch::Arm64)
+        );
+        assert_eq!(
+            parse_mach_header(&thin(CPU_TYPE_X86_64)),
+            Some(BinaryArch::X64)
+        );
+        assert_eq!(
+            parse_mach_header(&fat(&[CPU_TYPE_X86_64, CPU_TYPE_ARM64])),
+            Some(BinaryArch::Universal)
+        );
+        assert_eq!(
+            parse_mach_header(&fat(&[CPU_TYPE_X86_64])),
+            Some(BinaryArch::X64)
+        );
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert_eq!(parse_mach_header(b"\x7fELF\x02\x01\x01\x00"), None);
+        assert_eq!(parse_mach_header(b"#!/bin/sh\n"), None);
+        assert_eq!(parse_mach_header(&[0xcf, 0xfa]), None);
+    }
+
+    #[test]
+    fn universal_runs_everywhere() {
+        assert!(BinaryArch::Universal.runs_natively_on(BinaryArch::Arm64));
+        assert!(BinaryArch::Arm64.runs_natively_on(BinaryArch::Arm64));
+        assert!(!BinaryArch::X64.runs_natively_on(BinaryArch::Arm64));
+    }
+}
@@ -0,0 +1,174 @@
+use std::ffi::OsStr;
+use std::path::Path;
+
+use tokio::process::Command;
+
+use crate::HideWindow;
+
+/// Whether the current user can create files in `dir`, or in its closest
+/// existing parent when `dir` has not been created yet.
+pub fn is_writable(dir: &Path) -> bool {
+    let Some(existing) = dir.ancestors().find(|dir| dir.is_dir()) else {
+        return false;
+    };
+    let probe = existing.join(format!(".versi-write-check-{}", std::process::id()));
+    match std::fs::File::create(&probe) {
+        Ok(_) => {
+            let _ = std::fs::remove_file(&probe);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// How a command gets administrator rights when it has to write somewhere
+/// the current user cannot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Elevation {
+    /// polkit's `pkexec`, which shows the desktop's authentication dialog.
+    Pkexec,
+    /// `osascript` with administrator privileges, which shows the macOS
+    /// password prompt.
+    AppleScript,
+}
+
+impl Elevation {
+    /// The elevation available on this system. `None` on Windows, where
+    /// Versi has to be started as an administrator instead, and on Linux
+    /// without polkit.
+    pub fn available() -> Option<Self> {
+        if cfg!(target_os = "macos") {
+            Some(Self::AppleScript)
+        } else if cfg!(target_os = "linux") && in_path("pkexec") {
+            Some(Self::Pkexec)
+        } else {
+            None
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Pkexec => "pkexec",
+            Self::AppleScript => "the macOS administrator prompt",
+        }
+    }
+
+    /// `cmd` wrapped so that it runs as an administrator after the user
+    /// authenticates. Environment variables set on `cmd` are passed along;
+    /// both tools start the command with a clean environment otherwise.
+    pub fn wrap(self, cmd: &Command) -> Command {
+        let cmd = cmd.as_std();
+        let envs: Vec<(&OsStr, &OsStr)> = cmd
+            .get_envs()
+            .filter_map(|(key, value)| Some((key, value?)))
+            .collect();
+
+        let mut elevated = match self {
+            Self::Pkexec => {
+                let mut elevated = Command::new("pkexec");
+                elevated.arg("env");
+                elevated.args(envs.iter().map(|(key, value)| {
+                    let mut pair = key.to_os_string();
+                    pair.push("=");
+                    pair.push(value);
+                    pair
+                }));
+                elevated.arg(cmd.get_program());
+                elevated.args(cmd.get_args());
+                elevated
+            }
+            Self::AppleScript => {
+                let script = envs
+                    .iter()
+                    .map(|(key, value)| format!("{}={}", key.to_string_lossy(), shell_quote(value)))
+                    .chain(std::iter::once(shell_quote(cmd.get_program())))
+                    .chain(cmd.get_args().map(shell_quote))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let mut elevated = Command::new("osascript");
+                elevated.arg("-e").arg(format!(
+                    "do shell script \"{}\" with administrator privileges",
+                    script.replace('\\', "\\\\").replace('"', "\\\"")
+                ));
+                elevated
+            }
+        };
+        if let Some(dir) = cmd.get_current_dir() {
+            elevated.current_dir(dir);
+        }
+        elevated.hide_window();
+        elevated
+    }
+
+    /// Whether a failed elevated command failed because the user dismissed
+    /// the authentication prompt.
+    pub fn was_cancelled(self, stderr: &str) -> bool {
+        match self {
+            Self::Pkexec => {
+                stderr.contains("Request dismissed") || stderr.contains("Not authorized")
+            }
+            Self::AppleScript => stderr.contains("(-128)"),
+        }
+    }
+}
+
+fn in_path(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .is_some_and(|path| std::env::split_paths(&path).any(|dir| dir.join(program).is_file()))
+}
+
+/// `value` in single quotes for `/bin/sh`.
+fn shell_quote(value: &OsStr) -> String {
+    format!("'{}'", value.to_string_lossy().replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parts(cmd: &Command) -> Vec<String> {
+        let cmd = cmd.as_std();
+        std::iter::once(cmd.get_program())
+            .chain(cmd.get_args())
+            .map(|part| part.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn wraps_commands_with_their_environment() {
+        let mut cmd = Command::new("/usr/bin/fnm");
+        cmd.args(["install", "v22.1.0"])
+            .env("FNM_DIR", "/opt/versi/fnm");
+
+        assert_eq!(
+            parts(&Elevation::Pkexec.wrap(&cmd)),
+            [
+                "pkexec",
+                "env",
+                "FNM_DIR=/opt/versi/fnm",
+                "/usr/bin/fnm",
+                "install",
+                "v22.1.0"
+            ]
+        );
+
+        cmd.arg("it's");
+        assert_eq!(
+            parts(&Elevation::AppleScript.wrap(&cmd)),
+            [
+                "osascript",
+                "-e",
+                r#"do shell script "FNM_DIR='/opt/versi/fnm' '/usr/bin/fnm' 'install' 'v22.1.0' 'it'\\''s'" with administrator privileges"#
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_the_closest_existing_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(is_writable(dir.path()));
+        assert!(is_writable(&dir.path().join("not/created/yet")));
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+        assert!(!is_writable(Path::new("relative/dir")));
+    }
+}
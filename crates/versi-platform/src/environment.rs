@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -7,6 +9,11 @@ pub enum EnvironmentId {
         distro: String,
         backend_path: String,
     },
+    /// The bash of Git Bash or MSYS2 at `bash`, running `backend_path`.
+    Msys {
+        bash: String,
+        backend_path: String,
+    },
 }
 
 impl EnvironmentId {
@@ -27,8 +34,51 @@ impl EnvironmentId {
                 }
             }
             EnvironmentId::Wsl { distro, .. } => format!("WSL: {}", distro),
+            EnvironmentId::Msys { bash, .. } => msys_name(bash).to_string(),
+        }
+    }
+
+    pub fn settings_key(&self) -> String {
+        match self {
+            EnvironmentId::Native => "native".to_string(),
+            EnvironmentId::Wsl { distro, .. } => format!("wsl:{}", distro),
+            EnvironmentId::Msys { bash, .. } => format!("msys:{}", bash),
         }
     }
+
+    /// How a path on this machine is reached from inside the environment.
+    /// WSL mounts Windows drives under `/mnt/<drive>`, so `C:\certs\ca.pem`
+    /// becomes `/mnt/c/certs/ca.pem`; `None` when the path is not on a drive.
+    pub fn host_path(&self, path: &Path) -> Option<String> {
+        let path = path.to_string_lossy();
+        match self {
+            EnvironmentId::Native | EnvironmentId::Msys { .. } => Some(path.into_owned()),
+            EnvironmentId::Wsl { .. } => {
+                let mut chars = path.chars();
+                let drive = chars.next().filter(char::is_ascii_alphabetic)?;
+                let rest = chars.as_str().strip_prefix(':')?;
+                if !(rest.is_empty() || rest.starts_with(['\\', '/'])) {
+                    return None;
+                }
+                Some(format!(
+                    "/mnt/{}{}",
+                    drive.to_ascii_lowercase(),
+                    rest.replace('\\', "/")
+                ))
+            }
+        }
+    }
+}
+
+/// Git for Windows ships its bash under a `Git` directory; anything else is
+/// taken to be a full MSYS2 install.
+fn msys_name(bash: &str) -> &'static str {
+    let bash = bash.to_ascii_lowercase().replace('/', "\\");
+    if bash.contains("\\git\\") {
+        "Git Bash"
+    } else {
+        "MSYS2"
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -58,4 +108,13 @@ impl Environment {
             enabled: true,
         }
     }
+
+    pub fn msys(bash: String, backend_path: String) -> Self {
+        let id = EnvironmentId::Msys { bash, backend_path };
+        Self {
+            name: id.display_name(),
+            id,
+            enabled: true,
+        }
+    }
 }
@@ -1,13 +1,52 @@
+#[cfg(feature = "window")]
+mod announcer;
+mod binary;
 mod commands;
+mod elevation;
 mod environment;
+#[cfg(feature = "window")]
+mod monitors;
+#[cfg(feature = "window")]
+mod notifications;
 mod paths;
+#[cfg(feature = "window")]
+mod power;
+mod process;
+mod target;
+#[cfg(feature = "window")]
+mod vibrancy;
+#[cfg(feature = "window")]
+mod workspace;
 
+#[cfg(target_os = "windows")]
+mod msys;
 #[cfg(target_os = "windows")]
 mod wsl;
 
+#[cfg(feature = "window")]
+pub use announcer::{announce, set_announcement_window};
+pub use binary::{BinaryArch, binary_arch, host_arch};
 pub use commands::HideWindow;
+pub use elevation::{Elevation, is_writable};
 pub use environment::{Environment, EnvironmentId};
-pub use paths::AppPaths;
+#[cfg(feature = "window")]
+pub use monitors::{MonitorBounds, WindowPlacement, available_monitors, clamp_to_monitors};
+#[cfg(feature = "window")]
+pub use notifications::show_notification;
+pub use paths::{AppPaths, CustomDirs, move_file, system_wide_dir, validate_custom_dir};
+#[cfg(feature = "window")]
+pub use power::{on_ac_power, system_idle_time};
+pub use process::{
+    CommandTranscript, operation_transcripts, run_streamed, run_streamed_redraws, run_tracked,
+    terminate_tracked_processes, tracked_process_count, wait_for_tracked_processes, with_operation,
+};
+pub use target::{Libc, PROBE_COMMAND, PlatformTarget, TargetOs, UNOFFICIAL_BUILDS_MIRROR};
+#[cfg(feature = "window")]
+pub use vibrancy::set_window_vibrancy;
+#[cfg(feature = "window")]
+pub use workspace::{move_window_to_workspace, window_workspace, workspaces_supported};
 
+#[cfg(target_os = "windows")]
+pub use msys::{MsysShell, detect_msys_shells, execute_in_msys};
 #[cfg(target_os = "windows")]
 pub use wsl::{WslDistro, detect_wsl_distros, execute_in_wsl};
@@ -0,0 +1,384 @@
+/// Work area of a display in physical pixels, along with its scale factor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonitorBounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f32,
+}
+
+impl MonitorBounds {
+    fn contains(&self, x: f32, y: f32) -> bool {
+        x >= self.x as f32
+            && y >= self.y as f32
+            && x < (self.x + self.width as i32) as f32
+            && y < (self.y + self.height as i32) as f32
+    }
+
+    fn distance_squared(&self, x: f32, y: f32) -> f32 {
+        let dx = (self.x as f32 - x)
+            .max(0.0)
+            .max(x - (self.x + self.width as i32) as f32);
+        let dy = (self.y as f32 - y)
+            .max(0.0)
+            .max(y - (self.y + self.height as i32) as f32);
+        dx * dx + dy * dy
+    }
+}
+
+/// Window position and size in logical pixels, as reported by the windowing toolkit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowPlacement {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub scale_factor: f32,
+}
+
+/// Moves a saved window onto the monitor containing its center, or the nearest
+/// one when that monitor is gone, shrinking it if it no longer fits.
+///
+/// Returns `None` when no monitors are known.
+pub fn clamp_to_monitors(
+    placement: WindowPlacement,
+    monitors: &[MonitorBounds],
+) -> Option<WindowPlacement> {
+    let saved_scale = placement.scale_factor.max(0.1);
+    let center_x = (placement.x + placement.width / 2.0) * saved_scale;
+    let center_y = (placement.y + placement.height / 2.0) * saved_scale;
+
+    let target = monitors
+        .iter()
+        .find(|m| m.contains(center_x, center_y))
+        .or_else(|| {
+            monitors.iter().min_by(|a, b| {
+                a.distance_squared(center_x, center_y)
+                    .total_cmp(&b.distance_squared(center_x, center_y))
+            })
+        })?;
+
+    let scale = target.scale_factor.max(0.1);
+    let min_x = target.x as f32 / scale;
+    let min_y = target.y as f32 / scale;
+    let max_width = target.width as f32 / scale;
+    let max_height = target.height as f32 / scale;
+
+    let width = placement.width.min(max_width);
+    let height = placement.height.min(max_height);
+    let x = (placement.x * saved_scale / scale).clamp(min_x, min_x + max_width - width);
+    let y = (placement.y * saved_scale / scale).clamp(min_y, min_y + max_height - height);
+
+    Some(WindowPlacement {
+        x,
+        y,
+        width,
+        height,
+        scale_factor: scale,
+    })
+}
+
+/// Lists the work areas of all connected monitors.
+///
+/// Uses RandR on X11 and `NSScreen` on macOS, where it must be called on the
+/// main thread. Wayland compositors place windows themselves, so there the
+/// list is empty, as it is when the display can't be queried; callers should
+/// then fall back to their own heuristics.
+pub fn available_monitors() -> Vec<MonitorBounds> {
+    #[cfg(target_os = "windows")]
+    {
+        win32::available_monitors()
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::available_monitors()
+    }
+    #[cfg(target_os = "linux")]
+    {
+        x11::available_monitors().unwrap_or_default()
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    {
+        Vec::new()
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use super::MonitorBounds;
+    use objc2::MainThreadMarker;
+    use objc2_app_kit::NSScreen;
+
+    pub(super) fn available_monitors() -> Vec<MonitorBounds> {
+        let Some(mtm) = MainThreadMarker::new() else {
+            return Vec::new();
+        };
+        let screens = NSScreen::screens(mtm);
+        // Cocoa puts the origin at the bottom left of the primary screen and
+        // counts in points; the windowing toolkit flips it and scales by the
+        // backing factor of each screen.
+        let Some(primary_height) = screens.firstObject().map(|s| s.frame().size.height) else {
+            return Vec::new();
+        };
+        screens
+            .iter()
+            .map(|screen| {
+                let scale = screen.backingScaleFactor();
+                let work = screen.visibleFrame();
+                let top = primary_height - (work.origin.y + work.size.height);
+                MonitorBounds {
+                    x: (work.origin.x * scale).round() as i32,
+                    y: (top * scale).round() as i32,
+                    width: (work.size.width * scale).round().max(0.0) as u32,
+                    height: (work.size.height * scale).round().max(0.0) as u32,
+                    scale_factor: scale as f32,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod x11 {
+    use super::MonitorBounds;
+    use x11rb::connection::Connection;
+    use x11rb::protocol::randr::ConnectionExt as _;
+    use x11rb::protocol::xproto::{AtomEnum, ConnectionExt as _, Window};
+    use x11rb::rust_connection::RustConnection;
+
+    pub(super) fn available_monitors() -> Option<Vec<MonitorBounds>> {
+        // The windowing toolkit prefers Wayland when both are available.
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            return None;
+        }
+        std::env::var_os("DISPLAY")?;
+        let (conn, screen) = x11rb::connect(None).ok()?;
+        let root = conn.setup().roots.get(screen)?.root;
+        let reply = conn.randr_get_monitors(root, true).ok()?.reply().ok()?;
+        let global_scale = global_scale_factor(&conn, root);
+
+        Some(
+            reply
+                .monitors
+                .iter()
+                .map(|m| MonitorBounds {
+                    x: m.x.into(),
+                    y: m.y.into(),
+                    width: m.width.into(),
+                    height: m.height.into(),
+                    scale_factor: global_scale.unwrap_or_else(|| {
+                        scale_from_size(
+                            (m.width.into(), m.height.into()),
+                            (m.width_in_millimeters, m.height_in_millimeters),
+                        )
+                    }),
+                })
+                .collect(),
+        )
+    }
+
+    /// The scale factor set for the whole display, the way the windowing
+    /// toolkit picks it: `WINIT_X11_SCALE_FACTOR`, then `Xft.dpi`.
+    fn global_scale_factor(conn: &RustConnection, root: Window) -> Option<f32> {
+        if let Some(scale) = std::env::var("WINIT_X11_SCALE_FACTOR")
+            .ok()
+            .and_then(|v| v.trim().parse::<f32>().ok())
+            .filter(|scale| *scale > 0.0)
+        {
+            return Some(scale);
+        }
+        let resources = conn
+            .get_property(
+                false,
+                root,
+                AtomEnum::RESOURCE_MANAGER,
+                AtomEnum::STRING,
+                0,
+                u32::MAX,
+            )
+            .ok()?
+            .reply()
+            .ok()?;
+        xft_dpi(&String::from_utf8_lossy(&resources.value)).map(|dpi| dpi / 96.0)
+    }
+
+    fn xft_dpi(resources: &str) -> Option<f32> {
+        resources
+            .lines()
+            .find_map(|line| line.strip_prefix("Xft.dpi:"))
+            .and_then(|dpi| dpi.trim().parse::<f32>().ok())
+            .filter(|dpi| *dpi > 0.0)
+    }
+
+    /// Scale factor from the physical size of a monitor, quantized to
+    /// twelfths like the windowing toolkit does.
+    fn scale_from_size(
+        (width_px, height_px): (u32, u32),
+        (width_mm, height_mm): (u32, u32),
+    ) -> f32 {
+        if width_mm == 0 || height_mm == 0 {
+            return 1.0;
+        }
+        let ppmm = ((f64::from(width_px) * f64::from(height_px))
+            / (f64::from(width_mm) * f64::from(height_mm)))
+        .sqrt();
+        let scale = ((ppmm * (12.0 * 25.4 / 96.0)).round() / 12.0).max(1.0);
+        if scale <= 20.0 { scale as f32 } else { 1.0 }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn reads_xft_dpi() {
+            assert_eq!(xft_dpi("Xft.antialias:\t1\nXft.dpi:\t144\n"), Some(144.0));
+            assert_eq!(xft_dpi("Xcursor.size:\t24\n"), None);
+        }
+
+        #[test]
+        fn scales_by_pixel_density() {
+            assert_eq!(scale_from_size((1920, 1080), (527, 296)), 1.0);
+            assert_eq!(scale_from_size((3840, 2160), (508, 286)), 2.0);
+            assert_eq!(scale_from_size((1920, 1080), (0, 0)), 1.0);
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod win32 {
+    use super::MonitorBounds;
+    use windows_sys::Win32::Foundation::{LPARAM, RECT};
+    use windows_sys::Win32::Graphics::Gdi::{
+        EnumDisplayMonitors, GetMonitorInfoW, HDC, HMONITOR, MONITORINFO,
+    };
+    use windows_sys::Win32::UI::HiDpi::{
+        DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2, GetDpiForMonitor, MDT_EFFECTIVE_DPI,
+        SetThreadDpiAwarenessContext,
+    };
+    use windows_sys::core::BOOL;
+
+    unsafe extern "system" fn collect(
+        monitor: HMONITOR,
+        _hdc: HDC,
+        _rect: *mut RECT,
+        data: LPARAM,
+    ) -> BOOL {
+        // SAFETY: `data` is the pointer to the Vec passed to EnumDisplayMonitors below.
+        let monitors = unsafe { &mut *(data as *mut Vec<MonitorBounds>) };
+
+        let mut info: MONITORINFO = unsafe { std::mem::zeroed() };
+        info.cbSize = std::mem::size_of::<MONITORINFO>() as u32;
+        if unsafe { GetMonitorInfoW(monitor, &mut info) } == 0 {
+            return 1;
+        }
+
+        let (mut dpi_x, mut dpi_y) = (96u32, 96u32);
+        let scale_factor =
+            if unsafe { GetDpiForMonitor(monitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y) } == 0
+            {
+                dpi_x as f32 / 96.0
+            } else {
+                1.0
+            };
+
+        let work = info.rcWork;
+        monitors.push(MonitorBounds {
+            x: work.left,
+            y: work.top,
+            width: (work.right - work.left).max(0) as u32,
+            height: (work.bottom - work.top).max(0) as u32,
+            scale_factor,
+        });
+        1
+    }
+
+    pub(super) fn available_monitors() -> Vec<MonitorBounds> {
+        let mut monitors: Vec<MonitorBounds> = Vec::new();
+        unsafe {
+            // Enumerate in physical pixels regardless of the process DPI awareness,
+            // which the windowing toolkit only sets once its event loop starts.
+            let previous = SetThreadDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2);
+            EnumDisplayMonitors(
+                std::ptr::null_mut(),
+                std::ptr::null(),
+                Some(collect),
+                &mut monitors as *mut Vec<MonitorBounds> as LPARAM,
+            );
+            if !previous.is_null() {
+                SetThreadDpiAwarenessContext(previous);
+            }
+        }
+        monitors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(x: i32, y: i32, width: u32, height: u32, scale_factor: f32) -> MonitorBounds {
+        MonitorBounds {
+            x,
+            y,
+            width,
+            height,
+            scale_factor,
+        }
+    }
+
+    fn placement(x: f32, y: f32, width: f32, height: f32) -> WindowPlacement {
+        WindowPlacement {
+            x,
+            y,
+            width,
+            height,
+            scale_factor: 1.0,
+        }
+    }
+
+    #[test]
+    fn keeps_window_that_fits_on_its_monitor() {
+        let monitors = [
+            monitor(0, 0, 1920, 1080, 1.0),
+            monitor(1920, 0, 1920, 1080, 1.0),
+        ];
+        let result = clamp_to_monitors(placement(2000.0, 100.0, 800.0, 600.0), &monitors).unwrap();
+        assert_eq!(result, placement(2000.0, 100.0, 800.0, 600.0));
+    }
+
+    #[test]
+    fn moves_window_from_disconnected_monitor_to_nearest() {
+        let monitors = [monitor(0, 0, 1920, 1080, 1.0)];
+        let result = clamp_to_monitors(placement(2500.0, 200.0, 800.0, 600.0), &monitors).unwrap();
+        assert_eq!(result.x, 1120.0);
+        assert_eq!(result.y, 200.0);
+    }
+
+    #[test]
+    fn shrinks_window_larger_than_monitor() {
+        let monitors = [monitor(0, 0, 1280, 720, 1.0)];
+        let result = clamp_to_monitors(placement(-50.0, -50.0, 1600.0, 900.0), &monitors).unwrap();
+        assert_eq!(result, placement(0.0, 0.0, 1280.0, 720.0));
+    }
+
+    #[test]
+    fn converts_position_between_scale_factors() {
+        let monitors = [
+            monitor(0, 0, 1920, 1080, 1.0),
+            monitor(1920, 0, 3840, 2160, 2.0),
+        ];
+        let saved = WindowPlacement {
+            scale_factor: 2.0,
+            ..placement(1000.0, 100.0, 800.0, 600.0)
+        };
+        let result = clamp_to_monitors(saved, &monitors).unwrap();
+        assert_eq!(result, saved);
+    }
+
+    #[test]
+    fn returns_none_without_monitors() {
+        assert!(clamp_to_monitors(placement(0.0, 0.0, 800.0, 600.0), &[]).is_none());
+    }
+}
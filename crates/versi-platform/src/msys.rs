@@ -0,0 +1,151 @@
+use log::{debug, error, info, trace, warn};
+use std::path::PathBuf;
+use std::process::Command;
+use thiserror::Error;
+
+use crate::HideWindow;
+
+/// The bash of a Git Bash or MSYS2 install.
+#[derive(Debug, Clone)]
+pub struct MsysShell {
+    pub bash: String,
+    pub backend_path: Option<String>,
+}
+
+#[derive(Error, Debug)]
+pub enum MsysError {
+    #[error("Command failed: {stderr}")]
+    CommandFailed { stderr: String },
+
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+/// Where Git for Windows and MSYS2 put their bash by default.
+fn bash_candidates() -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    for var in ["ProgramFiles", "ProgramW6432"] {
+        if let Some(dir) = std::env::var_os(var) {
+            candidates.push(PathBuf::from(dir).join("Git").join("bin").join("bash.exe"));
+        }
+    }
+    if let Some(dir) = std::env::var_os("LOCALAPPDATA") {
+        candidates.push(
+            PathBuf::from(dir)
+                .join("Programs")
+                .join("Git")
+                .join("bin")
+                .join("bash.exe"),
+        );
+    }
+    candidates.push(PathBuf::from(r"C:\msys64\usr\bin\bash.exe"));
+    candidates
+}
+
+pub fn detect_msys_shells(search_paths: &[&str]) -> Vec<MsysShell> {
+    info!("Detecting Git Bash and MSYS2...");
+
+    let mut shells: Vec<MsysShell> = Vec::new();
+    for candidate in bash_candidates() {
+        if !candidate.is_file() {
+            trace!("No bash at {:?}", candidate);
+            continue;
+        }
+        let bash = candidate.display().to_string();
+        if shells.iter().any(|s| s.bash.eq_ignore_ascii_case(&bash)) {
+            continue;
+        }
+
+        let backend_path = find_backend_path(&bash, search_paths);
+        match &backend_path {
+            Some(path) => info!("Found backend for {}: {}", bash, path),
+            None => warn!("Backend not found for {}", bash),
+        }
+        shells.push(MsysShell { bash, backend_path });
+    }
+
+    info!("MSYS detection complete: {} shells", shells.len());
+    shells
+}
+
+/// A script that prints the first of `search_paths` that is executable.
+/// Paths are double quoted so `$HOME` still expands when it has spaces.
+fn backend_check_script(search_paths: &[&str]) -> String {
+    search_paths
+        .iter()
+        .map(|p| format!("[ -x \"{}\" ] && {{ echo \"{}\"; exit 0; }}", p, p))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+fn find_backend_path(bash: &str, search_paths: &[&str]) -> Option<String> {
+    if search_paths.is_empty() {
+        return None;
+    }
+
+    let check_cmd = backend_check_script(search_paths);
+    debug!(
+        "Running backend path detection: {} -c \"{}\"",
+        bash, check_cmd
+    );
+
+    let output = Command::new(bash)
+        .args(["-c", &check_cmd])
+        .hide_window()
+        .output();
+
+    match output {
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .next()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty()),
+        Ok(output) => {
+            debug!(
+                "Backend path detection for {} found nothing: {}",
+                bash,
+                String::from_utf8_lossy(&output.stderr)
+            );
+            None
+        }
+        Err(e) => {
+            error!("Failed to run backend path detection for {}: {}", bash, e);
+            None
+        }
+    }
+}
+
+pub async fn execute_in_msys(bash: &str, command: &str) -> Result<String, MsysError> {
+    debug!("Executing in MSYS: {} -c \"{}\"", bash, command);
+
+    let output = tokio::process::Command::new(bash)
+        .args(["-c", command])
+        .hide_window()
+        .output()
+        .await?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        error!(
+            "MSYS command failed in {}: command='{}', stderr='{}'",
+            bash, command, stderr
+        );
+        Err(MsysError::CommandFailed { stderr })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_script_quotes_paths() {
+        assert_eq!(
+            backend_check_script(&["$HOME/.nvm/nvm.sh", "/usr/bin/fnm"]),
+            "[ -x \"$HOME/.nvm/nvm.sh\" ] && { echo \"$HOME/.nvm/nvm.sh\"; exit 0; }; \
+             [ -x \"/usr/bin/fnm\" ] && { echo \"/usr/bin/fnm\"; exit 0; }"
+        );
+    }
+}
@@ -0,0 +1,73 @@
+use std::process::Command;
+
+use crate::HideWindow;
+
+/// Shows a desktop notification through the system's own tooling:
+/// `notify-send` on Linux, `osascript` on macOS and a PowerShell toast on
+/// Windows. Runs in the background; failures are logged and otherwise
+/// ignored.
+pub fn show_notification(title: &str, body: &str) {
+    let Some(mut command) = notification_command(title, body) else {
+        return;
+    };
+    std::thread::spawn(move || {
+        if let Err(e) = command.hide_window().status() {
+            log::warn!("Failed to show notification: {}", e);
+        }
+    });
+}
+
+#[cfg(target_os = "linux")]
+fn notification_command(title: &str, body: &str) -> Option<Command> {
+    let mut command = Command::new("notify-send");
+    command.args(["--app-name=Versi", title, body]);
+    Some(command)
+}
+
+#[cfg(target_os = "macos")]
+fn notification_command(title: &str, body: &str) -> Option<Command> {
+    let script = format!(
+        "display notification {} with title {}",
+        applescript_string(body),
+        applescript_string(title)
+    );
+    let mut command = Command::new("osascript");
+    command.args(["-e", &script]);
+    Some(command)
+}
+
+#[cfg(target_os = "windows")]
+fn notification_command(title: &str, body: &str) -> Option<Command> {
+    // Toasts need a registered app id; PowerShell's own is always present.
+    const APP_ID: &str =
+        r"{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\WindowsPowerShell\v1.0\powershell.exe";
+    let script = format!(
+        "[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] > $null; \
+         $xml = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02); \
+         $text = $xml.GetElementsByTagName('text'); \
+         $text.Item(0).AppendChild($xml.CreateTextNode({})) > $null; \
+         $text.Item(1).AppendChild($xml.CreateTextNode({})) > $null; \
+         [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier('{}').Show([Windows.UI.Notifications.ToastNotification]::new($xml))",
+        powershell_string(title),
+        powershell_string(body),
+        APP_ID
+    );
+    let mut command = Command::new("powershell");
+    command.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+    Some(command)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn notification_command(_title: &str, _body: &str) -> Option<Command> {
+    None
+}
+
+#[cfg(target_os = "macos")]
+fn applescript_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(target_os = "windows")]
+fn powershell_string(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
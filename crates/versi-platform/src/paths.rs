@@ -1,13 +1,43 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{PoisonError, RwLock};
 
 pub struct AppPaths {
     pub config_dir: PathBuf,
     pub cache_dir: PathBuf,
     pub data_dir: PathBuf,
+    pub log_dir: PathBuf,
 }
 
+/// Directories chosen in settings in place of the platform defaults.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CustomDirs {
+    pub cache_dir: Option<PathBuf>,
+    pub log_dir: Option<PathBuf>,
+}
+
+static CUSTOM_DIRS: RwLock<CustomDirs> = RwLock::new(CustomDirs {
+    cache_dir: None,
+    log_dir: None,
+});
+
 impl AppPaths {
+    /// The platform directories, with any [`CustomDirs`] in place of the
+    /// defaults. A custom directory that has gone missing, such as one on an
+    /// unmounted disk, falls back to the default.
     pub fn new() -> Result<Self, String> {
+        let mut paths = Self::defaults()?;
+        let custom = CUSTOM_DIRS.read().unwrap_or_else(PoisonError::into_inner);
+        if let Some(dir) = custom.cache_dir.as_ref().filter(|dir| dir.is_dir()) {
+            paths.cache_dir = dir.clone();
+        }
+        if let Some(dir) = custom.log_dir.as_ref().filter(|dir| dir.is_dir()) {
+            paths.log_dir = dir.clone();
+        }
+        Ok(paths)
+    }
+
+    /// The platform directories, ignoring [`CustomDirs`].
+    pub fn defaults() -> Result<Self, String> {
         #[cfg(target_os = "macos")]
         {
             let home = dirs::home_dir().ok_or("Could not determine home directory")?;
@@ -15,6 +45,7 @@ impl AppPaths {
                 config_dir: home.join("Library/Application Support/versi"),
                 cache_dir: home.join("Library/Caches/versi"),
                 data_dir: home.join("Library/Application Support/versi"),
+                log_dir: home.join("Library/Application Support/versi"),
             })
         }
 
@@ -30,6 +61,9 @@ impl AppPaths {
                 data_dir: dirs::data_dir()
                     .ok_or("Could not determine data directory")?
                     .join("versi"),
+                log_dir: dirs::data_dir()
+                    .ok_or("Could not determine data directory")?
+                    .join("versi"),
             })
         }
 
@@ -45,6 +79,9 @@ impl AppPaths {
                 data_dir: dirs::data_dir()
                     .ok_or("Could not determine data directory")?
                     .join("versi"),
+                log_dir: dirs::data_dir()
+                    .ok_or("Could not determine data directory")?
+                    .join("versi"),
             })
         }
     }
@@ -57,14 +94,177 @@ impl AppPaths {
         self.cache_dir.join("versions.json")
     }
 
+    pub fn pending_operations_file(&self) -> PathBuf {
+        self.data_dir.join("pending_operations.json")
+    }
+
+    pub fn renderer_probe_file(&self) -> PathBuf {
+        self.data_dir.join("renderer_probe")
+    }
+
+    /// Shared settings as of the last sync, to tell which machine changed
+    /// them since.
+    pub fn sync_base_file(&self) -> PathBuf {
+        self.data_dir.join("sync_base.json")
+    }
+
+    /// Summary of the running app for status-bar tools.
+    pub fn status_file(&self) -> PathBuf {
+        self.data_dir.join("status.json")
+    }
+
+    /// Directory only the current user may enter, holding the lock that
+    /// keeps Versi to one instance and the control socket.
+    pub fn instance_dir(&self) -> PathBuf {
+        self.data_dir.join("instance")
+    }
+
+    /// Held by the running instance, see [`instance_dir`](Self::instance_dir).
+    pub fn instance_lock(&self) -> PathBuf {
+        self.instance_dir().join("versi.lock")
+    }
+
+    /// Unix socket of the local control server.
+    pub fn ipc_socket(&self) -> PathBuf {
+        self.instance_dir().join("versi.sock")
+    }
+
+    /// Creates [`instance_dir`](Self::instance_dir), tightening it to the
+    /// current user on Unix. Fails when the directory belongs to someone
+    /// else or is a symlink.
+    pub fn ensure_instance_dir(&self) -> std::io::Result<()> {
+        std::fs::create_dir_all(&self.data_dir)?;
+        let dir = self.instance_dir();
+        let mut builder = std::fs::DirBuilder::new();
+        #[cfg(unix)]
+        std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+        match builder.create(&dir) {
+            Err(e) if e.kind() != std::io::ErrorKind::AlreadyExists => return Err(e),
+            _ => {}
+        }
+        if !std::fs::symlink_metadata(&dir)?.is_dir() {
+            return Err(std::io::Error::other(format!(
+                "{} is not a directory",
+                dir.display()
+            )));
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o700))?;
+        }
+        Ok(())
+    }
+
     pub fn log_file(&self) -> PathBuf {
-        self.data_dir.join("debug.log")
+        self.log_dir.join("debug.log")
     }
 
     pub fn ensure_dirs(&self) -> std::io::Result<()> {
         std::fs::create_dir_all(&self.config_dir)?;
         std::fs::create_dir_all(&self.cache_dir)?;
         std::fs::create_dir_all(&self.data_dir)?;
+        std::fs::create_dir_all(&self.log_dir)?;
         Ok(())
     }
+
+    pub fn set_custom_dirs(dirs: CustomDirs) {
+        *CUSTOM_DIRS.write().unwrap_or_else(PoisonError::into_inner) = dirs;
+    }
+}
+
+/// Directory shared by every user of the machine, for backends installed
+/// system-wide: `/opt/versi`, or `versi` under `ProgramData` on Windows.
+pub fn system_wide_dir() -> PathBuf {
+    if cfg!(windows) {
+        std::env::var_os("ProgramData")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(r"C:\ProgramData"))
+            .join("versi")
+    } else {
+        PathBuf::from("/opt/versi")
+    }
+}
+
+/// Checks that `dir` can hold Versi's files, creating it when it does not
+/// exist yet.
+pub fn validate_custom_dir(dir: &Path) -> Result<(), String> {
+    if !dir.is_absolute() {
+        return Err(format!("{} is not an absolute path", dir.display()));
+    }
+    if dir.exists() && !dir.is_dir() {
+        return Err(format!("{} is not a directory", dir.display()));
+    }
+    std::fs::create_dir_all(dir).map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+
+    let probe = dir.join(".versi-write-test");
+    std::fs::write(&probe, b"").map_err(|e| format!("Cannot write to {}: {}", dir.display(), e))?;
+    let _ = std::fs::remove_file(probe);
+    Ok(())
+}
+
+/// Renames `from` to `to`, copying across file systems when renaming fails.
+/// The directory `to` is in is created first.
+pub fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    std::fs::copy(from, to)?;
+    std::fs::remove_file(from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn instance_dir_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let paths = AppPaths {
+            config_dir: dir.path().join("config"),
+            cache_dir: dir.path().join("cache"),
+            data_dir: dir.path().join("data"),
+            log_dir: dir.path().join("logs"),
+        };
+        std::fs::create_dir_all(paths.instance_dir()).unwrap();
+        std::fs::set_permissions(paths.instance_dir(), std::fs::Permissions::from_mode(0o755))
+            .unwrap();
+
+        paths.ensure_instance_dir().unwrap();
+        let mode = std::fs::metadata(paths.instance_dir())
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o700);
+        assert!(paths.ipc_socket().starts_with(paths.instance_dir()));
+    }
+
+    #[test]
+    fn moves_files_into_new_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let from = dir.path().join("versions.json");
+        let to = dir.path().join("moved/cache/versions.json");
+        std::fs::write(&from, "{}").unwrap();
+
+        move_file(&from, &to).unwrap();
+        assert!(!from.exists());
+        assert_eq!(std::fs::read_to_string(&to).unwrap(), "{}");
+    }
+
+    #[test]
+    fn rejects_relative_and_file_paths() {
+        assert!(validate_custom_dir(Path::new("relative/cache")).is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("file");
+        std::fs::write(&file, "").unwrap();
+        assert!(validate_custom_dir(&file).is_err());
+        assert!(validate_custom_dir(&dir.path().join("new")).is_ok());
+    }
 }
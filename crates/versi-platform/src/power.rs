@@ -0,0 +1,187 @@
+use std::time::Duration;
+
+/// Time since the last keyboard or mouse input, or `None` when the platform
+/// cannot tell (for example a Wayland session without XWayland).
+pub fn system_idle_time() -> Option<Duration> {
+    #[cfg(target_os = "macos")]
+    {
+        macos::idle_time()
+    }
+    #[cfg(target_os = "windows")]
+    {
+        win32::idle_time()
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::idle_time()
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    {
+        None
+    }
+}
+
+/// Whether the machine runs on mains power. `None` when it has no battery or
+/// the state is unknown, which callers can treat like a desktop on AC.
+pub fn on_ac_power() -> Option<bool> {
+    #[cfg(target_os = "macos")]
+    {
+        macos::on_ac_power()
+    }
+    #[cfg(target_os = "windows")]
+    {
+        win32::on_ac_power()
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::on_ac_power()
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    {
+        None
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use std::process::Command;
+    use std::time::Duration;
+
+    pub(super) fn idle_time() -> Option<Duration> {
+        let output = Command::new("ioreg")
+            .args(["-c", "IOHIDSystem", "-d", "4"])
+            .output()
+            .ok()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        // `"HIDIdleTime" = 123456789` in nanoseconds.
+        let nanos = stdout
+            .lines()
+            .find_map(|line| line.split_once("\"HIDIdleTime\" = "))
+            .and_then(|(_, value)| value.trim().parse::<u64>().ok())?;
+        Some(Duration::from_nanos(nanos))
+    }
+
+    pub(super) fn on_ac_power() -> Option<bool> {
+        let output = Command::new("pmset").args(["-g", "batt"]).output().ok()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let first = stdout.lines().next()?;
+        if first.contains("'AC Power'") {
+            Some(true)
+        } else if first.contains("'Battery Power'") {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod win32 {
+    use std::time::Duration;
+
+    use windows_sys::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+    use windows_sys::Win32::System::SystemInformation::GetTickCount;
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+    pub(super) fn idle_time() -> Option<Duration> {
+        let mut info = LASTINPUTINFO {
+            cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+            dwTime: 0,
+        };
+        if unsafe { GetLastInputInfo(&mut info) } == 0 {
+            return None;
+        }
+        // Both are tick counts that wrap every ~49 days.
+        let now = unsafe { GetTickCount() };
+        Some(Duration::from_millis(u64::from(
+            now.wrapping_sub(info.dwTime),
+        )))
+    }
+
+    pub(super) fn on_ac_power() -> Option<bool> {
+        let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+        if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+            return None;
+        }
+        match status.ACLineStatus {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::path::Path;
+    use std::time::Duration;
+
+    use x11rb::connection::Connection;
+    use x11rb::protocol::screensaver::ConnectionExt;
+
+    const POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+
+    pub(super) fn idle_time() -> Option<Duration> {
+        std::env::var_os("DISPLAY")?;
+        let (conn, screen) = x11rb::connect(None).ok()?;
+        let root = conn.setup().roots.get(screen)?.root;
+        let info = conn.screensaver_query_info(root).ok()?.reply().ok()?;
+        Some(Duration::from_millis(u64::from(info.ms_since_user_input)))
+    }
+
+    pub(super) fn on_ac_power() -> Option<bool> {
+        on_ac_power_in(Path::new(POWER_SUPPLY_DIR))
+    }
+
+    pub(super) fn on_ac_power_in(dir: &Path) -> Option<bool> {
+        let read = |path: &Path, name: &str| {
+            std::fs::read_to_string(path.join(name))
+                .map(|value| value.trim().to_string())
+                .unwrap_or_default()
+        };
+
+        let mut has_mains = false;
+        let mut has_battery = false;
+        for entry in std::fs::read_dir(dir).ok()?.flatten() {
+            let path = entry.path();
+            match read(&path, "type").as_str() {
+                "Mains" | "USB" => {
+                    has_mains = true;
+                    if read(&path, "online") == "1" {
+                        return Some(true);
+                    }
+                }
+                "Battery" => has_battery = true,
+                _ => {}
+            }
+        }
+        (has_mains || has_battery).then_some(false)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn supply(dir: &Path, name: &str, kind: &str, online: Option<&str>) {
+            let path = dir.join(name);
+            std::fs::create_dir_all(&path).unwrap();
+            std::fs::write(path.join("type"), format!("{kind}\n")).unwrap();
+            if let Some(online) = online {
+                std::fs::write(path.join("online"), format!("{online}\n")).unwrap();
+            }
+        }
+
+        #[test]
+        fn reads_mains_state_from_sysfs() {
+            let dir = tempfile::tempdir().unwrap();
+            assert_eq!(on_ac_power_in(dir.path()), None);
+
+            supply(dir.path(), "BAT0", "Battery", None);
+            supply(dir.path(), "AC", "Mains", Some("0"));
+            assert_eq!(on_ac_power_in(dir.path()), Some(false));
+
+            supply(dir.path(), "AC", "Mains", Some("1"));
+            assert_eq!(on_ac_power_in(dir.path()), Some(true));
+        }
+    }
+}
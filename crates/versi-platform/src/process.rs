@@ -0,0 +1,373 @@
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::process::{Output, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::{Notify, mpsc};
+
+static REGISTRY: LazyLock<Mutex<HashMap<u64, Arc<Notify>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Transcripts kept for bug reports; older ones are dropped first.
+const MAX_TRANSCRIPTS: usize = 100;
+/// Bytes of stdout and stderr kept per transcript, from the end.
+const MAX_CAPTURED_BYTES: usize = 32 * 1024;
+
+static TRANSCRIPTS: LazyLock<Mutex<VecDeque<CommandTranscript>>> =
+    LazyLock::new(|| Mutex::new(VecDeque::new()));
+
+tokio::task_local! {
+    static OPERATION: String;
+}
+
+/// A command run through [`run_tracked`] and what it printed.
+#[derive(Debug, Clone)]
+pub struct CommandTranscript {
+    /// The operation the command ran for, see [`with_operation`].
+    pub operation: Option<String>,
+    pub command: String,
+    pub started_at: SystemTime,
+    pub duration: Duration,
+    /// Exit status, or the error that kept the command from finishing.
+    pub outcome: String,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Runs `future` with `operation` attached to the transcripts of every
+/// command it runs, so they can be looked up with [`operation_transcripts`].
+pub async fn with_operation<F: Future>(operation: String, future: F) -> F::Output {
+    OPERATION.scope(operation, future).await
+}
+
+/// Transcripts of commands run for `operation`, oldest first.
+pub fn operation_transcripts(operation: &str) -> Vec<CommandTranscript> {
+    TRANSCRIPTS
+        .lock()
+        .map(|transcripts| {
+            transcripts
+                .iter()
+                .filter(|t| t.operation.as_deref() == Some(operation))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn record_transcript(
+    cmd: &Command,
+    started_at: SystemTime,
+    duration: Duration,
+    result: &std::io::Result<Output>,
+) {
+    let std_cmd = cmd.as_std();
+    let command = std::iter::once(std_cmd.get_program())
+        .chain(std_cmd.get_args())
+        .map(|part| part.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let (outcome, stdout, stderr) = match result {
+        Ok(output) => (
+            output.status.to_string(),
+            captured(&output.stdout),
+            captured(&output.stderr),
+        ),
+        Err(e) => (e.to_string(), String::new(), String::new()),
+    };
+
+    let transcript = CommandTranscript {
+        operation: OPERATION.try_with(Clone::clone).ok(),
+        command,
+        started_at,
+        duration,
+        outcome,
+        stdout,
+        stderr,
+    };
+    if let Ok(mut transcripts) = TRANSCRIPTS.lock() {
+        if transcripts.len() == MAX_TRANSCRIPTS {
+            transcripts.pop_front();
+        }
+        transcripts.push_back(transcript);
+    }
+}
+
+fn captured(bytes: &[u8]) -> String {
+    let start = bytes.len().saturating_sub(MAX_CAPTURED_BYTES);
+    String::from_utf8_lossy(&bytes[start..]).into_owned()
+}
+
+struct Registration(u64);
+
+impl Registration {
+    fn new(cancel: Arc<Notify>) -> Self {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut registry) = REGISTRY.lock() {
+            registry.insert(id, cancel);
+        }
+        Self(id)
+    }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        if let Ok(mut registry) = REGISTRY.lock() {
+            registry.remove(&self.0);
+        }
+    }
+}
+
+/// Runs a command like [`Command::output`], but registers the child so it can
+/// be terminated with [`terminate_tracked_processes`].
+pub async fn run_tracked(cmd: &mut Command) -> std::io::Result<Output> {
+    let started_at = SystemTime::now();
+    let start = Instant::now();
+    let result = run_tracked_inner(cmd, None).await;
+    record_transcript(cmd, started_at, start.elapsed(), &result);
+    result
+}
+
+/// Runs a command like [`run_tracked`], sending each line it prints to
+/// `progress` as it appears.
+pub async fn run_streamed(
+    cmd: &mut Command,
+    progress: &mpsc::Sender<String>,
+) -> std::io::Result<Output> {
+    let started_at = SystemTime::now();
+    let start = Instant::now();
+    let result = run_tracked_inner(cmd, Some((progress, b"\n"))).await;
+    record_transcript(cmd, started_at, start.elapsed(), &result);
+    result
+}
+
+/// Like [`run_streamed`], but also sends every redraw of a progress bar,
+/// which ends on a carriage return rather than a newline.
+pub async fn run_streamed_redraws(
+    cmd: &mut Command,
+    progress: &mpsc::Sender<String>,
+) -> std::io::Result<Output> {
+    let started_at = SystemTime::now();
+    let start = Instant::now();
+    let result = run_tracked_inner(cmd, Some((progress, b"\n\r"))).await;
+    record_transcript(cmd, started_at, start.elapsed(), &result);
+    result
+}
+
+/// Output is sent to the progress channel a line at a time, a line ending
+/// on any of the given bytes.
+type LineSink<'a> = (&'a mpsc::Sender<String>, &'static [u8]);
+
+async fn run_tracked_inner(
+    cmd: &mut Command,
+    progress: Option<LineSink<'_>>,
+) -> std::io::Result<Output> {
+    cmd.stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    let mut child = cmd.spawn()?;
+    let cancel = Arc::new(Notify::new());
+    let _registration = Registration::new(cancel.clone());
+
+    let stdout_pipe = child.stdout.take();
+    let stderr_pipe = child.stderr.take();
+
+    let result = {
+        let read_stdout = read_pipe(stdout_pipe, progress);
+        let read_stderr = read_pipe(stderr_pipe, progress);
+        let wait = async { tokio::try_join!(child.wait(), read_stdout, read_stderr) };
+
+        tokio::select! {
+            result = wait => Some(result),
+            _ = cancel.notified() => None,
+        }
+    };
+
+    match result {
+        Some(result) => {
+            let (status, stdout, stderr) = result?;
+            Ok(Output {
+                status,
+                stdout,
+                stderr,
+            })
+        }
+        None => {
+            log::info!("Terminating tracked child process {:?}", child.id());
+            child.kill().await?;
+            Err(std::io::Error::new(
+                std::io::ErrorKind::Interrupted,
+                "Operation cancelled",
+            ))
+        }
+    }
+}
+
+async fn read_pipe<R: AsyncRead + Unpin>(
+    pipe: Option<R>,
+    progress: Option<LineSink<'_>>,
+) -> std::io::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    let Some(mut pipe) = pipe else {
+        return Ok(buf);
+    };
+    let Some((progress, line_ends)) = progress else {
+        pipe.read_to_end(&mut buf).await?;
+        return Ok(buf);
+    };
+
+    let mut reader = BufReader::new(pipe);
+    let mut line_start = 0;
+    loop {
+        let read = reader.fill_buf().await?;
+        if read.is_empty() {
+            if let Some(line) = progress_line(&String::from_utf8_lossy(&buf[line_start..])) {
+                let _ = progress.send(line.to_string()).await;
+            }
+            return Ok(buf);
+        }
+        let len = read.len();
+        buf.extend_from_slice(read);
+        reader.consume(len);
+
+        while let Some(end) = buf[line_start..].iter().position(|b| line_ends.contains(b)) {
+            let end = line_start + end + 1;
+            if let Some(line) = progress_line(&String::from_utf8_lossy(&buf[line_start..end])) {
+                let _ = progress.send(line.to_string()).await;
+            }
+            line_start = end;
+        }
+    }
+}
+
+/// The text a line of output ends on. Progress bars redraw themselves with
+/// carriage returns, so only the last redraw is kept, and spinner frames are
+/// dropped.
+fn progress_line(line: &str) -> Option<&str> {
+    let line = line.rsplit('\r').map(str::trim).find(|s| !s.is_empty())?;
+    (!line.chars().all(|c| matches!(c, '-' | '\\' | '|' | '/'))).then_some(line)
+}
+
+pub fn tracked_process_count() -> usize {
+    REGISTRY.lock().map(|r| r.len()).unwrap_or(0)
+}
+
+pub fn terminate_tracked_processes() {
+    if let Ok(registry) = REGISTRY.lock() {
+        for cancel in registry.values() {
+            cancel.notify_one();
+        }
+    }
+}
+
+/// Waits until every tracked child has exited, or the timeout elapses.
+pub async fn wait_for_tracked_processes(timeout: Duration) {
+    let deadline = tokio::time::Instant::now() + timeout;
+    while tracked_process_count() > 0 && tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn run_tracked_captures_output() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "echo out; echo err >&2"]);
+        let output = run_tracked(&mut cmd).await.unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "out\n");
+        assert_eq!(String::from_utf8_lossy(&output.stderr), "err\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn run_streamed_sends_each_line() {
+        let (tx, mut rx) = mpsc::channel(8);
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "echo one; printf '10%%\\r100%%\\n'; echo two >&2"]);
+        let output = run_streamed(&mut cmd, &tx).await.unwrap();
+        drop(tx);
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stderr), "two\n");
+
+        let mut lines = Vec::new();
+        while let Some(line) = rx.recv().await {
+            lines.push(line);
+        }
+        lines.sort();
+        assert_eq!(lines, ["100%", "one", "two"]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn run_streamed_redraws_sends_each_redraw() {
+        let (tx, mut rx) = mpsc::channel(8);
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "printf '10%%\\r100%%\\r\\n'"]);
+        run_streamed_redraws(&mut cmd, &tx).await.unwrap();
+        drop(tx);
+
+        let mut lines = Vec::new();
+        while let Some(line) = rx.recv().await {
+            lines.push(line);
+        }
+        assert_eq!(lines, ["10%", "100%"]);
+    }
+
+    #[test]
+    fn progress_line_keeps_the_last_redraw() {
+        assert_eq!(
+            progress_line("  1.0 MB / 4.0 MB\r  4.0 MB / 4.0 MB\r\n"),
+            Some("4.0 MB / 4.0 MB")
+        );
+        assert_eq!(progress_line("   \\ \n"), None);
+        assert_eq!(progress_line(""), None);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn records_transcripts_for_operation() {
+        let result = with_operation("test transcript".to_string(), async {
+            let mut cmd = Command::new("sh");
+            cmd.args(["-c", "echo installing; exit 3"]);
+            run_tracked(&mut cmd).await
+        })
+        .await;
+        assert!(!result.unwrap().status.success());
+
+        let transcripts = operation_transcripts("test transcript");
+        assert_eq!(transcripts.len(), 1);
+        assert_eq!(transcripts[0].command, "sh -c echo installing; exit 3");
+        assert_eq!(transcripts[0].stdout, "installing\n");
+        assert!(transcripts[0].outcome.contains('3'));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn terminate_cancels_running_process() {
+        let handle = tokio::spawn(async {
+            let mut cmd = Command::new("sleep");
+            cmd.arg("30");
+            run_tracked(&mut cmd).await
+        });
+
+        while tracked_process_count() == 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        terminate_tracked_processes();
+
+        let result = handle.await.unwrap();
+        assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::Interrupted);
+        wait_for_tracked_processes(Duration::from_secs(1)).await;
+        assert_eq!(tracked_process_count(), 0);
+    }
+}
@@ -0,0 +1,176 @@
+/// Mirror hosting community builds for platforms nodejs.org does not ship
+/// binaries for (musl, riscv64, loong64, ...).
+pub const UNOFFICIAL_BUILDS_MIRROR: &str = "https://unofficial-builds.nodejs.org/download/release";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TargetOs {
+    Linux,
+    MacOs,
+    Windows,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Libc {
+    Glibc,
+    Musl,
+}
+
+/// The operating system, CPU architecture, and C library of an environment,
+/// used to pick the Node.js binary that can run there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PlatformTarget {
+    pub os: TargetOs,
+    pub arch: &'static str,
+    pub libc: Option<Libc>,
+}
+
+/// Shell snippet whose output can be fed to [`PlatformTarget::parse_probe`].
+pub const PROBE_COMMAND: &str =
+    "uname -m; if ls /lib/ld-musl-* >/dev/null 2>&1; then echo musl; else echo glibc; fi";
+
+impl PlatformTarget {
+    /// Describes the platform this binary is running on.
+    pub fn native() -> Self {
+        let os = if cfg!(target_os = "windows") {
+            TargetOs::Windows
+        } else if cfg!(target_os = "macos") {
+            TargetOs::MacOs
+        } else {
+            TargetOs::Linux
+        };
+        let libc = (os == TargetOs::Linux).then(detect_libc);
+        Self {
+            os,
+            arch: node_arch(std::env::consts::ARCH),
+            libc,
+        }
+    }
+
+    /// Parses the output of [`PROBE_COMMAND`] run inside a Linux environment.
+    pub fn parse_probe(output: &str) -> Option<Self> {
+        let mut lines = output.lines().map(str::trim).filter(|l| !l.is_empty());
+        let arch = node_arch(lines.next()?);
+        let libc = match lines.next() {
+            Some("musl") => Libc::Musl,
+            _ => Libc::Glibc,
+        };
+        Some(Self {
+            os: TargetOs::Linux,
+            arch,
+            libc: Some(libc),
+        })
+    }
+
+    /// The entry in the `files` list of a dist `index.json` that holds a
+    /// binary for this target, e.g. `linux-x64-musl` or `osx-arm64-tar`.
+    pub fn dist_file_key(&self) -> String {
+        match self.os {
+            TargetOs::Linux => match self.libc {
+                Some(Libc::Musl) => format!("linux-{}-musl", self.arch),
+                _ => format!("linux-{}", self.arch),
+            },
+            TargetOs::MacOs => format!("osx-{}-tar", self.arch),
+            TargetOs::Windows => format!("win-{}-zip", self.arch),
+        }
+    }
+
+    /// Whether nodejs.org publishes no binaries for this target at all, so
+    /// installs must go through [`UNOFFICIAL_BUILDS_MIRROR`].
+    pub fn requires_unofficial_builds(&self) -> bool {
+        match self.os {
+            TargetOs::Linux => {
+                self.libc == Some(Libc::Musl)
+                    || !matches!(self.arch, "x64" | "arm64" | "armv7l" | "ppc64le" | "s390x")
+            }
+            TargetOs::MacOs => !matches!(self.arch, "x64" | "arm64"),
+            TargetOs::Windows => !matches!(self.arch, "x64" | "x86" | "arm64"),
+        }
+    }
+
+    /// Whether unofficial-builds.nodejs.org can have a binary for this
+    /// target. It publishes Linux and Windows builds only.
+    pub fn has_unofficial_builds(&self) -> bool {
+        self.os != TargetOs::MacOs
+    }
+
+    pub fn label(&self) -> String {
+        let os = match self.os {
+            TargetOs::Linux => "Linux",
+            TargetOs::MacOs => "macOS",
+            TargetOs::Windows => "Windows",
+        };
+        match self.libc {
+            Some(Libc::Musl) => format!("{os} {} (musl)", self.arch),
+            _ => format!("{os} {}", self.arch),
+        }
+    }
+}
+
+fn node_arch(machine: &str) -> &'static str {
+    match machine.trim() {
+        "x86_64" | "amd64" | "x64" => "x64",
+        "aarch64" | "arm64" => "arm64",
+        "armv7l" | "armv7" | "arm" => "armv7l",
+        "armv6l" => "armv6l",
+        "i386" | "i686" | "x86" => "x86",
+        "ppc64le" | "powerpc64le" | "powerpc64" => "ppc64le",
+        "s390x" => "s390x",
+        "riscv64" | "riscv64gc" => "riscv64",
+        "loongarch64" | "loong64" => "loong64",
+        _ => "unknown",
+    }
+}
+
+fn detect_libc() -> Libc {
+    let musl_loader = std::fs::read_dir("/lib").ok().is_some_and(|entries| {
+        entries
+            .flatten()
+            .any(|e| e.file_name().to_string_lossy().starts_with("ld-musl-"))
+    });
+    if musl_loader { Libc::Musl } else { Libc::Glibc }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_wsl_probe_output() {
+        let target = PlatformTarget::parse_probe("x86_64\nmusl\n").unwrap();
+        assert_eq!(target.arch, "x64");
+        assert_eq!(target.libc, Some(Libc::Musl));
+        assert_eq!(target.dist_file_key(), "linux-x64-musl");
+        assert!(target.requires_unofficial_builds());
+
+        let target = PlatformTarget::parse_probe("aarch64\nglibc").unwrap();
+        assert_eq!(target.dist_file_key(), "linux-arm64");
+        assert!(!target.requires_unofficial_builds());
+
+        assert!(PlatformTarget::parse_probe("").is_none());
+    }
+
+    #[test]
+    fn dist_file_keys_match_index_format() {
+        let mac = PlatformTarget {
+            os: TargetOs::MacOs,
+            arch: "arm64",
+            libc: None,
+        };
+        assert_eq!(mac.dist_file_key(), "osx-arm64-tar");
+        assert!(!mac.has_unofficial_builds());
+
+        let win = PlatformTarget {
+            os: TargetOs::Windows,
+            arch: "x64",
+            libc: None,
+        };
+        assert_eq!(win.dist_file_key(), "win-x64-zip");
+    }
+
+    #[test]
+    fn riscv_needs_unofficial_builds() {
+        let target = PlatformTarget::parse_probe("riscv64\nglibc").unwrap();
+        assert_eq!(target.dist_file_key(), "linux-riscv64");
+        assert!(target.requires_unofficial_builds());
+    }
+}
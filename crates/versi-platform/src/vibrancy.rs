@@ -0,0 +1,171 @@
+/// Turns the native blur-behind effect of a transparent window on or off,
+/// given its native id. Returns whether the effect is now applied.
+///
+/// Uses `NSVisualEffectView` on macOS, the DWM system backdrop on Windows 11
+/// and the KWin blur hint on X11. Returns `false` when the platform or
+/// compositor has no support, so callers can keep the window opaque.
+pub fn set_window_vibrancy(raw_window_id: u64, enabled: bool) -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        macos::set_window_vibrancy(raw_window_id, enabled)
+    }
+    #[cfg(target_os = "windows")]
+    {
+        win32::set_window_vibrancy(raw_window_id, enabled)
+    }
+    #[cfg(target_os = "linux")]
+    {
+        x11::set_window_vibrancy(raw_window_id, enabled)
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    {
+        let _ = (raw_window_id, enabled);
+        false
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use std::cell::RefCell;
+
+    use objc2::MainThreadMarker;
+    use objc2::rc::Retained;
+    use objc2_app_kit::{
+        NSAutoresizingMaskOptions, NSVisualEffectBlendingMode, NSVisualEffectMaterial,
+        NSVisualEffectState, NSVisualEffectView, NSWindow, NSWindowOrderingMode,
+    };
+
+    thread_local! {
+        static EFFECT_VIEW: RefCell<Option<Retained<NSVisualEffectView>>> =
+            const { RefCell::new(None) };
+    }
+
+    pub(super) fn set_window_vibrancy(raw_window_id: u64, enabled: bool) -> bool {
+        let Some(mtm) = MainThreadMarker::new() else {
+            return false;
+        };
+        EFFECT_VIEW.with_borrow_mut(|effect| {
+            if let Some(view) = effect.take() {
+                view.removeFromSuperview();
+            }
+            if !enabled || raw_window_id == 0 {
+                return false;
+            }
+
+            // SAFETY: on macOS the raw window id is the address of the NSWindow.
+            let window = unsafe { &*(raw_window_id as usize as *const NSWindow) };
+            let Some(content) = window.contentView() else {
+                return false;
+            };
+            // The content view draws the app itself, so the effect view goes
+            // behind it in the window frame view.
+            let Some(frame_view) = content.superview() else {
+                return false;
+            };
+
+            let view =
+                NSVisualEffectView::initWithFrame(NSVisualEffectView::alloc(mtm), content.frame());
+            view.setMaterial(NSVisualEffectMaterial::UnderWindowBackground);
+            view.setBlendingMode(NSVisualEffectBlendingMode::BehindWindow);
+            view.setState(NSVisualEffectState::FollowsWindowActiveState);
+            view.setAutoresizingMask(
+                NSAutoresizingMaskOptions::ViewWidthSizable
+                    | NSAutoresizingMaskOptions::ViewHeightSizable,
+            );
+            frame_view.addSubview_positioned_relativeTo(
+                &view,
+                NSWindowOrderingMode::Below,
+                Some(&*content),
+            );
+            *effect = Some(view);
+            true
+        })
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod win32 {
+    use windows_sys::Win32::Foundation::HWND;
+    use windows_sys::Win32::Graphics::Dwm::{
+        DWM_SYSTEMBACKDROP_TYPE, DWMSBT_NONE, DWMSBT_TRANSIENTWINDOW, DWMWA_SYSTEMBACKDROP_TYPE,
+        DwmExtendFrameIntoClientArea, DwmSetWindowAttribute,
+    };
+    use windows_sys::Win32::UI::Controls::MARGINS;
+
+    pub(super) fn set_window_vibrancy(raw_window_id: u64, enabled: bool) -> bool {
+        let hwnd = raw_window_id as usize as HWND;
+        // Negative margins extend the frame over the whole client area so the
+        // backdrop shows through transparent pixels.
+        let inset = if enabled { -1 } else { 0 };
+        let margins = MARGINS {
+            cxLeftWidth: inset,
+            cxRightWidth: inset,
+            cyTopHeight: inset,
+            cyBottomHeight: inset,
+        };
+        let backdrop: DWM_SYSTEMBACKDROP_TYPE = if enabled {
+            DWMSBT_TRANSIENTWINDOW
+        } else {
+            DWMSBT_NONE
+        };
+
+        // Both calls fail before Windows 11 22H2, which has no system backdrop.
+        let applied = unsafe {
+            DwmSetWindowAttribute(
+                hwnd,
+                DWMWA_SYSTEMBACKDROP_TYPE,
+                &backdrop as *const DWM_SYSTEMBACKDROP_TYPE as *const _,
+                std::mem::size_of::<DWM_SYSTEMBACKDROP_TYPE>() as u32,
+            ) == 0
+                && DwmExtendFrameIntoClientArea(hwnd, &margins) == 0
+        };
+        enabled && applied
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod x11 {
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::{AtomEnum, ConnectionExt, PropMode, Window};
+    use x11rb::wrapper::ConnectionExt as _;
+
+    const BLUR_ATOM: &[u8] = b"_KDE_NET_WM_BLUR_BEHIND_REGION";
+
+    pub(super) fn set_window_vibrancy(raw_window_id: u64, enabled: bool) -> bool {
+        let Ok(window) = Window::try_from(raw_window_id) else {
+            return false;
+        };
+        std::env::var_os("DISPLAY").is_some() && apply(window, enabled).unwrap_or(false) && enabled
+    }
+
+    fn apply(window: Window, enabled: bool) -> Option<bool> {
+        let (conn, screen) = x11rb::connect(None).ok()?;
+        let root = conn.setup().roots.get(screen)?.root;
+        let atom = conn.intern_atom(false, BLUR_ATOM).ok()?.reply().ok()?.atom;
+
+        if !enabled {
+            conn.delete_property(window, atom).ok()?;
+            return Some(conn.flush().is_ok());
+        }
+
+        // KWin's blur effect announces itself with a property on the root
+        // window; without it the hint is ignored and the window would be
+        // see-through instead of blurred.
+        let announced = conn
+            .get_property(false, root, atom, AtomEnum::ANY, 0, 0)
+            .ok()?
+            .reply()
+            .ok()?
+            .type_
+            != u32::from(AtomEnum::NONE);
+        if !announced {
+            return Some(false);
+        }
+
+        // An empty region blurs the whole window.
+        let region: [u32; 0] = [];
+        conn.change_property32(PropMode::REPLACE, window, atom, AtomEnum::CARDINAL, &region)
+            .ok()?;
+        Some(conn.flush().is_ok())
+    }
+}
@@ -0,0 +1,118 @@
+/// Whether windows can be moved between virtual desktops here.
+///
+/// Only X11 desktops support this, through `_NET_WM_DESKTOP`. Wayland and
+/// macOS Spaces have no public API to read or set a window's desktop, so
+/// remembering the workspace is unsupported there.
+pub fn workspaces_supported() -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        x11::connection().is_some()
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        false
+    }
+}
+
+/// Returns the virtual desktop the window is on, given its native id, or
+/// `None` where [`workspaces_supported`] is false.
+pub fn window_workspace(raw_window_id: u64) -> Option<u32> {
+    #[cfg(target_os = "linux")]
+    {
+        x11::window_workspace(raw_window_id)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = raw_window_id;
+        None
+    }
+}
+
+/// Asks the window manager to move the window to the given virtual desktop.
+/// Returns whether the request could be sent.
+pub fn move_window_to_workspace(raw_window_id: u64, workspace: u32) -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        x11::move_window_to_workspace(raw_window_id, workspace)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (raw_window_id, workspace);
+        false
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod x11 {
+    use std::sync::OnceLock;
+
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::{
+        Atom, AtomEnum, ClientMessageEvent, ConnectionExt, EventMask, Window,
+    };
+    use x11rb::rust_connection::RustConnection;
+
+    /// `_NET_WM_DESKTOP` value meaning "visible on all desktops".
+    const ALL_DESKTOPS: u32 = 0xFFFF_FFFF;
+
+    pub(super) struct X11 {
+        conn: RustConnection,
+        root: Window,
+        atom: Atom,
+    }
+
+    /// The connection shared by every lookup, opened on first use since the
+    /// workspace is read each time the window geometry is saved.
+    pub(super) fn connection() -> Option<&'static X11> {
+        static CONNECTION: OnceLock<Option<X11>> = OnceLock::new();
+        CONNECTION.get_or_init(connect).as_ref()
+    }
+
+    fn connect() -> Option<X11> {
+        std::env::var_os("DISPLAY")?;
+        let (conn, screen) = x11rb::connect(None).ok()?;
+        let root = conn.setup().roots.get(screen)?.root;
+        let atom = conn
+            .intern_atom(false, b"_NET_WM_DESKTOP")
+            .ok()?
+            .reply()
+            .ok()?
+            .atom;
+        Some(X11 { conn, root, atom })
+    }
+
+    pub(super) fn window_workspace(raw_window_id: u64) -> Option<u32> {
+        let window = Window::try_from(raw_window_id).ok()?;
+        let x11 = connection()?;
+        let reply = x11
+            .conn
+            .get_property(false, window, x11.atom, AtomEnum::CARDINAL, 0, 1)
+            .ok()?
+            .reply()
+            .ok()?;
+        reply
+            .value32()?
+            .next()
+            .filter(|desktop| *desktop != ALL_DESKTOPS)
+    }
+
+    pub(super) fn move_window_to_workspace(raw_window_id: u64, workspace: u32) -> bool {
+        let Ok(window) = Window::try_from(raw_window_id) else {
+            return false;
+        };
+        let Some(x11) = connection() else {
+            return false;
+        };
+        // EWMH: source indication 1 = normal application.
+        let event = ClientMessageEvent::new(32, window, x11.atom, [workspace, 1, 0, 0, 0]);
+        x11.conn
+            .send_event(
+                false,
+                x11.root,
+                EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY,
+                event,
+            )
+            .is_ok()
+            && x11.conn.flush().is_ok()
+    }
+}
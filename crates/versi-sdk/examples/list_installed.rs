@@ -0,0 +1,28 @@
+//! Lists the Node.js versions installed with fnm and marks the default.
+//!
+//! Run with `cargo run -p versi-sdk --example list_installed`.
+
+use versi_sdk::{Backend, ManagerBuilder};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let manager = ManagerBuilder::new(Backend::Fnm).build().await?;
+    let info = manager.backend_info();
+    println!(
+        "{} {} at {}",
+        info.name,
+        info.version.as_deref().unwrap_or("(unknown version)"),
+        info.path.display()
+    );
+
+    let default = manager.default_version().await?;
+    for installed in manager.list_installed().await? {
+        let marker = if Some(&installed.version) == default.as_ref() {
+            " (default)"
+        } else {
+            ""
+        };
+        println!("  {}{}", installed.version, marker);
+    }
+    Ok(())
+}
@@ -0,0 +1,36 @@
+//! Lists the Node.js versions installed with fnm in every running WSL distro.
+//!
+//! Run with `cargo run -p versi-sdk --example wsl` on Windows.
+
+#[cfg(target_os = "windows")]
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    use versi_sdk::{Backend, ManagerBuilder, detect_wsl_distros};
+
+    let builder = ManagerBuilder::new(Backend::Fnm);
+    let search_paths = builder.provider().wsl_search_paths();
+    for distro in detect_wsl_distros(&search_paths) {
+        let Some(backend_path) = distro.backend_path else {
+            println!("{}: fnm not found", distro.name);
+            continue;
+        };
+        let manager = builder
+            .clone()
+            .wsl(distro.name.as_str(), backend_path)
+            .build()
+            .await?;
+        let versions: Vec<String> = manager
+            .list_installed()
+            .await?
+            .into_iter()
+            .map(|installed| installed.version.to_string())
+            .collect();
+        println!("{}: {}", distro.name, versions.join(", "));
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn main() {
+    println!("WSL is only available on Windows");
+}
@@ -0,0 +1,111 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use versi_backend::{BackendError, BackendProvider, VersionManager};
+
+/// Version managers the SDK can drive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    #[cfg(feature = "fnm")]
+    Fnm,
+    #[cfg(feature = "nvm")]
+    Nvm,
+}
+
+/// Configures and creates a [`VersionManager`].
+#[derive(Debug, Clone)]
+pub struct ManagerBuilder {
+    backend: Backend,
+    fnm_binary: Option<PathBuf>,
+    nvm_dir: Option<PathBuf>,
+    wsl: Option<(String, String)>,
+    env: Vec<(String, String)>,
+}
+
+impl ManagerBuilder {
+    pub fn new(backend: Backend) -> Self {
+        Self {
+            backend,
+            fnm_binary: None,
+            nvm_dir: None,
+            wsl: None,
+            env: Vec::new(),
+        }
+    }
+
+    /// Uses this fnm binary instead of searching for one. Ignored for nvm.
+    pub fn fnm_binary(mut self, path: impl Into<PathBuf>) -> Self {
+        self.fnm_binary = Some(path.into());
+        self
+    }
+
+    /// Uses this `NVM_DIR` instead of searching for one. Ignored for fnm.
+    pub fn nvm_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.nvm_dir = Some(dir.into());
+        self
+    }
+
+    /// Runs the backend inside a WSL distro, where it lives at
+    /// `backend_path`. See `detect_wsl_distros` to find both on Windows.
+    pub fn wsl(mut self, distro: impl Into<String>, backend_path: impl Into<String>) -> Self {
+        self.wsl = Some((distro.into(), backend_path.into()));
+        self
+    }
+
+    /// Sets an environment variable for every backend command.
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+
+    /// The provider behind this builder, for detection details, installing
+    /// the backend itself or checking it for updates.
+    pub fn provider(&self) -> Arc<dyn BackendProvider> {
+        match self.backend {
+            #[cfg(feature = "fnm")]
+            Backend::Fnm => Arc::new(versi_fnm::FnmProvider::with_binary(self.fnm_binary.clone())),
+            #[cfg(feature = "nvm")]
+            Backend::Nvm => Arc::new(versi_nvm::NvmProvider::with_nvm_dir(self.nvm_dir.clone())),
+        }
+    }
+
+    /// Detects the backend and creates its manager. Returns
+    /// [`BackendError::NotFound`] when it is not installed natively; WSL
+    /// managers are created without detection.
+    pub async fn build(self) -> Result<Box<dyn VersionManager>, BackendError> {
+        let provider = self.provider();
+        let mut manager = match self.wsl {
+            Some((distro, backend_path)) => provider.create_manager_for_wsl(distro, backend_path),
+            None => {
+                let detection = provider.detect().await;
+                if !detection.found {
+                    return Err(BackendError::NotFound);
+                }
+                provider.create_manager(&detection)
+            }
+        };
+        manager.set_env_overrides(self.env);
+        Ok(manager)
+    }
+}
+
+#[cfg(all(test, feature = "fnm"))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn wsl_manager_is_created_without_detection() {
+        let manager = ManagerBuilder::new(Backend::Fnm)
+            .wsl("Ubuntu", "/home/dev/.cargo/bin/fnm")
+            .env("FNM_NODE_DIST_MIRROR", "https://example.com/dist")
+            .build()
+            .await
+            .unwrap();
+
+        assert_eq!(manager.name(), "fnm");
+        assert_eq!(
+            manager.backend_info().path,
+            PathBuf::from("/home/dev/.cargo/bin/fnm")
+        );
+    }
+}
@@ -0,0 +1,44 @@
+//! Embed Versi's Node.js version management in your own tools.
+//!
+//! [`ManagerBuilder`] finds fnm or nvm and returns a [`VersionManager`] that
+//! lists, installs and removes versions, natively or inside a WSL distro:
+//!
+//! ```no_run
+//! use versi_sdk::{Backend, ManagerBuilder};
+//!
+//! # async fn run() -> Result<(), versi_sdk::BackendError> {
+//! let manager = ManagerBuilder::new(Backend::Fnm).build().await?;
+//! for installed in manager.list_installed().await? {
+//!     println!("{}", installed.version);
+//! }
+//! manager.install("22").await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The backend crates are re-exported for lower-level access. The `fnm` and
+//! `nvm` features (on by default) select the backends, and `shell` adds
+//! shell config detection from `versi-shell`.
+
+mod builder;
+
+pub use builder::{Backend, ManagerBuilder};
+
+pub use versi_backend::{
+    BackendDetection, BackendError, BackendInfo, BackendProvider, BackendUpdate, InstalledVersion,
+    ManagerCapabilities, NodeVersion, RemoteVersion, ShellInitOptions, VersionManager,
+};
+pub use versi_platform::{EnvironmentId, PlatformTarget};
+
+#[cfg(target_os = "windows")]
+pub use versi_platform::{WslDistro, detect_wsl_distros};
+
+pub use versi_backend;
+pub use versi_core;
+#[cfg(feature = "fnm")]
+pub use versi_fnm;
+#[cfg(feature = "nvm")]
+pub use versi_nvm;
+pub use versi_platform;
+#[cfg(feature = "shell")]
+pub use versi_shell;
@@ -1,8 +1,11 @@
 use crate::detect::ShellType;
 use std::fs;
+use std::ops::RangeInclusive;
 use std::path::PathBuf;
 use thiserror::Error;
-use versi_backend::ShellInitOptions;
+use versi_backend::{CD_HOOK_END, CD_HOOK_START, ShellInitOptions};
+#[cfg(target_os = "windows")]
+use versi_platform::execute_in_wsl;
 
 #[derive(Error, Debug)]
 pub enum ConfigError {
@@ -14,6 +17,28 @@ pub enum ConfigError {
 
     #[error("Shell type does not support config files")]
     UnsupportedShell,
+
+    #[error("WSL error: {0}")]
+    Wsl(String),
+
+    #[error("Could not tell where the initialization on line {0} ends; edit the file by hand")]
+    UnclearInit(usize),
+
+    #[error(
+        "The initialization on line {0} shares a block with other commands; edit the file by hand"
+    )]
+    SharedBlock(usize),
+}
+
+/// Where a shell's config lives inside a WSL distro, relative to the home
+/// directory.
+pub fn wsl_config_path(shell_type: &ShellType) -> Option<&'static str> {
+    match shell_type {
+        ShellType::Bash => Some(".bashrc"),
+        ShellType::Zsh => Some(".zshrc"),
+        ShellType::Fish => Some(".config/fish/config.fish"),
+        _ => None,
+    }
 }
 
 pub struct ShellConfig {
@@ -37,6 +62,32 @@ impl ShellConfig {
         })
     }
 
+    /// Reads a shell's config inside a WSL distro. A missing file reads as
+    /// empty, so the first edit creates it.
+    #[cfg(target_os = "windows")]
+    pub async fn load_wsl(shell_type: ShellType, distro: &str) -> Result<Self, ConfigError> {
+        let path = wsl_config_path(&shell_type).ok_or(ConfigError::UnsupportedShell)?;
+        let content = execute_in_wsl(
+            distro,
+            &format!("cat \"$HOME/{}\" 2>/dev/null || true", path),
+        )
+        .await
+        .map_err(|e| ConfigError::Wsl(e.to_string()))?;
+
+        Ok(Self {
+            shell_type,
+            config_path: PathBuf::from(format!("~/{}", path)),
+            content,
+        })
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    pub async fn load_wsl(_shell_type: ShellType, _distro: &str) -> Result<Self, ConfigError> {
+        Err(ConfigError::Wsl(
+            "WSL is only available on Windows".to_string(),
+        ))
+    }
+
     pub fn has_init(&self, marker: &str) -> bool {
         self.content.contains(marker)
     }
@@ -46,11 +97,7 @@ impl ShellConfig {
             return None;
         }
 
-        Some(ShellInitOptions {
-            use_on_cd: self.content.contains("--use-on-cd"),
-            resolve_engines: self.content.contains("--resolve-engines"),
-            corepack_enabled: self.content.contains("--corepack-enabled"),
-        })
+        Some(ShellInitOptions::detect(&self.content))
     }
 
     pub fn add_init(&mut self, init_command: &str, label: &str) -> ShellConfigEdit {
@@ -64,7 +111,10 @@ impl ShellConfig {
         }
     }
 
-    pub fn update_flags(&mut self, marker: &str, options: &ShellInitOptions) -> ShellConfigEdit {
+    /// Brings an existing initialization in line with `init_command`, what
+    /// the backend would add now: flags on the `marker` line are added or
+    /// removed, and a use-on-cd hook block is added, replaced or removed.
+    pub fn update_flags(&mut self, marker: &str, init_command: &str) -> ShellConfigEdit {
         if !self.has_init(marker) {
             return ShellConfigEdit {
                 original: self.content.clone(),
@@ -76,13 +126,10 @@ impl ShellConfig {
         let mut modified = self.content.clone();
         let mut changes = Vec::new();
 
-        let flags = [
-            ("--use-on-cd", options.use_on_cd),
-            ("--resolve-engines", options.resolve_engines),
-            ("--corepack-enabled", options.corepack_enabled),
-        ];
+        let flags = ["--use-on-cd", "--resolve-engines", "--corepack-enabled"];
 
-        for (flag, enabled) in flags {
+        for flag in flags {
+            let enabled = init_command.contains(flag);
             let has_flag = modified.contains(flag);
 
             if enabled && !has_flag {
@@ -94,6 +141,37 @@ impl ShellConfig {
             }
         }
 
+        let init_lines: Vec<&str> = init_command.lines().collect();
+        let wanted = cd_hook_range(&init_lines).map(|(start, end)| &init_lines[start..=end]);
+        let mut lines: Vec<&str> = modified.lines().collect();
+        let current = cd_hook_range(&lines);
+        if current.map(|(start, end)| &lines[start..=end]) != wanted {
+            match (current, wanted) {
+                (Some((start, end)), Some(block)) => {
+                    lines.splice(start..=end, block.iter().copied());
+                    changes.push("Updated the use-on-cd hook".to_string());
+                }
+                (Some((start, end)), None) => {
+                    lines.drain(start..=end);
+                    changes.push("Removed the use-on-cd hook".to_string());
+                }
+                (None, Some(block)) => {
+                    let after = lines
+                        .iter()
+                        .rposition(|line| line.contains(marker))
+                        .map_or(lines.len(), |index| index + 1);
+                    lines.splice(after..after, block.iter().copied());
+                    changes.push("Added the use-on-cd hook".to_string());
+                }
+                (None, None) => {}
+            }
+            let mut joined = lines.join("\n");
+            if modified.ends_with('\n') {
+                joined.push('\n');
+            }
+            modified = joined;
+        }
+
         ShellConfigEdit {
             original: self.content.clone(),
             modified,
@@ -101,6 +179,152 @@ impl ShellConfig {
         }
     }
 
+    /// Moves the initialization, along with the comment right above it, to
+    /// the end of the file so later `PATH` changes cannot shadow the manager.
+    /// A guarded or multi-line initialization moves as a whole.
+    pub fn move_init_to_end(&self, marker: &str) -> Result<ShellConfigEdit, ConfigError> {
+        let lines: Vec<&str> = self.content.lines().collect();
+        let blocks = self.init_blocks(&lines, marker)?;
+        if blocks.is_empty() {
+            return Ok(self.unchanged());
+        }
+
+        let block: Vec<&str> = blocks
+            .iter()
+            .flat_map(|block| lines[block.lines.clone()].iter().copied())
+            .collect();
+        let rest = without_blocks(&lines, &blocks);
+        let modified = if rest.is_empty() {
+            format!("{}\n", block.join("\n"))
+        } else {
+            format!("{}\n\n{}\n", rest.join("\n"), block.join("\n"))
+        };
+
+        Ok(ShellConfigEdit {
+            original: self.content.clone(),
+            changes: if modified == self.content {
+                vec![]
+            } else {
+                vec!["Move initialization to the end of the file".to_string()]
+            },
+            modified,
+        })
+    }
+
+    /// Removes the initialization, along with the comment right above it and
+    /// the use-on-cd hook block. A guarded initialization goes with its
+    /// guard; one that shares a block with other commands is left alone.
+    pub fn remove_init(&self, marker: &str) -> Result<ShellConfigEdit, ConfigError> {
+        let lines: Vec<&str> = self.content.lines().collect();
+        let blocks = self.init_blocks(&lines, marker)?;
+        if blocks.is_empty() {
+            return Ok(self.unchanged());
+        }
+        if let Some(block) = blocks.iter().find(|block| block.shared) {
+            return Err(ConfigError::SharedBlock(block.lines.start() + 1));
+        }
+
+        let removed: usize = blocks.iter().map(|block| block.lines.clone().count()).sum();
+        let kept = without_blocks(&lines, &blocks);
+        let modified = if kept.is_empty() {
+            String::new()
+        } else {
+            format!("{}\n", kept.join("\n"))
+        };
+
+        Ok(ShellConfigEdit {
+            original: self.content.clone(),
+            modified,
+            changes: vec![format!("Remove {} initialization line(s)", removed)],
+        })
+    }
+
+    /// Writes the current content next to the config as `<name>.bak`, so an
+    /// edit can be undone by hand.
+    pub fn write_backup(&self) -> Result<PathBuf, ConfigError> {
+        let mut name = self
+            .config_path
+            .file_name()
+            .map(|name| name.to_os_string())
+            .unwrap_or_default();
+        name.push(".bak");
+        let backup = self.config_path.with_file_name(name);
+        fs::write(&backup, &self.content)?;
+        Ok(backup)
+    }
+
+    fn unchanged(&self) -> ShellConfigEdit {
+        ShellConfigEdit {
+            original: self.content.clone(),
+            modified: self.content.clone(),
+            changes: vec![],
+        }
+    }
+
+    /// The top-level statements that mention `marker`, each with the comment
+    /// right above it, plus the use-on-cd hook. A statement spans a whole
+    /// `if … fi`, `{ … }` or continued command, so a guard is never split
+    /// from what it guards.
+    fn init_blocks(&self, lines: &[&str], marker: &str) -> Result<Vec<InitBlock>, ConfigError> {
+        let mut blocks: Vec<InitBlock> = Vec::new();
+        let mut start = None;
+        let mut depth = 0;
+
+        for (index, line) in lines.iter().enumerate() {
+            let first = *start.get_or_insert(index);
+            depth += nesting_change(&self.shell_type, line);
+            let unclear = depth < 0;
+            if !unclear && (depth > 0 || continues(&self.shell_type, line)) {
+                continue;
+            }
+            start = None;
+            depth = 0;
+
+            let statement = &lines[first..=index];
+            if !statement.iter().any(|line| line.contains(marker)) {
+                continue;
+            }
+            if unclear {
+                return Err(ConfigError::UnclearInit(first + 1));
+            }
+            let shared = statement
+                .iter()
+                .skip(1)
+                .any(|line| !line.contains(marker) && !is_structural(line));
+            let labelled = first > 0 && lines[first - 1].trim_start().starts_with('#');
+            let from = if labelled { first - 1 } else { first };
+            blocks.push(InitBlock {
+                lines: from..=index,
+                shared,
+            });
+        }
+        if let Some(first) = start
+            && lines[first..].iter().any(|line| line.contains(marker))
+        {
+            return Err(ConfigError::UnclearInit(first + 1));
+        }
+
+        if let Some((hook_start, hook_end)) = cd_hook_range(lines) {
+            blocks.push(InitBlock {
+                lines: hook_start..=hook_end,
+                shared: false,
+            });
+            blocks.sort_by_key(|block| *block.lines.start());
+        }
+        let mut merged: Vec<InitBlock> = Vec::new();
+        for block in blocks {
+            match merged.last_mut() {
+                Some(last) if block.lines.start() <= last.lines.end() => {
+                    let end = *last.lines.end().max(block.lines.end());
+                    last.lines = *last.lines.start()..=end;
+                    last.shared |= block.shared;
+                }
+                _ => merged.push(block),
+            }
+        }
+        Ok(merged)
+    }
+
     pub fn apply_edit(&mut self, edit: &ShellConfigEdit) -> Result<(), ConfigError> {
         if let Some(parent) = self.config_path.parent() {
             fs::create_dir_all(parent)?;
@@ -112,6 +336,37 @@ impl ShellConfig {
         Ok(())
     }
 
+    /// Writes an edit to a config read with [`load_wsl`](Self::load_wsl).
+    #[cfg(target_os = "windows")]
+    pub async fn apply_edit_wsl(
+        &mut self,
+        distro: &str,
+        edit: &ShellConfigEdit,
+    ) -> Result<(), ConfigError> {
+        let path = wsl_config_path(&self.shell_type).ok_or(ConfigError::UnsupportedShell)?;
+        let script = format!(
+            "mkdir -p \"$(dirname \"$HOME/{path}\")\" && printf '%s' {} > \"$HOME/{path}\"",
+            single_quote(&edit.modified)
+        );
+        execute_in_wsl(distro, &script)
+            .await
+            .map_err(|e| ConfigError::Wsl(e.to_string()))?;
+        self.content = edit.modified.clone();
+
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    pub async fn apply_edit_wsl(
+        &mut self,
+        _distro: &str,
+        _edit: &ShellConfigEdit,
+    ) -> Result<(), ConfigError> {
+        Err(ConfigError::Wsl(
+            "WSL is only available on Windows".to_string(),
+        ))
+    }
+
     fn add_flag_to_init(content: &str, marker: &str, flag: &str) -> String {
         let mut result = String::new();
         for line in content.lines() {
@@ -150,17 +405,167 @@ impl ShellConfig {
     }
 }
 
+/// Quotes `value` as one word for a POSIX shell.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn single_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// Indices of the first and last line of the use-on-cd hook block.
+fn cd_hook_range(lines: &[&str]) -> Option<(usize, usize)> {
+    let start = lines.iter().position(|line| line.trim() == CD_HOOK_START)?;
+    let end = lines[start..]
+        .iter()
+        .position(|line| line.trim() == CD_HOOK_END)?;
+    Some((start, start + end))
+}
+
+/// Lines of a config that belong to the initialization.
+struct InitBlock {
+    lines: RangeInclusive<usize>,
+    /// Whether the block also runs commands unrelated to the initialization.
+    shared: bool,
+}
+
+/// `lines` without `blocks`, dropping the blank line a removed block would
+/// leave doubled and any blank lines at either end.
+fn without_blocks<'a>(lines: &[&'a str], blocks: &[InitBlock]) -> Vec<&'a str> {
+    let mut kept: Vec<&str> = Vec::new();
+    let mut after_block = false;
+    for (index, line) in lines.iter().enumerate() {
+        if blocks.iter().any(|block| block.lines.contains(&index)) {
+            after_block = true;
+            continue;
+        }
+        let blank = line.trim().is_empty();
+        if blank && after_block && kept.last().is_none_or(|last| last.trim().is_empty()) {
+            continue;
+        }
+        after_block = false;
+        kept.push(*line);
+    }
+    while kept.last().is_some_and(|line| line.trim().is_empty()) {
+        kept.pop();
+    }
+    kept
+}
+
+const POSIX_OPENERS: &[&str] = &["if", "case", "for", "while", "until", "select"];
+const POSIX_CLOSERS: &[&str] = &["fi", "esac", "done"];
+const FISH_OPENERS: &[&str] = &["if", "for", "while", "function", "begin", "switch"];
+const FISH_CLOSERS: &[&str] = &["end"];
+const STRUCTURAL: &[&str] = &["then", "else", "do", "fi", "esac", "done", "end"];
+
+/// How much deeper a line nests, such as `+1` for an `if` that continues on
+/// the next line and `-1` for its `fi`. Only keywords that start a command
+/// count, which is enough for the snippets version managers add.
+fn nesting_change(shell_type: &ShellType, line: &str) -> i32 {
+    let line = line.trim();
+    if line.starts_with('#') {
+        return 0;
+    }
+    let braces = line.matches('{').count() as i32 - line.matches('}').count() as i32;
+    let (openers, closers) = match shell_type {
+        ShellType::Bash | ShellType::Zsh => (POSIX_OPENERS, POSIX_CLOSERS),
+        ShellType::Fish => (FISH_OPENERS, FISH_CLOSERS),
+        ShellType::PowerShell | ShellType::Cmd => return braces,
+    };
+    let keywords: i32 = line
+        .split([';', '&', '|'])
+        .filter_map(|command| command.split_whitespace().next())
+        .map(|word| {
+            if openers.contains(&word) {
+                1
+            } else if closers.contains(&word) {
+                -1
+            } else {
+                0
+            }
+        })
+        .sum();
+    keywords + braces
+}
+
+/// Whether a command carries on past the end of `line`.
+fn continues(shell_type: &ShellType, line: &str) -> bool {
+    let line = line.trim_end();
+    let escape = if matches!(shell_type, ShellType::PowerShell) {
+        '`'
+    } else {
+        '\\'
+    };
+    !line.trim_start().starts_with('#')
+        && (line.ends_with(escape)
+            || line.ends_with("&&")
+            || line.ends_with("||")
+            || line.ends_with('|'))
+}
+
+/// Whether a line only opens, closes or continues a block, like `fi`,
+/// `else` or `}`.
+fn is_structural(line: &str) -> bool {
+    let line = line.trim();
+    line.is_empty()
+        || line.starts_with('#')
+        || line
+            .split(|c: char| c.is_whitespace() || matches!(c, ';' | '{' | '}'))
+            .all(|word| word.is_empty() || STRUCTURAL.contains(&word))
+}
+
+#[derive(Debug, Clone)]
 pub struct ShellConfigEdit {
     pub original: String,
     pub modified: String,
     pub changes: Vec<String>,
 }
 
+/// One line of [`ShellConfigEdit::line_diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Same(String),
+    Removed(String),
+    Added(String),
+}
+
 impl ShellConfigEdit {
     pub fn has_changes(&self) -> bool {
         !self.changes.is_empty()
     }
 
+    /// The edit line by line, keeping as many lines unchanged as possible.
+    pub fn line_diff(&self) -> Vec<DiffLine> {
+        let old: Vec<&str> = self.original.lines().collect();
+        let new: Vec<&str> = self.modified.lines().collect();
+
+        let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+        for i in (0..old.len()).rev() {
+            for j in (0..new.len()).rev() {
+                common[i][j] = if old[i] == new[j] {
+                    common[i + 1][j + 1] + 1
+                } else {
+                    common[i + 1][j].max(common[i][j + 1])
+                };
+            }
+        }
+
+        let mut diff = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < old.len() || j < new.len() {
+            if i < old.len() && j < new.len() && old[i] == new[j] {
+                diff.push(DiffLine::Same(old[i].to_string()));
+                i += 1;
+                j += 1;
+            } else if j < new.len() && (i == old.len() || common[i][j + 1] >= common[i + 1][j]) {
+                diff.push(DiffLine::Added(new[j].to_string()));
+                j += 1;
+            } else {
+                diff.push(DiffLine::Removed(old[i].to_string()));
+                i += 1;
+            }
+        }
+        diff
+    }
+
     pub fn diff_preview(&self) -> String {
         if !self.has_changes() {
             return "No changes needed.".to_string();
@@ -288,12 +693,7 @@ mod tests {
     #[test]
     fn test_update_flags_add() {
         let mut config = create_test_config(r#"eval "$(fnm env --shell bash)""#);
-        let options = ShellInitOptions {
-            use_on_cd: true,
-            resolve_engines: false,
-            corepack_enabled: false,
-        };
-        let edit = config.update_flags("fnm env", &options);
+        let edit = config.update_flags("fnm env", r#"eval "$(fnm env --use-on-cd)""#);
 
         assert!(edit.has_changes());
         assert!(edit.modified.contains("--use-on-cd"));
@@ -302,12 +702,7 @@ mod tests {
     #[test]
     fn test_update_flags_remove() {
         let mut config = create_test_config(r#"eval "$(fnm env --use-on-cd --shell bash)""#);
-        let options = ShellInitOptions {
-            use_on_cd: false,
-            resolve_engines: false,
-            corepack_enabled: false,
-        };
-        let edit = config.update_flags("fnm env", &options);
+        let edit = config.update_flags("fnm env", r#"eval "$(fnm env)""#);
 
         assert!(edit.has_changes());
         assert!(!edit.modified.contains("--use-on-cd"));
@@ -316,16 +711,145 @@ mod tests {
     #[test]
     fn test_update_flags_no_change() {
         let mut config = create_test_config(r#"eval "$(fnm env --use-on-cd --shell bash)""#);
-        let options = ShellInitOptions {
-            use_on_cd: true,
-            resolve_engines: false,
-            corepack_enabled: false,
-        };
-        let edit = config.update_flags("fnm env", &options);
+        let edit = config.update_flags("fnm env", r#"eval "$(fnm env --use-on-cd)""#);
 
         assert!(!edit.has_changes());
     }
 
+    #[test]
+    fn test_update_flags_adds_and_removes_cd_hook() {
+        let load = r#"export NVM_DIR="$HOME/.nvm" && \. "$NVM_DIR/nvm.sh""#;
+        let with_hook = format!("{}\n{}\nload_nvmrc\n{}", load, CD_HOOK_START, CD_HOOK_END);
+        let mut config = create_test_config(&format!("# nvm\n{}\nalias ll='ls -l'\n", load));
+
+        let edit = config.update_flags("NVM_DIR", &with_hook);
+        assert_eq!(edit.changes, ["Added the use-on-cd hook"]);
+        assert_eq!(
+            edit.modified,
+            format!("# nvm\n{}\nalias ll='ls -l'\n", with_hook)
+        );
+        assert!(!edit.modified.contains("--use-on-cd"));
+
+        config.content = edit.modified;
+        assert!(config.detect_options("NVM_DIR").unwrap().use_on_cd);
+        assert!(!config.update_flags("NVM_DIR", &with_hook).has_changes());
+
+        let edit = config.update_flags("NVM_DIR", load);
+        assert_eq!(edit.changes, ["Removed the use-on-cd hook"]);
+        assert_eq!(
+            edit.modified,
+            format!("# nvm\n{}\nalias ll='ls -l'\n", load)
+        );
+    }
+
+    #[test]
+    fn test_single_quote_escapes_quotes() {
+        assert_eq!(
+            single_quote("alias ll='ls -l'"),
+            r"'alias ll='\''ls -l'\'''"
+        );
+        assert_eq!(single_quote("$HOME"), "'$HOME'");
+    }
+
+    #[test]
+    fn test_remove_init_removes_cd_hook() {
+        let config = create_test_config(&format!(
+            "alias ll='ls -l'\n# nvm\nexport NVM_DIR=\"$HOME/.nvm\"\n{}\nload_nvmrc\n{}\n",
+            CD_HOOK_START, CD_HOOK_END
+        ));
+        let edit = config.remove_init("NVM_DIR").unwrap();
+
+        assert_eq!(edit.modified, "alias ll='ls -l'\n");
+    }
+
+    #[test]
+    fn test_move_init_to_end() {
+        let config = create_test_config(
+            "# fnm (Fast Node Manager)\neval \"$(fnm env --shell bash)\"\n\nexport PATH=\"/usr/local/bin:$PATH\"\n",
+        );
+        let edit = config.move_init_to_end("fnm env").unwrap();
+
+        assert!(edit.has_changes());
+        assert_eq!(
+            edit.modified,
+            "export PATH=\"/usr/local/bin:$PATH\"\n\n# fnm (Fast Node Manager)\neval \"$(fnm env --shell bash)\"\n"
+        );
+    }
+
+    #[test]
+    fn test_move_init_to_end_keeps_guard_together() {
+        let config = create_test_config(
+            "if command -v fnm > /dev/null; then\n  eval \"$(fnm env)\"\nfi\nexport PATH=\"/usr/local/bin:$PATH\"\n",
+        );
+        let edit = config.move_init_to_end("fnm env").unwrap();
+
+        assert_eq!(
+            edit.modified,
+            "export PATH=\"/usr/local/bin:$PATH\"\n\nif command -v fnm > /dev/null; then\n  eval \"$(fnm env)\"\nfi\n"
+        );
+        assert_eq!(
+            edit.line_diff(),
+            vec![
+                DiffLine::Added("export PATH=\"/usr/local/bin:$PATH\"".to_string()),
+                DiffLine::Added(String::new()),
+                DiffLine::Same("if command -v fnm > /dev/null; then".to_string()),
+                DiffLine::Same("  eval \"$(fnm env)\"".to_string()),
+                DiffLine::Same("fi".to_string()),
+                DiffLine::Removed("export PATH=\"/usr/local/bin:$PATH\"".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_move_init_to_end_already_last() {
+        let config =
+            create_test_config("export PATH=\"/usr/local/bin:$PATH\"\n\neval \"$(fnm env)\"\n");
+        assert!(!config.move_init_to_end("fnm env").unwrap().has_changes());
+    }
+
+    #[test]
+    fn test_remove_init() {
+        let config = create_test_config(
+            "export PATH=\"/usr/local/bin:$PATH\"\n\n# nvm (Node Version Manager)\nexport NVM_DIR=\"$HOME/.nvm\"\n[ -s \"$NVM_DIR/nvm.sh\" ] && \\. \"$NVM_DIR/nvm.sh\"\n",
+        );
+        let edit = config.remove_init("NVM_DIR").unwrap();
+
+        assert!(edit.has_changes());
+        assert_eq!(edit.modified, "export PATH=\"/usr/local/bin:$PATH\"\n");
+        assert!(
+            !create_test_config("alias ll='ls -l'\n")
+                .remove_init("fnm env")
+                .unwrap()
+                .has_changes()
+        );
+    }
+
+    #[test]
+    fn test_remove_init_removes_whole_guard() {
+        let config = create_test_config(
+            "alias ll='ls -l'\n\nexport NVM_DIR=\"$HOME/.nvm\"\nif [ -s \"$NVM_DIR/nvm.sh\" ]; then\n  . \"$NVM_DIR/nvm.sh\"\nfi\n\nexport EDITOR=vim\n",
+        );
+        let edit = config.remove_init("NVM_DIR").unwrap();
+
+        assert_eq!(edit.modified, "alias ll='ls -l'\n\nexport EDITOR=vim\n");
+    }
+
+    #[test]
+    fn test_remove_init_refuses_shared_block() {
+        let config = create_test_config(
+            "if [ -d \"$HOME/.local/bin\" ]; then\n  export PATH=\"$HOME/.local/bin:$PATH\"\n  eval \"$(fnm env)\"\nfi\n",
+        );
+
+        assert!(matches!(
+            config.remove_init("fnm env"),
+            Err(ConfigError::SharedBlock(1))
+        ));
+        assert!(matches!(
+            create_test_config("if true; then\n  eval \"$(fnm env)\"\n").remove_init("fnm env"),
+            Err(ConfigError::UnclearInit(1))
+        ));
+    }
+
     #[test]
     fn test_shell_config_edit_has_changes() {
         let edit = ShellConfigEdit {
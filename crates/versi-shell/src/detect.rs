@@ -222,6 +222,17 @@ pub fn detect_wsl_shells(_distro: &str) -> Vec<ShellInfo> {
     Vec::new()
 }
 
+/// Git Bash and MSYS2 read the same startup files as a native bash, from
+/// the Windows home directory.
+pub fn detect_msys_shells(bash: &str) -> Vec<ShellInfo> {
+    vec![ShellInfo {
+        shell_type: ShellType::Bash,
+        path: Some(PathBuf::from(bash)),
+        config_file: find_existing_config(&ShellType::Bash),
+        is_configured: false,
+    }]
+}
+
 fn find_existing_config(shell: &ShellType) -> Option<PathBuf> {
     shell.config_files().into_iter().find(|path| path.exists())
 }
@@ -1,11 +1,23 @@
 mod config;
 mod detect;
+mod resolve;
+mod troubleshoot;
 mod verify;
 
 pub mod shells;
 
-pub use config::{ShellConfig, ShellConfigEdit};
-pub use detect::{ShellInfo, ShellType, detect_native_shells, detect_shells, detect_wsl_shells};
+pub use config::{DiffLine, ShellConfig, ShellConfigEdit, wsl_config_path};
+pub use detect::{
+    ShellInfo, ShellType, detect_msys_shells, detect_native_shells, detect_shells,
+    detect_wsl_shells,
+};
+pub use resolve::{
+    NodeResolution, resolve_msys_node_version, resolve_node_version, resolve_wsl_node_version,
+};
+pub use troubleshoot::{
+    SOURCE_BASHRC_LINE, StepKind, StepOutcome, TroubleshootFix, TroubleshootReport,
+    TroubleshootStep, troubleshoot,
+};
 pub use verify::{
     VerificationResult, get_or_create_config_path, verify_shell_config, verify_wsl_shell_config,
 };
@@ -0,0 +1,145 @@
+use crate::detect::ShellType;
+use std::time::Duration;
+use tokio::process::Command;
+use versi_platform::HideWindow;
+
+const NODE_VERSION_CMD: &str = "node --version";
+
+/// What `node --version` printed when run the way a new terminal would run it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeResolution {
+    Version(String),
+    NotFound,
+    Error(String),
+}
+
+/// Launches `shell_type` with its profile loaded and reports which Node.js
+/// version it resolves.
+pub async fn resolve_node_version(shell_type: &ShellType, timeout: Duration) -> NodeResolution {
+    let (program, mut args) = match shell_type {
+        ShellType::Bash | ShellType::Zsh | ShellType::Fish => (
+            shell_type.shell_arg().to_string(),
+            terminal_args(shell_type),
+        ),
+        ShellType::PowerShell => {
+            let shell = if which::which("pwsh").is_ok() {
+                "pwsh"
+            } else {
+                "powershell"
+            };
+            (shell.to_string(), vec!["-Command"])
+        }
+        ShellType::Cmd => ("cmd".to_string(), vec!["/C"]),
+    };
+    args.push(NODE_VERSION_CMD);
+
+    let mut command = Command::new(program);
+    command.args(&args).hide_window();
+    run(command, timeout).await
+}
+
+#[cfg(target_os = "windows")]
+pub async fn resolve_wsl_node_version(
+    shell_type: &ShellType,
+    distro: &str,
+    timeout: Duration,
+) -> NodeResolution {
+    if !matches!(
+        shell_type,
+        ShellType::Bash | ShellType::Zsh | ShellType::Fish
+    ) {
+        return NodeResolution::Error("Shell not supported in WSL".to_string());
+    }
+
+    let mut args = vec!["-d", distro, "--", shell_type.shell_arg()];
+    args.extend(terminal_args(shell_type));
+    args.push(NODE_VERSION_CMD);
+
+    let mut command = Command::new("wsl.exe");
+    command.args(&args).hide_window();
+    run(command, timeout).await
+}
+
+#[cfg(not(target_os = "windows"))]
+pub async fn resolve_wsl_node_version(
+    _shell_type: &ShellType,
+    _distro: &str,
+    _timeout: Duration,
+) -> NodeResolution {
+    NodeResolution::Error("WSL is only available on Windows".to_string())
+}
+
+/// Flags matching how terminal emulators start the shell: interactive
+/// everywhere, and additionally a login shell on macOS.
+fn terminal_args(shell_type: &ShellType) -> Vec<&'static str> {
+    match shell_type {
+        ShellType::Fish if cfg!(target_os = "macos") => vec!["-l", "-c"],
+        ShellType::Fish => vec!["-c"],
+        _ if cfg!(target_os = "macos") => vec!["-l", "-i", "-c"],
+        _ => vec!["-i", "-c"],
+    }
+}
+
+/// Runs `node --version` in an interactive session of the Git Bash or MSYS2
+/// bash at `bash`.
+pub async fn resolve_msys_node_version(bash: &str, timeout: Duration) -> NodeResolution {
+    let mut args = terminal_args(&ShellType::Bash);
+    args.push(NODE_VERSION_CMD);
+
+    let mut command = Command::new(bash);
+    command.args(&args).hide_window();
+    run(command, timeout).await
+}
+
+async fn run(mut command: Command, timeout: Duration) -> NodeResolution {
+    command
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true);
+    match tokio::time::timeout(timeout, command.output()).await {
+        Ok(Ok(output)) => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            match parse_node_version(&stdout) {
+                Some(version) => NodeResolution::Version(version),
+                None => NodeResolution::NotFound,
+            }
+        }
+        Ok(Err(e)) => NodeResolution::Error(e.to_string()),
+        Err(_) => NodeResolution::Error("Timed out".to_string()),
+    }
+}
+
+/// Picks the `node --version` line out of output that may also contain
+/// banners or prompts printed by the user's profile.
+fn parse_node_version(output: &str) -> Option<String> {
+    output.lines().rev().map(str::trim).find_map(|line| {
+        let rest = line.strip_prefix('v')?;
+        let mut parts = rest.split('.');
+        let valid = parts.next().is_some_and(is_number)
+            && parts.next().is_some_and(is_number)
+            && parts.next().is_some_and(is_number)
+            && parts.next().is_none();
+        valid.then(|| line.to_string())
+    })
+}
+
+fn is_number(part: &str) -> bool {
+    !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_version_after_profile_noise() {
+        let output = "Welcome back!\nfnm: using v20.18.0\nv22.11.0\n";
+        assert_eq!(parse_node_version(output), Some("v22.11.0".to_string()));
+    }
+
+    #[test]
+    fn ignores_output_without_version() {
+        assert_eq!(parse_node_version("bash: node: command not found\n"), None);
+        assert_eq!(parse_node_version("v22\n"), None);
+        assert_eq!(parse_node_version(""), None);
+    }
+}
@@ -0,0 +1,478 @@
+use crate::detect::{ShellType, detect_native_shells};
+use std::path::{Path, PathBuf};
+
+/// Line appended to a bash login profile so login shells also read `~/.bashrc`.
+pub const SOURCE_BASHRC_LINE: &str = "[ -f ~/.bashrc ] && . ~/.bashrc";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepKind {
+    InitPresent,
+    PathOrder,
+    LoginShells,
+    EditorTerminal,
+}
+
+impl StepKind {
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::InitPresent => "Init line is in the shell profile",
+            Self::PathOrder => "Nothing changes PATH after the init line",
+            Self::LoginShells => "Login and interactive shells both load it",
+            Self::EditorTerminal => "Editor terminals inherit the environment",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StepOutcome {
+    Passed(String),
+    Issue(String),
+    Skipped(String),
+}
+
+/// A change to a shell profile that resolves a troubleshooting issue.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TroubleshootFix {
+    AddInit(PathBuf),
+    MoveInitToEnd(PathBuf),
+    SourceBashrc(PathBuf),
+}
+
+impl TroubleshootFix {
+    pub fn label(&self) -> String {
+        let file = |path: &Path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default()
+        };
+        match self {
+            Self::AddInit(path) => format!("Add to {}", file(path)),
+            Self::MoveInitToEnd(path) => format!("Move to end of {}", file(path)),
+            Self::SourceBashrc(path) => format!("Load .bashrc from {}", file(path)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TroubleshootStep {
+    pub kind: StepKind,
+    pub outcome: StepOutcome,
+    pub fix: Option<TroubleshootFix>,
+}
+
+impl TroubleshootStep {
+    fn new(kind: StepKind, outcome: StepOutcome) -> Self {
+        Self {
+            kind,
+            outcome,
+            fix: None,
+        }
+    }
+
+    fn with_fix(mut self, fix: TroubleshootFix) -> Self {
+        self.fix = Some(fix);
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TroubleshootReport {
+    pub shells: Vec<(ShellType, Vec<TroubleshootStep>)>,
+    pub editor: TroubleshootStep,
+}
+
+/// Runs the "works in Versi, not in my terminal" checks against every
+/// detected native shell.
+pub fn troubleshoot(marker: &str) -> TroubleshootReport {
+    let shells = detect_native_shells()
+        .into_iter()
+        .filter(|shell| shell.shell_type != ShellType::Cmd)
+        .map(|shell| {
+            let profiles = load_profiles(&shell.shell_type);
+            let rc_path = shell
+                .shell_type
+                .config_files()
+                .into_iter()
+                .next()
+                .unwrap_or_default();
+            let steps = check_profiles(&shell.shell_type, &profiles, &rc_path, marker);
+            (shell.shell_type, steps)
+        })
+        .collect();
+
+    TroubleshootReport {
+        shells,
+        editor: check_editor_terminal(),
+    }
+}
+
+fn profile_paths(shell_type: &ShellType) -> Vec<PathBuf> {
+    match shell_type {
+        ShellType::Bash => {
+            let home = dirs::home_dir().unwrap_or_default();
+            vec![
+                home.join(".bashrc"),
+                home.join(".bash_profile"),
+                home.join(".bash_login"),
+                home.join(".profile"),
+            ]
+        }
+        _ => shell_type.config_files(),
+    }
+}
+
+fn load_profiles(shell_type: &ShellType) -> Vec<(PathBuf, String)> {
+    profile_paths(shell_type)
+        .into_iter()
+        .filter_map(|path| {
+            let content = std::fs::read_to_string(&path).ok()?;
+            Some((path, content))
+        })
+        .collect()
+}
+
+fn file_name(path: &Path) -> String {
+    let home = dirs::home_dir().unwrap_or_default();
+    match path.strip_prefix(&home) {
+        Ok(relative) if !home.as_os_str().is_empty() => format!("~/{}", relative.display()),
+        _ => path.display().to_string(),
+    }
+}
+
+fn check_profiles(
+    shell_type: &ShellType,
+    profiles: &[(PathBuf, String)],
+    rc_path: &Path,
+    marker: &str,
+) -> Vec<TroubleshootStep> {
+    let with_init: Vec<&(PathBuf, String)> = profiles
+        .iter()
+        .filter(|(_, content)| content.contains(marker))
+        .collect();
+
+    if with_init.is_empty() {
+        let skipped = StepOutcome::Skipped("Add the init line first".to_string());
+        return vec![
+            TroubleshootStep::new(
+                StepKind::InitPresent,
+                StepOutcome::Issue(format!("No profile contains \"{}\"", marker)),
+            )
+            .with_fix(TroubleshootFix::AddInit(rc_path.to_path_buf())),
+            TroubleshootStep::new(StepKind::PathOrder, skipped.clone()),
+            TroubleshootStep::new(StepKind::LoginShells, skipped),
+        ];
+    }
+
+    let found_in: Vec<String> = with_init.iter().map(|(path, _)| file_name(path)).collect();
+    let mut steps = vec![TroubleshootStep::new(
+        StepKind::InitPresent,
+        StepOutcome::Passed(format!("Found in {}", found_in.join(", "))),
+    )];
+
+    let override_line = with_init.iter().find_map(|(path, content)| {
+        path_override_after_init(shell_type, content, marker)
+            .map(|(number, line)| (path, number, line))
+    });
+    steps.push(match override_line {
+        Some((path, number, line)) => TroubleshootStep::new(
+            StepKind::PathOrder,
+            StepOutcome::Issue(format!(
+                "{}:{} runs after the init line: {}",
+                file_name(path),
+                number,
+                line
+            )),
+        )
+        .with_fix(TroubleshootFix::MoveInitToEnd(path.clone())),
+        None => TroubleshootStep::new(
+            StepKind::PathOrder,
+            StepOutcome::Passed("The init line runs after other PATH changes".to_string()),
+        ),
+    });
+
+    steps.push(check_login_shells(shell_type, profiles, rc_path, marker));
+    steps
+}
+
+fn check_login_shells(
+    shell_type: &ShellType,
+    profiles: &[(PathBuf, String)],
+    rc_path: &Path,
+    marker: &str,
+) -> TroubleshootStep {
+    let rc_has_init = profiles
+        .iter()
+        .any(|(path, content)| path == rc_path && content.contains(marker));
+    let only_in_login_profile = || {
+        StepOutcome::Issue(format!(
+            "Only login shells load the init line; interactive shells read {}",
+            file_name(rc_path)
+        ))
+    };
+
+    match shell_type {
+        ShellType::Bash if rc_has_init => {
+            let login_profile = profiles.iter().find(|(path, _)| path != rc_path);
+            match login_profile {
+                Some((path, content))
+                    if !content.contains(".bashrc") && !content.contains(marker) =>
+                {
+                    TroubleshootStep::new(
+                        StepKind::LoginShells,
+                        StepOutcome::Issue(format!(
+                            "{} does not load ~/.bashrc, so login shells skip the init line",
+                            file_name(path)
+                        )),
+                    )
+                    .with_fix(TroubleshootFix::SourceBashrc(path.clone()))
+                }
+                Some(_) => TroubleshootStep::new(
+                    StepKind::LoginShells,
+                    StepOutcome::Passed("Login shells load ~/.bashrc".to_string()),
+                ),
+                None => TroubleshootStep::new(
+                    StepKind::LoginShells,
+                    StepOutcome::Passed("No login profile overrides ~/.bashrc".to_string()),
+                ),
+            }
+        }
+        ShellType::Bash | ShellType::Zsh if !rc_has_init => {
+            TroubleshootStep::new(StepKind::LoginShells, only_in_login_profile())
+                .with_fix(TroubleshootFix::AddInit(rc_path.to_path_buf()))
+        }
+        _ => TroubleshootStep::new(
+            StepKind::LoginShells,
+            StepOutcome::Passed(format!("Every shell reads {}", file_name(rc_path))),
+        ),
+    }
+}
+
+/// Finds the first line after the last init line that puts other directories
+/// ahead of the manager's on `PATH`. Returns the 1-based line number and text.
+fn path_override_after_init(
+    shell_type: &ShellType,
+    content: &str,
+    marker: &str,
+) -> Option<(usize, String)> {
+    let lines: Vec<&str> = content.lines().collect();
+    let last_init = lines.iter().rposition(|line| line.contains(marker))?;
+    lines
+        .iter()
+        .enumerate()
+        .skip(last_init + 1)
+        .find(|(_, line)| prepends_to_path(shell_type, line))
+        .map(|(index, line)| (index + 1, line.trim().to_string()))
+}
+
+fn prepends_to_path(shell_type: &ShellType, line: &str) -> bool {
+    let line = line.trim();
+    if line.starts_with('#') {
+        return false;
+    }
+
+    match shell_type {
+        ShellType::Fish => {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words.first() {
+                Some(&"fish_add_path") => !words.iter().any(|w| *w == "-a" || *w == "--append"),
+                Some(&"set") => words
+                    .iter()
+                    .position(|w| *w == "PATH" || *w == "fish_user_paths")
+                    .and_then(|pos| words.get(pos + 1))
+                    .is_some_and(|first| {
+                        !first.starts_with("$PATH") && !first.starts_with("$fish_user_paths")
+                    }),
+                _ => false,
+            }
+        }
+        ShellType::PowerShell => {
+            let lower = line.to_ascii_lowercase();
+            let Some(rest) = lower.strip_prefix("$env:path") else {
+                return false;
+            };
+            let rest = rest.trim_start();
+            match rest.strip_prefix('=') {
+                Some(value) => !value.trim_start().starts_with("$env:path"),
+                None => false,
+            }
+        }
+        ShellType::Bash | ShellType::Zsh => {
+            if line.contains("brew shellenv") {
+                return true;
+            }
+            let statement = line.strip_prefix("export ").unwrap_or(line).trim_start();
+            let Some(value) = statement.strip_prefix("PATH=") else {
+                return false;
+            };
+            let value = value.trim_start_matches(['"', '\'']);
+            !value.starts_with("$PATH") && !value.starts_with("${PATH}")
+        }
+        ShellType::Cmd => false,
+    }
+}
+
+fn vscode_settings_paths() -> Vec<PathBuf> {
+    let Some(config) = dirs::config_dir() else {
+        return Vec::new();
+    };
+    ["Code", "Code - Insiders"]
+        .iter()
+        .map(|app| config.join(app).join("User").join("settings.json"))
+        .collect()
+}
+
+fn check_editor_terminal() -> TroubleshootStep {
+    let settings = vscode_settings_paths()
+        .into_iter()
+        .find_map(|path| std::fs::read_to_string(path).ok());
+    match settings {
+        Some(content) => check_vscode_settings(&content),
+        None => TroubleshootStep::new(
+            StepKind::EditorTerminal,
+            StepOutcome::Skipped("No VS Code settings found".to_string()),
+        ),
+    }
+}
+
+fn check_vscode_settings(content: &str) -> TroubleshootStep {
+    let inherit_disabled = content
+        .split("\"terminal.integrated.inheritEnv\"")
+        .skip(1)
+        .any(|rest| {
+            rest.trim_start()
+                .strip_prefix(':')
+                .is_some_and(|value| value.trim_start().starts_with("false"))
+        });
+    let overrides_path = content
+        .split("\"terminal.integrated.env.")
+        .skip(1)
+        .any(|rest| {
+            rest.split('}')
+                .next()
+                .is_some_and(|env| env.contains("\"PATH\""))
+        });
+
+    let outcome = if inherit_disabled {
+        StepOutcome::Issue(
+            "VS Code has terminal.integrated.inheritEnv set to false; remove it and restart VS Code"
+                .to_string(),
+        )
+    } else if overrides_path {
+        StepOutcome::Issue(
+            "VS Code overrides PATH in terminal.integrated.env; remove the PATH entry".to_string(),
+        )
+    } else {
+        StepOutcome::Passed(
+            "Fully quit and reopen VS Code after changing your shell profile".to_string(),
+        )
+    };
+    TroubleshootStep::new(StepKind::EditorTerminal, outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(name: &str, content: &str) -> (PathBuf, String) {
+        (PathBuf::from("/home/user").join(name), content.to_string())
+    }
+
+    #[test]
+    fn reports_missing_init_with_fix() {
+        let profiles = [profile(".bashrc", "alias ll='ls -l'\n")];
+        let rc = PathBuf::from("/home/user/.bashrc");
+        let steps = check_profiles(&ShellType::Bash, &profiles, &rc, "fnm env");
+
+        assert!(matches!(steps[0].outcome, StepOutcome::Issue(_)));
+        assert_eq!(steps[0].fix, Some(TroubleshootFix::AddInit(rc)));
+        assert!(matches!(steps[1].outcome, StepOutcome::Skipped(_)));
+    }
+
+    #[test]
+    fn flags_path_prepended_after_init() {
+        let content = "eval \"$(fnm env --shell bash)\"\nexport PATH=\"$PATH:/opt/tools\"\nexport PATH=\"/usr/local/bin:$PATH\"\n";
+        assert_eq!(
+            path_override_after_init(&ShellType::Bash, content, "fnm env"),
+            Some((3, "export PATH=\"/usr/local/bin:$PATH\"".to_string()))
+        );
+
+        let ordered = "export PATH=\"/usr/local/bin:$PATH\"\neval \"$(fnm env --shell bash)\"\n";
+        assert_eq!(
+            path_override_after_init(&ShellType::Bash, ordered, "fnm env"),
+            None
+        );
+    }
+
+    #[test]
+    fn detects_prepends_in_other_shells() {
+        assert!(prepends_to_path(
+            &ShellType::Fish,
+            "fish_add_path /opt/homebrew/bin"
+        ));
+        assert!(!prepends_to_path(
+            &ShellType::Fish,
+            "fish_add_path -a /opt/tools"
+        ));
+        assert!(prepends_to_path(
+            &ShellType::Fish,
+            "set -gx PATH /usr/local/bin $PATH"
+        ));
+        assert!(prepends_to_path(
+            &ShellType::PowerShell,
+            "$env:PATH = \"C:\\tools;\" + $env:PATH"
+        ));
+        assert!(!prepends_to_path(
+            &ShellType::PowerShell,
+            "$env:PATH += \";C:\\tools\""
+        ));
+        assert!(prepends_to_path(
+            &ShellType::Zsh,
+            "eval \"$(/opt/homebrew/bin/brew shellenv)\""
+        ));
+    }
+
+    #[test]
+    fn flags_bash_profile_that_skips_bashrc() {
+        let rc = PathBuf::from("/home/user/.bashrc");
+        let profiles = [
+            profile(".bashrc", "eval \"$(fnm env)\"\n"),
+            profile(".bash_profile", "export EDITOR=vim\n"),
+        ];
+        let step = check_login_shells(&ShellType::Bash, &profiles, &rc, "fnm env");
+        assert_eq!(
+            step.fix,
+            Some(TroubleshootFix::SourceBashrc(PathBuf::from(
+                "/home/user/.bash_profile"
+            )))
+        );
+
+        let profiles = [
+            profile(".bashrc", "eval \"$(fnm env)\"\n"),
+            profile(".bash_profile", "[ -f ~/.bashrc ] && . ~/.bashrc\n"),
+        ];
+        let step = check_login_shells(&ShellType::Bash, &profiles, &rc, "fnm env");
+        assert!(matches!(step.outcome, StepOutcome::Passed(_)));
+    }
+
+    #[test]
+    fn flags_zsh_init_only_in_zprofile() {
+        let rc = PathBuf::from("/home/user/.zshrc");
+        let profiles = [profile(".zprofile", "eval \"$(fnm env)\"\n")];
+        let step = check_login_shells(&ShellType::Zsh, &profiles, &rc, "fnm env");
+        assert_eq!(step.fix, Some(TroubleshootFix::AddInit(rc)));
+    }
+
+    #[test]
+    fn detects_vscode_env_overrides() {
+        let step = check_vscode_settings(r#"{ "terminal.integrated.inheritEnv": false }"#);
+        assert!(matches!(step.outcome, StepOutcome::Issue(_)));
+
+        let step = check_vscode_settings(
+            r#"{ "terminal.integrated.env.osx": { "PATH": "/usr/bin" }, "editor.fontSize": 14 }"#,
+        );
+        assert!(matches!(step.outcome, StepOutcome::Issue(_)));
+
+        let step = check_vscode_settings(r#"{ "terminal.integrated.env.linux": { "FOO": "1" } }"#);
+        assert!(matches!(step.outcome, StepOutcome::Passed(_)));
+    }
+}
@@ -128,11 +128,7 @@ pub async fn verify_wsl_shell_config(
             if output.status.success() {
                 let content = String::from_utf8_lossy(&output.stdout);
                 if content.contains(marker) {
-                    let options = ShellInitOptions {
-                        use_on_cd: content.contains("--use-on-cd"),
-                        resolve_engines: content.contains("--resolve-engines"),
-                        corepack_enabled: content.contains("--corepack-enabled"),
-                    };
+                    let options = ShellInitOptions::detect(&content);
                     debug!("WSL shell {} is configured", shell_type.name());
                     VerificationResult::Configured(Some(options))
                 } else if wsl_functional_test(shell_type, distro, backend_binary).await {
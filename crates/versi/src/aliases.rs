@@ -0,0 +1,229 @@
+use chrono::NaiveDate;
+use versi_backend::{NodeVersion, RemoteVersion};
+use versi_core::ReleaseSchedule;
+
+/// A version named by one of the aliases nvm accepts, such as `lts/iron`.
+#[derive(Debug, Clone)]
+pub struct AliasMatch<'a> {
+    pub version: &'a RemoteVersion,
+    /// What the alias stands for, such as "latest Iron".
+    pub label: String,
+}
+
+/// Resolves `latest`, `current`, `stable`, `node`, `lts`, `lts/*`,
+/// `lts/<codename>` and `lts/-<n>` to the newest matching remote version.
+/// Codenames come from the remote list, or from the release schedule for
+/// backends that do not report them.
+pub fn resolve_alias<'a>(
+    versions: &'a [RemoteVersion],
+    schedule: Option<&ReleaseSchedule>,
+    query: &str,
+    today: NaiveDate,
+) -> Option<AliasMatch<'a>> {
+    let query = query.trim().to_lowercase();
+    let codename = |v: &RemoteVersion| -> Option<String> {
+        v.lts_codename.clone().or_else(|| {
+            let schedule = schedule?;
+            schedule
+                .lts_date(v.version.major)
+                .filter(|date| *date <= today)?;
+            schedule.codename(v.version.major).map(str::to_string)
+        })
+    };
+    let newest = |filter: &dyn Fn(&RemoteVersion) -> bool| {
+        versions
+            .iter()
+            .filter(|v| filter(v))
+            .max_by_key(|v| &v.version)
+    };
+    let named = |version: &'a RemoteVersion| {
+        let name = codename(version).map_or_else(|| "LTS".to_string(), |c| capitalize(&c));
+        AliasMatch {
+            version,
+            label: format!("latest {}", name),
+        }
+    };
+
+    match query.as_str() {
+        "latest" | "current" | "stable" | "node" => newest(&|_| true).map(|version| AliasMatch {
+            version,
+            label: "latest".to_string(),
+        }),
+        "lts" | "lts/*" => newest(&|v| codename(v).is_some()).map(|version| AliasMatch {
+            version,
+            label: "latest LTS".to_string(),
+        }),
+        q => {
+            let name = q.strip_prefix("lts/")?;
+            if let Some(back) = name.strip_prefix('-') {
+                let back: usize = back.parse().ok()?;
+                let mut majors: Vec<u32> = versions
+                    .iter()
+                    .filter(|v| codename(v).is_some())
+                    .map(|v| v.version.major)
+                    .collect();
+                majors.sort_unstable_by(|a, b| b.cmp(a));
+                majors.dedup();
+                let major = *majors.get(back)?;
+                return newest(&|v| v.version.major == major).map(named);
+            }
+            newest(&|v| codename(v).is_some_and(|c| c.to_lowercase() == name)).map(named)
+        }
+    }
+}
+
+/// Whether `spec` is a major or major and minor, such as `20` or `v20.12`,
+/// that the backend would otherwise resolve to a release on its own.
+pub fn is_partial_version(spec: &str) -> bool {
+    partial_parts(spec).is_some_and(|parts| parts.len() < 3)
+}
+
+/// Whether `version` is one of the versions a partial spec such as `20`,
+/// `v20.11` or `20.11.1` names.
+pub fn matches_partial(spec: &str, version: &NodeVersion) -> bool {
+    match partial_parts(spec).as_deref() {
+        Some([major]) => version.major == *major,
+        Some([major, minor]) => version.major == *major && version.minor == *minor,
+        Some([major, minor, patch]) => *version == NodeVersion::new(*major, *minor, *patch),
+        _ => false,
+    }
+}
+
+fn partial_parts(spec: &str) -> Option<Vec<u32>> {
+    let spec = spec.trim();
+    let spec = spec.strip_prefix('v').unwrap_or(spec);
+    spec.split('.').map(|part| part.parse().ok()).collect()
+}
+
+/// The releases an install of `spec` can pick from, newest first: every
+/// patch a partial version names, or for an alias, every release of the
+/// line it resolves to up to the one it names.
+pub fn install_candidates<'a>(
+    versions: &'a [RemoteVersion],
+    schedule: Option<&ReleaseSchedule>,
+    spec: &str,
+    today: NaiveDate,
+) -> Vec<&'a NodeVersion> {
+    let mut candidates: Vec<&NodeVersion> = match resolve_alias(versions, schedule, spec, today) {
+        Some(alias) => {
+            let named = &alias.version.version;
+            versions
+                .iter()
+                .map(|v| &v.version)
+                .filter(|v| v.major == named.major && *v <= named)
+                .collect()
+        }
+        None => versions
+            .iter()
+            .map(|v| &v.version)
+            .filter(|v| matches_partial(spec, v))
+            .collect(),
+    };
+    candidates.sort_unstable_by(|a, b| b.cmp(a));
+    candidates.dedup();
+    candidates
+}
+
+fn capitalize(value: &str) -> String {
+    let mut chars = value.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remote(version: &str, codename: Option<&str>) -> RemoteVersion {
+        RemoteVersion {
+            version: version.parse().unwrap(),
+            lts_codename: codename.map(str::to_string),
+            is_latest: false,
+        }
+    }
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 6, 1).unwrap()
+    }
+
+    fn resolve(versions: &[RemoteVersion], query: &str) -> Option<(String, String)> {
+        resolve_alias(versions, None, query, today())
+            .map(|m| (m.version.version.to_string(), m.label))
+    }
+
+    #[test]
+    fn resolves_nvm_style_aliases() {
+        let versions = vec![
+            remote("v18.20.4", Some("hydrogen")),
+            remote("v20.18.0", Some("Iron")),
+            remote("v20.18.1", Some("Iron")),
+            remote("v22.12.0", Some("Jod")),
+            remote("v23.3.0", None),
+        ];
+        let pair = |version: &str, label: &str| Some((version.to_string(), label.to_string()));
+
+        assert_eq!(resolve(&versions, "latest"), pair("v23.3.0", "latest"));
+        assert_eq!(resolve(&versions, "Node"), pair("v23.3.0", "latest"));
+        assert_eq!(resolve(&versions, "lts"), pair("v22.12.0", "latest LTS"));
+        assert_eq!(
+            resolve(&versions, "lts/iron"),
+            pair("v20.18.1", "latest Iron")
+        );
+        assert_eq!(
+            resolve(&versions, "LTS/-1"),
+            pair("v20.18.1", "latest Iron")
+        );
+        assert_eq!(
+            resolve(&versions, "lts/-2"),
+            pair("v18.20.4", "latest Hydrogen")
+        );
+        assert_eq!(resolve(&versions, "lts/-3"), None);
+        assert_eq!(resolve(&versions, "lts/argon"), None);
+        assert_eq!(resolve(&versions, "22"), None);
+    }
+
+    #[test]
+    fn falls_back_to_schedule_codenames() {
+        let versions = vec![remote("v20.18.1", None), remote("v24.1.0", None)];
+        let schedule: std::collections::HashMap<u32, versi_core::VersionSchedule> =
+            serde_json::from_str(
+                r#"{
+                    "20": {"start": "2023-04-18", "lts": "2023-10-24", "end": "2026-04-30", "codename": "Iron"},
+                    "24": {"start": "2025-05-06", "lts": "2025-10-28", "end": "2028-04-30", "codename": "Krypton"}
+                }"#,
+            )
+            .unwrap();
+        let schedule = ReleaseSchedule { versions: schedule };
+
+        let resolved = resolve_alias(&versions, Some(&schedule), "lts/*", today()).unwrap();
+        assert_eq!(resolved.version.version.to_string(), "v20.18.1");
+        assert!(resolve_alias(&versions, Some(&schedule), "lts/krypton", today()).is_none());
+    }
+
+    #[test]
+    fn lists_install_candidates_newest_first() {
+        let versions = vec![
+            remote("v20.11.1", Some("Iron")),
+            remote("v20.12.0", Some("Iron")),
+            remote("v20.12.2", Some("Iron")),
+            remote("v22.1.0", None),
+        ];
+        let candidates = |spec: &str| -> Vec<String> {
+            install_candidates(&versions, None, spec, today())
+                .into_iter()
+                .map(ToString::to_string)
+                .collect()
+        };
+
+        assert_eq!(candidates("20"), ["v20.12.2", "v20.12.0", "v20.11.1"]);
+        assert_eq!(candidates("v20.12"), ["v20.12.2", "v20.12.0"]);
+        assert_eq!(candidates("lts/iron"), ["v20.12.2", "v20.12.0", "v20.11.1"]);
+        assert_eq!(candidates("latest"), ["v22.1.0"]);
+        assert!(candidates("19").is_empty());
+        assert!(is_partial_version("v20.12"));
+        assert!(!is_partial_version("20.12.2"));
+        assert!(!is_partial_version("lts/iron"));
+    }
+}
@@ -1,7 +1,9 @@
-//! Application self-update: download, apply, and restart.
+//! Application self-update: download, apply, and restart, or put the update
+//! off.
 //!
 //! Handles messages: StartAppUpdate, AppUpdateProgress, AppUpdateExtracting,
-//! AppUpdateApplying, AppUpdateComplete, RestartApp
+//! AppUpdateApplying, AppUpdateComplete, RestartApp, RemindAppUpdateLater,
+//! SkipAppUpdate, ClearSkippedAppUpdate
 
 use iced::Task;
 use iced::futures::SinkExt;
@@ -36,6 +38,8 @@ impl Versi {
         };
 
         let url = url.clone();
+        let expected_size = update.download_size;
+        let expected_sha256 = update.download_sha256.clone();
         state.app_update_state = AppUpdateState::Downloading {
             downloaded: 0,
             total: update.download_size.unwrap_or(0),
@@ -50,7 +54,14 @@ impl Versi {
                     let (tx, mut rx) = tokio::sync::mpsc::channel(32);
 
                     let download_handle = tokio::spawn(async move {
-                        versi_core::auto_update::download_and_apply(&client, &url, tx).await
+                        versi_core::auto_update::download_and_apply(
+                            &client,
+                            &url,
+                            expected_size,
+                            expected_sha256.as_deref(),
+                            tx,
+                        )
+                        .await
                     });
 
                     while let Some(progress) = rx.recv().await {
@@ -77,6 +88,44 @@ impl Versi {
         )
     }
 
+    pub(super) fn handle_remind_app_update_later(&mut self) {
+        let AppState::Main(state) = &mut self.state else {
+            return;
+        };
+        if state.app_update.take().is_none() {
+            return;
+        }
+        self.settings
+            .update_deferral
+            .remind_later(chrono::Local::now().date_naive());
+        if let Err(e) = self.settings.save() {
+            log::error!("Failed to save settings: {e}");
+        }
+    }
+
+    pub(super) fn handle_skip_app_update(&mut self) {
+        let AppState::Main(state) = &mut self.state else {
+            return;
+        };
+        let Some(update) = state.app_update.take() else {
+            return;
+        };
+        info!("Skipping Versi {}", update.latest_version);
+        self.settings.update_deferral.skip(&update.latest_version);
+        if let Err(e) = self.settings.save() {
+            log::error!("Failed to save settings: {e}");
+        }
+    }
+
+    pub(super) fn handle_clear_skipped_app_update(&mut self) -> Task<Message> {
+        self.settings.update_deferral.skipped_version = None;
+        self.settings.update_deferral.remind_after = None;
+        if let Err(e) = self.settings.save() {
+            log::error!("Failed to save settings: {e}");
+        }
+        self.handle_check_for_app_update()
+    }
+
     pub(super) fn handle_app_update_progress(&mut self, downloaded: u64, total: u64) {
         if let AppState::Main(state) = &mut self.state {
             state.app_update_state = AppUpdateState::Downloading { downloaded, total };
@@ -0,0 +1,143 @@
+//! Manual overrides for where backends live, for installs that detection
+//! cannot find: the fnm binary and the nvm install directory. Also switches
+//! backends that support it to installing versions system-wide, and controls
+//! whether other managers' variables reach backend commands.
+//!
+//! Handles messages: PickFnmBinary, FnmBinaryPicked, ClearFnmBinary,
+//! PickNvmDir, NvmDirPicked, ClearNvmDir, SystemWideToggled,
+//! SanitizeBackendEnvToggled
+
+use log::error;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use iced::Task;
+
+use versi_backend::BackendProvider;
+
+use crate::message::Message;
+use crate::settings::AppSettings;
+use crate::state::AppState;
+
+use super::{Versi, init};
+
+impl Versi {
+    pub(super) fn handle_pick_fnm_binary(&mut self) -> Task<Message> {
+        Task::perform(
+            async {
+                rfd::AsyncFileDialog::new()
+                    .set_title("Select the fnm binary")
+                    .pick_file()
+                    .await
+                    .map(|handle| handle.path().to_path_buf())
+            },
+            Message::FnmBinaryPicked,
+        )
+    }
+
+    pub(super) fn handle_fnm_binary_picked(&mut self, path: Option<PathBuf>) -> Task<Message> {
+        match path {
+            Some(path) => self.set_fnm_binary(Some(path)),
+            None => Task::none(),
+        }
+    }
+
+    pub(super) fn set_fnm_binary(&mut self, path: Option<PathBuf>) -> Task<Message> {
+        if self.settings.fnm_binary == path {
+            return Task::none();
+        }
+        self.settings.fnm_binary = path;
+        self.replace_provider(Arc::new(fnm_provider(&self.settings)))
+    }
+
+    pub(super) fn handle_pick_nvm_dir(&mut self) -> Task<Message> {
+        Task::perform(
+            async {
+                rfd::AsyncFileDialog::new()
+                    .set_title("Select NVM_DIR")
+                    .pick_folder()
+                    .await
+                    .map(|handle| handle.path().to_path_buf())
+            },
+            Message::NvmDirPicked,
+        )
+    }
+
+    pub(super) fn handle_nvm_dir_picked(&mut self, path: Option<PathBuf>) -> Task<Message> {
+        match path {
+            Some(path) => self.set_nvm_dir(Some(path)),
+            None => Task::none(),
+        }
+    }
+
+    pub(super) fn set_nvm_dir(&mut self, path: Option<PathBuf>) -> Task<Message> {
+        if self.settings.nvm_dir == path {
+            return Task::none();
+        }
+        self.settings.nvm_dir = path;
+        self.replace_provider(Arc::new(nvm_provider(&self.settings)))
+    }
+
+    pub(super) fn handle_system_wide_toggled(&mut self, enabled: bool) -> Task<Message> {
+        if self.settings.system_wide == enabled {
+            return Task::none();
+        }
+        self.settings.system_wide = enabled;
+        self.replace_provider(Arc::new(fnm_provider(&self.settings)))
+    }
+
+    pub(super) fn handle_sanitize_backend_env_toggled(&mut self, enabled: bool) -> Task<Message> {
+        if self.settings.sanitize_backend_env == enabled {
+            return Task::none();
+        }
+        self.settings.sanitize_backend_env = enabled;
+        self.reload_backend_providers()
+    }
+
+    /// Rebuilds the fnm and nvm providers from the current settings.
+    pub(super) fn reload_backend_providers(&mut self) -> Task<Message> {
+        let nvm: Arc<dyn BackendProvider> = Arc::new(nvm_provider(&self.settings));
+        if self.provider.name() == nvm.name() {
+            self.provider = nvm.clone();
+        }
+        self.providers.insert(nvm.name(), nvm);
+        self.replace_provider(Arc::new(fnm_provider(&self.settings)))
+    }
+
+    /// Saves settings, swaps in `provider` and detects backends again, so
+    /// every command afterwards runs against the new location.
+    fn replace_provider(&mut self, provider: Arc<dyn BackendProvider>) -> Task<Message> {
+        if let Err(e) = self.settings.save() {
+            error!("Failed to save settings: {e}");
+        }
+
+        if self.provider.name() == provider.name() {
+            self.provider = provider.clone();
+        }
+        self.providers.insert(provider.name(), provider);
+
+        if matches!(self.state, AppState::Loading) {
+            return Task::none();
+        }
+        let all_providers = self.all_providers();
+        let preferred = self.settings.preferred_backend.clone();
+        self.state = AppState::Loading;
+        Task::perform(
+            init::initialize(all_providers, preferred),
+            Message::Initialized,
+        )
+    }
+}
+
+/// The fnm provider for the binary and system-wide mode in `settings`.
+pub(crate) fn fnm_provider(settings: &AppSettings) -> versi_fnm::FnmProvider {
+    versi_fnm::FnmProvider::with_binary(settings.fnm_binary.clone())
+        .with_system_wide_dir(settings.system_wide_fnm_dir())
+        .with_sanitized_env(settings.sanitize_backend_env)
+}
+
+/// The nvm provider for the `NVM_DIR` in `settings`.
+pub(crate) fn nvm_provider(settings: &AppSettings) -> versi_nvm::NvmProvider {
+    versi_nvm::NvmProvider::with_nvm_dir(settings.nvm_dir.clone())
+        .with_sanitized_env(settings.sanitize_backend_env)
+}
@@ -0,0 +1,113 @@
+//! Assisted uninstall of the native version manager: its Node versions,
+//! shell integration, data directory, and Windows environment variables
+//! and PATH entries.
+//!
+//! Handles messages: PlanBackendRemoval, BackendRemovalPlanned,
+//! ContinueBackendRemoval, ConfirmBackendRemoval, BackendRemovalFinished
+
+use log::{info, warn};
+
+use iced::Task;
+
+use versi_platform::EnvironmentId;
+
+use crate::backend_removal::{self, RemovalPlan, RemovalStep};
+use crate::message::Message;
+use crate::state::{AppState, BackendRemoval};
+
+use super::Versi;
+
+impl Versi {
+    pub(super) fn handle_plan_backend_removal(&mut self) -> Task<Message> {
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+        let env = state.active_environment();
+        if env.id != EnvironmentId::Native || state.settings_state.backend_removal.is_some() {
+            return Task::none();
+        }
+
+        let backend_name = state.backend_name;
+        let Some(provider) = self.providers.get(backend_name) else {
+            return Task::none();
+        };
+        let marker = provider.shell_config_marker().to_string();
+        let versions: Vec<String> = env
+            .installed_versions
+            .iter()
+            .map(|v| v.version.to_string())
+            .collect();
+        let data_dir = state.backend.backend_info().data_dir.clone();
+
+        state.settings_state.backend_removal = Some(BackendRemoval::Planning);
+        Task::perform(
+            async move { backend_removal::plan(backend_name, versions, &marker, data_dir) },
+            Message::BackendRemovalPlanned,
+        )
+    }
+
+    pub(super) fn handle_backend_removal_planned(&mut self, plan: RemovalPlan) {
+        if let AppState::Main(state) = &mut self.state
+            && matches!(
+                state.settings_state.backend_removal,
+                Some(BackendRemoval::Planning)
+            )
+        {
+            state.settings_state.backend_removal = Some(BackendRemoval::Review(plan));
+        }
+    }
+
+    pub(super) fn handle_continue_backend_removal(&mut self) {
+        if let AppState::Main(state) = &mut self.state
+            && let Some(BackendRemoval::Review(plan)) = state.settings_state.backend_removal.take()
+        {
+            state.settings_state.backend_removal = Some(BackendRemoval::Confirm(plan));
+        }
+    }
+
+    pub(super) fn handle_confirm_backend_removal(&mut self) -> Task<Message> {
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+        let plan = match state.settings_state.backend_removal.take() {
+            Some(BackendRemoval::Confirm(plan)) => plan,
+            other => {
+                state.settings_state.backend_removal = other;
+                return Task::none();
+            }
+        };
+        let Some(provider) = self.providers.get(plan.backend) else {
+            return Task::none();
+        };
+        let marker = provider.shell_config_marker().to_string();
+
+        info!(
+            "Uninstalling {}: {} versions, {} shell configs",
+            plan.backend,
+            plan.versions.len(),
+            plan.shell_configs.len()
+        );
+        state.settings_state.backend_removal = Some(BackendRemoval::Running);
+        let backend = state.backend.clone();
+        Task::perform(
+            backend_removal::execute(plan, backend, marker),
+            Message::BackendRemovalFinished,
+        )
+    }
+
+    pub(super) fn handle_backend_removal_finished(
+        &mut self,
+        steps: Vec<RemovalStep>,
+    ) -> Task<Message> {
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+        for step in &steps {
+            if let Some(error) = &step.error {
+                warn!("{} failed: {}", step.label, error);
+            }
+        }
+        state.settings_state.backend_removal = Some(BackendRemoval::Done(steps));
+        self.refresh_environment(&EnvironmentId::Native)
+    }
+}
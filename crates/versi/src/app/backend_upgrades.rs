@@ -0,0 +1,194 @@
+//! Upgrading the backend in every environment at once, when a newer
+//! release is out.
+//!
+//! Handles messages: UpgradeAllBackends, BackendUpgradeProgress,
+//! BackendUpgraded
+
+use log::{info, warn};
+
+use iced::Task;
+use iced::futures::SinkExt;
+use versi_backend::BackendError;
+use versi_core::is_newer_version;
+use versi_platform::EnvironmentId;
+
+use crate::message::Message;
+use crate::state::{AppState, BackendUpgrade, BackendUpgradeOutcome, Modal};
+
+use super::Versi;
+
+impl Versi {
+    /// Upgrades the backend in each available environment that runs an
+    /// older release than the latest one, all at once.
+    pub(super) fn handle_upgrade_all_backends(&mut self) -> Task<Message> {
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+        let Some(update) = &state.backend_update else {
+            return Task::none();
+        };
+        if let Some(Modal::BackendUpgrades { upgrades, .. }) = &state.modal
+            && upgrades
+                .iter()
+                .any(|u| u.outcome == BackendUpgradeOutcome::Running)
+        {
+            return Task::none();
+        }
+
+        let version = update.latest_version.clone();
+        let mut upgrades = Vec::new();
+        let mut tasks = Vec::new();
+        for env in &state.environments {
+            // Git Bash and MSYS2 run a backend installed on Windows, which is
+            // upgraded from its own environment.
+            if !env.available
+                || matches!(env.id, EnvironmentId::Msys { .. })
+                || env.backend_name != state.backend_name
+                || !is_outdated(env.backend_version.as_deref(), &version)
+            {
+                continue;
+            }
+            let Some(provider) = self.providers.get(env.backend_name).cloned() else {
+                continue;
+            };
+
+            info!(
+                "Upgrading {} in {} to {}",
+                env.backend_name, env.name, version
+            );
+            upgrades.push(BackendUpgrade {
+                env_id: env.id.clone(),
+                name: env.name.clone(),
+                from: env.backend_version.clone(),
+                outcome: BackendUpgradeOutcome::Running,
+                progress: None,
+            });
+            let env_id = env.id.clone();
+            let version = version.clone();
+            tasks.push(Task::run(
+                iced::stream::channel(
+                    32,
+                    move |mut sender: iced::futures::channel::mpsc::Sender<Message>| async move {
+                        let (tx, mut rx) = tokio::sync::mpsc::channel(32);
+                        let upgrade_env = env_id.clone();
+                        let upgrade_version = version.clone();
+                        let upgrade = tokio::spawn(async move {
+                            match &upgrade_env {
+                                EnvironmentId::Native => {
+                                    provider.update_backend(&upgrade_version, tx).await
+                                }
+                                EnvironmentId::Wsl {
+                                    distro,
+                                    backend_path,
+                                } => {
+                                    provider
+                                        .update_backend_in_wsl(
+                                            distro,
+                                            backend_path,
+                                            &upgrade_version,
+                                            tx,
+                                        )
+                                        .await
+                                }
+                                EnvironmentId::Msys { .. } => {
+                                    Err(BackendError::Unsupported(format!(
+                                        "updating {} inside {}",
+                                        provider.name(),
+                                        upgrade_env.display_name()
+                                    )))
+                                }
+                            }
+                        });
+
+                        while let Some(line) = rx.recv().await {
+                            let _ = sender
+                                .send(Message::BackendUpgradeProgress {
+                                    env_id: env_id.clone(),
+                                    line,
+                                })
+                                .await;
+                        }
+
+                        let result = match upgrade.await {
+                            Ok(result) => result.map_err(|e| e.to_string()),
+                            Err(e) => Err(format!("Upgrade task panicked: {e}")),
+                        };
+                        let _ = sender
+                            .send(Message::BackendUpgraded {
+                                env_id,
+                                version,
+                                result,
+                            })
+                            .await;
+                    },
+                ),
+                std::convert::identity,
+            ));
+        }
+
+        state.modal = Some(Modal::BackendUpgrades {
+            backend: state.backend_name,
+            version,
+            upgrades,
+        });
+        Task::batch(tasks)
+    }
+
+    pub(super) fn handle_backend_upgrade_progress(&mut self, env_id: EnvironmentId, line: String) {
+        if let AppState::Main(state) = &mut self.state
+            && let Some(Modal::BackendUpgrades { upgrades, .. }) = &mut state.modal
+            && let Some(upgrade) = upgrades.iter_mut().find(|u| u.env_id == env_id)
+            && !line.trim().is_empty()
+        {
+            upgrade.progress = Some(line);
+        }
+    }
+
+    pub(super) fn handle_backend_upgraded(
+        &mut self,
+        env_id: EnvironmentId,
+        version: String,
+        result: Result<String, String>,
+    ) {
+        let AppState::Main(state) = &mut self.state else {
+            return;
+        };
+
+        match &result {
+            Ok(how) => {
+                info!("Upgraded the backend in {}: {}", env_id.display_name(), how);
+                if let Some(env) = state.environments.iter_mut().find(|e| e.id == env_id) {
+                    env.backend_version = Some(version.clone());
+                }
+                let backend = state.backend_name;
+                let all_upgraded = state
+                    .environments
+                    .iter()
+                    .filter(|e| e.available && e.backend_name == backend)
+                    .all(|e| !is_outdated(e.backend_version.as_deref(), &version));
+                if all_upgraded {
+                    state.backend_update = None;
+                }
+            }
+            Err(e) => warn!(
+                "Failed to upgrade the backend in {}: {}",
+                env_id.display_name(),
+                e
+            ),
+        }
+
+        if let Some(Modal::BackendUpgrades { upgrades, .. }) = &mut state.modal
+            && let Some(upgrade) = upgrades.iter_mut().find(|u| u.env_id == env_id)
+        {
+            upgrade.outcome = match result {
+                Ok(how) => BackendUpgradeOutcome::Upgraded(how),
+                Err(e) => BackendUpgradeOutcome::Failed(e),
+            };
+        }
+    }
+}
+
+/// An environment whose backend version is unknown counts as outdated.
+fn is_outdated(current: Option<&str>, latest: &str) -> bool {
+    current.is_none_or(|current| is_newer_version(latest, current.trim_start_matches('v')))
+}
@@ -1,15 +1,17 @@
 use iced::Task;
+use log::info;
 
 use crate::message::Message;
-use crate::state::{AppState, Modal, OperationRequest};
+use crate::snapshots::{self, EnvironmentSnapshot};
+use crate::state::{AppState, MainState, Modal, OperationRequest};
 
-use super::Versi;
+use super::{Versi, protection};
 
 impl Versi {
     pub(super) fn handle_request_bulk_update_majors(&mut self) -> Task<Message> {
         if let AppState::Main(state) = &mut self.state {
             let env = state.active_environment();
-            let remote = &state.available_versions.versions;
+            let remote = &state.available_versions.remote().versions;
 
             let latest_remote_by_major: std::collections::HashMap<u32, versi_backend::NodeVersion> = {
                 let mut latest = std::collections::HashMap::new();
@@ -84,6 +86,7 @@ impl Versi {
                         .unwrap_or(false)
                 })
                 .map(|v| v.version.to_string())
+                .filter(|v| !self.settings.is_protected(&env.id, v))
                 .collect();
 
             if eol_versions.is_empty() {
@@ -106,6 +109,7 @@ impl Versi {
                 .iter()
                 .filter(|v| v.version.major == major)
                 .map(|v| v.version.to_string())
+                .filter(|v| !self.settings.is_protected(&env.id, v))
                 .collect();
 
             if versions.is_empty() {
@@ -121,6 +125,7 @@ impl Versi {
         if let AppState::Main(state) = &mut self.state
             && let Some(Modal::ConfirmBulkUpdateMajors { versions }) = state.modal.take()
         {
+            record_snapshot(state);
             for (_from, to) in versions {
                 state
                     .operation_queue
@@ -135,6 +140,7 @@ impl Versi {
         if let AppState::Main(state) = &mut self.state
             && let Some(Modal::ConfirmBulkUninstallEOL { versions }) = state.modal.take()
         {
+            record_snapshot(state);
             for version in versions {
                 state
                     .operation_queue
@@ -151,6 +157,7 @@ impl Versi {
                 state.modal.take()
             && m == major
         {
+            record_snapshot(state);
             for version in versions {
                 state
                     .operation_queue
@@ -189,7 +196,11 @@ impl Versi {
                 .iter()
                 .skip(1)
                 .map(|v| v.version.to_string())
+                .filter(|v| !self.settings.is_protected(&env.id, v))
                 .collect();
+            if versions.is_empty() {
+                return Task::none();
+            }
 
             state.modal = Some(Modal::ConfirmBulkUninstallMajorExceptLatest {
                 major,
@@ -210,6 +221,7 @@ impl Versi {
             }) = state.modal.take()
             && m == major
         {
+            record_snapshot(state);
             for version in versions {
                 state
                     .operation_queue
@@ -219,4 +231,169 @@ impl Versi {
         }
         Task::none()
     }
+
+    /// Queues the operations that undo the latest bulk operation in the
+    /// active environment.
+    pub(super) fn handle_roll_back_environment(&mut self) -> Task<Message> {
+        if let AppState::Main(state) = &mut self.state {
+            state.modal = None;
+            let env_id = state.active_environment().id.clone();
+            let Some(snapshot) = state.snapshots.take_latest(&env_id) else {
+                return Task::none();
+            };
+
+            let plan = protection::without_protected(
+                &self.settings,
+                &env_id,
+                snapshots::plan_rollback(&snapshot, state.active_environment()),
+            );
+            // The button is disabled then, so this only happens if the
+            // environment changed since the summary was drawn.
+            if plan.is_empty() {
+                info!("Nothing to roll back in {}", env_id.display_name());
+                return Task::none();
+            }
+
+            info!(
+                "Rolling back {} with {} operation(s)",
+                env_id.display_name(),
+                plan.len()
+            );
+            for request in plan {
+                state.operation_queue.enqueue(request);
+            }
+            return self.process_next_operation();
+        }
+        Task::none()
+    }
+
+    pub(super) fn handle_version_selection_toggled(&mut self, version: String) {
+        if let AppState::Main(state) = &mut self.state
+            && !state.selected_versions.remove(&version)
+        {
+            state.selected_versions.insert(version);
+        }
+    }
+
+    /// Clicks on an installed version's row: Ctrl (Cmd on macOS) toggles it
+    /// in the selection and Alt makes it the default.
+    pub(super) fn handle_version_row_clicked(&mut self, version: String) -> Task<Message> {
+        let AppState::Main(state) = &self.state else {
+            return Task::none();
+        };
+        #[cfg(target_os = "macos")]
+        let cmd = state.modifiers.command();
+        #[cfg(not(target_os = "macos"))]
+        let cmd = state.modifiers.control();
+
+        if cmd {
+            self.handle_version_selection_toggled(version);
+            Task::none()
+        } else if state.modifiers.alt() {
+            let is_default = state
+                .active_environment()
+                .default_version
+                .as_ref()
+                .is_some_and(|dv| dv.to_string() == version);
+            if is_default {
+                return Task::none();
+            }
+            // Goes back through `update` so read-only mode still applies.
+            Task::done(Message::SetDefault(version))
+        } else {
+            Task::none()
+        }
+    }
+
+    /// Asks to uninstall the selected versions. A selected default version
+    /// is left out, since uninstalling it needs its own confirmation; when
+    /// it is the only one selected, that confirmation is shown instead.
+    pub(super) fn handle_request_bulk_uninstall_selected(&mut self) -> Task<Message> {
+        if let AppState::Main(state) = &mut self.state {
+            let env = state.active_environment();
+            let default = env.default_version.as_ref().map(|v| v.to_string());
+            let (defaults, versions): (Vec<String>, Vec<String>) =
+                selected_installed_versions(state)
+                    .into_iter()
+                    .filter(|v| !self.settings.is_protected(&env.id, v))
+                    .partition(|v| default.as_ref() == Some(v));
+            let kept_default = defaults.into_iter().next();
+            if versions.is_empty() {
+                return match kept_default {
+                    Some(default) => self.handle_uninstall(default),
+                    None => Task::none(),
+                };
+            }
+
+            state.modal = Some(Modal::ConfirmBulkUninstallSelected {
+                versions,
+                kept_default,
+            });
+        }
+        Task::none()
+    }
+
+    pub(super) fn handle_confirm_bulk_uninstall_selected(&mut self) -> Task<Message> {
+        if let AppState::Main(state) = &mut self.state
+            && let Some(Modal::ConfirmBulkUninstallSelected { versions, .. }) = state.modal.take()
+        {
+            record_snapshot(state);
+            for version in versions {
+                state
+                    .operation_queue
+                    .enqueue(OperationRequest::Uninstall { version });
+            }
+            return self.process_next_operation();
+        }
+        Task::none()
+    }
+
+    pub(super) fn handle_compare_selected_versions(&mut self) -> Task<Message> {
+        let AppState::Main(state) = &self.state else {
+            return Task::none();
+        };
+
+        let versions = selected_installed_versions(state);
+        let [newer, older] = versions.as_slice() else {
+            return Task::none();
+        };
+
+        let url = format!("https://github.com/nodejs/node/compare/{older}...{newer}");
+        Task::perform(
+            async move {
+                let _ = open::that(&url);
+            },
+            |_| Message::NoOp,
+        )
+    }
+
+    pub(super) fn handle_copy_selected_versions(&mut self) -> Task<Message> {
+        let AppState::Main(state) = &self.state else {
+            return Task::none();
+        };
+
+        let versions = selected_installed_versions(state);
+        if versions.is_empty() {
+            return Task::none();
+        }
+
+        iced::clipboard::write(versions.join("\n"))
+    }
+}
+
+pub(super) fn record_snapshot(state: &mut MainState) {
+    let snapshot = EnvironmentSnapshot::capture(state.active_environment());
+    state.snapshots.record(snapshot);
+}
+
+fn selected_installed_versions(state: &MainState) -> Vec<String> {
+    let mut versions: Vec<&versi_backend::NodeVersion> = state
+        .active_environment()
+        .installed_versions
+        .iter()
+        .filter(|v| state.selected_versions.contains(&v.version.to_string()))
+        .map(|v| &v.version)
+        .collect();
+    versions.sort_by(|a, b| b.cmp(a));
+    versions.into_iter().map(|v| v.to_string()).collect()
 }
@@ -0,0 +1,117 @@
+//! Moving the cache and log directories to locations picked in settings,
+//! taking the files already written there along.
+//!
+//! Handles messages: PickDataDir, DataDirPicked, ResetDataDir
+
+use log::{error, info};
+use std::path::PathBuf;
+
+use iced::Task;
+
+use versi_platform::AppPaths;
+
+use crate::logging;
+use crate::message::Message;
+use crate::settings::DataDir;
+use crate::state::{AppState, Toast};
+
+use super::Versi;
+
+impl Versi {
+    pub(super) fn handle_pick_data_dir(&mut self, dir: DataDir) -> Task<Message> {
+        Task::perform(
+            async move {
+                rfd::AsyncFileDialog::new()
+                    .set_title(format!("Select the {} directory", dir.label()))
+                    .pick_folder()
+                    .await
+                    .map(|handle| handle.path().to_path_buf())
+            },
+            move |path| Message::DataDirPicked(dir, path),
+        )
+    }
+
+    pub(super) fn handle_data_dir_picked(
+        &mut self,
+        dir: DataDir,
+        path: Option<PathBuf>,
+    ) -> Task<Message> {
+        if let Some(path) = path {
+            self.set_data_dir(dir, Some(path));
+        }
+        Task::none()
+    }
+
+    /// Points `dir` at `path`, or back at the default with `None`, and moves
+    /// the files Versi keeps there.
+    pub(super) fn set_data_dir(&mut self, dir: DataDir, path: Option<PathBuf>) {
+        if self.settings.data_dir(dir) == path.as_ref() {
+            return;
+        }
+        if let Some(path) = &path
+            && let Err(e) = versi_platform::validate_custom_dir(path)
+        {
+            error!(
+                "Cannot use {} for the {} directory: {}",
+                path.display(),
+                dir.label(),
+                e
+            );
+            self.show_data_dir_error(e);
+            return;
+        }
+
+        let Ok(before) = AppPaths::new() else {
+            return;
+        };
+        *self.settings.data_dir_mut(dir) = path;
+        AppPaths::set_custom_dirs(self.settings.custom_dirs());
+        let Ok(after) = AppPaths::new() else {
+            return;
+        };
+
+        let moved = match dir {
+            DataDir::Cache => {
+                let (from, to) = (before.version_cache_file(), after.version_cache_file());
+                if from != to && from.exists() {
+                    versi_platform::move_file(&from, &to)
+                } else {
+                    Ok(())
+                }
+            }
+            DataDir::Logs => logging::move_log_file(&before.log_file(), &after.log_file()),
+        };
+        match moved {
+            Ok(()) => info!(
+                "Moved the {} directory to {}",
+                dir.label(),
+                match dir {
+                    DataDir::Cache => after.cache_dir.display(),
+                    DataDir::Logs => after.log_dir.display(),
+                }
+            ),
+            Err(e) => {
+                error!("Failed to move {} files: {}", dir.label(), e);
+                self.show_data_dir_error(format!(
+                    "Files in the old {} directory could not be moved: {}",
+                    dir.label(),
+                    e
+                ));
+            }
+        }
+
+        if let Err(e) = self.settings.save() {
+            error!("Failed to save settings: {e}");
+        }
+    }
+
+    fn show_data_dir_error(&mut self, message: String) {
+        if let AppState::Main(state) = &mut self.state {
+            let id = state.next_toast_id();
+            state.add_toast(
+                Toast::error(id, message),
+                &self.settings.notification_delivery,
+            );
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! Debug overlay listing recently dispatched messages.
+//!
+//! Handles messages: EventLogToggled, SaveEventLog, EventLogSaved
+
+use log::info;
+use std::path::PathBuf;
+
+use iced::Task;
+
+use crate::message::Message;
+use crate::state::{AppState, Toast};
+
+use super::Versi;
+
+impl Versi {
+    pub(super) fn handle_event_log_toggled(&mut self) -> Task<Message> {
+        self.settings.show_event_log = !self.settings.show_event_log;
+        if let Err(e) = self.settings.save() {
+            log::error!("Failed to save settings: {e}");
+        }
+        Task::none()
+    }
+
+    pub(super) fn handle_save_event_log(&mut self) -> Task<Message> {
+        let content = self.event_log.dump();
+        let file_name = format!(
+            "versi-events-{}.log",
+            chrono::Local::now().format("%Y-%m-%d-%H%M%S")
+        );
+
+        Task::perform(
+            async move {
+                let handle = rfd::AsyncFileDialog::new()
+                    .set_file_name(&file_name)
+                    .add_filter("Log", &["log", "txt"])
+                    .save_file()
+                    .await
+                    .ok_or_else(|| "Cancelled".to_string())?;
+                let path = handle.path().to_path_buf();
+                tokio::fs::write(&path, content)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(path)
+            },
+            Message::EventLogSaved,
+        )
+    }
+
+    pub(super) fn handle_event_log_saved(&mut self, result: Result<PathBuf, String>) {
+        match result {
+            Ok(path) => info!("Saved event log to {:?}", path),
+            Err(e) if e != "Cancelled" => {
+                if let AppState::Main(state) = &mut self.state {
+                    let id = state.next_toast_id();
+                    state.add_toast(
+                        Toast::error(id, format!("Failed to save event log: {}", e)),
+                        &self.settings.notification_delivery,
+                    );
+                }
+            }
+            Err(_) => {}
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! Noticing when the default version is removed outside Versi, such as
+//! with `fnm uninstall` or by deleting its directory, so that a
+//! replacement can be picked from the banner.
+//!
+//! Handles messages: DismissMissingDefault
+
+use log::{error, info, warn};
+
+use versi_platform::EnvironmentId;
+
+use crate::state::AppState;
+
+use super::Versi;
+
+impl Versi {
+    /// Remembers the default of `env_id` after a load, or flags it as
+    /// missing when the remembered one is no longer installed.
+    pub(super) fn track_default(&mut self, env_id: &EnvironmentId) {
+        let AppState::Main(state) = &mut self.state else {
+            return;
+        };
+        let Some(env) = state.environments.iter_mut().find(|e| &e.id == env_id) else {
+            return;
+        };
+        let key = env_id.settings_key();
+
+        match &env.default_version {
+            Some(default) => {
+                let default = default.to_string();
+                if self.settings.known_defaults.get(&key) == Some(&default) {
+                    return;
+                }
+                self.settings.known_defaults.insert(key, default);
+            }
+            None => {
+                let missing = self
+                    .settings
+                    .known_defaults
+                    .get(&key)
+                    .filter(|known| !env.installed_set.contains(*known));
+                if missing.is_some() && env.missing_default.as_ref() != missing {
+                    warn!(
+                        "Default Node {} is no longer installed in {}",
+                        missing.map(String::as_str).unwrap_or_default(),
+                        env.name
+                    );
+                }
+                env.missing_default = missing.cloned();
+                return;
+            }
+        }
+        if let Err(e) = self.settings.save() {
+            error!("Failed to save settings: {e}");
+        }
+    }
+
+    /// Forgets the default of `env_id` when Versi itself uninstalled it, as
+    /// that was already confirmed.
+    pub(super) fn forget_default(&mut self, env_id: &EnvironmentId, version: &str) {
+        let key = env_id.settings_key();
+        if self.settings.known_defaults.get(&key).map(String::as_str) != Some(version) {
+            return;
+        }
+        self.settings.known_defaults.remove(&key);
+        if let Err(e) = self.settings.save() {
+            error!("Failed to save settings: {e}");
+        }
+    }
+
+    pub(super) fn handle_dismiss_missing_default(&mut self) {
+        let AppState::Main(state) = &mut self.state else {
+            return;
+        };
+        let env = state.active_environment_mut();
+        let Some(missing) = env.missing_default.take() else {
+            return;
+        };
+        info!("Dismissed missing default Node {} in {}", missing, env.name);
+        let env_id = env.id.clone();
+        self.forget_default(&env_id, &missing);
+    }
+}
@@ -0,0 +1,121 @@
+//! Per-environment environment variable overrides for backend commands.
+//!
+//! Edits go to a draft that is saved when a field is submitted, a row is
+//! removed or the user leaves the page, and only if every name is valid.
+//!
+//! Handles messages: EnvVarAdded, EnvVarKeyChanged, EnvVarValueChanged,
+//! EnvVarRemoved, EnvVarsSubmitted
+
+use iced::Task;
+
+use crate::message::Message;
+use crate::settings::EnvVarOverride;
+use crate::state::{AppState, EnvVarDraft};
+
+use super::Versi;
+
+impl Versi {
+    pub(super) fn handle_env_var_added(&mut self) -> Task<Message> {
+        self.edit_env_var_draft(|vars| vars.push(EnvVarOverride::default()));
+        Task::none()
+    }
+
+    pub(super) fn handle_env_var_key_changed(
+        &mut self,
+        index: usize,
+        key: String,
+    ) -> Task<Message> {
+        self.edit_env_var_draft(|vars| {
+            if let Some(var) = vars.get_mut(index) {
+                var.key = key;
+            }
+        });
+        Task::none()
+    }
+
+    pub(super) fn handle_env_var_value_changed(
+        &mut self,
+        index: usize,
+        value: String,
+    ) -> Task<Message> {
+        self.edit_env_var_draft(|vars| {
+            if let Some(var) = vars.get_mut(index) {
+                var.value = value;
+            }
+        });
+        Task::none()
+    }
+
+    pub(super) fn handle_env_var_removed(&mut self, index: usize) -> Task<Message> {
+        self.edit_env_var_draft(|vars| {
+            if index < vars.len() {
+                vars.remove(index);
+            }
+        });
+        self.commit_env_var_draft();
+        Task::none()
+    }
+
+    /// Saves the draft and hands it to the backend. A draft with an invalid
+    /// name stays open so the editor can point at it.
+    pub(super) fn commit_env_var_draft(&mut self) {
+        let AppState::Main(state) = &mut self.state else {
+            return;
+        };
+        if !state
+            .settings_state
+            .env_var_draft
+            .as_ref()
+            .is_some_and(EnvVarDraft::is_valid)
+        {
+            return;
+        }
+        let Some(draft) = state.settings_state.env_var_draft.take() else {
+            return;
+        };
+
+        *self.settings.env_vars_for_mut(&draft.env_id) = draft
+            .vars
+            .into_iter()
+            .map(|var| EnvVarOverride {
+                key: var.key.trim().to_string(),
+                value: var.value,
+            })
+            .collect();
+        if let Err(e) = self.settings.save() {
+            log::error!("Failed to save settings: {e}");
+        }
+
+        let env = state.active_environment();
+        if env.id == draft.env_id {
+            let target = env.target;
+            state.backend.set_env_overrides(
+                self.settings
+                    .env_overrides_for(&draft.env_id, target.as_ref()),
+            );
+        }
+    }
+
+    fn edit_env_var_draft(&mut self, edit: impl FnOnce(&mut Vec<EnvVarOverride>)) {
+        let AppState::Main(state) = &mut self.state else {
+            return;
+        };
+
+        let env_id = state.active_environment().id.clone();
+        let draft = &mut state.settings_state.env_var_draft;
+        if draft.as_ref().is_none_or(|draft| draft.env_id != env_id) {
+            *draft = Some(EnvVarDraft {
+                vars: self
+                    .settings
+                    .environment_variables
+                    .get(&env_id.settings_key())
+                    .cloned()
+                    .unwrap_or_default(),
+                env_id,
+            });
+        }
+        if let Some(draft) = draft {
+            edit(&mut draft.vars);
+        }
+    }
+}
@@ -1,45 +1,97 @@
 //! Environment switching, version loading, and search.
 //!
-//! Handles messages: EnvironmentSelected, EnvironmentLoaded, RefreshEnvironment,
-//! VersionGroupToggled, SearchChanged
+//! Handles messages: EnvironmentSelected, EnvironmentLoaded, ArchitecturesDetected,
+//! ActiveVersionDetected, RefreshEnvironment, RetryEnvironmentLoad, VersionGroupToggled, SearchChanged, SearchSubmitted, SearchFilterPinned,
+//! SearchFilterUnpinned, SearchHistoryCleared, SearchAllEnvironmentsToggled, ShowInEnvironment,
+//! UninstallFromEnvironment
 
-use std::time::Duration;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use log::{debug, info, trace};
+use tokio::sync::Semaphore;
+
+use log::{debug, info, trace, warn};
 
 use iced::Task;
 
-use versi_platform::EnvironmentId;
+use versi_backend::{NodeVersion, VersionManager};
+use versi_platform::{BinaryArch, EnvironmentId, TargetOs};
 
 use crate::message::Message;
-use crate::state::{AppState, MainViewKind};
+use crate::metrics::{self, OperationKind};
+use crate::state::{AppState, BackendRemoval, EnvironmentError, MainViewKind};
 
 use super::Versi;
 use super::init::create_backend_for_environment;
 
+/// Environments loaded at the same time; each WSL distro load spawns its own
+/// `wsl.exe`, so loading all of them at once mostly adds contention.
+pub(super) const MAX_PARALLEL_LOADS: usize = 4;
+
+/// Lists the installed versions of one environment once a load permit is free.
+pub(super) fn load_installed_task(
+    env_id: EnvironmentId,
+    backend: Box<dyn VersionManager>,
+    permits: Arc<Semaphore>,
+    fetch_timeout: Duration,
+) -> Task<Message> {
+    Task::perform(
+        async move {
+            let _permit = permits.acquire_owned().await.ok();
+            debug!("Fetching installed versions for {:?}...", env_id);
+            let started = Instant::now();
+            let result = match tokio::time::timeout(fetch_timeout, backend.list_installed()).await {
+                Ok(result) => result.map_err(|e| e.to_string()),
+                Err(_) => Err("Listing installed versions timed out".to_string()),
+            };
+            metrics::record(
+                OperationKind::Refresh,
+                &env_id,
+                "installed versions".to_string(),
+                started.elapsed(),
+            );
+            (env_id, result)
+        },
+        |(env_id, result)| Message::EnvironmentLoaded { env_id, result },
+    )
+}
+
 impl Versi {
     pub(super) fn handle_environment_loaded(
         &mut self,
         env_id: EnvironmentId,
-        versions: Vec<versi_backend::InstalledVersion>,
+        result: Result<Vec<versi_backend::InstalledVersion>, String>,
     ) -> Task<Message> {
-        info!(
-            "Environment loaded: {:?} with {} versions",
-            env_id,
-            versions.len()
-        );
-        for v in &versions {
-            trace!(
-                "  Installed version: {} (default={})",
-                v.version, v.is_default
-            );
-        }
+        let retry_task = match result {
+            Ok(versions) => {
+                info!(
+                    "Environment loaded: {:?} with {} versions",
+                    env_id,
+                    versions.len()
+                );
+                for v in &versions {
+                    trace!(
+                        "  Installed version: {} (default={})",
+                        v.version, v.is_default
+                    );
+                }
 
-        if let AppState::Main(state) = &mut self.state
-            && let Some(env) = state.environments.iter_mut().find(|e| e.id == env_id)
-        {
-            env.update_versions(versions);
-        }
+                if let AppState::Main(state) = &mut self.state
+                    && let Some(env) = state.environments.iter_mut().find(|e| e.id == env_id)
+                {
+                    env.update_versions(versions);
+                }
+                self.track_default(&env_id);
+                self.announce_support_warnings();
+                Task::batch([
+                    self.refresh_project_pin(),
+                    self.detect_architectures(&env_id),
+                    self.detect_active_version(&env_id),
+                ])
+            }
+            Err(error) => self.environment_load_failed(env_id, error),
+        };
         self.update_tray_menu();
 
         if self.pending_minimize
@@ -52,13 +104,165 @@ impl Versi {
             } else {
                 iced::window::set_mode(id, iced::window::Mode::Hidden)
             };
-            return Task::batch([Task::done(Message::HideDockIcon), hide_task]);
+            return Task::batch([retry_task, Task::done(Message::HideDockIcon), hide_task]);
         }
 
-        Task::none()
+        retry_task
+    }
+
+    /// Reads which architecture each installed version was built for. Only
+    /// Macs can run both, so other platforms skip it.
+    fn detect_architectures(&self, env_id: &EnvironmentId) -> Task<Message> {
+        let AppState::Main(state) = &self.state else {
+            return Task::none();
+        };
+        let env = state.active_environment();
+        if &env.id != env_id || !env.target.is_some_and(|t| t.os == TargetOs::MacOs) {
+            return Task::none();
+        }
+
+        let versions: Vec<String> = env
+            .installed_versions
+            .iter()
+            .map(|v| v.version.to_string())
+            .collect();
+        let backend = state.backend.clone();
+        let env_id = env_id.clone();
+
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    let architectures = versions
+                        .into_iter()
+                        .filter_map(|version| {
+                            let binary = backend.node_binary(&version)?;
+                            Some((version, versi_platform::binary_arch(&binary)?))
+                        })
+                        .collect();
+                    (versi_platform::host_arch(), architectures)
+                })
+                .await
+                .unwrap_or_default()
+            },
+            move |(host, architectures)| Message::ArchitecturesDetected {
+                env_id: env_id.clone(),
+                host,
+                architectures,
+            },
+        )
+    }
+
+    /// Asks the backend which version the last shell used, which only
+    /// matches the default until something runs `use`.
+    fn detect_active_version(&self, env_id: &EnvironmentId) -> Task<Message> {
+        let Some(backend) = self.backend_for_environment(env_id) else {
+            return Task::none();
+        };
+        let env_id = env_id.clone();
+        let fetch_timeout = Duration::from_secs(self.settings.fetch_timeout_secs);
+
+        Task::perform(
+            async move {
+                match tokio::time::timeout(fetch_timeout, backend.current_version()).await {
+                    Ok(Ok(version)) => version,
+                    Ok(Err(e)) => {
+                        debug!("Failed to read the active version: {}", e);
+                        None
+                    }
+                    Err(_) => None,
+                }
+            },
+            move |version| Message::ActiveVersionDetected {
+                env_id: env_id.clone(),
+                version,
+            },
+        )
+    }
+
+    pub(super) fn handle_active_version_detected(
+        &mut self,
+        env_id: EnvironmentId,
+        version: Option<NodeVersion>,
+    ) {
+        if let AppState::Main(state) = &mut self.state
+            && let Some(env) = state.environments.iter_mut().find(|e| e.id == env_id)
+        {
+            env.active_version = version;
+        }
+    }
+
+    pub(super) fn handle_architectures_detected(
+        &mut self,
+        env_id: EnvironmentId,
+        host: Option<BinaryArch>,
+        architectures: HashMap<String, BinaryArch>,
+    ) {
+        let AppState::Main(state) = &mut self.state else {
+            return;
+        };
+        let Some(env) = state.environments.iter_mut().find(|e| e.id == env_id) else {
+            return;
+        };
+        let foreign: Vec<&String> = architectures
+            .iter()
+            .filter(|(_, arch)| host.is_some_and(|host| !arch.runs_natively_on(host)))
+            .map(|(version, _)| version)
+            .collect();
+        if !foreign.is_empty() {
+            info!("Versions built for another architecture: {:?}", foreign);
+        }
+        env.host_arch = host;
+        env.architectures = architectures;
+    }
+
+    /// Records a failed load and schedules another attempt with the
+    /// network retry policy's backoff until its retries are used up.
+    fn environment_load_failed(&mut self, env_id: EnvironmentId, error: String) -> Task<Message> {
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+        let Some(env) = state.environments.iter_mut().find(|e| e.id == env_id) else {
+            return Task::none();
+        };
+        warn!("Failed to load environment {:?}: {}", env_id, error);
+        env.loading = false;
+        env.error = Some(EnvironmentError::ListFailed(error));
+
+        let policy = &self.settings.retry_policy;
+        if env.load_attempts >= policy.retries {
+            return Task::none();
+        }
+        env.load_attempts += 1;
+        let delay = policy.delay(env.load_attempts);
+        env.retry_in = Some(delay);
+        debug!(
+            "Retrying {:?} in {}s (attempt {} of {})",
+            env_id,
+            delay.as_secs(),
+            env.load_attempts,
+            policy.retries
+        );
+        Task::perform(tokio::time::sleep(delay), move |()| {
+            Message::RetryEnvironmentLoad(env_id.clone())
+        })
+    }
+
+    pub(super) fn handle_retry_environment_load(&mut self, env_id: EnvironmentId) -> Task<Message> {
+        let still_failing = matches!(
+            &self.state,
+            AppState::Main(state) if state
+                .environments
+                .iter()
+                .any(|e| e.id == env_id && !e.loading && e.error.is_some())
+        );
+        if !still_failing {
+            return Task::none();
+        }
+        self.refresh_environment(&env_id)
     }
 
     pub(super) fn handle_environment_selected(&mut self, idx: usize) -> Task<Message> {
+        self.commit_env_var_draft();
         if let AppState::Main(state) = &mut self.state {
             if idx >= state.environments.len() || idx == state.active_environment_idx {
                 debug!(
@@ -70,13 +274,14 @@ impl Versi {
 
             info!("Switching to environment {}", idx);
             state.active_environment_idx = idx;
+            state.selected_versions.clear();
 
             let env = &state.environments[idx];
             let env_id = env.id.clone();
             debug!("Selected environment: {:?}", env_id);
 
             let needs_load =
-                env.loading || (env.installed_versions.is_empty() && env.error.is_none());
+                !env.loading && env.installed_versions.is_empty() && env.error.is_none();
             debug!("Environment needs loading: {}", needs_load);
 
             let env_provider = self
@@ -89,12 +294,23 @@ impl Versi {
                 &env_id,
                 &self.backend_path,
                 &self.backend_dir,
+                &self.backend_source,
                 &env_provider,
+                self.settings
+                    .env_overrides_for(&env_id, env.target.as_ref()),
             );
             state.backend = new_backend;
             state.backend_name = env.backend_name;
 
             state.backend_update = None;
+            state.settings_state.npm_checks.clear();
+            state.settings_state.resolved_versions.clear();
+            if !matches!(
+                state.settings_state.backend_removal,
+                Some(BackendRemoval::Running)
+            ) {
+                state.settings_state.backend_removal = None;
+            }
 
             let in_settings = state.view == MainViewKind::Settings;
             if in_settings {
@@ -106,30 +322,17 @@ impl Versi {
                 let env = state.active_environment_mut();
                 env.loading = true;
 
-                let backend = state.backend.clone();
-                let fetch_timeout = Duration::from_secs(self.settings.fetch_timeout_secs);
-
-                Task::perform(
-                    async move {
-                        debug!("Fetching installed versions for {:?}...", env_id);
-                        let versions =
-                            tokio::time::timeout(fetch_timeout, backend.list_installed())
-                                .await
-                                .unwrap_or(Ok(Vec::new()))
-                                .unwrap_or_default();
-                        debug!(
-                            "Environment {:?} loaded: {} versions",
-                            env_id,
-                            versions.len(),
-                        );
-                        (env_id, versions)
-                    },
-                    |(env_id, versions)| Message::EnvironmentLoaded { env_id, versions },
+                load_installed_task(
+                    env_id,
+                    state.backend.clone(),
+                    self.load_permits.clone(),
+                    Duration::from_secs(self.settings.fetch_timeout_secs),
                 )
             } else {
                 Task::none()
             };
 
+            let remote_task = self.switch_remote_source();
             let backend_update_task = self.handle_check_for_backend_update();
             let shell_task = if in_settings {
                 self.handle_check_shell_setup()
@@ -137,7 +340,7 @@ impl Versi {
                 Task::none()
             };
 
-            return Task::batch([load_task, backend_update_task, shell_task]);
+            return Task::batch([load_task, remote_task, backend_update_task, shell_task]);
         }
         Task::none()
     }
@@ -145,23 +348,65 @@ impl Versi {
     pub(super) fn handle_refresh_environment(&mut self) -> Task<Message> {
         if let AppState::Main(state) = &mut self.state {
             let env = state.active_environment_mut();
+            env.load_attempts = 0;
+            let env_id = env.id.clone();
+            return self.refresh_environment(&env_id);
+        }
+        Task::none()
+    }
+
+    /// The backend for `env_id`: the active one, or a new one for other environments.
+    pub(super) fn backend_for_environment(
+        &self,
+        env_id: &EnvironmentId,
+    ) -> Option<Box<dyn VersionManager>> {
+        let AppState::Main(state) = &self.state else {
+            return None;
+        };
+        if state.active_environment().id == *env_id {
+            return Some(state.backend.clone());
+        }
+        let env = state.environments.iter().find(|e| e.id == *env_id)?;
+        let provider = self
+            .providers
+            .get(env.backend_name)
+            .cloned()
+            .unwrap_or_else(|| self.provider.clone());
+        Some(create_backend_for_environment(
+            env_id,
+            &self.backend_path,
+            &self.backend_dir,
+            &self.backend_source,
+            &provider,
+            self.settings.env_overrides_for(env_id, env.target.as_ref()),
+        ))
+    }
+
+    /// Reloads the installed versions of one environment.
+    pub(super) fn refresh_environment(&mut self, env_id: &EnvironmentId) -> Task<Message> {
+        let Some(backend) = self.backend_for_environment(env_id) else {
+            return Task::none();
+        };
+        if let AppState::Main(state) = &mut self.state {
+            let is_active = state.active_environment().id == *env_id;
+            let Some(env) = state.environments.iter_mut().find(|e| e.id == *env_id) else {
+                return Task::none();
+            };
+            if !env.available {
+                return Task::none();
+            }
             env.loading = true;
-            env.error = None;
+            env.retry_in = None;
             let env_id = env.id.clone();
 
-            state.refresh_rotation = std::f32::consts::TAU / 40.0;
-            let backend = state.backend.clone();
-            let fetch_timeout = Duration::from_secs(self.settings.fetch_timeout_secs);
-
-            return Task::perform(
-                async move {
-                    let versions = tokio::time::timeout(fetch_timeout, backend.list_installed())
-                        .await
-                        .unwrap_or(Ok(Vec::new()))
-                        .unwrap_or_default();
-                    (env_id, versions)
-                },
-                |(env_id, versions)| Message::EnvironmentLoaded { env_id, versions },
+            if is_active {
+                state.refresh_rotation = std::f32::consts::TAU / 40.0;
+            }
+            return load_installed_task(
+                env_id,
+                backend,
+                self.load_permits.clone(),
+                Duration::from_secs(self.settings.fetch_timeout_secs),
             );
         }
         Task::none()
@@ -181,4 +426,85 @@ impl Versi {
             state.search_query = query;
         }
     }
+
+    pub(super) fn handle_search_submitted(&mut self) {
+        if let AppState::Main(state) = &self.state {
+            let env_id = state.active_environment().id.clone();
+            self.settings.record_search(&env_id, &state.search_query);
+            if let Err(e) = self.settings.save() {
+                log::error!("Failed to save settings: {e}");
+            }
+        }
+    }
+
+    pub(super) fn handle_search_filter_pinned(&mut self, query: String) {
+        let query = query.trim().to_string();
+        if query.is_empty() || self.settings.saved_filters.contains(&query) {
+            return;
+        }
+        self.settings.saved_filters.push(query);
+        if let Err(e) = self.settings.save() {
+            log::error!("Failed to save settings: {e}");
+        }
+    }
+
+    pub(super) fn handle_search_filter_unpinned(&mut self, query: String) {
+        self.settings.saved_filters.retain(|q| q != &query);
+        if let Err(e) = self.settings.save() {
+            log::error!("Failed to save settings: {e}");
+        }
+    }
+
+    pub(super) fn handle_search_history_cleared(&mut self) {
+        if let AppState::Main(state) = &self.state {
+            self.settings
+                .search_history
+                .remove(&state.active_environment().id.settings_key());
+            if let Err(e) = self.settings.save() {
+                log::error!("Failed to save settings: {e}");
+            }
+        }
+    }
+
+    /// Searching every environment needs their installed versions, so any
+    /// not yet loaded are loaded now.
+    pub(super) fn handle_search_all_environments_toggled(&mut self) -> Task<Message> {
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+        state.search_all_environments = !state.search_all_environments;
+        if !state.search_all_environments {
+            return Task::none();
+        }
+        let unloaded: Vec<EnvironmentId> = state
+            .environments
+            .iter()
+            .filter(|env| {
+                env.available
+                    && !env.loading
+                    && env.installed_versions.is_empty()
+                    && env.error.is_none()
+            })
+            .map(|env| env.id.clone())
+            .collect();
+        Task::batch(
+            unloaded
+                .iter()
+                .map(|env_id| self.refresh_environment(env_id)),
+        )
+    }
+
+    /// Leaves the search across environments for `env_idx`, filtered to
+    /// `version`.
+    pub(super) fn handle_show_in_environment(
+        &mut self,
+        env_idx: usize,
+        version: String,
+    ) -> Task<Message> {
+        if let AppState::Main(state) = &mut self.state {
+            state.search_all_environments = false;
+            state.search_query = version;
+        }
+        self.handle_environment_selected(env_idx)
+    }
 }
@@ -0,0 +1,128 @@
+//! End-of-life and maintenance warnings: strict mode, grace periods,
+//! countdown badges, and snoozing or dismissing the banners per major.
+//!
+//! Handles messages: EolStrictToggled, EolGraceDaysChanged, EolCountdownDaysChanged,
+//! SnoozeSupportWarnings, DismissSupportWarnings, RestoreSupportWarning
+
+use log::info;
+
+use crate::eol::{self, SupportPhase, SupportWarning, WarningAcknowledgement};
+use crate::settings::NotificationCategory;
+use crate::state::{AppState, Toast};
+
+use super::Versi;
+
+impl Versi {
+    pub(super) fn handle_eol_strict_toggled(&mut self, strict: bool) {
+        self.settings.eol_strict = strict;
+        if let Err(e) = self.settings.save() {
+            log::error!("Failed to save settings: {e}");
+        }
+    }
+
+    pub(super) fn handle_eol_grace_days_changed(&mut self, days: u32) {
+        self.settings.eol_grace_days = days;
+        if let Err(e) = self.settings.save() {
+            log::error!("Failed to save settings: {e}");
+        }
+    }
+
+    pub(super) fn handle_eol_countdown_days_changed(&mut self, days: u32) {
+        self.settings.eol_countdown_days = days;
+        if let Err(e) = self.settings.save() {
+            log::error!("Failed to save settings: {e}");
+        }
+    }
+
+    pub(super) fn handle_snooze_support_warnings(&mut self, phase: SupportPhase) {
+        let today = chrono::Local::now().date_naive();
+        self.acknowledge_support_warnings(phase, |warning| {
+            WarningAcknowledgement::snoozed(warning, today)
+        });
+    }
+
+    pub(super) fn handle_dismiss_support_warnings(&mut self, phase: SupportPhase) {
+        self.acknowledge_support_warnings(phase, WarningAcknowledgement::dismissed);
+    }
+
+    pub(super) fn handle_restore_support_warning(&mut self, major: u32) {
+        self.settings.eol_acknowledgements.remove(&major);
+        if let Err(e) = self.settings.save() {
+            log::error!("Failed to save settings: {e}");
+        }
+    }
+
+    /// Notifies about installed majors that newly warrant a warning, once
+    /// per major and phase each session.
+    pub(super) fn announce_support_warnings(&mut self) {
+        let AppState::Main(state) = &mut self.state else {
+            return;
+        };
+        let Some(schedule) = &state.available_versions.schedule else {
+            return;
+        };
+        let majors = state
+            .active_environment()
+            .version_groups
+            .iter()
+            .map(|group| group.major);
+        let today = chrono::Local::now().date_naive();
+        let warnings = eol::visible_warnings(schedule, majors, &self.settings, today);
+
+        for warning in warnings {
+            if !state
+                .announced_warnings
+                .insert((warning.major, warning.phase))
+            {
+                continue;
+            }
+            let message = match warning.phase {
+                SupportPhase::EndOfLife => {
+                    format!("Node {} has reached end-of-life", warning.major)
+                }
+                SupportPhase::Maintenance => {
+                    format!("Node {} only receives critical fixes now", warning.major)
+                }
+            };
+            let id = state.next_toast_id();
+            state.add_toast(
+                Toast::notice(id, message, NotificationCategory::EolWarnings),
+                &self.settings.notification_delivery,
+            );
+        }
+    }
+
+    /// Records an acknowledgement for each major the `phase` banner lists.
+    fn acknowledge_support_warnings(
+        &mut self,
+        phase: SupportPhase,
+        acknowledge: impl Fn(&SupportWarning) -> WarningAcknowledgement,
+    ) {
+        let AppState::Main(state) = &self.state else {
+            return;
+        };
+        let Some(schedule) = &state.available_versions.schedule else {
+            return;
+        };
+        let majors = state
+            .active_environment()
+            .version_groups
+            .iter()
+            .map(|group| group.major);
+        let today = chrono::Local::now().date_naive();
+        let warnings = eol::visible_warnings(schedule, majors, &self.settings, today);
+
+        for warning in warnings.iter().filter(|w| w.phase == phase) {
+            info!(
+                "Acknowledged {:?} warning for Node {}",
+                phase, warning.major
+            );
+            self.settings
+                .eol_acknowledgements
+                .insert(warning.major, acknowledge(warning));
+        }
+        if let Err(e) = self.settings.save() {
+            log::error!("Failed to save settings: {e}");
+        }
+    }
+}
@@ -7,15 +7,18 @@ use std::sync::Arc;
 use iced::Task;
 
 use versi_backend::{BackendDetection, BackendProvider, VersionManager};
-use versi_platform::EnvironmentId;
+use versi_platform::{EnvironmentId, PlatformTarget};
 use versi_shell::detect_shells;
 
 use crate::message::{EnvironmentInfo, InitResult, Message};
+use crate::metrics::{self, OperationKind};
 use crate::state::{
-    AppState, BackendOption, EnvironmentState, MainState, OnboardingState, ShellConfigStatus,
+    AppState, BackendOption, EnvironmentError, EnvironmentState, InstallMethod, MainState, Modal,
+    OnboardingState, ShellConfigStatus,
 };
 
 use super::Versi;
+use super::environment::load_installed_task;
 
 impl Versi {
     pub(super) fn handle_initialized(&mut self, result: InitResult) -> Task<Message> {
@@ -46,6 +49,7 @@ impl Versi {
 
             let mut onboarding = OnboardingState::new();
             onboarding.detected_shells = shell_statuses;
+            onboarding.install_methods = InstallMethod::available();
 
             onboarding.available_backends = self
                 .providers
@@ -57,7 +61,7 @@ impl Versi {
                 })
                 .collect();
 
-            self.state = AppState::Onboarding(onboarding);
+            self.state = AppState::Onboarding(Box::new(onboarding));
             return Task::none();
         }
 
@@ -75,6 +79,7 @@ impl Versi {
 
         self.backend_path = backend_path.clone();
         self.backend_dir = backend_dir.clone();
+        self.backend_source = result.backend_source.clone();
 
         let detection = BackendDetection {
             found: true,
@@ -82,35 +87,29 @@ impl Versi {
             version: result.backend_version.clone(),
             in_path: true,
             data_dir: backend_dir.clone(),
+            source: result.backend_source,
         };
-        let backend = self.provider.create_manager(&detection);
+        let mut backend = self.provider.create_manager(&detection);
+        let native_target = PlatformTarget::native();
+        backend.set_env_overrides(
+            self.settings
+                .env_overrides_for(&EnvironmentId::Native, Some(&native_target)),
+        );
 
         let environments: Vec<EnvironmentState> = result
             .environments
             .iter()
-            .map(|env_info| {
-                if env_info.available {
-                    EnvironmentState::new(
-                        env_info.id.clone(),
-                        env_info.backend_name,
-                        env_info.backend_version.clone(),
-                    )
-                } else {
-                    EnvironmentState::unavailable(
-                        env_info.id.clone(),
-                        env_info.backend_name,
-                        env_info
-                            .unavailable_reason
-                            .as_deref()
-                            .unwrap_or("Unavailable"),
-                    )
-                }
-            })
+            .map(environment_state_from_info)
             .collect();
 
         let mut main_state =
             MainState::new_with_environments(backend, environments, active_backend_name);
         main_state.detected_backends = result.detected_backends;
+        if std::mem::take(&mut self.pending_tips) {
+            main_state.tips = crate::tips::TipsTour::start();
+        }
+        let source = self.remote_source(main_state.active_environment());
+        main_state.available_versions.set_active(source);
 
         if let Some(disk_cache) = crate::cache::DiskCache::load() {
             debug!(
@@ -119,62 +118,70 @@ impl Versi {
                 disk_cache.remote_versions.len(),
                 disk_cache.release_schedule.is_some()
             );
-            main_state.available_versions.disk_cached_at = Some(disk_cache.cached_at);
+            let remote = main_state.available_versions.remote_mut();
+            remote.disk_cached_at = Some(disk_cache.cached_at);
             if !disk_cache.remote_versions.is_empty() {
-                main_state
-                    .available_versions
-                    .set_versions(disk_cache.remote_versions);
-                main_state.available_versions.loaded_from_disk = true;
+                remote.set_versions(disk_cache.remote_versions);
+                remote.loaded_from_disk = true;
             }
-            if let Some(schedule) = disk_cache.release_schedule {
-                main_state.available_versions.schedule = Some(schedule);
+            match disk_cache
+                .release_schedule
+                .map(|s| s.validate().map(|()| s))
+            {
+                Some(Ok(schedule)) => {
+                    main_state.available_versions.schedule = Some(schedule);
+                    main_state.available_versions.schedule_fetched_at =
+                        disk_cache.schedule_fetched_at;
+                }
+                Some(Err(e)) => debug!("Ignoring cached release schedule: {}", e),
+                None => {}
             }
+            main_state.available_versions.release_dates = disk_cache.release_dates;
         }
 
-        self.state = AppState::Main(Box::new(main_state));
-
-        let mut load_tasks: Vec<Task<Message>> = Vec::new();
-
-        for env_info in &result.environments {
-            if !env_info.available {
-                debug!(
-                    "Skipping load for unavailable environment: {:?}",
-                    env_info.id
+        if let Some(pending) = crate::recovery::PendingOperations::load() {
+            if pending.environment == main_state.active_environment().id.settings_key() {
+                info!(
+                    "Found {} unfinished operation(s) from {}",
+                    pending.operations.len(),
+                    pending.saved_at
                 );
-                continue;
+                main_state.persisted_operations = pending.operations.clone();
+                main_state.resume_offered = true;
+                main_state.modal = Some(Modal::ResumeOperations {
+                    environment: main_state.active_environment().id.display_name(),
+                    operations: pending.operations,
+                });
+            } else {
+                info!(
+                    "Discarding unfinished operations for unavailable environment {}",
+                    pending.environment
+                );
+                crate::recovery::PendingOperations::clear();
             }
+        }
 
-            let env_id = env_info.id.clone();
-            let env_backend_name = env_info.backend_name;
+        self.state = AppState::Main(Box::new(main_state));
 
-            let provider = self
-                .providers
-                .get(env_backend_name)
-                .cloned()
-                .unwrap_or_else(|| self.provider.clone());
-
-            let backend =
-                create_backend_for_environment(&env_id, &backend_path, &backend_dir, &provider);
-
-            let fetch_timeout = std::time::Duration::from_secs(self.settings.fetch_timeout_secs);
-            load_tasks.push(Task::perform(
-                async move {
-                    let versions = tokio::time::timeout(fetch_timeout, backend.list_installed())
-                        .await
-                        .unwrap_or(Ok(Vec::new()))
-                        .unwrap_or_default();
-                    (env_id, versions)
-                },
-                move |(env_id, versions)| Message::EnvironmentLoaded { env_id, versions },
-            ));
-        }
+        let mut load_tasks: Vec<Task<Message>> = result
+            .environments
+            .iter()
+            .map(|env_info| self.load_environment_task(env_info))
+            .collect();
+
+        #[cfg(windows)]
+        load_tasks.push(self.detect_wsl_environments_task(active_backend_name));
+        #[cfg(windows)]
+        load_tasks.push(self.detect_msys_environments_task(active_backend_name));
 
         let fetch_remote = self.handle_fetch_remote_versions();
         let fetch_schedule = self.handle_fetch_release_schedule();
         let check_app_update = self.handle_check_for_app_update();
         let check_backend_update = self.handle_check_for_backend_update();
+        let detect_proxy = self.handle_detect_proxy();
 
         load_tasks.extend([
+            detect_proxy,
             fetch_remote,
             fetch_schedule,
             check_app_update,
@@ -183,6 +190,383 @@ impl Versi {
 
         Task::batch(load_tasks)
     }
+
+    #[cfg(windows)]
+    pub(super) fn handle_environment_detected(
+        &mut self,
+        env_info: EnvironmentInfo,
+    ) -> Task<Message> {
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+
+        if state.environments.iter().any(|e| e.id == env_info.id) {
+            return Task::none();
+        }
+
+        // The settings key leaves out the backend path, which is only known
+        // once the environment is available.
+        let key = env_info.id.settings_key();
+        let previous = state
+            .environments
+            .iter()
+            .position(|e| !e.available && e.id.settings_key() == key);
+        match previous {
+            Some(idx) => {
+                info!("Re-detected environment: {:?}", env_info.id);
+                state.environments[idx] = environment_state_from_info(&env_info);
+                if idx == state.active_environment_idx {
+                    let provider = self
+                        .providers
+                        .get(env_info.backend_name)
+                        .cloned()
+                        .unwrap_or_else(|| self.provider.clone());
+                    state.backend = create_backend_for_environment(
+                        &env_info.id,
+                        &self.backend_path,
+                        &self.backend_dir,
+                        &self.backend_source,
+                        &provider,
+                        self.settings
+                            .env_overrides_for(&env_info.id, env_info.target.as_ref()),
+                    );
+                    state.backend_name = env_info.backend_name;
+                }
+            }
+            None => {
+                info!("Adding detected environment: {:?}", env_info.id);
+                state
+                    .environments
+                    .push(environment_state_from_info(&env_info));
+            }
+        }
+
+        // Only switch while the native tab is still showing, so a tab the
+        // user picked in the meantime is kept.
+        let startup_idx = (self.settings.startup_environment.as_deref()
+            == Some(env_info.id.settings_key().as_str())
+            && state.active_environment_idx == 0)
+            .then(|| state.environments.iter().position(|e| e.id == env_info.id))
+            .flatten();
+
+        self.update_tray_menu();
+        let load_task = self.load_environment_task(&env_info);
+        match startup_idx {
+            Some(idx) => Task::batch([load_task, self.handle_environment_selected(idx)]),
+            None => load_task,
+        }
+    }
+
+    #[cfg(windows)]
+    pub(super) fn handle_start_wsl_distro(&mut self, distro: String) -> Task<Message> {
+        info!("Starting WSL distro {}", distro);
+        Task::perform(
+            async move { versi_platform::execute_in_wsl(&distro, "true").await },
+            |result| {
+                if let Err(e) = result {
+                    log::warn!("Failed to start WSL distro: {}", e);
+                }
+                Message::DetectWslEnvironments
+            },
+        )
+    }
+
+    #[cfg(windows)]
+    pub(super) fn handle_detect_wsl_environments(&mut self) -> Task<Message> {
+        let AppState::Main(state) = &self.state else {
+            return Task::none();
+        };
+        let native_backend_name = state
+            .environments
+            .iter()
+            .find(|e| e.id == EnvironmentId::Native)
+            .map(|e| e.backend_name)
+            .unwrap_or_else(|| self.provider.name());
+        self.detect_wsl_environments_task(native_backend_name)
+    }
+
+    #[cfg(windows)]
+    pub(super) fn handle_detect_msys_environments(&mut self) -> Task<Message> {
+        let AppState::Main(state) = &self.state else {
+            return Task::none();
+        };
+        let native_backend_name = state
+            .environments
+            .iter()
+            .find(|e| e.id == EnvironmentId::Native)
+            .map(|e| e.backend_name)
+            .unwrap_or_else(|| self.provider.name());
+        self.detect_msys_environments_task(native_backend_name)
+    }
+
+    fn load_environment_task(&self, env_info: &EnvironmentInfo) -> Task<Message> {
+        if !env_info.available {
+            debug!(
+                "Skipping load for unavailable environment: {:?}",
+                env_info.id
+            );
+            return Task::none();
+        }
+
+        let env_id = env_info.id.clone();
+
+        let provider = self
+            .providers
+            .get(env_info.backend_name)
+            .cloned()
+            .unwrap_or_else(|| self.provider.clone());
+
+        let backend = create_backend_for_environment(
+            &env_id,
+            &self.backend_path,
+            &self.backend_dir,
+            &self.backend_source,
+            &provider,
+            self.settings
+                .env_overrides_for(&env_id, env_info.target.as_ref()),
+        );
+
+        load_installed_task(
+            env_id,
+            backend,
+            self.load_permits.clone(),
+            std::time::Duration::from_secs(self.settings.fetch_timeout_secs),
+        )
+    }
+
+    #[cfg(windows)]
+    fn detect_wsl_environments_task(&self, native_backend_name: &'static str) -> Task<Message> {
+        let providers: Vec<Arc<dyn BackendProvider>> = self.providers.values().cloned().collect();
+        let preferred_name: &'static str = match self.settings.preferred_backend.as_deref() {
+            Some("nvm") => "nvm",
+            _ => "fnm",
+        };
+
+        Task::run(
+            iced::stream::channel(
+                8,
+                move |mut sender: iced::futures::channel::mpsc::Sender<Message>| async move {
+                    use iced::futures::{SinkExt, StreamExt};
+
+                    let mut all_search_paths: Vec<&'static str> = Vec::new();
+                    for provider in &providers {
+                        all_search_paths.extend(provider.wsl_search_paths());
+                    }
+                    all_search_paths.sort();
+                    all_search_paths.dedup();
+
+                    info!("Detecting WSL distros in the background...");
+                    let distros = tokio::task::spawn_blocking(move || {
+                        versi_platform::detect_wsl_distros(&all_search_paths)
+                    })
+                    .await
+                    .unwrap_or_default();
+                    debug!(
+                        "WSL distros found: {:?}",
+                        distros.iter().map(|d| &d.name).collect::<Vec<_>>()
+                    );
+
+                    let provider_map: HashMap<&str, &Arc<dyn BackendProvider>> =
+                        providers.iter().map(|p| (p.name(), p)).collect();
+
+                    let mut detected = iced::futures::stream::iter(distros)
+                        .map(|distro| {
+                            wsl_environment_info(
+                                distro,
+                                &provider_map,
+                                native_backend_name,
+                                preferred_name,
+                            )
+                        })
+                        .buffer_unordered(super::environment::MAX_PARALLEL_LOADS);
+                    while let Some(env_info) = detected.next().await {
+                        let _ = sender.send(Message::EnvironmentDetected(env_info)).await;
+                    }
+                },
+            ),
+            std::convert::identity,
+        )
+    }
+
+    #[cfg(windows)]
+    fn detect_msys_environments_task(&self, native_backend_name: &'static str) -> Task<Message> {
+        let providers: Vec<Arc<dyn BackendProvider>> = self.providers.values().cloned().collect();
+        let preferred_name: &'static str = match self.settings.preferred_backend.as_deref() {
+            Some("nvm") => "nvm",
+            _ => "fnm",
+        };
+
+        Task::run(
+            iced::stream::channel(
+                8,
+                move |mut sender: iced::futures::channel::mpsc::Sender<Message>| async move {
+                    use iced::futures::SinkExt;
+
+                    let mut all_search_paths: Vec<&'static str> = Vec::new();
+                    for provider in &providers {
+                        all_search_paths.extend(provider.msys_search_paths());
+                    }
+                    all_search_paths.sort();
+                    all_search_paths.dedup();
+
+                    info!("Detecting Git Bash and MSYS2 in the background...");
+                    let shells = tokio::task::spawn_blocking(move || {
+                        versi_platform::detect_msys_shells(&all_search_paths)
+                    })
+                    .await
+                    .unwrap_or_default();
+
+                    for shell in shells {
+                        let env_info =
+                            probe_msys_environment(shell, native_backend_name, preferred_name)
+                                .await;
+                        let _ = sender.send(Message::EnvironmentDetected(env_info)).await;
+                    }
+                },
+            ),
+            std::convert::identity,
+        )
+    }
+}
+
+/// Git Bash and MSYS2 run the backend's Windows binaries, so their target is
+/// the native one.
+#[cfg(windows)]
+async fn probe_msys_environment(
+    shell: versi_platform::MsysShell,
+    native_backend_name: &'static str,
+    preferred_name: &'static str,
+) -> EnvironmentInfo {
+    let Some(bp) = shell.backend_path else {
+        info!(
+            "Adding unavailable MSYS environment: {} (no backend found)",
+            shell.bash
+        );
+        return EnvironmentInfo {
+            id: EnvironmentId::Msys {
+                bash: shell.bash,
+                backend_path: String::new(),
+            },
+            backend_name: native_backend_name,
+            backend_version: None,
+            available: false,
+            unavailable_reason: Some(EnvironmentError::BackendMissing),
+            target: None,
+        };
+    };
+
+    let backend_name = determine_backend_from_path(&bp, &HashMap::new(), preferred_name);
+    info!(
+        "Adding MSYS environment: {} ({} at {})",
+        shell.bash, backend_name, bp
+    );
+    let backend_version =
+        versi_platform::execute_in_msys(&shell.bash, &format!("\"{}\" --version", bp))
+            .await
+            .ok()
+            .map(|output| {
+                let output = output.trim();
+                output.strip_prefix("fnm ").unwrap_or(output).to_string()
+            });
+    EnvironmentInfo {
+        id: EnvironmentId::Msys {
+            bash: shell.bash,
+            backend_path: bp,
+        },
+        backend_name,
+        backend_version,
+        available: true,
+        unavailable_reason: None,
+        target: Some(PlatformTarget::native()),
+    }
+}
+
+#[cfg(windows)]
+async fn wsl_environment_info(
+    distro: versi_platform::WslDistro,
+    provider_map: &HashMap<&str, &Arc<dyn BackendProvider>>,
+    native_backend_name: &'static str,
+    preferred_name: &'static str,
+) -> EnvironmentInfo {
+    let started = std::time::Instant::now();
+    let info =
+        probe_wsl_environment(distro, provider_map, native_backend_name, preferred_name).await;
+    if info.available {
+        metrics::record(
+            OperationKind::Detection,
+            &info.id,
+            info.backend_name.to_string(),
+            started.elapsed(),
+        );
+    }
+    info
+}
+
+#[cfg(windows)]
+async fn probe_wsl_environment(
+    distro: versi_platform::WslDistro,
+    provider_map: &HashMap<&str, &Arc<dyn BackendProvider>>,
+    native_backend_name: &'static str,
+    preferred_name: &'static str,
+) -> EnvironmentInfo {
+    if !distro.is_running {
+        info!(
+            "Adding unavailable WSL environment: {} (not running)",
+            distro.name
+        );
+        return EnvironmentInfo {
+            id: EnvironmentId::Wsl {
+                distro: distro.name,
+                backend_path: String::new(),
+            },
+            backend_name: native_backend_name,
+            backend_version: None,
+            available: false,
+            unavailable_reason: Some(EnvironmentError::NotRunning),
+            target: None,
+        };
+    }
+
+    let Some(bp) = distro.backend_path else {
+        info!(
+            "Adding unavailable WSL environment: {} (no backend found)",
+            distro.name
+        );
+        return EnvironmentInfo {
+            id: EnvironmentId::Wsl {
+                distro: distro.name,
+                backend_path: String::new(),
+            },
+            backend_name: native_backend_name,
+            backend_version: None,
+            available: false,
+            unavailable_reason: Some(EnvironmentError::BackendMissing),
+            target: None,
+        };
+    };
+
+    let wsl_backend_name = determine_backend_from_path(&bp, provider_map, preferred_name);
+    info!(
+        "Adding WSL environment: {} ({} at {})",
+        distro.name, wsl_backend_name, bp
+    );
+    let backend_version = get_wsl_backend_version(&distro.name, &bp).await;
+    let target = versi_platform::execute_in_wsl(&distro.name, versi_platform::PROBE_COMMAND)
+        .await
+        .ok()
+        .and_then(|output| PlatformTarget::parse_probe(&output));
+    debug!("WSL {} platform: {:?}", distro.name, target);
+    EnvironmentInfo {
+        id: EnvironmentId::Wsl {
+            distro: distro.name,
+            backend_path: bp,
+        },
+        backend_name: wsl_backend_name,
+        backend_version,
+        available: true,
+        unavailable_reason: None,
+        target,
+    }
 }
 
 pub(super) async fn initialize(
@@ -194,6 +578,7 @@ pub(super) async fn initialize(
         providers.len()
     );
 
+    let started = std::time::Instant::now();
     let mut detections: Vec<(&'static str, BackendDetection)> = Vec::new();
     for provider in &providers {
         debug!("Detecting {} installation...", provider.name());
@@ -207,6 +592,12 @@ pub(super) async fn initialize(
         );
         detections.push((provider.name(), detection));
     }
+    metrics::record(
+        OperationKind::Detection,
+        &EnvironmentId::Native,
+        "installed backends".to_string(),
+        started.elapsed(),
+    );
 
     let preferred_name: &'static str = match preferred.as_deref() {
         Some("nvm") => "nvm",
@@ -232,13 +623,15 @@ pub(super) async fn initialize(
                 backend_found: false,
                 backend_path: None,
                 backend_dir: None,
+                backend_source: None,
                 backend_version: None,
                 environments: vec![EnvironmentInfo {
                     id: EnvironmentId::Native,
                     backend_name: preferred_name,
                     backend_version: None,
                     available: false,
-                    unavailable_reason: Some("No backend installed".to_string()),
+                    unavailable_reason: Some(EnvironmentError::BackendMissing),
+                    target: None,
                 }],
                 detected_backends,
             };
@@ -251,88 +644,13 @@ pub(super) async fn initialize(
         backend_version: detection.version.clone(),
         available: true,
         unavailable_reason: None,
+        target: Some(PlatformTarget::native()),
     };
 
-    #[cfg(not(windows))]
+    // WSL environments are detected after the main view is shown, see
+    // `detect_wsl_environments_task`.
     let environments = vec![native_env];
 
-    #[cfg(windows)]
-    let environments = {
-        let mut envs = vec![native_env];
-
-        use versi_platform::detect_wsl_distros;
-        info!("Running on Windows, detecting WSL distros...");
-
-        let mut all_search_paths: Vec<&str> = Vec::new();
-        for provider in &providers {
-            all_search_paths.extend(provider.wsl_search_paths());
-        }
-        all_search_paths.sort();
-        all_search_paths.dedup();
-
-        let distros = detect_wsl_distros(&all_search_paths);
-        debug!(
-            "WSL distros found: {:?}",
-            distros.iter().map(|d| &d.name).collect::<Vec<_>>()
-        );
-
-        let provider_map: HashMap<&str, &Arc<dyn BackendProvider>> =
-            providers.iter().map(|p| (p.name(), p)).collect();
-
-        for distro in distros {
-            if !distro.is_running {
-                info!(
-                    "Adding unavailable WSL environment: {} (not running)",
-                    distro.name
-                );
-                envs.push(EnvironmentInfo {
-                    id: EnvironmentId::Wsl {
-                        distro: distro.name,
-                        backend_path: String::new(),
-                    },
-                    backend_name,
-                    backend_version: None,
-                    available: false,
-                    unavailable_reason: Some("Not running".to_string()),
-                });
-            } else if let Some(bp) = distro.backend_path {
-                let wsl_backend_name = determine_wsl_backend(&bp, &provider_map, preferred_name);
-                info!(
-                    "Adding WSL environment: {} ({} at {})",
-                    distro.name, wsl_backend_name, bp
-                );
-                let backend_version = get_wsl_backend_version(&distro.name, &bp).await;
-                envs.push(EnvironmentInfo {
-                    id: EnvironmentId::Wsl {
-                        distro: distro.name,
-                        backend_path: bp,
-                    },
-                    backend_name: wsl_backend_name,
-                    backend_version,
-                    available: true,
-                    unavailable_reason: None,
-                });
-            } else {
-                info!(
-                    "Adding unavailable WSL environment: {} (no backend found)",
-                    distro.name
-                );
-                envs.push(EnvironmentInfo {
-                    id: EnvironmentId::Wsl {
-                        distro: distro.name,
-                        backend_path: String::new(),
-                    },
-                    backend_name,
-                    backend_version: None,
-                    available: false,
-                    unavailable_reason: Some("No backend installed".to_string()),
-                });
-            }
-        }
-
-        envs
-    };
-
     info!(
         "Initialization complete with {} environments",
         environments.len()
@@ -345,6 +663,7 @@ pub(super) async fn initialize(
         backend_found: detection.found,
         backend_path: detection.path,
         backend_dir: detection.data_dir,
+        backend_source: detection.source,
         backend_version: detection.version,
         environments,
         detected_backends,
@@ -352,7 +671,7 @@ pub(super) async fn initialize(
 }
 
 #[cfg(windows)]
-fn determine_wsl_backend<'a>(
+fn determine_backend_from_path<'a>(
     path: &str,
     _providers: &HashMap<&str, &Arc<dyn BackendProvider>>,
     default_name: &'a str,
@@ -394,13 +713,36 @@ async fn get_wsl_backend_version(distro: &str, backend_path: &str) -> Option<Str
     }
 }
 
+fn environment_state_from_info(env_info: &EnvironmentInfo) -> EnvironmentState {
+    let mut env = if env_info.available {
+        EnvironmentState::new(
+            env_info.id.clone(),
+            env_info.backend_name,
+            env_info.backend_version.clone(),
+        )
+    } else {
+        EnvironmentState::unavailable(
+            env_info.id.clone(),
+            env_info.backend_name,
+            env_info
+                .unavailable_reason
+                .clone()
+                .unwrap_or(EnvironmentError::BackendMissing),
+        )
+    };
+    env.target = env_info.target;
+    env
+}
+
 pub(super) fn create_backend_for_environment(
     env_id: &EnvironmentId,
     detected_path: &Path,
     detected_dir: &Option<PathBuf>,
+    detected_source: &Option<String>,
     provider: &Arc<dyn BackendProvider>,
+    env_overrides: Vec<(String, String)>,
 ) -> Box<dyn VersionManager> {
-    match env_id {
+    let mut backend = match env_id {
         EnvironmentId::Native => {
             let detection = BackendDetection {
                 found: true,
@@ -408,6 +750,7 @@ pub(super) fn create_backend_for_environment(
                 version: None,
                 in_path: true,
                 data_dir: detected_dir.clone(),
+                source: detected_source.clone(),
             };
             provider.create_manager(&detection)
         }
@@ -415,5 +758,10 @@ pub(super) fn create_backend_for_environment(
             distro,
             backend_path,
         } => provider.create_manager_for_wsl(distro.clone(), backend_path.clone()),
-    }
+        EnvironmentId::Msys { bash, backend_path } => {
+            provider.create_manager_for_msys(bash.clone(), backend_path.clone())
+        }
+    };
+    backend.set_env_overrides(env_overrides);
+    backend
 }
@@ -0,0 +1,141 @@
+//! Keeping IDE Node.js interpreter settings pointed at the default version.
+//!
+//! Handles messages: JetBrainsSyncToggled, PreviewJetBrainsUpdate,
+//! JetBrainsPlanReady, ApplyJetBrainsUpdate, JetBrainsUpdated
+
+use log::{info, warn};
+use std::time::Duration;
+
+use iced::Task;
+
+use versi_platform::EnvironmentId;
+
+use crate::integrations::jetbrains::{self, InterpreterChange};
+use crate::message::Message;
+use crate::state::{AppState, Modal, Toast};
+
+use super::Versi;
+
+impl Versi {
+    pub(super) fn handle_jetbrains_sync_toggled(&mut self, enabled: bool) -> Task<Message> {
+        self.settings.jetbrains_sync = enabled;
+        if let Err(e) = self.settings.save() {
+            log::error!("Failed to save settings: {e}");
+        }
+        Task::none()
+    }
+
+    /// Works out which IDE settings would change to follow the native default
+    /// version. With `prompt`, a non-empty plan opens a confirmation modal;
+    /// otherwise it is shown in settings.
+    pub(super) fn plan_jetbrains_update(&mut self, prompt: bool) -> Task<Message> {
+        let Some(backend) = self.backend_for_environment(&EnvironmentId::Native) else {
+            return Task::none();
+        };
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+
+        let default_version = state
+            .environments
+            .iter()
+            .find(|env| env.id == EnvironmentId::Native)
+            .and_then(|env| env.default_version.as_ref())
+            .map(|version| version.to_string());
+        let Some(default_version) = default_version else {
+            if !prompt {
+                state.settings_state.jetbrains_plan = Some(Err("No default version set".into()));
+            }
+            return Task::none();
+        };
+        if !prompt {
+            state.settings_state.checking_jetbrains = true;
+        }
+
+        let timeout = Duration::from_secs(self.settings.fetch_timeout_secs);
+        Task::perform(
+            async move {
+                let node_path = tokio::time::timeout(
+                    timeout,
+                    backend.exec(&default_version, &["node", "-p", "process.execPath"]),
+                )
+                .await
+                .map_err(|_| "Timed out locating the node binary".to_string())?
+                .map_err(|e| e.to_string())?;
+                Ok(jetbrains::plan_update(
+                    &jetbrains::detect_ide_configs(),
+                    node_path.trim(),
+                ))
+            },
+            move |result| Message::JetBrainsPlanReady { result, prompt },
+        )
+    }
+
+    pub(super) fn handle_jetbrains_plan_ready(
+        &mut self,
+        result: Result<Vec<InterpreterChange>, String>,
+        prompt: bool,
+    ) {
+        let AppState::Main(state) = &mut self.state else {
+            return;
+        };
+        if !prompt {
+            state.settings_state.checking_jetbrains = false;
+            state.settings_state.jetbrains_plan = Some(result);
+            return;
+        }
+        match result {
+            Ok(changes) if !changes.is_empty() && state.modal.is_none() => {
+                state.modal = Some(Modal::ConfirmJetBrainsUpdate { changes });
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Could not plan JetBrains interpreter update: {}", e),
+        }
+    }
+
+    pub(super) fn handle_apply_jetbrains_update(&mut self) -> Task<Message> {
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+        let changes = match state.modal.take() {
+            Some(Modal::ConfirmJetBrainsUpdate { changes }) => changes,
+            other => {
+                state.modal = other;
+                match &state.settings_state.jetbrains_plan {
+                    Some(Ok(changes)) => changes.clone(),
+                    _ => return Task::none(),
+                }
+            }
+        };
+        if changes.is_empty() {
+            return Task::none();
+        }
+
+        Task::perform(
+            async move { jetbrains::apply(&changes) },
+            Message::JetBrainsUpdated,
+        )
+    }
+
+    pub(super) fn handle_jetbrains_updated(&mut self, result: Result<usize, String>) {
+        let AppState::Main(state) = &mut self.state else {
+            return;
+        };
+        match result {
+            Ok(files) => {
+                info!(
+                    "Updated Node.js interpreter in {} JetBrains config file(s)",
+                    files
+                );
+                state.settings_state.jetbrains_plan = None;
+            }
+            Err(e) => {
+                let id = state.next_toast_id();
+                state.add_toast(
+                    Toast::error(id, format!("Failed to update JetBrains settings: {}", e)),
+                    &self.settings.notification_delivery,
+                );
+            }
+        }
+    }
+}
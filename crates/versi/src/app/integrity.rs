@@ -0,0 +1,272 @@
+//! Verifying installed versions and reinstalling them in place.
+//!
+//! Handles messages: VerifyVersion, VersionVerified, ReinstallVersion,
+//! ReinstallGlobalsToggled, ConfirmReinstall, GlobalPackagesCaptured,
+//! GlobalPackagesRestored
+
+use log::{info, warn};
+use std::time::Duration;
+
+use iced::Task;
+
+use versi_platform::EnvironmentId;
+
+use crate::integrity::{self, Integrity};
+use crate::message::Message;
+use crate::npm_doctor;
+use crate::state::{AppState, Modal, OperationRequest, Toast};
+
+use super::Versi;
+
+impl Versi {
+    pub(super) fn handle_verify_version(&mut self, version: String) -> Task<Message> {
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+        let env = state.active_environment_mut();
+        if env.integrity.get(&version) == Some(&Integrity::Checking) {
+            return Task::none();
+        }
+        env.integrity.insert(version.clone(), Integrity::Checking);
+
+        let env_id = env.id.clone();
+        let backend = state.backend.clone();
+        let timeout = Duration::from_secs(self.settings.fetch_timeout_secs);
+
+        Task::perform(
+            async move {
+                let result = integrity::verify(&*backend, &version, timeout).await;
+                (version, result)
+            },
+            move |(version, result)| Message::VersionVerified {
+                env_id: env_id.clone(),
+                version,
+                result,
+            },
+        )
+    }
+
+    pub(super) fn handle_version_verified(
+        &mut self,
+        env_id: EnvironmentId,
+        version: String,
+        result: Result<(), String>,
+    ) {
+        let AppState::Main(state) = &mut self.state else {
+            return;
+        };
+        let Some(env) = state.environments.iter_mut().find(|e| e.id == env_id) else {
+            return;
+        };
+        if !env.installed_set.contains(&version) {
+            return;
+        }
+
+        let status = match result {
+            Ok(()) => {
+                info!("Node {} passed verification", version);
+                Integrity::Healthy
+            }
+            Err(e) => {
+                warn!("Node {} failed verification: {}", version, e);
+                Integrity::Corrupted(e)
+            }
+        };
+        env.integrity.insert(version, status);
+    }
+
+    pub(super) fn handle_reinstall_version(&mut self, version: String) {
+        let AppState::Main(state) = &mut self.state else {
+            return;
+        };
+        let is_default = state
+            .active_environment()
+            .default_version
+            .as_ref()
+            .is_some_and(|dv| dv.to_string() == version);
+        state.modal = Some(Modal::ConfirmReinstall {
+            version,
+            is_default,
+            keep_globals: false,
+        });
+    }
+
+    pub(super) fn handle_reinstall_globals_toggled(&mut self, enabled: bool) {
+        if let AppState::Main(state) = &mut self.state
+            && let Some(Modal::ConfirmReinstall { keep_globals, .. }) = &mut state.modal
+        {
+            *keep_globals = enabled;
+        }
+    }
+
+    pub(super) fn handle_confirm_reinstall(&mut self) -> Task<Message> {
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+        let Some(Modal::ConfirmReinstall {
+            version,
+            keep_globals,
+            ..
+        }) = state.modal.take()
+        else {
+            return Task::none();
+        };
+        if !keep_globals {
+            return self.queue_reinstall(version, Vec::new());
+        }
+
+        let npm = npm_doctor::npm_program(&state.active_environment().id);
+        let backend = state.backend.clone();
+        let timeout = Duration::from_secs(self.settings.fetch_timeout_secs);
+        Task::perform(
+            async move {
+                let result = npm_doctor::global_packages(&*backend, npm, &version, timeout).await;
+                (version, result)
+            },
+            |(version, result)| Message::GlobalPackagesCaptured { version, result },
+        )
+    }
+
+    pub(super) fn handle_global_packages_captured(
+        &mut self,
+        version: String,
+        result: Result<Vec<String>, String>,
+    ) -> Task<Message> {
+        let packages = match result {
+            Ok(packages) => packages,
+            Err(e) => {
+                // A broken install often cannot run npm either, which is no
+                // reason to leave it broken.
+                warn!("Could not list global packages of Node {}: {}", version, e);
+                if let AppState::Main(state) = &mut self.state {
+                    let toast_id = state.next_toast_id();
+                    state.add_toast(
+                        Toast::error(
+                            toast_id,
+                            format!(
+                                "Reinstalling Node {} without its global packages: {}",
+                                version, e
+                            ),
+                        ),
+                        &self.settings.notification_delivery,
+                    );
+                }
+                Vec::new()
+            }
+        };
+        self.queue_reinstall(version, packages)
+    }
+
+    /// Queues removing and installing `version` again, then restoring it as
+    /// the default if it was one.
+    fn queue_reinstall(&mut self, version: String, packages: Vec<String>) -> Task<Message> {
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+        let was_default = state
+            .active_environment()
+            .default_version
+            .as_ref()
+            .is_some_and(|dv| dv.to_string() == version);
+
+        info!(
+            "Reinstalling Node {} with {} global package(s)",
+            version,
+            packages.len()
+        );
+        state.reinstalls.insert(version.clone(), packages);
+        let queue = &mut state.operation_queue;
+        queue.enqueue(OperationRequest::Uninstall {
+            version: version.clone(),
+        });
+        queue.enqueue(OperationRequest::Install {
+            version: version.clone(),
+        });
+        if was_default {
+            queue.enqueue(OperationRequest::SetDefault { version });
+        }
+
+        self.process_next_operation()
+    }
+
+    /// Stops a reinstall whose uninstall or install failed, so the rest of
+    /// its steps don't run against a missing version.
+    pub(super) fn abandon_reinstall(&mut self, version: &str) {
+        if let AppState::Main(state) = &mut self.state
+            && state.reinstalls.remove(version).is_some()
+        {
+            let cancelled = state.operation_queue.cancel_pending_for(version);
+            warn!(
+                "Reinstall of Node {} stopped, {} queued step(s) cancelled",
+                version, cancelled
+            );
+        }
+    }
+
+    /// Puts back the global packages of a reinstalled version once it is
+    /// installed again.
+    pub(super) fn restore_global_packages(&mut self, version: &str) -> Task<Message> {
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+        let Some(packages) = state.reinstalls.remove(version) else {
+            return Task::none();
+        };
+        if packages.is_empty() {
+            return Task::none();
+        }
+
+        let npm = npm_doctor::npm_program(&state.active_environment().id);
+        let backend = state.backend.clone();
+        let version = version.to_string();
+        let count = packages.len();
+        Task::perform(
+            async move {
+                let result =
+                    npm_doctor::install_global_packages(&*backend, npm, &version, &packages).await;
+                (version, result)
+            },
+            move |(version, result)| Message::GlobalPackagesRestored {
+                version,
+                count,
+                result,
+            },
+        )
+    }
+
+    pub(super) fn handle_global_packages_restored(
+        &mut self,
+        version: String,
+        count: usize,
+        result: Result<(), String>,
+    ) {
+        match result {
+            Ok(()) => {
+                info!("Restored {} global package(s) of Node {}", count, version);
+                versi_platform::announce(&format!(
+                    "Global packages of Node {} reinstalled",
+                    version
+                ));
+            }
+            Err(e) => {
+                warn!(
+                    "Could not restore global packages of Node {}: {}",
+                    version, e
+                );
+                if let AppState::Main(state) = &mut self.state {
+                    let toast_id = state.next_toast_id();
+                    state.add_toast(
+                        Toast::error(
+                            toast_id,
+                            format!(
+                                "Failed to reinstall global packages of Node {}: {}",
+                                version, e
+                            ),
+                        ),
+                        &self.settings.notification_delivery,
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,122 @@
+//! Answering commands from editor extensions and scripts over the local
+//! control socket.
+//!
+//! Handles messages: IpcRequest, IpcServerToggled
+
+use log::info;
+use serde_json::{Value, json};
+
+use iced::Task;
+
+use crate::ipc::{IpcCommand, IpcRequest};
+use crate::message::Message;
+use crate::state::{AppState, Modal};
+
+use super::Versi;
+
+impl Versi {
+    pub(super) fn handle_ipc_request(&mut self, request: IpcRequest) -> Task<Message> {
+        info!("IPC request: {:?}", request.command);
+        let AppState::Main(state) = &self.state else {
+            request.respond(Err("Versi is still starting up".to_string()));
+            return Task::none();
+        };
+        if request.command.is_mutating() && self.settings.is_read_only() {
+            request.respond(Err("Versi is in read-only mode".to_string()));
+            return Task::none();
+        }
+
+        let env = state.active_environment();
+        match &request.command {
+            IpcCommand::GetStatus => {
+                request.respond(Ok(json!({
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "backend": state.backend_name,
+                    "backend_version": env.backend_version,
+                    "environment": env.name,
+                    "default": env.default_version.as_ref().map(|v| v.to_string()),
+                    "read_only": self.settings.is_read_only(),
+                    "operations": {
+                        "active": state.operation_queue.running_count(),
+                        "pending": state.operation_queue.pending.len(),
+                    },
+                })));
+                Task::none()
+            }
+            IpcCommand::ListInstalled => {
+                let versions: Vec<Value> = env
+                    .installed_versions
+                    .iter()
+                    .map(|v| {
+                        json!({
+                            "version": v.version.to_string(),
+                            "default": v.is_default,
+                            "lts": v.lts_codename,
+                        })
+                    })
+                    .collect();
+                request.respond(Ok(Value::Array(versions)));
+                Task::none()
+            }
+            IpcCommand::Install { version } => {
+                let Some(resolved) = self.resolve_install_version(version.clone()) else {
+                    request.respond(Err(format!("No Node release matches '{}'", version)));
+                    return Task::none();
+                };
+                let task = self.handle_start_install(resolved.clone());
+                request.respond(self.install_outcome(&resolved));
+                task
+            }
+            IpcCommand::SetDefault { version } => {
+                let installed = env
+                    .installed_versions
+                    .iter()
+                    .map(|v| v.version.to_string())
+                    .find(|v| v.trim_start_matches('v') == version.trim_start_matches('v'));
+                let Some(installed) = installed else {
+                    request.respond(Err(format!("Node {} is not installed", version)));
+                    return Task::none();
+                };
+                request.respond(Ok(json!({ "queued": true, "version": installed })));
+                Task::done(Message::SetDefault(installed))
+            }
+            IpcCommand::FollowDirectory { path } => {
+                let path = std::path::PathBuf::from(path);
+                if !path.is_absolute() || !path.is_dir() {
+                    request.respond(Err(format!("{} is not a directory", path.display())));
+                    return Task::none();
+                }
+                request.respond(Ok(json!({ "following": path })));
+                Task::done(Message::FollowedDirectoryPicked(Some(path)))
+            }
+        }
+    }
+
+    /// What became of an install started from the control socket: queued or
+    /// started, or waiting on the user to confirm it in the window.
+    fn install_outcome(&self, version: &str) -> Result<Value, String> {
+        let AppState::Main(state) = &self.state else {
+            return Err("Versi is still starting up".to_string());
+        };
+        if let Some(Modal::ConfirmInstallWithoutBinary {
+            version: pending, ..
+        }) = &state.modal
+            && pending == version
+        {
+            return Ok(json!({ "pending_confirmation": true, "version": version }));
+        }
+        let queue = &state.operation_queue;
+        if queue.has_active_install(version) || queue.has_pending_for_version(version) {
+            Ok(json!({ "queued": true, "version": version }))
+        } else {
+            Err(format!("Node {} could not be queued", version))
+        }
+    }
+
+    pub(super) fn handle_ipc_server_toggled(&mut self, enabled: bool) {
+        self.settings.ipc_server = enabled;
+        if let Err(e) = self.settings.save() {
+            log::error!("Failed to save settings: {e}");
+        }
+    }
+}
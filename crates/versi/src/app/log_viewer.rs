@@ -0,0 +1,65 @@
+//! The in-app log viewer, which follows the log file while it is open.
+//!
+//! Handles messages: ShowLogViewer, LogTail, LogViewerLevelChanged,
+//! LogViewerModuleChanged, LogViewerSearchChanged, LogViewerLineClicked,
+//! LogViewerCopy, LogViewerPauseToggled
+
+use iced::Task;
+
+use crate::log_viewer::{LogViewer, TailEvent};
+use crate::message::Message;
+use crate::state::{AppState, MainViewKind};
+
+use super::Versi;
+
+impl Versi {
+    pub(super) fn handle_show_log_viewer(&mut self) {
+        if let AppState::Main(state) = &mut self.state {
+            state.log_viewer = LogViewer::default();
+            state.view = MainViewKind::Logs;
+        }
+    }
+
+    pub(super) fn handle_log_tail(&mut self, event: TailEvent) {
+        if let AppState::Main(state) = &mut self.state {
+            state.log_viewer.apply(event);
+        }
+    }
+
+    pub(super) fn handle_log_viewer_level_changed(&mut self, level: Option<log::Level>) {
+        if let AppState::Main(state) = &mut self.state {
+            state.log_viewer.level = level;
+        }
+    }
+
+    pub(super) fn handle_log_viewer_module_changed(&mut self, module: Option<String>) {
+        if let AppState::Main(state) = &mut self.state {
+            state.log_viewer.module = module;
+        }
+    }
+
+    pub(super) fn handle_log_viewer_search_changed(&mut self, query: String) {
+        if let AppState::Main(state) = &mut self.state {
+            state.log_viewer.query = query;
+        }
+    }
+
+    pub(super) fn handle_log_viewer_line_clicked(&mut self, id: u64) {
+        if let AppState::Main(state) = &mut self.state {
+            state.log_viewer.click(id);
+        }
+    }
+
+    pub(super) fn handle_log_viewer_copy(&mut self) -> Task<Message> {
+        match &self.state {
+            AppState::Main(state) => iced::clipboard::write(state.log_viewer.copy_text()),
+            _ => Task::none(),
+        }
+    }
+
+    pub(super) fn handle_log_viewer_pause_toggled(&mut self) {
+        if let AppState::Main(state) = &mut self.state {
+            state.log_viewer.toggle_paused();
+        }
+    }
+}
@@ -1,15 +1,48 @@
 mod auto_update;
+mod backend_paths;
+mod backend_removal;
+mod backend_upgrades;
 mod bulk_operations;
+mod data_dirs;
+mod debug_overlay;
+mod default_fallback;
+mod env_vars;
 mod environment;
+mod eol;
 mod init;
+mod integrations;
+mod integrity;
+mod ipc;
+mod log_viewer;
+mod network;
+mod notes;
+mod notifications;
+mod npm_doctor;
 mod onboarding;
+mod operation_log;
 mod operations;
 mod platform;
+mod project_watch;
+mod protection;
+mod report_export;
+mod safe_mode;
+mod scheduler;
+mod settings_history;
+mod settings_sync;
+mod settings_watch;
 mod shell;
+mod snippets;
+mod support_bundle;
+mod test_matrix;
+mod tips;
 mod tray_handlers;
+mod trust;
 mod versions;
 mod window;
 
+pub(crate) use backend_paths::{fnm_provider, nvm_provider};
+pub(crate) use network::build_http_client;
+
 use log::info;
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -19,10 +52,11 @@ use iced::{Element, Subscription, Task, Theme};
 
 use versi_backend::BackendProvider;
 
+use crate::event_log::EventLog;
 use crate::message::Message;
 use crate::settings::{AppSettings, ThemeSetting, TrayBehavior};
-use crate::state::{AppState, MainViewKind};
-use crate::theme::{dark_theme, light_theme};
+use crate::state::{AppState, BackendRemoval, MainViewKind};
+use crate::theme::{THEME_SCHEDULE_INTERVAL, VIBRANCY_BACKGROUND_ALPHA, dark_theme, light_theme};
 use crate::tray;
 use crate::views;
 
@@ -35,30 +69,48 @@ pub struct Versi {
     pub(crate) window_visible: bool,
     pub(crate) backend_path: PathBuf,
     pub(crate) backend_dir: Option<PathBuf>,
+    pub(crate) backend_source: Option<String>,
     pub(crate) window_size: Option<iced::Size>,
     pub(crate) window_position: Option<iced::Point>,
+    pub(crate) window_maximized: bool,
+    pub(crate) window_scale_factor: f32,
+    pub(crate) window_raw_id: Option<u64>,
+    /// Whether the window was created with a transparent surface, which
+    /// vibrancy needs and which cannot change while it is open.
+    pub(crate) window_transparent: bool,
+    pub(crate) vibrancy_active: bool,
+    pub(crate) operation_progress: Option<(usize, usize)>,
+    /// Held while operations run, when keeping the machine awake is on.
+    pub(crate) sleep_inhibitor: Option<crate::sleep_inhibit::SleepInhibitor>,
     pub(crate) http_client: reqwest::Client,
+    pub(crate) github: versi_core::GitHubClient,
     pub(crate) providers: HashMap<&'static str, Arc<dyn BackendProvider>>,
     pub(crate) provider: Arc<dyn BackendProvider>,
     pub(crate) system_theme_mode: iced::theme::Mode,
+    pub(crate) event_log: EventLog,
+    /// Bounds how many environments list their installed versions at once.
+    pub(crate) load_permits: Arc<tokio::sync::Semaphore>,
+    /// Set when onboarding finishes, so the main view opens with tips.
+    pub(crate) pending_tips: bool,
 }
 
 impl Versi {
     pub fn new() -> (Self, Task<Message>) {
-        let settings = AppSettings::load();
+        let mut settings = AppSettings::load();
+        settings.versions_layout = settings.startup_view.layout(settings.versions_layout);
 
         let should_minimize = settings.start_minimized
             && settings.tray_behavior != TrayBehavior::Disabled
             && tray::is_tray_active();
 
-        let http_client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(settings.http_timeout_secs))
-            .user_agent(format!("versi/{}", env!("CARGO_PKG_VERSION")))
-            .build()
-            .unwrap_or_default();
+        let http_client = network::build_http_client(&settings);
+        let github =
+            versi_core::GitHubClient::new(http_client.clone(), settings.github_token.clone());
 
-        let fnm_provider: Arc<dyn BackendProvider> = Arc::new(versi_fnm::FnmProvider::new());
-        let nvm_provider: Arc<dyn BackendProvider> = Arc::new(versi_nvm::NvmProvider::new());
+        let fnm_provider: Arc<dyn BackendProvider> =
+            Arc::new(backend_paths::fnm_provider(&settings));
+        let nvm_provider: Arc<dyn BackendProvider> =
+            Arc::new(backend_paths::nvm_provider(&settings));
 
         let mut providers: HashMap<&'static str, Arc<dyn BackendProvider>> = HashMap::new();
         providers.insert(fnm_provider.name(), fnm_provider.clone());
@@ -67,6 +119,9 @@ impl Versi {
         let preferred = settings.preferred_backend.as_deref().unwrap_or("fnm");
         let active_provider = providers.get(preferred).cloned().unwrap_or(fnm_provider);
 
+        let saved_geometry = settings.window_geometry.clone();
+        let window_transparent = settings.window_vibrancy;
+
         let app = Self {
             state: AppState::Loading,
             settings,
@@ -76,12 +131,28 @@ impl Versi {
             window_visible: !should_minimize,
             backend_path: PathBuf::from(active_provider.name()),
             backend_dir: None,
-            window_size: None,
-            window_position: None,
+            backend_source: None,
+            window_size: saved_geometry
+                .as_ref()
+                .map(|geo| iced::Size::new(geo.width, geo.height)),
+            window_position: saved_geometry
+                .as_ref()
+                .map(|geo| iced::Point::new(geo.x as f32, geo.y as f32)),
+            window_maximized: saved_geometry.as_ref().is_some_and(|geo| geo.maximized),
+            window_scale_factor: 1.0,
+            window_raw_id: None,
+            window_transparent,
+            vibrancy_active: false,
+            operation_progress: None,
+            sleep_inhibitor: None,
             http_client,
+            github,
             providers: providers.clone(),
             provider: active_provider,
             system_theme_mode: iced::theme::Mode::None,
+            event_log: EventLog::default(),
+            load_permits: Arc::new(tokio::sync::Semaphore::new(environment::MAX_PARALLEL_LOADS)),
+            pending_tips: false,
         };
 
         let all_providers: Vec<Arc<dyn BackendProvider>> = providers.values().cloned().collect();
@@ -110,12 +181,50 @@ impl Versi {
     }
 
     pub fn update(&mut self, message: Message) -> Task<Message> {
+        if !message.is_high_frequency() {
+            self.event_log
+                .record(message.log_entry(), chrono::Local::now());
+        }
+        let task = self.dispatch(message);
+        self.sync_pending_operations();
+        self.sync_operation_progress();
+        self.sync_sleep_inhibitor();
+        self.sync_status_file();
+        task
+    }
+
+    fn dispatch(&mut self, message: Message) -> Task<Message> {
+        if message.is_mutating() && self.settings.is_read_only() {
+            return self.reject_in_read_only_mode(&message);
+        }
+
         match message {
             Message::Initialized(result) => self.handle_initialized(result),
-            Message::EnvironmentLoaded { env_id, versions } => {
-                self.handle_environment_loaded(env_id, versions)
+            #[cfg(windows)]
+            Message::EnvironmentDetected(env_info) => self.handle_environment_detected(env_info),
+            Message::EnvironmentLoaded { env_id, result } => {
+                self.handle_environment_loaded(env_id, result)
+            }
+            Message::ArchitecturesDetected {
+                env_id,
+                host,
+                architectures,
+            } => {
+                self.handle_architectures_detected(env_id, host, architectures);
+                Task::none()
             }
+            Message::ActiveVersionDetected { env_id, version } => {
+                self.handle_active_version_detected(env_id, version);
+                Task::none()
+            }
+            #[cfg(windows)]
+            Message::StartWslDistro(distro) => self.handle_start_wsl_distro(distro),
+            #[cfg(windows)]
+            Message::DetectWslEnvironments => self.handle_detect_wsl_environments(),
+            #[cfg(windows)]
+            Message::DetectMsysEnvironments => self.handle_detect_msys_environments(),
             Message::RefreshEnvironment => self.handle_refresh_environment(),
+            Message::RetryEnvironmentLoad(env_id) => self.handle_retry_environment_load(env_id),
             Message::FocusSearch => {
                 if let AppState::Main(state) = &mut self.state {
                     state.view = MainViewKind::Versions;
@@ -186,19 +295,53 @@ impl Versi {
                 self.handle_search_changed(query);
                 Task::none()
             }
+            Message::SearchSubmitted => {
+                self.handle_search_submitted();
+                Task::none()
+            }
+            Message::SearchFilterPinned(query) => {
+                self.handle_search_filter_pinned(query);
+                Task::none()
+            }
+            Message::SearchFilterUnpinned(query) => {
+                self.handle_search_filter_unpinned(query);
+                Task::none()
+            }
+            Message::SearchHistoryCleared => {
+                self.handle_search_history_cleared();
+                Task::none()
+            }
+            Message::SearchAllEnvironmentsToggled => self.handle_search_all_environments_toggled(),
+            Message::ShowInEnvironment { env_idx, version } => {
+                self.handle_show_in_environment(env_idx, version)
+            }
+            Message::UninstallFromEnvironment { env_idx, version } => {
+                let show_task = self.handle_show_in_environment(env_idx, version.clone());
+                Task::batch([show_task, self.handle_uninstall(version)])
+            }
             Message::FetchRemoteVersions => self.handle_fetch_remote_versions(),
-            Message::RemoteVersionsFetched(result) => {
-                self.handle_remote_versions_fetched(result);
+            Message::RemoteVersionsFetched { source, result } => {
+                self.handle_remote_versions_fetched(source, result);
                 Task::none()
             }
             Message::ReleaseScheduleFetched(result) => {
                 self.handle_release_schedule_fetched(result);
                 Task::none()
             }
+            Message::ReleaseIndexFetched { mirror, result } => {
+                self.handle_release_index_fetched(mirror, result);
+                Task::none()
+            }
             Message::CloseModal => {
                 if let AppState::Main(state) = &mut self.state {
                     if state.modal.is_some() {
                         state.modal = None;
+                    } else if state.tips.current().is_some() {
+                        state.tips = crate::tips::TipsTour::Hidden;
+                    } else if state.notifications_open {
+                        state.notifications_open = false;
+                    } else if state.view == MainViewKind::Logs {
+                        state.view = MainViewKind::Settings;
                     } else if state.view == MainViewKind::About
                         || state.view == MainViewKind::Settings
                     {
@@ -216,21 +359,148 @@ impl Versi {
                     |_| Message::NoOp,
                 )
             }
+            Message::ShowSetupSnippet(version) => {
+                self.handle_show_setup_snippet(version);
+                Task::none()
+            }
+            Message::SetupSnippetTargetSelected(target) => {
+                self.handle_setup_snippet_target_selected(target);
+                Task::none()
+            }
+            Message::ShowCiMatrix => {
+                self.handle_show_ci_matrix();
+                Task::none()
+            }
+            Message::CiMatrixMajorToggled(major) => {
+                self.handle_ci_matrix_major_toggled(major);
+                Task::none()
+            }
+            Message::CiMatrixEnginesToggled(enabled) => {
+                self.handle_ci_matrix_engines_toggled(enabled);
+                Task::none()
+            }
+            Message::ShowTestMatrix => {
+                self.handle_show_test_matrix();
+                Task::none()
+            }
+            Message::PickTestMatrixProject => self.handle_pick_test_matrix_project(),
+            Message::TestMatrixProjectPicked(path) => self.handle_test_matrix_project_picked(path),
+            Message::TestMatrixToolchainRead(path, toolchain) => {
+                self.handle_test_matrix_toolchain_read(path, toolchain);
+                Task::none()
+            }
+            Message::TestMatrixCommandChanged(value) => {
+                self.handle_test_matrix_command_changed(value);
+                Task::none()
+            }
+            Message::RunTestMatrix => self.handle_run_test_matrix(),
+            Message::ProjectTrustDecided { project, trusted } => {
+                self.handle_project_trust_decided(project, trusted)
+            }
+            Message::ForgetProjectTrust(project) => {
+                self.handle_forget_project_trust(project);
+                Task::none()
+            }
+            Message::TestMatrixRunFinished {
+                version,
+                result,
+                duration,
+            } => self.handle_test_matrix_run_finished(version, result, duration),
+            Message::EditMajorNote(major) => {
+                self.handle_edit_major_note(major);
+                Task::none()
+            }
+            Message::MajorNoteDraftChanged(value) => {
+                self.handle_major_note_draft_changed(value);
+                Task::none()
+            }
+            Message::SaveMajorNote => {
+                self.handle_save_major_note();
+                Task::none()
+            }
+            Message::RemoveMajorNote(major) => {
+                self.handle_remove_major_note(major);
+                Task::none()
+            }
             Message::StartInstall(version) => self.handle_start_install(version),
+            Message::PreviewInstall(spec) => {
+                self.handle_preview_install(spec);
+                Task::none()
+            }
+            Message::InstallCandidatePicked(version) => {
+                self.handle_install_candidate_picked(version);
+                Task::none()
+            }
+            Message::ConfirmInstallWithoutBinary(version) => {
+                self.handle_confirm_install_without_binary(version)
+            }
+            Message::InstallFromUnofficialBuilds(version) => {
+                self.handle_install_from_unofficial_builds(version)
+            }
+            Message::InstallProgress { version, progress } => {
+                self.handle_install_progress(version, progress);
+                Task::none()
+            }
             Message::InstallComplete {
+                env_id,
                 version,
                 success,
                 error,
-            } => self.handle_install_complete(version, success, error),
+            } => self.handle_install_complete(env_id, version, success, error),
             Message::RequestUninstall(version) => self.handle_uninstall(version),
+            Message::VerifyVersion(version) => self.handle_verify_version(version),
+            Message::VersionVerified {
+                env_id,
+                version,
+                result,
+            } => {
+                self.handle_version_verified(env_id, version, result);
+                Task::none()
+            }
+            Message::ReinstallVersion(version) => {
+                self.handle_reinstall_version(version);
+                Task::none()
+            }
+            Message::ReinstallGlobalsToggled(enabled) => {
+                self.handle_reinstall_globals_toggled(enabled);
+                Task::none()
+            }
+            Message::ConfirmReinstall => self.handle_confirm_reinstall(),
+            Message::GlobalPackagesCaptured { version, result } => {
+                self.handle_global_packages_captured(version, result)
+            }
+            Message::GlobalPackagesRestored {
+                version,
+                count,
+                result,
+            } => {
+                self.handle_global_packages_restored(version, count, result);
+                Task::none()
+            }
             Message::ConfirmUninstallDefault(version) => {
                 self.handle_confirm_uninstall_default(version)
             }
+            Message::ConfirmUninstallProtected(version) => {
+                self.handle_confirm_uninstall_protected(version)
+            }
+            Message::DismissMissingDefault => {
+                self.handle_dismiss_missing_default();
+                Task::none()
+            }
+            Message::DismissSlowOperation => {
+                crate::metrics::dismiss_slow_operation();
+                Task::none()
+            }
+            Message::ToggleVersionProtected(version) => {
+                self.handle_toggle_version_protected(version);
+                Task::none()
+            }
             Message::UninstallComplete {
+                env_id,
                 version,
                 success,
                 error,
-            } => self.handle_uninstall_complete(version, success, error),
+            } => self.handle_uninstall_complete(env_id, version, success, error),
             Message::RequestBulkUpdateMajors => self.handle_request_bulk_update_majors(),
             Message::RequestBulkUninstallEOL => self.handle_request_bulk_uninstall_eol(),
             Message::RequestBulkUninstallMajor { major } => {
@@ -251,17 +521,59 @@ impl Versi {
                 self.handle_close_modal();
                 Task::none()
             }
+            Message::VersionSelectionToggled(version) => {
+                self.handle_version_selection_toggled(version);
+                Task::none()
+            }
+            Message::VersionRowClicked(version) => self.handle_version_row_clicked(version),
+            Message::ModifiersChanged(modifiers) => {
+                if let AppState::Main(state) = &mut self.state {
+                    state.modifiers = modifiers;
+                }
+                Task::none()
+            }
+            Message::ClearVersionSelection => {
+                if let AppState::Main(state) = &mut self.state {
+                    state.selected_versions.clear();
+                }
+                Task::none()
+            }
+            Message::RequestBulkUninstallSelected => self.handle_request_bulk_uninstall_selected(),
+            Message::ConfirmBulkUninstallSelected => self.handle_confirm_bulk_uninstall_selected(),
+            Message::RollBackEnvironment => self.handle_roll_back_environment(),
+            Message::ShowOperationFailure(failure) => {
+                self.handle_show_operation_failure(failure);
+                Task::none()
+            }
+            Message::ExportOperationLog => self.handle_export_operation_log(),
+            Message::OperationLogExported(result) => {
+                self.handle_operation_log_exported(result);
+                Task::none()
+            }
+            Message::CompareSelectedVersions => self.handle_compare_selected_versions(),
+            Message::CopySelectedVersions => self.handle_copy_selected_versions(),
             Message::SetDefault(version) => self.handle_set_default(version),
-            Message::DefaultChanged { success, error } => {
-                self.handle_default_changed(success, error)
+            Message::ConfirmSetDefaultForeignArch(version) => {
+                self.handle_confirm_set_default_foreign_arch(version)
             }
+            Message::DefaultChanged {
+                env_id,
+                version,
+                success,
+                error,
+            } => self.handle_default_changed(env_id, version, success, error),
             Message::ToastDismiss(id) => {
                 if let AppState::Main(state) = &mut self.state {
                     state.remove_toast(id);
                 }
                 Task::none()
             }
+            Message::ShowBatchSummary(id) => {
+                self.handle_show_batch_summary(id);
+                Task::none()
+            }
             Message::NavigateToVersions => {
+                self.commit_env_var_draft();
                 if let AppState::Main(state) = &mut self.state {
                     state.view = MainViewKind::Versions;
                 }
@@ -283,6 +595,7 @@ impl Versi {
                 Task::batch([shell_task, log_stats_task])
             }
             Message::NavigateToAbout => {
+                self.commit_env_var_draft();
                 if let AppState::Main(state) = &mut self.state {
                     state.view = MainViewKind::About;
                 }
@@ -305,6 +618,132 @@ impl Versi {
                 }
                 Task::none()
             }
+            Message::ThemeScheduleChanged(schedule) => {
+                self.settings.theme_schedule = schedule;
+                if let Err(e) = self.settings.save() {
+                    log::error!("Failed to save settings: {e}");
+                }
+                Task::none()
+            }
+            // Nothing to update: the theme is resolved from the clock again
+            // after every message.
+            Message::ThemeScheduleTick => Task::none(),
+            Message::RendererChanged(renderer) => {
+                self.settings.renderer = renderer;
+                if let Err(e) = self.settings.save() {
+                    log::error!("Failed to save settings: {e}");
+                }
+                Task::none()
+            }
+            Message::StartupViewChanged(view) => {
+                self.settings.startup_view = view;
+                if let Err(e) = self.settings.save() {
+                    log::error!("Failed to save settings: {e}");
+                }
+                Task::none()
+            }
+            Message::StartupEnvironmentChanged(key) => {
+                self.settings.startup_environment = Some(key);
+                if let Err(e) = self.settings.save() {
+                    log::error!("Failed to save settings: {e}");
+                }
+                Task::none()
+            }
+            Message::ScheduleDraftTaskChanged(task) => {
+                self.handle_schedule_draft_task_changed(task);
+                Task::none()
+            }
+            Message::ScheduleDraftTimeChanged(time) => {
+                self.handle_schedule_draft_time_changed(time);
+                Task::none()
+            }
+            Message::ScheduleDraftWhenIdleToggled(when_idle) => {
+                self.handle_schedule_draft_when_idle_toggled(when_idle);
+                Task::none()
+            }
+            Message::AddScheduledJob => {
+                self.handle_add_scheduled_job();
+                Task::none()
+            }
+            Message::RemoveScheduledJob(id) => {
+                self.handle_remove_scheduled_job(id);
+                Task::none()
+            }
+            Message::EolStrictToggled(strict) => {
+                self.handle_eol_strict_toggled(strict);
+                Task::none()
+            }
+            Message::EolGraceDaysChanged(days) => {
+                self.handle_eol_grace_days_changed(days);
+                Task::none()
+            }
+            Message::EolCountdownDaysChanged(days) => {
+                self.handle_eol_countdown_days_changed(days);
+                Task::none()
+            }
+            Message::SnoozeSupportWarnings(phase) => {
+                self.handle_snooze_support_warnings(phase);
+                Task::none()
+            }
+            Message::DismissSupportWarnings(phase) => {
+                self.handle_dismiss_support_warnings(phase);
+                Task::none()
+            }
+            Message::RestoreSupportWarning(major) => {
+                self.handle_restore_support_warning(major);
+                Task::none()
+            }
+            Message::SuppressBanner(id, snooze) => {
+                self.handle_suppress_banner(id, snooze);
+                Task::none()
+            }
+            Message::RestoreBanner(id) => {
+                self.handle_restore_banner(id);
+                Task::none()
+            }
+            Message::NotificationsToggled => {
+                self.handle_notifications_toggled();
+                Task::none()
+            }
+            Message::SettingsFileCheck => self.handle_settings_file_check(),
+            Message::PickSyncFile => self.handle_pick_sync_file(),
+            Message::SyncFilePicked(path) => self.handle_sync_file_picked(path),
+            Message::ClearSyncFile => {
+                self.handle_clear_sync_file();
+                Task::none()
+            }
+            Message::SchedulerTick => self.handle_scheduler_tick(),
+            Message::SchedulerConditionsChecked(conditions) => {
+                self.handle_scheduler_conditions_checked(conditions)
+            }
+            Message::VersionsLayoutChanged(layout) => {
+                self.settings.versions_layout = layout;
+                if let Err(e) = self.settings.save() {
+                    log::error!("Failed to save settings: {e}");
+                }
+                Task::none()
+            }
+            Message::VersionGroupingChanged(grouping) => {
+                self.settings.version_grouping = grouping;
+                if let Err(e) = self.settings.save() {
+                    log::error!("Failed to save settings: {e}");
+                }
+                Task::none()
+            }
+            Message::ListDensityChanged(density) => {
+                self.settings.list_density = density;
+                if let Err(e) = self.settings.save() {
+                    log::error!("Failed to save settings: {e}");
+                }
+                Task::none()
+            }
+            Message::NotificationDeliveryChanged(category, delivery) => {
+                self.settings.notification_delivery.set(category, delivery);
+                if let Err(e) = self.settings.save() {
+                    log::error!("Failed to save settings: {e}");
+                }
+                Task::none()
+            }
             Message::ShellOptionUseOnCdToggled(value) => {
                 self.settings
                     .shell_options_for_mut(self.provider.name())
@@ -332,6 +771,16 @@ impl Versi {
                 }
                 self.update_shell_flags()
             }
+            Message::EventLogToggled => self.handle_event_log_toggled(),
+            Message::ClearEventLog => {
+                self.event_log.clear();
+                Task::none()
+            }
+            Message::SaveEventLog => self.handle_save_event_log(),
+            Message::EventLogSaved(result) => {
+                self.handle_event_log_saved(result);
+                Task::none()
+            }
             Message::DebugLoggingToggled(value) => {
                 self.settings.debug_logging = value;
                 if let Err(e) = self.settings.save() {
@@ -343,6 +792,109 @@ impl Versi {
                 }
                 Task::none()
             }
+            Message::EnvVarAdded => self.handle_env_var_added(),
+            Message::EnvVarKeyChanged(index, key) => self.handle_env_var_key_changed(index, key),
+            Message::EnvVarValueChanged(index, value) => {
+                self.handle_env_var_value_changed(index, value)
+            }
+            Message::EnvVarRemoved(index) => self.handle_env_var_removed(index),
+            Message::EnvVarsSubmitted => {
+                self.commit_env_var_draft();
+                Task::none()
+            }
+            Message::PickCaBundle => self.handle_pick_ca_bundle(),
+            Message::CaBundlePicked(path) => self.handle_ca_bundle_picked(path),
+            Message::ClearCaBundle => self.set_ca_bundle(None),
+            Message::PickFnmBinary => self.handle_pick_fnm_binary(),
+            Message::FnmBinaryPicked(path) => self.handle_fnm_binary_picked(path),
+            Message::ClearFnmBinary => self.set_fnm_binary(None),
+            Message::PickNvmDir => self.handle_pick_nvm_dir(),
+            Message::NvmDirPicked(path) => self.handle_nvm_dir_picked(path),
+            Message::ClearNvmDir => self.set_nvm_dir(None),
+            Message::SystemWideToggled(enabled) => self.handle_system_wide_toggled(enabled),
+            Message::SanitizeBackendEnvToggled(enabled) => {
+                self.handle_sanitize_backend_env_toggled(enabled)
+            }
+            Message::PickFollowedDirectory => self.handle_pick_followed_directory(),
+            Message::FollowedDirectoryPicked(path) => self.handle_followed_directory_picked(path),
+            Message::ClearFollowedDirectory => self.handle_clear_followed_directory(),
+            Message::ProjectWatchTick => self.handle_project_watch_tick(),
+            Message::ProjectPinRead(dir, pin) => self.handle_project_pin_read(dir, pin),
+            Message::PickDataDir(dir) => self.handle_pick_data_dir(dir),
+            Message::DataDirPicked(dir, path) => self.handle_data_dir_picked(dir, path),
+            Message::ResetDataDir(dir) => {
+                self.set_data_dir(dir, None);
+                Task::none()
+            }
+            Message::TestConnection => self.handle_test_connection(),
+            Message::RetryCountChanged(retries) => {
+                self.update_retry_policy(|policy| policy.retries = retries);
+                Task::none()
+            }
+            Message::RetryBackoffChanged(backoff) => {
+                self.update_retry_policy(|policy| policy.backoff = backoff);
+                Task::none()
+            }
+            Message::RetryTimeoutChanged(secs) => {
+                self.update_retry_policy(|policy| policy.timeout_secs = secs);
+                Task::none()
+            }
+            Message::GitHubTokenChanged(token) => {
+                self.handle_github_token_changed(token);
+                Task::none()
+            }
+            Message::ConnectionTested(result) => {
+                self.handle_connection_tested(result);
+                Task::none()
+            }
+            Message::DetectProxy => self.handle_detect_proxy(),
+            Message::ProxyResolved(result) => {
+                self.handle_proxy_resolved(result);
+                Task::none()
+            }
+            Message::ProxyModeChanged(mode) => self.handle_proxy_mode_changed(mode),
+            Message::PacUrlChanged(url) => {
+                self.handle_pac_url_changed(url);
+                Task::none()
+            }
+            Message::CheckNpmConfig => self.handle_check_npm_config(),
+            Message::NpmConfigChecked { env_id, checks } => {
+                self.handle_npm_config_checked(env_id, checks);
+                Task::none()
+            }
+            Message::CopyNpmConfig { from, to } => self.handle_copy_npm_config(from, to),
+            Message::NpmConfigCopied(result) => self.handle_npm_config_copied(result),
+            Message::CheckNpmCache => self.handle_check_npm_cache(),
+            Message::NpmCacheChecked { env_id, result } => {
+                self.handle_npm_cache_checked(env_id, result);
+                Task::none()
+            }
+            Message::ClearNpmCache => self.handle_clear_npm_cache(),
+            Message::NpmCacheCleared { env_id, result } => {
+                self.handle_npm_cache_cleared(env_id, result)
+            }
+            Message::PlanBackendRemoval => self.handle_plan_backend_removal(),
+            Message::BackendRemovalPlanned(plan) => {
+                self.handle_backend_removal_planned(plan);
+                Task::none()
+            }
+            Message::ContinueBackendRemoval => {
+                self.handle_continue_backend_removal();
+                Task::none()
+            }
+            Message::ConfirmBackendRemoval => self.handle_confirm_backend_removal(),
+            Message::CancelBackendRemoval => {
+                if let AppState::Main(state) = &mut self.state
+                    && !matches!(
+                        state.settings_state.backend_removal,
+                        Some(BackendRemoval::Running)
+                    )
+                {
+                    state.settings_state.backend_removal = None;
+                }
+                Task::none()
+            }
+            Message::BackendRemovalFinished(steps) => self.handle_backend_removal_finished(steps),
             Message::CopyToClipboard(text) => iced::clipboard::write(text),
             Message::ClearLogFile => {
                 let Some(log_path) = versi_platform::AppPaths::new().ok().map(|p| p.log_file())
@@ -374,6 +926,35 @@ impl Versi {
                     |_| Message::NoOp,
                 )
             }
+            Message::ShowLogViewer => {
+                self.handle_show_log_viewer();
+                Task::none()
+            }
+            Message::LogTail(event) => {
+                self.handle_log_tail(event);
+                Task::none()
+            }
+            Message::LogViewerLevelChanged(level) => {
+                self.handle_log_viewer_level_changed(level);
+                Task::none()
+            }
+            Message::LogViewerModuleChanged(module) => {
+                self.handle_log_viewer_module_changed(module);
+                Task::none()
+            }
+            Message::LogViewerSearchChanged(query) => {
+                self.handle_log_viewer_search_changed(query);
+                Task::none()
+            }
+            Message::LogViewerLineClicked(id) => {
+                self.handle_log_viewer_line_clicked(id);
+                Task::none()
+            }
+            Message::LogViewerCopy => self.handle_log_viewer_copy(),
+            Message::LogViewerPauseToggled => {
+                self.handle_log_viewer_pause_toggled();
+                Task::none()
+            }
             Message::RevealSettingsFile => {
                 if let Err(e) = self.settings.save() {
                     log::error!("Failed to save settings: {e}");
@@ -396,8 +977,14 @@ impl Versi {
                 Task::none()
             }
             Message::ShellFlagsUpdated => Task::none(),
+            Message::CheckResolvedVersions => self.handle_check_resolved_versions(),
+            Message::ResolvedVersionsChecked { env_id, results } => {
+                self.handle_resolved_versions_checked(env_id, results);
+                Task::none()
+            }
             Message::ExportSettings => {
-                let settings = self.settings.clone();
+                let mut settings = self.settings.clone();
+                settings.github_token = None;
                 Task::perform(
                     async move {
                         let dialog = rfd::AsyncFileDialog::new()
@@ -421,16 +1008,51 @@ impl Versi {
                     Message::SettingsExported,
                 )
             }
+            Message::ReadOnlyToggled(value) => {
+                self.settings.read_only = value;
+                if let Err(e) = self.settings.save() {
+                    log::error!("Failed to save settings: {e}");
+                }
+                self.update_tray_menu();
+                Task::none()
+            }
+            Message::ExportReport(format) => self.handle_export_report(format),
+            Message::CopyEnvironmentReport(idx) => self.handle_copy_environment_report(idx),
+            Message::ReportExported(result) => {
+                self.handle_report_exported(result);
+                Task::none()
+            }
+            Message::ExportSupportBundle => self.handle_export_support_bundle(),
+            Message::ShowTips => {
+                self.handle_show_tips();
+                Task::none()
+            }
+            Message::NextTip => {
+                self.handle_next_tip();
+                Task::none()
+            }
+            Message::PreviousTip => {
+                self.handle_previous_tip();
+                Task::none()
+            }
+            Message::DismissTips => {
+                self.handle_dismiss_tips();
+                Task::none()
+            }
+            Message::SupportBundleExported(result) => {
+                self.handle_support_bundle_exported(result);
+                Task::none()
+            }
             Message::SettingsExported(result) => {
                 if let Err(e) = result
                     && e != "Cancelled"
                     && let AppState::Main(state) = &mut self.state
                 {
                     let id = state.next_toast_id();
-                    state.add_toast(crate::state::Toast::error(
-                        id,
-                        format!("Export failed: {}", e),
-                    ));
+                    state.add_toast(
+                        crate::state::Toast::error(id, format!("Export failed: {}", e)),
+                        &self.settings.notification_delivery,
+                    );
                 }
                 Task::none()
             }
@@ -445,8 +1067,7 @@ impl Versi {
                             let content = tokio::fs::read_to_string(handle.path())
                                 .await
                                 .map_err(|e| e.to_string())?;
-                            let imported: crate::settings::AppSettings =
-                                serde_json::from_str(&content).map_err(|e| e.to_string())?;
+                            let imported = crate::settings::AppSettings::parse(&content)?;
                             imported.save().map_err(|e| e.to_string())?;
                             Ok(())
                         }
@@ -463,10 +1084,10 @@ impl Versi {
                     Err(e) if e != "Cancelled" => {
                         if let AppState::Main(state) = &mut self.state {
                             let id = state.next_toast_id();
-                            state.add_toast(crate::state::Toast::error(
-                                id,
-                                format!("Import failed: {}", e),
-                            ));
+                            state.add_toast(
+                                crate::state::Toast::error(id, format!("Import failed: {}", e)),
+                                &self.settings.notification_delivery,
+                            );
                         }
                     }
                     _ => {}
@@ -477,9 +1098,61 @@ impl Versi {
                 self.handle_shell_setup_checked(results);
                 Task::none()
             }
-            Message::ConfigureShell(shell_type) => self.handle_configure_shell(shell_type),
-            Message::ShellConfigured(shell_type, result) => {
-                self.handle_shell_configured(shell_type, result);
+            Message::ConfigureShell(env_id, shell_type) => {
+                self.handle_configure_shell(env_id, shell_type)
+            }
+            Message::PreviewShellConfig(env_id, shell_type) => {
+                self.handle_preview_shell_config(env_id, shell_type)
+            }
+            Message::ShellConfigPreviewed {
+                env_id,
+                shell_type,
+                result,
+            } => {
+                self.handle_shell_config_previewed(env_id, shell_type, result);
+                Task::none()
+            }
+            Message::RunTerminalTroubleshooter => self.handle_run_terminal_troubleshooter(),
+            Message::TerminalTroubleshooterFinished(report) => {
+                self.handle_terminal_troubleshooter_finished(report);
+                Task::none()
+            }
+            Message::CloseTerminalTroubleshooter => {
+                if let AppState::Main(state) = &mut self.state {
+                    state.settings_state.troubleshoot_report = None;
+                    state.settings_state.terminal_fix = None;
+                    state.settings_state.terminal_fix_backup = None;
+                }
+                Task::none()
+            }
+            Message::PreviewTerminalFix(shell_type, fix) => {
+                self.handle_preview_terminal_fix(shell_type, fix)
+            }
+            Message::TerminalFixPreviewed(result) => {
+                self.handle_terminal_fix_previewed(result);
+                Task::none()
+            }
+            Message::ApplyTerminalFix => self.handle_apply_terminal_fix(),
+            Message::CancelTerminalFix => {
+                if let AppState::Main(state) = &mut self.state {
+                    state.settings_state.terminal_fix = None;
+                }
+                Task::none()
+            }
+            Message::TerminalFixApplied(result) => self.handle_terminal_fix_applied(result),
+            Message::JetBrainsSyncToggled(enabled) => self.handle_jetbrains_sync_toggled(enabled),
+            Message::PreviewJetBrainsUpdate => self.plan_jetbrains_update(false),
+            Message::JetBrainsPlanReady { result, prompt } => {
+                self.handle_jetbrains_plan_ready(result, prompt);
+                Task::none()
+            }
+            Message::ApplyJetBrainsUpdate => self.handle_apply_jetbrains_update(),
+            Message::JetBrainsUpdated(result) => {
+                self.handle_jetbrains_updated(result);
+                Task::none()
+            }
+            Message::ShellConfigured(env_id, shell_type, result) => {
+                self.handle_shell_configured(env_id, shell_type, result);
                 Task::none()
             }
             Message::PreferredBackendChanged(name) => self.handle_preferred_backend_changed(name),
@@ -492,10 +1165,22 @@ impl Versi {
                 self.handle_onboarding_select_backend(name);
                 Task::none()
             }
+            Message::OnboardingSetupDetected(setup) => {
+                self.handle_onboarding_setup_detected(setup);
+                Task::none()
+            }
             Message::OnboardingInstallBackend => self.handle_onboarding_install_backend(),
             Message::OnboardingBackendInstallResult(result) => {
                 self.handle_onboarding_backend_install_result(result)
             }
+            Message::OnboardingInstallWith(method) => self.handle_onboarding_install_with(method),
+            Message::OnboardingInstallProgress(line) => {
+                self.handle_onboarding_install_progress(line);
+                Task::none()
+            }
+            Message::OnboardingInstallWithResult(method, result) => {
+                self.handle_onboarding_install_with_result(method, result)
+            }
             Message::OnboardingConfigureShell(shell_type) => {
                 self.handle_onboarding_configure_shell(shell_type)
             }
@@ -523,6 +1208,9 @@ impl Versi {
                         }
                     }
                 }
+                if self.window_id.is_some() {
+                    crate::renderer::confirm_working();
+                }
                 if let AppState::Main(state) = &mut self.state {
                     let timeout = self.settings.toast_timeout_secs;
                     state.toasts.retain(|t| !t.is_expired(timeout));
@@ -533,23 +1221,70 @@ impl Versi {
             | Message::WindowEvent(iced::window::Event::Closed)
             | Message::CloseWindow => self.handle_window_close(),
             Message::WindowEvent(iced::window::Event::Resized(size)) => {
-                self.window_size = Some(size);
+                self.handle_window_resized(size)
+            }
+            Message::WindowResized { size, maximized } => {
+                self.window_maximized = maximized;
+                if !maximized {
+                    self.window_size = Some(size);
+                }
                 Task::none()
             }
             Message::WindowEvent(iced::window::Event::Moved(point)) => {
-                self.window_position = Some(point);
+                if !self.window_maximized {
+                    self.window_position = Some(point);
+                }
+                Task::none()
+            }
+            Message::WindowEvent(iced::window::Event::Rescaled(scale_factor)) => {
+                self.window_scale_factor = scale_factor;
+                Task::none()
+            }
+            Message::CloseBehaviorChanged(behavior) => {
+                self.handle_close_behavior_changed(behavior);
+                Task::none()
+            }
+            Message::CloseDialogRememberToggled(value) => {
+                if let AppState::Main(state) = &mut self.state
+                    && let Some(crate::state::Modal::ConfirmClose { remember, .. }) =
+                        &mut state.modal
+                {
+                    *remember = value;
+                }
+                Task::none()
+            }
+            Message::CloseDialogChoice(choice) => self.handle_close_dialog_choice(choice),
+            Message::QuitWhenIdle => self.handle_quit_when_idle(),
+            Message::ConfirmQuit => self.handle_confirm_quit(),
+            Message::ResumePendingOperations => self.handle_resume_pending_operations(),
+            Message::DiscardPendingOperations => {
+                self.handle_discard_pending_operations();
                 Task::none()
             }
             Message::WindowOpened(id) => self.handle_window_opened(id),
+            Message::WindowRawIdReceived(raw_id) => {
+                self.handle_window_raw_id_received(raw_id);
+                Task::none()
+            }
+            Message::AlwaysOnTopToggled(value) => self.handle_always_on_top_toggled(value),
+            Message::WindowVibrancyToggled(value) => {
+                self.handle_window_vibrancy_toggled(value);
+                Task::none()
+            }
+            Message::RememberWorkspaceToggled(value) => {
+                self.settings.remember_workspace = value;
+                if let Err(e) = self.settings.save() {
+                    log::error!("Failed to save settings: {e}");
+                }
+                Task::none()
+            }
             Message::HideDockIcon => {
                 platform::set_dock_visible(false);
                 Task::none()
             }
             Message::WindowEvent(_) => Task::none(),
-            Message::AppUpdateChecked(result) => {
-                self.handle_app_update_checked(result);
-                Task::none()
-            }
+            Message::CheckForAppUpdate => self.handle_check_for_app_update(),
+            Message::AppUpdateChecked(result) => self.handle_app_update_checked(result),
             Message::OpenAppUpdate => {
                 if let AppState::Main(state) = &self.state
                     && let Some(update) = &state.app_update
@@ -565,6 +1300,15 @@ impl Versi {
                 Task::none()
             }
             Message::StartAppUpdate => self.handle_start_app_update(),
+            Message::RemindAppUpdateLater => {
+                self.handle_remind_app_update_later();
+                Task::none()
+            }
+            Message::SkipAppUpdate => {
+                self.handle_skip_app_update();
+                Task::none()
+            }
+            Message::ClearSkippedAppUpdate => self.handle_clear_skipped_app_update(),
             Message::AppUpdateProgress { downloaded, total } => {
                 self.handle_app_update_progress(downloaded, total);
                 Task::none()
@@ -579,8 +1323,22 @@ impl Versi {
             }
             Message::AppUpdateComplete(result) => self.handle_app_update_complete(result),
             Message::RestartApp => self.handle_restart_app(),
-            Message::BackendUpdateChecked(result) => {
-                self.handle_backend_update_checked(result);
+            Message::CheckForBackendUpdate => self.handle_check_for_backend_update(),
+            Message::BackendUpdateChecked(result) => self.handle_backend_update_checked(result),
+            Message::BackendUpdateChannelChanged(channel) => {
+                self.handle_backend_update_channel_changed(channel)
+            }
+            Message::UpgradeAllBackends => self.handle_upgrade_all_backends(),
+            Message::BackendUpgradeProgress { env_id, line } => {
+                self.handle_backend_upgrade_progress(env_id, line);
+                Task::none()
+            }
+            Message::BackendUpgraded {
+                env_id,
+                version,
+                result,
+            } => {
+                self.handle_backend_upgraded(env_id, version, result);
                 Task::none()
             }
             Message::FetchReleaseSchedule => self.handle_fetch_release_schedule(),
@@ -634,7 +1392,18 @@ impl Versi {
                 Task::none()
             }
             Message::TrayEvent(tray_msg) => self.handle_tray_event(tray_msg),
+            Message::IpcRequest(request) => self.handle_ipc_request(request),
+            Message::IpcServerToggled(enabled) => {
+                self.handle_ipc_server_toggled(enabled);
+                Task::none()
+            }
             Message::TrayBehaviorChanged(behavior) => self.handle_tray_behavior_changed(behavior),
+            Message::RevertSettingsChange => self.handle_revert_settings_change(),
+            Message::ResetCorruptFiles => {
+                self.handle_reset_corrupt_files();
+                Task::none()
+            }
+            Message::ResetSettingsSection(section) => self.handle_reset_settings_section(section),
             Message::StartMinimizedToggled(value) => {
                 self.settings.start_minimized = value;
                 if let Err(e) = self.settings.save() {
@@ -642,6 +1411,13 @@ impl Versi {
                 }
                 Task::none()
             }
+            Message::PreventSleepToggled(value) => {
+                self.settings.prevent_sleep = value;
+                if let Err(e) = self.settings.save() {
+                    log::error!("Failed to save settings: {e}");
+                }
+                Task::none()
+            }
             Message::SystemThemeChanged(mode) => {
                 self.system_theme_mode = mode;
                 Task::none()
@@ -677,15 +1453,28 @@ impl Versi {
                         has_tabs,
                         self.is_system_dark(),
                     ),
-                    MainViewKind::About => views::about_view::view(state, has_tabs),
+                    MainViewKind::Logs => views::log_viewer::view(state, has_tabs),
+                    MainViewKind::About => views::about_view::view(state, &self.settings, has_tabs),
                 };
 
-                if let Some(tabs) = tab_row {
+                let content = if let Some(tabs) = tab_row {
                     let tabs_container = container(tabs)
                         .padding(iced::Padding::new(0.0).top(12.0).left(24.0).right(24.0));
                     column![tabs_container, inner].spacing(0).into()
                 } else {
                     inner
+                };
+
+                let content = if state.view == MainViewKind::Versions {
+                    crate::widgets::tips_overlay::view(content, state.tips)
+                } else {
+                    content
+                };
+
+                if self.settings.show_event_log {
+                    crate::widgets::event_log_overlay::view(content, &self.event_log)
+                } else {
+                    content
                 }
             }
         }
@@ -702,7 +1491,28 @@ impl Versi {
             }
             ThemeSetting::Light => light_theme(),
             ThemeSetting::Dark => dark_theme(),
+            ThemeSetting::Scheduled => {
+                if self
+                    .settings
+                    .theme_schedule
+                    .is_dark_at(chrono::Local::now().time())
+                {
+                    dark_theme()
+                } else {
+                    light_theme()
+                }
+            }
+        }
+    }
+
+    /// Lets the native blur show through the window background while
+    /// vibrancy is applied.
+    pub fn style(&self, theme: &Theme) -> iced::theme::Style {
+        let mut style = iced::theme::Base::base(theme);
+        if self.vibrancy_active {
+            style.background_color.a = VIBRANCY_BACKGROUND_ALPHA;
         }
+        style
     }
 
     pub fn is_system_dark(&self) -> bool {
@@ -724,6 +1534,10 @@ impl Versi {
             iced::time::every(std::time::Duration::from_millis(tick_ms)).map(|_| Message::Tick);
 
         let keyboard = iced::event::listen_with(|event, _status, _id| {
+            if let iced::Event::Keyboard(iced::keyboard::Event::ModifiersChanged(modifiers)) = event
+            {
+                return Some(Message::ModifiersChanged(modifiers));
+            }
             if let iced::Event::Keyboard(iced::keyboard::Event::KeyPressed {
                 key, modifiers, ..
             }) = event
@@ -737,6 +1551,14 @@ impl Versi {
                 #[cfg(not(target_os = "macos"))]
                 let cmd = modifiers.control();
 
+                if cmd
+                    && modifiers.shift()
+                    && let iced::keyboard::Key::Character(c) = &key
+                    && c.eq_ignore_ascii_case("d")
+                {
+                    return Some(Message::EventLogToggled);
+                }
+
                 if cmd && let iced::keyboard::Key::Character(c) = &key {
                     match c.as_str() {
                         "k" => return Some(Message::FocusSearch),
@@ -798,6 +1620,18 @@ impl Versi {
 
         let window_open_sub = iced::window::open_events().map(Message::WindowOpened);
 
+        let ipc_sub = if self.settings.ipc_server {
+            Subscription::run(crate::ipc::requests)
+        } else {
+            Subscription::none()
+        };
+
+        let scheduler_tick = if self.settings.scheduled_jobs.is_empty() {
+            Subscription::none()
+        } else {
+            iced::time::every(crate::scheduler::CHECK_INTERVAL).map(|_| Message::SchedulerTick)
+        };
+
         let animation_tick = if self.is_refresh_animating() {
             iced::time::every(std::time::Duration::from_millis(16)).map(|_| Message::AnimationTick)
         } else {
@@ -806,14 +1640,43 @@ impl Versi {
 
         let theme_changes = iced::system::theme_changes().map(Message::SystemThemeChanged);
 
+        let theme_schedule = if self.settings.theme == ThemeSetting::Scheduled {
+            iced::time::every(THEME_SCHEDULE_INTERVAL).map(|_| Message::ThemeScheduleTick)
+        } else {
+            Subscription::none()
+        };
+
+        let settings_watch = iced::time::every(crate::settings_watch::POLL_INTERVAL)
+            .map(|_| Message::SettingsFileCheck);
+
+        let project_watch = if self.settings.followed_directory.is_some() {
+            iced::time::every(crate::project_watch::POLL_INTERVAL)
+                .map(|_| Message::ProjectWatchTick)
+        } else {
+            Subscription::none()
+        };
+
+        let log_tail = if matches!(&self.state, AppState::Main(state) if state.view == MainViewKind::Logs)
+        {
+            Subscription::run(crate::log_viewer::tail)
+        } else {
+            Subscription::none()
+        };
+
         Subscription::batch([
             tick,
             keyboard,
             window_events,
             tray_sub,
             window_open_sub,
+            ipc_sub,
+            scheduler_tick,
             animation_tick,
             theme_changes,
+            theme_schedule,
+            settings_watch,
+            project_watch,
+            log_tail,
         ])
     }
 
@@ -0,0 +1,209 @@
+//! Custom CA certificate configuration, proxy detection, HTTPS connection
+//! testing, the retry policy for network requests, and the GitHub access
+//! token.
+//!
+//! Handles messages: PickCaBundle, CaBundlePicked, ClearCaBundle,
+//! TestConnection, ConnectionTested, RetryCountChanged, RetryBackoffChanged,
+//! RetryTimeoutChanged, GitHubTokenChanged, DetectProxy, ProxyResolved,
+//! ProxyModeChanged, PacUrlChanged
+
+use log::{error, info, warn};
+use std::path::{Path, PathBuf};
+
+use iced::Task;
+
+use versi_core::RetryPolicy;
+
+use crate::message::Message;
+use crate::proxy::ResolvedProxy;
+use crate::settings::{AppSettings, ProxyMode};
+use crate::state::AppState;
+
+use super::Versi;
+
+const CONNECTION_TEST_URL: &str = "https://nodejs.org/dist/index.json";
+
+pub(crate) fn build_http_client(settings: &AppSettings) -> reqwest::Client {
+    let builder = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(settings.http_timeout_secs))
+        .user_agent(format!("versi/{}", env!("CARGO_PKG_VERSION")));
+    let builder = crate::proxy::apply(builder);
+
+    let builder = match &settings.ca_bundle_path {
+        Some(path) => match load_ca_bundle(path) {
+            Ok(certs) => {
+                info!("Loaded {} certificate(s) from {:?}", certs.len(), path);
+                // Added to the built-in roots rather than replacing them.
+                builder.tls_certs_merge(certs)
+            }
+            Err(e) => {
+                error!("Failed to load CA bundle {:?}: {}", path, e);
+                builder
+            }
+        },
+        None => builder,
+    };
+
+    builder.build().unwrap_or_else(|e| {
+        error!("Failed to build the HTTP client: {}", e);
+        reqwest::Client::default()
+    })
+}
+
+fn load_ca_bundle(path: &Path) -> Result<Vec<reqwest::Certificate>, String> {
+    let pem = std::fs::read(path).map_err(|e| e.to_string())?;
+    let certs = reqwest::Certificate::from_pem_bundle(&pem).map_err(|e| e.to_string())?;
+    if certs.is_empty() {
+        return Err("No certificates found".to_string());
+    }
+    Ok(certs)
+}
+
+impl Versi {
+    pub(super) fn handle_pick_ca_bundle(&mut self) -> Task<Message> {
+        Task::perform(
+            async {
+                rfd::AsyncFileDialog::new()
+                    .add_filter("Certificates", &["pem", "crt", "cer"])
+                    .pick_file()
+                    .await
+                    .map(|handle| handle.path().to_path_buf())
+            },
+            Message::CaBundlePicked,
+        )
+    }
+
+    pub(super) fn handle_ca_bundle_picked(&mut self, path: Option<PathBuf>) -> Task<Message> {
+        match path {
+            Some(path) => self.set_ca_bundle(Some(path)),
+            None => Task::none(),
+        }
+    }
+
+    pub(super) fn set_ca_bundle(&mut self, path: Option<PathBuf>) -> Task<Message> {
+        self.settings.ca_bundle_path = path;
+        if let Err(e) = self.settings.save() {
+            error!("Failed to save settings: {e}");
+        }
+
+        self.http_client = build_http_client(&self.settings);
+        self.github = self
+            .github
+            .reconfigure(self.http_client.clone(), self.settings.github_token.clone());
+
+        if let AppState::Main(state) = &mut self.state {
+            let env = state.active_environment();
+            let overrides = self
+                .settings
+                .env_overrides_for(&env.id, env.target.as_ref());
+            state.backend.set_env_overrides(overrides);
+            state.settings_state.connection_test = None;
+        }
+
+        Task::none()
+    }
+
+    pub(super) fn handle_test_connection(&mut self) -> Task<Message> {
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+        if state.settings_state.testing_connection {
+            return Task::none();
+        }
+        state.settings_state.testing_connection = true;
+        state.settings_state.connection_test = None;
+
+        let client = self.http_client.clone();
+        Task::perform(
+            async move {
+                let response = client
+                    .head(CONNECTION_TEST_URL)
+                    .send()
+                    .await
+                    .map_err(|e| e.to_string())?;
+                response.error_for_status().map_err(|e| e.to_string())?;
+                Ok(())
+            },
+            Message::ConnectionTested,
+        )
+    }
+
+    pub(super) fn handle_connection_tested(&mut self, result: Result<(), String>) {
+        if let AppState::Main(state) = &mut self.state {
+            state.settings_state.testing_connection = false;
+            state.settings_state.connection_test = Some(result);
+        }
+    }
+
+    pub(super) fn handle_github_token_changed(&mut self, token: String) {
+        let token = token.trim().to_string();
+        self.settings.github_token = (!token.is_empty()).then_some(token);
+        if let Err(e) = self.settings.save() {
+            error!("Failed to save settings: {e}");
+        }
+        self.github = self
+            .github
+            .reconfigure(self.http_client.clone(), self.settings.github_token.clone());
+    }
+
+    pub(super) fn update_retry_policy(&mut self, edit: impl FnOnce(&mut RetryPolicy)) {
+        edit(&mut self.settings.retry_policy);
+        if let Err(e) = self.settings.save() {
+            log::error!("Failed to save settings: {e}");
+        }
+    }
+
+    pub(super) fn handle_detect_proxy(&mut self) -> Task<Message> {
+        if let AppState::Main(state) = &mut self.state {
+            state.settings_state.proxy_status = None;
+        }
+        Task::perform(
+            crate::proxy::resolve(self.settings.proxy_mode, self.settings.pac_url.clone()),
+            Message::ProxyResolved,
+        )
+    }
+
+    pub(super) fn handle_proxy_resolved(&mut self, result: Result<ResolvedProxy, String>) {
+        let status = match result {
+            Ok(resolved) => {
+                info!("Proxy: {}", resolved.describe());
+                let status = resolved.describe();
+                crate::proxy::set(resolved);
+                self.http_client = build_http_client(&self.settings);
+                self.github = self
+                    .github
+                    .reconfigure(self.http_client.clone(), self.settings.github_token.clone());
+                Ok(status)
+            }
+            Err(e) => {
+                warn!("Proxy detection failed: {}", e);
+                Err(e)
+            }
+        };
+
+        if let AppState::Main(state) = &mut self.state {
+            let env = state.active_environment();
+            let overrides = self
+                .settings
+                .env_overrides_for(&env.id, env.target.as_ref());
+            state.backend.set_env_overrides(overrides);
+            state.settings_state.proxy_status = Some(status);
+        }
+    }
+
+    pub(super) fn handle_proxy_mode_changed(&mut self, mode: ProxyMode) -> Task<Message> {
+        self.settings.proxy_mode = mode;
+        if let Err(e) = self.settings.save() {
+            error!("Failed to save settings: {e}");
+        }
+        self.handle_detect_proxy()
+    }
+
+    pub(super) fn handle_pac_url_changed(&mut self, url: String) {
+        let url = url.trim().to_string();
+        self.settings.pac_url = (!url.is_empty()).then_some(url);
+        if let Err(e) = self.settings.save() {
+            error!("Failed to save settings: {e}");
+        }
+    }
+}
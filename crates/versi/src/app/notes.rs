@@ -0,0 +1,57 @@
+//! Freeform notes attached to Node major versions.
+//!
+//! Handles messages: EditMajorNote, MajorNoteDraftChanged, SaveMajorNote,
+//! RemoveMajorNote
+
+use crate::state::{AppState, Modal};
+
+use super::Versi;
+
+impl Versi {
+    pub(super) fn handle_edit_major_note(&mut self, major: u32) {
+        if let AppState::Main(state) = &mut self.state {
+            let draft = self
+                .settings
+                .major_notes
+                .get(&major)
+                .cloned()
+                .unwrap_or_default();
+            state.modal = Some(Modal::EditMajorNote { major, draft });
+        }
+    }
+
+    pub(super) fn handle_major_note_draft_changed(&mut self, value: String) {
+        if let AppState::Main(state) = &mut self.state
+            && let Some(Modal::EditMajorNote { draft, .. }) = &mut state.modal
+        {
+            *draft = value;
+        }
+    }
+
+    pub(super) fn handle_save_major_note(&mut self) {
+        let AppState::Main(state) = &mut self.state else {
+            return;
+        };
+        let Some(Modal::EditMajorNote { major, draft }) = state.modal.take() else {
+            return;
+        };
+
+        let note = draft.trim();
+        if note.is_empty() {
+            self.settings.major_notes.remove(&major);
+        } else {
+            self.settings.major_notes.insert(major, note.to_string());
+        }
+        if let Err(e) = self.settings.save() {
+            log::error!("Failed to save settings: {e}");
+        }
+    }
+
+    pub(super) fn handle_remove_major_note(&mut self, major: u32) {
+        if self.settings.major_notes.remove(&major).is_some()
+            && let Err(e) = self.settings.save()
+        {
+            log::error!("Failed to save settings: {e}");
+        }
+    }
+}
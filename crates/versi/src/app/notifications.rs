@@ -0,0 +1,36 @@
+//! Snoozing and dismissing contextual banners, and the header list that
+//! brings hidden ones back.
+//!
+//! Handles messages: SuppressBanner, RestoreBanner, NotificationsToggled
+
+use log::info;
+
+use crate::banner_state::{BannerId, BannerSnooze};
+use crate::state::AppState;
+
+use super::Versi;
+
+impl Versi {
+    pub(super) fn handle_suppress_banner(&mut self, id: BannerId, snooze: BannerSnooze) {
+        info!("Hiding the {:?} banner: {:?}", id, snooze);
+        let today = chrono::Local::now().date_naive();
+        self.settings.banner_states.suppress(id, snooze, today);
+        if let Err(e) = self.settings.save() {
+            log::error!("Failed to save settings: {e}");
+        }
+    }
+
+    pub(super) fn handle_restore_banner(&mut self, id: BannerId) {
+        if self.settings.banner_states.restore(id)
+            && let Err(e) = self.settings.save()
+        {
+            log::error!("Failed to save settings: {e}");
+        }
+    }
+
+    pub(super) fn handle_notifications_toggled(&mut self) {
+        if let AppState::Main(state) = &mut self.state {
+            state.notifications_open = !state.notifications_open;
+        }
+    }
+}
@@ -0,0 +1,233 @@
+//! Per-version npm registry and login checks, copying npmrc between versions,
+//! and measuring and clearing each environment's npm cache.
+//!
+//! Handles messages: CheckNpmConfig, NpmConfigChecked, CopyNpmConfig,
+//! NpmConfigCopied, CheckNpmCache, NpmCacheChecked, ClearNpmCache,
+//! NpmCacheCleared
+
+use log::{info, warn};
+use std::time::Duration;
+
+use iced::Task;
+
+use versi_platform::EnvironmentId;
+
+use crate::message::Message;
+use crate::npm_doctor::{self, NpmCache, NpmCheck};
+use crate::state::{AppState, EnvironmentState, Toast};
+
+use super::Versi;
+
+impl Versi {
+    pub(super) fn handle_check_npm_config(&mut self) -> Task<Message> {
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+        if state.settings_state.checking_npm {
+            return Task::none();
+        }
+
+        let env = state.active_environment();
+        let env_id = env.id.clone();
+        let npm = npm_doctor::npm_program(&env.id);
+        let default_version = env.default_version.as_ref().map(|v| v.to_string());
+        let versions: Vec<String> = env
+            .installed_versions
+            .iter()
+            .map(|v| v.version.to_string())
+            .collect();
+
+        state.settings_state.checking_npm = true;
+        let backend = state.backend.clone();
+        let timeout = Duration::from_secs(self.settings.fetch_timeout_secs);
+
+        Task::perform(
+            async move {
+                let mut checks = Vec::with_capacity(versions.len());
+                for version in &versions {
+                    checks.push(npm_doctor::check_version(&*backend, npm, version, timeout).await);
+                }
+                npm_doctor::diagnose(&mut checks, default_version.as_deref());
+                (env_id, checks)
+            },
+            |(env_id, checks)| Message::NpmConfigChecked { env_id, checks },
+        )
+    }
+
+    pub(super) fn handle_npm_config_checked(
+        &mut self,
+        env_id: EnvironmentId,
+        checks: Vec<NpmCheck>,
+    ) {
+        let AppState::Main(state) = &mut self.state else {
+            return;
+        };
+        state.settings_state.checking_npm = false;
+        if state.active_environment().id != env_id {
+            return;
+        }
+        info!(
+            "npm check finished: {} of {} versions have issues",
+            checks.iter().filter(|c| !c.issues.is_empty()).count(),
+            checks.len()
+        );
+        state.settings_state.npm_checks = checks;
+    }
+
+    pub(super) fn handle_copy_npm_config(&mut self, from: String, to: String) -> Task<Message> {
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+        let checks = &state.settings_state.npm_checks;
+        let config_of = |version: &str| {
+            checks
+                .iter()
+                .find(|c| c.version == version)
+                .and_then(|c| c.global_config.clone())
+        };
+        let (Some(source), Some(target)) = (config_of(&from), config_of(&to)) else {
+            return Task::none();
+        };
+
+        info!(
+            "Copying npmrc from {} ({}) to {} ({})",
+            from, source, to, target
+        );
+        let backend = state.backend.clone();
+        Task::perform(
+            async move { npm_doctor::copy_global_config(&*backend, &to, &source, &target).await },
+            Message::NpmConfigCopied,
+        )
+    }
+
+    pub(super) fn handle_npm_config_copied(&mut self, result: Result<(), String>) -> Task<Message> {
+        match result {
+            Ok(()) => {
+                if let AppState::Main(state) = &mut self.state {
+                    state.settings_state.npm_checks.clear();
+                }
+                self.handle_check_npm_config()
+            }
+            Err(e) => {
+                warn!("Failed to copy npmrc: {}", e);
+                if let AppState::Main(state) = &mut self.state {
+                    let id = state.next_toast_id();
+                    state.add_toast(
+                        Toast::error(id, format!("Failed to copy npmrc: {}", e)),
+                        &self.settings.notification_delivery,
+                    );
+                }
+                Task::none()
+            }
+        }
+    }
+
+    pub(super) fn handle_check_npm_cache(&mut self) -> Task<Message> {
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+        let backend = state.backend.clone();
+        let timeout = Duration::from_secs(self.settings.fetch_timeout_secs);
+        let env = state.active_environment_mut();
+        if env.npm_cache_busy {
+            return Task::none();
+        }
+        let Some(version) = npm_version(env) else {
+            env.npm_cache = Some(Err(
+                "Install a Node version to measure the npm cache".to_string()
+            ));
+            return Task::none();
+        };
+
+        env.npm_cache_busy = true;
+        let env_id = env.id.clone();
+        let npm = npm_doctor::npm_program(&env.id);
+        Task::perform(
+            async move { npm_doctor::cache_usage(&*backend, npm, &version, timeout).await },
+            move |result| Message::NpmCacheChecked {
+                env_id: env_id.clone(),
+                result,
+            },
+        )
+    }
+
+    pub(super) fn handle_npm_cache_checked(
+        &mut self,
+        env_id: EnvironmentId,
+        result: Result<NpmCache, String>,
+    ) {
+        let AppState::Main(state) = &mut self.state else {
+            return;
+        };
+        let Some(env) = state.environments.iter_mut().find(|e| e.id == env_id) else {
+            return;
+        };
+        match &result {
+            Ok(cache) => info!("npm cache at {} holds {} bytes", cache.path, cache.size),
+            Err(e) => warn!("Failed to measure the npm cache: {}", e),
+        }
+        env.npm_cache_busy = false;
+        env.npm_cache = Some(result);
+    }
+
+    pub(super) fn handle_clear_npm_cache(&mut self) -> Task<Message> {
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+        let backend = state.backend.clone();
+        let env = state.active_environment_mut();
+        if env.npm_cache_busy {
+            return Task::none();
+        }
+        let Some(version) = npm_version(env) else {
+            return Task::none();
+        };
+
+        info!("Clearing the npm cache in {}", env.name);
+        env.npm_cache_busy = true;
+        let env_id = env.id.clone();
+        let npm = npm_doctor::npm_program(&env.id);
+        Task::perform(
+            async move { npm_doctor::clear_cache(&*backend, npm, &version).await },
+            move |result| Message::NpmCacheCleared {
+                env_id: env_id.clone(),
+                result,
+            },
+        )
+    }
+
+    pub(super) fn handle_npm_cache_cleared(
+        &mut self,
+        env_id: EnvironmentId,
+        result: Result<(), String>,
+    ) -> Task<Message> {
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+        if let Some(env) = state.environments.iter_mut().find(|e| e.id == env_id) {
+            env.npm_cache_busy = false;
+        }
+        if let Err(e) = result {
+            warn!("Failed to clear the npm cache: {}", e);
+            let id = state.next_toast_id();
+            state.add_toast(
+                Toast::error(id, format!("Failed to clear the npm cache: {}", e)),
+                &self.settings.notification_delivery,
+            );
+            return Task::none();
+        }
+        if state.active_environment().id != env_id {
+            return Task::none();
+        }
+        self.handle_check_npm_cache()
+    }
+}
+
+/// The version whose npm reports the cache: the default, or the newest
+/// installed one.
+fn npm_version(env: &EnvironmentState) -> Option<String> {
+    env.default_version
+        .as_ref()
+        .or_else(|| env.installed_versions.iter().map(|v| &v.version).max())
+        .map(|v| v.to_string())
+}
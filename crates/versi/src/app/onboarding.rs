@@ -1,7 +1,13 @@
+use std::sync::Arc;
+
 use iced::Task;
+use iced::futures::SinkExt;
+
+use versi_backend::BackendProvider;
+use versi_shell::{VerificationResult, detect_shells, verify_shell_config};
 
 use crate::message::Message;
-use crate::state::{AppState, OnboardingStep};
+use crate::state::{AppState, ExistingSetup, InstallMethod, OnboardingStep};
 
 use super::Versi;
 
@@ -9,21 +15,106 @@ impl Versi {
     pub(super) fn handle_onboarding_next(&mut self) -> Task<Message> {
         if let AppState::Onboarding(state) = &mut self.state {
             state.step = match state.step {
-                OnboardingStep::Welcome => {
-                    if state.available_backends.len() > 1 {
-                        OnboardingStep::SelectBackend
-                    } else {
-                        OnboardingStep::InstallBackend
-                    }
+                OnboardingStep::Welcome if state.available_backends.len() > 1 => {
+                    OnboardingStep::SelectBackend
+                }
+                OnboardingStep::Welcome | OnboardingStep::SelectBackend => {
+                    return self.detect_existing_setup();
                 }
-                OnboardingStep::SelectBackend => OnboardingStep::InstallBackend,
                 OnboardingStep::InstallBackend => OnboardingStep::ConfigureShell,
-                OnboardingStep::ConfigureShell => return self.handle_onboarding_complete(),
+                OnboardingStep::ConfigureShell | OnboardingStep::Ready => {
+                    return self.handle_onboarding_complete();
+                }
             };
         }
         Task::none()
     }
 
+    /// Checks for a backend installed since Versi started, such as from a
+    /// terminal, before offering to install one. The selected backend is
+    /// tried first.
+    fn detect_existing_setup(&mut self) -> Task<Message> {
+        let AppState::Onboarding(state) = &mut self.state else {
+            return Task::none();
+        };
+        state.detecting = true;
+
+        let mut providers: Vec<Arc<dyn BackendProvider>> = vec![self.provider.clone()];
+        providers.extend(
+            self.providers
+                .values()
+                .filter(|p| p.name() != self.provider.name())
+                .cloned(),
+        );
+
+        Task::perform(
+            async move {
+                for provider in providers {
+                    let detection = provider.detect().await;
+                    if !detection.found {
+                        continue;
+                    }
+                    let mut configured_shells = Vec::new();
+                    for shell in detect_shells() {
+                        let result = verify_shell_config(
+                            &shell.shell_type,
+                            provider.shell_config_marker(),
+                            provider.name(),
+                        )
+                        .await;
+                        if matches!(
+                            result,
+                            VerificationResult::Configured(_)
+                                | VerificationResult::FunctionalButNotInConfig
+                        ) {
+                            configured_shells.push(shell.shell_type);
+                        }
+                    }
+                    return Some(ExistingSetup {
+                        backend: provider.name(),
+                        display_name: provider.display_name(),
+                        version: detection.version,
+                        source: detection.source,
+                        configured_shells,
+                    });
+                }
+                None
+            },
+            Message::OnboardingSetupDetected,
+        )
+    }
+
+    pub(super) fn handle_onboarding_setup_detected(&mut self, setup: Option<ExistingSetup>) {
+        let AppState::Onboarding(state) = &mut self.state else {
+            return;
+        };
+        state.detecting = false;
+        let Some(setup) = setup else {
+            if state.step == OnboardingStep::InstallBackend {
+                state.install_error = Some(
+                    "The install finished, but Versi could not find it. Restart Versi to pick it up."
+                        .to_string(),
+                );
+            }
+            state.step = OnboardingStep::InstallBackend;
+            return;
+        };
+
+        log::info!(
+            "Found an existing {} install during onboarding",
+            setup.backend
+        );
+        for shell in &mut state.detected_shells {
+            shell.configured = setup.configured_shells.contains(&shell.shell_type);
+        }
+        state.selected_backend = Some(setup.backend.to_string());
+        if let Some(provider) = self.providers.get(setup.backend) {
+            self.provider = provider.clone();
+        }
+        state.step = OnboardingStep::Ready;
+        state.existing = Some(setup);
+    }
+
     pub(super) fn handle_onboarding_back(&mut self) {
         if let AppState::Onboarding(state) = &mut self.state {
             state.step = match state.step {
@@ -37,6 +128,13 @@ impl Versi {
                     }
                 }
                 OnboardingStep::ConfigureShell => OnboardingStep::InstallBackend,
+                OnboardingStep::Ready => {
+                    if state.available_backends.len() > 1 {
+                        OnboardingStep::SelectBackend
+                    } else {
+                        OnboardingStep::Welcome
+                    }
+                }
             };
         }
     }
@@ -87,6 +185,88 @@ impl Versi {
         Task::none()
     }
 
+    pub(super) fn handle_onboarding_install_with(
+        &mut self,
+        method: InstallMethod,
+    ) -> Task<Message> {
+        let AppState::Onboarding(state) = &mut self.state else {
+            return Task::none();
+        };
+        state.backend_installing = true;
+        state.install_error = None;
+        state.install_progress = None;
+
+        Task::run(
+            iced::stream::channel(
+                32,
+                move |mut sender: iced::futures::channel::mpsc::Sender<Message>| async move {
+                    let (tx, mut rx) = tokio::sync::mpsc::channel(32);
+
+                    let install_handle = tokio::spawn(async move {
+                        match method {
+                            InstallMethod::Fnm(method) => {
+                                method.install(tx).await.map_err(|e| e.to_string())
+                            }
+                            InstallMethod::Nvm(package_manager) => package_manager
+                                .install_nvm(tx)
+                                .await
+                                .map_err(|e| e.to_string()),
+                        }
+                    });
+
+                    while let Some(line) = rx.recv().await {
+                        let _ = sender.send(Message::OnboardingInstallProgress(line)).await;
+                    }
+
+                    let result = match install_handle.await {
+                        Ok(r) => r,
+                        Err(e) => Err(format!("Install task panicked: {e}")),
+                    };
+
+                    let _ = sender
+                        .send(Message::OnboardingInstallWithResult(method, result))
+                        .await;
+                },
+            ),
+            std::convert::identity,
+        )
+    }
+
+    pub(super) fn handle_onboarding_install_progress(&mut self, line: String) {
+        if let AppState::Onboarding(state) = &mut self.state {
+            state.install_progress = Some(line);
+        }
+    }
+
+    /// An installer only reports that it ran, so the backend is looked for
+    /// again before moving on.
+    pub(super) fn handle_onboarding_install_with_result(
+        &mut self,
+        method: InstallMethod,
+        result: Result<(), String>,
+    ) -> Task<Message> {
+        let AppState::Onboarding(state) = &mut self.state else {
+            return Task::none();
+        };
+        state.backend_installing = false;
+        state.install_progress = None;
+        match result {
+            Ok(()) => {
+                if let InstallMethod::Fnm(method) = method {
+                    self.settings.fnm_install_method = Some(method);
+                    if let Err(e) = self.settings.save() {
+                        log::error!("Failed to save settings: {e}");
+                    }
+                }
+                self.detect_existing_setup()
+            }
+            Err(error) => {
+                state.install_error = Some(error);
+                Task::none()
+            }
+        }
+    }
+
     pub(super) fn handle_onboarding_configure_shell(
         &mut self,
         shell_type: versi_shell::ShellType,
@@ -121,24 +301,24 @@ impl Versi {
 
                     let mut config =
                         ShellConfig::load(shell_type, config_path).map_err(|e| e.to_string())?;
+                    let init_command = backend
+                        .create_manager(&versi_backend::BackendDetection {
+                            found: true,
+                            path: None,
+                            version: None,
+                            in_path: true,
+                            data_dir: None,
+                            source: None,
+                        })
+                        .shell_init_command(shell_type_to_str(&config.shell_type), &options)
+                        .ok_or_else(|| "Shell not supported".to_string())?;
 
                     if config.has_init(&backend_marker) {
-                        let edit = config.update_flags(&backend_marker, &options);
+                        let edit = config.update_flags(&backend_marker, &init_command);
                         if edit.has_changes() {
                             config.apply_edit(&edit).map_err(|e| e.to_string())?;
                         }
                     } else {
-                        let init_command = backend
-                            .create_manager(&versi_backend::BackendDetection {
-                                found: true,
-                                path: None,
-                                version: None,
-                                in_path: true,
-                                data_dir: None,
-                            })
-                            .shell_init_command(shell_type_to_str(&config.shell_type), &options)
-                            .ok_or_else(|| "Shell not supported".to_string())?;
-
                         let edit = config.add_init(&init_command, &backend_label);
                         if edit.has_changes() {
                             config.apply_edit(&edit).map_err(|e| e.to_string())?;
@@ -174,6 +354,7 @@ impl Versi {
     }
 
     pub(super) fn handle_onboarding_complete(&mut self) -> Task<Message> {
+        self.pending_tips = true;
         let all_providers = self.all_providers();
         let preferred = self.settings.preferred_backend.clone();
         Task::perform(
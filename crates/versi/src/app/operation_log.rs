@@ -0,0 +1,96 @@
+//! Error details for failed operations and the operation log bundle that
+//! can be attached to bug reports.
+//!
+//! Handles messages: ShowOperationFailure, ExportOperationLog,
+//! OperationLogExported
+
+use log::info;
+use std::path::PathBuf;
+
+use iced::Task;
+
+use crate::message::Message;
+use crate::operation_log::{self, OperationLog};
+use crate::state::{AppState, Modal, OperationFailure, Toast};
+
+use super::Versi;
+
+impl Versi {
+    pub(super) fn handle_show_operation_failure(&mut self, failure: OperationFailure) {
+        if let AppState::Main(state) = &mut self.state {
+            state.toasts.retain(|t| {
+                t.failure
+                    .as_ref()
+                    .is_none_or(|f| f.request != failure.request)
+            });
+            state.modal = Some(Modal::OperationFailure { failure });
+        }
+    }
+
+    pub(super) fn handle_export_operation_log(&mut self) -> Task<Message> {
+        let AppState::Main(state) = &self.state else {
+            return Task::none();
+        };
+        let Some(Modal::OperationFailure { failure }) = &state.modal else {
+            return Task::none();
+        };
+        let Some(environment) = state
+            .environments
+            .iter()
+            .find(|env| env.id == failure.environment)
+        else {
+            return Task::none();
+        };
+
+        let app_log = versi_platform::AppPaths::new()
+            .map(|paths| operation_log::read_log_tail(&paths.log_file()))
+            .unwrap_or_default();
+        let log = OperationLog {
+            failure,
+            transcripts: versi_platform::operation_transcripts(
+                &failure.request.log_key(&failure.environment),
+            ),
+            app_log,
+            backend: state.backend.backend_info(),
+            environment,
+        };
+        let file_name = log.file_name();
+        let archive = match log.to_zip() {
+            Ok(archive) => archive,
+            Err(e) => return Task::done(Message::OperationLogExported(Err(e))),
+        };
+
+        Task::perform(
+            async move {
+                let handle = rfd::AsyncFileDialog::new()
+                    .set_file_name(&file_name)
+                    .add_filter("Zip archive", &["zip"])
+                    .save_file()
+                    .await
+                    .ok_or_else(|| "Cancelled".to_string())?;
+                let path = handle.path().to_path_buf();
+                tokio::fs::write(&path, archive)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(path)
+            },
+            Message::OperationLogExported,
+        )
+    }
+
+    pub(super) fn handle_operation_log_exported(&mut self, result: Result<PathBuf, String>) {
+        match result {
+            Ok(path) => info!("Exported operation log to {:?}", path),
+            Err(e) if e != "Cancelled" => {
+                if let AppState::Main(state) = &mut self.state {
+                    let id = state.next_toast_id();
+                    state.add_toast(
+                        Toast::error(id, format!("Operation log export failed: {}", e)),
+                        &self.settings.notification_delivery,
+                    );
+                }
+            }
+            Err(_) => {}
+        }
+    }
+}
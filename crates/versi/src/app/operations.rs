@@ -1,34 +1,207 @@
 //! Install, uninstall, and set-default operations with queuing.
 //!
-//! Handles messages: StartInstall, InstallComplete, Uninstall, UninstallComplete,
-//! SetDefault, DefaultChanged, CloseModal
+//! Handles messages: StartInstall, PreviewInstall, InstallCandidatePicked,
+//! ConfirmInstallWithoutBinary, InstallProgress, InstallComplete,
+//! Uninstall, UninstallComplete,
+//! SetDefault, ConfirmSetDefaultForeignArch, DefaultChanged, CloseModal, ResumePendingOperations,
+//! DiscardPendingOperations, ShowBatchSummary
 
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use iced::Task;
+use iced::futures::SinkExt;
+use log::info;
 
+use versi_backend::{InstallProgress, NodeVersion};
+use versi_platform::{EnvironmentId, PlatformTarget, UNOFFICIAL_BUILDS_MIRROR};
+
+use crate::aliases::install_candidates;
 use crate::message::Message;
-use crate::state::{AppState, Modal, Operation, OperationRequest, Toast};
+use crate::metrics::{self, OperationKind};
+use crate::recovery::PendingOperations;
+use crate::settings::NotificationCategory;
+use crate::state::{AppState, Modal, Operation, OperationFailure, OperationRequest, Toast};
+use crate::status_file::PublicStatus;
 
 use super::Versi;
 
 impl Versi {
     pub(super) fn handle_close_modal(&mut self) {
-        if let AppState::Main(state) = &mut self.state {
+        // Unfinished operations stay on offer until they are resumed or
+        // discarded explicitly.
+        if let AppState::Main(state) = &mut self.state
+            && !matches!(state.modal, Some(Modal::ResumeOperations { .. }))
+        {
             state.modal = None;
         }
     }
 
+    pub(super) fn handle_show_batch_summary(&mut self, toast_id: usize) {
+        if let AppState::Main(state) = &mut self.state
+            && let Some(summary) = state
+                .toasts
+                .iter()
+                .find(|t| t.id == toast_id)
+                .and_then(|t| t.summary.clone())
+        {
+            state.remove_toast(toast_id);
+            state.modal = Some(Modal::BatchSummary { summary });
+        }
+    }
+
+    /// Shows which release a partial version or alias installs, so another
+    /// patch can be picked before anything is passed to the backend.
+    pub(super) fn handle_preview_install(&mut self, spec: String) {
+        let AppState::Main(state) = &mut self.state else {
+            return;
+        };
+        let candidates: Vec<String> = install_candidates(
+            &state.available_versions.remote().versions,
+            state.available_versions.schedule.as_ref(),
+            &spec,
+            chrono::Local::now().date_naive(),
+        )
+        .into_iter()
+        .map(ToString::to_string)
+        .collect();
+        let Some(selected) = candidates.first().cloned() else {
+            let id = state.next_toast_id();
+            state.add_toast(
+                Toast::error(id, format!("No Node release matches '{}'", spec)),
+                &self.settings.notification_delivery,
+            );
+            return;
+        };
+        state.modal = Some(Modal::ConfirmResolvedInstall {
+            spec,
+            candidates,
+            selected,
+        });
+    }
+
+    pub(super) fn handle_install_candidate_picked(&mut self, version: String) {
+        if let AppState::Main(state) = &mut self.state
+            && let Some(Modal::ConfirmResolvedInstall { selected, .. }) = &mut state.modal
+        {
+            *selected = version;
+        }
+    }
+
     pub(super) fn handle_start_install(&mut self, version: String) -> Task<Message> {
+        let Some(version) = self.resolve_install_version(version) else {
+            return Task::none();
+        };
         if let AppState::Main(state) = &mut self.state {
             state.modal = None;
 
+            if !state.search_query.is_empty() {
+                let env_id = state.active_environment().id.clone();
+                self.settings.record_search(&env_id, &state.search_query);
+                if let Err(e) = self.settings.save() {
+                    log::error!("Failed to save settings: {e}");
+                }
+            }
+
             if state.operation_queue.has_active_install(&version)
                 || state.operation_queue.has_pending_for_version(&version)
             {
                 return Task::none();
             }
+        }
+
+        if let Some(target) = self.missing_binary_target(&version)
+            && let AppState::Main(state) = &mut self.state
+        {
+            let unofficial_builds = target.has_unofficial_builds()
+                && self.settings.dist_mirror_for(Some(&target)).as_deref()
+                    != Some(UNOFFICIAL_BUILDS_MIRROR);
+            state.modal = Some(Modal::ConfirmInstallWithoutBinary {
+                version,
+                platform: target.label(),
+                unofficial_builds,
+            });
+            return Task::none();
+        }
+
+        self.queue_install(version)
+    }
+
+    /// Turns a partial version or alias into the newest release it names,
+    /// so fnm and nvm install the same thing. Left as is while the release
+    /// list has not loaded; `None` when the list has no such release.
+    pub(super) fn resolve_install_version(&mut self, version: String) -> Option<String> {
+        let AppState::Main(state) = &mut self.state else {
+            return Some(version);
+        };
+        let remote = &state.available_versions.remote().versions;
+        if version.parse::<NodeVersion>().is_ok() || remote.is_empty() {
+            return Some(version);
+        }
+
+        let resolved = install_candidates(
+            remote,
+            state.available_versions.schedule.as_ref(),
+            &version,
+            chrono::Local::now().date_naive(),
+        )
+        .first()
+        .map(ToString::to_string);
+        match resolved {
+            Some(resolved) => {
+                info!("Resolved install of {} to {}", version, resolved);
+                Some(resolved)
+            }
+            None => {
+                let id = state.next_toast_id();
+                state.add_toast(
+                    Toast::error(id, format!("No Node release matches '{}'", version)),
+                    &self.settings.notification_delivery,
+                );
+                None
+            }
+        }
+    }
+
+    pub(super) fn handle_confirm_install_without_binary(
+        &mut self,
+        version: String,
+    ) -> Task<Message> {
+        if let AppState::Main(state) = &mut self.state {
+            state.modal = None;
+        }
+        self.queue_install(version)
+    }
+
+    pub(super) fn handle_install_from_unofficial_builds(
+        &mut self,
+        version: String,
+    ) -> Task<Message> {
+        if let AppState::Main(state) = &mut self.state {
+            state.modal = None;
+            state.unofficial_installs.insert(version.clone());
+        }
+        self.queue_install(version)
+    }
+
+    /// Returns the active environment's platform when the dist index lists
+    /// `version` without a binary for it, so the install would fail to
+    /// download (or fall back to compiling from source).
+    fn missing_binary_target(&self, version: &str) -> Option<PlatformTarget> {
+        let AppState::Main(state) = &self.state else {
+            return None;
+        };
+        let target = state.active_environment().target?;
+        let cache = &state.available_versions;
+        if cache.release_files_mirror != self.settings.dist_mirror_for(Some(&target)) {
+            return None;
+        }
+        let files = cache.release_files.get(version)?;
+        let key = target.dist_file_key();
+        (!files.contains(&key)).then_some(target)
+    }
 
+    fn queue_install(&mut self, version: String) -> Task<Message> {
+        if let AppState::Main(state) = &mut self.state {
             if state.operation_queue.is_busy_for_install() {
                 state
                     .operation_queue
@@ -44,53 +217,133 @@ impl Versi {
     pub(super) fn start_install_internal(&mut self, version: String) -> Task<Message> {
         if let AppState::Main(state) = &mut self.state {
             state.operation_queue.start_install(version.clone());
+            if !state.operation_queue.in_batch() {
+                versi_platform::announce(&format!("Installing Node {}", version));
+            }
 
-            let backend = state.backend.clone();
+            let env = state.active_environment();
+            let env_id = env.id.clone();
+            let mut backend = state.backend.clone();
+            if state.unofficial_installs.contains(&version) {
+                info!("Installing Node {} from the unofficial builds", version);
+                backend.set_env_overrides(
+                    self.settings
+                        .unofficial_install_overrides_for(&env.id, env.target.as_ref()),
+                );
+            }
             let timeout = Duration::from_secs(self.settings.install_timeout_secs);
-
-            return Task::perform(
-                async move {
-                    match tokio::time::timeout(timeout, backend.install(&version)).await {
-                        Ok(Ok(())) => (version, true, None),
-                        Ok(Err(e)) => (version, false, Some(e.to_string())),
-                        Err(_) => (version, false, Some("Installation timed out".to_string())),
-                    }
-                },
-                |(version, success, error)| Message::InstallComplete {
-                    version,
-                    success,
-                    error,
-                },
+            let operation = OperationRequest::Install {
+                version: version.clone(),
+            }
+            .log_key(&env_id);
+
+            return Task::run(
+                iced::stream::channel(
+                    32,
+                    move |mut sender: iced::futures::channel::mpsc::Sender<Message>| async move {
+                        let (tx, mut rx) = tokio::sync::mpsc::channel(32);
+                        let metrics_env = env_id.clone();
+                        let install_version = version.clone();
+                        let install_handle =
+                            tokio::spawn(versi_platform::with_operation(operation, async move {
+                                let started = Instant::now();
+                                let install = backend.install_with_progress(&install_version, tx);
+                                let error = match tokio::time::timeout(timeout, install).await {
+                                    Ok(Ok(())) => None,
+                                    Ok(Err(e)) => Some(e.to_string()),
+                                    Err(_) => Some("Installation timed out".to_string()),
+                                };
+                                metrics::record(
+                                    OperationKind::Install,
+                                    &metrics_env,
+                                    format!("Node {}", install_version),
+                                    started.elapsed(),
+                                );
+                                error
+                            }));
+
+                        while let Some(progress) = rx.recv().await {
+                            let _ = sender
+                                .send(Message::InstallProgress {
+                                    version: version.clone(),
+                                    progress,
+                                })
+                                .await;
+                        }
+
+                        let error = install_handle
+                            .await
+                            .unwrap_or_else(|e| Some(format!("Install task panicked: {e}")));
+                        let _ = sender
+                            .send(Message::InstallComplete {
+                                env_id,
+                                version,
+                                success: error.is_none(),
+                                error,
+                            })
+                            .await;
+                    },
+                ),
+                std::convert::identity,
             );
         }
         Task::none()
     }
 
+    pub(super) fn handle_install_progress(&mut self, version: String, progress: InstallProgress) {
+        if let AppState::Main(state) = &mut self.state {
+            state.operation_queue.record_transfer(&version, progress);
+        }
+    }
+
     pub(super) fn handle_install_complete(
         &mut self,
+        env_id: EnvironmentId,
         version: String,
         success: bool,
         error: Option<String>,
     ) -> Task<Message> {
         if let AppState::Main(state) = &mut self.state {
             state.operation_queue.remove_completed_install(&version);
+            state.unofficial_installs.remove(&version);
+            state.operation_queue.record_result(
+                OperationRequest::Install {
+                    version: version.clone(),
+                },
+                error.clone().filter(|_| !success),
+            );
 
-            if !success {
+            if success && !state.operation_queue.in_batch() {
+                versi_platform::announce(&format!("Node {} installed", version));
+            } else if !success && !state.operation_queue.in_batch() {
+                let error = error.unwrap_or_default();
                 let toast_id = state.next_toast_id();
-                state.add_toast(Toast::error(
-                    toast_id,
-                    format!(
-                        "Failed to install Node {}: {}",
-                        version,
-                        error.unwrap_or_default()
+                state.add_toast(
+                    Toast::operation_failed(
+                        toast_id,
+                        format!("Failed to install Node {}: {}", version, error),
+                        OperationFailure::new(
+                            env_id.clone(),
+                            OperationRequest::Install {
+                                version: version.clone(),
+                            },
+                            error,
+                        ),
                     ),
-                ));
+                    &self.settings.notification_delivery,
+                );
             }
         }
 
+        let restore_task = if success {
+            self.restore_global_packages(&version)
+        } else {
+            self.abandon_reinstall(&version);
+            Task::none()
+        };
         let next_task = self.process_next_operation();
-        let refresh_task = self.handle_refresh_environment();
-        Task::batch([refresh_task, next_task])
+        let refresh_task = self.refresh_environment(&env_id);
+        Task::batch([refresh_task, next_task, restore_task])
     }
 
     pub(super) fn handle_uninstall(&mut self, version: String) -> Task<Message> {
@@ -101,6 +354,17 @@ impl Versi {
                 .as_ref()
                 .is_some_and(|dv| dv.to_string() == version);
 
+            if self
+                .settings
+                .is_protected(&state.active_environment().id, &version)
+            {
+                state.modal = Some(Modal::ConfirmUninstallProtected {
+                    version,
+                    is_default,
+                });
+                return Task::none();
+            }
+
             if is_default {
                 state.modal = Some(Modal::ConfirmUninstallDefault {
                     version: version.clone(),
@@ -141,13 +405,18 @@ impl Versi {
             state.operation_queue.start_exclusive(Operation::Uninstall {
                 version: version.clone(),
             });
+            if !state.operation_queue.in_batch() {
+                versi_platform::announce(&format!("Removing Node {}", version));
+            }
 
+            let env_id = state.active_environment().id.clone();
             let backend = state.backend.clone();
             let version_clone = version.clone();
             let timeout = Duration::from_secs(self.settings.uninstall_timeout_secs);
+            let operation = OperationRequest::Uninstall { version }.log_key(&env_id);
 
             return Task::perform(
-                async move {
+                versi_platform::with_operation(operation, async move {
                     match tokio::time::timeout(timeout, backend.uninstall(&version_clone)).await {
                         Ok(Ok(())) => (version_clone, true, None),
                         Ok(Err(e)) => (version_clone, false, Some(e.to_string())),
@@ -157,8 +426,9 @@ impl Versi {
                             Some("Uninstall timed out".to_string()),
                         ),
                     }
-                },
-                |(version, success, error)| Message::UninstallComplete {
+                }),
+                move |(version, success, error)| Message::UninstallComplete {
+                    env_id: env_id.clone(),
                     version,
                     success,
                     error,
@@ -170,32 +440,87 @@ impl Versi {
 
     pub(super) fn handle_uninstall_complete(
         &mut self,
+        env_id: EnvironmentId,
         version: String,
         success: bool,
         error: Option<String>,
     ) -> Task<Message> {
+        let mut updated_locally = false;
         if let AppState::Main(state) = &mut self.state {
             state.operation_queue.complete_exclusive();
+            state.operation_queue.record_result(
+                OperationRequest::Uninstall {
+                    version: version.clone(),
+                },
+                error.clone().filter(|_| !success),
+            );
 
-            if !success {
+            if success {
+                if !state.operation_queue.in_batch() {
+                    versi_platform::announce(&format!("Node {} removed", version));
+                }
+                state.selected_versions.remove(&version);
+                updated_locally = state
+                    .environments
+                    .iter_mut()
+                    .find(|e| e.id == env_id)
+                    .is_some_and(|env| env.remove_version(&version));
+            } else if !state.operation_queue.in_batch() {
+                let error = error.unwrap_or_default();
                 let toast_id = state.next_toast_id();
-                state.add_toast(Toast::error(
-                    toast_id,
-                    format!(
-                        "Failed to uninstall Node {}: {}",
-                        version,
-                        error.unwrap_or_default()
+                state.add_toast(
+                    Toast::operation_failed(
+                        toast_id,
+                        format!("Failed to uninstall Node {}: {}", version, error),
+                        OperationFailure::new(
+                            env_id.clone(),
+                            OperationRequest::Uninstall {
+                                version: version.clone(),
+                            },
+                            error,
+                        ),
                     ),
-                ));
+                    &self.settings.notification_delivery,
+                );
             }
         }
 
+        if success {
+            self.forget_default(&env_id, &version);
+        } else {
+            self.abandon_reinstall(&version);
+        }
+
         let next_task = self.process_next_operation();
-        let refresh_task = self.handle_refresh_environment();
+        if updated_locally {
+            self.update_tray_menu();
+            return next_task;
+        }
+        let refresh_task = self.refresh_environment(&env_id);
         Task::batch([refresh_task, next_task])
     }
 
     pub(super) fn handle_set_default(&mut self, version: String) -> Task<Message> {
+        if let AppState::Main(state) = &mut self.state
+            && let Some(arch) = state.active_environment().foreign_arch(&version)
+        {
+            state.modal = Some(Modal::ConfirmSetDefaultForeignArch { version, arch });
+            return Task::none();
+        }
+        self.queue_set_default(version)
+    }
+
+    pub(super) fn handle_confirm_set_default_foreign_arch(
+        &mut self,
+        version: String,
+    ) -> Task<Message> {
+        if let AppState::Main(state) = &mut self.state {
+            state.modal = None;
+        }
+        self.queue_set_default(version)
+    }
+
+    fn queue_set_default(&mut self, version: String) -> Task<Message> {
         if let AppState::Main(state) = &mut self.state {
             if state.operation_queue.is_busy_for_exclusive() {
                 state
@@ -216,19 +541,32 @@ impl Versi {
                 .start_exclusive(Operation::SetDefault {
                     version: version.clone(),
                 });
+            if !state.operation_queue.in_batch() {
+                versi_platform::announce(&format!("Setting Node {} as default", version));
+            }
 
+            let env_id = state.active_environment().id.clone();
             let backend = state.backend.clone();
             let timeout = Duration::from_secs(self.settings.set_default_timeout_secs);
+            let operation = OperationRequest::SetDefault {
+                version: version.clone(),
+            }
+            .log_key(&env_id);
 
             return Task::perform(
-                async move {
+                versi_platform::with_operation(operation, async move {
                     match tokio::time::timeout(timeout, backend.set_default(&version)).await {
-                        Ok(Ok(())) => (true, None),
-                        Ok(Err(e)) => (false, Some(e.to_string())),
-                        Err(_) => (false, Some("Set default timed out".to_string())),
+                        Ok(Ok(())) => (version, true, None),
+                        Ok(Err(e)) => (version, false, Some(e.to_string())),
+                        Err(_) => (version, false, Some("Set default timed out".to_string())),
                     }
+                }),
+                move |(version, success, error)| Message::DefaultChanged {
+                    env_id: env_id.clone(),
+                    version,
+                    success,
+                    error,
                 },
-                |(success, error)| Message::DefaultChanged { success, error },
             );
         }
         Task::none()
@@ -236,24 +574,68 @@ impl Versi {
 
     pub(super) fn handle_default_changed(
         &mut self,
+        env_id: EnvironmentId,
+        version: String,
         success: bool,
         error: Option<String>,
     ) -> Task<Message> {
+        let mut updated_locally = false;
         if let AppState::Main(state) = &mut self.state {
             state.operation_queue.complete_exclusive();
+            state.operation_queue.record_result(
+                OperationRequest::SetDefault {
+                    version: version.clone(),
+                },
+                error.clone().filter(|_| !success),
+            );
 
-            if !success {
+            if success {
+                if !state.operation_queue.in_batch() {
+                    versi_platform::announce(&format!("Node {} is now the default", version));
+                }
+                updated_locally = state
+                    .environments
+                    .iter_mut()
+                    .find(|e| e.id == env_id)
+                    .is_some_and(|env| env.mark_default(&version));
+            } else if !state.operation_queue.in_batch() {
+                let error = error.unwrap_or_default();
                 let toast_id = state.next_toast_id();
-                state.add_toast(Toast::error(
-                    toast_id,
-                    format!("Failed to set default: {}", error.unwrap_or_default()),
-                ));
+                state.add_toast(
+                    Toast::operation_failed(
+                        toast_id,
+                        format!("Failed to set default: {}", error),
+                        OperationFailure::new(
+                            env_id.clone(),
+                            OperationRequest::SetDefault {
+                                version: version.clone(),
+                            },
+                            error,
+                        ),
+                    ),
+                    &self.settings.notification_delivery,
+                );
             }
         }
 
+        if updated_locally {
+            self.track_default(&env_id);
+        }
+
+        let jetbrains_task =
+            if success && self.settings.jetbrains_sync && env_id == EnvironmentId::Native {
+                self.plan_jetbrains_update(true)
+            } else {
+                Task::none()
+            };
+
         let next_task = self.process_next_operation();
-        let refresh_task = self.handle_refresh_environment();
-        Task::batch([refresh_task, next_task])
+        if updated_locally {
+            self.update_tray_menu();
+            return Task::batch([next_task, jetbrains_task]);
+        }
+        let refresh_task = self.refresh_environment(&env_id);
+        Task::batch([refresh_task, next_task, jetbrains_task])
     }
 
     pub(super) fn process_next_operation(&mut self) -> Task<Message> {
@@ -280,6 +662,153 @@ impl Versi {
                 return Task::batch(tasks);
             }
         }
+
+        if let AppState::Main(state) = &mut self.state
+            && state.operation_queue.is_idle()
+            && let Some(job) = state.running_job.take()
+            && let Some(summary) = state.operation_queue.take_any_summary()
+        {
+            info!("Scheduled {:?} finished: {}", job.task, summary.headline());
+            versi_platform::show_notification(job.task.label(), &summary.headline());
+            if summary.results.len() > 1 {
+                let toast_id = state.next_toast_id();
+                state.add_toast(
+                    Toast::batch_summary(toast_id, summary),
+                    &self.settings.notification_delivery,
+                );
+            }
+        }
+
+        if let AppState::Main(state) = &mut self.state
+            && let Some(summary) = state.operation_queue.take_batch_summary()
+        {
+            info!("Operation batch finished: {}", summary.headline());
+            let toast_id = state.next_toast_id();
+            state.add_toast(
+                Toast::batch_summary(toast_id, summary),
+                &self.settings.notification_delivery,
+            );
+        }
+
+        if let AppState::Main(state) = &self.state
+            && state.quit_when_idle
+            && state.operation_queue.is_idle()
+        {
+            info!("Operations finished, exiting application");
+            return iced::exit();
+        }
         Task::none()
     }
+
+    pub(super) fn handle_resume_pending_operations(&mut self) -> Task<Message> {
+        if let AppState::Main(state) = &mut self.state
+            && let Some(Modal::ResumeOperations { operations, .. }) = state.modal.take()
+        {
+            state.resume_offered = false;
+            info!("Resuming {} pending operation(s)", operations.len());
+            for request in operations {
+                state.operation_queue.enqueue(request);
+            }
+            return self.process_next_operation();
+        }
+        Task::none()
+    }
+
+    pub(super) fn handle_discard_pending_operations(&mut self) {
+        if let AppState::Main(state) = &mut self.state {
+            state.modal = None;
+            state.resume_offered = false;
+            state.persisted_operations.clear();
+        }
+        PendingOperations::clear();
+    }
+
+    pub(super) fn sync_pending_operations(&mut self) {
+        let AppState::Main(state) = &mut self.state else {
+            return;
+        };
+        if matches!(state.modal, Some(Modal::ResumeOperations { .. })) {
+            return;
+        }
+        if state.resume_offered {
+            // Another dialog replaced the offer; say so rather than dropping
+            // the operations without a word.
+            state.resume_offered = false;
+            let id = state.next_toast_id();
+            state.add_toast(
+                Toast::notice(
+                    id,
+                    format!(
+                        "{} unfinished operation(s) from the last session were discarded",
+                        state.persisted_operations.len()
+                    ),
+                    NotificationCategory::OperationFailures,
+                ),
+                &self.settings.notification_delivery,
+            );
+        }
+
+        let snapshot = state.operation_queue.snapshot();
+        if snapshot == state.persisted_operations {
+            return;
+        }
+
+        if snapshot.is_empty() {
+            PendingOperations::clear();
+        } else {
+            PendingOperations {
+                environment: state.active_environment().id.settings_key(),
+                operations: snapshot.clone(),
+                saved_at: chrono::Utc::now(),
+            }
+            .save();
+        }
+        state.persisted_operations = snapshot;
+    }
+
+    pub(super) fn sync_status_file(&mut self) {
+        let AppState::Main(state) = &mut self.state else {
+            return;
+        };
+        let status = PublicStatus::capture(state);
+        if state.published_status.as_ref() == Some(&status) {
+            return;
+        }
+        status.save();
+        state.published_status = Some(status);
+    }
+
+    /// The controls for changes are disabled in read-only mode and the banner
+    /// says why, so a change that still arrives, e.g. from a shortcut, is
+    /// only logged.
+    pub(super) fn reject_in_read_only_mode(&mut self, message: &Message) -> Task<Message> {
+        info!("Read-only mode: ignoring {}", message.log_entry());
+        if let AppState::Main(state) = &mut self.state {
+            state.modal = None;
+        }
+        Task::none()
+    }
+
+    pub(super) fn sync_operation_progress(&mut self) {
+        let progress = match &self.state {
+            AppState::Main(state) => state.operation_queue.progress(),
+            _ => None,
+        };
+        if progress != self.operation_progress {
+            self.operation_progress = progress;
+            super::platform::set_operation_progress(progress);
+        }
+    }
+
+    /// Keeps the machine awake from the first queued operation until the
+    /// queue drains.
+    pub(super) fn sync_sleep_inhibitor(&mut self) {
+        let busy = self.settings.prevent_sleep
+            && matches!(&self.state, AppState::Main(state) if !state.operation_queue.is_idle());
+        if busy != self.sleep_inhibitor.is_some() {
+            self.sleep_inhibitor = busy.then(|| {
+                crate::sleep_inhibit::SleepInhibitor::acquire("Managing Node.js versions")
+            });
+        }
+    }
 }
@@ -228,6 +228,138 @@ pub(super) fn set_update_badge(visible: bool) {
 #[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
 pub(super) fn set_update_badge(_visible: bool) {}
 
+#[cfg(target_os = "macos")]
+pub(super) fn set_operation_progress(progress: Option<(usize, usize)>) {
+    use objc2::{MainThreadMarker, MainThreadOnly};
+    use objc2_app_kit::{
+        NSApplication, NSImageView, NSProgressIndicator, NSProgressIndicatorStyle, NSView,
+    };
+    use objc2_foundation::{NSPoint, NSRect, NSSize};
+
+    let Some(mtm) = MainThreadMarker::new() else {
+        return;
+    };
+    let app = NSApplication::sharedApplication(mtm);
+    let tile = app.dockTile();
+
+    let Some((completed, total)) = progress else {
+        tile.setContentView(None);
+        tile.display();
+        return;
+    };
+    let Some(icon) = app.applicationIconImage() else {
+        return;
+    };
+
+    let size = tile.size();
+    let view = NSImageView::imageViewWithImage(&icon, mtm);
+    view.setFrame(NSRect::new(NSPoint::new(0.0, 0.0), size));
+
+    let bar_frame = NSRect::new(
+        NSPoint::new(size.width * 0.1, size.height * 0.08),
+        NSSize::new(size.width * 0.8, size.height * 0.12),
+    );
+    let bar = NSProgressIndicator::initWithFrame(NSProgressIndicator::alloc(mtm), bar_frame);
+    bar.setStyle(NSProgressIndicatorStyle::Bar);
+    bar.setIndeterminate(false);
+    bar.setMinValue(0.0);
+    bar.setMaxValue(total.max(1) as f64);
+    // Show a sliver of progress while the first operation runs.
+    bar.setDoubleValue((completed as f64).max(total as f64 * 0.05));
+    view.addSubview(&bar);
+
+    let content: &NSView = &view;
+    tile.setContentView(Some(content));
+    tile.display();
+}
+
+#[cfg(target_os = "linux")]
+pub(super) fn set_operation_progress(progress: Option<(usize, usize)>) {
+    use log::debug;
+
+    std::thread::spawn(move || {
+        let result = (|| -> Result<(), Box<dyn std::error::Error>> {
+            let connection = zbus::blocking::Connection::session()?;
+
+            let value = progress
+                .map(|(completed, total)| completed as f64 / total.max(1) as f64)
+                .unwrap_or(0.0);
+            let mut props = std::collections::HashMap::new();
+            props.insert("progress", zbus::zvariant::Value::from(value));
+            props.insert(
+                "progress-visible",
+                zbus::zvariant::Value::from(progress.is_some()),
+            );
+
+            connection.emit_signal(
+                None::<zbus::names::BusName>,
+                "/",
+                "com.canonical.Unity.LauncherEntry",
+                "Update",
+                &("application://dev.almeidx.versi.desktop", props),
+            )?;
+
+            Ok(())
+        })();
+
+        if let Err(e) = result {
+            debug!("Failed to set launcher progress: {}", e);
+        }
+    });
+}
+
+#[cfg(windows)]
+pub(super) fn set_operation_progress(progress: Option<(usize, usize)>) {
+    use log::debug;
+    use windows::Win32::System::Com::{
+        CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED, CoCreateInstance, CoInitializeEx,
+        CoUninitialize,
+    };
+    use windows::Win32::UI::Shell::{
+        ITaskbarList3, TBPF_INDETERMINATE, TBPF_NOPROGRESS, TBPF_NORMAL, TaskbarList,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::FindWindowA;
+    use windows::core::{PCSTR, s};
+
+    unsafe {
+        let hwnd = match FindWindowA(PCSTR::null(), s!("Versi")) {
+            Ok(h) if !h.is_invalid() => h,
+            _ => {
+                debug!("Could not find Versi window for taskbar progress");
+                return;
+            }
+        };
+
+        let com_initialized = CoInitializeEx(None, COINIT_APARTMENTTHREADED).is_ok();
+
+        let result = (|| -> Result<(), Box<dyn std::error::Error>> {
+            let taskbar: ITaskbarList3 =
+                CoCreateInstance(&TaskbarList, None, CLSCTX_INPROC_SERVER)?;
+
+            match progress {
+                None => taskbar.SetProgressState(hwnd, TBPF_NOPROGRESS)?,
+                Some((0, _)) => taskbar.SetProgressState(hwnd, TBPF_INDETERMINATE)?,
+                Some((completed, total)) => {
+                    taskbar.SetProgressState(hwnd, TBPF_NORMAL)?;
+                    taskbar.SetProgressValue(hwnd, completed as u64, total as u64)?;
+                }
+            }
+            Ok(())
+        })();
+
+        if com_initialized {
+            CoUninitialize();
+        }
+
+        if let Err(e) = result {
+            debug!("Failed to set taskbar progress: {}", e);
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+pub(super) fn set_operation_progress(_progress: Option<(usize, usize)>) {}
+
 #[cfg(target_os = "macos")]
 pub(super) fn set_dock_visible(visible: bool) {
     use objc2::MainThreadMarker;
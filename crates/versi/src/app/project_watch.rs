@@ -0,0 +1,131 @@
+//! Following a project directory: its version file is read while it is
+//! followed, so the version it needs can be highlighted and made the
+//! default in one click.
+//!
+//! Handles messages: PickFollowedDirectory, FollowedDirectoryPicked,
+//! ClearFollowedDirectory, ProjectWatchTick, ProjectPinRead
+
+use log::{error, info};
+use std::path::PathBuf;
+
+use iced::Task;
+
+use crate::message::Message;
+use crate::project_watch::ProjectPin;
+use crate::state::AppState;
+
+use super::Versi;
+
+impl Versi {
+    pub(super) fn handle_pick_followed_directory(&mut self) -> Task<Message> {
+        Task::perform(
+            async {
+                rfd::AsyncFileDialog::new()
+                    .set_title("Select a project to follow")
+                    .pick_folder()
+                    .await
+                    .map(|handle| handle.path().to_path_buf())
+            },
+            Message::FollowedDirectoryPicked,
+        )
+    }
+
+    pub(super) fn handle_followed_directory_picked(
+        &mut self,
+        path: Option<PathBuf>,
+    ) -> Task<Message> {
+        let Some(path) = path else {
+            return Task::none();
+        };
+        if self.settings.followed_directory.as_ref() != Some(&path) {
+            info!("Following project directory {}", path.display());
+            self.settings.followed_directory = Some(path);
+            if let Err(e) = self.settings.save() {
+                error!("Failed to save settings: {e}");
+            }
+        }
+        self.refresh_project_pin()
+    }
+
+    pub(super) fn handle_clear_followed_directory(&mut self) -> Task<Message> {
+        self.settings.followed_directory = None;
+        if let Err(e) = self.settings.save() {
+            error!("Failed to save settings: {e}");
+        }
+        if let AppState::Main(state) = &mut self.state {
+            state.project_pin = None;
+        }
+        Task::none()
+    }
+
+    pub(super) fn handle_project_watch_tick(&mut self) -> Task<Message> {
+        self.refresh_project_pin()
+    }
+
+    /// Reads the followed directory's version file again off the UI thread,
+    /// as it may be on a slow or unreachable drive. A read still running is
+    /// not started again.
+    pub(super) fn refresh_project_pin(&mut self) -> Task<Message> {
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+        let Some(dir) = self.settings.followed_directory.clone() else {
+            state.project_pin = None;
+            return Task::none();
+        };
+        if state.reading_project_pin {
+            return Task::none();
+        }
+        state.reading_project_pin = true;
+
+        Task::perform(
+            async move {
+                let root = dir.clone();
+                let pin = tokio::task::spawn_blocking(move || ProjectPin::find(&root))
+                    .await
+                    .unwrap_or_default();
+                (dir, pin)
+            },
+            |(dir, pin)| Message::ProjectPinRead(dir, pin),
+        )
+    }
+
+    /// When the pin changes, the major it asks for is expanded in the
+    /// version list.
+    pub(super) fn handle_project_pin_read(
+        &mut self,
+        dir: PathBuf,
+        pin: Option<ProjectPin>,
+    ) -> Task<Message> {
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+        state.reading_project_pin = false;
+        // The directory was changed or cleared while it was read.
+        if self.settings.followed_directory.as_ref() != Some(&dir) {
+            return self.refresh_project_pin();
+        }
+        if pin == state.project_pin {
+            return Task::none();
+        }
+
+        if let Some(pin) = &pin {
+            info!("{} pins Node {} in {}", pin.name(), pin.spec, pin.file);
+            let major = pin
+                .installed_match(
+                    &state.active_environment().installed_versions,
+                    &state.available_versions.remote().versions,
+                    state.available_versions.schedule.as_ref(),
+                    chrono::Local::now().date_naive(),
+                )
+                .map(|version| version.major);
+            for group in &mut state.active_environment_mut().version_groups {
+                if Some(group.major) == major {
+                    group.is_expanded = true;
+                }
+            }
+        }
+        state.project_pin = pin;
+        Task::none()
+    }
+}
@@ -0,0 +1,63 @@
+//! Protected versions: kept out of bulk and scheduled removals, and
+//! uninstalled one at a time only after an extra confirmation.
+//!
+//! Handles messages: ToggleVersionProtected, ConfirmUninstallProtected
+
+use log::{error, info};
+
+use iced::Task;
+
+use versi_platform::EnvironmentId;
+
+use crate::message::Message;
+use crate::settings::AppSettings;
+use crate::state::{AppState, OperationRequest};
+
+use super::Versi;
+
+impl Versi {
+    pub(super) fn handle_toggle_version_protected(&mut self, version: String) {
+        let AppState::Main(state) = &self.state else {
+            return;
+        };
+        let env_id = &state.active_environment().id;
+        self.settings.toggle_protected(env_id, &version);
+        info!(
+            "Node {} is {} in {}",
+            version,
+            if self.settings.is_protected(env_id, &version) {
+                "protected"
+            } else {
+                "no longer protected"
+            },
+            env_id.display_name()
+        );
+        if let Err(e) = self.settings.save() {
+            error!("Failed to save settings: {e}");
+        }
+    }
+
+    pub(super) fn handle_confirm_uninstall_protected(&mut self, version: String) -> Task<Message> {
+        self.handle_confirm_uninstall_default(version)
+    }
+}
+
+/// Removes uninstalls of protected versions from a bulk or unattended plan.
+pub(super) fn without_protected(
+    settings: &AppSettings,
+    env_id: &EnvironmentId,
+    plan: Vec<OperationRequest>,
+) -> Vec<OperationRequest> {
+    plan.into_iter()
+        .filter(|request| match request {
+            OperationRequest::Uninstall { version } => {
+                let protected = settings.is_protected(env_id, version);
+                if protected {
+                    info!("Keeping protected Node {}", version);
+                }
+                !protected
+            }
+            _ => true,
+        })
+        .collect()
+}
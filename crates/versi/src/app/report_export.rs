@@ -0,0 +1,91 @@
+//! Installed-versions report export for audits, and the short summary of
+//! one environment copied for chat.
+//!
+//! Handles messages: ExportReport, ReportExported, CopyEnvironmentReport
+
+use log::info;
+use std::path::PathBuf;
+
+use iced::Task;
+
+use crate::message::Message;
+use crate::report::{self, Report, ReportFormat};
+use crate::settings::NotificationCategory;
+use crate::state::{AppState, Toast};
+
+use super::Versi;
+
+impl Versi {
+    pub(super) fn handle_export_report(&mut self, format: ReportFormat) -> Task<Message> {
+        let AppState::Main(state) = &self.state else {
+            return Task::none();
+        };
+
+        let report = Report::build(
+            &state.environments,
+            state.available_versions.schedule.as_ref(),
+            chrono::Utc::now(),
+        );
+        let content = match report.render(format) {
+            Ok(content) => content,
+            Err(e) => return Task::done(Message::ReportExported(Err(e))),
+        };
+        let file_name = format!(
+            "versi-report-{}.{}",
+            report.generated_at.format("%Y-%m-%d"),
+            format.extension()
+        );
+
+        Task::perform(
+            async move {
+                let handle = rfd::AsyncFileDialog::new()
+                    .set_file_name(&file_name)
+                    .add_filter(format.label(), &[format.extension()])
+                    .save_file()
+                    .await
+                    .ok_or_else(|| "Cancelled".to_string())?;
+                let path = handle.path().to_path_buf();
+                tokio::fs::write(&path, content)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(path)
+            },
+            Message::ReportExported,
+        )
+    }
+
+    pub(super) fn handle_report_exported(&mut self, result: Result<PathBuf, String>) {
+        match result {
+            Ok(path) => info!("Exported report to {:?}", path),
+            Err(e) if e != "Cancelled" => {
+                if let AppState::Main(state) = &mut self.state {
+                    let id = state.next_toast_id();
+                    state.add_toast(
+                        Toast::error(id, format!("Report export failed: {}", e)),
+                        &self.settings.notification_delivery,
+                    );
+                }
+            }
+            Err(_) => {}
+        }
+    }
+
+    pub(super) fn handle_copy_environment_report(&mut self, idx: usize) -> Task<Message> {
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+        let Some(env) = state.environments.get(idx) else {
+            return Task::none();
+        };
+        info!("Copying the {} report", env.name);
+        let summary =
+            report::environment_summary(env, &state.available_versions.remote().latest_by_major);
+        let message = format!("Copied the {} report", env.name);
+        let id = state.next_toast_id();
+        state.add_toast(
+            Toast::notice(id, message, NotificationCategory::Completions),
+            &self.settings.notification_delivery,
+        );
+        iced::clipboard::write(summary)
+    }
+}
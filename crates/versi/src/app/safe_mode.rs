@@ -0,0 +1,41 @@
+//! Leaving safe mode after state files failed to load.
+//!
+//! Handles messages: ResetCorruptFiles
+
+use log::{error, info};
+
+use crate::safe_mode;
+use crate::state::{AppState, Toast};
+
+use super::Versi;
+
+impl Versi {
+    /// Backs up the files that failed to load and saves the default
+    /// settings in their place.
+    pub(super) fn handle_reset_corrupt_files(&mut self) {
+        let AppState::Main(state) = &mut self.state else {
+            return;
+        };
+        let Some(safe) = &mut state.safe_mode else {
+            return;
+        };
+
+        match safe_mode::back_up_and_reset(&safe.corrupt) {
+            Ok(backups) => {
+                for backup in &backups {
+                    info!("Backed up a corrupted file to {}", backup.display());
+                }
+                safe.corrupt.clear();
+                safe.backups = backups;
+                if let Err(e) = self.settings.save() {
+                    error!("Failed to save settings: {e}");
+                }
+            }
+            Err(e) => {
+                error!("{}", e);
+                let id = state.next_toast_id();
+                state.add_toast(Toast::error(id, e), &self.settings.notification_delivery);
+            }
+        }
+    }
+}
@@ -0,0 +1,174 @@
+//! Running bulk operations unattended at a set time or once the machine is
+//! idle, then reporting the results with a desktop notification.
+//!
+//! Handles messages: ScheduleDraftTaskChanged, ScheduleDraftTimeChanged,
+//! ScheduleDraftWhenIdleToggled, AddScheduledJob, RemoveScheduledJob,
+//! SchedulerTick, SchedulerConditionsChecked
+
+use log::info;
+
+use iced::Task;
+
+use crate::message::Message;
+use crate::scheduler::{ScheduledJob, ScheduledTask, SystemConditions};
+use crate::state::{AppState, Toast};
+
+use super::bulk_operations::record_snapshot;
+use super::{Versi, protection};
+
+impl Versi {
+    pub(super) fn handle_schedule_draft_task_changed(&mut self, task: ScheduledTask) {
+        if let AppState::Main(state) = &mut self.state {
+            state.settings_state.schedule_draft.task = task;
+        }
+    }
+
+    pub(super) fn handle_schedule_draft_time_changed(&mut self, time: String) {
+        if let AppState::Main(state) = &mut self.state {
+            state.settings_state.schedule_draft.time = time;
+        }
+    }
+
+    pub(super) fn handle_schedule_draft_when_idle_toggled(&mut self, when_idle: bool) {
+        if let AppState::Main(state) = &mut self.state {
+            state.settings_state.schedule_draft.when_idle = when_idle;
+        }
+    }
+
+    pub(super) fn handle_add_scheduled_job(&mut self) {
+        let AppState::Main(state) = &mut self.state else {
+            return;
+        };
+        let draft = &state.settings_state.schedule_draft;
+        let Some(trigger) = draft.trigger(chrono::Local::now()) else {
+            let id = state.next_toast_id();
+            state.add_toast(
+                Toast::error(id, "Enter a time as HH:MM".to_string()),
+                &self.settings.notification_delivery,
+            );
+            return;
+        };
+
+        let job = ScheduledJob {
+            id: self
+                .settings
+                .scheduled_jobs
+                .iter()
+                .map(|job| job.id + 1)
+                .max()
+                .unwrap_or(1),
+            task: draft.task,
+            trigger,
+            environment: state.active_environment().id.settings_key(),
+        };
+        info!(
+            "Scheduled {:?} in {} {}",
+            job.task,
+            job.environment,
+            job.trigger.describe()
+        );
+        self.settings.scheduled_jobs.push(job);
+        if let Err(e) = self.settings.save() {
+            log::error!("Failed to save settings: {e}");
+        }
+    }
+
+    pub(super) fn handle_remove_scheduled_job(&mut self, id: u64) {
+        self.settings.scheduled_jobs.retain(|job| job.id != id);
+        if let Err(e) = self.settings.save() {
+            log::error!("Failed to save settings: {e}");
+        }
+    }
+
+    pub(super) fn handle_scheduler_tick(&mut self) -> Task<Message> {
+        if self.settings.scheduled_jobs.is_empty() {
+            return Task::none();
+        }
+        Task::perform(
+            SystemConditions::read(),
+            Message::SchedulerConditionsChecked,
+        )
+    }
+
+    /// Starts the first due job, one job at a time and only while no other
+    /// operations are running. A job for another environment switches to it
+    /// first and starts on a later check, once it is loaded and ready.
+    pub(super) fn handle_scheduler_conditions_checked(
+        &mut self,
+        conditions: SystemConditions,
+    ) -> Task<Message> {
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+        if state.running_job.is_some()
+            || !state.operation_queue.is_idle()
+            || self.settings.is_read_only()
+        {
+            return Task::none();
+        }
+
+        let now = chrono::Utc::now();
+        let due = |job: &ScheduledJob| job.trigger.is_due(now, &conditions);
+        let env_key = state.active_environment().id.settings_key();
+
+        // Jobs for the active environment go first, so two environments with
+        // due jobs don't keep switching back and forth.
+        if !self
+            .settings
+            .scheduled_jobs
+            .iter()
+            .any(|job| job.environment == env_key && due(job))
+        {
+            let target = self.settings.scheduled_jobs.iter().find_map(|job| {
+                let idx = state.environments.iter().position(|env| {
+                    env.available && env.error.is_none() && env.id.settings_key() == job.environment
+                })?;
+                due(job).then_some((idx, job.task, job.environment.clone()))
+            });
+            let Some((idx, task, environment)) = target else {
+                return Task::none();
+            };
+            info!("Switching to {} for scheduled {:?}", environment, task);
+            return self.handle_environment_selected(idx);
+        }
+
+        let env = state.active_environment();
+        let latest_by_major = &state.available_versions.remote().latest_by_major;
+        let schedule = state.available_versions.schedule.as_ref();
+        let Some(index) = self.settings.scheduled_jobs.iter().position(|job| {
+            job.environment == env_key
+                && job.task.is_ready(env, latest_by_major, schedule)
+                && due(job)
+        }) else {
+            return Task::none();
+        };
+
+        let job = self.settings.scheduled_jobs.remove(index);
+        if let Err(e) = self.settings.save() {
+            log::error!("Failed to save settings: {e}");
+        }
+
+        let plan = protection::without_protected(
+            &self.settings,
+            &env.id,
+            job.task.plan(env, latest_by_major, schedule),
+        );
+        info!(
+            "Running scheduled {:?} in {} with {} operation(s)",
+            job.task,
+            env_key,
+            plan.len()
+        );
+        if plan.is_empty() {
+            versi_platform::show_notification(job.task.label(), "Nothing to do");
+            return Task::none();
+        }
+
+        record_snapshot(state);
+        for request in plan {
+            state.operation_queue.enqueue(request);
+        }
+        state.running_job = Some(job);
+        self.process_next_operation()
+    }
+}
@@ -0,0 +1,56 @@
+//! Undoing settings changes and resetting sections to their defaults.
+//!
+//! Handles messages: RevertSettingsChange, ResetSettingsSection
+
+use log::info;
+
+use iced::Task;
+
+use crate::message::Message;
+use crate::settings::{AppSettings, SettingsSection};
+use crate::settings_journal;
+use crate::state::AppState;
+
+use super::Versi;
+
+impl Versi {
+    pub(super) fn handle_revert_settings_change(&mut self) -> Task<Message> {
+        let Some((settings, labels)) = settings_journal::revert_last(&self.settings) else {
+            return Task::none();
+        };
+        info!("Reverting settings change to {}", labels.join(", "));
+        self.replace_settings(settings)
+    }
+
+    pub(super) fn handle_reset_settings_section(
+        &mut self,
+        section: SettingsSection,
+    ) -> Task<Message> {
+        let backend = match &self.state {
+            AppState::Main(state) => state.backend_name,
+            _ => self.provider.name(),
+        };
+        let mut settings = self.settings.clone();
+        settings.reset_section(section, backend);
+        info!("Resetting {} settings to defaults", section.label());
+        self.replace_settings(settings)
+    }
+
+    /// Saves `settings` and applies whatever changed.
+    fn replace_settings(&mut self, settings: AppSettings) -> Task<Message> {
+        let previous = std::mem::replace(&mut self.settings, settings);
+        if let Err(e) = self.settings.save() {
+            log::error!("Failed to save settings: {e}");
+        }
+
+        if previous.tray_behavior != self.settings.tray_behavior {
+            self.apply_tray_behavior(&previous.tray_behavior);
+        }
+        let providers = if previous.sanitize_backend_env != self.settings.sanitize_backend_env {
+            self.reload_backend_providers()
+        } else {
+            Task::none()
+        };
+        Task::batch([self.apply_settings_change(&previous), providers])
+    }
+}
@@ -0,0 +1,106 @@
+//! Keeping shared settings the same on several machines through a sync
+//! file, such as one in a Dropbox folder.
+//!
+//! Handles messages: PickSyncFile, SyncFilePicked, ClearSyncFile
+
+use log::{error, info, warn};
+use std::path::PathBuf;
+
+use iced::Task;
+
+use versi_platform::AppPaths;
+
+use crate::message::Message;
+use crate::settings_sync::{self, SyncOutcome};
+
+use super::Versi;
+
+impl Versi {
+    pub(super) fn handle_pick_sync_file(&mut self) -> Task<Message> {
+        Task::perform(
+            async {
+                rfd::AsyncFileDialog::new()
+                    .set_title("Choose a file to sync settings through")
+                    .set_file_name("versi-sync.json")
+                    .add_filter("JSON", &["json"])
+                    .save_file()
+                    .await
+                    .map(|handle| handle.path().to_path_buf())
+            },
+            Message::SyncFilePicked,
+        )
+    }
+
+    pub(super) fn handle_sync_file_picked(&mut self, path: Option<PathBuf>) -> Task<Message> {
+        let Some(path) = path else {
+            return Task::none();
+        };
+        if self.settings.sync_file.as_ref() == Some(&path) {
+            return Task::none();
+        }
+        let Ok(paths) = AppPaths::new() else {
+            return Task::none();
+        };
+        info!("Syncing settings through {}", path.display());
+        settings_sync::forget(&paths.sync_base_file());
+        self.settings.sync_file = Some(path.clone());
+        if let Err(e) = self.settings.save() {
+            error!("Failed to save settings: {e}");
+        }
+        let outcome = settings_sync::sync(&path, &paths.sync_base_file(), &self.settings);
+        self.apply_sync_outcome(outcome)
+    }
+
+    pub(super) fn handle_clear_sync_file(&mut self) {
+        self.settings.sync_file = None;
+        if let Ok(paths) = AppPaths::new() {
+            settings_sync::forget(&paths.sync_base_file());
+        }
+        if let Err(e) = self.settings.save() {
+            error!("Failed to save settings: {e}");
+        }
+    }
+
+    /// Merges changes other machines made to the sync file, if it changed.
+    pub(super) fn check_sync_file(&mut self) -> Task<Message> {
+        if crate::safe_mode::is_active() {
+            return Task::none();
+        }
+        let (Some(path), Ok(paths)) = (&self.settings.sync_file, AppPaths::new()) else {
+            return Task::none();
+        };
+        match settings_sync::check(path, &paths.sync_base_file(), &self.settings) {
+            Some(outcome) => self.apply_sync_outcome(outcome),
+            None => Task::none(),
+        }
+    }
+
+    fn apply_sync_outcome(&mut self, outcome: SyncOutcome) -> Task<Message> {
+        if let Some(e) = outcome.error {
+            self.settings_error(format!("Could not sync settings: {}", e));
+        }
+        let mut task = Task::none();
+        if let Some(settings) = outcome.settings {
+            info!(
+                "Took settings from the sync file: {}",
+                outcome.changed.join(", ")
+            );
+            let previous = std::mem::replace(&mut self.settings, *settings);
+            task = self.apply_settings_change(&previous);
+            if let Err(e) = self.settings.save() {
+                error!("Failed to save settings: {e}");
+            }
+        }
+        if !outcome.conflicts.is_empty() {
+            warn!(
+                "Kept this machine's values for {}",
+                outcome.conflicts.join(", ")
+            );
+            self.settings_notice(format!(
+                "Another machine also changed {}; kept the changes made here",
+                outcome.conflicts.join(", ")
+            ));
+        }
+        task
+    }
+}
@@ -0,0 +1,142 @@
+//! Reloading settings edited by hand while Versi runs, and polling the
+//! sync file shared with other machines.
+//!
+//! Handles messages: SettingsFileCheck
+
+use log::{info, warn};
+
+use iced::Task;
+
+use versi_platform::AppPaths;
+
+use crate::message::Message;
+use crate::settings::{AppSettings, NotificationCategory};
+use crate::settings_journal;
+use crate::settings_watch::{self, ExternalEdit, RESTART_REQUIRED};
+use crate::state::{AppState, Toast};
+
+use super::Versi;
+use super::network::build_http_client;
+
+impl Versi {
+    pub(super) fn handle_settings_file_check(&mut self) -> Task<Message> {
+        let Ok(paths) = AppPaths::new() else {
+            return Task::none();
+        };
+        let reload = match settings_watch::check(&paths.settings_file(), &self.settings) {
+            None => Task::none(),
+            Some(ExternalEdit::Invalid(e)) => {
+                warn!("Ignoring invalid settings file: {}", e);
+                self.settings_error(format!(
+                    "settings.json could not be read, keeping the current settings: {}",
+                    e
+                ));
+                Task::none()
+            }
+            Some(ExternalEdit::Changed {
+                settings,
+                changed,
+                conflicts,
+            }) => self.apply_external_settings(*settings, changed, conflicts),
+        };
+        Task::batch([reload, self.check_sync_file()])
+    }
+
+    /// Switches to settings edited outside Versi and applies the ones that
+    /// can change while it runs.
+    fn apply_external_settings(
+        &mut self,
+        settings: AppSettings,
+        changed: Vec<String>,
+        conflicts: Vec<String>,
+    ) -> Task<Message> {
+        info!(
+            "Reloaded settings edited outside Versi: {}",
+            changed.join(", ")
+        );
+        let previous = std::mem::replace(&mut self.settings, settings);
+        settings_journal::record_loaded(&self.settings);
+        let task = self.apply_settings_change(&previous);
+
+        let mut notes = Vec::new();
+        if !conflicts.is_empty() {
+            warn!("Kept in-app values for {}", conflicts.join(", "));
+            notes.push(format!("kept Versi's changes to {}", conflicts.join(", ")));
+            if let Err(e) = self.settings.save() {
+                log::error!("Failed to save settings: {e}");
+            }
+        }
+        let restart: Vec<&str> = changed
+            .iter()
+            .map(String::as_str)
+            .filter(|key| RESTART_REQUIRED.contains(key))
+            .collect();
+        if !restart.is_empty() {
+            notes.push(format!("restart Versi to apply {}", restart.join(", ")));
+        }
+        if !notes.is_empty() {
+            self.settings_notice(format!("Reloaded settings.json; {}", notes.join("; ")));
+        }
+        task
+    }
+
+    /// Applies the settings that can change while Versi runs after
+    /// `self.settings` was replaced wholesale.
+    pub(super) fn apply_settings_change(&mut self, previous: &AppSettings) -> Task<Message> {
+        if previous.debug_logging != self.settings.debug_logging {
+            crate::logging::set_logging_enabled(self.settings.debug_logging);
+        }
+        if previous.http_timeout_secs != self.settings.http_timeout_secs
+            || previous.ca_bundle_path != self.settings.ca_bundle_path
+            || previous.github_token != self.settings.github_token
+        {
+            self.http_client = build_http_client(&self.settings);
+            self.github = self
+                .github
+                .reconfigure(self.http_client.clone(), self.settings.github_token.clone());
+        }
+        if let AppState::Main(state) = &mut self.state {
+            let env = state.active_environment();
+            let overrides = self
+                .settings
+                .env_overrides_for(&env.id, env.target.as_ref());
+            state.backend.set_env_overrides(overrides);
+        }
+        if previous.window_vibrancy != self.settings.window_vibrancy {
+            self.handle_window_vibrancy_toggled(self.settings.window_vibrancy);
+        }
+        self.update_tray_menu();
+
+        let detect_proxy = if previous.proxy_mode != self.settings.proxy_mode
+            || previous.pac_url != self.settings.pac_url
+        {
+            self.handle_detect_proxy()
+        } else {
+            Task::none()
+        };
+        if previous.always_on_top != self.settings.always_on_top {
+            return Task::batch([
+                detect_proxy,
+                self.handle_always_on_top_toggled(self.settings.always_on_top),
+            ]);
+        }
+        detect_proxy
+    }
+
+    pub(super) fn settings_error(&mut self, message: String) {
+        self.settings_toast(|id| Toast::error(id, message));
+    }
+
+    /// Reports something worth knowing about reloaded or synced settings
+    /// that did not fail.
+    pub(super) fn settings_notice(&mut self, message: String) {
+        self.settings_toast(|id| Toast::notice(id, message, NotificationCategory::Updates));
+    }
+
+    fn settings_toast(&mut self, toast: impl FnOnce(usize) -> Toast) {
+        if let AppState::Main(state) = &mut self.state {
+            let id = state.next_toast_id();
+            state.add_toast(toast(id), &self.settings.notification_delivery);
+        }
+    }
+}
@@ -1,63 +1,76 @@
 //! Shell configuration detection, setup, and flag updates.
 //!
 //! Handles messages: ShellSetupChecked, ConfigureShell, ShellConfigured,
-//! ShellFlagsUpdated
+//! PreviewShellConfig, ShellConfigPreviewed, ShellFlagsUpdated, CheckResolvedVersions, ResolvedVersionsChecked,
+//! RunTerminalTroubleshooter, TerminalTroubleshooterFinished,
+//! PreviewTerminalFix, TerminalFixPreviewed, ApplyTerminalFix, TerminalFixApplied
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 
 use iced::Task;
 
-#[cfg(target_os = "windows")]
+use versi_backend::BackendProvider;
 use versi_platform::EnvironmentId;
-use versi_shell::{ShellInitOptions, detect_shells};
+use versi_shell::{
+    NodeResolution, ShellConfig, ShellConfigEdit, ShellInitOptions, ShellType, TroubleshootFix,
+    detect_shells,
+};
 
 use crate::message::Message;
-use crate::state::{AppState, ShellSetupStatus, ShellVerificationStatus};
+use crate::state::{
+    AppState, ShellSetupStatus, ShellVerificationStatus, TerminalFixPreview, Toast,
+};
 
 use super::Versi;
 
 impl Versi {
     pub(super) fn handle_check_shell_setup(&mut self) -> Task<Message> {
-        use versi_shell::{detect_native_shells, verify_shell_config};
-        #[cfg(target_os = "windows")]
-        use versi_shell::{detect_wsl_shells, verify_wsl_shell_config};
-
-        #[cfg(target_os = "windows")]
-        let env_id = if let AppState::Main(state) = &self.state {
-            Some(state.active_environment().id.clone())
-        } else {
-            None
+        use versi_shell::{
+            detect_msys_shells, detect_native_shells, detect_wsl_shells, verify_shell_config,
+            verify_wsl_shell_config,
         };
 
-        let marker = self.provider.shell_config_marker().to_string();
-        let backend_name = self.provider.name().to_string();
+        let targets = self.shell_targets();
 
         Task::perform(
             async move {
-                #[cfg(target_os = "windows")]
-                let (shells, wsl_distro) = match &env_id {
-                    Some(EnvironmentId::Wsl { distro, .. }) => {
-                        (detect_wsl_shells(distro), Some(distro.clone()))
-                    }
-                    _ => (detect_native_shells(), None),
-                };
-                #[cfg(not(target_os = "windows"))]
-                let (shells, wsl_distro): (Vec<_>, Option<String>) = (detect_native_shells(), None);
-
                 let mut results = Vec::new();
 
-                for shell in shells {
-                    #[cfg(target_os = "windows")]
-                    let result = if let Some(distro) = &wsl_distro {
-                        verify_wsl_shell_config(&shell.shell_type, distro, &marker, &backend_name)
-                            .await
-                    } else {
-                        verify_shell_config(&shell.shell_type, &marker, &backend_name).await
-                    };
-                    #[cfg(not(target_os = "windows"))]
-                    let result = {
-                        let _ = &wsl_distro;
-                        verify_shell_config(&shell.shell_type, &marker, &backend_name).await
-                    };
-                    results.push((shell.shell_type, result));
+                for (env_id, provider) in targets {
+                    let marker = provider.shell_config_marker();
+                    let backend_name = provider.name();
+                    match &env_id {
+                        EnvironmentId::Native => {
+                            for shell in detect_native_shells() {
+                                let result =
+                                    verify_shell_config(&shell.shell_type, marker, backend_name)
+                                        .await;
+                                results.push((env_id.clone(), shell.shell_type, result));
+                            }
+                        }
+                        EnvironmentId::Wsl { distro, .. } => {
+                            for shell in detect_wsl_shells(distro) {
+                                let result = verify_wsl_shell_config(
+                                    &shell.shell_type,
+                                    distro,
+                                    marker,
+                                    backend_name,
+                                )
+                                .await;
+                                results.push((env_id.clone(), shell.shell_type, result));
+                            }
+                        }
+                        EnvironmentId::Msys { bash, .. } => {
+                            for shell in detect_msys_shells(bash) {
+                                let result =
+                                    verify_shell_config(&shell.shell_type, marker, backend_name)
+                                        .await;
+                                results.push((env_id.clone(), shell.shell_type, result));
+                            }
+                        }
+                    }
                 }
 
                 results
@@ -66,20 +79,64 @@ impl Versi {
         )
     }
 
+    /// Every available environment whose shells Settings checks, with the
+    /// provider of the backend it runs.
+    fn shell_targets(&self) -> Vec<(EnvironmentId, Arc<dyn BackendProvider>)> {
+        let AppState::Main(state) = &self.state else {
+            return vec![(EnvironmentId::Native, self.provider.clone())];
+        };
+        state
+            .environments
+            .iter()
+            .filter(|env| env.available)
+            .filter_map(|env| {
+                let provider = self.providers.get(env.backend_name)?;
+                Some((env.id.clone(), provider.clone()))
+            })
+            .collect()
+    }
+
+    fn provider_for(&self, env_id: &EnvironmentId) -> Arc<dyn BackendProvider> {
+        let AppState::Main(state) = &self.state else {
+            return self.provider.clone();
+        };
+        state
+            .environments
+            .iter()
+            .find(|env| &env.id == env_id)
+            .and_then(|env| self.providers.get(env.backend_name))
+            .cloned()
+            .unwrap_or_else(|| self.provider.clone())
+    }
+
+    fn shell_init_options(&self, backend: &str) -> ShellInitOptions {
+        let backend_opts = self.settings.shell_options_for(backend);
+        ShellInitOptions {
+            use_on_cd: backend_opts.use_on_cd,
+            resolve_engines: backend_opts.resolve_engines,
+            corepack_enabled: backend_opts.corepack_enabled,
+        }
+    }
+
     pub(super) fn handle_shell_setup_checked(
         &mut self,
-        results: Vec<(versi_shell::ShellType, versi_shell::VerificationResult)>,
+        results: Vec<(
+            EnvironmentId,
+            versi_shell::ShellType,
+            versi_shell::VerificationResult,
+        )>,
     ) {
         let mut first_detected_options: Option<ShellInitOptions> = None;
 
         if let AppState::Main(state) = &mut self.state {
+            let active_env = state.active_environment().id.clone();
             state.settings_state.checking_shells = false;
             state.settings_state.shell_statuses = results
                 .into_iter()
-                .map(|(shell_type, result)| {
+                .map(|(environment, shell_type, result)| {
                     let status = match result {
                         versi_shell::VerificationResult::Configured(options) => {
-                            if first_detected_options.is_none() {
+                            if first_detected_options.is_none() && environment == active_env {
                                 first_detected_options = options;
                             }
                             ShellVerificationStatus::Configured
@@ -96,10 +153,12 @@ impl Versi {
                         versi_shell::VerificationResult::Error(_) => ShellVerificationStatus::Error,
                     };
                     ShellSetupStatus {
+                        environment,
                         shell_name: shell_type.name().to_string(),
                         shell_type,
                         status,
                         configuring: false,
+                        preview: None,
                     }
                 })
                 .collect();
@@ -113,84 +172,62 @@ impl Versi {
         }
     }
 
+    fn shell_status_mut(
+        &mut self,
+        env_id: &EnvironmentId,
+        shell_type: &ShellType,
+    ) -> Option<&mut ShellSetupStatus> {
+        let AppState::Main(state) = &mut self.state else {
+            return None;
+        };
+        state
+            .settings_state
+            .shell_statuses
+            .iter_mut()
+            .find(|s| &s.environment == env_id && &s.shell_type == shell_type)
+    }
+
     pub(super) fn handle_configure_shell(
         &mut self,
-        shell_type: versi_shell::ShellType,
+        env_id: EnvironmentId,
+        shell_type: ShellType,
     ) -> Task<Message> {
-        if let AppState::Main(state) = &mut self.state
-            && let Some(shell) = state
-                .settings_state
-                .shell_statuses
-                .iter_mut()
-                .find(|s| s.shell_type == shell_type)
-        {
+        if let Some(shell) = self.shell_status_mut(&env_id, &shell_type) {
             shell.configuring = true;
         }
 
-        let backend_opts = self.settings.shell_options_for(self.provider.name());
-        let options = ShellInitOptions {
-            use_on_cd: backend_opts.use_on_cd,
-            resolve_engines: backend_opts.resolve_engines,
-            corepack_enabled: backend_opts.corepack_enabled,
-        };
+        let provider = self.provider_for(&env_id);
+        let options = self.shell_init_options(provider.name());
 
-        let provider = self.provider.clone();
-        let marker = provider.shell_config_marker().to_string();
-        let label = provider.shell_config_label().to_string();
-
-        let shell_type_for_callback = shell_type.clone();
         Task::perform(
             async move {
-                use versi_shell::{ShellConfig, get_or_create_config_path};
-
-                let config_path = get_or_create_config_path(&shell_type)
-                    .ok_or_else(|| "No config file path found".to_string())?;
-
-                let mut config = ShellConfig::load(shell_type.clone(), config_path)
-                    .map_err(|e| e.to_string())?;
-
-                if config.has_init(&marker) {
-                    let edit = config.update_flags(&marker, &options);
+                let result = async {
+                    let (mut config, edit) =
+                        plan_shell_edit(&*provider, &env_id, &shell_type, &options).await?;
                     if edit.has_changes() {
-                        config.apply_edit(&edit).map_err(|e| e.to_string())?;
-                    }
-                } else {
-                    let init_command = provider
-                        .create_manager(&versi_backend::BackendDetection {
-                            found: true,
-                            path: None,
-                            version: None,
-                            in_path: true,
-                            data_dir: None,
-                        })
-                        .shell_init_command(shell_type.shell_arg(), &options)
-                        .ok_or_else(|| "Shell not supported".to_string())?;
-
-                    let edit = config.add_init(&init_command, &label);
-                    if edit.has_changes() {
-                        config.apply_edit(&edit).map_err(|e| e.to_string())?;
+                        write_shell_edit(&env_id, &mut config, &edit).await?;
                     }
+                    Ok::<_, String>(())
                 }
-
-                Ok::<_, String>(())
+                .await;
+                (env_id, shell_type, result)
             },
-            move |result| Message::ShellConfigured(shell_type_for_callback.clone(), result),
+            |(env_id, shell_type, result)| Message::ShellConfigured(env_id, shell_type, result),
         )
     }
 
     pub(super) fn handle_shell_configured(
         &mut self,
-        shell_type: versi_shell::ShellType,
+        env_id: EnvironmentId,
+        shell_type: ShellType,
         result: Result<(), String>,
     ) {
-        if let AppState::Main(state) = &mut self.state
-            && let Some(shell) = state
-                .settings_state
-                .shell_statuses
-                .iter_mut()
-                .find(|s| s.shell_type == shell_type)
-        {
+        if let Err(e) = &result {
+            log::error!("Failed to configure {}: {}", shell_type.name(), e);
+        }
+        if let Some(shell) = self.shell_status_mut(&env_id, &shell_type) {
             shell.configuring = false;
+            shell.preview = None;
             match result {
                 Ok(()) => shell.status = ShellVerificationStatus::Configured,
                 Err(_) => shell.status = ShellVerificationStatus::Error,
@@ -198,31 +235,283 @@ impl Versi {
         }
     }
 
-    pub(super) fn update_shell_flags(&self) -> Task<Message> {
-        let backend_opts = self.settings.shell_options_for(self.provider.name());
-        let options = ShellInitOptions {
-            use_on_cd: backend_opts.use_on_cd,
-            resolve_engines: backend_opts.resolve_engines,
-            corepack_enabled: backend_opts.corepack_enabled,
+    /// Works out what configuring a shell would change, without writing it.
+    pub(super) fn handle_preview_shell_config(
+        &mut self,
+        env_id: EnvironmentId,
+        shell_type: ShellType,
+    ) -> Task<Message> {
+        let provider = self.provider_for(&env_id);
+        let options = self.shell_init_options(provider.name());
+
+        Task::perform(
+            async move {
+                let result = plan_shell_edit(&*provider, &env_id, &shell_type, &options)
+                    .await
+                    .map(|(_, edit)| edit.changes);
+                (env_id, shell_type, result)
+            },
+            |(env_id, shell_type, result)| Message::ShellConfigPreviewed {
+                env_id,
+                shell_type,
+                result,
+            },
+        )
+    }
+
+    pub(super) fn handle_shell_config_previewed(
+        &mut self,
+        env_id: EnvironmentId,
+        shell_type: ShellType,
+        result: Result<Vec<String>, String>,
+    ) {
+        if let Some(shell) = self.shell_status_mut(&env_id, &shell_type) {
+            shell.preview = Some(result);
+        }
+    }
+
+    pub(super) fn handle_check_resolved_versions(&mut self) -> Task<Message> {
+        use versi_shell::{
+            detect_msys_shells, detect_native_shells, detect_wsl_shells, resolve_msys_node_version,
+            resolve_node_version, resolve_wsl_node_version,
+        };
+
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+        if state.settings_state.resolving_shells {
+            return Task::none();
+        }
+        state.settings_state.resolving_shells = true;
+
+        let env_id = state.active_environment().id.clone();
+        let timeout = Duration::from_secs(self.settings.fetch_timeout_secs);
+
+        Task::perform(
+            async move {
+                let shells = match &env_id {
+                    EnvironmentId::Wsl { distro, .. } => detect_wsl_shells(distro),
+                    EnvironmentId::Msys { bash, .. } => detect_msys_shells(bash),
+                    EnvironmentId::Native => detect_native_shells(),
+                };
+
+                let mut results = Vec::new();
+                for shell in shells {
+                    let resolution = match &env_id {
+                        EnvironmentId::Wsl { distro, .. } => {
+                            resolve_wsl_node_version(&shell.shell_type, distro, timeout).await
+                        }
+                        EnvironmentId::Msys { bash, .. } => {
+                            resolve_msys_node_version(bash, timeout).await
+                        }
+                        EnvironmentId::Native => {
+                            resolve_node_version(&shell.shell_type, timeout).await
+                        }
+                    };
+                    results.push((shell.shell_type, resolution));
+                }
+                (env_id, results)
+            },
+            |(env_id, results)| Message::ResolvedVersionsChecked { env_id, results },
+        )
+    }
+
+    pub(super) fn handle_resolved_versions_checked(
+        &mut self,
+        env_id: EnvironmentId,
+        results: Vec<(ShellType, NodeResolution)>,
+    ) {
+        let AppState::Main(state) = &mut self.state else {
+            return;
         };
+        state.settings_state.resolving_shells = false;
+        if state.active_environment().id == env_id {
+            state.settings_state.resolved_versions = results;
+        }
+    }
+
+    pub(super) fn handle_run_terminal_troubleshooter(&mut self) -> Task<Message> {
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+        if state.settings_state.troubleshooting {
+            return Task::none();
+        }
+        state.settings_state.troubleshooting = true;
 
         let marker = self.provider.shell_config_marker().to_string();
+        Task::perform(
+            async move { versi_shell::troubleshoot(&marker) },
+            Message::TerminalTroubleshooterFinished,
+        )
+    }
+
+    pub(super) fn handle_terminal_troubleshooter_finished(
+        &mut self,
+        report: versi_shell::TroubleshootReport,
+    ) {
+        if let AppState::Main(state) = &mut self.state {
+            state.settings_state.troubleshooting = false;
+            state.settings_state.troubleshoot_report = Some(report);
+        }
+    }
+
+    /// Works out a troubleshooter fix so the user can look over the change
+    /// before anything is written.
+    pub(super) fn handle_preview_terminal_fix(
+        &mut self,
+        shell_type: ShellType,
+        fix: TroubleshootFix,
+    ) -> Task<Message> {
+        let options = self.shell_init_options(self.provider.name());
+        let provider = self.provider.clone();
 
         Task::perform(
             async move {
-                use versi_shell::ShellConfig;
+                use versi_shell::SOURCE_BASHRC_LINE;
 
-                let shells = detect_shells();
+                let path = match &fix {
+                    TroubleshootFix::AddInit(path)
+                    | TroubleshootFix::MoveInitToEnd(path)
+                    | TroubleshootFix::SourceBashrc(path) => path.clone(),
+                };
+                let mut config = ShellConfig::load(shell_type.clone(), path.clone())
+                    .map_err(|e| e.to_string())?;
 
-                for shell in shells {
-                    if let Some(config_path) = shell.config_file
-                        && let Ok(mut config) =
-                            ShellConfig::load(shell.shell_type.clone(), config_path)
-                        && config.has_init(&marker)
-                    {
-                        let edit = config.update_flags(&marker, &options);
-                        if edit.has_changes() {
-                            config.apply_edit(&edit).map_err(|e| e.to_string())?;
+                let edit = match fix {
+                    TroubleshootFix::AddInit(_) => {
+                        let init_command = init_command_for(
+                            &*provider,
+                            &EnvironmentId::Native,
+                            &shell_type,
+                            &options,
+                        )
+                        .ok_or_else(|| "Shell not supported".to_string())?;
+                        config.add_init(&init_command, provider.shell_config_label())
+                    }
+                    TroubleshootFix::MoveInitToEnd(_) => config
+                        .move_init_to_end(provider.shell_config_marker())
+                        .map_err(|e| e.to_string())?,
+                    TroubleshootFix::SourceBashrc(_) => {
+                        config.add_init(SOURCE_BASHRC_LINE, "Load ~/.bashrc in login shells")
+                    }
+                };
+
+                Ok::<_, String>(TerminalFixPreview {
+                    path,
+                    shell_type,
+                    edit,
+                })
+            },
+            Message::TerminalFixPreviewed,
+        )
+    }
+
+    pub(super) fn handle_terminal_fix_previewed(
+        &mut self,
+        result: Result<TerminalFixPreview, String>,
+    ) {
+        match result {
+            Ok(preview) => {
+                if let AppState::Main(state) = &mut self.state {
+                    state.settings_state.terminal_fix = Some(preview);
+                }
+            }
+            Err(e) => self.show_terminal_fix_error(&e),
+        }
+    }
+
+    /// Writes the previewed fix, backing up the profile first.
+    pub(super) fn handle_apply_terminal_fix(&mut self) -> Task<Message> {
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+        let Some(preview) = state.settings_state.terminal_fix.take() else {
+            return Task::none();
+        };
+
+        Task::perform(
+            async move {
+                let mut config = ShellConfig::load(preview.shell_type, preview.path)
+                    .map_err(|e| e.to_string())?;
+                if config.content != preview.edit.original {
+                    return Err(
+                        "The file changed since the preview; run the checks again".to_string()
+                    );
+                }
+                let backup = config.write_backup().map_err(|e| e.to_string())?;
+                if preview.edit.has_changes() {
+                    config
+                        .apply_edit(&preview.edit)
+                        .map_err(|e| e.to_string())?;
+                }
+                Ok::<_, String>(backup)
+            },
+            Message::TerminalFixApplied,
+        )
+    }
+
+    pub(super) fn handle_terminal_fix_applied(
+        &mut self,
+        result: Result<PathBuf, String>,
+    ) -> Task<Message> {
+        let backup = match result {
+            Ok(backup) => backup,
+            Err(e) => {
+                self.show_terminal_fix_error(&e);
+                return Task::none();
+            }
+        };
+        log::info!("Backed up the shell profile to {}", backup.display());
+        if let AppState::Main(state) = &mut self.state {
+            state.settings_state.terminal_fix_backup = Some(backup);
+        }
+        Task::batch([
+            self.handle_run_terminal_troubleshooter(),
+            self.handle_check_shell_setup(),
+        ])
+    }
+
+    fn show_terminal_fix_error(&mut self, error: &str) {
+        log::error!("Failed to apply terminal fix: {}", error);
+        if let AppState::Main(state) = &mut self.state {
+            let id = state.next_toast_id();
+            state.add_toast(
+                Toast::error(id, format!("Failed to update shell profile: {}", error)),
+                &self.settings.notification_delivery,
+            );
+        }
+    }
+
+    pub(super) fn update_shell_flags(&self) -> Task<Message> {
+        use versi_shell::{detect_msys_shells, detect_wsl_shells};
+
+        let provider = self.provider.clone();
+        let options = self.shell_init_options(provider.name());
+        let targets: Vec<EnvironmentId> = self
+            .shell_targets()
+            .into_iter()
+            .filter(|(_, target)| target.name() == provider.name())
+            .map(|(env_id, _)| env_id)
+            .collect();
+
+        Task::perform(
+            async move {
+                for env_id in targets {
+                    let shells = match &env_id {
+                        EnvironmentId::Native => detect_shells(),
+                        EnvironmentId::Wsl { distro, .. } => detect_wsl_shells(distro),
+                        EnvironmentId::Msys { bash, .. } => detect_msys_shells(bash),
+                    };
+
+                    for shell in shells {
+                        // Only shells already set up get their flags updated.
+                        if let Ok((mut config, edit)) =
+                            plan_shell_edit(&*provider, &env_id, &shell.shell_type, &options).await
+                            && config.has_init(provider.shell_config_marker())
+                            && edit.has_changes()
+                        {
+                            write_shell_edit(&env_id, &mut config, &edit).await?;
                         }
                     }
                 }
@@ -233,3 +522,71 @@ impl Versi {
         )
     }
 }
+
+/// The config of `shell_type` in `env_id` and the edit that sets the backend
+/// up in it: the init line when it is missing, otherwise updated flags.
+async fn plan_shell_edit(
+    provider: &dyn BackendProvider,
+    env_id: &EnvironmentId,
+    shell_type: &ShellType,
+    options: &ShellInitOptions,
+) -> Result<(ShellConfig, ShellConfigEdit), String> {
+    let mut config = match env_id {
+        EnvironmentId::Native | EnvironmentId::Msys { .. } => {
+            let config_path = versi_shell::get_or_create_config_path(shell_type)
+                .ok_or_else(|| "No config file path found".to_string())?;
+            ShellConfig::load(shell_type.clone(), config_path).map_err(|e| e.to_string())?
+        }
+        EnvironmentId::Wsl { distro, .. } => ShellConfig::load_wsl(shell_type.clone(), distro)
+            .await
+            .map_err(|e| e.to_string())?,
+    };
+    let init_command = init_command_for(provider, env_id, shell_type, options)
+        .ok_or_else(|| "Shell not supported".to_string())?;
+
+    let marker = provider.shell_config_marker();
+    let edit = if config.has_init(marker) {
+        config.update_flags(marker, &init_command)
+    } else {
+        config.add_init(&init_command, provider.shell_config_label())
+    };
+    Ok((config, edit))
+}
+
+async fn write_shell_edit(
+    env_id: &EnvironmentId,
+    config: &mut ShellConfig,
+    edit: &ShellConfigEdit,
+) -> Result<(), String> {
+    match env_id {
+        EnvironmentId::Native | EnvironmentId::Msys { .. } => config.apply_edit(edit),
+        EnvironmentId::Wsl { distro, .. } => config.apply_edit_wsl(distro, edit).await,
+    }
+    .map_err(|e| e.to_string())
+}
+
+fn init_command_for(
+    provider: &dyn BackendProvider,
+    env_id: &EnvironmentId,
+    shell_type: &ShellType,
+    options: &ShellInitOptions,
+) -> Option<String> {
+    let manager = match env_id {
+        EnvironmentId::Native => provider.create_manager(&versi_backend::BackendDetection {
+            found: true,
+            path: None,
+            version: None,
+            in_path: true,
+            data_dir: None,
+            source: None,
+        }),
+        EnvironmentId::Wsl {
+            distro,
+            backend_path,
+        } => provider.create_manager_for_wsl(distro.clone(), backend_path.clone()),
+        EnvironmentId::Msys { bash, backend_path } => {
+            provider.create_manager_for_msys(bash.clone(), backend_path.clone())
+        }
+    };
+    manager.shell_init_command(shell_type.shell_arg(), options)
+}
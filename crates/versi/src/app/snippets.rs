@@ -0,0 +1,103 @@
+//! Copy-paste setup snippets pinning a version for CI, containers, and shells.
+//!
+//! Handles messages: ShowSetupSnippet, SetupSnippetTargetSelected, ShowCiMatrix,
+//! CiMatrixMajorToggled, CiMatrixEnginesToggled
+
+use versi_backend::ShellInitOptions;
+
+use crate::snippets::{self, SnippetTarget};
+use crate::state::{AppState, Modal};
+
+use super::Versi;
+
+impl Versi {
+    pub(super) fn handle_show_setup_snippet(&mut self, version: String) {
+        self.show_setup_snippet(version, SnippetTarget::GitHubActions);
+    }
+
+    pub(super) fn handle_setup_snippet_target_selected(&mut self, target: SnippetTarget) {
+        let AppState::Main(state) = &self.state else {
+            return;
+        };
+        if let Some(Modal::SetupSnippet { version, .. }) = &state.modal {
+            let version = version.clone();
+            self.show_setup_snippet(version, target);
+        }
+    }
+
+    fn show_setup_snippet(&mut self, version: String, target: SnippetTarget) {
+        let backend_opts = self.settings.shell_options_for(self.provider.name());
+        let options = ShellInitOptions {
+            use_on_cd: backend_opts.use_on_cd,
+            resolve_engines: backend_opts.resolve_engines,
+            corepack_enabled: backend_opts.corepack_enabled,
+        };
+
+        let AppState::Main(state) = &mut self.state else {
+            return;
+        };
+        let shell_init = state.backend.shell_init_command("bash", &options);
+        let snippet = snippets::render(target, &version, state.backend_name, shell_init.as_deref());
+        state.modal = Some(Modal::SetupSnippet {
+            version,
+            target,
+            snippet,
+        });
+    }
+
+    pub(super) fn handle_show_ci_matrix(&mut self) {
+        let AppState::Main(state) = &mut self.state else {
+            return;
+        };
+        let mut majors: Vec<u32> = state
+            .active_environment()
+            .installed_versions
+            .iter()
+            .map(|v| v.version.major)
+            .collect();
+        majors.sort_unstable();
+        majors.dedup();
+
+        let mut selected: Vec<u32> = state
+            .active_environment()
+            .installed_versions
+            .iter()
+            .filter(|v| state.selected_versions.contains(&v.version.to_string()))
+            .map(|v| v.version.major)
+            .collect();
+        selected.sort_unstable();
+        selected.dedup();
+        if selected.is_empty() {
+            selected = majors.clone();
+        }
+
+        state.modal = Some(Modal::CiMatrix {
+            majors,
+            selected,
+            include_engines: false,
+        });
+    }
+
+    pub(super) fn handle_ci_matrix_major_toggled(&mut self, major: u32) {
+        if let AppState::Main(state) = &mut self.state
+            && let Some(Modal::CiMatrix { selected, .. }) = &mut state.modal
+        {
+            if let Some(pos) = selected.iter().position(|m| *m == major) {
+                selected.remove(pos);
+            } else {
+                selected.push(major);
+                selected.sort_unstable();
+            }
+        }
+    }
+
+    pub(super) fn handle_ci_matrix_engines_toggled(&mut self, enabled: bool) {
+        if let AppState::Main(state) = &mut self.state
+            && let Some(Modal::CiMatrix {
+                include_engines, ..
+            }) = &mut state.modal
+        {
+            *include_engines = enabled;
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! Anonymized support bundle export from the About view.
+//!
+//! Handles messages: ExportSupportBundle, SupportBundleExported
+
+use log::info;
+use std::path::PathBuf;
+
+use iced::Task;
+
+use crate::message::Message;
+use crate::operation_log;
+use crate::state::{AppState, Toast};
+use crate::support_bundle::{Anonymizer, SupportBundle};
+
+use super::Versi;
+
+impl Versi {
+    pub(super) fn handle_export_support_bundle(&mut self) -> Task<Message> {
+        let AppState::Main(state) = &self.state else {
+            return Task::none();
+        };
+
+        let app_log = versi_platform::AppPaths::new()
+            .map(|paths| operation_log::read_log_tail(&paths.log_file()))
+            .unwrap_or_default();
+        let bundle = SupportBundle {
+            settings: &self.settings,
+            environments: &state.environments,
+            backend: state.backend.backend_info(),
+            app_log,
+            created_at: chrono::Local::now(),
+            anonymizer: Anonymizer::current(),
+        };
+        let file_name = bundle.file_name();
+        let archive = match bundle.to_zip() {
+            Ok(archive) => archive,
+            Err(e) => return Task::done(Message::SupportBundleExported(Err(e))),
+        };
+
+        Task::perform(
+            async move {
+                let handle = rfd::AsyncFileDialog::new()
+                    .set_file_name(&file_name)
+                    .add_filter("Zip archive", &["zip"])
+                    .save_file()
+                    .await
+                    .ok_or_else(|| "Cancelled".to_string())?;
+                let path = handle.path().to_path_buf();
+                tokio::fs::write(&path, archive)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(path)
+            },
+            Message::SupportBundleExported,
+        )
+    }
+
+    pub(super) fn handle_support_bundle_exported(&mut self, result: Result<PathBuf, String>) {
+        match result {
+            Ok(path) => info!("Exported support bundle to {:?}", path),
+            Err(e) if e != "Cancelled" => {
+                if let AppState::Main(state) = &mut self.state {
+                    let id = state.next_toast_id();
+                    state.add_toast(
+                        Toast::error(id, format!("Support bundle export failed: {}", e)),
+                        &self.settings.notification_delivery,
+                    );
+                }
+            }
+            Err(_) => {}
+        }
+    }
+}
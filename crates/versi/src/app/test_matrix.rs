@@ -0,0 +1,232 @@
+//! Running a project's test command under each selected version in turn.
+//!
+//! Handles messages: ShowTestMatrix, PickTestMatrixProject, TestMatrixProjectPicked,
+//! TestMatrixToolchainRead, TestMatrixCommandChanged, RunTestMatrix, TestMatrixRunFinished
+
+use log::info;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use iced::Task;
+
+use crate::message::Message;
+use crate::state::{AppState, Modal};
+use crate::test_matrix::{self, MatrixOutcome, MatrixRun};
+use crate::toolchain::ProjectToolchain;
+
+use super::Versi;
+
+const DEFAULT_COMMAND: &str = "npm test";
+
+impl Versi {
+    pub(super) fn handle_show_test_matrix(&mut self) {
+        let AppState::Main(state) = &mut self.state else {
+            return;
+        };
+        let versions: Vec<String> = state
+            .active_environment()
+            .installed_versions
+            .iter()
+            .map(|v| v.version.to_string())
+            .filter(|v| state.selected_versions.contains(v))
+            .collect();
+        if versions.is_empty() {
+            return;
+        }
+
+        state.modal = Some(Modal::TestMatrix {
+            versions,
+            project: None,
+            command: DEFAULT_COMMAND.to_string(),
+            runs: Vec::new(),
+            toolchain: ProjectToolchain::default(),
+            confirming_trust: false,
+        });
+    }
+
+    pub(super) fn handle_pick_test_matrix_project(&mut self) -> Task<Message> {
+        Task::perform(
+            async {
+                rfd::AsyncFileDialog::new()
+                    .set_title("Select a project")
+                    .pick_folder()
+                    .await
+                    .map(|handle| handle.path().to_path_buf())
+            },
+            Message::TestMatrixProjectPicked,
+        )
+    }
+
+    pub(super) fn handle_test_matrix_project_picked(
+        &mut self,
+        path: Option<PathBuf>,
+    ) -> Task<Message> {
+        if let AppState::Main(state) = &mut self.state
+            && let Some(Modal::TestMatrix {
+                project,
+                runs,
+                toolchain,
+                confirming_trust,
+                ..
+            }) = &mut state.modal
+            && let Some(path) = path
+        {
+            *project = Some(path.clone());
+            runs.clear();
+            *toolchain = ProjectToolchain::default();
+            *confirming_trust = false;
+            return Task::perform(
+                async move {
+                    let dir = path.clone();
+                    let toolchain =
+                        tokio::task::spawn_blocking(move || ProjectToolchain::read(&dir))
+                            .await
+                            .unwrap_or_default();
+                    (path, toolchain)
+                },
+                |(path, toolchain)| Message::TestMatrixToolchainRead(path, toolchain),
+            );
+        }
+        Task::none()
+    }
+
+    pub(super) fn handle_test_matrix_toolchain_read(
+        &mut self,
+        path: PathBuf,
+        read: ProjectToolchain,
+    ) {
+        if let AppState::Main(state) = &mut self.state
+            && let Some(Modal::TestMatrix {
+                project, toolchain, ..
+            }) = &mut state.modal
+            && project.as_ref() == Some(&path)
+        {
+            info!(
+                "Project {} declares {} Node requirement(s)",
+                path.display(),
+                read.requirements.len()
+            );
+            *toolchain = read;
+        }
+    }
+
+    pub(super) fn handle_test_matrix_command_changed(&mut self, value: String) {
+        if let AppState::Main(state) = &mut self.state
+            && let Some(Modal::TestMatrix { command, .. }) = &mut state.modal
+        {
+            *command = value;
+        }
+    }
+
+    pub(super) fn handle_run_test_matrix(&mut self) -> Task<Message> {
+        if let AppState::Main(state) = &mut self.state
+            && let Some(Modal::TestMatrix {
+                versions,
+                project: Some(project),
+                command,
+                runs,
+                confirming_trust,
+                ..
+            }) = &mut state.modal
+        {
+            if test_matrix::split_command(command).is_empty() || is_running(runs) {
+                return Task::none();
+            }
+            // Test commands run the project's own scripts, so a project
+            // nobody has vouched for asks first.
+            match self.settings.project_trust_for(project) {
+                Some(true) => *confirming_trust = false,
+                Some(false) => return Task::none(),
+                None => {
+                    *confirming_trust = true;
+                    return Task::none();
+                }
+            }
+            info!(
+                "Running `{}` in {} under {} version(s)",
+                command,
+                project.display(),
+                versions.len()
+            );
+            *runs = versions.iter().cloned().map(MatrixRun::pending).collect();
+        }
+        self.start_next_matrix_run()
+    }
+
+    pub(super) fn handle_test_matrix_run_finished(
+        &mut self,
+        version: String,
+        result: Result<(), String>,
+        duration: Duration,
+    ) -> Task<Message> {
+        if let AppState::Main(state) = &mut self.state
+            && let Some(Modal::TestMatrix { runs, .. }) = &mut state.modal
+            && let Some(run) = runs
+                .iter_mut()
+                .find(|r| r.version == version && r.outcome == MatrixOutcome::Running)
+        {
+            info!("Test run under {} finished: {:?}", version, result);
+            run.outcome = match result {
+                Ok(()) => MatrixOutcome::Passed,
+                Err(e) => MatrixOutcome::Failed(e),
+            };
+            run.duration = Some(duration);
+        }
+        self.start_next_matrix_run()
+    }
+
+    /// Starts the first pending run. Versions run one at a time so they do
+    /// not fight over the project's `node_modules`.
+    fn start_next_matrix_run(&mut self) -> Task<Message> {
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+        let Some(Modal::TestMatrix {
+            project: Some(project),
+            command,
+            runs,
+            ..
+        }) = &mut state.modal
+        else {
+            return Task::none();
+        };
+        if is_running(runs) {
+            return Task::none();
+        }
+        let Some(run) = runs
+            .iter_mut()
+            .find(|r| r.outcome == MatrixOutcome::Pending)
+        else {
+            return Task::none();
+        };
+
+        run.outcome = MatrixOutcome::Running;
+        let version = run.version.clone();
+        let project = project.clone();
+        let command = test_matrix::split_command(command);
+        let backend = state.backend.clone();
+
+        Task::perform(
+            async move {
+                let (result, duration) = test_matrix::run_version(
+                    &*backend,
+                    &version,
+                    &command,
+                    &project,
+                    test_matrix::RUN_TIMEOUT,
+                )
+                .await;
+                (version, result, duration)
+            },
+            |(version, result, duration)| Message::TestMatrixRunFinished {
+                version,
+                result,
+                duration,
+            },
+        )
+    }
+}
+
+fn is_running(runs: &[MatrixRun]) -> bool {
+    runs.iter().any(|r| r.outcome == MatrixOutcome::Running)
+}
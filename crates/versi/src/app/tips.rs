@@ -0,0 +1,35 @@
+//! Feature tips overlaid on the version list.
+//!
+//! Handles messages: ShowTips, NextTip, PreviousTip, DismissTips
+
+use crate::state::{AppState, MainViewKind};
+use crate::tips::TipsTour;
+
+use super::Versi;
+
+impl Versi {
+    pub(super) fn handle_show_tips(&mut self) {
+        if let AppState::Main(state) = &mut self.state {
+            state.view = MainViewKind::Versions;
+            state.tips = TipsTour::start();
+        }
+    }
+
+    pub(super) fn handle_next_tip(&mut self) {
+        if let AppState::Main(state) = &mut self.state {
+            state.tips = state.tips.next();
+        }
+    }
+
+    pub(super) fn handle_previous_tip(&mut self) {
+        if let AppState::Main(state) = &mut self.state {
+            state.tips = state.tips.previous();
+        }
+    }
+
+    pub(super) fn handle_dismiss_tips(&mut self) {
+        if let AppState::Main(state) = &mut self.state {
+            state.tips = TipsTour::Hidden;
+        }
+    }
+}
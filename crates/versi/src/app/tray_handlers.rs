@@ -20,7 +20,7 @@ impl Versi {
         match msg {
             TrayMessage::ShowWindow => self.tray_show_window(),
             TrayMessage::HideWindow => self.tray_hide_window(),
-            TrayMessage::Quit => iced::exit(),
+            TrayMessage::Quit => self.request_quit(),
             _ if !matches!(self.state, AppState::Main(_)) => Task::none(),
             TrayMessage::OpenSettings => {
                 if let AppState::Main(state) = &mut self.state {
@@ -67,13 +67,17 @@ impl Versi {
                     && env_index != state.active_environment_idx
                 {
                     state.active_environment_idx = env_index;
+                    state.selected_versions.clear();
                     let env = &state.environments[env_index];
                     let env_id = env.id.clone();
                     state.backend = create_backend_for_environment(
                         &env_id,
                         &self.backend_path,
                         &self.backend_dir,
+                        &self.backend_source,
                         &self.provider,
+                        self.settings
+                            .env_overrides_for(&env_id, env.target.as_ref()),
                     );
                 }
                 self.handle_set_default(version)
@@ -128,12 +132,20 @@ impl Versi {
 
     pub(super) fn handle_tray_behavior_changed(&mut self, behavior: TrayBehavior) -> Task<Message> {
         let old_behavior = self.settings.tray_behavior.clone();
-        self.settings.tray_behavior = behavior.clone();
+        self.settings.tray_behavior = behavior;
         if let Err(e) = self.settings.save() {
             log::error!("Failed to save settings: {e}");
         }
+        self.apply_tray_behavior(&old_behavior);
 
-        if old_behavior == TrayBehavior::Disabled && behavior != TrayBehavior::Disabled {
+        Task::none()
+    }
+
+    /// Creates or removes the tray icon after the setting changed from
+    /// `old_behavior`.
+    pub(super) fn apply_tray_behavior(&mut self, old_behavior: &TrayBehavior) {
+        let behavior = self.settings.tray_behavior.clone();
+        if *old_behavior == TrayBehavior::Disabled && behavior != TrayBehavior::Disabled {
             if let Err(e) = tray::init_tray(&behavior) {
                 error!("Failed to initialize tray: {}", e);
             } else {
@@ -142,13 +154,15 @@ impl Versi {
         } else if behavior == TrayBehavior::Disabled {
             tray::destroy_tray();
         }
-
-        Task::none()
     }
 
     pub(super) fn update_tray_menu(&self) {
         if let AppState::Main(state) = &self.state {
-            let data = TrayMenuData::from_environments(&state.environments, self.window_visible);
+            let data = TrayMenuData::from_environments(
+                &state.environments,
+                self.window_visible,
+                self.settings.is_read_only(),
+            );
             tray::update_menu(&data);
         }
     }
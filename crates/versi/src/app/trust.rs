@@ -0,0 +1,57 @@
+//! Remembered answers to whether a project directory is trusted to run the
+//! commands it defines.
+//!
+//! Handles messages: ProjectTrustDecided, ForgetProjectTrust
+
+use log::info;
+use std::path::PathBuf;
+
+use iced::Task;
+
+use crate::message::Message;
+use crate::state::{AppState, Modal};
+
+use super::Versi;
+
+impl Versi {
+    pub(super) fn handle_project_trust_decided(
+        &mut self,
+        project: PathBuf,
+        trusted: bool,
+    ) -> Task<Message> {
+        info!(
+            "{} {}",
+            if trusted { "Trusting" } else { "Not trusting" },
+            project.display()
+        );
+        self.settings.project_trust.insert(project.clone(), trusted);
+        if let Err(e) = self.settings.save() {
+            log::error!("Failed to save settings: {e}");
+        }
+
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+        if let Some(Modal::TestMatrix {
+            project: Some(current),
+            confirming_trust,
+            ..
+        }) = &mut state.modal
+            && *current == project
+        {
+            *confirming_trust = false;
+            if trusted {
+                return self.handle_run_test_matrix();
+            }
+        }
+        Task::none()
+    }
+
+    pub(super) fn handle_forget_project_trust(&mut self, project: PathBuf) {
+        if self.settings.project_trust.remove(&project).is_some()
+            && let Err(e) = self.settings.save()
+        {
+            log::error!("Failed to save settings: {e}");
+        }
+    }
+}
@@ -1,59 +1,109 @@
 //! Remote version fetching, release schedule, and update checks.
 //!
 //! Handles messages: RemoteVersionsFetched, ReleaseScheduleFetched,
-//! AppUpdateChecked, BackendUpdateChecked
+//! ReleaseIndexFetched, CheckForAppUpdate, AppUpdateChecked,
+//! CheckForBackendUpdate, BackendUpdateChecked, BackendUpdateChannelChanged
 
-use std::time::{Duration, Instant};
+use std::time::Instant;
 
-use log::debug;
+use log::{debug, error, info};
 
 use iced::Task;
 
-use versi_core::{check_for_update, fetch_release_schedule};
+use versi_core::{ReleaseIndex, check_for_update, fetch_release_index, fetch_release_schedule};
 
 use crate::message::Message;
-use crate::state::AppState;
+use crate::settings::NotificationCategory;
+use crate::state::{AppState, EnvironmentState, RemoteSource, Toast};
 
 use super::Versi;
 
 impl Versi {
+    /// Where `env` gets its remote versions from: its backend, and the
+    /// mirror that backend is pointed at.
+    pub(super) fn remote_source(&self, env: &EnvironmentState) -> RemoteSource {
+        let mirror = self
+            .settings
+            .env_overrides_for(&env.id, env.target.as_ref())
+            .into_iter()
+            .find(|(key, _)| key == "FNM_NODE_DIST_MIRROR" || key == "NVM_NODEJS_ORG_MIRROR")
+            .map(|(_, mirror)| mirror);
+        RemoteSource {
+            backend: env.backend_name,
+            mirror,
+        }
+    }
+
+    /// The source of the native environment, whose list is the one kept in
+    /// the disk cache and shown at startup.
+    fn native_remote_source(&self) -> Option<RemoteSource> {
+        let AppState::Main(state) = &self.state else {
+            return None;
+        };
+        state
+            .environments
+            .first()
+            .map(|env| self.remote_source(env))
+    }
+
+    fn native_remote_versions(&self) -> Vec<versi_backend::RemoteVersion> {
+        let AppState::Main(state) = &self.state else {
+            return Vec::new();
+        };
+        self.native_remote_source()
+            .and_then(|source| state.available_versions.list(&source))
+            .map(|remote| remote.versions.clone())
+            .unwrap_or_default()
+    }
+
+    /// Shows the remote list of the active environment's source, fetching it
+    /// the first time an environment with that source is opened.
+    pub(super) fn switch_remote_source(&mut self) -> Task<Message> {
+        let source = match &self.state {
+            AppState::Main(state) => self.remote_source(state.active_environment()),
+            _ => return Task::none(),
+        };
+        let AppState::Main(state) = &mut self.state else {
+            return Task::none();
+        };
+        state.available_versions.set_active(source);
+        if state.available_versions.remote().is_unloaded() {
+            self.handle_fetch_remote_versions()
+        } else {
+            Task::none()
+        }
+    }
+
+    /// Fetches the remote versions of the active environment's source. Other
+    /// environments with the same source reuse them.
     pub(super) fn handle_fetch_remote_versions(&mut self) -> Task<Message> {
+        let source = match &self.state {
+            AppState::Main(state) => self.remote_source(state.active_environment()),
+            _ => return Task::none(),
+        };
         if let AppState::Main(state) = &mut self.state {
-            if state.available_versions.loading {
+            state.available_versions.set_active(source.clone());
+            let remote = state.available_versions.remote_mut();
+            if remote.loading {
                 return Task::none();
             }
-            state.available_versions.loading = true;
+            remote.loading = true;
 
             let backend = state.backend.clone();
-            let fetch_timeout = Duration::from_secs(self.settings.fetch_timeout_secs);
-            let retry_delays = self.settings.retry_delays_secs.clone();
+            let policy = self.settings.retry_policy.clone();
 
             return Task::perform(
                 async move {
-                    let mut last_err = String::new();
-                    for (attempt, &delay) in retry_delays.iter().enumerate() {
-                        if delay > 0 {
-                            tokio::time::sleep(std::time::Duration::from_secs(delay)).await;
-                        }
-                        match tokio::time::timeout(fetch_timeout, backend.list_remote()).await {
-                            Err(_) => {
-                                last_err = "Request timed out".to_string();
-                                debug!("Remote versions fetch attempt {} timed out", attempt + 1,);
-                            }
-                            Ok(Ok(versions)) => return Ok(versions),
-                            Ok(Err(e)) => {
-                                last_err = e.to_string();
-                                debug!(
-                                    "Remote versions fetch attempt {} failed: {}",
-                                    attempt + 1,
-                                    last_err
-                                );
-                            }
-                        }
-                    }
-                    Err(last_err)
+                    policy
+                        .run("Remote versions fetch", || async {
+                            backend.list_remote().await.map_err(|e| e.to_string())
+                        })
+                        .await
+                },
+                move |result| Message::RemoteVersionsFetched {
+                    source: source.clone(),
+                    result,
                 },
-                Message::RemoteVersionsFetched,
             );
         }
         Task::none()
@@ -61,16 +111,23 @@ impl Versi {
 
     pub(super) fn handle_remote_versions_fetched(
         &mut self,
+        source: RemoteSource,
         result: Result<Vec<versi_backend::RemoteVersion>, String>,
     ) {
+        let native_source = self.native_remote_source();
         if let AppState::Main(state) = &mut self.state {
-            state.available_versions.loading = false;
+            let remote = state.available_versions.list_mut(&source);
+            remote.loading = false;
             match result {
                 Ok(versions) => {
-                    state.available_versions.set_versions(versions.clone());
-                    state.available_versions.fetched_at = Some(Instant::now());
-                    state.available_versions.error = None;
-                    state.available_versions.loaded_from_disk = false;
+                    remote.set_versions(versions.clone());
+                    remote.fetched_at = Some(Instant::now());
+                    remote.error = None;
+                    remote.loaded_from_disk = false;
+
+                    if *state.available_versions.active_source() != source {
+                        return;
+                    }
 
                     // Show badge if any installed major line has a newer version available
                     let env = state.active_environment();
@@ -82,58 +139,76 @@ impl Versi {
                     let has_update = installed_majors.iter().any(|major| {
                         state
                             .available_versions
+                            .remote()
                             .latest_by_major
                             .get(major)
                             .is_some_and(|latest| !env.installed_set.contains(&latest.to_string()))
                     });
                     super::platform::set_update_badge(has_update);
 
+                    // The disk cache holds the list shown at startup.
+                    if native_source.as_ref() != Some(&source) {
+                        return;
+                    }
+
                     let schedule = state.available_versions.schedule.clone();
+                    let schedule_fetched_at = state.available_versions.schedule_fetched_at;
+                    let release_dates = state.available_versions.release_dates.clone();
                     // std::thread::spawn, not tokio — Iced doesn't guarantee a tokio runtime context
                     std::thread::spawn(move || {
                         let cache = crate::cache::DiskCache {
                             remote_versions: versions,
                             release_schedule: schedule,
+                            schedule_fetched_at,
+                            release_dates,
                             cached_at: chrono::Utc::now(),
                         };
                         cache.save();
                     });
                 }
                 Err(error) => {
-                    state.available_versions.error = Some(error);
+                    remote.error = Some(error);
                 }
             }
         }
     }
 
     pub(super) fn handle_fetch_release_schedule(&mut self) -> Task<Message> {
-        if let AppState::Main(_) = &self.state {
+        if let AppState::Main(state) = &self.state {
             let client = self.http_client.clone();
-            let retry_delays = self.settings.retry_delays_secs.clone();
+            let policy = self.settings.retry_policy.clone();
 
-            return Task::perform(
+            let dates_client = client.clone();
+            let mirror = self
+                .settings
+                .dist_mirror_for(state.active_environment().target.as_ref());
+            let fetch_mirror = mirror.clone();
+            let fetch_dates = Task::perform(
+                async move { fetch_release_index(&dates_client, fetch_mirror.as_deref()).await },
+                move |result| Message::ReleaseIndexFetched {
+                    mirror: mirror.clone(),
+                    result,
+                },
+            );
+
+            if state
+                .available_versions
+                .schedule_is_fresh(chrono::Utc::now())
+            {
+                debug!("Release schedule is fresh, skipping fetch");
+                return fetch_dates;
+            }
+
+            let fetch_schedule = Task::perform(
                 async move {
-                    let mut last_err = String::new();
-                    for (attempt, &delay) in retry_delays.iter().enumerate() {
-                        if delay > 0 {
-                            tokio::time::sleep(std::time::Duration::from_secs(delay)).await;
-                        }
-                        match fetch_release_schedule(&client).await {
-                            Ok(schedule) => return Ok(schedule),
-                            Err(e) => {
-                                last_err = e;
-                                debug!(
-                                    "Release schedule fetch attempt {} failed: {}",
-                                    attempt + 1,
-                                    last_err
-                                );
-                            }
-                        }
-                    }
-                    Err(last_err)
+                    policy
+                        .run("Release schedule fetch", || fetch_release_schedule(&client))
+                        .await
                 },
                 Message::ReleaseScheduleFetched,
             );
+
+            return Task::batch([fetch_schedule, fetch_dates]);
         }
         Task::none()
     }
@@ -142,18 +217,23 @@ impl Versi {
         &mut self,
         result: Result<versi_core::ReleaseSchedule, String>,
     ) {
+        let versions = self.native_remote_versions();
         if let AppState::Main(state) = &mut self.state {
             match result {
                 Ok(schedule) => {
+                    let fetched_at = chrono::Utc::now();
                     state.available_versions.schedule = Some(schedule.clone());
                     state.available_versions.schedule_error = None;
+                    state.available_versions.schedule_fetched_at = Some(fetched_at);
 
-                    let versions = state.available_versions.versions.clone();
+                    let release_dates = state.available_versions.release_dates.clone();
                     // std::thread::spawn, not tokio — Iced doesn't guarantee a tokio runtime context
                     std::thread::spawn(move || {
                         let cache = crate::cache::DiskCache {
                             remote_versions: versions,
                             release_schedule: Some(schedule),
+                            schedule_fetched_at: Some(fetched_at),
+                            release_dates,
                             cached_at: chrono::Utc::now(),
                         };
                         cache.save();
@@ -165,13 +245,54 @@ impl Versi {
                 }
             }
         }
+        self.announce_support_warnings();
+    }
+
+    pub(super) fn handle_release_index_fetched(
+        &mut self,
+        mirror: Option<String>,
+        result: Result<ReleaseIndex, String>,
+    ) {
+        let versions = self.native_remote_versions();
+        if let AppState::Main(state) = &mut self.state {
+            match result {
+                Ok(index) => {
+                    let dates = index.dates;
+                    state.available_versions.release_dates = dates.clone();
+                    state.available_versions.release_files = index.files;
+                    state.available_versions.release_files_mirror = mirror;
+
+                    let schedule = state.available_versions.schedule.clone();
+                    let schedule_fetched_at = state.available_versions.schedule_fetched_at;
+                    // std::thread::spawn, not tokio — Iced doesn't guarantee a tokio runtime context
+                    std::thread::spawn(move || {
+                        let cache = crate::cache::DiskCache {
+                            remote_versions: versions,
+                            release_schedule: schedule,
+                            schedule_fetched_at,
+                            release_dates: dates,
+                            cached_at: chrono::Utc::now(),
+                        };
+                        cache.save();
+                    });
+                }
+                Err(error) => debug!("Release dates fetch failed: {}", error),
+            }
+        }
     }
 
     pub(super) fn handle_check_for_app_update(&mut self) -> Task<Message> {
         let current_version = env!("CARGO_PKG_VERSION").to_string();
-        let client = self.http_client.clone();
+        let github = self.github.clone();
+        let policy = self.settings.retry_policy.clone();
         Task::perform(
-            async move { check_for_update(&client, &current_version).await },
+            async move {
+                policy
+                    .run("App update check", || {
+                        check_for_update(&github, &current_version)
+                    })
+                    .await
+            },
             Message::AppUpdateChecked,
         )
     }
@@ -179,13 +300,46 @@ impl Versi {
     pub(super) fn handle_app_update_checked(
         &mut self,
         result: Result<Option<versi_core::AppUpdate>, String>,
-    ) {
+    ) -> Task<Message> {
         if let AppState::Main(state) = &mut self.state {
             match result {
-                Ok(update) => state.app_update = update,
-                Err(e) => debug!("App update check failed: {}", e),
+                Ok(update) => {
+                    let deferral = &mut self.settings.update_deferral;
+                    if update
+                        .as_ref()
+                        .is_some_and(|u| u.rollout_percentage.is_some())
+                        && deferral.ensure_rollout_bucket()
+                        && let Err(e) = self.settings.save()
+                    {
+                        error!("Failed to save settings: {e}");
+                    }
+                    let today = chrono::Local::now().date_naive();
+                    let offered = state.app_update.as_ref().map(|u| u.latest_version.clone());
+                    state.app_update = update.filter(|update| {
+                        let allowed = self.settings.update_deferral.allows(update, today);
+                        if !allowed {
+                            info!("Not offering Versi {} yet", update.latest_version);
+                        }
+                        allowed
+                    });
+                    if let Some(latest) = state.app_update.as_ref().map(|u| &u.latest_version)
+                        && offered.as_ref() != Some(latest)
+                    {
+                        let message = format!("Versi {} is available", latest);
+                        let id = state.next_toast_id();
+                        state.add_toast(
+                            Toast::notice(id, message, NotificationCategory::Updates),
+                            &self.settings.notification_delivery,
+                        );
+                    }
+                }
+                Err(e) => {
+                    debug!("App update check failed: {}", e);
+                    return self.retry_after_rate_limit(Message::CheckForAppUpdate);
+                }
             }
         }
+        Task::none()
     }
 
     pub(super) fn handle_check_for_backend_update(&mut self) -> Task<Message> {
@@ -193,10 +347,18 @@ impl Versi {
             && let Some(version) = &state.active_environment().backend_version
         {
             let version = version.clone();
-            let client = self.http_client.clone();
+            let github = self.github.clone();
             let provider = self.provider.clone();
+            let channel = self.settings.update_channel_for(provider.name());
+            let policy = self.settings.retry_policy.clone();
             return Task::perform(
-                async move { provider.check_for_update(&client, &version).await },
+                async move {
+                    policy
+                        .run("Backend update check", || {
+                            provider.check_for_update(&github, &version, &channel)
+                        })
+                        .await
+                },
                 Message::BackendUpdateChecked,
             );
         }
@@ -206,12 +368,61 @@ impl Versi {
     pub(super) fn handle_backend_update_checked(
         &mut self,
         result: Result<Option<versi_backend::BackendUpdate>, String>,
-    ) {
+    ) -> Task<Message> {
         if let AppState::Main(state) = &mut self.state {
             match result {
-                Ok(update) => state.backend_update = update,
-                Err(e) => debug!("Backend update check failed: {}", e),
+                Ok(update) => {
+                    let offered = state
+                        .backend_update
+                        .as_ref()
+                        .map(|u| u.latest_version.clone());
+                    if let Some(latest) = update.as_ref().map(|u| &u.latest_version)
+                        && offered.as_ref() != Some(latest)
+                    {
+                        let message = format!("{} {} is available", state.backend_name, latest);
+                        let id = state.next_toast_id();
+                        state.add_toast(
+                            Toast::notice(id, message, NotificationCategory::Updates),
+                            &self.settings.notification_delivery,
+                        );
+                    }
+                    state.backend_update = update;
+                }
+                Err(e) => {
+                    debug!("Backend update check failed: {}", e);
+                    return self.retry_after_rate_limit(Message::CheckForBackendUpdate);
+                }
             }
         }
+        Task::none()
+    }
+
+    pub(super) fn handle_backend_update_channel_changed(
+        &mut self,
+        channel: versi_backend::BackendUpdateChannel,
+    ) -> Task<Message> {
+        info!("{} updates: {}", self.provider.name(), channel.describe());
+        self.settings
+            .backend_update_channels
+            .insert(self.provider.name().to_string(), channel);
+        if let Err(e) = self.settings.save() {
+            error!("Failed to save settings: {e}");
+        }
+        if let AppState::Main(state) = &mut self.state {
+            state.backend_update = None;
+        }
+        self.handle_check_for_backend_update()
+    }
+
+    /// Sends `message` again once GitHub lifts its rate limit, if one is in effect.
+    fn retry_after_rate_limit(&self, message: Message) -> Task<Message> {
+        let Some(wait) = self.github.retry_after() else {
+            return Task::none();
+        };
+        info!(
+            "GitHub rate limit reached, checking again in {} minute(s)",
+            wait.as_secs().div_ceil(60)
+        );
+        Task::perform(tokio::time::sleep(wait), move |_| message.clone())
     }
 }
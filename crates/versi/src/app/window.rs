@@ -1,13 +1,18 @@
 //! Window lifecycle: open, close, hide-to-tray, and geometry persistence.
 //!
-//! Handles messages: WindowClose, WindowOpened
+//! Handles messages: WindowClose, WindowOpened, WindowRawIdReceived, WindowResized,
+//! CloseBehaviorChanged, CloseDialogChoice, AlwaysOnTopToggled, WindowVibrancyToggled,
+//! QuitWhenIdle, ConfirmQuit
+
+use std::time::Duration;
 
 use log::info;
 
 use iced::Task;
 
 use crate::message::Message;
-use crate::settings::TrayBehavior;
+use crate::settings::{CloseBehavior, TrayBehavior};
+use crate::state::{AppState, Modal, OperationQueue};
 use crate::tray;
 
 use super::Versi;
@@ -15,35 +20,233 @@ use super::platform;
 
 impl Versi {
     pub(super) fn handle_window_close(&mut self) -> Task<Message> {
+        let behavior = self.settings.close_behavior();
         info!(
-            "Window close: tray_behavior={:?}, tray_active={}",
+            "Window close: close_behavior={:?}, tray_behavior={:?}, tray_active={}",
+            behavior,
             self.settings.tray_behavior,
             tray::is_tray_active()
         );
         self.save_window_geometry();
-        if self.settings.tray_behavior == TrayBehavior::AlwaysRunning && tray::is_tray_active() {
-            self.window_visible = false;
-            self.update_tray_menu();
-            if let Some(id) = self.window_id {
-                platform::set_dock_visible(false);
-                if platform::is_wayland() {
-                    info!("Minimizing window (Wayland fallback)");
-                    iced::window::minimize(id, true)
+        let can_minimize = self.can_minimize_to_tray();
+        match behavior {
+            CloseBehavior::MinimizeToTray if can_minimize => self.hide_to_tray(),
+            CloseBehavior::MinimizeToTray => self.minimize_window(),
+            CloseBehavior::Ask => {
+                if let AppState::Main(state) = &mut self.state {
+                    state.modal = Some(Modal::ConfirmClose {
+                        can_minimize,
+                        remember: false,
+                    });
+                    Task::none()
                 } else {
-                    info!("Hiding window to tray");
-                    iced::window::set_mode(id, iced::window::Mode::Hidden)
+                    self.request_quit()
                 }
+            }
+            _ => self.request_quit(),
+        }
+    }
+
+    pub(super) fn handle_close_dialog_choice(&mut self, choice: CloseBehavior) -> Task<Message> {
+        if let AppState::Main(state) = &mut self.state
+            && let Some(Modal::ConfirmClose { remember, .. }) = state.modal.take()
+            && remember
+        {
+            self.handle_close_behavior_changed(choice);
+        }
+
+        match choice {
+            CloseBehavior::MinimizeToTray if self.can_minimize_to_tray() => self.hide_to_tray(),
+            CloseBehavior::Ask => Task::none(),
+            _ => self.request_quit(),
+        }
+    }
+
+    pub(super) fn handle_close_behavior_changed(&mut self, behavior: CloseBehavior) {
+        self.settings.close_behavior = Some(behavior);
+        if let Err(e) = self.settings.save() {
+            log::error!("Failed to save settings: {e}");
+        }
+    }
+
+    /// Hiding to the tray needs a tray icon that outlives the window, so it
+    /// is only offered when the tray is always running.
+    fn can_minimize_to_tray(&self) -> bool {
+        self.settings.tray_behavior == TrayBehavior::AlwaysRunning && tray::is_tray_active()
+    }
+
+    /// Stands in for hiding to the tray when there is no tray to come back
+    /// from, leaving the window in the taskbar.
+    fn minimize_window(&mut self) -> Task<Message> {
+        match self.window_id {
+            Some(id) => {
+                info!("Tray unavailable, minimizing window instead");
+                iced::window::minimize(id, true)
+            }
+            None => Task::none(),
+        }
+    }
+
+    fn hide_to_tray(&mut self) -> Task<Message> {
+        self.window_visible = false;
+        self.update_tray_menu();
+        if let Some(id) = self.window_id {
+            platform::set_dock_visible(false);
+            if platform::is_wayland() {
+                info!("Minimizing window (Wayland fallback)");
+                iced::window::minimize(id, true)
             } else {
-                Task::none()
+                info!("Hiding window to tray");
+                iced::window::set_mode(id, iced::window::Mode::Hidden)
             }
         } else {
+            Task::none()
+        }
+    }
+
+    pub(super) fn request_quit(&mut self) -> Task<Message> {
+        let active = match &self.state {
+            AppState::Main(state) => state
+                .operation_queue
+                .active_count()
+                .max(versi_platform::tracked_process_count()),
+            _ => versi_platform::tracked_process_count(),
+        };
+
+        if active == 0 {
             info!("Exiting application");
-            iced::exit()
+            return iced::exit();
+        }
+
+        info!("Quit requested with {active} active operation(s), asking for confirmation");
+        if let AppState::Main(state) = &mut self.state {
+            state.modal = Some(Modal::ConfirmQuit { active });
+        }
+        self.window_visible = true;
+        self.update_tray_menu();
+        match self.window_id {
+            Some(id) => {
+                platform::set_dock_visible(true);
+                Task::batch([
+                    iced::window::set_mode(id, iced::window::Mode::Windowed),
+                    iced::window::minimize(id, false),
+                    iced::window::gain_focus(id),
+                ])
+            }
+            None => Task::none(),
+        }
+    }
+
+    pub(super) fn handle_quit_when_idle(&mut self) -> Task<Message> {
+        if let AppState::Main(state) = &mut self.state {
+            state.modal = None;
+            state.quit_when_idle = true;
+            if state.operation_queue.is_idle() {
+                return iced::exit();
+            }
+        }
+        Task::none()
+    }
+
+    pub(super) fn handle_confirm_quit(&mut self) -> Task<Message> {
+        if let AppState::Main(state) = &mut self.state {
+            state.modal = None;
+            state.operation_queue = OperationQueue::new();
+        }
+        info!(
+            "Cancelling {} running process(es) before exit",
+            versi_platform::tracked_process_count()
+        );
+        versi_platform::terminate_tracked_processes();
+        Task::perform(
+            versi_platform::wait_for_tracked_processes(Duration::from_secs(5)),
+            |_| Message::NoOp,
+        )
+        .then(|_| iced::exit())
+    }
+
+    pub(super) fn handle_window_resized(&mut self, size: iced::Size) -> Task<Message> {
+        match self.window_id {
+            Some(id) => iced::window::is_maximized(id)
+                .map(move |maximized| Message::WindowResized { size, maximized }),
+            None => {
+                self.window_size = Some(size);
+                Task::none()
+            }
         }
     }
 
     pub(super) fn handle_window_opened(&mut self, id: iced::window::Id) -> Task<Message> {
         self.window_id = Some(id);
+        let scale_task = iced::window::scale_factor(id)
+            .map(|scale| Message::WindowEvent(iced::window::Event::Rescaled(scale)));
+        let raw_id_task = iced::window::raw_id::<Message>(id).map(Message::WindowRawIdReceived);
+        Task::batch([scale_task, raw_id_task, self.show_opened_window(id)])
+    }
+
+    pub(super) fn handle_window_raw_id_received(&mut self, raw_id: u64) {
+        versi_platform::set_announcement_window(raw_id);
+
+        // On Wayland the raw id is a surface pointer, not an X11 window.
+        if platform::is_wayland() {
+            return;
+        }
+        self.window_raw_id = Some(raw_id);
+
+        if self.window_transparent && self.settings.window_vibrancy {
+            self.vibrancy_active = versi_platform::set_window_vibrancy(raw_id, true);
+            if !self.vibrancy_active {
+                info!("Window vibrancy is not supported here, keeping the window opaque");
+            }
+        }
+
+        if !self.settings.remember_workspace {
+            return;
+        }
+        if let Some(workspace) = self
+            .settings
+            .window_geometry
+            .as_ref()
+            .and_then(|geo| geo.workspace)
+            && versi_platform::window_workspace(raw_id) != Some(workspace)
+        {
+            info!("Moving window to workspace {}", workspace);
+            versi_platform::move_window_to_workspace(raw_id, workspace);
+        }
+    }
+
+    pub(super) fn handle_always_on_top_toggled(&mut self, value: bool) -> Task<Message> {
+        self.settings.always_on_top = value;
+        if let Err(e) = self.settings.save() {
+            log::error!("Failed to save settings: {e}");
+        }
+        let Some(id) = self.window_id else {
+            return Task::none();
+        };
+        iced::window::set_level(
+            id,
+            if value {
+                iced::window::Level::AlwaysOnTop
+            } else {
+                iced::window::Level::Normal
+            },
+        )
+    }
+
+    pub(super) fn handle_window_vibrancy_toggled(&mut self, value: bool) {
+        self.settings.window_vibrancy = value;
+        if let Err(e) = self.settings.save() {
+            log::error!("Failed to save settings: {e}");
+        }
+        // A window created opaque only picks this up after a restart.
+        if self.window_transparent
+            && let Some(raw_id) = self.window_raw_id
+        {
+            self.vibrancy_active = versi_platform::set_window_vibrancy(raw_id, value);
+        }
+    }
+
+    fn show_opened_window(&mut self, id: iced::window::Id) -> Task<Message> {
         if self.pending_show {
             self.pending_show = false;
             self.pending_minimize = false;
@@ -72,11 +275,26 @@ impl Versi {
 
     pub(super) fn save_window_geometry(&mut self) {
         if let (Some(size), Some(pos)) = (self.window_size, self.window_position) {
+            let previous_workspace = self
+                .settings
+                .window_geometry
+                .as_ref()
+                .and_then(|geo| geo.workspace);
+            let workspace = if self.settings.remember_workspace {
+                self.window_raw_id
+                    .and_then(versi_platform::window_workspace)
+                    .or(previous_workspace)
+            } else {
+                None
+            };
             self.settings.window_geometry = Some(crate::settings::WindowGeometry {
                 width: size.width,
                 height: size.height,
                 x: pos.x as i32,
                 y: pos.y as i32,
+                maximized: self.window_maximized,
+                scale_factor: self.window_scale_factor,
+                workspace,
             });
             if let Err(e) = self.settings.save() {
                 log::error!("Failed to save settings: {e}");
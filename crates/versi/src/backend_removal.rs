@@ -0,0 +1,429 @@
+use std::path::{Path, PathBuf};
+
+use versi_backend::VersionManager;
+use versi_shell::{ShellConfig, ShellType};
+
+const SHELLS: [ShellType; 4] = [
+    ShellType::Bash,
+    ShellType::Zsh,
+    ShellType::Fish,
+    ShellType::PowerShell,
+];
+
+/// Everything that uninstalling a version manager would touch.
+#[derive(Debug, Clone)]
+pub struct RemovalPlan {
+    pub backend: &'static str,
+    pub versions: Vec<String>,
+    pub shell_configs: Vec<(ShellType, PathBuf)>,
+    /// Configs whose initialization cannot be removed safely, with why.
+    pub shell_config_issues: Vec<(PathBuf, String)>,
+    pub data_dir: Option<PathBuf>,
+    pub data_size: u64,
+    /// A data directory left alone because it does not look like one the
+    /// version manager created.
+    pub kept_dir: Option<PathBuf>,
+    /// Variables under `HKCU\Environment` the Windows installer set.
+    pub env_vars: Vec<&'static str>,
+    /// Entries of the user's `PATH` in the registry that point into the
+    /// version manager.
+    pub path_entries: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RemovalStep {
+    pub label: String,
+    pub error: Option<String>,
+}
+
+pub fn plan(
+    backend: &'static str,
+    versions: Vec<String>,
+    marker: &str,
+    data_dir: Option<PathBuf>,
+) -> RemovalPlan {
+    let mut shell_configs = Vec::new();
+    let mut shell_config_issues = Vec::new();
+    for shell_type in &SHELLS {
+        for path in shell_type.config_files() {
+            let Ok(config) = ShellConfig::load(shell_type.clone(), path.clone()) else {
+                continue;
+            };
+            if !config.has_init(marker) {
+                continue;
+            }
+            match config.remove_init(marker) {
+                Ok(_) => shell_configs.push((shell_type.clone(), path)),
+                Err(e) => shell_config_issues.push((path, e.to_string())),
+            }
+        }
+    }
+
+    let (data_dir, kept_dir) = match data_dir.filter(|dir| dir.is_dir()) {
+        Some(dir) if is_safe_to_delete(backend, &dir) => (Some(dir), None),
+        other => (None, other),
+    };
+    let data_size = data_dir.as_deref().map(dir_size).unwrap_or(0);
+    let env_vars = env_vars_for(backend);
+    let path_entries = path_entries_for(&env_vars, data_dir.as_deref());
+
+    RemovalPlan {
+        backend,
+        versions,
+        shell_configs,
+        shell_config_issues,
+        data_dir,
+        data_size,
+        kept_dir,
+        env_vars,
+        path_entries,
+    }
+}
+
+/// Carries out `plan` step by step, continuing past failures so the user
+/// sees everything that is left to clean up by hand.
+pub async fn execute(
+    plan: RemovalPlan,
+    backend: Box<dyn VersionManager>,
+    marker: String,
+) -> Vec<RemovalStep> {
+    let mut steps = Vec::new();
+
+    for version in &plan.versions {
+        steps.push(RemovalStep {
+            label: format!("Uninstall Node {}", version),
+            error: backend
+                .uninstall(version)
+                .await
+                .err()
+                .map(|e| e.to_string()),
+        });
+    }
+
+    for (shell_type, path) in &plan.shell_configs {
+        let result = ShellConfig::load(shell_type.clone(), path.clone()).and_then(|mut config| {
+            let edit = config.remove_init(&marker)?;
+            config.apply_edit(&edit)
+        });
+        steps.push(RemovalStep {
+            label: format!("Remove shell integration from {}", path.display()),
+            error: result.err().map(|e| e.to_string()),
+        });
+    }
+
+    if let Some(dir) = &plan.data_dir {
+        let error = if is_safe_to_delete(plan.backend, dir) {
+            tokio::fs::remove_dir_all(dir)
+                .await
+                .err()
+                .map(|e| e.to_string())
+        } else {
+            Some(format!(
+                "it no longer looks like a {} directory",
+                plan.backend
+            ))
+        };
+        steps.push(RemovalStep {
+            label: format!("Delete {}", dir.display()),
+            error,
+        });
+    }
+
+    if !plan.path_entries.is_empty() {
+        steps.push(RemovalStep {
+            label: format!("Remove {} from PATH", plan.path_entries.join(", ")),
+            error: remove_user_path_entries(&plan.path_entries).await.err(),
+        });
+    }
+
+    for name in &plan.env_vars {
+        steps.push(RemovalStep {
+            label: format!("Remove environment variable {}", name),
+            error: remove_user_env_var(name).await.err(),
+        });
+    }
+
+    steps
+}
+
+/// Only directories laid out the way `backend` lays out its own are deleted,
+/// so a custom `NVM_DIR` or `FNM_DIR` pointing at a shared folder such as
+/// `/opt/tools` is never wiped.
+fn is_safe_to_delete(backend: &str, dir: &Path) -> bool {
+    let is_home = dirs::home_dir().is_some_and(|home| home == dir);
+    if is_home || !dir.is_absolute() || dir.components().count() < 3 {
+        return false;
+    }
+    match backend {
+        "nvm" => {
+            (dir.join("nvm.sh").is_file() && dir.join("versions/node").is_dir())
+                || (dir.join("nvm.exe").is_file() && dir.join("settings.txt").is_file())
+        }
+        "fnm" => dir.join("node-versions").is_dir() && dir.join("aliases").is_dir(),
+        _ => false,
+    }
+}
+
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => dir_size(&entry.path()),
+            Ok(kind) if kind.is_file() => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// A value as `reg query` prints it.
+#[derive(Debug, Clone, PartialEq)]
+struct RegValue {
+    kind: String,
+    data: String,
+}
+
+/// Reads `name` from the `reg query` output of a key. Fields are separated
+/// by four spaces, so single spaces inside the data survive.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn parse_reg_value(output: &str, name: &str) -> Option<RegValue> {
+    output.lines().find_map(|line| {
+        let (value_name, rest) = line.trim_start().split_once("    ")?;
+        if !value_name.eq_ignore_ascii_case(name) {
+            return None;
+        }
+        let rest = rest.trim_start();
+        let (kind, data) = rest.split_once("    ").unwrap_or((rest, ""));
+        Some(RegValue {
+            kind: kind.to_string(),
+            data: data.to_string(),
+        })
+    })
+}
+
+/// The entries of `path` inside one of `dirs`, or spelled through one of
+/// the variables in `names`, such as `%NVM_HOME%`.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn matching_path_entries(path: &str, dirs: &[String], names: &[&str]) -> Vec<String> {
+    let normalize = |entry: &str| entry.trim().trim_end_matches('\\').to_ascii_lowercase();
+    let dirs: Vec<String> = dirs
+        .iter()
+        .map(|dir| normalize(dir))
+        .filter(|dir| !dir.is_empty())
+        .collect();
+    path.split(';')
+        .filter(|entry| {
+            let entry = normalize(entry);
+            !entry.is_empty()
+                && (dirs
+                    .iter()
+                    .any(|dir| entry == *dir || entry.starts_with(&format!("{}\\", dir)))
+                    || names
+                        .iter()
+                        .any(|name| entry.contains(&format!("%{}%", name.to_ascii_lowercase()))))
+        })
+        .map(|entry| entry.trim().to_string())
+        .collect()
+}
+
+/// `path` without `entries`.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn without_entries(path: &str, entries: &[String]) -> String {
+    path.split(';')
+        .filter(|entry| !entries.iter().any(|e| e.as_str() == entry.trim()))
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// User-level environment variables the Windows installers set, read from
+/// `HKCU\Environment` rather than Versi's own environment, which may be
+/// stale or inherited from elsewhere.
+#[cfg(target_os = "windows")]
+fn env_vars_for(backend: &str) -> Vec<&'static str> {
+    let names: &[&'static str] = match backend {
+        "nvm" => &["NVM_HOME", "NVM_SYMLINK"],
+        "fnm" => &["FNM_DIR"],
+        _ => &[],
+    };
+    names
+        .iter()
+        .copied()
+        .filter(|name| query_user_env(name).is_some())
+        .collect()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn env_vars_for(_backend: &str) -> Vec<&'static str> {
+    Vec::new()
+}
+
+/// Entries of the user's registry `PATH` that point at the version
+/// manager's variables or its data directory.
+#[cfg(target_os = "windows")]
+fn path_entries_for(env_vars: &[&'static str], data_dir: Option<&Path>) -> Vec<String> {
+    let Some(path) = query_user_env("Path") else {
+        return Vec::new();
+    };
+    let dirs: Vec<String> = env_vars
+        .iter()
+        .filter_map(|name| query_user_env(name))
+        .map(|value| value.data)
+        .chain(data_dir.map(|dir| dir.display().to_string()))
+        .collect();
+    matching_path_entries(&path.data, &dirs, env_vars)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn path_entries_for(_env_vars: &[&'static str], _data_dir: Option<&Path>) -> Vec<String> {
+    Vec::new()
+}
+
+#[cfg(target_os = "windows")]
+fn query_user_env(name: &str) -> Option<RegValue> {
+    use versi_platform::HideWindow;
+
+    let output = std::process::Command::new("reg")
+        .args(["query", "HKCU\\Environment", "/v", name])
+        .hide_window()
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_reg_value(&String::from_utf8_lossy(&output.stdout), name)
+}
+
+#[cfg(target_os = "windows")]
+async fn reg(args: &[&str]) -> Result<(), String> {
+    use versi_platform::HideWindow;
+
+    let output = tokio::process::Command::new("reg")
+        .args(args)
+        .hide_window()
+        .output()
+        .await
+        .map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+#[cfg(target_os = "windows")]
+async fn remove_user_env_var(name: &str) -> Result<(), String> {
+    reg(&["delete", "HKCU\\Environment", "/v", name, "/f"]).await
+}
+
+#[cfg(not(target_os = "windows"))]
+async fn remove_user_env_var(_name: &str) -> Result<(), String> {
+    Ok(())
+}
+
+/// Rewrites the user's `PATH` without `entries`, keeping its registry type
+/// so `%VAR%` references still expand.
+#[cfg(target_os = "windows")]
+async fn remove_user_path_entries(entries: &[String]) -> Result<(), String> {
+    let entries = entries.to_vec();
+    let path = tokio::task::spawn_blocking(|| query_user_env("Path"))
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("PATH is no longer set")?;
+    let data = without_entries(&path.data, &entries);
+    reg(&[
+        "add",
+        "HKCU\\Environment",
+        "/v",
+        "Path",
+        "/t",
+        &path.kind,
+        "/d",
+        &data,
+        "/f",
+    ])
+    .await
+}
+
+#[cfg(not(target_os = "windows"))]
+async fn remove_user_path_entries(_entries: &[String]) -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refuses_broad_directories() {
+        assert!(!is_safe_to_delete("fnm", Path::new("/")));
+        assert!(!is_safe_to_delete("fnm", Path::new("relative/fnm")));
+        if let Some(home) = dirs::home_dir() {
+            assert!(!is_safe_to_delete("nvm", &home));
+        }
+    }
+
+    #[test]
+    fn only_deletes_the_managers_own_layout() {
+        let dir = tempfile::tempdir().unwrap();
+        let nvm = dir.path().join("nvm");
+        std::fs::create_dir_all(nvm.join("versions/node")).unwrap();
+        assert!(!is_safe_to_delete("nvm", &nvm));
+        std::fs::write(nvm.join("nvm.sh"), "").unwrap();
+        assert!(is_safe_to_delete("nvm", &nvm));
+        assert!(!is_safe_to_delete("fnm", &nvm));
+
+        let fnm = dir.path().join("fnm");
+        std::fs::create_dir_all(fnm.join("node-versions")).unwrap();
+        assert!(!is_safe_to_delete("fnm", &fnm));
+        std::fs::create_dir_all(fnm.join("aliases")).unwrap();
+        assert!(is_safe_to_delete("fnm", &fnm));
+    }
+
+    #[test]
+    fn sums_nested_file_sizes() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        std::fs::create_dir_all(dir.join("node-versions/v22.11.0")).unwrap();
+        std::fs::write(dir.join("aliases"), [0u8; 10]).unwrap();
+        std::fs::write(dir.join("node-versions/v22.11.0/node"), [0u8; 32]).unwrap();
+
+        assert_eq!(dir_size(dir), 42);
+    }
+
+    #[test]
+    fn reads_registry_values_with_spaces() {
+        let output = "\r\nHKEY_CURRENT_USER\\Environment\r\n    Path    REG_EXPAND_SZ    C:\\Program Files\\tool;%NVM_HOME%;%NVM_SYMLINK%\r\n\r\n";
+        assert_eq!(
+            parse_reg_value(output, "PATH"),
+            Some(RegValue {
+                kind: "REG_EXPAND_SZ".to_string(),
+                data: "C:\\Program Files\\tool;%NVM_HOME%;%NVM_SYMLINK%".to_string(),
+            })
+        );
+        assert_eq!(parse_reg_value(output, "NVM_HOME"), None);
+    }
+
+    #[test]
+    fn strips_only_the_managers_path_entries() {
+        let path = r"C:\Windows;%NVM_HOME%;C:\Users\me\AppData\Roaming\nvm\;c:\nvm4w\nodejs;C:\Users\me\AppData\Roaming\nvm-tools;%USERPROFILE%\bin";
+        let dirs = [
+            r"C:\Users\me\AppData\Roaming\nvm".to_string(),
+            r"C:\nvm4w\nodejs".to_string(),
+        ];
+        let entries = matching_path_entries(path, &dirs, &["NVM_HOME", "NVM_SYMLINK"]);
+        assert_eq!(
+            entries,
+            [
+                "%NVM_HOME%",
+                r"C:\Users\me\AppData\Roaming\nvm\",
+                r"c:\nvm4w\nodejs"
+            ]
+        );
+        assert_eq!(
+            without_entries(path, &entries),
+            r"C:\Windows;C:\Users\me\AppData\Roaming\nvm-tools;%USERPROFILE%\bin"
+        );
+    }
+}
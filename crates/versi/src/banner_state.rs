@@ -0,0 +1,129 @@
+use std::collections::BTreeMap;
+
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Contextual banners that can be snoozed or dismissed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BannerId {
+    MajorUpdates,
+    Offline,
+    StaleVersions,
+    StaleSchedule,
+    ScheduleUnavailable,
+}
+
+impl BannerId {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::MajorUpdates => "Updates available",
+            Self::Offline => "Available versions could not load",
+            Self::StaleVersions => "Using cached versions",
+            Self::StaleSchedule => "Using the cached release schedule",
+            Self::ScheduleUnavailable => "Release schedule unavailable",
+        }
+    }
+}
+
+/// How long a banner stays hidden.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BannerSnooze {
+    Day,
+    Week,
+    /// Until restored from the notifications center.
+    Forever,
+}
+
+impl BannerSnooze {
+    pub const ALL: [BannerSnooze; 3] = [Self::Day, Self::Week, Self::Forever];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Day => "Snooze 1d",
+            Self::Week => "Snooze 1w",
+            Self::Forever => "Dismiss",
+        }
+    }
+
+    fn until(self, today: NaiveDate) -> Option<NaiveDate> {
+        let days = match self {
+            Self::Day => 1,
+            Self::Week => 7,
+            Self::Forever => return None,
+        };
+        today.checked_add_days(Days::new(days))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BannerSuppression {
+    /// `None` when dismissed for good.
+    pub snoozed_until: Option<NaiveDate>,
+}
+
+impl BannerSuppression {
+    fn hides(&self, today: NaiveDate) -> bool {
+        self.snoozed_until.is_none_or(|until| today < until)
+    }
+}
+
+/// Banners the user snoozed or dismissed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct BannerStates(BTreeMap<BannerId, BannerSuppression>);
+
+impl BannerStates {
+    pub fn is_hidden(&self, id: BannerId, today: NaiveDate) -> bool {
+        self.0.get(&id).is_some_and(|s| s.hides(today))
+    }
+
+    /// Hides `id` for `snooze`, dropping snoozes that have run out.
+    pub fn suppress(&mut self, id: BannerId, snooze: BannerSnooze, today: NaiveDate) {
+        self.0.retain(|_, s| s.hides(today));
+        self.0.insert(
+            id,
+            BannerSuppression {
+                snoozed_until: snooze.until(today),
+            },
+        );
+    }
+
+    pub fn restore(&mut self, id: BannerId) -> bool {
+        self.0.remove(&id).is_some()
+    }
+
+    /// Banners hidden on `today`.
+    pub fn suppressed(
+        &self,
+        today: NaiveDate,
+    ) -> impl Iterator<Item = (BannerId, &BannerSuppression)> {
+        self.0
+            .iter()
+            .filter(move |(_, s)| s.hides(today))
+            .map(|(id, s)| (*id, s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, day).unwrap()
+    }
+
+    #[test]
+    fn snoozed_banners_come_back() {
+        let mut states = BannerStates::default();
+        states.suppress(BannerId::MajorUpdates, BannerSnooze::Day, date(1));
+        assert!(states.is_hidden(BannerId::MajorUpdates, date(1)));
+        assert!(!states.is_hidden(BannerId::MajorUpdates, date(2)));
+        assert_eq!(states.suppressed(date(2)).count(), 0);
+
+        states.suppress(BannerId::Offline, BannerSnooze::Forever, date(2));
+        assert!(states.is_hidden(BannerId::Offline, date(28)));
+        assert!(states.restore(BannerId::Offline));
+        assert!(!states.is_hidden(BannerId::Offline, date(28)));
+    }
+}
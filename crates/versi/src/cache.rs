@@ -1,5 +1,6 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use versi_backend::RemoteVersion;
 use versi_core::ReleaseSchedule;
 use versi_platform::AppPaths;
@@ -8,11 +9,19 @@ use versi_platform::AppPaths;
 pub struct DiskCache {
     pub remote_versions: Vec<RemoteVersion>,
     pub release_schedule: Option<ReleaseSchedule>,
+    /// When the schedule was last fetched, for its own expiry.
+    #[serde(default)]
+    pub schedule_fetched_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub release_dates: HashMap<String, NaiveDate>,
     pub cached_at: DateTime<Utc>,
 }
 
 impl DiskCache {
     pub fn load() -> Option<Self> {
+        if crate::safe_mode::is_active() {
+            return None;
+        }
         let paths = AppPaths::new().ok()?;
         let path = paths.version_cache_file();
         let data = std::fs::read_to_string(path).ok()?;
@@ -20,6 +29,9 @@ impl DiskCache {
     }
 
     pub fn save(&self) {
+        if crate::safe_mode::is_active() {
+            return;
+        }
         let Ok(paths) = AppPaths::new() else {
             return;
         };
@@ -0,0 +1,366 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use serde::Serialize;
+use versi_backend::{BackendDetection, BackendProvider, VersionManager};
+use versi_platform::{EnvironmentId, PlatformTarget};
+
+use crate::app;
+use crate::settings::AppSettings;
+
+const USAGE: &str = "Usage: versi [OPTIONS]
+
+Without options, Versi opens its window.
+
+Options:
+      --version        Print the version and exit
+      --status         Print detected backends and environments as JSON and exit
+      --check-updates  Print available app, backend and Node updates as JSON and exit
+      --safe-mode      Open with default settings and without cached data
+  -h, --help           Print this help and exit";
+
+/// A command-line flag that answers on stdout instead of opening the window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CliCommand {
+    Version,
+    Status,
+    CheckUpdates,
+    Help,
+}
+
+impl CliCommand {
+    /// The first flag in `args` that Versi answers without its window.
+    /// Other arguments, like `--software-rendering`, are left for the GUI.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Option<Self> {
+        args.into_iter().find_map(|arg| match arg.as_str() {
+            "--version" | "-V" => Some(Self::Version),
+            "--status" => Some(Self::Status),
+            "--check-updates" => Some(Self::CheckUpdates),
+            "--help" | "-h" => Some(Self::Help),
+            _ => None,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Status {
+    version: &'static str,
+    preferred_backend: Option<String>,
+    backends: Vec<BackendStatus>,
+    environments: Vec<EnvironmentStatus>,
+}
+
+#[derive(Debug, Serialize)]
+struct BackendStatus {
+    name: &'static str,
+    found: bool,
+    path: Option<PathBuf>,
+    version: Option<String>,
+    data_dir: Option<PathBuf>,
+    source: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct EnvironmentStatus {
+    id: String,
+    name: String,
+    backend: &'static str,
+    default: Option<String>,
+    active: Option<String>,
+    installed: Vec<String>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct Updates {
+    app: UpdateStatus,
+    backend: UpdateStatus,
+    /// Newest release of each installed major that is ahead of what is
+    /// installed.
+    node: Vec<String>,
+    node_error: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct UpdateStatus {
+    current: Option<String>,
+    latest: Option<String>,
+    url: Option<String>,
+    error: Option<String>,
+}
+
+/// Runs `command`, printing its output, and returns the exit code.
+pub fn run(command: CliCommand, settings: &AppSettings) -> i32 {
+    attach_console();
+
+    let output = match command {
+        CliCommand::Version => {
+            println!("versi {}", env!("CARGO_PKG_VERSION"));
+            return 0;
+        }
+        CliCommand::Help => {
+            println!("{USAGE}");
+            return 0;
+        }
+        CliCommand::Status | CliCommand::CheckUpdates => {
+            let runtime = match tokio::runtime::Runtime::new() {
+                Ok(runtime) => runtime,
+                Err(e) => {
+                    eprintln!("Error: failed to start the async runtime: {e}");
+                    return 1;
+                }
+            };
+            if command == CliCommand::Status {
+                serde_json::to_string_pretty(&runtime.block_on(status(settings)))
+            } else {
+                serde_json::to_string_pretty(&runtime.block_on(check_updates(settings)))
+            }
+        }
+    };
+
+    match output {
+        Ok(json) => {
+            println!("{json}");
+            0
+        }
+        Err(e) => {
+            eprintln!("Error: {e}");
+            1
+        }
+    }
+}
+
+fn providers(settings: &AppSettings) -> Vec<Arc<dyn BackendProvider>> {
+    vec![
+        Arc::new(app::fnm_provider(settings)),
+        Arc::new(app::nvm_provider(settings)),
+    ]
+}
+
+async fn detect(settings: &AppSettings) -> Vec<(Arc<dyn BackendProvider>, BackendDetection)> {
+    let mut detections = Vec::new();
+    for provider in providers(settings) {
+        let detection = provider.detect().await;
+        detections.push((provider, detection));
+    }
+    detections
+}
+
+/// The backend Versi would pick at startup: the preferred one when it is
+/// installed, otherwise the first one found.
+fn active_backend<'a>(
+    settings: &AppSettings,
+    detections: &'a [(Arc<dyn BackendProvider>, BackendDetection)],
+) -> Option<&'a (Arc<dyn BackendProvider>, BackendDetection)> {
+    let preferred = settings.preferred_backend.as_deref().unwrap_or("fnm");
+    detections
+        .iter()
+        .filter(|(_, detection)| detection.found)
+        .min_by_key(|(provider, _)| provider.name() != preferred)
+}
+
+fn native_manager(
+    settings: &AppSettings,
+    provider: &Arc<dyn BackendProvider>,
+    detection: &BackendDetection,
+) -> Box<dyn VersionManager> {
+    let mut manager = provider.create_manager(detection);
+    let target = PlatformTarget::native();
+    manager.set_env_overrides(settings.env_overrides_for(&EnvironmentId::Native, Some(&target)));
+    manager
+}
+
+async fn status(settings: &AppSettings) -> Status {
+    let detections = detect(settings).await;
+
+    let mut environments = Vec::new();
+    if let Some((provider, detection)) = active_backend(settings, &detections) {
+        let manager = native_manager(settings, provider, detection);
+        let id = EnvironmentId::Native;
+        let (default, installed, error) = match manager.list_installed().await {
+            Ok(versions) => (
+                versions
+                    .iter()
+                    .find(|v| v.is_default)
+                    .map(|v| v.version.to_string()),
+                versions.iter().map(|v| v.version.to_string()).collect(),
+                None,
+            ),
+            Err(e) => (None, Vec::new(), Some(e.to_string())),
+        };
+        let active = manager.current_version().await.ok().flatten();
+        environments.push(EnvironmentStatus {
+            id: id.settings_key(),
+            name: id.display_name(),
+            backend: provider.name(),
+            default,
+            active: active.map(|v| v.to_string()),
+            installed,
+            error,
+        });
+    }
+
+    Status {
+        version: env!("CARGO_PKG_VERSION"),
+        preferred_backend: settings.preferred_backend.clone(),
+        backends: detections
+            .into_iter()
+            .map(|(provider, detection)| BackendStatus {
+                name: provider.name(),
+                found: detection.found,
+                path: detection.path,
+                version: detection.version,
+                data_dir: detection.data_dir,
+                source: detection.source,
+            })
+            .collect(),
+        environments,
+    }
+}
+
+async fn check_updates(settings: &AppSettings) -> Updates {
+    let github = versi_core::GitHubClient::new(
+        app::build_http_client(settings),
+        settings.github_token.clone(),
+    );
+    let policy = &settings.retry_policy;
+
+    let current = env!("CARGO_PKG_VERSION");
+    let app = match policy
+        .run("App update check", || {
+            versi_core::check_for_update(&github, current)
+        })
+        .await
+    {
+        Ok(update) => UpdateStatus {
+            current: Some(current.to_string()),
+            latest: update.as_ref().map(|u| u.latest_version.clone()),
+            url: update.map(|u| u.release_url),
+            error: None,
+        },
+        Err(e) => UpdateStatus {
+            current: Some(current.to_string()),
+            error: Some(e),
+            ..Default::default()
+        },
+    };
+
+    let detections = detect(settings).await;
+    let active = active_backend(settings, &detections);
+    let channel = active
+        .map(|(provider, _)| settings.update_channel_for(provider.name()))
+        .unwrap_or_default();
+    let backend = match active {
+        Some((provider, detection)) => match &detection.version {
+            Some(version) => match policy
+                .run("Backend update check", || {
+                    provider.check_for_update(&github, version, &channel)
+                })
+                .await
+            {
+                Ok(update) => UpdateStatus {
+                    current: Some(version.clone()),
+                    latest: update.as_ref().map(|u| u.latest_version.clone()),
+                    url: update.map(|u| u.release_url),
+                    error: None,
+                },
+                Err(e) => UpdateStatus {
+                    current: Some(version.clone()),
+                    error: Some(e),
+                    ..Default::default()
+                },
+            },
+            None => UpdateStatus {
+                error: Some(format!("Could not read the {} version", provider.name())),
+                ..Default::default()
+            },
+        },
+        None => UpdateStatus {
+            error: Some("No backend is installed".to_string()),
+            ..Default::default()
+        },
+    };
+
+    let (node, node_error) = match active {
+        Some((provider, detection)) => {
+            match node_updates(native_manager(settings, provider, detection).as_ref()).await {
+                Ok(node) => (node, None),
+                Err(e) => (Vec::new(), Some(e)),
+            }
+        }
+        None => (Vec::new(), None),
+    };
+
+    Updates {
+        app,
+        backend,
+        node,
+        node_error,
+    }
+}
+
+async fn node_updates(manager: &dyn VersionManager) -> Result<Vec<String>, String> {
+    let installed = manager.list_installed().await.map_err(|e| e.to_string())?;
+    let remote = manager.list_remote().await.map_err(|e| e.to_string())?;
+
+    let mut majors: Vec<u32> = installed.iter().map(|v| v.version.major).collect();
+    majors.sort_unstable();
+    majors.dedup();
+
+    Ok(majors
+        .into_iter()
+        .filter_map(|major| {
+            let installed_latest = installed
+                .iter()
+                .map(|v| &v.version)
+                .filter(|v| v.major == major)
+                .max()?;
+            remote
+                .iter()
+                .map(|v| &v.version)
+                .filter(|v| v.major == major && *v > installed_latest)
+                .max()
+                .map(|latest| latest.to_string())
+        })
+        .collect())
+}
+
+/// Versi is built as a GUI program on Windows, so it has no console of its
+/// own; output goes to the terminal it was started from instead.
+#[cfg(windows)]
+fn attach_console() {
+    use windows_sys::Win32::System::Console::{ATTACH_PARENT_PROCESS, AttachConsole};
+
+    // SAFETY: AttachConsole only fails when there is no parent console, in
+    // which case output is dropped as before.
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_the_first_command_flag() {
+        assert_eq!(CliCommand::parse(args(&[])), None);
+        assert_eq!(CliCommand::parse(args(&["--software-rendering"])), None);
+        assert_eq!(
+            CliCommand::parse(args(&["--software-rendering", "--status", "--version"])),
+            Some(CliCommand::Status)
+        );
+        assert_eq!(
+            CliCommand::parse(args(&["--check-updates"])),
+            Some(CliCommand::CheckUpdates)
+        );
+        assert_eq!(CliCommand::parse(args(&["-V"])), Some(CliCommand::Version));
+    }
+}
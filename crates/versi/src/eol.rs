@@ -0,0 +1,193 @@
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+use versi_core::ReleaseSchedule;
+
+use crate::settings::AppSettings;
+
+/// How long a snoozed warning stays hidden.
+pub const SNOOZE_DAYS: u64 = 30;
+
+/// Grace periods offered in settings, in days.
+pub const GRACE_PERIODS: [u32; 4] = [0, 7, 30, 90];
+
+/// How far ahead of end-of-life the countdown badges can start, in days.
+pub const COUNTDOWN_THRESHOLDS: [u32; 4] = [30, 90, 180, 365];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SupportPhase {
+    Maintenance,
+    EndOfLife,
+}
+
+/// An installed major that warrants a warning.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SupportWarning {
+    pub major: u32,
+    pub phase: SupportPhase,
+    /// When the major entered `phase`, if the schedule lists it.
+    pub since: Option<NaiveDate>,
+}
+
+/// A warning the user snoozed or dismissed. It is tied to the date the
+/// major entered its phase, so it comes back when the major moves on from
+/// maintenance to end-of-life.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WarningAcknowledgement {
+    pub since: Option<NaiveDate>,
+    /// `None` when dismissed for good.
+    pub snoozed_until: Option<NaiveDate>,
+}
+
+impl WarningAcknowledgement {
+    pub fn snoozed(warning: &SupportWarning, today: NaiveDate) -> Self {
+        Self {
+            since: warning.since,
+            snoozed_until: today.checked_add_days(Days::new(SNOOZE_DAYS)),
+        }
+    }
+
+    pub fn dismissed(warning: &SupportWarning) -> Self {
+        Self {
+            since: warning.since,
+            snoozed_until: None,
+        }
+    }
+
+    pub fn hides(&self, warning: &SupportWarning, today: NaiveDate) -> bool {
+        self.since == warning.since && self.snoozed_until.is_none_or(|until| today < until)
+    }
+}
+
+/// The warning `major` deserves today, if any. Maintenance only counts in
+/// strict mode, and nothing is flagged until `grace_days` have passed.
+pub fn warning_for(
+    schedule: &ReleaseSchedule,
+    major: u32,
+    strict: bool,
+    grace_days: u32,
+    today: NaiveDate,
+) -> Option<SupportWarning> {
+    let (phase, since) = match schedule.end_date(major) {
+        Some(end) if end <= today => (SupportPhase::EndOfLife, Some(end)),
+        None if !schedule.is_active(major) => (SupportPhase::EndOfLife, None),
+        _ if strict => {
+            let start = schedule
+                .maintenance_date(major)
+                .filter(|&date| date <= today)?;
+            (SupportPhase::Maintenance, Some(start))
+        }
+        _ => return None,
+    };
+    if let Some(since) = since
+        && since
+            .checked_add_days(Days::new(u64::from(grace_days)))
+            .is_some_and(|flagged_from| today < flagged_from)
+    {
+        return None;
+    }
+    Some(SupportWarning {
+        major,
+        phase,
+        since,
+    })
+}
+
+/// Warnings for `majors` that have not been snoozed or dismissed.
+pub fn visible_warnings(
+    schedule: &ReleaseSchedule,
+    majors: impl IntoIterator<Item = u32>,
+    settings: &AppSettings,
+    today: NaiveDate,
+) -> Vec<SupportWarning> {
+    majors
+        .into_iter()
+        .filter_map(|major| {
+            warning_for(
+                schedule,
+                major,
+                settings.eol_strict,
+                settings.eol_grace_days,
+                today,
+            )
+        })
+        .filter(|warning| {
+            settings
+                .eol_acknowledgements
+                .get(&warning.major)
+                .is_none_or(|ack| !ack.hides(warning, today))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use versi_core::ReleaseSchedule;
+
+    fn schedule() -> ReleaseSchedule {
+        let json = r#"{
+            "v16": {"start": "2021-04-20", "lts": "2021-10-26", "maintenance": "2022-10-18", "end": "2023-09-11", "codename": "Gallium"},
+            "v20": {"start": "2023-04-18", "lts": "2023-10-24", "maintenance": "2024-10-22", "end": "2026-04-30", "codename": "Iron"},
+            "v22": {"start": "2024-04-24", "lts": "2024-10-29", "maintenance": "2025-10-21", "end": "2027-04-30", "codename": "Jod"}
+        }"#;
+        let versions: HashMap<String, versi_core::VersionSchedule> =
+            serde_json::from_str(json).unwrap();
+        ReleaseSchedule {
+            versions: versions
+                .into_iter()
+                .map(|(key, value)| (key[1..].parse().unwrap(), value))
+                .collect(),
+        }
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn strict_mode_flags_maintenance() {
+        let schedule = schedule();
+        let today = date("2025-01-15");
+
+        let eol = warning_for(&schedule, 16, false, 0, today).unwrap();
+        assert_eq!(eol.phase, SupportPhase::EndOfLife);
+        assert_eq!(eol.since, Some(date("2023-09-11")));
+
+        assert_eq!(warning_for(&schedule, 20, false, 0, today), None);
+        let maintenance = warning_for(&schedule, 20, true, 0, today).unwrap();
+        assert_eq!(maintenance.phase, SupportPhase::Maintenance);
+        assert_eq!(warning_for(&schedule, 22, true, 0, today), None);
+
+        assert_eq!(
+            warning_for(&schedule, 10, false, 0, today).map(|w| w.since),
+            Some(None)
+        );
+    }
+
+    #[test]
+    fn grace_period_delays_warnings() {
+        let schedule = schedule();
+        assert_eq!(
+            warning_for(&schedule, 20, true, 90, date("2025-01-15")),
+            None
+        );
+        assert!(warning_for(&schedule, 20, true, 90, date("2025-01-20")).is_some());
+    }
+
+    #[test]
+    fn acknowledgements_expire_or_follow_the_phase() {
+        let schedule = schedule();
+        let today = date("2025-01-15");
+        let maintenance = warning_for(&schedule, 20, true, 0, today).unwrap();
+
+        let snoozed = WarningAcknowledgement::snoozed(&maintenance, today);
+        assert!(snoozed.hides(&maintenance, date("2025-02-13")));
+        assert!(!snoozed.hides(&maintenance, date("2025-02-14")));
+
+        let dismissed = WarningAcknowledgement::dismissed(&maintenance);
+        assert!(dismissed.hides(&maintenance, date("2026-04-29")));
+        let eol = warning_for(&schedule, 20, true, 0, date("2026-05-01")).unwrap();
+        assert!(!dismissed.hides(&eol, date("2026-05-01")));
+    }
+}
@@ -0,0 +1,100 @@
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Local};
+
+const CAPACITY: usize = 500;
+const MAX_SUMMARY_LEN: usize = 240;
+
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub at: DateTime<Local>,
+    pub summary: String,
+}
+
+/// Ring buffer of recently dispatched messages, kept for the debug overlay.
+#[derive(Debug, Default)]
+pub struct EventLog {
+    entries: VecDeque<LogEntry>,
+}
+
+impl EventLog {
+    pub fn record(&mut self, summary: String, at: DateTime<Local>) {
+        if self.entries.len() == CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(LogEntry {
+            at,
+            summary: truncate(summary),
+        });
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// The most recent `count` entries, newest first.
+    pub fn recent(&self, count: usize) -> impl Iterator<Item = &LogEntry> {
+        self.entries.iter().rev().take(count)
+    }
+
+    /// Plain-text dump, oldest first, suitable for attaching to a bug report.
+    pub fn dump(&self) -> String {
+        let mut out = format!(
+            "Versi {} event log ({} entries)\n",
+            env!("CARGO_PKG_VERSION"),
+            self.entries.len()
+        );
+        for entry in &self.entries {
+            out.push_str(&format!(
+                "{} {}\n",
+                entry.at.format("%Y-%m-%d %H:%M:%S%.3f"),
+                entry.summary
+            ));
+        }
+        out
+    }
+}
+
+fn truncate(mut summary: String) -> String {
+    if summary.len() > MAX_SUMMARY_LEN {
+        let mut end = MAX_SUMMARY_LEN;
+        while !summary.is_char_boundary(end) {
+            end -= 1;
+        }
+        summary.truncate(end);
+        summary.push('…');
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drops_oldest_entries_past_capacity() {
+        let mut log = EventLog::default();
+        for i in 0..CAPACITY + 5 {
+            log.record(format!("Message{i}"), Local::now());
+        }
+        assert_eq!(log.len(), CAPACITY);
+        assert_eq!(
+            log.recent(1).next().unwrap().summary,
+            format!("Message{}", CAPACITY + 4)
+        );
+        assert!(log.dump().lines().nth(1).unwrap().ends_with("Message5"));
+    }
+
+    #[test]
+    fn truncates_long_summaries() {
+        let mut log = EventLog::default();
+        log.record("é".repeat(MAX_SUMMARY_LEN), Local::now());
+        let summary = &log.recent(1).next().unwrap().summary;
+        assert!(summary.len() <= MAX_SUMMARY_LEN + '…'.len_utf8());
+        assert!(summary.ends_with('…'));
+    }
+}
@@ -47,6 +47,14 @@ pub fn close(size: f32) -> svg::Svg<'static, Theme> {
     themed_icon(include_bytes!("../../../assets/icons/close.svg"), size)
 }
 
+pub fn warning(size: f32) -> svg::Svg<'static, Theme> {
+    themed_icon(include_bytes!("../../../assets/icons/warning.svg"), size)
+}
+
+pub fn lock(size: f32) -> svg::Svg<'static, Theme> {
+    themed_icon(include_bytes!("../../../assets/icons/lock.svg"), size)
+}
+
 pub fn check(size: f32) -> svg::Svg<'static, Theme> {
     themed_icon(include_bytes!("../../../assets/icons/check.svg"), size)
 }
@@ -0,0 +1,231 @@
+use std::path::{Path, PathBuf};
+
+const INTERPRETER_KEY: &str = "nodejs_interpreter_path";
+
+/// Product prefixes of config directories under `<config>/JetBrains` whose
+/// IDEs ship the Node.js plugin.
+const PRODUCTS: &[&str] = &[
+    "WebStorm",
+    "IntelliJIdea",
+    "IdeaIC",
+    "PhpStorm",
+    "PyCharm",
+    "GoLand",
+    "RubyMine",
+    "Rider",
+    "CLion",
+    "RustRover",
+];
+
+/// Files inside an IDE config directory that may hold the interpreter path:
+/// application-wide properties and the template for new projects.
+const OPTION_FILES: &[&str] = &["options/other.xml", "options/project.default.xml"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdeConfig {
+    pub name: String,
+    pub dir: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterpreterChange {
+    pub ide: String,
+    pub file: PathBuf,
+    pub from: String,
+    pub to: String,
+}
+
+pub fn detect_ide_configs() -> Vec<IdeConfig> {
+    let Some(root) = dirs::config_dir().map(|dir| dir.join("JetBrains")) else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(&root) else {
+        return Vec::new();
+    };
+
+    let mut configs: Vec<IdeConfig> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            PRODUCTS
+                .iter()
+                .any(|product| name.starts_with(product))
+                .then(|| IdeConfig {
+                    name,
+                    dir: entry.path(),
+                })
+        })
+        .collect();
+    configs.sort_by(|a, b| a.name.cmp(&b.name));
+    configs
+}
+
+/// Lists the interpreter paths that point at a version-manager install and
+/// would change to `node_path`.
+pub fn plan_update(configs: &[IdeConfig], node_path: &str) -> Vec<InterpreterChange> {
+    let mut changes = Vec::new();
+    for config in configs {
+        for file in OPTION_FILES.iter().map(|f| config.dir.join(f)) {
+            let Ok(content) = std::fs::read_to_string(&file) else {
+                continue;
+            };
+            for from in interpreter_paths(&content) {
+                if from != node_path && is_managed_path(&from) {
+                    changes.push(InterpreterChange {
+                        ide: config.name.clone(),
+                        file: file.clone(),
+                        from,
+                        to: node_path.to_string(),
+                    });
+                }
+            }
+        }
+    }
+    changes
+}
+
+pub fn apply(changes: &[InterpreterChange]) -> Result<usize, String> {
+    let mut files: Vec<&Path> = changes.iter().map(|c| c.file.as_path()).collect();
+    files.dedup();
+
+    for file in &files {
+        let mut content = std::fs::read_to_string(file).map_err(|e| e.to_string())?;
+        for change in changes.iter().filter(|c| c.file == *file) {
+            content = replace_interpreter_path(&content, &change.from, &change.to);
+        }
+        std::fs::write(file, content).map_err(|e| format!("{}: {}", file.display(), e))?;
+    }
+    Ok(files.len())
+}
+
+/// The ways the interpreter path is serialized: an XML property, and the
+/// `keyToString` JSON map, either raw or XML-escaped.
+fn patterns() -> [(String, &'static str, bool); 3] {
+    [
+        (
+            format!("name=\"{}\" value=\"", INTERPRETER_KEY),
+            "\"",
+            false,
+        ),
+        (format!("\"{}\": \"", INTERPRETER_KEY), "\"", true),
+        (
+            format!("&quot;{}&quot;: &quot;", INTERPRETER_KEY),
+            "&quot;",
+            true,
+        ),
+    ]
+}
+
+fn interpreter_paths(content: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+    for (prefix, terminator, json) in patterns() {
+        for (start, _) in content.match_indices(&prefix) {
+            let rest = &content[start + prefix.len()..];
+            if let Some(end) = rest.find(terminator) {
+                let value = &rest[..end];
+                let value = if json {
+                    value.replace("\\\\", "\\")
+                } else {
+                    value.to_string()
+                };
+                if !paths.contains(&value) {
+                    paths.push(value);
+                }
+            }
+        }
+    }
+    paths
+}
+
+fn replace_interpreter_path(content: &str, from: &str, to: &str) -> String {
+    let mut result = content.to_string();
+    for (prefix, terminator, json) in patterns() {
+        let escape = |path: &str| {
+            if json {
+                path.replace('\\', "\\\\")
+            } else {
+                path.to_string()
+            }
+        };
+        result = result.replace(
+            &format!("{}{}{}", prefix, escape(from), terminator),
+            &format!("{}{}{}", prefix, escape(to), terminator),
+        );
+    }
+    result
+}
+
+/// Whether a Node.js path lives inside an fnm or nvm installation, so a
+/// hand-picked system interpreter is never replaced.
+fn is_managed_path(path: &str) -> bool {
+    let normalized = path.replace('\\', "/").to_ascii_lowercase();
+    [
+        "/node-versions/",
+        "/fnm_multishells/",
+        "/.nvm/versions/node/",
+        "/nvm/v",
+    ]
+    .iter()
+    .any(|pattern| normalized.contains(pattern))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_paths_in_every_format() {
+        let content = r#"<application>
+  <component name="PropertiesComponent">
+    <property name="nodejs_interpreter_path" value="/home/dev/.nvm/versions/node/v20.18.0/bin/node" />
+  </component>
+  <component name="Other"><![CDATA[{ "keyToString": { "nodejs_interpreter_path": "C:\\nvm\\v18.20.4\\node.exe" } }]]></component>
+  <component name="Escaped">{&quot;keyToString&quot;: {&quot;nodejs_interpreter_path&quot;: &quot;/usr/bin/node&quot;}}</component>
+</application>"#;
+
+        assert_eq!(
+            interpreter_paths(content),
+            vec![
+                "/home/dev/.nvm/versions/node/v20.18.0/bin/node".to_string(),
+                "C:\\nvm\\v18.20.4\\node.exe".to_string(),
+                "/usr/bin/node".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn replaces_only_matching_path() {
+        let content = r#"<property name="nodejs_interpreter_path" value="/a/node-versions/v20.18.0/installation/bin/node" />
+{ "nodejs_interpreter_path": "C:\\fnm\\node-versions\\v20.18.0\\installation\\node.exe" }"#;
+
+        let updated = replace_interpreter_path(
+            content,
+            "/a/node-versions/v20.18.0/installation/bin/node",
+            "/a/node-versions/v22.11.0/installation/bin/node",
+        );
+        assert!(updated.contains("value=\"/a/node-versions/v22.11.0/installation/bin/node\""));
+        assert!(updated.contains("v20.18.0\\\\installation"));
+
+        let updated = replace_interpreter_path(
+            &updated,
+            "C:\\fnm\\node-versions\\v20.18.0\\installation\\node.exe",
+            "C:\\fnm\\node-versions\\v22.11.0\\installation\\node.exe",
+        );
+        assert!(updated.contains(r#""C:\\fnm\\node-versions\\v22.11.0\\installation\\node.exe""#));
+    }
+
+    #[test]
+    fn only_managed_paths_are_updated() {
+        assert!(is_managed_path(
+            "/home/dev/.local/share/fnm/node-versions/v22.11.0/installation/bin/node"
+        ));
+        assert!(is_managed_path(
+            "/Users/dev/.nvm/versions/node/v20.18.0/bin/node"
+        ));
+        assert!(is_managed_path(
+            "C:\\Users\\dev\\AppData\\Roaming\\nvm\\v18.20.4\\node.exe"
+        ));
+        assert!(!is_managed_path("/usr/local/bin/node"));
+    }
+}
@@ -0,0 +1,87 @@
+use std::time::Duration;
+
+use versi_backend::{BackendError, VersionManager};
+
+/// Result of verifying one installed version.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Integrity {
+    Checking,
+    Healthy,
+    Corrupted(String),
+}
+
+/// Checks that the `node` binary of `version` exists and reports the
+/// version it was installed as.
+pub async fn verify(
+    backend: &dyn VersionManager,
+    version: &str,
+    timeout: Duration,
+) -> Result<(), String> {
+    let binary = backend.node_binary(version);
+    if let Some(path) = &binary {
+        let metadata = tokio::fs::metadata(path)
+            .await
+            .map_err(|_| format!("node binary is missing at {}", path.display()))?;
+        if !metadata.is_file() || metadata.len() == 0 {
+            return Err(format!("node binary at {} is empty", path.display()));
+        }
+    }
+
+    let run = async {
+        match backend.exec(version, &["node", "--version"]).await {
+            Err(BackendError::Unsupported(_)) => match &binary {
+                Some(path) => run_directly(path).await,
+                None => Err("Verification is not supported for this backend".to_string()),
+            },
+            result => result.map_err(|e| e.to_string()),
+        }
+    };
+    let output = tokio::time::timeout(timeout, run)
+        .await
+        .map_err(|_| "node --version timed out".to_string())?
+        .map_err(|e| format!("node failed to run: {}", e))?;
+
+    if reports_version(&output, version) {
+        Ok(())
+    } else {
+        Err(format!("node reports {} instead", output.trim()))
+    }
+}
+
+async fn run_directly(path: &std::path::Path) -> Result<String, String> {
+    use versi_platform::HideWindow;
+
+    let output = tokio::process::Command::new(path)
+        .arg("--version")
+        .hide_window()
+        .output()
+        .await
+        .map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+fn reports_version(output: &str, version: &str) -> bool {
+    let normalize = |v: &str| v.trim().trim_start_matches('v').to_string();
+    output
+        .lines()
+        .map(normalize)
+        .any(|line| line == normalize(version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_reported_version() {
+        assert!(reports_version("v22.11.0\n", "v22.11.0"));
+        assert!(reports_version("v20.18.1", "20.18.1"));
+        assert!(reports_version("Using Node v18.0.0\nv18.0.0\n", "v18.0.0"));
+        assert!(!reports_version("v22.1.0", "v22.11.0"));
+        assert!(!reports_version("", "v22.11.0"));
+    }
+}
@@ -0,0 +1,336 @@
+use std::sync::{Arc, Mutex};
+
+use iced::futures::channel::mpsc;
+use iced::futures::{SinkExt, Stream};
+use log::{info, warn};
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::oneshot;
+
+use crate::message::Message;
+
+/// Named pipe the control server listens on, one per user.
+#[cfg(windows)]
+pub fn pipe_name() -> std::io::Result<String> {
+    crate::single_instance::current_user_sid().map(|sid| format!(r"\\.\pipe\versi-{}", sid))
+}
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// Server-defined code for requests the app refused or could not handle.
+const APP_ERROR: i64 = -32000;
+
+type Reply = oneshot::Sender<Result<Value, String>>;
+
+/// Commands accepted over the control socket.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IpcCommand {
+    GetStatus,
+    ListInstalled,
+    Install {
+        version: String,
+    },
+    SetDefault {
+        version: String,
+    },
+    /// Follows the project in `path`, e.g. from a shell hook run on `cd`.
+    FollowDirectory {
+        path: String,
+    },
+}
+
+impl IpcCommand {
+    pub fn is_mutating(&self) -> bool {
+        matches!(self, Self::Install { .. } | Self::SetDefault { .. })
+    }
+}
+
+/// A command waiting for the app to answer it.
+#[derive(Debug, Clone)]
+pub struct IpcRequest {
+    pub command: IpcCommand,
+    reply: Arc<Mutex<Option<Reply>>>,
+}
+
+impl IpcRequest {
+    fn new(command: IpcCommand) -> (Self, oneshot::Receiver<Result<Value, String>>) {
+        let (sender, receiver) = oneshot::channel();
+        let request = Self {
+            command,
+            reply: Arc::new(Mutex::new(Some(sender))),
+        };
+        (request, receiver)
+    }
+
+    /// Sends the answer back to the client. Only the first call has an effect.
+    pub fn respond(&self, result: Result<Value, String>) {
+        if let Some(reply) = self.reply.lock().ok().and_then(|mut reply| reply.take()) {
+            let _ = reply.send(result);
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// Parses one line of JSON-RPC 2.0. Errors carry the request id, or `null`
+/// when it could not be read.
+fn parse_request(line: &str) -> Result<(Value, IpcCommand), (Value, RpcError)> {
+    let request: Value = serde_json::from_str(line)
+        .map_err(|e| (Value::Null, RpcError::new(PARSE_ERROR, e.to_string())))?;
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let Some(method) = request.get("method").and_then(Value::as_str) else {
+        return Err((id, RpcError::new(INVALID_REQUEST, "Missing method")));
+    };
+
+    let version = || {
+        request
+            .get("params")
+            .and_then(|params| params.get("version"))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Missing params.version"))
+    };
+    let command = match method {
+        "get-status" => Ok(IpcCommand::GetStatus),
+        "list-installed" => Ok(IpcCommand::ListInstalled),
+        "install" => version().map(|version| IpcCommand::Install { version }),
+        "set-default" => version().map(|version| IpcCommand::SetDefault { version }),
+        "follow-directory" => request
+            .get("params")
+            .and_then(|params| params.get("path"))
+            .and_then(Value::as_str)
+            .map(|path| IpcCommand::FollowDirectory {
+                path: path.to_string(),
+            })
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Missing params.path")),
+        _ => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("Unknown method: {}", method),
+        )),
+    };
+    match command {
+        Ok(command) => Ok((id, command)),
+        Err(error) => Err((id, error)),
+    }
+}
+
+fn format_response(id: Value, result: Result<Value, RpcError>) -> String {
+    let response = match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": error.code, "message": error.message },
+        }),
+    };
+    response.to_string()
+}
+
+/// Serves the control socket and turns each request into
+/// [`Message::IpcRequest`]. Only the instance holding the
+/// [`SingleInstance`](crate::single_instance::SingleInstance) guard serves.
+pub fn requests() -> impl Stream<Item = Message> {
+    iced::stream::channel(16, async |output: mpsc::Sender<Message>| {
+        if !crate::single_instance::is_primary() {
+            warn!("IPC server not started: this is not the running instance");
+            return;
+        }
+        if let Err(e) = serve(output).await {
+            warn!("IPC server stopped: {}", e);
+        }
+    })
+}
+
+#[cfg(unix)]
+async fn serve(output: mpsc::Sender<Message>) -> std::io::Result<()> {
+    use tokio::net::UnixListener;
+
+    let paths = versi_platform::AppPaths::new().map_err(std::io::Error::other)?;
+    // The socket is created inside a directory only this user can enter,
+    // so it is never reachable by others, even before it is bound.
+    paths.ensure_instance_dir()?;
+    let path = paths.ipc_socket();
+    // This process holds the instance lock, so a socket already there was
+    // left behind by an instance that did not shut down cleanly.
+    let _ = std::fs::remove_file(&path);
+
+    let listener = UnixListener::bind(&path)?;
+    info!("IPC server listening on {}", path.display());
+    loop {
+        let (stream, _) = listener.accept().await?;
+        tokio::spawn(handle_connection(stream, output.clone()));
+    }
+}
+
+#[cfg(windows)]
+async fn serve(output: mpsc::Sender<Message>) -> std::io::Result<()> {
+    let sid = crate::single_instance::current_user_sid()?;
+    let name = pipe_name()?;
+    // Owning the first instance keeps another process from squatting on the
+    // name before Versi starts.
+    let mut server = create_pipe(&name, &sid, true)?;
+    info!("IPC server listening on {}", name);
+    loop {
+        server.connect().await?;
+        let connected = std::mem::replace(&mut server, create_pipe(&name, &sid, false)?);
+        tokio::spawn(handle_connection(connected, output.clone()));
+    }
+}
+
+/// A pipe instance whose DACL lets in only the user `sid` and refuses
+/// remote clients.
+#[cfg(windows)]
+fn create_pipe(
+    name: &str,
+    sid: &str,
+    first: bool,
+) -> std::io::Result<tokio::net::windows::named_pipe::NamedPipeServer> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+    use windows_sys::Win32::Foundation::LocalFree;
+    use windows_sys::Win32::Security::Authorization::{
+        ConvertStringSecurityDescriptorToSecurityDescriptorW, SDDL_REVISION_1,
+    };
+    use windows_sys::Win32::Security::SECURITY_ATTRIBUTES;
+
+    let sddl: Vec<u16> = format!("D:P(A;;GA;;;{})", sid)
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+    let mut descriptor = std::ptr::null_mut();
+    // SAFETY: `sddl` is NUL-terminated and `descriptor` is freed below.
+    if unsafe {
+        ConvertStringSecurityDescriptorToSecurityDescriptorW(
+            sddl.as_ptr(),
+            SDDL_REVISION_1,
+            &mut descriptor,
+            std::ptr::null_mut(),
+        )
+    } == 0
+    {
+        return Err(std::io::Error::last_os_error());
+    }
+    let mut attributes = SECURITY_ATTRIBUTES {
+        nLength: std::mem::size_of::<SECURITY_ATTRIBUTES>() as u32,
+        lpSecurityDescriptor: descriptor,
+        bInheritHandle: 0,
+    };
+    // SAFETY: `attributes` and the descriptor it points to outlive the call.
+    let server = unsafe {
+        ServerOptions::new()
+            .first_pipe_instance(first)
+            .reject_remote_clients(true)
+            .create_with_security_attributes_raw(name, (&raw mut attributes).cast())
+    };
+    unsafe { LocalFree(descriptor) };
+    server
+}
+
+/// Answers newline-delimited requests on one connection until it closes.
+async fn handle_connection<S>(stream: S, mut output: mpsc::Sender<Message>)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match parse_request(&line) {
+            Ok((id, command)) => {
+                let (request, reply) = IpcRequest::new(command);
+                let result = if output.send(Message::IpcRequest(request)).await.is_err() {
+                    Err("Versi is shutting down".to_string())
+                } else {
+                    reply
+                        .await
+                        .unwrap_or_else(|_| Err("Request was dropped".to_string()))
+                };
+                format_response(id, result.map_err(|e| RpcError::new(APP_ERROR, e)))
+            }
+            Err((id, error)) => format_response(id, Err(error)),
+        };
+        if writer.write_all(response.as_bytes()).await.is_err()
+            || writer.write_all(b"\n").await.is_err()
+        {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_known_methods() {
+        assert_eq!(
+            parse_request(r#"{"jsonrpc":"2.0","id":1,"method":"get-status"}"#),
+            Ok((json!(1), IpcCommand::GetStatus))
+        );
+        assert_eq!(
+            parse_request(
+                r#"{"jsonrpc":"2.0","id":"a","method":"install","params":{"version":"22"}}"#
+            ),
+            Ok((
+                json!("a"),
+                IpcCommand::Install {
+                    version: "22".to_string()
+                }
+            ))
+        );
+        assert_eq!(
+            parse_request(
+                r#"{"jsonrpc":"2.0","id":2,"method":"follow-directory","params":{"path":"/work/app"}}"#
+            ),
+            Ok((
+                json!(2),
+                IpcCommand::FollowDirectory {
+                    path: "/work/app".to_string()
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn reports_protocol_errors() {
+        let (id, error) = parse_request("not json").unwrap_err();
+        assert_eq!((id, error.code), (Value::Null, PARSE_ERROR));
+
+        let (id, error) = parse_request(r#"{"id":2,"method":"set-default"}"#).unwrap_err();
+        assert_eq!((id, error.code), (json!(2), INVALID_PARAMS));
+
+        let (_, error) = parse_request(r#"{"id":3,"method":"uninstall-everything"}"#).unwrap_err();
+        assert_eq!(error.code, METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn formats_results_and_errors() {
+        let ok: Value =
+            serde_json::from_str(&format_response(json!(1), Ok(json!({"queued": true})))).unwrap();
+        assert_eq!(ok["result"]["queued"], json!(true));
+
+        let err: Value = serde_json::from_str(&format_response(
+            json!(1),
+            Err(RpcError::new(APP_ERROR, "read-only")),
+        ))
+        .unwrap();
+        assert_eq!(err["error"]["code"], json!(APP_ERROR));
+        assert_eq!(err["error"]["message"], json!("read-only"));
+    }
+}
@@ -0,0 +1,363 @@
+use std::collections::{BTreeSet, VecDeque};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use iced::futures::{SinkExt, Stream, channel::mpsc};
+use log::Level;
+
+use crate::message::Message;
+
+/// How often the log file is checked for new lines while the viewer is open.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How much of the end of the log is loaded when the viewer opens.
+const INITIAL_BYTES: u64 = 256 * 1024;
+
+/// Entries kept in the viewer; older ones are dropped as new lines arrive.
+const MAX_ENTRIES: usize = 5000;
+
+/// New lines read from the log file.
+#[derive(Debug, Clone)]
+pub enum TailEvent {
+    /// The file was opened, replaced or truncated; earlier lines are gone.
+    Reset(Vec<String>),
+    Appended(Vec<String>),
+    Failed(String),
+}
+
+/// Follows the log file, reporting what was written since the last read.
+pub fn tail() -> impl Stream<Item = Message> {
+    iced::stream::channel(16, async |mut output: mpsc::Sender<Message>| {
+        let mut tail = Tail::default();
+        let mut failed = false;
+        loop {
+            let event = match versi_platform::AppPaths::new() {
+                Ok(paths) => tail.read(&paths.log_file()),
+                Err(e) => Err(std::io::Error::other(e)),
+            };
+            let event = match event {
+                Ok(event) => {
+                    failed = false;
+                    event
+                }
+                // Reported once, then retried quietly until the file is back.
+                Err(_) if failed => None,
+                Err(e) => {
+                    failed = true;
+                    tail = Tail::default();
+                    Some(TailEvent::Failed(e.to_string()))
+                }
+            };
+            if let Some(event) = event
+                && output.send(Message::LogTail(event)).await.is_err()
+            {
+                return;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    })
+}
+
+/// Read position in the log file.
+#[derive(Debug, Default)]
+struct Tail {
+    path: Option<PathBuf>,
+    offset: u64,
+    /// The last line read while it was still being written.
+    partial: String,
+}
+
+impl Tail {
+    fn read(&mut self, path: &Path) -> std::io::Result<Option<TailEvent>> {
+        let mut file = std::fs::File::open(path)?;
+        let len = file.metadata()?.len();
+
+        let reset = self.path.as_deref() != Some(path) || len < self.offset;
+        if reset {
+            self.path = Some(path.to_path_buf());
+            self.offset = len.saturating_sub(INITIAL_BYTES);
+            self.partial.clear();
+        } else if len == self.offset {
+            return Ok(None);
+        }
+
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut bytes = Vec::new();
+        file.take(len - self.offset).read_to_end(&mut bytes)?;
+        let mut text = String::from_utf8_lossy(&bytes).into_owned();
+        if reset && self.offset > 0 {
+            // Starting mid-file, so the first line is cut off.
+            text = text
+                .split_once('\n')
+                .map(|(_, rest)| rest.to_string())
+                .unwrap_or_default();
+        }
+        self.offset = len;
+
+        let text = std::mem::take(&mut self.partial) + &text;
+        let mut lines: Vec<String> = text.split('\n').map(str::to_string).collect();
+        self.partial = lines.pop().unwrap_or_default();
+        lines.retain(|line| !line.trim().is_empty());
+
+        Ok(if reset {
+            Some(TailEvent::Reset(lines))
+        } else if lines.is_empty() {
+            None
+        } else {
+            Some(TailEvent::Appended(lines))
+        })
+    }
+}
+
+/// One log record; messages spanning several lines keep them together.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    pub id: u64,
+    pub level: Option<Level>,
+    pub target: Option<String>,
+    pub text: String,
+}
+
+/// Level and module of a line written by the file logger, as in
+/// `2025-01-01T10:00:00Z [INFO] (1) versi::app: message`. `None` for lines
+/// that continue the previous message.
+fn parse_header(line: &str) -> Option<(Level, Option<String>)> {
+    let mut words = line.split(' ').filter(|word| !word.is_empty());
+    words.next()?;
+    let level = words
+        .next()?
+        .strip_prefix('[')?
+        .strip_suffix(']')?
+        .trim()
+        .parse()
+        .ok()?;
+
+    let mut next = words.next();
+    if next.is_some_and(|word| word.starts_with('(') && word.ends_with(')')) {
+        next = words.next();
+    }
+    let target = next
+        .and_then(|word| word.strip_suffix(':'))
+        .filter(|target| target.starts_with("versi"))
+        .map(str::to_string);
+    Some((level, target))
+}
+
+/// The viewer's copy of the log and how it is filtered.
+#[derive(Debug, Clone, Default)]
+pub struct LogViewer {
+    entries: VecDeque<LogEntry>,
+    next_id: u64,
+    pub error: Option<String>,
+    /// Most verbose level shown.
+    pub level: Option<Level>,
+    /// Module shown along with its submodules; `None` shows every module.
+    pub module: Option<String>,
+    pub query: String,
+    /// First and last entry of the selected range, in click order.
+    pub selection: Option<(u64, u64)>,
+    /// Entries after this one are held back while the viewer is paused.
+    pub paused_at: Option<u64>,
+}
+
+impl LogViewer {
+    pub fn apply(&mut self, event: TailEvent) {
+        match event {
+            TailEvent::Reset(lines) => {
+                self.entries.clear();
+                self.selection = None;
+                self.error = None;
+                self.push_lines(lines);
+            }
+            TailEvent::Appended(lines) => {
+                self.error = None;
+                self.push_lines(lines);
+            }
+            TailEvent::Failed(e) => self.error = Some(e),
+        }
+    }
+
+    fn push_lines(&mut self, lines: Vec<String>) {
+        for line in lines {
+            match parse_header(&line) {
+                Some((level, target)) => {
+                    self.entries.push_back(LogEntry {
+                        id: self.next_id,
+                        level: Some(level),
+                        target,
+                        text: line,
+                    });
+                    self.next_id += 1;
+                }
+                None => match self.entries.back_mut() {
+                    Some(last) => {
+                        last.text.push('\n');
+                        last.text.push_str(&line);
+                    }
+                    None => {
+                        self.entries.push_back(LogEntry {
+                            id: self.next_id,
+                            level: None,
+                            target: None,
+                            text: line,
+                        });
+                        self.next_id += 1;
+                    }
+                },
+            }
+        }
+        while self.entries.len() > MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Modules that wrote the entries, for the module filter.
+    pub fn modules(&self) -> Vec<String> {
+        self.entries
+            .iter()
+            .filter_map(|entry| entry.target.clone())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Entries passing the level, module and search filters.
+    pub fn visible(&self) -> impl Iterator<Item = &LogEntry> {
+        let query = self.query.trim().to_lowercase();
+        self.entries.iter().filter(move |entry| {
+            self.paused_at.is_none_or(|id| entry.id <= id)
+                && self
+                    .level
+                    .is_none_or(|level| entry.level.is_none_or(|l| l <= level))
+                && self.module.as_ref().is_none_or(|module| {
+                    entry.target.as_ref().is_some_and(|target| {
+                        target == module || target.starts_with(&format!("{}::", module))
+                    })
+                })
+                && (query.is_empty() || entry.text.to_lowercase().contains(&query))
+        })
+    }
+
+    pub fn is_selected(&self, id: u64) -> bool {
+        self.selection
+            .is_some_and(|(a, b)| (a.min(b)..=a.max(b)).contains(&id))
+    }
+
+    /// Clicking a line starts a selection, clicking another extends it to
+    /// there, and a third click starts over.
+    pub fn click(&mut self, id: u64) {
+        self.selection = match self.selection {
+            Some((start, end)) if start == end && start != id => Some((start, id)),
+            _ => Some((id, id)),
+        };
+    }
+
+    /// Text of the selected visible entries, or of every visible entry when
+    /// nothing is selected.
+    pub fn copy_text(&self) -> String {
+        self.visible()
+            .filter(|entry| self.selection.is_none() || self.is_selected(entry.id))
+            .map(|entry| entry.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn toggle_paused(&mut self) {
+        self.paused_at = match self.paused_at {
+            Some(_) => None,
+            None => Some(self.next_id.saturating_sub(1)),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn parses_file_logger_lines() {
+        assert_eq!(
+            parse_header("2025-01-01T10:00:00Z [INFO] versi::app::init: Versi starting"),
+            Some((Level::Info, Some("versi::app::init".to_string())))
+        );
+        assert_eq!(
+            parse_header("2025-01-01T10:00:00Z [DEBUG] (3) versi::tray: menu rebuilt"),
+            Some((Level::Debug, Some("versi::tray".to_string())))
+        );
+        assert_eq!(
+            parse_header("2025-01-01T10:00:00Z [WARN] Retrying: timed out"),
+            Some((Level::Warn, None))
+        );
+        assert_eq!(parse_header("    at line 3"), None);
+    }
+
+    #[test]
+    fn filters_and_copies_selected_range() {
+        let mut viewer = LogViewer::default();
+        viewer.apply(TailEvent::Reset(vec![
+            "t [INFO] versi::app: started".to_string(),
+            "t [DEBUG] versi::app::init: detected fnm".to_string(),
+            "t [ERROR] versi::tray: failed".to_string(),
+            "  caused by: no display".to_string(),
+        ]));
+
+        assert_eq!(viewer.total(), 3);
+        assert_eq!(
+            viewer.modules(),
+            vec!["versi::app", "versi::app::init", "versi::tray"]
+        );
+
+        viewer.level = Some(Level::Info);
+        assert_eq!(viewer.visible().count(), 2);
+        viewer.level = None;
+        viewer.module = Some("versi::app".to_string());
+        assert_eq!(viewer.visible().count(), 2);
+        viewer.module = None;
+        viewer.query = "DISPLAY".to_string();
+        assert_eq!(viewer.visible().count(), 1);
+        viewer.query.clear();
+
+        viewer.click(2);
+        viewer.click(1);
+        assert_eq!(
+            viewer.copy_text(),
+            "t [DEBUG] versi::app::init: detected fnm\nt [ERROR] versi::tray: failed\n  caused by: no display"
+        );
+    }
+
+    #[test]
+    fn tails_appends_and_truncation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("debug.log");
+        std::fs::write(&path, "one\ntw").unwrap();
+        let mut tail = Tail::default();
+
+        assert!(matches!(
+            tail.read(&path).unwrap(),
+            Some(TailEvent::Reset(lines)) if lines == ["one"]
+        ));
+        assert!(tail.read(&path).unwrap().is_none());
+
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        file.write_all(b"o\nthree\n").unwrap();
+        assert!(matches!(
+            tail.read(&path).unwrap(),
+            Some(TailEvent::Appended(lines)) if lines == ["two", "three"]
+        ));
+
+        std::fs::write(&path, "four\n").unwrap();
+        assert!(matches!(
+            tail.read(&path).unwrap(),
+            Some(TailEvent::Reset(lines)) if lines == ["four"]
+        ));
+    }
+}
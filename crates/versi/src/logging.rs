@@ -3,35 +3,36 @@ use simplelog::{ColorChoice, TermLogger, TerminalMode};
 use simplelog::{CombinedLogger, ConfigBuilder, LevelFilter, WriteLogger};
 use std::fs::{File, OpenOptions};
 use std::io::{self, Write};
-use std::path::PathBuf;
-use std::sync::Mutex;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
 use versi_platform::AppPaths;
 
 struct ResilientFileWriter {
-    path: PathBuf,
+    path: Mutex<PathBuf>,
     file: Mutex<Option<File>>,
 }
 
+/// The writer the file logger uses, kept so the log can be moved.
+static WRITER: OnceLock<Arc<ResilientFileWriter>> = OnceLock::new();
+
 impl ResilientFileWriter {
     fn new(path: PathBuf) -> io::Result<Self> {
         let file = OpenOptions::new().create(true).append(true).open(&path)?;
         Ok(Self {
-            path,
+            path: Mutex::new(path),
             file: Mutex::new(Some(file)),
         })
     }
 
     fn ensure_file(&self) -> io::Result<()> {
         let mut guard = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        let path = self.path.lock().unwrap_or_else(|e| e.into_inner());
 
-        if !self.path.exists() {
-            if let Some(parent) = self.path.parent() {
+        if guard.is_none() || !path.exists() {
+            if let Some(parent) = path.parent() {
                 std::fs::create_dir_all(parent)?;
             }
-            let file = OpenOptions::new()
-                .create(true)
-                .append(true)
-                .open(&self.path)?;
+            let file = OpenOptions::new().create(true).append(true).open(&*path)?;
             *guard = Some(file);
         }
 
@@ -39,7 +40,20 @@ impl ResilientFileWriter {
     }
 }
 
-impl Write for ResilientFileWriter {
+/// Shares one [`ResilientFileWriter`] between the logger and [`move_log_file`].
+struct SharedWriter(Arc<ResilientFileWriter>);
+
+impl Write for SharedWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (&*self.0).write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        (&*self.0).flush()
+    }
+}
+
+impl Write for &ResilientFileWriter {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         self.ensure_file()?;
         let mut guard = self.file.lock().unwrap_or_else(|e| e.into_inner());
@@ -82,12 +96,17 @@ pub fn init_logging(debug_enabled: bool, max_log_size: u64) {
 
     let config = ConfigBuilder::new()
         .set_time_format_rfc3339()
+        // The log viewer filters by module, so every line names one.
+        .set_target_level(LevelFilter::Error)
         .add_filter_allow_str("versi")
         .build();
 
     let file_logger = ResilientFileWriter::new(log_path.clone())
         .ok()
-        .map(|writer| WriteLogger::new(LevelFilter::Debug, config.clone(), writer));
+        .map(|writer| {
+            let writer = WRITER.get_or_init(|| Arc::new(writer)).clone();
+            WriteLogger::new(LevelFilter::Debug, config.clone(), SharedWriter(writer))
+        });
 
     #[cfg(debug_assertions)]
     {
@@ -119,6 +138,25 @@ pub fn init_logging(debug_enabled: bool, max_log_size: u64) {
     }
 }
 
+/// Moves the log file from `from` to `to` and keeps logging there.
+pub fn move_log_file(from: &Path, to: &Path) -> io::Result<()> {
+    // Holding the writer's locks keeps anything from being logged mid-move.
+    let writer = WRITER.get();
+    let mut file = writer.map(|w| w.file.lock().unwrap_or_else(|e| e.into_inner()));
+    let mut current = writer.map(|w| w.path.lock().unwrap_or_else(|e| e.into_inner()));
+
+    if let Some(file) = &mut file {
+        file.take();
+    }
+    if from != to && from.exists() {
+        versi_platform::move_file(from, to)?;
+    }
+    if let Some(current) = &mut current {
+        **current = to.to_path_buf();
+    }
+    Ok(())
+}
+
 pub fn set_logging_enabled(enabled: bool) {
     if enabled {
         log::set_max_level(log::LevelFilter::Debug);
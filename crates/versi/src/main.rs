@@ -2,20 +2,59 @@
 
 use iced::window;
 
+mod aliases;
 mod app;
+mod backend_removal;
+mod banner_state;
 mod cache;
+mod cli;
+mod eol;
+mod event_log;
 mod icon;
+mod integrations;
+mod integrity;
+mod ipc;
+mod log_viewer;
 mod logging;
 mod message;
+mod metrics;
+mod npm_doctor;
+mod operation_log;
+mod project_watch;
+mod proxy;
+mod recovery;
+mod renderer;
+mod report;
+mod safe_mode;
+mod scheduler;
 mod settings;
+mod settings_journal;
+mod settings_sync;
+mod settings_watch;
 mod single_instance;
+mod sleep_inhibit;
+mod snapshots;
+mod snippets;
 mod state;
+mod status_file;
+mod support_bundle;
+mod test_matrix;
 mod theme;
+mod tips;
+mod toolchain;
+mod transfer;
 mod tray;
+mod update_deferral;
 mod views;
 mod widgets;
 
 fn main() -> iced::Result {
+    if let Some(command) = cli::CliCommand::parse(std::env::args().skip(1)) {
+        let settings = settings::AppSettings::load();
+        versi_platform::AppPaths::set_custom_dirs(settings.custom_dirs());
+        std::process::exit(cli::run(command, &settings));
+    }
+
     let _instance_guard = match single_instance::SingleInstance::acquire() {
         Ok(guard) => guard,
         Err(_) => {
@@ -31,10 +70,40 @@ fn main() -> iced::Result {
         std::process::exit(1);
     }
 
-    let settings = settings::AppSettings::load();
+    let mut settings = settings::AppSettings::load();
+    versi_platform::AppPaths::set_custom_dirs(settings.custom_dirs());
+    let safe_mode = safe_mode::detect();
+    if safe_mode.is_some() {
+        settings = settings::AppSettings::default();
+        versi_platform::AppPaths::set_custom_dirs(settings.custom_dirs());
+    }
+    let renderer = renderer::select(settings.renderer);
+    renderer::apply(renderer);
+
     logging::init_logging(settings.debug_logging, settings.max_log_size_bytes);
 
     log::info!("Versi {} starting", env!("CARGO_PKG_VERSION"));
+    log::info!("Renderer: {}", renderer.describe());
+    if let Some(safe_mode) = safe_mode {
+        if let Some(requested) = safe_mode.requested {
+            log::warn!("Starting in safe mode ({})", requested);
+        }
+        for file in &safe_mode.corrupt {
+            log::warn!(
+                "Starting in safe mode: {} at {} could not be read: {}",
+                file.name,
+                file.path.display(),
+                file.error
+            );
+        }
+    }
+    if renderer.reason == renderer::SelectionReason::PreviousLaunchFailed {
+        log::warn!("Previous launch did not finish rendering, switching to software rendering");
+        settings.renderer = settings::RendererSetting::Software;
+        if let Err(e) = settings.save() {
+            log::error!("Failed to save settings: {e}");
+        }
+    }
 
     #[cfg(target_os = "linux")]
     {
@@ -49,12 +118,23 @@ fn main() -> iced::Result {
 
     let icon = window::icon::from_file_data(include_bytes!("../../../assets/logo.png"), None).ok();
 
-    let (window_size, window_position) = match &settings.window_geometry {
-        Some(geo) if geo.is_likely_visible() => (
+    let monitors = versi_platform::available_monitors();
+    let restored_geometry = settings
+        .window_geometry
+        .as_ref()
+        .and_then(|geo| geo.restore(&monitors));
+
+    let (window_size, window_position, maximized) = match &restored_geometry {
+        Some(geo) => (
             iced::Size::new(geo.width, geo.height),
             window::Position::Specific(iced::Point::new(geo.x as f32, geo.y as f32)),
+            geo.maximized,
+        ),
+        None => (
+            iced::Size::new(800.0, 600.0),
+            window::Position::Default,
+            false,
         ),
-        _ => (iced::Size::new(800.0, 600.0), window::Position::Default),
     };
 
     #[cfg(target_os = "linux")]
@@ -69,12 +149,20 @@ fn main() -> iced::Result {
         .title(|state: &app::Versi| state.title())
         .subscription(|state: &app::Versi| state.subscription())
         .theme(|state: &app::Versi| state.theme())
+        .style(|state: &app::Versi, theme| state.style(theme))
         .window(window::Settings {
             size: window_size,
             position: window_position,
+            maximized,
+            level: if settings.always_on_top {
+                window::Level::AlwaysOnTop
+            } else {
+                window::Level::Normal
+            },
             min_size: Some(iced::Size::new(600.0, 400.0)),
             icon,
             visible: true,
+            transparent: settings.window_vibrancy,
             exit_on_close_request: false,
             platform_specific,
             ..Default::default()
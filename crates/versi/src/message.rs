@@ -1,11 +1,27 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::Duration;
 
-use versi_backend::{BackendUpdate, InstalledVersion, RemoteVersion};
-use versi_core::{AppUpdate, ReleaseSchedule};
-use versi_platform::EnvironmentId;
+use versi_backend::{
+    BackendUpdate, BackendUpdateChannel, InstallProgress, InstalledVersion, NodeVersion,
+    RemoteVersion,
+};
+use versi_core::{AppUpdate, ReleaseIndex, ReleaseSchedule};
+use versi_platform::{BinaryArch, EnvironmentId, PlatformTarget};
 use versi_shell::ShellType;
 
-use crate::settings::TrayBehavior;
+use crate::banner_state::{BannerId, BannerSnooze};
+use crate::eol::SupportPhase;
+use crate::integrations::jetbrains::InterpreterChange;
+use crate::project_watch::ProjectPin;
+use crate::report::ReportFormat;
+use crate::scheduler::{ScheduledTask, SystemConditions};
+use crate::settings::{CloseBehavior, DataDir, RendererSetting, StartupView, TrayBehavior};
+use crate::snippets::SnippetTarget;
+use crate::state::{
+    EnvironmentError, ExistingSetup, InstallMethod, OperationFailure, RemoteSource,
+};
+use crate::toolchain::ProjectToolchain;
 use crate::tray::TrayMessage;
 
 #[derive(Debug, Clone)]
@@ -18,9 +34,28 @@ pub enum Message {
     SelectPreviousEnvironment,
     EnvironmentLoaded {
         env_id: EnvironmentId,
-        versions: Vec<InstalledVersion>,
+        result: Result<Vec<InstalledVersion>, String>,
     },
+    ArchitecturesDetected {
+        env_id: EnvironmentId,
+        host: Option<BinaryArch>,
+        architectures: HashMap<String, BinaryArch>,
+    },
+    ActiveVersionDetected {
+        env_id: EnvironmentId,
+        version: Option<NodeVersion>,
+    },
+    /// A WSL distro, Git Bash or MSYS2 found after startup.
+    #[cfg(windows)]
+    EnvironmentDetected(EnvironmentInfo),
+    #[cfg(windows)]
+    StartWslDistro(String),
+    #[cfg(windows)]
+    DetectWslEnvironments,
+    #[cfg(windows)]
+    DetectMsysEnvironments,
     RefreshEnvironment,
+    RetryEnvironmentLoad(EnvironmentId),
     FocusSearch,
     SelectPreviousVersion,
     SelectNextVersion,
@@ -30,23 +65,100 @@ pub enum Message {
         major: u32,
     },
     SearchChanged(String),
+    SearchSubmitted,
+    SearchFilterPinned(String),
+    SearchFilterUnpinned(String),
+    SearchHistoryCleared,
+    SearchAllEnvironmentsToggled,
+    ShowInEnvironment {
+        env_idx: usize,
+        version: String,
+    },
+    UninstallFromEnvironment {
+        env_idx: usize,
+        version: String,
+    },
 
     FetchRemoteVersions,
-    RemoteVersionsFetched(Result<Vec<RemoteVersion>, String>),
+    RemoteVersionsFetched {
+        source: RemoteSource,
+        result: Result<Vec<RemoteVersion>, String>,
+    },
     ReleaseScheduleFetched(Result<ReleaseSchedule, String>),
+    ReleaseIndexFetched {
+        mirror: Option<String>,
+        result: Result<ReleaseIndex, String>,
+    },
 
     CloseModal,
     OpenChangelog(String),
+    ShowSetupSnippet(String),
+    SetupSnippetTargetSelected(SnippetTarget),
+    ShowCiMatrix,
+    CiMatrixMajorToggled(u32),
+    CiMatrixEnginesToggled(bool),
+    ShowTestMatrix,
+    PickTestMatrixProject,
+    TestMatrixProjectPicked(Option<PathBuf>),
+    TestMatrixToolchainRead(PathBuf, ProjectToolchain),
+    TestMatrixCommandChanged(String),
+    RunTestMatrix,
+    ProjectTrustDecided {
+        project: PathBuf,
+        trusted: bool,
+    },
+    ForgetProjectTrust(PathBuf),
+    TestMatrixRunFinished {
+        version: String,
+        result: Result<(), String>,
+        duration: Duration,
+    },
+    EditMajorNote(u32),
+    MajorNoteDraftChanged(String),
+    SaveMajorNote,
+    RemoveMajorNote(u32),
     StartInstall(String),
+    PreviewInstall(String),
+    InstallCandidatePicked(String),
+    ConfirmInstallWithoutBinary(String),
+    InstallFromUnofficialBuilds(String),
+    InstallProgress {
+        version: String,
+        progress: InstallProgress,
+    },
     InstallComplete {
+        env_id: EnvironmentId,
         version: String,
         success: bool,
         error: Option<String>,
     },
 
     RequestUninstall(String),
+    VerifyVersion(String),
+    VersionVerified {
+        env_id: EnvironmentId,
+        version: String,
+        result: Result<(), String>,
+    },
+    ReinstallVersion(String),
+    ReinstallGlobalsToggled(bool),
+    ConfirmReinstall,
+    GlobalPackagesCaptured {
+        version: String,
+        result: Result<Vec<String>, String>,
+    },
+    GlobalPackagesRestored {
+        version: String,
+        count: usize,
+        result: Result<(), String>,
+    },
     ConfirmUninstallDefault(String),
+    ConfirmUninstallProtected(String),
+    ToggleVersionProtected(String),
+    DismissMissingDefault,
+    DismissSlowOperation,
     UninstallComplete {
+        env_id: EnvironmentId,
         version: String,
         success: bool,
         error: Option<String>,
@@ -69,65 +181,251 @@ pub enum Message {
         major: u32,
     },
     CancelBulkOperation,
+    VersionSelectionToggled(String),
+    ClearVersionSelection,
+    RequestBulkUninstallSelected,
+    ConfirmBulkUninstallSelected,
+    RollBackEnvironment,
+    ShowOperationFailure(OperationFailure),
+    ExportOperationLog,
+    OperationLogExported(Result<PathBuf, String>),
+    CompareSelectedVersions,
+    CopySelectedVersions,
 
     SetDefault(String),
+    ConfirmSetDefaultForeignArch(String),
     DefaultChanged {
+        env_id: EnvironmentId,
+        version: String,
         success: bool,
         error: Option<String>,
     },
 
     ToastDismiss(usize),
+    ShowBatchSummary(usize),
 
     NavigateToVersions,
     NavigateToSettings,
     NavigateToAbout,
     VersionRowHovered(Option<String>),
+    VersionRowClicked(String),
+    ModifiersChanged(iced::keyboard::Modifiers),
     ThemeChanged(crate::settings::ThemeSetting),
+    ThemeScheduleChanged(crate::settings::ThemeSchedule),
+    ThemeScheduleTick,
+    RendererChanged(RendererSetting),
+    StartupViewChanged(StartupView),
+    StartupEnvironmentChanged(String),
+    ScheduleDraftTaskChanged(ScheduledTask),
+    ScheduleDraftTimeChanged(String),
+    ScheduleDraftWhenIdleToggled(bool),
+    AddScheduledJob,
+    RemoveScheduledJob(u64),
+    EolStrictToggled(bool),
+    EolGraceDaysChanged(u32),
+    EolCountdownDaysChanged(u32),
+    SnoozeSupportWarnings(SupportPhase),
+    DismissSupportWarnings(SupportPhase),
+    RestoreSupportWarning(u32),
+    SuppressBanner(BannerId, BannerSnooze),
+    RestoreBanner(BannerId),
+    NotificationsToggled,
+    SettingsFileCheck,
+    PickSyncFile,
+    SyncFilePicked(Option<std::path::PathBuf>),
+    ClearSyncFile,
+    SchedulerTick,
+    SchedulerConditionsChecked(SystemConditions),
+    VersionsLayoutChanged(crate::settings::VersionsLayout),
+    ListDensityChanged(crate::settings::ListDensity),
+    NotificationDeliveryChanged(
+        crate::settings::NotificationCategory,
+        crate::settings::NotificationDelivery,
+    ),
+    VersionGroupingChanged(crate::settings::VersionGrouping),
     ShellOptionUseOnCdToggled(bool),
     ShellOptionResolveEnginesToggled(bool),
     ShellOptionCorepackEnabledToggled(bool),
     DebugLoggingToggled(bool),
+    EnvVarAdded,
+    EnvVarKeyChanged(usize, String),
+    EnvVarValueChanged(usize, String),
+    EnvVarRemoved(usize),
+    EnvVarsSubmitted,
+    PickCaBundle,
+    CaBundlePicked(Option<std::path::PathBuf>),
+    ClearCaBundle,
+    PickFnmBinary,
+    FnmBinaryPicked(Option<std::path::PathBuf>),
+    ClearFnmBinary,
+    PickNvmDir,
+    NvmDirPicked(Option<std::path::PathBuf>),
+    ClearNvmDir,
+    SystemWideToggled(bool),
+    SanitizeBackendEnvToggled(bool),
+    PickFollowedDirectory,
+    FollowedDirectoryPicked(Option<std::path::PathBuf>),
+    ClearFollowedDirectory,
+    ProjectWatchTick,
+    ProjectPinRead(std::path::PathBuf, Option<ProjectPin>),
+    PickDataDir(DataDir),
+    DataDirPicked(DataDir, Option<std::path::PathBuf>),
+    ResetDataDir(DataDir),
+    TestConnection,
+    ConnectionTested(Result<(), String>),
+    RetryCountChanged(u32),
+    RetryBackoffChanged(versi_core::BackoffStrategy),
+    RetryTimeoutChanged(u64),
+    GitHubTokenChanged(String),
+    DetectProxy,
+    ProxyResolved(Result<crate::proxy::ResolvedProxy, String>),
+    ProxyModeChanged(crate::settings::ProxyMode),
+    PacUrlChanged(String),
+    CheckNpmConfig,
+    NpmConfigChecked {
+        env_id: EnvironmentId,
+        checks: Vec<crate::npm_doctor::NpmCheck>,
+    },
+    CopyNpmConfig {
+        from: String,
+        to: String,
+    },
+    NpmConfigCopied(Result<(), String>),
+    CheckNpmCache,
+    NpmCacheChecked {
+        env_id: EnvironmentId,
+        result: Result<crate::npm_doctor::NpmCache, String>,
+    },
+    ClearNpmCache,
+    NpmCacheCleared {
+        env_id: EnvironmentId,
+        result: Result<(), String>,
+    },
+    PlanBackendRemoval,
+    BackendRemovalPlanned(crate::backend_removal::RemovalPlan),
+    ContinueBackendRemoval,
+    ConfirmBackendRemoval,
+    CancelBackendRemoval,
+    BackendRemovalFinished(Vec<crate::backend_removal::RemovalStep>),
     CopyToClipboard(String),
     ClearLogFile,
     LogFileCleared,
     RevealLogFile,
+    ShowLogViewer,
+    LogTail(crate::log_viewer::TailEvent),
+    LogViewerLevelChanged(Option<log::Level>),
+    LogViewerModuleChanged(Option<String>),
+    LogViewerSearchChanged(String),
+    LogViewerLineClicked(u64),
+    LogViewerCopy,
+    LogViewerPauseToggled,
     RevealSettingsFile,
     LogFileStatsLoaded(Option<u64>),
-    ShellSetupChecked(Vec<(ShellType, versi_shell::VerificationResult)>),
-    ConfigureShell(ShellType),
-    ShellConfigured(ShellType, Result<(), String>),
+    ShellSetupChecked(Vec<(EnvironmentId, ShellType, versi_shell::VerificationResult)>),
+    ConfigureShell(EnvironmentId, ShellType),
+    ShellConfigured(EnvironmentId, ShellType, Result<(), String>),
+    PreviewShellConfig(EnvironmentId, ShellType),
+    ShellConfigPreviewed {
+        env_id: EnvironmentId,
+        shell_type: ShellType,
+        result: Result<Vec<String>, String>,
+    },
     ShellFlagsUpdated,
+    CheckResolvedVersions,
+    ResolvedVersionsChecked {
+        env_id: EnvironmentId,
+        results: Vec<(ShellType, versi_shell::NodeResolution)>,
+    },
+    RunTerminalTroubleshooter,
+    TerminalTroubleshooterFinished(versi_shell::TroubleshootReport),
+    CloseTerminalTroubleshooter,
+    PreviewTerminalFix(ShellType, versi_shell::TroubleshootFix),
+    TerminalFixPreviewed(Result<crate::state::TerminalFixPreview, String>),
+    ApplyTerminalFix,
+    CancelTerminalFix,
+    TerminalFixApplied(Result<PathBuf, String>),
+    JetBrainsSyncToggled(bool),
+    PreviewJetBrainsUpdate,
+    JetBrainsPlanReady {
+        result: Result<Vec<InterpreterChange>, String>,
+        prompt: bool,
+    },
+    ApplyJetBrainsUpdate,
+    JetBrainsUpdated(Result<usize, String>),
 
     ExportSettings,
     SettingsExported(Result<std::path::PathBuf, String>),
+    ReadOnlyToggled(bool),
+    ExportReport(ReportFormat),
+    ReportExported(Result<std::path::PathBuf, String>),
+    CopyEnvironmentReport(usize),
+    ExportSupportBundle,
+    ShowTips,
+    NextTip,
+    PreviousTip,
+    DismissTips,
+    SupportBundleExported(Result<std::path::PathBuf, String>),
     ImportSettings,
     SettingsImported(Result<(), String>),
+    ResetCorruptFiles,
+    RevertSettingsChange,
+    ResetSettingsSection(crate::settings::SettingsSection),
 
     PreferredBackendChanged(String),
 
     OnboardingNext,
     OnboardingBack,
     OnboardingSelectBackend(String),
+    OnboardingSetupDetected(Option<ExistingSetup>),
     OnboardingInstallBackend,
     OnboardingBackendInstallResult(Result<(), String>),
+    OnboardingInstallWith(InstallMethod),
+    OnboardingInstallProgress(String),
+    OnboardingInstallWithResult(InstallMethod, Result<(), String>),
     OnboardingConfigureShell(ShellType),
     OnboardingShellConfigResult(Result<(), String>),
     OnboardingComplete,
 
     AnimationTick,
     Tick,
+    EventLogToggled,
+    ClearEventLog,
+    SaveEventLog,
+    EventLogSaved(Result<PathBuf, String>),
     WindowEvent(iced::window::Event),
+    WindowResized {
+        size: iced::Size,
+        maximized: bool,
+    },
     CloseWindow,
     HideDockIcon,
+    QuitWhenIdle,
+    ConfirmQuit,
+    ResumePendingOperations,
+    DiscardPendingOperations,
 
     TrayEvent(TrayMessage),
+    IpcRequest(crate::ipc::IpcRequest),
+    IpcServerToggled(bool),
     TrayBehaviorChanged(TrayBehavior),
+    CloseBehaviorChanged(CloseBehavior),
+    CloseDialogRememberToggled(bool),
+    CloseDialogChoice(CloseBehavior),
     StartMinimizedToggled(bool),
+    PreventSleepToggled(bool),
     WindowOpened(iced::window::Id),
+    WindowRawIdReceived(u64),
+    AlwaysOnTopToggled(bool),
+    RememberWorkspaceToggled(bool),
+    WindowVibrancyToggled(bool),
 
+    CheckForAppUpdate,
     AppUpdateChecked(Result<Option<AppUpdate>, String>),
     OpenAppUpdate,
     StartAppUpdate,
+    RemindAppUpdateLater,
+    SkipAppUpdate,
+    ClearSkippedAppUpdate,
     AppUpdateProgress {
         downloaded: u64,
         total: u64,
@@ -136,7 +434,19 @@ pub enum Message {
     AppUpdateApplying,
     AppUpdateComplete(Result<versi_core::auto_update::ApplyResult, String>),
     RestartApp,
+    CheckForBackendUpdate,
     BackendUpdateChecked(Result<Option<BackendUpdate>, String>),
+    BackendUpdateChannelChanged(BackendUpdateChannel),
+    UpgradeAllBackends,
+    BackendUpgradeProgress {
+        env_id: EnvironmentId,
+        line: String,
+    },
+    BackendUpgraded {
+        env_id: EnvironmentId,
+        version: String,
+        result: Result<String, String>,
+    },
     OpenBackendUpdate,
 
     FetchReleaseSchedule,
@@ -147,11 +457,76 @@ pub enum Message {
     SystemThemeChanged(iced::theme::Mode),
 }
 
+impl Message {
+    /// Messages fired continuously by timers or pointer movement, left out of
+    /// the debug event log so they don't drown out user actions.
+    pub fn is_high_frequency(&self) -> bool {
+        matches!(
+            self,
+            Message::AnimationTick
+                | Message::Tick
+                | Message::SettingsFileCheck
+                | Message::ProjectWatchTick
+                | Message::ProjectPinRead(..)
+                | Message::LogTail(_)
+                | Message::VersionRowHovered(_)
+                | Message::ModifiersChanged(_)
+                | Message::WindowEvent(_)
+                | Message::WindowResized { .. }
+        )
+    }
+
+    /// How the message appears in the debug event log, which can be saved to
+    /// disk, so secrets such as the GitHub token are left out.
+    pub fn log_entry(&self) -> String {
+        match self {
+            Message::GitHubTokenChanged(_) => "GitHubTokenChanged(<redacted>)".to_string(),
+            _ => format!("{self:?}"),
+        }
+    }
+
+    /// Messages that change installed versions or defaults, refused in read-only mode.
+    pub fn is_mutating(&self) -> bool {
+        matches!(
+            self,
+            Message::StartInstall(_)
+                | Message::ConfirmInstallWithoutBinary(_)
+                | Message::InstallFromUnofficialBuilds(_)
+                | Message::RequestUninstall(_)
+                | Message::UninstallFromEnvironment { .. }
+                | Message::ReinstallVersion(_)
+                | Message::ConfirmReinstall
+                | Message::ConfirmUninstallDefault(_)
+                | Message::ConfirmUninstallProtected(_)
+                | Message::SetDefault(_)
+                | Message::ConfirmSetDefaultForeignArch(_)
+                | Message::RequestBulkUpdateMajors
+                | Message::RequestBulkUninstallEOL
+                | Message::RequestBulkUninstallMajor { .. }
+                | Message::RequestBulkUninstallMajorExceptLatest { .. }
+                | Message::RequestBulkUninstallSelected
+                | Message::ConfirmBulkUpdateMajors
+                | Message::ConfirmBulkUninstallEOL
+                | Message::ConfirmBulkUninstallMajor { .. }
+                | Message::ConfirmBulkUninstallMajorExceptLatest { .. }
+                | Message::ConfirmBulkUninstallSelected
+                | Message::RollBackEnvironment
+                | Message::AddScheduledJob
+                | Message::ResumePendingOperations
+                | Message::CopyNpmConfig { .. }
+                | Message::ConfirmBackendRemoval
+                | Message::UpgradeAllBackends
+                | Message::TrayEvent(TrayMessage::SetDefault { .. })
+        )
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct InitResult {
     pub backend_found: bool,
     pub backend_path: Option<PathBuf>,
     pub backend_dir: Option<PathBuf>,
+    pub backend_source: Option<String>,
     pub backend_version: Option<String>,
     pub environments: Vec<EnvironmentInfo>,
     pub detected_backends: Vec<&'static str>,
@@ -163,5 +538,6 @@ pub struct EnvironmentInfo {
     pub backend_name: &'static str,
     pub backend_version: Option<String>,
     pub available: bool,
-    pub unavailable_reason: Option<String>,
+    pub unavailable_reason: Option<EnvironmentError>,
+    pub target: Option<PlatformTarget>,
 }
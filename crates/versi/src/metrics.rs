@@ -0,0 +1,318 @@
+use std::collections::VecDeque;
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+use versi_platform::EnvironmentId;
+
+/// Timings kept for the diagnostics shown in About.
+const CAPACITY: usize = 200;
+
+/// Earlier timings needed before an operation is compared against them.
+const MIN_HISTORY: usize = 3;
+
+/// How many times slower than usual an operation must be to count as slow.
+const SLOWDOWN_FACTOR: u32 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationKind {
+    Install,
+    Refresh,
+    Detection,
+}
+
+impl OperationKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Install => "Install",
+            Self::Refresh => "Refresh",
+            Self::Detection => "Detection",
+        }
+    }
+
+    /// Slower than this is worth a warning even without earlier timings.
+    fn limit(self) -> Duration {
+        match self {
+            Self::Install => Duration::from_secs(180),
+            Self::Refresh => Duration::from_secs(20),
+            Self::Detection => Duration::from_secs(15),
+        }
+    }
+
+    /// Faster than this is never reported, however quick it usually is.
+    fn floor(self) -> Duration {
+        match self {
+            Self::Install => Duration::from_secs(30),
+            Self::Refresh | Self::Detection => Duration::from_secs(5),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Sample {
+    kind: OperationKind,
+    env: EnvironmentId,
+    duration: Duration,
+}
+
+/// Timings of one kind of operation in one environment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub kind: OperationKind,
+    pub env: EnvironmentId,
+    pub count: usize,
+    pub last: Duration,
+    pub median: Duration,
+    pub slowest: Duration,
+}
+
+/// An operation that took much longer than it should have.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlowOperation {
+    pub kind: OperationKind,
+    pub env: EnvironmentId,
+    /// What ran, such as "Node 22.1.0".
+    pub subject: String,
+    pub duration: Duration,
+    /// How long it usually takes, when there are earlier timings.
+    pub typical: Option<Duration>,
+}
+
+impl SlowOperation {
+    /// A likely cause and what to do about it.
+    pub fn hint(&self) -> &'static str {
+        match (&self.env, self.kind) {
+            (EnvironmentId::Wsl { .. }, _) => {
+                "WSL may have been starting its virtual machine; later commands in this distro are usually faster"
+            }
+            (EnvironmentId::Msys { .. }, _) => {
+                "Each command starts a new bash, which loads slowly when antivirus scans it; excluding the Git or MSYS2 directory from real-time scanning usually helps"
+            }
+            (EnvironmentId::Native, OperationKind::Install) if cfg!(windows) => {
+                "Antivirus software scanning the extracted files can slow installs; excluding the backend's data directory from real-time scanning usually helps"
+            }
+            (EnvironmentId::Native, OperationKind::Install) => {
+                "Downloads may be slow; check your connection or set a closer mirror in Settings"
+            }
+            (EnvironmentId::Native, _) => {
+                "Slow shell startup files or a busy disk can delay the backend; the logs show which commands ran"
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Collector {
+    samples: VecDeque<Sample>,
+    slow: Option<SlowOperation>,
+}
+
+impl Collector {
+    fn record(
+        &mut self,
+        kind: OperationKind,
+        env: &EnvironmentId,
+        subject: String,
+        duration: Duration,
+    ) -> Option<SlowOperation> {
+        let history: Vec<Duration> = self
+            .samples
+            .iter()
+            .filter(|sample| sample.kind == kind && sample.env == *env)
+            .map(|sample| sample.duration)
+            .collect();
+        let typical = (history.len() >= MIN_HISTORY).then(|| median(history));
+
+        if self.samples.len() == CAPACITY {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(Sample {
+            kind,
+            env: env.clone(),
+            duration,
+        });
+
+        if !is_slow(kind, duration, typical) {
+            return None;
+        }
+        let slow = SlowOperation {
+            kind,
+            env: env.clone(),
+            subject,
+            duration,
+            typical,
+        };
+        self.slow = Some(slow.clone());
+        Some(slow)
+    }
+
+    fn summaries(&self) -> Vec<Summary> {
+        let mut summaries: Vec<Summary> = Vec::new();
+        for sample in &self.samples {
+            let existing = summaries
+                .iter_mut()
+                .find(|s| s.kind == sample.kind && s.env == sample.env);
+            match existing {
+                Some(summary) => {
+                    summary.count += 1;
+                    summary.last = sample.duration;
+                    summary.slowest = summary.slowest.max(sample.duration);
+                }
+                None => summaries.push(Summary {
+                    kind: sample.kind,
+                    env: sample.env.clone(),
+                    count: 1,
+                    last: sample.duration,
+                    median: sample.duration,
+                    slowest: sample.duration,
+                }),
+            }
+        }
+        for summary in &mut summaries {
+            summary.median = median(
+                self.samples
+                    .iter()
+                    .filter(|s| s.kind == summary.kind && s.env == summary.env)
+                    .map(|s| s.duration)
+                    .collect(),
+            );
+        }
+        summaries
+    }
+}
+
+static METRICS: Mutex<Collector> = Mutex::new(Collector {
+    samples: VecDeque::new(),
+    slow: None,
+});
+
+/// Records how long an operation took in `env`, and logs a warning when it
+/// was abnormally slow.
+pub fn record(kind: OperationKind, env: &EnvironmentId, subject: String, duration: Duration) {
+    let slow = METRICS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .record(kind, env, subject, duration);
+    if let Some(slow) = slow {
+        log::warn!(
+            "{} of {} in {} took {:.1}s: {}",
+            kind.label(),
+            slow.subject,
+            env.display_name(),
+            duration.as_secs_f64(),
+            slow.hint()
+        );
+    }
+}
+
+/// Timings per environment and kind of operation, in the order first seen.
+pub fn summaries() -> Vec<Summary> {
+    METRICS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .summaries()
+}
+
+/// The latest slow operation, until it is dismissed.
+pub fn slow_operation() -> Option<SlowOperation> {
+    METRICS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .slow
+        .clone()
+}
+
+pub fn dismiss_slow_operation() {
+    METRICS.lock().unwrap_or_else(PoisonError::into_inner).slow = None;
+}
+
+fn is_slow(kind: OperationKind, duration: Duration, typical: Option<Duration>) -> bool {
+    if duration < kind.floor() {
+        return false;
+    }
+    match typical {
+        Some(typical) => duration > typical * SLOWDOWN_FACTOR,
+        None => duration > kind.limit(),
+    }
+}
+
+fn median(mut durations: Vec<Duration>) -> Duration {
+    durations.sort();
+    durations
+        .get(durations.len() / 2)
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Seconds with one decimal below a minute, then minutes and seconds.
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs_f64();
+    if secs < 60.0 {
+        format!("{secs:.1}s")
+    } else {
+        let whole = duration.as_secs();
+        format!("{}m {:02}s", whole / 60, whole % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collector() -> Collector {
+        Collector {
+            samples: VecDeque::new(),
+            slow: None,
+        }
+    }
+
+    #[test]
+    fn compares_against_earlier_timings_once_there_are_enough() {
+        let mut metrics = collector();
+        let env = EnvironmentId::Native;
+        for _ in 0..MIN_HISTORY {
+            let slow = metrics.record(
+                OperationKind::Refresh,
+                &env,
+                "versions".to_string(),
+                Duration::from_secs(2),
+            );
+            assert!(slow.is_none());
+        }
+
+        let slow = metrics
+            .record(
+                OperationKind::Refresh,
+                &env,
+                "versions".to_string(),
+                Duration::from_secs(10),
+            )
+            .unwrap();
+        assert_eq!(slow.typical, Some(Duration::from_secs(2)));
+        assert_eq!(metrics.slow, Some(slow));
+
+        let summaries = metrics.summaries();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].count, MIN_HISTORY + 1);
+        assert_eq!(summaries[0].slowest, Duration::from_secs(10));
+    }
+
+    #[test]
+    fn quick_operations_are_never_slow() {
+        assert!(!is_slow(
+            OperationKind::Refresh,
+            Duration::from_secs(4),
+            Some(Duration::from_millis(100))
+        ));
+        assert!(is_slow(
+            OperationKind::Detection,
+            Duration::from_secs(16),
+            None
+        ));
+    }
+
+    #[test]
+    fn formats_durations() {
+        assert_eq!(format_duration(Duration::from_millis(1520)), "1.5s");
+        assert_eq!(format_duration(Duration::from_secs(125)), "2m 05s");
+    }
+}
@@ -0,0 +1,403 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use versi_backend::VersionManager;
+use versi_platform::EnvironmentId;
+
+const PUBLIC_REGISTRY: &str = "https://registry.npmjs.org";
+
+/// Copies `process.argv[1]` to `process.argv[2]`, creating the target directory.
+const COPY_SCRIPT: &str = "const fs=require('fs'),path=require('path');\
+fs.mkdirSync(path.dirname(process.argv[2]),{recursive:true});\
+fs.copyFileSync(process.argv[1],process.argv[2]);";
+
+/// Prints the total size in bytes of the files under `process.argv[1]`.
+const DIR_SIZE_SCRIPT: &str = "const fs=require('fs'),path=require('path');let total=0;\
+const walk=d=>{for(const e of fs.readdirSync(d,{withFileTypes:true})){const p=path.join(d,e.name);\
+try{if(e.isDirectory())walk(p);else total+=fs.lstatSync(p).size;}catch{}}};\
+try{walk(process.argv[1]);}catch{}console.log(total);";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NpmIssue {
+    RegistryMismatch { expected: String },
+    MissingAuth,
+}
+
+impl NpmIssue {
+    pub fn label(&self) -> String {
+        match self {
+            Self::RegistryMismatch { expected } => format!("Registry differs from {}", expected),
+            Self::MissingAuth => "Not logged in".to_string(),
+        }
+    }
+}
+
+/// npm registry and login state as seen by one installed Node version.
+#[derive(Debug, Clone)]
+pub struct NpmCheck {
+    pub version: String,
+    pub registry: Option<String>,
+    pub user: Option<String>,
+    pub global_config: Option<String>,
+    pub error: Option<String>,
+    pub issues: Vec<NpmIssue>,
+    pub fix_from: Option<String>,
+}
+
+/// Name of the npm executable to spawn inside an environment.
+pub fn npm_program(env_id: &EnvironmentId) -> &'static str {
+    if cfg!(windows) && matches!(env_id, EnvironmentId::Native) {
+        "npm.cmd"
+    } else {
+        "npm"
+    }
+}
+
+pub async fn check_version(
+    backend: &dyn VersionManager,
+    npm: &str,
+    version: &str,
+    timeout: Duration,
+) -> NpmCheck {
+    let run = |args: &'static [&'static str]| async move {
+        let mut command = vec![npm];
+        command.extend_from_slice(args);
+        match tokio::time::timeout(timeout, backend.exec(version, &command)).await {
+            Ok(Ok(output)) => Ok(output.trim().to_string()),
+            Ok(Err(e)) => Err(e.to_string()),
+            Err(_) => Err("Timed out".to_string()),
+        }
+    };
+
+    let (registry, error) = match run(&["config", "get", "registry"]).await {
+        Ok(registry) => (Some(registry), None),
+        Err(e) => (None, Some(e)),
+    };
+    let user = if registry.is_some() {
+        run(&["whoami"]).await.ok().filter(|u| !u.is_empty())
+    } else {
+        None
+    };
+    let global_config = if registry.is_some() {
+        run(&["config", "get", "globalconfig"])
+            .await
+            .ok()
+            .filter(|p| !p.is_empty())
+    } else {
+        None
+    };
+
+    NpmCheck {
+        version: version.to_string(),
+        registry,
+        user,
+        global_config,
+        error,
+        issues: Vec::new(),
+        fix_from: None,
+    }
+}
+
+/// Copies the global npmrc used by `from` over the one used by `to`.
+pub async fn copy_global_config(
+    backend: &dyn VersionManager,
+    to: &str,
+    source: &str,
+    target: &str,
+) -> Result<(), String> {
+    backend
+        .exec(to, &["node", "-e", COPY_SCRIPT, source, target])
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// npm's cache directory in one environment and the space it takes.
+#[derive(Debug, Clone)]
+pub struct NpmCache {
+    pub path: String,
+    pub size: u64,
+}
+
+/// Measures npm's cache with `version`, walking the directory with Node so
+/// it works the same inside WSL.
+pub async fn cache_usage(
+    backend: &dyn VersionManager,
+    npm: &str,
+    version: &str,
+    timeout: Duration,
+) -> Result<NpmCache, String> {
+    let path = exec_trimmed(backend, version, &[npm, "config", "get", "cache"], timeout).await?;
+    if path.is_empty() {
+        return Err("npm did not report a cache directory".to_string());
+    }
+    let size = exec_trimmed(
+        backend,
+        version,
+        &["node", "-e", DIR_SIZE_SCRIPT, &path],
+        timeout,
+    )
+    .await?
+    .parse()
+    .map_err(|_| "Could not read the cache size".to_string())?;
+    Ok(NpmCache { path, size })
+}
+
+async fn exec_trimmed(
+    backend: &dyn VersionManager,
+    version: &str,
+    command: &[&str],
+    timeout: Duration,
+) -> Result<String, String> {
+    match tokio::time::timeout(timeout, backend.exec(version, command)).await {
+        Ok(Ok(output)) => Ok(output.trim().to_string()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err("Timed out".to_string()),
+    }
+}
+
+/// Packages that come with Node itself rather than being installed by the user.
+const BUNDLED_PACKAGES: &[&str] = &["npm", "corepack"];
+
+/// Lists the packages installed globally with `version`'s npm as
+/// `name@version`, so they can be installed again after a reinstall.
+pub async fn global_packages(
+    backend: &dyn VersionManager,
+    npm: &str,
+    version: &str,
+    timeout: Duration,
+) -> Result<Vec<String>, String> {
+    let output = exec_trimmed(
+        backend,
+        version,
+        &[npm, "ls", "--global", "--depth=0", "--json"],
+        timeout,
+    )
+    .await?;
+    parse_global_packages(&output)
+}
+
+/// Leaves out bundled packages and ones linked from a local directory,
+/// which the registry cannot provide.
+fn parse_global_packages(output: &str) -> Result<Vec<String>, String> {
+    let listing: serde_json::Value = serde_json::from_str(output)
+        .map_err(|_| "npm did not list its global packages".to_string())?;
+    let Some(dependencies) = listing.get("dependencies").and_then(|d| d.as_object()) else {
+        return Ok(Vec::new());
+    };
+    Ok(dependencies
+        .iter()
+        .filter(|(name, _)| !BUNDLED_PACKAGES.contains(&name.as_str()))
+        .filter(|(_, info)| {
+            !info
+                .get("resolved")
+                .and_then(|r| r.as_str())
+                .is_some_and(|r| r.starts_with("file:"))
+        })
+        .map(
+            |(name, info)| match info.get("version").and_then(|v| v.as_str()) {
+                Some(version) => format!("{name}@{version}"),
+                None => name.clone(),
+            },
+        )
+        .collect())
+}
+
+/// Installs `packages` globally with `version`'s npm.
+pub async fn install_global_packages(
+    backend: &dyn VersionManager,
+    npm: &str,
+    version: &str,
+    packages: &[String],
+) -> Result<(), String> {
+    let mut command = vec![npm, "install", "--global"];
+    command.extend(packages.iter().map(String::as_str));
+    backend
+        .exec(version, &command)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Empties npm's cache with `npm cache clean --force`.
+pub async fn clear_cache(
+    backend: &dyn VersionManager,
+    npm: &str,
+    version: &str,
+) -> Result<(), String> {
+    backend
+        .exec(version, &[npm, "cache", "clean", "--force"])
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+fn normalize(registry: &str) -> &str {
+    registry.trim().trim_end_matches('/')
+}
+
+/// Flags versions whose registry differs from the default version's (or the
+/// most common one), and versions that are logged out of a registry other
+/// versions are logged in to, then picks a healthy version to copy from.
+pub fn diagnose(checks: &mut [NpmCheck], default_version: Option<&str>) {
+    let expected = default_version
+        .and_then(|default| checks.iter().find(|c| c.version == default))
+        .and_then(|c| c.registry.as_deref())
+        .map(normalize)
+        .or_else(|| {
+            let mut counts: HashMap<&str, usize> = HashMap::new();
+            for registry in checks.iter().filter_map(|c| c.registry.as_deref()) {
+                *counts.entry(normalize(registry)).or_default() += 1;
+            }
+            counts
+                .into_iter()
+                .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+                .map(|(registry, _)| registry)
+        })
+        .map(str::to_string);
+
+    let authenticated: Vec<String> = checks
+        .iter()
+        .filter(|c| c.user.is_some())
+        .filter_map(|c| c.registry.as_deref().map(|r| normalize(r).to_string()))
+        .collect();
+
+    for check in checks.iter_mut() {
+        check.issues.clear();
+        let Some(registry) = check.registry.as_deref().map(normalize) else {
+            continue;
+        };
+        if let Some(expected) = &expected
+            && registry != expected
+        {
+            check.issues.push(NpmIssue::RegistryMismatch {
+                expected: expected.clone(),
+            });
+        }
+        if check.user.is_none()
+            && registry != PUBLIC_REGISTRY
+            && authenticated.iter().any(|r| r == registry)
+        {
+            check.issues.push(NpmIssue::MissingAuth);
+        }
+    }
+
+    let healthy = |c: &NpmCheck| {
+        c.issues.is_empty()
+            && c.global_config.is_some()
+            && c.registry.as_deref().map(normalize) == expected.as_deref()
+    };
+    let source = default_version
+        .and_then(|default| checks.iter().find(|c| c.version == default && healthy(c)))
+        .or_else(|| {
+            checks
+                .iter()
+                .filter(|c| healthy(c))
+                .max_by_key(|c| c.user.is_some())
+        })
+        .map(|c| c.version.clone());
+
+    for check in checks.iter_mut() {
+        check.fix_from = match &source {
+            Some(source)
+                if !check.issues.is_empty()
+                    && check.global_config.is_some()
+                    && *source != check.version =>
+            {
+                Some(source.clone())
+            }
+            _ => None,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(version: &str, registry: &str, user: Option<&str>) -> NpmCheck {
+        NpmCheck {
+            version: version.to_string(),
+            registry: Some(registry.to_string()),
+            user: user.map(str::to_string),
+            global_config: Some(format!("/nodes/{version}/etc/npmrc")),
+            error: None,
+            issues: Vec::new(),
+            fix_from: None,
+        }
+    }
+
+    #[test]
+    fn lists_user_installed_global_packages() {
+        let output = r#"{
+            "name": "lib",
+            "dependencies": {
+                "corepack": { "version": "0.29.4" },
+                "npm": { "version": "10.9.0" },
+                "local-tool": { "version": "1.0.0", "resolved": "file:../../src/local-tool" },
+                "typescript": { "version": "5.6.3" }
+            }
+        }"#;
+        assert_eq!(
+            parse_global_packages(output).unwrap(),
+            vec!["typescript@5.6.3"]
+        );
+        assert_eq!(parse_global_packages("{}").unwrap(), Vec::<String>::new());
+        assert!(parse_global_packages("npm ERR!").is_err());
+    }
+
+    #[test]
+    fn flags_registry_that_differs_from_default() {
+        let mut checks = vec![
+            check("v22.11.0", "https://npm.corp.example/", Some("dev")),
+            check("v20.18.0", "https://registry.npmjs.org/", None),
+            check("v18.20.4", "https://npm.corp.example", Some("dev")),
+        ];
+        diagnose(&mut checks, Some("v22.11.0"));
+
+        assert!(checks[0].issues.is_empty());
+        assert!(checks[2].issues.is_empty());
+        assert_eq!(
+            checks[1].issues,
+            vec![NpmIssue::RegistryMismatch {
+                expected: "https://npm.corp.example".to_string()
+            }]
+        );
+        assert_eq!(checks[1].fix_from.as_deref(), Some("v22.11.0"));
+        assert_eq!(checks[0].fix_from, None);
+    }
+
+    #[test]
+    fn flags_missing_auth_only_where_others_are_logged_in() {
+        let mut checks = vec![
+            check("v22.11.0", "https://npm.corp.example/", None),
+            check("v20.18.0", "https://npm.corp.example/", Some("dev")),
+        ];
+        diagnose(&mut checks, None);
+
+        assert_eq!(checks[0].issues, vec![NpmIssue::MissingAuth]);
+        assert_eq!(checks[0].fix_from.as_deref(), Some("v20.18.0"));
+
+        let mut public = vec![
+            check("v22.11.0", "https://registry.npmjs.org/", None),
+            check("v20.18.0", "https://registry.npmjs.org/", Some("dev")),
+        ];
+        diagnose(&mut public, None);
+        assert!(public[0].issues.is_empty());
+    }
+
+    #[test]
+    fn failed_checks_are_not_fixable() {
+        let mut checks = vec![
+            check("v22.11.0", "https://registry.npmjs.org/", None),
+            NpmCheck {
+                registry: None,
+                error: Some("npm: command not found".to_string()),
+                ..check("v16.20.2", "", None)
+            },
+        ];
+        diagnose(&mut checks, Some("v22.11.0"));
+        assert!(checks[1].issues.is_empty());
+        assert_eq!(checks[1].fix_from, None);
+    }
+}
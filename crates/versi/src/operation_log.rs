@@ -0,0 +1,207 @@
+use std::io::{Cursor, Write};
+use std::path::Path;
+
+use versi_backend::BackendInfo;
+use versi_platform::CommandTranscript;
+use zip::write::SimpleFileOptions;
+
+use crate::state::{EnvironmentState, OperationFailure};
+
+/// Lines from the end of the app log included in a bundle.
+const LOG_TAIL_LINES: usize = 500;
+
+/// Everything attached to a bug report about a failed operation.
+pub struct OperationLog<'a> {
+    pub failure: &'a OperationFailure,
+    pub transcripts: Vec<CommandTranscript>,
+    pub app_log: String,
+    pub backend: &'a BackendInfo,
+    pub environment: &'a EnvironmentState,
+}
+
+impl OperationLog<'_> {
+    pub fn file_name(&self) -> String {
+        let failed_at = chrono::DateTime::<chrono::Local>::from(self.failure.failed_at);
+        format!(
+            "versi-operation-log-{}.zip",
+            failed_at.format("%Y-%m-%d-%H%M%S")
+        )
+    }
+
+    /// Zips the failure summary, command transcripts, app log and backend
+    /// details into one archive.
+    pub fn to_zip(&self) -> Result<Vec<u8>, String> {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let files = [
+            ("summary.txt", self.summary()),
+            ("commands.txt", self.commands()),
+            ("versi.log", self.app_log.clone()),
+        ];
+        for (name, content) in files {
+            zip.start_file(name, SimpleFileOptions::default())
+                .map_err(|e| e.to_string())?;
+            zip.write_all(content.as_bytes())
+                .map_err(|e| e.to_string())?;
+        }
+        let cursor = zip.finish().map_err(|e| e.to_string())?;
+        Ok(cursor.into_inner())
+    }
+
+    fn summary(&self) -> String {
+        let failure = self.failure;
+        let failed_at = chrono::DateTime::<chrono::Utc>::from(failure.failed_at);
+        let env = self.environment;
+        let lines = [
+            format!("Versi: {}", env!("CARGO_PKG_VERSION")),
+            format!("OS: {} ({})", std::env::consts::OS, std::env::consts::ARCH),
+            format!("Failed at: {}", failed_at.to_rfc3339()),
+            format!(
+                "Operation: {}",
+                failure.request.log_key(&failure.environment)
+            ),
+            format!("Error: {}", failure.error),
+            String::new(),
+            format!("Backend: {}", self.backend.name),
+            format!(
+                "Backend version: {}",
+                self.backend.version.as_deref().unwrap_or("unknown")
+            ),
+            format!("Backend path: {}", self.backend.path.display()),
+            format!(
+                "Backend data directory: {}",
+                self.backend
+                    .data_dir
+                    .as_ref()
+                    .map(|dir| dir.display().to_string())
+                    .unwrap_or_else(|| "unknown".to_string())
+            ),
+            format!(
+                "Found via: {}",
+                self.backend.source.as_deref().unwrap_or("unknown")
+            ),
+            String::new(),
+            format!("Environment: {}", env.name),
+            format!(
+                "Target: {}",
+                env.target
+                    .map(|target| target.dist_file_key())
+                    .unwrap_or_else(|| "unknown".to_string())
+            ),
+            format!(
+                "Installed versions: {}",
+                env.installed_versions
+                    .iter()
+                    .map(|v| v.version.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            format!(
+                "Default: {}",
+                env.default_version
+                    .as_ref()
+                    .map(|v| v.to_string())
+                    .unwrap_or_else(|| "none".to_string())
+            ),
+        ];
+        lines.join("\n") + "\n"
+    }
+
+    fn commands(&self) -> String {
+        if self.transcripts.is_empty() {
+            return "No commands were recorded for this operation.\n".to_string();
+        }
+        self.transcripts
+            .iter()
+            .map(|t| {
+                format!(
+                    "$ {}\nStarted: {}\nDuration: {} ms\nResult: {}\n--- stdout ---\n{}\n--- stderr ---\n{}\n",
+                    t.command,
+                    chrono::DateTime::<chrono::Utc>::from(t.started_at).to_rfc3339(),
+                    t.duration.as_millis(),
+                    t.outcome,
+                    t.stdout.trim_end(),
+                    t.stderr.trim_end()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// The last lines of the app log, or a note when it cannot be read.
+pub fn read_log_tail(path: &Path) -> String {
+    match std::fs::read_to_string(path) {
+        Ok(content) => {
+            let lines: Vec<&str> = content.lines().collect();
+            let start = lines.len().saturating_sub(LOG_TAIL_LINES);
+            lines[start..].join("\n") + "\n"
+        }
+        Err(e) => format!("Could not read {}: {}\n", path.display(), e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+
+    use versi_platform::EnvironmentId;
+
+    use super::*;
+    use crate::state::OperationRequest;
+
+    #[test]
+    fn bundles_summary_commands_and_log() {
+        let failure = OperationFailure {
+            environment: EnvironmentId::Native,
+            request: OperationRequest::Install {
+                version: "v22.1.0".to_string(),
+            },
+            error: "download failed".to_string(),
+            failed_at: SystemTime::now(),
+        };
+        let backend = BackendInfo {
+            name: "fnm",
+            path: PathBuf::from("/usr/bin/fnm"),
+            version: Some("1.38.1".to_string()),
+            data_dir: None,
+            in_path: true,
+            source: Some("PATH".to_string()),
+        };
+        let environment = EnvironmentState::new(EnvironmentId::Native, "fnm", None);
+        let log = OperationLog {
+            failure: &failure,
+            transcripts: vec![CommandTranscript {
+                operation: Some(failure.request.log_key(&failure.environment)),
+                command: "fnm install v22.1.0".to_string(),
+                started_at: SystemTime::now(),
+                duration: Duration::from_millis(1200),
+                outcome: "exit status: 1".to_string(),
+                stdout: String::new(),
+                stderr: "error: download failed".to_string(),
+            }],
+            app_log: "[INFO] Installing v22.1.0\n".to_string(),
+            backend: &backend,
+            environment: &environment,
+        };
+
+        let bytes = log.to_zip().unwrap();
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let mut read = |name: &str| {
+            let mut content = String::new();
+            archive
+                .by_name(name)
+                .unwrap()
+                .read_to_string(&mut content)
+                .unwrap();
+            content
+        };
+
+        let summary = read("summary.txt");
+        assert!(summary.contains("Error: download failed"));
+        assert!(summary.contains("Found via: PATH"));
+        assert!(read("commands.txt").contains("$ fnm install v22.1.0"));
+        assert_eq!(read("versi.log"), "[INFO] Installing v22.1.0\n");
+    }
+}
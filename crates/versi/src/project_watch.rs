@@ -0,0 +1,157 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::NaiveDate;
+use versi_backend::{InstalledVersion, NodeVersion, RemoteVersion};
+use versi_core::ReleaseSchedule;
+
+use crate::aliases::{install_candidates, matches_partial, resolve_alias};
+
+/// How often the followed directory is checked for a changed version file.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Version files read, in order of preference.
+const VERSION_FILES: [&str; 2] = [".nvmrc", ".node-version"];
+
+/// The Node version a project pins in a version file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectPin {
+    /// Directory the version file is in.
+    pub root: PathBuf,
+    pub file: &'static str,
+    /// The version as written, e.g. `20`, `v22.11.0` or `lts/iron`.
+    pub spec: String,
+}
+
+impl ProjectPin {
+    /// The version file nearest to `dir`, looking in its parents as well
+    /// like `nvm use` does.
+    pub fn find(dir: &Path) -> Option<Self> {
+        dir.ancestors().find_map(|root| {
+            VERSION_FILES.into_iter().find_map(|file| {
+                let content = std::fs::read_to_string(root.join(file)).ok()?;
+                Some(Self {
+                    root: root.to_path_buf(),
+                    file,
+                    spec: parse_version_file(&content)?,
+                })
+            })
+        })
+    }
+
+    /// Name of the project, taken from its directory.
+    pub fn name(&self) -> String {
+        self.root.file_name().map_or_else(
+            || self.root.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        )
+    }
+
+    /// Whether `version` is one the pin selects. Aliases such as `lts/iron`
+    /// take any release of the line they resolve to, as `nvm use` picks the
+    /// newest one installed.
+    pub fn accepts(
+        &self,
+        version: &NodeVersion,
+        remote: &[RemoteVersion],
+        schedule: Option<&ReleaseSchedule>,
+        today: NaiveDate,
+    ) -> bool {
+        match resolve_alias(remote, schedule, &self.spec, today) {
+            Some(alias) => alias.version.version.major == version.major,
+            None => matches_partial(&self.spec, version),
+        }
+    }
+
+    /// Newest installed version the pin selects.
+    pub fn installed_match<'a>(
+        &self,
+        installed: &'a [InstalledVersion],
+        remote: &[RemoteVersion],
+        schedule: Option<&ReleaseSchedule>,
+        today: NaiveDate,
+    ) -> Option<&'a NodeVersion> {
+        installed
+            .iter()
+            .map(|v| &v.version)
+            .filter(|v| self.accepts(v, remote, schedule, today))
+            .max()
+    }
+
+    /// Version to install when nothing installed matches: the newest
+    /// release it selects, or the spec itself when the release list is not
+    /// loaded.
+    pub fn install_target(
+        &self,
+        remote: &[RemoteVersion],
+        schedule: Option<&ReleaseSchedule>,
+        today: NaiveDate,
+    ) -> String {
+        install_candidates(remote, schedule, &self.spec, today)
+            .first()
+            .map_or_else(|| self.spec.clone(), ToString::to_string)
+    }
+}
+
+/// First version in a version file, without comments.
+fn parse_version_file(content: &str) -> Option<String> {
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn installed(version: &str) -> InstalledVersion {
+        InstalledVersion {
+            version: version.parse().unwrap(),
+            is_default: false,
+            lts_codename: None,
+            install_date: None,
+            disk_size: None,
+        }
+    }
+
+    #[test]
+    fn finds_the_nearest_version_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("packages/app");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(dir.path().join(".node-version"), "18\n").unwrap();
+        std::fs::write(dir.path().join(".nvmrc"), "# pinned for CI\nv20.11 # LTS\n").unwrap();
+
+        let pin = ProjectPin::find(&nested).unwrap();
+        assert_eq!(pin.root, dir.path());
+        assert_eq!(pin.file, ".nvmrc");
+        assert_eq!(pin.spec, "v20.11");
+    }
+
+    #[test]
+    fn matches_the_newest_installed_version() {
+        let pin = ProjectPin {
+            root: PathBuf::from("/work/app"),
+            file: ".nvmrc",
+            spec: "20".to_string(),
+        };
+        let versions = [
+            installed("v20.10.0"),
+            installed("v20.11.1"),
+            installed("v22.1.0"),
+        ];
+        let today = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+
+        assert_eq!(
+            pin.installed_match(&versions, &[], None, today),
+            Some(&NodeVersion::new(20, 11, 1))
+        );
+        assert!(!pin.accepts(&NodeVersion::new(22, 1, 0), &[], None, today));
+        assert_eq!(pin.name(), "app");
+        assert!(matches_partial("20.10.0", &NodeVersion::new(20, 10, 0)));
+        assert!(!matches_partial("lts/iron", &NodeVersion::new(20, 10, 0)));
+        assert_eq!(pin.install_target(&[], None, today), "20");
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+
+use versi_core::{PacScript, SystemProxy, detect_system_proxy, fetch_pac_script, first_proxy};
+
+use crate::settings::ProxyMode;
+
+/// The proxy configuration requests and backends use.
+#[derive(Debug, Clone, Default)]
+pub enum ResolvedProxy {
+    /// Not detected yet; reqwest and the backends read the environment
+    /// themselves.
+    #[default]
+    Inherit,
+    Direct,
+    Fixed(SystemProxy),
+    Pac {
+        location: String,
+        script: Arc<PacScript>,
+    },
+}
+
+impl ResolvedProxy {
+    /// A one-line summary for the settings screen.
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Inherit => "Using proxy environment variables".to_string(),
+            Self::Direct => "Connecting directly".to_string(),
+            Self::Fixed(proxy) => match (&proxy.http, &proxy.https) {
+                (None, None) => "No system proxy configured".to_string(),
+                (Some(http), Some(https)) if http == https => format!("Using {}", http),
+                (http, https) => format!(
+                    "HTTP via {}, HTTPS via {}",
+                    http.as_deref().unwrap_or("direct"),
+                    https.as_deref().unwrap_or("direct")
+                ),
+            },
+            Self::Pac { location, .. } => format!("Using PAC file {}", location),
+        }
+    }
+}
+
+static CURRENT: RwLock<ResolvedProxy> = RwLock::new(ResolvedProxy::Inherit);
+
+/// PAC results per URL, so retries and repeated requests don't run the
+/// script again. Scripts may match on the path, so the whole URL is the key.
+static PAC_RESULTS: Mutex<Option<HashMap<String, Option<String>>>> = Mutex::new(None);
+
+/// How many URLs [`PAC_RESULTS`] remembers before starting over.
+const PAC_RESULTS_LIMIT: usize = 512;
+
+/// Detects the proxy for `mode`, fetching and parsing the PAC file when
+/// one applies.
+pub async fn resolve(mode: ProxyMode, pac_url: Option<String>) -> Result<ResolvedProxy, String> {
+    if mode == ProxyMode::Direct {
+        return Ok(ResolvedProxy::Direct);
+    }
+    let system = detect_system_proxy().await;
+    let location = match mode {
+        ProxyMode::Pac => Some(
+            pac_url
+                .filter(|url| !url.trim().is_empty())
+                .or_else(|| system.pac_url.clone())
+                .ok_or_else(|| "No PAC file configured".to_string())?,
+        ),
+        _ => system.pac_url.clone(),
+    };
+    let Some(location) = location else {
+        return Ok(ResolvedProxy::Fixed(system));
+    };
+
+    // PAC files are served from inside the network, never through a proxy.
+    let client = reqwest::Client::builder()
+        .no_proxy()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let source = fetch_pac_script(&client, location.trim()).await?;
+    let script = PacScript::parse(&source)?;
+    Ok(ResolvedProxy::Pac {
+        location,
+        script: Arc::new(script),
+    })
+}
+
+pub fn set(resolved: ResolvedProxy) {
+    *PAC_RESULTS.lock().unwrap_or_else(PoisonError::into_inner) = None;
+    *CURRENT.write().unwrap_or_else(PoisonError::into_inner) = resolved;
+}
+
+pub fn current() -> ResolvedProxy {
+    CURRENT
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// Routes a client's requests the way the resolved proxy says.
+pub fn apply(builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+    match current() {
+        ResolvedProxy::Inherit => builder,
+        ResolvedProxy::Direct => builder.no_proxy(),
+        ResolvedProxy::Fixed(system) => {
+            let mut builder = builder.no_proxy();
+            let no_proxy = || reqwest::NoProxy::from_string(&system.bypass.join(","));
+            if let Some(http) = system.http.as_deref().map(with_scheme)
+                && let Ok(proxy) = reqwest::Proxy::http(http)
+            {
+                builder = builder.proxy(proxy.no_proxy(no_proxy()));
+            }
+            if let Some(https) = system.https.as_deref().map(with_scheme)
+                && let Ok(proxy) = reqwest::Proxy::https(https)
+            {
+                builder = builder.proxy(proxy.no_proxy(no_proxy()));
+            }
+            builder
+        }
+        ResolvedProxy::Pac { script, .. } => builder.proxy(reqwest::Proxy::custom(move |url| {
+            evaluate(&script, url.as_str(), url.host_str()?)
+        })),
+    }
+}
+
+/// Proxy variables for a backend whose downloads go to `url`. Curl, wget
+/// and fnm all read these.
+pub fn env_vars(url: &str) -> Vec<(&'static str, String)> {
+    let direct = || vec![("NO_PROXY", "*".to_string()), ("no_proxy", "*".to_string())];
+    match current() {
+        ResolvedProxy::Inherit => Vec::new(),
+        ResolvedProxy::Direct => direct(),
+        ResolvedProxy::Fixed(system) => {
+            let mut vars = Vec::new();
+            if let Some(http) = system.http.as_deref().map(with_scheme) {
+                vars.push(("HTTP_PROXY", http.clone()));
+                vars.push(("http_proxy", http));
+            }
+            if let Some(https) = system.https.as_deref().map(with_scheme) {
+                vars.push(("HTTPS_PROXY", https.clone()));
+                vars.push(("https_proxy", https));
+            }
+            if !vars.is_empty() && !system.bypass.is_empty() {
+                vars.push(("NO_PROXY", system.bypass.join(",")));
+                vars.push(("no_proxy", system.bypass.join(",")));
+            }
+            vars
+        }
+        ResolvedProxy::Pac { script, .. } => {
+            let Ok(parsed) = reqwest::Url::parse(url) else {
+                return Vec::new();
+            };
+            let proxy = parsed
+                .host_str()
+                .and_then(|host| evaluate(&script, url, host));
+            match proxy {
+                Some(proxy) => ["HTTP_PROXY", "http_proxy", "HTTPS_PROXY", "https_proxy"]
+                    .into_iter()
+                    .map(|key| (key, proxy.clone()))
+                    .collect(),
+                None => direct(),
+            }
+        }
+    }
+}
+
+fn evaluate(script: &PacScript, url: &str, host: &str) -> Option<String> {
+    if let Some(cached) = PAC_RESULTS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_ref()
+        .and_then(|results| results.get(url))
+    {
+        return cached.clone();
+    }
+
+    // Scripts may resolve host names, which blocks. Reqwest asks for the
+    // proxy on a runtime worker, so let the runtime move its other tasks
+    // off this thread while the script runs.
+    let find = || script.find_proxy(url, host);
+    let result = match tokio::runtime::Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == tokio::runtime::RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(find)
+        }
+        _ => find(),
+    };
+    let proxy = match result {
+        Ok(result) => first_proxy(&result),
+        Err(e) => {
+            log::warn!("PAC file failed for {}: {}", host, e);
+            None
+        }
+    };
+    let mut results = PAC_RESULTS.lock().unwrap_or_else(PoisonError::into_inner);
+    let results = results.get_or_insert_with(HashMap::new);
+    if results.len() >= PAC_RESULTS_LIMIT {
+        results.clear();
+    }
+    results.insert(url.to_string(), proxy.clone());
+    proxy
+}
+
+/// System settings usually give `host:port`; reqwest and curl want a URL.
+fn with_scheme(address: &str) -> String {
+    if address.contains("://") {
+        address.to_string()
+    } else {
+        format!("http://{}", address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bare_addresses_get_a_scheme() {
+        assert_eq!(with_scheme("proxy:8080"), "http://proxy:8080");
+        assert_eq!(with_scheme("https://proxy:443"), "https://proxy:443");
+    }
+
+    #[test]
+    fn describes_split_proxies() {
+        let resolved = ResolvedProxy::Fixed(SystemProxy {
+            https: Some("secure:443".to_string()),
+            ..SystemProxy::default()
+        });
+        assert_eq!(resolved.describe(), "HTTP via direct, HTTPS via secure:443");
+    }
+
+    #[test]
+    fn path_rules_are_not_served_from_another_url() {
+        let script = PacScript::parse(
+            r#"function FindProxyForURL(url, host) {
+                if (shExpMatch(url, "*/internal/*")) return "DIRECT";
+                return "PROXY corp:8080";
+            }"#,
+        )
+        .unwrap();
+        let host = "paths.example";
+        assert_eq!(
+            evaluate(&script, "https://paths.example/public/a", host).as_deref(),
+            Some("http://corp:8080")
+        );
+        assert_eq!(
+            evaluate(&script, "https://paths.example/internal/a", host),
+            None
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn evaluates_on_a_runtime_worker() {
+        let script = PacScript::parse(
+            r#"function FindProxyForURL(url, host) {
+                if (isResolvable(host)) return "PROXY corp:8080";
+                return "DIRECT";
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            evaluate(&script, "https://127.0.0.1/", "127.0.0.1").as_deref(),
+            Some("http://corp:8080")
+        );
+    }
+}
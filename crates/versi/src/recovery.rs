@@ -0,0 +1,74 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use versi_platform::AppPaths;
+
+use crate::state::OperationRequest;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingOperations {
+    pub environment: String,
+    pub operations: Vec<OperationRequest>,
+    pub saved_at: DateTime<Utc>,
+}
+
+impl PendingOperations {
+    pub fn load() -> Option<Self> {
+        if crate::safe_mode::is_active() {
+            return None;
+        }
+        let paths = AppPaths::new().ok()?;
+        let data = std::fs::read_to_string(paths.pending_operations_file()).ok()?;
+        let pending: Self = serde_json::from_str(&data).ok()?;
+        (!pending.operations.is_empty()).then_some(pending)
+    }
+
+    pub fn save(&self) {
+        if crate::safe_mode::is_active() {
+            return;
+        }
+        let Ok(paths) = AppPaths::new() else {
+            return;
+        };
+        let _ = paths.ensure_dirs();
+        if let Ok(data) = serde_json::to_string(self) {
+            let _ = std::fs::write(paths.pending_operations_file(), data);
+        }
+    }
+
+    pub fn clear() {
+        if crate::safe_mode::is_active() {
+            return;
+        }
+        if let Ok(paths) = AppPaths::new() {
+            let _ = std::fs::remove_file(paths.pending_operations_file());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_operation_requests() {
+        let pending = PendingOperations {
+            environment: "native".to_string(),
+            operations: vec![
+                OperationRequest::Install {
+                    version: "v22.1.0".to_string(),
+                },
+                OperationRequest::Uninstall {
+                    version: "v18.0.0".to_string(),
+                },
+            ],
+            saved_at: Utc::now(),
+        };
+
+        let json = serde_json::to_string(&pending).unwrap();
+        assert!(json.contains(r#""kind":"install""#));
+
+        let parsed: PendingOperations = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.environment, "native");
+        assert_eq!(parsed.operations, pending.operations);
+    }
+}
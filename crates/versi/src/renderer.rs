@@ -0,0 +1,155 @@
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use versi_platform::AppPaths;
+
+use crate::settings::RendererSetting;
+
+const SOFTWARE_FLAG: &str = "--software-rendering";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SelectionReason {
+    Settings,
+    CommandLine,
+    EnvironmentVariable,
+    PreviousLaunchFailed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RendererSelection {
+    pub renderer: RendererSetting,
+    pub reason: SelectionReason,
+}
+
+impl RendererSelection {
+    pub fn describe(&self) -> String {
+        let reason = match self.reason {
+            SelectionReason::Settings => return self.renderer.label().to_string(),
+            SelectionReason::CommandLine => SOFTWARE_FLAG,
+            SelectionReason::EnvironmentVariable => "ICED_BACKEND",
+            SelectionReason::PreviousLaunchFailed => "previous launch failed to render",
+        };
+        format!("{} ({})", self.renderer.label(), reason)
+    }
+}
+
+static SELECTION: OnceLock<RendererSelection> = OnceLock::new();
+static CONFIRMED: AtomicBool = AtomicBool::new(false);
+
+/// Picks the renderer for this launch from the command line, `ICED_BACKEND`,
+/// a leftover startup probe, or the saved setting, in that order.
+pub fn select(configured: RendererSetting) -> RendererSelection {
+    let probe_left_over = probe_file().is_some_and(|path| path.exists());
+    let selection = choose(
+        std::env::args().any(|arg| arg == SOFTWARE_FLAG),
+        std::env::var("ICED_BACKEND").ok().as_deref(),
+        probe_left_over,
+        configured,
+    );
+    let _ = SELECTION.set(selection);
+    selection
+}
+
+fn choose(
+    software_flag: bool,
+    env_backend: Option<&str>,
+    probe_left_over: bool,
+    configured: RendererSetting,
+) -> RendererSelection {
+    if software_flag {
+        return RendererSelection {
+            renderer: RendererSetting::Software,
+            reason: SelectionReason::CommandLine,
+        };
+    }
+    if let Some(backend) = env_backend.filter(|b| !b.trim().is_empty()) {
+        let renderer = match backend.split(',').next().map(str::trim) {
+            Some("tiny-skia") => RendererSetting::Software,
+            Some("wgpu") => RendererSetting::Gpu,
+            _ => RendererSetting::Auto,
+        };
+        return RendererSelection {
+            renderer,
+            reason: SelectionReason::EnvironmentVariable,
+        };
+    }
+    if probe_left_over && configured != RendererSetting::Software {
+        return RendererSelection {
+            renderer: RendererSetting::Software,
+            reason: SelectionReason::PreviousLaunchFailed,
+        };
+    }
+    RendererSelection {
+        renderer: configured,
+        reason: SelectionReason::Settings,
+    }
+}
+
+/// Exports the selection to iced and, for GPU renderers, leaves a probe file
+/// behind that `confirm_working` removes once the window has rendered.
+///
+/// Must run before any other thread is spawned.
+pub fn apply(selection: RendererSelection) {
+    if selection.reason != SelectionReason::EnvironmentVariable
+        && let Some(backend) = selection.renderer.iced_backend()
+    {
+        // SAFETY: called from `main` before the tray, logging, or iced spawn threads.
+        unsafe { std::env::set_var("ICED_BACKEND", backend) };
+    }
+
+    if selection.renderer != RendererSetting::Software
+        && let Some(path) = probe_file()
+    {
+        let _ = std::fs::write(path, env!("CARGO_PKG_VERSION"));
+    }
+}
+
+/// Clears the startup probe once the window has been up for a tick.
+pub fn confirm_working() {
+    if CONFIRMED.swap(true, Ordering::Relaxed) {
+        return;
+    }
+    if let Some(path) = probe_file() {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+pub fn current() -> Option<RendererSelection> {
+    SELECTION.get().copied()
+}
+
+fn probe_file() -> Option<std::path::PathBuf> {
+    let paths = AppPaths::new().ok()?;
+    let _ = paths.ensure_dirs();
+    Some(paths.renderer_probe_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_line_flag_wins() {
+        let selection = choose(true, Some("wgpu"), false, RendererSetting::Gpu);
+        assert_eq!(selection.renderer, RendererSetting::Software);
+        assert_eq!(selection.reason, SelectionReason::CommandLine);
+    }
+
+    #[test]
+    fn respects_iced_backend_variable() {
+        let selection = choose(false, Some("tiny-skia,wgpu"), true, RendererSetting::Auto);
+        assert_eq!(selection.renderer, RendererSetting::Software);
+        assert_eq!(selection.reason, SelectionReason::EnvironmentVariable);
+    }
+
+    #[test]
+    fn falls_back_to_software_after_failed_launch() {
+        let selection = choose(false, None, true, RendererSetting::Auto);
+        assert_eq!(selection.renderer, RendererSetting::Software);
+        assert_eq!(selection.reason, SelectionReason::PreviousLaunchFailed);
+
+        let selection = choose(false, None, false, RendererSetting::Gpu);
+        assert_eq!(selection.renderer, RendererSetting::Gpu);
+        assert_eq!(selection.reason, SelectionReason::Settings);
+    }
+}
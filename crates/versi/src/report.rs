@@ -0,0 +1,369 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use versi_backend::NodeVersion;
+use versi_core::ReleaseSchedule;
+
+use crate::state::EnvironmentState;
+use crate::widgets::helpers::format_bytes;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportFormat {
+    Json,
+    Csv,
+    Markdown,
+}
+
+impl ReportFormat {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Json => "JSON",
+            Self::Csv => "CSV",
+            Self::Markdown => "Markdown",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Csv => "csv",
+            Self::Markdown => "md",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SupportStatus {
+    Active,
+    EndOfLife,
+    Unknown,
+}
+
+impl SupportStatus {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::EndOfLife => "eol",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionReport {
+    pub version: String,
+    pub is_default: bool,
+    pub lts: bool,
+    pub lts_codename: Option<String>,
+    pub status: SupportStatus,
+    pub end_of_life: Option<NaiveDate>,
+    pub disk_size_bytes: Option<u64>,
+    pub installed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EnvironmentReport {
+    pub environment: String,
+    pub backend: String,
+    pub backend_version: Option<String>,
+    pub default_version: Option<String>,
+    pub versions: Vec<VersionReport>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub generated_at: DateTime<Utc>,
+    pub versi_version: String,
+    pub environments: Vec<EnvironmentReport>,
+}
+
+impl Report {
+    pub fn build(
+        environments: &[EnvironmentState],
+        schedule: Option<&ReleaseSchedule>,
+        generated_at: DateTime<Utc>,
+    ) -> Self {
+        let today = generated_at.date_naive();
+        let environments = environments
+            .iter()
+            .filter(|env| env.available)
+            .map(|env| EnvironmentReport {
+                environment: env.name.clone(),
+                backend: env.backend_name.to_string(),
+                backend_version: env.backend_version.clone(),
+                default_version: env.default_version.as_ref().map(|v| v.to_string()),
+                versions: env
+                    .installed_versions
+                    .iter()
+                    .map(|installed| {
+                        let major = installed.version.major;
+                        let end_of_life = schedule.and_then(|s| s.end_date(major));
+                        let status = match (schedule, end_of_life) {
+                            (_, Some(end)) if end <= today => SupportStatus::EndOfLife,
+                            (_, Some(_)) => SupportStatus::Active,
+                            (Some(s), None) if s.is_active(major) => SupportStatus::Active,
+                            _ => SupportStatus::Unknown,
+                        };
+                        VersionReport {
+                            version: installed.version.to_string(),
+                            is_default: installed.is_default,
+                            lts: installed.lts_codename.is_some()
+                                || schedule.is_some_and(|s| s.is_lts(major)),
+                            lts_codename: installed.lts_codename.clone(),
+                            status,
+                            end_of_life,
+                            disk_size_bytes: installed.disk_size,
+                            installed_at: installed.install_date,
+                        }
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Self {
+            generated_at,
+            versi_version: env!("CARGO_PKG_VERSION").to_string(),
+            environments,
+        }
+    }
+
+    pub fn render(&self, format: ReportFormat) -> Result<String, String> {
+        match format {
+            ReportFormat::Json => serde_json::to_string_pretty(self).map_err(|e| e.to_string()),
+            ReportFormat::Csv => Ok(self.to_csv()),
+            ReportFormat::Markdown => Ok(self.to_markdown()),
+        }
+    }
+
+    fn to_csv(&self) -> String {
+        let mut out = String::from(
+            "environment,backend,backend_version,version,default,lts,status,end_of_life,disk_size_bytes,installed_at\n",
+        );
+        for env in &self.environments {
+            for v in &env.versions {
+                let fields = [
+                    env.environment.clone(),
+                    env.backend.clone(),
+                    env.backend_version.clone().unwrap_or_default(),
+                    v.version.clone(),
+                    v.is_default.to_string(),
+                    v.lts.to_string(),
+                    v.status.label().to_string(),
+                    v.end_of_life.map(|d| d.to_string()).unwrap_or_default(),
+                    v.disk_size_bytes.map(|s| s.to_string()).unwrap_or_default(),
+                    v.installed_at.map(|d| d.to_rfc3339()).unwrap_or_default(),
+                ];
+                let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+                out.push_str(&row.join(","));
+                out.push('\n');
+            }
+        }
+        out
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "# Node.js Versions Report\n\nGenerated {} by Versi {}\n",
+            self.generated_at.format("%Y-%m-%d %H:%M UTC"),
+            self.versi_version
+        );
+        for env in &self.environments {
+            out.push_str(&format!(
+                "\n## {}\n\n- Backend: {} {}\n- Default: {}\n\n",
+                env.environment,
+                env.backend,
+                env.backend_version
+                    .as_deref()
+                    .unwrap_or("(unknown version)"),
+                env.default_version.as_deref().unwrap_or("none"),
+            ));
+            if env.versions.is_empty() {
+                out.push_str("No versions installed.\n");
+                continue;
+            }
+            out.push_str("| Version | Default | LTS | Status | End of Life | Disk Size |\n");
+            out.push_str("| --- | --- | --- | --- | --- | --- |\n");
+            for v in &env.versions {
+                out.push_str(&format!(
+                    "| {} | {} | {} | {} | {} | {} |\n",
+                    markdown_cell(&v.version),
+                    if v.is_default { "yes" } else { "" },
+                    markdown_cell(v.lts_codename.as_deref().unwrap_or(if v.lts {
+                        "yes"
+                    } else {
+                        ""
+                    })),
+                    v.status.label(),
+                    v.end_of_life.map(|d| d.to_string()).unwrap_or_default(),
+                    v.disk_size_bytes.map(format_bytes).unwrap_or_default(),
+                ));
+            }
+        }
+        out
+    }
+}
+
+/// A few lines on one environment to paste into team chat: its backend,
+/// installed versions with the default marked, and newer releases of each
+/// installed major.
+pub fn environment_summary(
+    env: &EnvironmentState,
+    latest_by_major: &HashMap<u32, NodeVersion>,
+) -> String {
+    let mut lines = vec![format!(
+        "{} \u{2014} {} {}",
+        env.name,
+        env.backend_name,
+        env.backend_version
+            .as_deref()
+            .unwrap_or("(unknown version)")
+    )];
+    if env.version_groups.is_empty() {
+        lines.push("No versions installed".to_string());
+    }
+    for group in &env.version_groups {
+        let update = latest_by_major
+            .get(&group.major)
+            .filter(|latest| group.versions.first().is_some_and(|v| *latest > &v.version));
+        for (idx, installed) in group.versions.iter().enumerate() {
+            let mut line = format!("  {}", installed.version);
+            if installed.is_default {
+                line.push_str(" (default)");
+            }
+            if let Some(latest) = update.filter(|_| idx == 0) {
+                line.push_str(&format!(" \u{2192} {} available", latest));
+            }
+            lines.push(line);
+        }
+    }
+    let updates = env.majors_with_updates(latest_by_major);
+    if updates > 0 {
+        lines.push(format!(
+            "{} {} with updates available",
+            updates,
+            if updates == 1 { "major" } else { "majors" }
+        ));
+    }
+    lines.join("\n")
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Keeps a value inside its Markdown table cell: pipes are escaped and line
+/// breaks, which would end the row, become spaces.
+fn markdown_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use versi_backend::InstalledVersion;
+    use versi_platform::EnvironmentId;
+
+    fn sample_environment() -> EnvironmentState {
+        let mut env = EnvironmentState::new(EnvironmentId::Native, "fnm", Some("1.38.1".into()));
+        env.update_versions(vec![
+            InstalledVersion {
+                version: NodeVersion::new(22, 11, 0),
+                is_default: true,
+                lts_codename: Some("Jod".into()),
+                install_date: None,
+                disk_size: Some(2048),
+            },
+            InstalledVersion {
+                version: NodeVersion::new(16, 20, 2),
+                is_default: false,
+                lts_codename: Some("Gallium".into()),
+                install_date: None,
+                disk_size: None,
+            },
+        ]);
+        env
+    }
+
+    fn sample_report() -> Report {
+        let env = sample_environment();
+        let schedule: ReleaseSchedule = serde_json::from_str(
+            r#"{"versions": {
+                "16": {"start": "2021-04-20", "lts": "2021-10-26", "end": "2023-09-11"},
+                "22": {"start": "2024-04-24", "lts": "2024-10-29", "end": "2027-04-30"}
+            }}"#,
+        )
+        .unwrap();
+
+        let now = DateTime::parse_from_rfc3339("2025-01-15T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        Report::build(&[env], Some(&schedule), now)
+    }
+
+    #[test]
+    fn build_classifies_support_status() {
+        let report = sample_report();
+        let versions = &report.environments[0].versions;
+        assert_eq!(
+            report.environments[0].default_version.as_deref(),
+            Some("v22.11.0")
+        );
+        assert_eq!(versions[0].status, SupportStatus::Active);
+        assert_eq!(versions[1].status, SupportStatus::EndOfLife);
+    }
+
+    #[test]
+    fn csv_has_row_per_version() {
+        let csv = sample_report().render(ReportFormat::Csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].contains("v22.11.0,true,true,active,2027-04-30,2048"));
+    }
+
+    #[test]
+    fn summary_marks_default_and_updates() {
+        let env = sample_environment();
+        let latest = HashMap::from([
+            (22, NodeVersion::new(22, 12, 0)),
+            (16, NodeVersion::new(16, 20, 2)),
+        ]);
+        let summary = environment_summary(&env, &latest);
+        let lines: Vec<&str> = summary.lines().collect();
+        assert_eq!(
+            &lines[1..],
+            [
+                "  v22.11.0 (default) \u{2192} v22.12.0 available",
+                "  v16.20.2",
+                "1 major with updates available",
+            ]
+        );
+        assert!(lines[0].ends_with("fnm 1.38.1"));
+    }
+
+    #[test]
+    fn csv_field_quotes_special_characters() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("WSL: Ubuntu, 22"), "\"WSL: Ubuntu, 22\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn markdown_cells_escape_pipes() {
+        assert_eq!(markdown_cell("Jod"), "Jod");
+        assert_eq!(markdown_cell("a|b"), "a\\|b");
+        assert_eq!(markdown_cell("two\nlines"), "two lines");
+
+        let mut report = sample_report();
+        report.environments[0].versions[0].lts_codename = Some("Iron|Jod".to_string());
+        let markdown = report.render(ReportFormat::Markdown).unwrap();
+        let row = markdown.lines().find(|l| l.contains("Iron")).unwrap();
+        assert!(row.contains("Iron\\|Jod"));
+        assert_eq!(row.matches(" | ").count(), 5);
+    }
+}
@@ -0,0 +1,170 @@
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use versi_platform::AppPaths;
+
+use crate::cache::DiskCache;
+use crate::recovery::PendingOperations;
+use crate::settings::AppSettings;
+
+/// Launch flag that starts Versi in safe mode.
+pub const SAFE_MODE_FLAG: &str = "--safe-mode";
+
+/// A state file that could not be read at launch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorruptFile {
+    pub name: &'static str,
+    pub path: PathBuf,
+    pub error: String,
+}
+
+/// Why this launch runs in safe mode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SafeMode {
+    /// How safe mode was asked for, when it was.
+    pub requested: Option<&'static str>,
+    pub corrupt: Vec<CorruptFile>,
+    /// Where the corrupted files were moved once reset.
+    pub backups: Vec<PathBuf>,
+}
+
+static SAFE_MODE: OnceLock<SafeMode> = OnceLock::new();
+
+/// While set, settings and caches load as defaults and nothing is written
+/// over the files on disk.
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Decides whether this launch runs in safe mode: from the command line, a
+/// held Shift key, or state files that no longer parse. Call once, after
+/// the data directories are known.
+pub fn detect() -> Option<&'static SafeMode> {
+    let requested = if std::env::args().any(|arg| arg == SAFE_MODE_FLAG) {
+        Some(SAFE_MODE_FLAG)
+    } else if shift_held() {
+        Some("Shift held at launch")
+    } else {
+        None
+    };
+    let corrupt = AppPaths::new()
+        .map(|paths| corrupt_files(&paths))
+        .unwrap_or_default();
+    if requested.is_none() && corrupt.is_empty() {
+        return None;
+    }
+
+    ACTIVE.store(true, Ordering::SeqCst);
+    Some(SAFE_MODE.get_or_init(|| SafeMode {
+        requested,
+        corrupt,
+        backups: Vec::new(),
+    }))
+}
+
+/// The safe mode this launch started in.
+pub fn current() -> Option<&'static SafeMode> {
+    SAFE_MODE.get()
+}
+
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::SeqCst)
+}
+
+/// Moves each corrupted file aside and lets Versi write fresh ones.
+/// Returns where the files were moved.
+pub fn back_up_and_reset(files: &[CorruptFile]) -> Result<Vec<PathBuf>, String> {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let mut backups = Vec::with_capacity(files.len());
+    for file in files {
+        let backup = backup_path(&file.path, &stamp.to_string());
+        if file.path.exists() {
+            std::fs::rename(&file.path, &backup)
+                .map_err(|e| format!("Failed to back up {}: {e}", file.path.display()))?;
+        }
+        backups.push(backup);
+    }
+    ACTIVE.store(false, Ordering::SeqCst);
+    Ok(backups)
+}
+
+fn backup_path(path: &Path, stamp: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".corrupt-{stamp}"));
+    path.with_file_name(name)
+}
+
+/// Checks that a state file's contents still parse.
+type Parser = fn(&str) -> Result<(), String>;
+
+fn corrupt_files(paths: &AppPaths) -> Vec<CorruptFile> {
+    let checks: [(&'static str, PathBuf, Parser); 3] = [
+        ("Settings", paths.settings_file(), |content| {
+            AppSettings::parse(content).map(|_| ())
+        }),
+        ("Version cache", paths.version_cache_file(), |content| {
+            serde_json::from_str::<DiskCache>(content)
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        }),
+        (
+            "Unfinished operations",
+            paths.pending_operations_file(),
+            |content| {
+                serde_json::from_str::<PendingOperations>(content)
+                    .map(|_| ())
+                    .map_err(|e| e.to_string())
+            },
+        ),
+    ];
+
+    checks
+        .into_iter()
+        .filter_map(|(name, path, parse)| {
+            let error = match std::fs::read_to_string(&path) {
+                Ok(content) => parse(&content).err()?,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+                Err(e) => e.to_string(),
+            };
+            Some(CorruptFile { name, path, error })
+        })
+        .collect()
+}
+
+#[cfg(windows)]
+fn shift_held() -> bool {
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{GetAsyncKeyState, VK_SHIFT};
+    // The high bit is set while the key is down.
+    unsafe { GetAsyncKeyState(i32::from(VK_SHIFT)) < 0 }
+}
+
+#[cfg(target_os = "macos")]
+fn shift_held() -> bool {
+    const COMBINED_SESSION_STATE: i32 = 0;
+    const SHIFT_MASK: u64 = 0x0002_0000;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    unsafe extern "C" {
+        fn CGEventSourceFlagsState(state_id: i32) -> u64;
+    }
+    unsafe { CGEventSourceFlagsState(COMBINED_SESSION_STATE) & SHIFT_MASK != 0 }
+}
+
+/// Key state isn't readable before a window exists on Linux desktops, so
+/// only the launch flag starts safe mode there.
+#[cfg(not(any(windows, target_os = "macos")))]
+fn shift_held() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backups_keep_the_original_name() {
+        assert_eq!(
+            backup_path(Path::new("/data/settings.json"), "20260301-120000"),
+            PathBuf::from("/data/settings.json.corrupt-20260301-120000")
+        );
+    }
+}
@@ -0,0 +1,293 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Local, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use versi_backend::NodeVersion;
+use versi_core::ReleaseSchedule;
+
+use crate::state::{EnvironmentState, OperationRequest};
+
+/// How long the user must have been away before a "when idle" job starts.
+pub const IDLE_THRESHOLD: Duration = Duration::from_secs(10 * 60);
+
+/// How often due jobs are checked for.
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A bulk operation waiting to run unattended. Jobs run once and are then
+/// removed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledJob {
+    pub id: u64,
+    pub task: ScheduledTask,
+    pub trigger: ScheduleTrigger,
+    /// Settings key of the environment the job applies to.
+    pub environment: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduledTask {
+    UpdateMajors,
+    UninstallEol,
+    RemoveOldPatches,
+}
+
+impl ScheduledTask {
+    pub const ALL: [Self; 3] = [
+        Self::UpdateMajors,
+        Self::UninstallEol,
+        Self::RemoveOldPatches,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::UpdateMajors => "Update all majors",
+            Self::UninstallEol => "Remove end-of-life versions",
+            Self::RemoveOldPatches => "Remove older patches",
+        }
+    }
+
+    /// Whether the data the plan depends on has loaded, so a job does not
+    /// run, and get used up, while it would find nothing to do.
+    pub fn is_ready(
+        self,
+        env: &EnvironmentState,
+        latest_by_major: &HashMap<u32, NodeVersion>,
+        schedule: Option<&ReleaseSchedule>,
+    ) -> bool {
+        !env.loading
+            && match self {
+                Self::UpdateMajors => !latest_by_major.is_empty(),
+                Self::UninstallEol => schedule.is_some(),
+                Self::RemoveOldPatches => true,
+            }
+    }
+
+    /// Operations that carry out the task in `env`. Unattended removals
+    /// never touch the default version.
+    pub fn plan(
+        self,
+        env: &EnvironmentState,
+        latest_by_major: &HashMap<u32, NodeVersion>,
+        schedule: Option<&ReleaseSchedule>,
+    ) -> Vec<OperationRequest> {
+        let is_default = |version: &NodeVersion| env.default_version.as_ref() == Some(version);
+
+        match self {
+            Self::UpdateMajors => env
+                .version_groups
+                .iter()
+                .filter_map(|group| {
+                    let installed = group.versions.iter().map(|v| &v.version).max()?;
+                    latest_by_major
+                        .get(&group.major)
+                        .filter(|latest| *latest > installed)
+                        .map(|latest| OperationRequest::Install {
+                            version: latest.to_string(),
+                        })
+                })
+                .collect(),
+            Self::UninstallEol => env
+                .installed_versions
+                .iter()
+                .filter(|v| schedule.is_some_and(|s| !s.is_active(v.version.major)))
+                .filter(|v| !is_default(&v.version))
+                .map(|v| OperationRequest::Uninstall {
+                    version: v.version.to_string(),
+                })
+                .collect(),
+            Self::RemoveOldPatches => env
+                .version_groups
+                .iter()
+                .flat_map(|group| {
+                    let latest = group.versions.iter().map(|v| &v.version).max();
+                    group
+                        .versions
+                        .iter()
+                        .filter(move |v| Some(&v.version) != latest)
+                })
+                .filter(|v| !is_default(&v.version))
+                .map(|v| OperationRequest::Uninstall {
+                    version: v.version.to_string(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ScheduleTrigger {
+    At {
+        time: DateTime<Utc>,
+    },
+    /// Once nobody has used the machine for [`IDLE_THRESHOLD`] and it is
+    /// not running on battery.
+    WhenIdle,
+}
+
+impl ScheduleTrigger {
+    pub fn is_due(&self, now: DateTime<Utc>, conditions: &SystemConditions) -> bool {
+        match self {
+            Self::At { time } => now >= *time,
+            Self::WhenIdle => {
+                conditions.idle.is_some_and(|idle| idle >= IDLE_THRESHOLD)
+                    && conditions.on_ac_power != Some(false)
+            }
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Self::At { time } => time.with_timezone(&Local).format("at %a %H:%M").to_string(),
+            Self::WhenIdle => "when idle on AC power".to_string(),
+        }
+    }
+}
+
+/// Idle time and power source, read from the system on each check.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SystemConditions {
+    pub idle: Option<Duration>,
+    pub on_ac_power: Option<bool>,
+}
+
+impl SystemConditions {
+    pub async fn read() -> Self {
+        tokio::task::spawn_blocking(|| Self {
+            idle: versi_platform::system_idle_time(),
+            on_ac_power: versi_platform::on_ac_power(),
+        })
+        .await
+        .unwrap_or_default()
+    }
+}
+
+/// Job being entered in settings.
+#[derive(Debug, Clone)]
+pub struct ScheduleDraft {
+    pub task: ScheduledTask,
+    pub when_idle: bool,
+    pub time: String,
+}
+
+impl Default for ScheduleDraft {
+    fn default() -> Self {
+        Self {
+            task: ScheduledTask::UpdateMajors,
+            when_idle: false,
+            time: "03:00".to_string(),
+        }
+    }
+}
+
+impl ScheduleDraft {
+    pub fn trigger(&self, now: DateTime<Local>) -> Option<ScheduleTrigger> {
+        if self.when_idle {
+            return Some(ScheduleTrigger::WhenIdle);
+        }
+        next_occurrence(&self.time, now).map(|time| ScheduleTrigger::At { time })
+    }
+}
+
+/// The next time the clock shows `input` (`HH:MM`), today or tomorrow.
+pub fn next_occurrence(input: &str, now: DateTime<Local>) -> Option<DateTime<Utc>> {
+    let time = NaiveTime::parse_from_str(input.trim(), "%H:%M").ok()?;
+    let today = now.date_naive().and_time(time);
+    let date = if today > now.naive_local() {
+        today
+    } else {
+        today + chrono::Duration::days(1)
+    };
+    // Around DST changes the wall-clock time may be skipped or repeated.
+    date.and_local_timezone(Local)
+        .earliest()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use versi_backend::InstalledVersion;
+    use versi_platform::EnvironmentId;
+
+    fn environment(installed: &[&str], default: Option<&str>) -> EnvironmentState {
+        let mut env = EnvironmentState::new(EnvironmentId::Native, "fnm", None);
+        env.update_versions(
+            installed
+                .iter()
+                .map(|v| InstalledVersion {
+                    version: v.parse().unwrap(),
+                    is_default: Some(*v) == default,
+                    lts_codename: None,
+                    install_date: None,
+                    disk_size: None,
+                })
+                .collect(),
+        );
+        env
+    }
+
+    fn versions(requests: &[OperationRequest]) -> Vec<&str> {
+        requests.iter().map(OperationRequest::version).collect()
+    }
+
+    #[test]
+    fn plans_updates_and_cleanups() {
+        let env = environment(
+            &["v20.10.0", "v20.11.0", "v22.0.0", "v22.1.0"],
+            Some("v22.0.0"),
+        );
+        let latest_by_major = HashMap::from([
+            (20, "v20.12.2".parse().unwrap()),
+            (22, "v22.1.0".parse().unwrap()),
+        ]);
+
+        assert_eq!(
+            versions(&ScheduledTask::UpdateMajors.plan(&env, &latest_by_major, None)),
+            vec!["v20.12.2"]
+        );
+        assert_eq!(
+            versions(&ScheduledTask::RemoveOldPatches.plan(&env, &latest_by_major, None)),
+            vec!["v20.10.0"]
+        );
+        assert!(
+            ScheduledTask::UninstallEol
+                .plan(&env, &latest_by_major, None)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn idle_trigger_needs_idle_time_and_mains_power() {
+        let now = Utc::now();
+        let idle = |minutes: u64, on_ac_power: Option<bool>| SystemConditions {
+            idle: Some(Duration::from_secs(minutes * 60)),
+            on_ac_power,
+        };
+
+        assert!(ScheduleTrigger::WhenIdle.is_due(now, &idle(15, Some(true))));
+        assert!(ScheduleTrigger::WhenIdle.is_due(now, &idle(15, None)));
+        assert!(!ScheduleTrigger::WhenIdle.is_due(now, &idle(15, Some(false))));
+        assert!(!ScheduleTrigger::WhenIdle.is_due(now, &idle(2, Some(true))));
+        assert!(!ScheduleTrigger::WhenIdle.is_due(now, &SystemConditions::default()));
+    }
+
+    #[test]
+    fn next_occurrence_rolls_over_to_tomorrow() {
+        let now = Local.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+
+        let later = next_occurrence("18:30", now).unwrap().with_timezone(&Local);
+        assert_eq!(later.format("%d %H:%M").to_string(), "10 18:30");
+
+        let earlier = next_occurrence(" 03:00 ", now)
+            .unwrap()
+            .with_timezone(&Local);
+        assert_eq!(earlier.format("%d %H:%M").to_string(), "11 03:00");
+
+        assert_eq!(next_occurrence("25:00", now), None);
+        assert_eq!(next_occurrence("soon", now), None);
+    }
+}
@@ -1,13 +1,48 @@
+use chrono::NaiveTime;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::PathBuf;
-use versi_platform::AppPaths;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use versi_backend::BackendUpdateChannel;
+use versi_core::{BackoffStrategy, RetryPolicy};
+use versi_fnm::FnmInstallMethod;
+use versi_platform::{
+    AppPaths, CustomDirs, EnvironmentId, Libc, MonitorBounds, PlatformTarget,
+    UNOFFICIAL_BUILDS_MIRROR, WindowPlacement, clamp_to_monitors,
+};
+
+use crate::banner_state::BannerStates;
+use crate::eol::WarningAcknowledgement;
+use crate::safe_mode;
+use crate::scheduler::ScheduledJob;
+use crate::settings_journal;
+use crate::settings_sync;
+use crate::settings_watch;
+use crate::update_deferral::UpdateDeferral;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppSettings {
     #[serde(default)]
     pub theme: ThemeSetting,
 
+    #[serde(default)]
+    pub theme_schedule: ThemeSchedule,
+
+    #[serde(default)]
+    pub versions_layout: VersionsLayout,
+
+    #[serde(default)]
+    pub version_grouping: VersionGrouping,
+
+    #[serde(default)]
+    pub list_density: ListDensity,
+
+    #[serde(default)]
+    pub startup_view: StartupView,
+
+    /// Settings key of the environment tab selected when Versi opens.
+    #[serde(default)]
+    pub startup_environment: Option<String>,
+
     #[serde(default = "default_cache_ttl")]
     pub cache_ttl_hours: u64,
 
@@ -17,24 +52,116 @@ pub struct AppSettings {
     #[serde(default)]
     pub start_minimized: bool,
 
+    #[serde(default)]
+    pub close_behavior: Option<CloseBehavior>,
+
+    #[serde(default)]
+    pub read_only: bool,
+
     #[serde(default)]
     pub fnm_dir: Option<PathBuf>,
 
+    /// fnm binary to use when it isn't on `PATH` or in a known prefix.
+    #[serde(default)]
+    pub fnm_binary: Option<PathBuf>,
+
+    /// How onboarding installed fnm, so update notices can say how to
+    /// upgrade it.
+    #[serde(default)]
+    pub fnm_install_method: Option<FnmInstallMethod>,
+
+    /// Overrides the `NVM_DIR` nvm is detected in and run with.
+    #[serde(default)]
+    pub nvm_dir: Option<PathBuf>,
+
+    /// Installs versions into a directory shared by every user, for backends
+    /// that support it.
+    #[serde(default)]
+    pub system_wide: bool,
+
+    /// Leaves variables another version manager set up in the shell Versi
+    /// was started from out of backend commands.
+    #[serde(default = "default_true")]
+    pub sanitize_backend_env: bool,
+
+    /// Keeps the machine awake while installs and other operations run.
+    #[serde(default = "default_true")]
+    pub prevent_sleep: bool,
+
+    /// Project directory whose version file Versi follows, set from the
+    /// settings or by a shell hook over the control socket.
+    #[serde(default)]
+    pub followed_directory: Option<PathBuf>,
+
+    /// Where the version and release caches are kept instead of the default
+    /// cache directory.
+    #[serde(default)]
+    pub custom_cache_dir: Option<PathBuf>,
+
+    /// Where `debug.log` is written instead of the data directory.
+    #[serde(default)]
+    pub custom_log_dir: Option<PathBuf>,
+
+    /// File, such as one in a Dropbox or OneDrive folder, whose shared
+    /// settings are kept the same on every machine that uses it.
+    #[serde(default)]
+    pub sync_file: Option<PathBuf>,
+
     #[serde(default)]
     pub node_dist_mirror: Option<String>,
 
+    #[serde(default)]
+    pub ca_bundle_path: Option<PathBuf>,
+
+    #[serde(default)]
+    pub proxy_mode: ProxyMode,
+
+    /// PAC file to use instead of the one the system points to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pac_url: Option<String>,
+
+    /// Personal access token for GitHub API requests, raising the rate limit
+    /// on shared networks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub github_token: Option<String>,
+
     #[serde(default)]
     pub backend_shell_options: HashMap<String, ShellOptions>,
 
     #[serde(default, skip_serializing)]
     shell_options: Option<ShellOptions>,
 
+    /// Which releases of each backend count as updates, by backend name.
+    #[serde(default)]
+    pub backend_update_channels: HashMap<String, BackendUpdateChannel>,
+
     #[serde(default)]
     pub preferred_backend: Option<String>,
 
     #[serde(default)]
     pub debug_logging: bool,
 
+    #[serde(default)]
+    pub show_event_log: bool,
+
+    #[serde(default)]
+    pub renderer: RendererSetting,
+
+    #[serde(default)]
+    pub always_on_top: bool,
+
+    #[serde(default)]
+    pub remember_workspace: bool,
+
+    #[serde(default)]
+    pub window_vibrancy: bool,
+
+    #[serde(default)]
+    pub ipc_server: bool,
+
+    #[serde(default)]
+    pub jetbrains_sync: bool,
+
     #[serde(default)]
     pub window_geometry: Option<WindowGeometry>,
 
@@ -56,6 +183,10 @@ pub struct AppSettings {
     #[serde(default = "default_toast_timeout")]
     pub toast_timeout_secs: u64,
 
+    /// How each kind of notification is shown.
+    #[serde(default)]
+    pub notification_delivery: NotificationPrefs,
+
     #[serde(default = "default_max_visible_toasts")]
     pub max_visible_toasts: usize,
 
@@ -68,8 +199,71 @@ pub struct AppSettings {
     #[serde(default = "default_max_log_size_bytes")]
     pub max_log_size_bytes: u64,
 
-    #[serde(default = "default_retry_delays")]
-    pub retry_delays_secs: Vec<u64>,
+    #[serde(default)]
+    pub retry_policy: RetryPolicy,
+
+    /// Waits before each attempt, from before `retry_policy`.
+    #[serde(default, skip_serializing)]
+    retry_delays_secs: Option<Vec<u64>>,
+
+    #[serde(default)]
+    pub environment_variables: HashMap<String, Vec<EnvVarOverride>>,
+
+    #[serde(default)]
+    pub search_history: HashMap<String, Vec<String>>,
+
+    #[serde(default)]
+    pub saved_filters: Vec<String>,
+
+    #[serde(default)]
+    pub major_notes: BTreeMap<u32, String>,
+
+    /// Versions per environment that bulk and scheduled removals skip and
+    /// that need an extra confirmation to uninstall.
+    #[serde(default)]
+    pub protected_versions: HashMap<String, BTreeSet<String>>,
+
+    /// Last default version seen per environment, to notice when it is
+    /// removed outside Versi.
+    #[serde(default)]
+    pub known_defaults: HashMap<String, String>,
+
+    #[serde(default)]
+    pub scheduled_jobs: Vec<ScheduledJob>,
+
+    /// Also warn about majors that entered maintenance, not just end-of-life.
+    #[serde(default)]
+    pub eol_strict: bool,
+
+    #[serde(default)]
+    pub eol_grace_days: u32,
+
+    /// Days before a major's end-of-life date that its badges start
+    /// counting down.
+    #[serde(default = "default_eol_countdown_days")]
+    pub eol_countdown_days: u32,
+
+    #[serde(default)]
+    pub eol_acknowledgements: BTreeMap<u32, WarningAcknowledgement>,
+
+    /// Contextual banners snoozed or dismissed.
+    #[serde(default)]
+    pub banner_states: BannerStates,
+
+    /// App updates put off with "Later" or "Skip".
+    #[serde(default)]
+    pub update_deferral: UpdateDeferral,
+
+    /// Whether commands a project defines, like its test scripts, may run,
+    /// as answered per project directory.
+    #[serde(default)]
+    pub project_trust: BTreeMap<PathBuf, bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EnvVarOverride {
+    pub key: String,
+    pub value: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -138,23 +332,89 @@ fn default_max_log_size_bytes() -> u64 {
     5 * 1024 * 1024
 }
 
-fn default_retry_delays() -> Vec<u64> {
-    vec![0, 2, 5, 15]
+fn default_eol_countdown_days() -> u32 {
+    180
+}
+
+/// A group of settings that can be reset to defaults together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsSection {
+    Appearance,
+    Tray,
+    ShellOptions,
+    Advanced,
+}
+
+impl SettingsSection {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Appearance => "Appearance",
+            Self::Tray => "System Tray",
+            Self::ShellOptions => "Shell Options",
+            Self::Advanced => "Advanced",
+        }
+    }
+}
+
+/// A directory Versi writes to that can be moved in settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataDir {
+    Cache,
+    Logs,
+}
+
+impl DataDir {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Cache => "cache",
+            Self::Logs => "log",
+        }
+    }
 }
 
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
             theme: ThemeSetting::System,
+            theme_schedule: ThemeSchedule::default(),
+            versions_layout: VersionsLayout::List,
+            version_grouping: VersionGrouping::Major,
+            list_density: ListDensity::Comfortable,
+            startup_view: StartupView::default(),
+            startup_environment: None,
             cache_ttl_hours: 1,
             tray_behavior: TrayBehavior::WhenWindowOpen,
             start_minimized: false,
+            close_behavior: None,
+            read_only: false,
             fnm_dir: None,
+            fnm_binary: None,
+            fnm_install_method: None,
+            nvm_dir: None,
+            system_wide: false,
+            sanitize_backend_env: true,
+            prevent_sleep: true,
+            followed_directory: None,
+            custom_cache_dir: None,
+            custom_log_dir: None,
+            sync_file: None,
             node_dist_mirror: None,
+            ca_bundle_path: None,
+            proxy_mode: ProxyMode::default(),
+            pac_url: None,
+            github_token: None,
             preferred_backend: None,
             backend_shell_options: HashMap::new(),
             shell_options: None,
+            backend_update_channels: HashMap::new(),
             debug_logging: false,
+            show_event_log: false,
+            renderer: RendererSetting::default(),
+            always_on_top: false,
+            remember_workspace: false,
+            window_vibrancy: false,
+            ipc_server: false,
+            jetbrains_sync: false,
             window_geometry: None,
             install_timeout_secs: default_install_timeout(),
             uninstall_timeout_secs: default_operation_timeout(),
@@ -162,30 +422,59 @@ impl Default for AppSettings {
             fetch_timeout_secs: default_fetch_timeout(),
             http_timeout_secs: default_http_timeout(),
             toast_timeout_secs: default_toast_timeout(),
+            notification_delivery: NotificationPrefs::default(),
             max_visible_toasts: default_max_visible_toasts(),
             search_results_limit: default_search_results_limit(),
             modal_preview_limit: default_modal_preview_limit(),
             max_log_size_bytes: default_max_log_size_bytes(),
-            retry_delays_secs: default_retry_delays(),
+            retry_policy: RetryPolicy::default(),
+            retry_delays_secs: None,
+            environment_variables: HashMap::new(),
+            search_history: HashMap::new(),
+            saved_filters: Vec::new(),
+            major_notes: BTreeMap::new(),
+            protected_versions: HashMap::new(),
+            known_defaults: HashMap::new(),
+            scheduled_jobs: Vec::new(),
+            eol_strict: false,
+            eol_grace_days: 0,
+            eol_countdown_days: default_eol_countdown_days(),
+            eol_acknowledgements: BTreeMap::new(),
+            banner_states: BannerStates::default(),
+            update_deferral: UpdateDeferral::default(),
+            project_trust: BTreeMap::new(),
         }
     }
 }
 
 impl AppSettings {
     pub fn load() -> Self {
+        if safe_mode::is_active() {
+            return Self::default();
+        }
         let Ok(paths) = AppPaths::new() else {
             return Self::default();
         };
         let settings_path = paths.settings_file();
 
-        let mut settings: Self = if settings_path.exists() {
+        let settings = if settings_path.exists() {
             match std::fs::read_to_string(&settings_path) {
-                Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
+                Ok(content) => Self::parse(&content).unwrap_or_default(),
                 Err(_) => Self::default(),
             }
         } else {
             Self::default()
         };
+        settings_watch::record_loaded(&settings_path, &settings);
+        settings_journal::record_loaded(&settings);
+
+        settings
+    }
+
+    /// Parses the settings file, moving settings from older layouts to
+    /// where they live now.
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut settings: Self = serde_json::from_str(content).map_err(|e| e.to_string())?;
 
         if let Some(legacy) = settings.shell_options.take()
             && settings.backend_shell_options.is_empty()
@@ -195,18 +484,107 @@ impl AppSettings {
                 .insert("fnm".to_string(), legacy);
         }
 
-        settings
+        if let Some(delays) = settings.retry_delays_secs.take()
+            && settings.retry_policy == RetryPolicy::default()
+            && let Some(policy) = retry_policy_from_delays(&delays)
+        {
+            settings.retry_policy = policy;
+        }
+
+        Ok(settings)
     }
 
     pub fn save(&self) -> Result<(), std::io::Error> {
+        // Safe mode keeps the file on disk as it was until it is reset.
+        if safe_mode::is_active() {
+            return Ok(());
+        }
         let paths = AppPaths::new().map_err(std::io::Error::other)?;
         paths.ensure_dirs()?;
 
-        let content = serde_json::to_string_pretty(self)?;
-        std::fs::write(paths.settings_file(), content)?;
+        settings_watch::write(&paths.settings_file(), self)?;
+        settings_journal::record_saved(self);
+        if let Some(sync_file) = &self.sync_file {
+            settings_sync::publish(sync_file, &paths.sync_base_file(), self);
+        }
         Ok(())
     }
 
+    /// Puts the settings shown in `section` back to their defaults. Shell
+    /// options are reset for `backend` only.
+    pub fn reset_section(&mut self, section: SettingsSection, backend: &str) {
+        let defaults = Self::default();
+        match section {
+            SettingsSection::Appearance => {
+                self.theme = defaults.theme;
+                self.theme_schedule = defaults.theme_schedule;
+                self.list_density = defaults.list_density;
+            }
+            SettingsSection::Tray => {
+                self.tray_behavior = defaults.tray_behavior;
+                self.start_minimized = defaults.start_minimized;
+            }
+            SettingsSection::ShellOptions => {
+                self.backend_shell_options.remove(backend);
+            }
+            SettingsSection::Advanced => {
+                self.read_only = defaults.read_only;
+                self.sanitize_backend_env = defaults.sanitize_backend_env;
+                self.prevent_sleep = defaults.prevent_sleep;
+                self.ipc_server = defaults.ipc_server;
+                self.debug_logging = defaults.debug_logging;
+                self.show_event_log = defaults.show_event_log;
+            }
+        }
+    }
+
+    /// Where fnm installs versions in system-wide mode, when it is on.
+    pub fn system_wide_fnm_dir(&self) -> Option<PathBuf> {
+        self.system_wide
+            .then(|| versi_platform::system_wide_dir().join("fnm"))
+    }
+
+    pub fn custom_dirs(&self) -> CustomDirs {
+        CustomDirs {
+            cache_dir: self.custom_cache_dir.clone(),
+            log_dir: self.custom_log_dir.clone(),
+        }
+    }
+
+    pub fn data_dir(&self, dir: DataDir) -> Option<&PathBuf> {
+        match dir {
+            DataDir::Cache => self.custom_cache_dir.as_ref(),
+            DataDir::Logs => self.custom_log_dir.as_ref(),
+        }
+    }
+
+    pub fn data_dir_mut(&mut self, dir: DataDir) -> &mut Option<PathBuf> {
+        match dir {
+            DataDir::Cache => &mut self.custom_cache_dir,
+            DataDir::Logs => &mut self.custom_log_dir,
+        }
+    }
+
+    /// Whether `VERSI_READ_ONLY` forces read-only mode regardless of the setting.
+    pub fn read_only_enforced() -> bool {
+        std::env::var("VERSI_READ_ONLY").is_ok_and(|v| !v.is_empty() && v != "0")
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only || Self::read_only_enforced()
+    }
+
+    /// Close-button behavior, defaulting to what the tray setting implied before
+    /// it could be chosen explicitly.
+    pub fn close_behavior(&self) -> CloseBehavior {
+        self.close_behavior
+            .unwrap_or(if self.tray_behavior == TrayBehavior::AlwaysRunning {
+                CloseBehavior::MinimizeToTray
+            } else {
+                CloseBehavior::Quit
+            })
+    }
+
     pub fn shell_options_for(&self, backend: &str) -> ShellOptions {
         self.backend_shell_options
             .get(backend)
@@ -214,11 +592,159 @@ impl AppSettings {
             .unwrap_or_default()
     }
 
+    pub fn update_channel_for(&self, backend: &str) -> BackendUpdateChannel {
+        self.backend_update_channels
+            .get(backend)
+            .cloned()
+            .unwrap_or_default()
+    }
+
     pub fn shell_options_for_mut(&mut self, backend: &str) -> &mut ShellOptions {
         self.backend_shell_options
             .entry(backend.to_string())
             .or_default()
     }
+
+    /// Dist mirror to install from: the configured one, or the unofficial
+    /// builds mirror when nodejs.org has no binaries for the target.
+    pub fn dist_mirror_for(&self, target: Option<&PlatformTarget>) -> Option<String> {
+        self.node_dist_mirror
+            .clone()
+            .filter(|m| !m.trim().is_empty())
+            .or_else(|| {
+                target
+                    .filter(|t| t.requires_unofficial_builds())
+                    .map(|_| UNOFFICIAL_BUILDS_MIRROR.to_string())
+            })
+    }
+
+    /// [`env_overrides_for`](Self::env_overrides_for) for a single install
+    /// from the unofficial builds, whatever mirror is configured.
+    pub fn unofficial_install_overrides_for(
+        &self,
+        env_id: &EnvironmentId,
+        target: Option<&PlatformTarget>,
+    ) -> Vec<(String, String)> {
+        let mut overrides = self.env_overrides_for(env_id, target);
+        overrides
+            .retain(|(key, _)| key != "FNM_NODE_DIST_MIRROR" && key != "NVM_NODEJS_ORG_MIRROR");
+        for key in ["FNM_NODE_DIST_MIRROR", "NVM_NODEJS_ORG_MIRROR"] {
+            overrides.push((key.to_string(), UNOFFICIAL_BUILDS_MIRROR.to_string()));
+        }
+        overrides
+    }
+
+    pub fn env_overrides_for(
+        &self,
+        env_id: &EnvironmentId,
+        target: Option<&PlatformTarget>,
+    ) -> Vec<(String, String)> {
+        let mut overrides: Vec<(String, String)> = self
+            .environment_variables
+            .get(&env_id.settings_key())
+            .map(|vars| {
+                vars.iter()
+                    .filter(|var| versi_backend::is_valid_env_key(var.key.trim()))
+                    .map(|var| (var.key.trim().to_string(), var.value.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if let Some(ca_path) = self
+            .ca_bundle_path
+            .as_deref()
+            .and_then(|path| env_id.host_path(path))
+        {
+            // Node adds these to its built-in roots. SSL_CERT_FILE would
+            // replace the store curl and fnm use, breaking public downloads.
+            if !overrides.iter().any(|(k, _)| k == "NODE_EXTRA_CA_CERTS") {
+                overrides.push(("NODE_EXTRA_CA_CERTS".to_string(), ca_path));
+            }
+        }
+
+        let mut defaults = Vec::new();
+        if let Some(mirror) = self.dist_mirror_for(target) {
+            defaults.push(("FNM_NODE_DIST_MIRROR", mirror.clone()));
+            defaults.push(("NVM_NODEJS_ORG_MIRROR", mirror));
+        }
+        // The unofficial builds only publish musl binaries for x64; other musl
+        // targets go through the missing-binary warning instead.
+        if let Some(target) = target.filter(|t| t.libc == Some(Libc::Musl) && t.arch == "x64") {
+            defaults.push(("FNM_ARCH", format!("{}-musl", target.arch)));
+        }
+        // WSL distros have their own network settings.
+        if *env_id == EnvironmentId::Native {
+            let dist = self
+                .dist_mirror_for(target)
+                .unwrap_or_else(|| "https://nodejs.org/dist".to_string());
+            for (key, value) in crate::proxy::env_vars(&dist) {
+                defaults.push((key, value));
+            }
+        }
+        for (key, value) in defaults {
+            if !overrides.iter().any(|(k, _)| k == key) {
+                overrides.push((key.to_string(), value));
+            }
+        }
+
+        overrides
+    }
+
+    pub fn search_history_for(&self, env_id: &EnvironmentId) -> &[String] {
+        self.search_history
+            .get(&env_id.settings_key())
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn record_search(&mut self, env_id: &EnvironmentId, query: &str) {
+        const MAX_SEARCH_HISTORY: usize = 8;
+
+        let query = query.trim();
+        if query.is_empty() {
+            return;
+        }
+
+        let history = self
+            .search_history
+            .entry(env_id.settings_key())
+            .or_default();
+        history.retain(|q| q != query);
+        history.insert(0, query.to_string());
+        history.truncate(MAX_SEARCH_HISTORY);
+    }
+
+    pub fn protected_versions_for(&self, env_id: &EnvironmentId) -> &BTreeSet<String> {
+        static NONE: BTreeSet<String> = BTreeSet::new();
+        self.protected_versions
+            .get(&env_id.settings_key())
+            .unwrap_or(&NONE)
+    }
+
+    pub fn is_protected(&self, env_id: &EnvironmentId, version: &str) -> bool {
+        self.protected_versions_for(env_id).contains(version)
+    }
+
+    pub fn toggle_protected(&mut self, env_id: &EnvironmentId, version: &str) {
+        let key = env_id.settings_key();
+        let protected = self.protected_versions.entry(key.clone()).or_default();
+        if !protected.remove(version) {
+            protected.insert(version.to_string());
+        }
+        if protected.is_empty() {
+            self.protected_versions.remove(&key);
+        }
+    }
+
+    pub fn project_trust_for(&self, project: &Path) -> Option<bool> {
+        self.project_trust.get(project).copied()
+    }
+
+    pub fn env_vars_for_mut(&mut self, env_id: &EnvironmentId) -> &mut Vec<EnvVarOverride> {
+        self.environment_variables
+            .entry(env_id.settings_key())
+            .or_default()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -227,6 +753,16 @@ pub struct WindowGeometry {
     pub height: f32,
     pub x: i32,
     pub y: i32,
+    #[serde(default)]
+    pub maximized: bool,
+    #[serde(default = "default_scale_factor")]
+    pub scale_factor: f32,
+    #[serde(default)]
+    pub workspace: Option<u32>,
+}
+
+fn default_scale_factor() -> f32 {
+    1.0
 }
 
 impl WindowGeometry {
@@ -242,6 +778,35 @@ impl WindowGeometry {
             && self.width >= MIN_SIZE
             && self.height >= MIN_SIZE
     }
+
+    /// Fits the saved geometry onto the current monitor layout, falling back to
+    /// [`Self::is_likely_visible`] when monitors can't be enumerated.
+    pub fn restore(&self, monitors: &[MonitorBounds]) -> Option<Self> {
+        if monitors.is_empty() {
+            return self.is_likely_visible().then(|| self.clone());
+        }
+
+        let placement = clamp_to_monitors(
+            WindowPlacement {
+                x: self.x as f32,
+                y: self.y as f32,
+                width: self.width,
+                height: self.height,
+                scale_factor: self.scale_factor,
+            },
+            monitors,
+        )?;
+
+        Some(Self {
+            width: placement.width,
+            height: placement.height,
+            x: placement.x.round() as i32,
+            y: placement.y.round() as i32,
+            maximized: self.maximized,
+            scale_factor: placement.scale_factor,
+            workspace: self.workspace,
+        })
+    }
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
@@ -250,6 +815,239 @@ pub enum ThemeSetting {
     System,
     Light,
     Dark,
+    /// Follows [`ThemeSchedule`] instead of the OS.
+    Scheduled,
+}
+
+/// When the `Scheduled` theme switches, for systems that don't switch
+/// between light and dark on their own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThemeSchedule {
+    pub light_from: NaiveTime,
+    pub dark_from: NaiveTime,
+}
+
+impl Default for ThemeSchedule {
+    fn default() -> Self {
+        Self {
+            light_from: NaiveTime::from_hms_opt(7, 0, 0).unwrap_or_default(),
+            dark_from: NaiveTime::from_hms_opt(19, 0, 0).unwrap_or_default(),
+        }
+    }
+}
+
+impl ThemeSchedule {
+    /// Whether `time` falls in the dark hours. Light hours may wrap past
+    /// midnight, for a schedule like light from 22:00 to 06:00.
+    pub fn is_dark_at(&self, time: NaiveTime) -> bool {
+        if self.light_from <= self.dark_from {
+            !(self.light_from <= time && time < self.dark_from)
+        } else {
+            self.dark_from <= time && time < self.light_from
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum VersionsLayout {
+    #[default]
+    List,
+    Overview,
+}
+
+/// How installed versions are grouped in the list layout.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum VersionGrouping {
+    #[default]
+    Major,
+    /// By LTS codename, with non-LTS lines under "Current".
+    Codename,
+}
+
+/// How tightly rows are packed in the version list.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum ListDensity {
+    #[default]
+    Comfortable,
+    /// Shorter rows and less spacing, so more versions fit without scrolling.
+    Compact,
+}
+
+impl ListDensity {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Comfortable => "Comfortable",
+            Self::Compact => "Compact",
+        }
+    }
+}
+
+/// A kind of event Versi tells the user about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationCategory {
+    OperationFailures,
+    Completions,
+    Updates,
+    EolWarnings,
+}
+
+impl NotificationCategory {
+    pub const ALL: [NotificationCategory; 4] = [
+        Self::OperationFailures,
+        Self::Completions,
+        Self::Updates,
+        Self::EolWarnings,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::OperationFailures => "Operation failures",
+            Self::Completions => "Completed operations",
+            Self::Updates => "Available updates",
+            Self::EolWarnings => "End-of-life warnings",
+        }
+    }
+}
+
+/// How a notification is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NotificationDelivery {
+    /// A toast in the corner of the window.
+    Toast,
+    /// A desktop notification, seen even while the window is hidden.
+    Notification,
+    /// Only written to the log.
+    Silent,
+}
+
+impl NotificationDelivery {
+    pub const ALL: [NotificationDelivery; 3] = [Self::Toast, Self::Notification, Self::Silent];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Toast => "Toast",
+            Self::Notification => "Notification",
+            Self::Silent => "Silent",
+        }
+    }
+}
+
+/// Delivery chosen per [`NotificationCategory`]. Updates and end-of-life
+/// warnings already have banners, so they are silent unless asked for.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationPrefs {
+    pub operation_failures: NotificationDelivery,
+    pub completions: NotificationDelivery,
+    pub updates: NotificationDelivery,
+    pub eol_warnings: NotificationDelivery,
+}
+
+impl Default for NotificationPrefs {
+    fn default() -> Self {
+        Self {
+            operation_failures: NotificationDelivery::Toast,
+            completions: NotificationDelivery::Toast,
+            updates: NotificationDelivery::Silent,
+            eol_warnings: NotificationDelivery::Silent,
+        }
+    }
+}
+
+impl NotificationPrefs {
+    pub fn get(&self, category: NotificationCategory) -> NotificationDelivery {
+        match category {
+            NotificationCategory::OperationFailures => self.operation_failures,
+            NotificationCategory::Completions => self.completions,
+            NotificationCategory::Updates => self.updates,
+            NotificationCategory::EolWarnings => self.eol_warnings,
+        }
+    }
+
+    pub fn set(&mut self, category: NotificationCategory, delivery: NotificationDelivery) {
+        let slot = match category {
+            NotificationCategory::OperationFailures => &mut self.operation_failures,
+            NotificationCategory::Completions => &mut self.completions,
+            NotificationCategory::Updates => &mut self.updates,
+            NotificationCategory::EolWarnings => &mut self.eol_warnings,
+        };
+        *slot = delivery;
+    }
+}
+
+/// What the versions view shows when Versi opens.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum StartupView {
+    #[default]
+    LastUsed,
+    List,
+    Overview,
+}
+
+impl StartupView {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::LastUsed => "Last Used",
+            Self::List => "Versions",
+            Self::Overview => "Overview",
+        }
+    }
+
+    /// The layout to open with, given the one used last.
+    pub fn layout(self, last_used: VersionsLayout) -> VersionsLayout {
+        match self {
+            Self::LastUsed => last_used,
+            Self::List => VersionsLayout::List,
+            Self::Overview => VersionsLayout::Overview,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum RendererSetting {
+    #[default]
+    Auto,
+    Gpu,
+    Software,
+}
+
+impl RendererSetting {
+    /// Value for iced's `ICED_BACKEND`; `None` lets iced try the GPU first and
+    /// fall back to software rendering when it fails to initialize.
+    pub fn iced_backend(self) -> Option<&'static str> {
+        match self {
+            Self::Auto => None,
+            Self::Gpu => Some("wgpu"),
+            Self::Software => Some("tiny-skia"),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Auto => "Automatic",
+            Self::Gpu => "GPU",
+            Self::Software => "Software",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CloseBehavior {
+    MinimizeToTray,
+    Quit,
+    Ask,
+}
+
+/// How requests, and the backends' downloads, reach the internet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProxyMode {
+    /// Never use a proxy.
+    Direct,
+    /// The proxy or PAC file configured for the system.
+    #[default]
+    System,
+    /// Always evaluate a PAC file.
+    Pac,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
@@ -259,3 +1057,94 @@ pub enum TrayBehavior {
     AlwaysRunning,
     Disabled,
 }
+
+/// The retry policy closest to a list of waits before each attempt, the
+/// first usually 0. The old default list maps to the new default policy.
+fn retry_policy_from_delays(delays: &[u64]) -> Option<RetryPolicy> {
+    if delays == [0, 2, 5, 15] {
+        return None;
+    }
+    let waits = delays.get(1..).unwrap_or_default();
+    let Some(&base) = waits.first() else {
+        return Some(RetryPolicy {
+            retries: 0,
+            ..RetryPolicy::default()
+        });
+    };
+    let matches = |backoff: BackoffStrategy| {
+        let policy = RetryPolicy {
+            backoff,
+            base_delay_secs: base,
+            ..RetryPolicy::default()
+        };
+        (1..)
+            .zip(waits)
+            .all(|(retry, &wait)| policy.delay(retry).as_secs() == wait)
+    };
+    let backoff = [BackoffStrategy::Fixed, BackoffStrategy::Linear]
+        .into_iter()
+        .find(|backoff| matches(*backoff))
+        .unwrap_or(BackoffStrategy::Exponential);
+    Some(RetryPolicy {
+        retries: u32::try_from(waits.len()).unwrap_or(u32::MAX),
+        backoff,
+        base_delay_secs: base,
+        ..RetryPolicy::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrates_retry_delays() {
+        let settings = AppSettings::parse(r#"{"retry_delays_secs": [0, 10, 10]}"#).unwrap();
+        assert_eq!(
+            settings.retry_policy,
+            RetryPolicy {
+                retries: 2,
+                backoff: BackoffStrategy::Fixed,
+                base_delay_secs: 10,
+                ..RetryPolicy::default()
+            }
+        );
+
+        let settings = AppSettings::parse(r#"{"retry_delays_secs": [0, 3, 6, 9, 12]}"#).unwrap();
+        assert_eq!(settings.retry_policy.retries, 4);
+        assert_eq!(settings.retry_policy.backoff, BackoffStrategy::Linear);
+
+        let settings = AppSettings::parse(r#"{"retry_delays_secs": [0]}"#).unwrap();
+        assert_eq!(settings.retry_policy.retries, 0);
+
+        let settings = AppSettings::parse(r#"{"retry_delays_secs": [0, 2, 5, 15]}"#).unwrap();
+        assert_eq!(settings.retry_policy, RetryPolicy::default());
+    }
+
+    #[test]
+    fn adds_the_ca_bundle_without_replacing_the_store() {
+        let settings = AppSettings {
+            ca_bundle_path: Some(PathBuf::from("/etc/corp/ca.pem")),
+            ..AppSettings::default()
+        };
+        let overrides = settings.env_overrides_for(&EnvironmentId::Native, None);
+        assert!(overrides.contains(&(
+            "NODE_EXTRA_CA_CERTS".to_string(),
+            "/etc/corp/ca.pem".to_string()
+        )));
+        assert!(!overrides.iter().any(|(key, _)| key == "SSL_CERT_FILE"));
+    }
+
+    #[test]
+    fn keeps_a_retry_policy_already_set() {
+        let settings =
+            AppSettings::parse(r#"{"retry_delays_secs": [0, 10], "retry_policy": {"retries": 5}}"#)
+                .unwrap();
+        assert_eq!(settings.retry_policy.retries, 5);
+        assert!(
+            !serde_json::to_string(&settings)
+                .unwrap()
+                .contains("retry_delays_secs")
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use std::sync::{Mutex, PoisonError};
+
+use chrono::{DateTime, Local};
+use serde_json::{Map, Value};
+
+use crate::settings::AppSettings;
+
+/// How many changes can be reverted.
+const MAX_CHANGES: usize = 20;
+
+/// Settings Versi updates on its own rather than from the settings screen.
+/// They are left out of the journal and keep their current value when a
+/// change is reverted.
+const UNTRACKED: &[&str] = &[
+    "window_geometry",
+    "search_history",
+    "known_defaults",
+    "eol_acknowledgements",
+    "update_deferral",
+    "followed_directory",
+    "fnm_install_method",
+];
+
+/// A saved change to the settings, as shown in the settings screen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Breadcrumb {
+    pub at: DateTime<Local>,
+    /// Names of the settings that changed.
+    pub labels: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+struct Change {
+    at: DateTime<Local>,
+    keys: Vec<String>,
+    /// The settings as saved before the change.
+    before: Value,
+}
+
+#[derive(Debug, Default)]
+struct Journal {
+    /// The settings as last loaded or saved.
+    saved: Option<Value>,
+    changes: Vec<Change>,
+}
+
+impl Journal {
+    fn record(&mut self, value: Value, at: DateTime<Local>) {
+        let Some(before) = self.saved.replace(value) else {
+            return;
+        };
+        let keys = changed_keys(&before, self.saved.as_ref().unwrap_or(&Value::Null));
+        if keys.is_empty() {
+            return;
+        }
+        self.changes.push(Change { at, keys, before });
+        if self.changes.len() > MAX_CHANGES {
+            self.changes.remove(0);
+        }
+    }
+
+    /// Removes the newest change and returns `current` with its tracked
+    /// settings put back to how they were before it.
+    fn revert(&mut self, current: &Value) -> Option<(Value, Change)> {
+        let change = self.changes.pop()?;
+        let mut restored = change.before.as_object().cloned().unwrap_or_default();
+        if let Some(current) = current.as_object() {
+            for key in UNTRACKED {
+                match current.get(*key) {
+                    Some(value) => restored.insert(key.to_string(), value.clone()),
+                    None => restored.remove(*key),
+                };
+            }
+        }
+        Some((Value::Object(restored), change))
+    }
+}
+
+static JOURNAL: Mutex<Journal> = Mutex::new(Journal {
+    saved: None,
+    changes: Vec::new(),
+});
+
+/// Marks `settings` as the saved state without recording a change, for
+/// settings loaded from disk or edited outside Versi.
+pub fn record_loaded(settings: &AppSettings) {
+    if let Ok(value) = serde_json::to_value(settings) {
+        JOURNAL.lock().unwrap_or_else(PoisonError::into_inner).saved = Some(value);
+    }
+}
+
+/// Records `settings` as saved, remembering what changed since the last
+/// save.
+pub fn record_saved(settings: &AppSettings) {
+    if let Ok(value) = serde_json::to_value(settings) {
+        JOURNAL
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .record(value, Local::now());
+    }
+}
+
+/// Recent changes, newest first.
+pub fn breadcrumbs() -> Vec<Breadcrumb> {
+    let journal = JOURNAL.lock().unwrap_or_else(PoisonError::into_inner);
+    journal
+        .changes
+        .iter()
+        .rev()
+        .map(|change| Breadcrumb {
+            at: change.at,
+            labels: change.keys.iter().map(|key| label(key)).collect(),
+        })
+        .collect()
+}
+
+/// `current` with the newest change undone, and the names of the settings
+/// it puts back. The result still has to be saved.
+pub fn revert_last(current: &AppSettings) -> Option<(AppSettings, Vec<String>)> {
+    let current = serde_json::to_value(current).ok()?;
+    let mut journal = JOURNAL.lock().unwrap_or_else(PoisonError::into_inner);
+    let (restored, change) = journal.revert(&current)?;
+    let Ok(settings) = serde_json::from_value::<AppSettings>(restored) else {
+        journal.changes.push(change);
+        return None;
+    };
+    // Saving the reverted settings is not a change of its own.
+    journal.saved = serde_json::to_value(&settings).ok();
+    Some((settings, change.keys.iter().map(|key| label(key)).collect()))
+}
+
+fn changed_keys(before: &Value, after: &Value) -> Vec<String> {
+    let empty = Map::new();
+    let before = before.as_object().unwrap_or(&empty);
+    let after = after.as_object().unwrap_or(&empty);
+    let mut keys: Vec<String> = before
+        .keys()
+        .chain(after.keys())
+        .filter(|key| !UNTRACKED.contains(&key.as_str()))
+        .filter(|key| before.get(*key) != after.get(*key))
+        .cloned()
+        .collect();
+    keys.sort();
+    keys.dedup();
+    keys
+}
+
+/// `tray_behavior` as "Tray behavior".
+fn label(key: &str) -> String {
+    let words = key.replace('_', " ");
+    let mut chars = words.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn records_and_reverts_tracked_changes() {
+        let now = Local::now();
+        let mut journal = Journal::default();
+        journal.record(json!({ "theme": "System", "window_geometry": null }), now);
+        journal.record(json!({ "theme": "Dark", "window_geometry": null }), now);
+        journal.record(json!({ "theme": "Dark", "window_geometry": [1, 2] }), now);
+        assert_eq!(journal.changes.len(), 1);
+        assert_eq!(journal.changes[0].keys, ["theme"]);
+
+        let current = json!({ "theme": "Dark", "window_geometry": [3, 4] });
+        let (restored, change) = journal.revert(&current).unwrap();
+        assert_eq!(
+            restored,
+            json!({ "theme": "System", "window_geometry": [3, 4] })
+        );
+        assert_eq!(label(&change.keys[0]), "Theme");
+        assert!(journal.revert(&current).is_none());
+    }
+}
@@ -0,0 +1,323 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::{Mutex, PoisonError};
+
+use serde_json::{Map, Value};
+
+use crate::settings::AppSettings;
+use crate::settings_watch::FileStamp;
+
+/// Settings shared through the sync file. Paths, window state, environment
+/// variables (which may hold tokens or machine-specific paths) and other
+/// settings that only make sense on one machine stay local.
+pub const SHARED_KEYS: &[&str] = &[
+    "theme",
+    "theme_schedule",
+    "versions_layout",
+    "version_grouping",
+    "list_density",
+    "startup_view",
+    "cache_ttl_hours",
+    "backend_shell_options",
+    "backend_update_channels",
+    "install_timeout_secs",
+    "uninstall_timeout_secs",
+    "set_default_timeout_secs",
+    "fetch_timeout_secs",
+    "toast_timeout_secs",
+    "notification_delivery",
+    "max_visible_toasts",
+    "search_results_limit",
+    "modal_preview_limit",
+    "retry_policy",
+    "saved_filters",
+    "major_notes",
+    "protected_versions",
+    "eol_strict",
+    "eol_grace_days",
+    "eol_countdown_days",
+    "eol_acknowledgements",
+];
+
+/// The sync file as this machine last read or wrote it; `None` until the
+/// first sync.
+static SYNCED: Mutex<Option<Option<FileStamp>>> = Mutex::new(None);
+
+/// What syncing with the shared file changed here.
+#[derive(Debug)]
+pub struct SyncOutcome {
+    /// The settings with the shared changes merged in, when there were any.
+    pub settings: Option<Box<AppSettings>>,
+    /// Settings taken from the file.
+    pub changed: Vec<String>,
+    /// Settings edited both here and in the file since the last sync, which
+    /// keep this machine's value.
+    pub conflicts: Vec<String>,
+    /// Why the file could not be read or written.
+    pub error: Option<String>,
+}
+
+/// Syncs with the file at `path` when it changed since the last sync, or
+/// when nothing was synced yet this session.
+pub fn check(path: &Path, base_path: &Path, current: &AppSettings) -> Option<SyncOutcome> {
+    let stamp = FileStamp::read(path);
+    if *SYNCED.lock().unwrap_or_else(PoisonError::into_inner) == Some(stamp) {
+        return None;
+    }
+    Some(sync(path, base_path, current))
+}
+
+/// Merges the file at `path` with `current` and writes the result back, so
+/// both sides hold the same shared settings. `base_path` keeps what was
+/// shared at the last sync, to tell which side changed a setting. A file
+/// synced for the first time wins over this machine's settings.
+pub fn sync(path: &Path, base_path: &Path, current: &AppSettings) -> SyncOutcome {
+    let mut synced = SYNCED.lock().unwrap_or_else(PoisonError::into_inner);
+    let ours = shared(current);
+    let theirs = match read_normalized(path, current) {
+        Ok(theirs) => theirs,
+        Err(e) => {
+            log::warn!("Could not read sync file {}: {}", path.display(), e);
+            *synced = Some(FileStamp::read(path));
+            return SyncOutcome {
+                error: Some(e),
+                ..SyncOutcome::unchanged()
+            };
+        }
+    };
+    let base = read_map(base_path).unwrap_or_else(|| ours.clone());
+
+    let mut outcome = SyncOutcome::unchanged();
+    let merged = match &theirs {
+        Some(theirs) => merge(&base, &ours, theirs, &mut outcome),
+        None => ours,
+    };
+    if theirs.as_ref() != Some(&merged)
+        && let Err(e) = write_map(path, &merged)
+    {
+        log::warn!("Could not write sync file {}: {}", path.display(), e);
+        outcome.error = Some(e.to_string());
+    }
+    if let Err(e) = write_map(base_path, &merged) {
+        log::warn!("Could not record the synced settings: {}", e);
+    }
+    *synced = Some(FileStamp::read(path));
+
+    if !outcome.changed.is_empty() {
+        outcome.settings = apply(current, &merged).ok().map(Box::new);
+    }
+    outcome
+}
+
+/// Writes `settings` to the file at `path` when it has not changed since the
+/// last sync. Otherwise the next [`check`] merges both sides.
+pub fn publish(path: &Path, base_path: &Path, settings: &AppSettings) {
+    let mut synced = SYNCED.lock().unwrap_or_else(PoisonError::into_inner);
+    if *synced != Some(FileStamp::read(path)) {
+        return;
+    }
+    let ours = shared(settings);
+    if read_map(base_path).as_ref() == Some(&ours) {
+        return;
+    }
+    if let Err(e) = write_map(path, &ours).and_then(|()| write_map(base_path, &ours)) {
+        log::warn!("Could not write sync file {}: {}", path.display(), e);
+    }
+    *synced = Some(FileStamp::read(path));
+}
+
+/// Forgets the last sync, so a newly chosen file is merged from scratch.
+pub fn forget(base_path: &Path) {
+    *SYNCED.lock().unwrap_or_else(PoisonError::into_inner) = None;
+    let _ = std::fs::remove_file(base_path);
+}
+
+impl SyncOutcome {
+    fn unchanged() -> Self {
+        Self {
+            settings: None,
+            changed: Vec::new(),
+            conflicts: Vec::new(),
+            error: None,
+        }
+    }
+}
+
+fn shared(settings: &AppSettings) -> Map<String, Value> {
+    let Ok(Value::Object(all)) = serde_json::to_value(settings) else {
+        return Map::new();
+    };
+    all.into_iter()
+        .filter(|(key, _)| SHARED_KEYS.contains(&key.as_str()))
+        .collect()
+}
+
+fn apply(current: &AppSettings, shared: &Map<String, Value>) -> Result<AppSettings, String> {
+    let mut all = serde_json::to_value(current).map_err(|e| e.to_string())?;
+    if let Value::Object(all) = &mut all {
+        for (key, value) in shared {
+            if SHARED_KEYS.contains(&key.as_str()) {
+                all.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    serde_json::from_value(all).map_err(|e| e.to_string())
+}
+
+/// The shared settings in the file, parsed into settings and back so that
+/// omitted ones compare equal to their defaults. `None` when there is no
+/// file yet.
+fn read_normalized(
+    path: &Path,
+    current: &AppSettings,
+) -> Result<Option<Map<String, Value>>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let Value::Object(theirs) = serde_json::from_str(&content).map_err(|e| e.to_string())? else {
+        return Err("expected a JSON object".to_string());
+    };
+    Ok(Some(shared(&apply(current, &theirs)?)))
+}
+
+fn read_map(path: &Path) -> Option<Map<String, Value>> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn write_map(path: &Path, map: &Map<String, Value>) -> std::io::Result<()> {
+    std::fs::write(path, serde_json::to_string_pretty(map)?)
+}
+
+/// Three-way merge of the shared settings, descending into maps such as
+/// the notes per major so that edits to different entries both survive.
+fn merge(
+    base: &Map<String, Value>,
+    ours: &Map<String, Value>,
+    theirs: &Map<String, Value>,
+    outcome: &mut SyncOutcome,
+) -> Map<String, Value> {
+    let keys: BTreeSet<&String> = base
+        .keys()
+        .chain(ours.keys())
+        .chain(theirs.keys())
+        .collect();
+    keys.into_iter()
+        .filter_map(|key| {
+            let value = merge_value(key, base.get(key), ours.get(key), theirs.get(key), outcome)?;
+            Some((key.clone(), value))
+        })
+        .collect()
+}
+
+fn merge_value(
+    path: &str,
+    base: Option<&Value>,
+    ours: Option<&Value>,
+    theirs: Option<&Value>,
+    outcome: &mut SyncOutcome,
+) -> Option<Value> {
+    if theirs == ours || theirs == base {
+        return ours.cloned();
+    }
+    if ours == base {
+        outcome.changed.push(path.to_string());
+        return theirs.cloned();
+    }
+    if let (Some(Value::Object(base)), Some(Value::Object(ours)), Some(Value::Object(theirs))) =
+        (base, ours, theirs)
+    {
+        let keys: BTreeSet<&String> = base
+            .keys()
+            .chain(ours.keys())
+            .chain(theirs.keys())
+            .collect();
+        let merged = keys
+            .into_iter()
+            .filter_map(|key| {
+                let value = merge_value(
+                    &format!("{}.{}", path, key),
+                    base.get(key),
+                    ours.get(key),
+                    theirs.get(key),
+                    outcome,
+                )?;
+                Some((key.clone(), value))
+            })
+            .collect();
+        return Some(Value::Object(merged));
+    }
+    outcome.conflicts.push(path.to_string());
+    ours.cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn map(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(map) => map,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn merges_edits_from_both_machines() {
+        let base = map(json!({
+            "theme": "System",
+            "eol_grace_days": 0,
+            "major_notes": { "18": "legacy app" },
+        }));
+        let ours = map(json!({
+            "theme": "Dark",
+            "eol_grace_days": 0,
+            "major_notes": { "18": "legacy app", "20": "ci" },
+        }));
+        let theirs = map(json!({
+            "theme": "Light",
+            "eol_grace_days": 30,
+            "major_notes": {},
+        }));
+
+        let mut outcome = SyncOutcome::unchanged();
+        let merged = merge(&base, &ours, &theirs, &mut outcome);
+
+        assert_eq!(
+            Value::Object(merged),
+            json!({
+                "theme": "Dark",
+                "eol_grace_days": 30,
+                "major_notes": { "20": "ci" },
+            })
+        );
+        assert_eq!(outcome.changed, vec!["eol_grace_days", "major_notes.18"]);
+        assert_eq!(outcome.conflicts, vec!["theme"]);
+    }
+
+    #[test]
+    fn first_sync_adopts_the_file_and_keeps_local_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("versi-sync.json");
+        let base_path = dir.path().join("sync_base.json");
+        std::fs::write(
+            &path,
+            r#"{ "search_results_limit": 50, "fnm_dir": "/elsewhere" }"#,
+        )
+        .unwrap();
+        let mut current = AppSettings::default();
+        current.fnm_dir = Some("/here".into());
+
+        let outcome = sync(&path, &base_path, &current);
+        let synced = outcome.settings.expect("settings taken from the file");
+        assert_eq!(synced.search_results_limit, 50);
+        assert_eq!(synced.fnm_dir, current.fnm_dir);
+        assert!(outcome.conflicts.is_empty());
+
+        let base = read_map(&base_path).unwrap();
+        assert_eq!(base["search_results_limit"], 50);
+        assert!(!base.contains_key("fnm_dir"));
+    }
+}
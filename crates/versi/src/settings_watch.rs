@@ -0,0 +1,236 @@
+use std::path::Path;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, SystemTime};
+
+use serde_json::{Map, Value};
+
+use crate::settings::AppSettings;
+
+/// How often the settings file is checked for edits made outside Versi.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Settings only read at startup, so edits to them wait for a restart.
+pub const RESTART_REQUIRED: &[&str] = &[
+    "renderer",
+    "tray_behavior",
+    "preferred_backend",
+    "fnm_dir",
+    "fnm_binary",
+    "nvm_dir",
+    "system_wide",
+    "custom_cache_dir",
+    "custom_log_dir",
+    "max_log_size_bytes",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct FileStamp {
+    modified: SystemTime,
+    len: u64,
+}
+
+impl FileStamp {
+    pub(crate) fn read(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some(Self {
+            modified: metadata.modified().ok()?,
+            len: metadata.len(),
+        })
+    }
+}
+
+/// The settings file as Versi last read or wrote it.
+struct Synced {
+    /// `None` forces the next check to read the file again.
+    stamp: Option<FileStamp>,
+    value: Value,
+}
+
+static SYNCED: Mutex<Option<Synced>> = Mutex::new(None);
+
+/// An edit to the settings file made while Versi was running.
+#[derive(Debug)]
+pub enum ExternalEdit {
+    /// The file no longer parses; the settings in memory are kept.
+    Invalid(String),
+    Changed {
+        settings: Box<AppSettings>,
+        /// Keys taken from the file.
+        changed: Vec<String>,
+        /// Keys edited both in the file and in Versi, which keep Versi's value.
+        conflicts: Vec<String>,
+    },
+}
+
+/// Marks the file at `path` as holding `settings`.
+pub fn record_loaded(path: &Path, settings: &AppSettings) {
+    if let Ok(value) = serde_json::to_value(settings) {
+        *SYNCED.lock().unwrap_or_else(PoisonError::into_inner) = Some(Synced {
+            stamp: FileStamp::read(path),
+            value,
+        });
+    }
+}
+
+/// Writes `settings` to `path`. An outside edit that has not been picked up
+/// yet is merged in instead of overwritten, and loaded by the next
+/// [`check`].
+pub fn write(path: &Path, settings: &AppSettings) -> std::io::Result<()> {
+    let ours = serde_json::to_value(settings)?;
+    let mut synced = SYNCED.lock().unwrap_or_else(PoisonError::into_inner);
+
+    let pending = synced
+        .as_ref()
+        .filter(|synced| FileStamp::read(path) != synced.stamp)
+        .and_then(|synced| {
+            let theirs = read_normalized(path).ok()?;
+            let merged = merge(&synced.value, &ours, &theirs);
+            (!merged.changed.is_empty()).then_some(merged.value)
+        });
+
+    let content = serde_json::to_string_pretty(pending.as_ref().unwrap_or(&ours))?;
+    std::fs::write(path, content)?;
+    *synced = Some(Synced {
+        stamp: if pending.is_some() {
+            None
+        } else {
+            FileStamp::read(path)
+        },
+        value: ours,
+    });
+    Ok(())
+}
+
+/// Looks for an outside edit to the file at `path` and merges it into
+/// `current`. Each edit is reported once.
+pub fn check(path: &Path, current: &AppSettings) -> Option<ExternalEdit> {
+    let mut synced = SYNCED.lock().unwrap_or_else(PoisonError::into_inner);
+    let synced = synced.as_mut()?;
+    let stamp = FileStamp::read(path);
+    if stamp.is_none() || stamp == synced.stamp {
+        return None;
+    }
+    synced.stamp = stamp;
+
+    let theirs = match read_normalized(path) {
+        Ok(theirs) => theirs,
+        Err(e) => return Some(ExternalEdit::Invalid(e)),
+    };
+    let ours = serde_json::to_value(current).ok()?;
+    let merged = merge(&synced.value, &ours, &theirs);
+    synced.value = theirs;
+    if merged.changed.is_empty() {
+        return None;
+    }
+
+    let settings = serde_json::from_value(merged.value).ok()?;
+    Some(ExternalEdit::Changed {
+        settings: Box::new(settings),
+        changed: merged.changed,
+        conflicts: merged.conflicts,
+    })
+}
+
+/// The file parsed into settings and back, so that omitted fields compare
+/// equal to their defaults.
+fn read_normalized(path: &Path) -> Result<Value, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let settings = AppSettings::parse(&content)?;
+    serde_json::to_value(settings).map_err(|e| e.to_string())
+}
+
+struct Merged {
+    value: Value,
+    changed: Vec<String>,
+    conflicts: Vec<String>,
+}
+
+/// Three-way merge of top-level keys: `theirs` wins where only it changed
+/// since `base`, `ours` wins everywhere else.
+fn merge(base: &Value, ours: &Value, theirs: &Value) -> Merged {
+    let empty = Map::new();
+    let object = |value: &Value| value.as_object().cloned().unwrap_or_default();
+    let (base, theirs_map) = (
+        base.as_object().unwrap_or(&empty),
+        theirs.as_object().unwrap_or(&empty),
+    );
+
+    let mut value = object(ours);
+    let mut changed = Vec::new();
+    let mut conflicts = Vec::new();
+    for (key, theirs) in theirs_map {
+        let base = base.get(key);
+        let ours = value.get(key);
+        if Some(theirs) == base || Some(theirs) == ours {
+            continue;
+        }
+        if ours == base {
+            value.insert(key.clone(), theirs.clone());
+            changed.push(key.clone());
+        } else {
+            conflicts.push(key.clone());
+        }
+    }
+
+    Merged {
+        value: Value::Object(value),
+        changed,
+        conflicts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn merges_outside_edits_and_keeps_in_app_conflicts() {
+        let base = json!({ "theme": "System", "search_results_limit": 20, "read_only": false });
+        let ours = json!({ "theme": "Dark", "search_results_limit": 20, "read_only": true });
+        let theirs = json!({ "theme": "Light", "search_results_limit": 50, "read_only": true });
+
+        let merged = merge(&base, &ours, &theirs);
+
+        assert_eq!(
+            merged.value,
+            json!({ "theme": "Dark", "search_results_limit": 50, "read_only": true })
+        );
+        assert_eq!(merged.changed, vec!["search_results_limit"]);
+        assert_eq!(merged.conflicts, vec!["theme"]);
+    }
+
+    #[test]
+    fn reloads_edits_but_not_its_own_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        let mut settings = AppSettings::default();
+
+        write(&path, &settings).unwrap();
+        assert!(check(&path, &settings).is_none());
+
+        settings.search_results_limit = 7;
+        write(&path, &settings).unwrap();
+        assert!(check(&path, &settings).is_none());
+
+        std::fs::write(&path, r#"{ "search_results_limit": 42, "theme": "Dark" }"#).unwrap();
+        match check(&path, &settings) {
+            Some(ExternalEdit::Changed {
+                settings: reloaded,
+                changed,
+                conflicts,
+            }) => {
+                assert_eq!(reloaded.search_results_limit, 42);
+                assert!(changed.contains(&"theme".to_string()));
+                assert!(conflicts.is_empty());
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        std::fs::write(&path, "{ not json").unwrap();
+        assert!(matches!(
+            check(&path, &settings),
+            Some(ExternalEdit::Invalid(_))
+        ));
+    }
+}
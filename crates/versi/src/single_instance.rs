@@ -1,8 +1,28 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set once this process holds the instance guard. The control server only
+/// listens in the instance that does.
+static PRIMARY: AtomicBool = AtomicBool::new(false);
+
+/// Whether this process is the running instance, so no other Versi of the
+/// same user can be serving the control socket.
+pub fn is_primary() -> bool {
+    PRIMARY.load(Ordering::Acquire)
+}
+
 #[cfg(windows)]
 mod windows_impl {
+    use std::io;
     use std::ptr;
-    use windows_sys::Win32::Foundation::{CloseHandle, ERROR_ALREADY_EXISTS, GetLastError, HANDLE};
-    use windows_sys::Win32::System::Threading::CreateMutexA;
+    use std::sync::atomic::Ordering;
+    use windows_sys::Win32::Foundation::{
+        CloseHandle, ERROR_ALREADY_EXISTS, GetLastError, HANDLE, LocalFree,
+    };
+    use windows_sys::Win32::Security::Authorization::ConvertSidToStringSidW;
+    use windows_sys::Win32::Security::{GetTokenInformation, TOKEN_QUERY, TOKEN_USER, TokenUser};
+    use windows_sys::Win32::System::Threading::{
+        CreateMutexA, GetCurrentProcess, OpenProcessToken,
+    };
 
     const MUTEX_NAME: &[u8] = b"Global\\VersiAppMutex\0";
 
@@ -25,6 +45,7 @@ mod windows_impl {
                     return Err(());
                 }
 
+                super::PRIMARY.store(true, Ordering::Release);
                 Ok(Self { handle })
             }
         }
@@ -51,22 +72,86 @@ mod windows_impl {
             }
         }
     }
+
+    /// The SID of the user running Versi, such as `S-1-5-21-...`.
+    pub fn current_user_sid() -> io::Result<String> {
+        unsafe {
+            let mut token: HANDLE = ptr::null_mut();
+            if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) == 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            let mut len = 0u32;
+            GetTokenInformation(token, TokenUser, ptr::null_mut(), 0, &mut len);
+            // u64 keeps the buffer aligned for TOKEN_USER.
+            let mut buffer = vec![0u64; (len as usize).div_ceil(8)];
+            let queried =
+                GetTokenInformation(token, TokenUser, buffer.as_mut_ptr().cast(), len, &mut len);
+            let error = io::Error::last_os_error();
+            CloseHandle(token);
+            if queried == 0 {
+                return Err(error);
+            }
+
+            let user = &*buffer.as_ptr().cast::<TOKEN_USER>();
+            let mut wide = ptr::null_mut();
+            if ConvertSidToStringSidW(user.User.Sid, &mut wide) == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let len = (0..).take_while(|&i| *wide.add(i) != 0).count();
+            let sid = String::from_utf16_lossy(std::slice::from_raw_parts(wide, len));
+            LocalFree(wide.cast());
+            Ok(sid)
+        }
+    }
 }
 
 #[cfg(not(windows))]
 mod other_impl {
-    pub struct SingleInstance;
+    use std::fs::{File, TryLockError};
+    use std::sync::atomic::Ordering;
+
+    /// An exclusive lock on [`versi_platform::AppPaths::instance_lock`],
+    /// released when the process exits.
+    pub struct SingleInstance {
+        _lock: Option<File>,
+    }
 
     impl SingleInstance {
+        /// Fails when another instance holds the lock. When the lock can't be
+        /// taken at all, Versi still starts, without the control server.
         pub fn acquire() -> Result<Self, ()> {
-            Ok(Self)
+            let Some(file) = open_lock() else {
+                return Ok(Self { _lock: None });
+            };
+            match file.try_lock() {
+                Ok(()) => {
+                    super::PRIMARY.store(true, Ordering::Release);
+                    Ok(Self { _lock: Some(file) })
+                }
+                Err(TryLockError::WouldBlock) => Err(()),
+                Err(TryLockError::Error(_)) => Ok(Self { _lock: None }),
+            }
         }
     }
 
-    pub fn bring_existing_window_to_front() {}
+    fn open_lock() -> Option<File> {
+        let paths = versi_platform::AppPaths::new().ok()?;
+        paths.ensure_instance_dir().ok()?;
+        File::options()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(paths.instance_lock())
+            .ok()
+    }
+
+    pub fn bring_existing_window_to_front() {
+        eprintln!("Versi is already running");
+    }
 }
 
 #[cfg(not(windows))]
 pub use other_impl::{SingleInstance, bring_existing_window_to_front};
 #[cfg(windows)]
-pub use windows_impl::{SingleInstance, bring_existing_window_to_front};
+pub use windows_impl::{SingleInstance, bring_existing_window_to_front, current_user_sid};
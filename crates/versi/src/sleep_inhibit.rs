@@ -0,0 +1,169 @@
+/// Keeps the machine from sleeping until dropped. The display may still
+/// turn off; only system sleep is held back.
+pub struct SleepInhibitor {
+    #[cfg(target_os = "macos")]
+    assertion: Option<u32>,
+    #[cfg(target_os = "linux")]
+    release: Option<std::sync::mpsc::Sender<()>>,
+}
+
+impl std::fmt::Debug for SleepInhibitor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SleepInhibitor").finish_non_exhaustive()
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod iokit {
+    use std::ffi::{c_char, c_void};
+
+    pub type CFStringRef = *const c_void;
+
+    pub const UTF8: u32 = 0x0800_0100;
+    pub const ASSERTION_LEVEL_ON: u32 = 255;
+    pub const SUCCESS: i32 = 0;
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    unsafe extern "C" {
+        pub fn CFStringCreateWithCString(
+            allocator: *const c_void,
+            string: *const c_char,
+            encoding: u32,
+        ) -> CFStringRef;
+        pub fn CFRelease(value: *const c_void);
+    }
+
+    #[link(name = "IOKit", kind = "framework")]
+    unsafe extern "C" {
+        pub fn IOPMAssertionCreateWithName(
+            assertion_type: CFStringRef,
+            level: u32,
+            name: CFStringRef,
+            id: *mut u32,
+        ) -> i32;
+        pub fn IOPMAssertionRelease(id: u32) -> i32;
+    }
+}
+
+impl SleepInhibitor {
+    /// Asks the system to stay awake, giving `reason` where the platform
+    /// shows one. Failing to is logged and otherwise ignored.
+    #[cfg(target_os = "macos")]
+    pub fn acquire(reason: &str) -> Self {
+        use std::ffi::CString;
+
+        let Ok(reason) = CString::new(reason) else {
+            return Self { assertion: None };
+        };
+        let mut id = 0;
+        let result = unsafe {
+            let kind = iokit::CFStringCreateWithCString(
+                std::ptr::null(),
+                c"PreventUserIdleSystemSleep".as_ptr(),
+                iokit::UTF8,
+            );
+            let name =
+                iokit::CFStringCreateWithCString(std::ptr::null(), reason.as_ptr(), iokit::UTF8);
+            let result =
+                iokit::IOPMAssertionCreateWithName(kind, iokit::ASSERTION_LEVEL_ON, name, &mut id);
+            iokit::CFRelease(kind);
+            iokit::CFRelease(name);
+            result
+        };
+        if result != iokit::SUCCESS {
+            log::debug!("Failed to create power assertion: {:#x}", result);
+            return Self { assertion: None };
+        }
+        log::debug!("Preventing sleep: {}", reason.to_string_lossy());
+        Self {
+            assertion: Some(id),
+        }
+    }
+
+    /// Asks the system to stay awake, giving `reason` where the platform
+    /// shows one. Failing to is logged and otherwise ignored.
+    ///
+    /// The execution state belongs to the calling thread, so this must be
+    /// acquired and dropped on the same, long-lived thread.
+    #[cfg(windows)]
+    pub fn acquire(reason: &str) -> Self {
+        use windows_sys::Win32::System::Power::{
+            ES_CONTINUOUS, ES_SYSTEM_REQUIRED, SetThreadExecutionState,
+        };
+
+        if unsafe { SetThreadExecutionState(ES_CONTINUOUS | ES_SYSTEM_REQUIRED) } == 0 {
+            log::debug!("Failed to set the thread execution state");
+        } else {
+            log::debug!("Preventing sleep: {}", reason);
+        }
+        Self {}
+    }
+
+    /// Asks the system to stay awake, giving `reason` where the platform
+    /// shows one. Failing to is logged and otherwise ignored.
+    ///
+    /// systemd-logind holds the inhibitor lock for as long as the file
+    /// descriptor it hands out stays open, so a thread keeps it until
+    /// released.
+    #[cfg(target_os = "linux")]
+    pub fn acquire(reason: &str) -> Self {
+        let (release, released) = std::sync::mpsc::channel::<()>();
+        let reason = reason.to_string();
+        std::thread::spawn(move || {
+            let result = (|| -> Result<zbus::zvariant::OwnedFd, Box<dyn std::error::Error>> {
+                let connection = zbus::blocking::Connection::system()?;
+                let reply = connection.call_method(
+                    Some("org.freedesktop.login1"),
+                    "/org/freedesktop/login1",
+                    Some("org.freedesktop.login1.Manager"),
+                    "Inhibit",
+                    &("sleep:idle", "Versi", reason.as_str(), "block"),
+                )?;
+                Ok(reply.body().deserialize()?)
+            })();
+            match result {
+                Ok(fd) => {
+                    log::debug!("Preventing sleep: {}", reason);
+                    // Returns once the sender is dropped.
+                    let _ = released.recv();
+                    drop(fd);
+                    log::debug!("Allowing sleep again");
+                }
+                Err(e) => log::debug!("Failed to take a sleep inhibitor lock: {}", e),
+            }
+        });
+        Self {
+            release: Some(release),
+        }
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+    pub fn acquire(_reason: &str) -> Self {
+        Self {}
+    }
+}
+
+impl Drop for SleepInhibitor {
+    fn drop(&mut self) {
+        #[cfg(target_os = "macos")]
+        if let Some(id) = self.assertion.take() {
+            unsafe {
+                iokit::IOPMAssertionRelease(id);
+            }
+            log::debug!("Allowing sleep again");
+        }
+
+        #[cfg(windows)]
+        {
+            use windows_sys::Win32::System::Power::{ES_CONTINUOUS, SetThreadExecutionState};
+
+            unsafe {
+                SetThreadExecutionState(ES_CONTINUOUS);
+            }
+            log::debug!("Allowing sleep again");
+        }
+
+        #[cfg(target_os = "linux")]
+        self.release.take();
+    }
+}
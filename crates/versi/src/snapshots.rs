@@ -0,0 +1,192 @@
+use std::collections::HashSet;
+use std::time::SystemTime;
+
+use versi_platform::EnvironmentId;
+
+use crate::state::{EnvironmentState, OperationRequest};
+
+/// Snapshots kept per environment; older ones are dropped first.
+const MAX_PER_ENVIRONMENT: usize = 5;
+
+/// Installed versions and default of an environment before a bulk operation.
+#[derive(Debug, Clone)]
+pub struct EnvironmentSnapshot {
+    pub environment: EnvironmentId,
+    pub taken_at: SystemTime,
+    pub installed: Vec<String>,
+    pub default: Option<String>,
+}
+
+impl EnvironmentSnapshot {
+    pub fn capture(env: &EnvironmentState) -> Self {
+        Self {
+            environment: env.id.clone(),
+            taken_at: SystemTime::now(),
+            installed: env
+                .installed_versions
+                .iter()
+                .map(|v| v.version.to_string())
+                .collect(),
+            default: env.default_version.as_ref().map(|v| v.to_string()),
+        }
+    }
+}
+
+/// Snapshots for this session, oldest first.
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotStore {
+    snapshots: Vec<EnvironmentSnapshot>,
+}
+
+impl SnapshotStore {
+    pub fn record(&mut self, snapshot: EnvironmentSnapshot) {
+        let environment = snapshot.environment.clone();
+        self.snapshots.push(snapshot);
+
+        let count = self
+            .snapshots
+            .iter()
+            .filter(|s| s.environment == environment)
+            .count();
+        if count > MAX_PER_ENVIRONMENT
+            && let Some(oldest) = self
+                .snapshots
+                .iter()
+                .position(|s| s.environment == environment)
+        {
+            self.snapshots.remove(oldest);
+        }
+    }
+
+    pub fn latest(&self, environment: &EnvironmentId) -> Option<&EnvironmentSnapshot> {
+        self.snapshots
+            .iter()
+            .rev()
+            .find(|s| &s.environment == environment)
+    }
+
+    /// Removes and returns the newest snapshot of `environment`.
+    pub fn take_latest(&mut self, environment: &EnvironmentId) -> Option<EnvironmentSnapshot> {
+        let index = self
+            .snapshots
+            .iter()
+            .rposition(|s| &s.environment == environment)?;
+        Some(self.snapshots.remove(index))
+    }
+}
+
+/// Operations that bring `env` back to `snapshot`: reinstalling what was
+/// removed, restoring the default, then removing what was added. The default
+/// is restored before uninstalls so the current default can be removed.
+pub fn plan_rollback(
+    snapshot: &EnvironmentSnapshot,
+    env: &EnvironmentState,
+) -> Vec<OperationRequest> {
+    let current: HashSet<String> = env
+        .installed_versions
+        .iter()
+        .map(|v| v.version.to_string())
+        .collect();
+    let previous: HashSet<&String> = snapshot.installed.iter().collect();
+
+    let mut plan: Vec<OperationRequest> = snapshot
+        .installed
+        .iter()
+        .filter(|version| !current.contains(*version))
+        .map(|version| OperationRequest::Install {
+            version: version.clone(),
+        })
+        .collect();
+
+    let current_default = env.default_version.as_ref().map(|v| v.to_string());
+    if let Some(default) = &snapshot.default
+        && current_default.as_ref() != Some(default)
+    {
+        plan.push(OperationRequest::SetDefault {
+            version: default.clone(),
+        });
+    }
+
+    let mut added: Vec<&String> = current
+        .iter()
+        .filter(|version| !previous.contains(version))
+        .collect();
+    added.sort();
+    plan.extend(
+        added
+            .into_iter()
+            .map(|version| OperationRequest::Uninstall {
+                version: version.clone(),
+            }),
+    );
+
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use versi_backend::InstalledVersion;
+
+    fn environment(installed: &[&str], default: Option<&str>) -> EnvironmentState {
+        let mut env = EnvironmentState::new(EnvironmentId::Native, "fnm", None);
+        env.update_versions(
+            installed
+                .iter()
+                .map(|v| InstalledVersion {
+                    version: v.parse().unwrap(),
+                    is_default: Some(*v) == default,
+                    lts_codename: None,
+                    install_date: None,
+                    disk_size: None,
+                })
+                .collect(),
+        );
+        env
+    }
+
+    #[test]
+    fn rollback_reverses_installs_uninstalls_and_default() {
+        let before =
+            EnvironmentSnapshot::capture(&environment(&["v18.20.0", "v20.11.0"], Some("v18.20.0")));
+        let after = environment(&["v20.11.0", "v22.1.0"], Some("v22.1.0"));
+
+        assert_eq!(
+            plan_rollback(&before, &after),
+            vec![
+                OperationRequest::Install {
+                    version: "v18.20.0".to_string()
+                },
+                OperationRequest::SetDefault {
+                    version: "v18.20.0".to_string()
+                },
+                OperationRequest::Uninstall {
+                    version: "v22.1.0".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn unchanged_environment_needs_no_rollback() {
+        let env = environment(&["v20.11.0"], Some("v20.11.0"));
+        let snapshot = EnvironmentSnapshot::capture(&env);
+        assert!(plan_rollback(&snapshot, &env).is_empty());
+    }
+
+    #[test]
+    fn store_keeps_latest_per_environment() {
+        let mut store = SnapshotStore::default();
+        for version in ["v18.0.0", "v20.0.0"] {
+            store.record(EnvironmentSnapshot::capture(&environment(&[version], None)));
+        }
+
+        let native = EnvironmentId::Native;
+        assert_eq!(store.latest(&native).unwrap().installed, vec!["v20.0.0"]);
+        assert_eq!(
+            store.take_latest(&native).unwrap().installed,
+            vec!["v20.0.0"]
+        );
+        assert_eq!(store.latest(&native).unwrap().installed, vec!["v18.0.0"]);
+    }
+}
@@ -0,0 +1,125 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SnippetTarget {
+    GitHubActions,
+    Dockerfile,
+    Direnv,
+    Shell,
+}
+
+impl SnippetTarget {
+    pub const ALL: [SnippetTarget; 4] = [
+        SnippetTarget::GitHubActions,
+        SnippetTarget::Dockerfile,
+        SnippetTarget::Direnv,
+        SnippetTarget::Shell,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::GitHubActions => "GitHub Actions",
+            Self::Dockerfile => "Dockerfile",
+            Self::Direnv => "direnv",
+            Self::Shell => "Shell",
+        }
+    }
+}
+
+/// Ready-to-paste configuration pinning `version` for the given target.
+///
+/// `shell_init` is the backend's bash init line, when it has one.
+pub fn render(
+    target: SnippetTarget,
+    version: &str,
+    backend: &str,
+    shell_init: Option<&str>,
+) -> String {
+    let bare = version.trim_start_matches('v');
+    match target {
+        SnippetTarget::GitHubActions => format!(
+            "steps:\n  - uses: actions/checkout@v4\n  - uses: actions/setup-node@v4\n    with:\n      node-version: '{}'\n",
+            bare
+        ),
+        SnippetTarget::Dockerfile => format!("FROM node:{}\n", bare),
+        SnippetTarget::Direnv => match backend {
+            "nvm" => format!(
+                "# .envrc\nexport NVM_DIR=\"${{NVM_DIR:-$HOME/.nvm}}\"\n. \"$NVM_DIR/nvm.sh\"\nnvm use {}\n",
+                bare
+            ),
+            _ => format!(
+                "# .envrc\neval \"$(fnm env --shell bash)\"\nfnm use --install-if-missing {}\n",
+                bare
+            ),
+        },
+        SnippetTarget::Shell => {
+            let use_command = format!("{} use {}", backend, bare);
+            match shell_init {
+                Some(init) => format!("# ~/.bashrc\n{}\n{}\n", init, use_command),
+                None => format!(
+                    "# {} has no shell init; run this once:\n{}\n",
+                    backend, use_command
+                ),
+            }
+        }
+    }
+}
+
+/// A GitHub Actions `strategy.matrix` over `majors`, optionally followed by
+/// a matching package.json `engines` field.
+pub fn ci_matrix(majors: &[u32], include_engines: bool) -> String {
+    let mut majors = majors.to_vec();
+    majors.sort_unstable();
+    majors.dedup();
+
+    let versions: Vec<String> = majors.iter().map(|m| format!("{}.x", m)).collect();
+    let mut out = format!(
+        "strategy:\n  matrix:\n    node-version: [{}]\n",
+        versions.join(", ")
+    );
+    if include_engines && !majors.is_empty() {
+        let range: Vec<String> = majors.iter().map(|m| format!("^{}", m)).collect();
+        out.push_str(&format!(
+            "\n# package.json\n\"engines\": {{\n  \"node\": \"{}\"\n}}\n",
+            range.join(" || ")
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ci_matrix_sorts_majors_and_adds_engines() {
+        assert_eq!(
+            ci_matrix(&[22, 18, 20, 22], false),
+            "strategy:\n  matrix:\n    node-version: [18.x, 20.x, 22.x]\n"
+        );
+        let with_engines = ci_matrix(&[20, 22], true);
+        assert!(with_engines.ends_with("\"node\": \"^20 || ^22\"\n}\n"));
+    }
+
+    #[test]
+    fn pins_bare_version_for_ci_and_docker() {
+        let actions = render(SnippetTarget::GitHubActions, "v22.11.0", "fnm", None);
+        assert!(actions.contains("node-version: '22.11.0'"));
+        assert_eq!(
+            render(SnippetTarget::Dockerfile, "v22.11.0", "fnm", None),
+            "FROM node:22.11.0\n"
+        );
+    }
+
+    #[test]
+    fn direnv_and_shell_follow_backend() {
+        assert!(render(SnippetTarget::Direnv, "v20.18.0", "nvm", None).contains("nvm use 20.18.0"));
+        assert!(render(SnippetTarget::Direnv, "v20.18.0", "fnm", None).contains("fnm use"));
+
+        let shell = render(
+            SnippetTarget::Shell,
+            "v20.18.0",
+            "fnm",
+            Some("eval \"$(fnm env)\""),
+        );
+        assert_eq!(shell, "# ~/.bashrc\neval \"$(fnm env)\"\nfnm use 20.18.0\n");
+    }
+}
@@ -1,7 +1,45 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
 
 use versi_backend::{InstalledVersion, NodeVersion, VersionGroup};
-use versi_platform::EnvironmentId;
+use versi_platform::{BinaryArch, EnvironmentId, PlatformTarget};
+
+use crate::integrity::Integrity;
+use crate::npm_doctor::NpmCache;
+
+/// Why an environment has no versions to show.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EnvironmentError {
+    /// The WSL distro is stopped.
+    #[cfg_attr(not(windows), allow(dead_code))]
+    NotRunning,
+    /// No supported version manager was found.
+    BackendMissing,
+    /// The version manager was found but listing versions failed.
+    ListFailed(String),
+}
+
+impl EnvironmentError {
+    pub fn summary(&self) -> &str {
+        match self {
+            Self::NotRunning => "Not running",
+            Self::BackendMissing => "No backend installed",
+            Self::ListFailed(_) => "Failed to load versions",
+        }
+    }
+
+    pub fn remediation(&self) -> &'static str {
+        match self {
+            Self::NotRunning => "Start the distro, then Versi will detect its version manager.",
+            Self::BackendMissing => {
+                "Install fnm or nvm in this environment, then detect environments again."
+            }
+            Self::ListFailed(_) => {
+                "Check that the version manager runs from a terminal and that its path in Settings is correct."
+            }
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct EnvironmentState {
@@ -11,11 +49,28 @@ pub struct EnvironmentState {
     pub installed_set: HashSet<String>,
     pub version_groups: Vec<VersionGroup>,
     pub default_version: Option<NodeVersion>,
+    /// Version the most recently used shell runs, when the backend can tell.
+    pub active_version: Option<NodeVersion>,
     pub backend_name: &'static str,
     pub backend_version: Option<String>,
     pub loading: bool,
-    pub error: Option<String>,
+    pub error: Option<EnvironmentError>,
+    /// Automatic reload attempts since the last successful load.
+    pub load_attempts: u32,
+    /// Delay before the scheduled automatic reload, if one is pending.
+    pub retry_in: Option<Duration>,
     pub available: bool,
+    pub target: Option<PlatformTarget>,
+    pub integrity: HashMap<String, Integrity>,
+    /// Architecture of each installed version's binary, read on macOS.
+    pub architectures: HashMap<String, BinaryArch>,
+    pub host_arch: Option<BinaryArch>,
+    /// Default version removed outside Versi, until another one is set or
+    /// the warning is dismissed.
+    pub missing_default: Option<String>,
+    /// npm cache location and size, once measured from Settings.
+    pub npm_cache: Option<Result<NpmCache, String>>,
+    pub npm_cache_busy: bool,
 }
 
 impl EnvironmentState {
@@ -32,15 +87,29 @@ impl EnvironmentState {
             installed_set: HashSet::new(),
             version_groups: Vec::new(),
             default_version: None,
+            active_version: None,
             backend_name,
             backend_version,
             loading: true,
             error: None,
+            load_attempts: 0,
+            retry_in: None,
             available: true,
+            target: None,
+            integrity: HashMap::new(),
+            architectures: HashMap::new(),
+            host_arch: None,
+            missing_default: None,
+            npm_cache: None,
+            npm_cache_busy: false,
         }
     }
 
-    pub fn unavailable(id: EnvironmentId, backend_name: &'static str, reason: &str) -> Self {
+    pub fn unavailable(
+        id: EnvironmentId,
+        backend_name: &'static str,
+        error: EnvironmentError,
+    ) -> Self {
         let name = id.display_name();
         Self {
             id,
@@ -49,11 +118,21 @@ impl EnvironmentState {
             installed_set: HashSet::new(),
             version_groups: Vec::new(),
             default_version: None,
+            active_version: None,
             backend_name,
             backend_version: None,
             loading: false,
-            error: Some(reason.to_string()),
+            error: Some(error),
+            load_attempts: 0,
+            retry_in: None,
             available: false,
+            target: None,
+            integrity: HashMap::new(),
+            architectures: HashMap::new(),
+            host_arch: None,
+            missing_default: None,
+            npm_cache: None,
+            npm_cache_busy: false,
         }
     }
 
@@ -63,9 +142,172 @@ impl EnvironmentState {
             .find(|v| v.is_default)
             .map(|v| v.version.clone());
         self.installed_set = versions.iter().map(|v| v.version.to_string()).collect();
+        self.integrity
+            .retain(|version, _| self.installed_set.contains(version));
+        self.architectures
+            .retain(|version, _| self.installed_set.contains(version));
+        if self.default_version.is_some() {
+            self.missing_default = None;
+        }
         self.version_groups = VersionGroup::from_versions(versions.clone());
         self.installed_versions = versions;
         self.loading = false;
         self.error = None;
+        self.load_attempts = 0;
+        self.retry_in = None;
+    }
+
+    /// Architecture of `version` when the machine can only run it through
+    /// emulation, such as an x86_64 build under Rosetta 2 on Apple Silicon.
+    pub fn foreign_arch(&self, version: &str) -> Option<BinaryArch> {
+        let arch = *self.architectures.get(version)?;
+        let host = self.host_arch?;
+        (!arch.runs_natively_on(host)).then_some(arch)
+    }
+
+    /// Drops an uninstalled version without asking the backend again.
+    /// Returns `false` when the version isn't known, so the caller can reload.
+    pub fn remove_version(&mut self, version: &str) -> bool {
+        let mut versions = self.installed_versions.clone();
+        let before = versions.len();
+        versions.retain(|v| v.version.to_string() != version);
+        if versions.len() == before {
+            return false;
+        }
+        self.update_versions(versions);
+        true
+    }
+
+    /// Replacement for a missing default: the newest installed LTS release,
+    /// or the newest release when no LTS is installed.
+    pub fn suggested_default(&self) -> Option<&NodeVersion> {
+        let newest = |lts: bool| {
+            self.installed_versions
+                .iter()
+                .filter(|v| !lts || v.lts_codename.is_some())
+                .map(|v| &v.version)
+                .max()
+        };
+        newest(true).or_else(|| newest(false))
+    }
+
+    /// Majors whose newest installed release is behind the newest published
+    /// one.
+    pub fn majors_with_updates(&self, latest_by_major: &HashMap<u32, NodeVersion>) -> usize {
+        self.version_groups
+            .iter()
+            .filter(|group| {
+                let installed_latest = group.versions.iter().map(|v| &v.version).max();
+                latest_by_major.get(&group.major).is_some_and(|latest| {
+                    installed_latest.is_some_and(|installed| latest > installed)
+                })
+            })
+            .count()
+    }
+
+    /// Space the installed versions take up, counting those measured so far.
+    pub fn disk_used(&self) -> Option<u64> {
+        self.installed_versions
+            .iter()
+            .filter_map(|v| v.disk_size)
+            .reduce(|a, b| a + b)
+    }
+
+    /// Marks `version` as the default without asking the backend again.
+    /// Returns `false` when the version isn't installed, so the caller can reload.
+    pub fn mark_default(&mut self, version: &str) -> bool {
+        if !self.installed_set.contains(version) {
+            return false;
+        }
+        let mut versions = self.installed_versions.clone();
+        for v in &mut versions {
+            v.is_default = v.version.to_string() == version;
+        }
+        self.update_versions(versions);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env_with(versions: &[(u32, bool)]) -> EnvironmentState {
+        let mut env = EnvironmentState::new(EnvironmentId::Native, "fnm", None);
+        env.update_versions(
+            versions
+                .iter()
+                .map(|&(major, is_default)| InstalledVersion {
+                    version: NodeVersion::new(major, 0, 0),
+                    is_default,
+                    lts_codename: None,
+                    install_date: None,
+                    disk_size: None,
+                })
+                .collect(),
+        );
+        env
+    }
+
+    #[test]
+    fn remove_version_updates_sets_and_default() {
+        let mut env = env_with(&[(22, true), (20, false)]);
+        assert!(env.remove_version("v22.0.0"));
+        assert!(!env.installed_set.contains("v22.0.0"));
+        assert_eq!(env.installed_versions.len(), 1);
+        assert!(env.default_version.is_none());
+        assert!(!env.remove_version("v18.0.0"));
+    }
+
+    #[test]
+    fn mark_default_moves_default_flag() {
+        let mut env = env_with(&[(22, true), (20, false)]);
+        assert!(env.mark_default("v20.0.0"));
+        assert_eq!(env.default_version, Some(NodeVersion::new(20, 0, 0)));
+        assert_eq!(
+            env.installed_versions
+                .iter()
+                .filter(|v| v.is_default)
+                .count(),
+            1
+        );
+        assert!(!env.mark_default("v18.0.0"));
+    }
+
+    #[test]
+    fn suggests_newest_lts_as_replacement_default() {
+        let mut env = env_with(&[(23, false), (22, false), (20, false)]);
+        assert_eq!(env.suggested_default(), Some(&NodeVersion::new(23, 0, 0)));
+
+        for v in &mut env.installed_versions {
+            if v.version.major % 2 == 0 {
+                v.lts_codename = Some("lts".to_string());
+            }
+        }
+        assert_eq!(env.suggested_default(), Some(&NodeVersion::new(22, 0, 0)));
+    }
+
+    #[test]
+    fn counts_majors_behind_latest_release() {
+        let env = env_with(&[(22, true), (20, false)]);
+        let latest = HashMap::from([
+            (22, NodeVersion::new(22, 3, 0)),
+            (20, NodeVersion::new(20, 0, 0)),
+        ]);
+        assert_eq!(env.majors_with_updates(&latest), 1);
+        assert_eq!(env.disk_used(), None);
+    }
+
+    #[test]
+    fn successful_load_clears_error_and_retries() {
+        let mut env = env_with(&[(22, true)]);
+        env.error = Some(EnvironmentError::ListFailed("timed out".to_string()));
+        env.load_attempts = 2;
+        env.retry_in = Some(Duration::from_secs(4));
+
+        env.update_versions(env.installed_versions.clone());
+        assert!(env.error.is_none());
+        assert_eq!(env.load_attempts, 0);
+        assert!(env.retry_in.is_none());
     }
 }
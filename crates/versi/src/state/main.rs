@@ -1,11 +1,23 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::time::Instant;
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use versi_backend::{BackendUpdate, NodeVersion, RemoteVersion, VersionManager};
 use versi_core::{AppUpdate, ReleaseSchedule};
 
-use super::{EnvironmentState, MainViewKind, Modal, OperationQueue, SettingsModalState, Toast};
+use crate::aliases::resolve_alias;
+use crate::log_viewer::LogViewer;
+use crate::project_watch::ProjectPin;
+use crate::safe_mode::SafeMode;
+use crate::scheduler::ScheduledJob;
+use crate::settings::{NotificationDelivery, NotificationPrefs};
+use crate::snapshots::SnapshotStore;
+use crate::status_file::PublicStatus;
+
+use super::{
+    EnvironmentState, MainViewKind, Modal, OperationQueue, OperationRequest, SettingsModalState,
+    Toast,
+};
 
 pub struct MainState {
     pub environments: Vec<EnvironmentState>,
@@ -15,6 +27,10 @@ pub struct MainState {
     pub toasts: Vec<Toast>,
     pub modal: Option<Modal>,
     pub search_query: String,
+    /// Whether the search lists installed versions of every environment.
+    pub search_all_environments: bool,
+    /// Whether the header's list of hidden banners is open.
+    pub notifications_open: bool,
     pub backend: Box<dyn VersionManager>,
     pub app_update: Option<AppUpdate>,
     pub app_update_state: AppUpdateState,
@@ -22,9 +38,38 @@ pub struct MainState {
     pub view: MainViewKind,
     pub settings_state: SettingsModalState,
     pub hovered_version: Option<String>,
+    pub selected_versions: HashSet<String>,
+    /// Keyboard modifiers held down, for modifier-clicks on version rows.
+    pub modifiers: iced::keyboard::Modifiers,
+    pub quit_when_idle: bool,
+    pub persisted_operations: Vec<OperationRequest>,
+    /// Unfinished operations from the last session are on offer and have
+    /// not been resumed or discarded yet.
+    pub resume_offered: bool,
+    pub published_status: Option<PublicStatus>,
+    /// Scheduled job whose operations are in the queue.
+    pub running_job: Option<ScheduledJob>,
     pub backend_name: &'static str,
     pub detected_backends: Vec<&'static str>,
     pub refresh_rotation: f32,
+    /// Environment states taken before bulk operations, for rolling back.
+    pub snapshots: SnapshotStore,
+    pub log_viewer: LogViewer,
+    /// Version file of the followed project directory.
+    pub project_pin: Option<ProjectPin>,
+    /// Whether the followed directory's version file is being read.
+    pub reading_project_pin: bool,
+    /// Why this launch started in safe mode, when it did.
+    pub safe_mode: Option<SafeMode>,
+    /// Versions being reinstalled, with the global npm packages to put back
+    /// once they are installed again.
+    pub reinstalls: HashMap<String, Vec<String>>,
+    /// Versions being installed from the unofficial builds.
+    pub unofficial_installs: HashSet<String>,
+    /// Support warnings already notified about this session.
+    pub announced_warnings: HashSet<(u32, crate::eol::SupportPhase)>,
+    /// Feature tips walked through after onboarding or from About.
+    pub tips: crate::tips::TipsTour,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -51,11 +96,14 @@ impl std::fmt::Debug for MainState {
             .field("toasts", &self.toasts)
             .field("modal", &self.modal)
             .field("search_query", &self.search_query)
+            .field("search_all_environments", &self.search_all_environments)
+            .field("notifications_open", &self.notifications_open)
             .field("backend", &self.backend.name())
             .field("app_update", &self.app_update)
             .field("backend_update", &self.backend_update)
             .field("view", &self.view)
             .field("hovered_version", &self.hovered_version)
+            .field("selected_versions", &self.selected_versions)
             .finish()
     }
 }
@@ -69,11 +117,16 @@ impl MainState {
         Self {
             environments,
             active_environment_idx: 0,
-            available_versions: VersionCache::new(),
+            available_versions: VersionCache::new(RemoteSource {
+                backend: backend_name,
+                mirror: None,
+            }),
             operation_queue: OperationQueue::new(),
             toasts: Vec::new(),
             modal: None,
             search_query: String::new(),
+            search_all_environments: false,
+            notifications_open: false,
             backend,
             app_update: None,
             app_update_state: AppUpdateState::default(),
@@ -81,9 +134,25 @@ impl MainState {
             view: MainViewKind::default(),
             settings_state: SettingsModalState::new(),
             hovered_version: None,
+            selected_versions: HashSet::new(),
+            modifiers: iced::keyboard::Modifiers::default(),
+            quit_when_idle: false,
+            persisted_operations: Vec::new(),
+            resume_offered: false,
+            published_status: None,
+            running_job: None,
             backend_name,
             detected_backends: Vec::new(),
             refresh_rotation: 0.0,
+            snapshots: SnapshotStore::default(),
+            log_viewer: LogViewer::default(),
+            project_pin: None,
+            reading_project_pin: false,
+            safe_mode: crate::safe_mode::current().cloned(),
+            reinstalls: HashMap::new(),
+            unofficial_installs: HashSet::new(),
+            announced_warnings: HashSet::new(),
+            tips: crate::tips::TipsTour::default(),
         }
     }
 
@@ -95,8 +164,20 @@ impl MainState {
         &mut self.environments[self.active_environment_idx]
     }
 
-    pub fn add_toast(&mut self, toast: Toast) {
-        self.toasts.push(toast);
+    /// Shows `toast` the way `prefs` deliver its category.
+    pub fn add_toast(&mut self, toast: Toast, prefs: &NotificationPrefs) {
+        match prefs.get(toast.category) {
+            NotificationDelivery::Toast => {
+                versi_platform::announce(&toast.message);
+                self.toasts.push(toast);
+            }
+            NotificationDelivery::Notification => {
+                versi_platform::show_notification("Versi", &toast.message);
+            }
+            NotificationDelivery::Silent => {
+                log::info!("{}: {}", toast.category.label(), toast.message);
+            }
+        }
     }
 
     pub fn remove_toast(&mut self, id: usize) {
@@ -122,10 +203,18 @@ impl MainState {
         } else {
             let query = &self.search_query;
             let query_lower = query.to_lowercase();
-            let versions = &self.available_versions.versions;
+            let versions = &self.available_versions.remote().versions;
 
-            if let Some(resolved) = resolve_alias_query(versions, &query_lower) {
-                result.push(resolved.version.to_string());
+            // A bare "lts" lists the newest of each LTS line instead.
+            if query_lower != "lts"
+                && let Some(resolved) = resolve_alias(
+                    versions,
+                    self.available_versions.schedule.as_ref(),
+                    query,
+                    chrono::Local::now().date_naive(),
+                )
+            {
+                result.push(resolved.version.version.to_string());
                 return result;
             }
 
@@ -179,34 +268,28 @@ impl MainState {
     }
 }
 
-#[derive(Debug)]
-pub struct VersionCache {
+/// Where a remote version list comes from. Environments with the same
+/// backend and mirror see the same versions, so they share one list.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RemoteSource {
+    pub backend: &'static str,
+    /// `None` for the official nodejs.org index.
+    pub mirror: Option<String>,
+}
+
+/// Versions available from one [`RemoteSource`].
+#[derive(Debug, Default)]
+pub struct RemoteList {
     pub versions: Vec<RemoteVersion>,
     pub latest_by_major: HashMap<u32, NodeVersion>,
     pub fetched_at: Option<Instant>,
     pub loading: bool,
     pub error: Option<String>,
-    pub schedule: Option<ReleaseSchedule>,
-    pub schedule_error: Option<String>,
     pub loaded_from_disk: bool,
     pub disk_cached_at: Option<DateTime<Utc>>,
 }
 
-impl VersionCache {
-    pub fn new() -> Self {
-        Self {
-            versions: Vec::new(),
-            latest_by_major: HashMap::new(),
-            fetched_at: None,
-            loading: false,
-            error: None,
-            schedule: None,
-            schedule_error: None,
-            loaded_from_disk: false,
-            disk_cached_at: None,
-        }
-    }
-
+impl RemoteList {
     pub fn set_versions(&mut self, versions: Vec<RemoteVersion>) {
         self.recompute_latest_by_major(&versions);
         self.versions = versions;
@@ -226,6 +309,11 @@ impl VersionCache {
         }
     }
 
+    /// Whether nothing has been fetched or loaded from disk yet.
+    pub fn is_unloaded(&self) -> bool {
+        !self.loading && self.fetched_at.is_none() && !self.loaded_from_disk
+    }
+
     pub fn network_status(&self) -> NetworkStatus {
         if self.loading {
             return NetworkStatus::Fetching;
@@ -240,6 +328,82 @@ impl VersionCache {
     }
 }
 
+#[derive(Debug)]
+pub struct VersionCache {
+    /// Remote lists by source, shared by every environment using it.
+    lists: HashMap<RemoteSource, RemoteList>,
+    /// The source of the active environment, always present in `lists`.
+    active: RemoteSource,
+    pub schedule: Option<ReleaseSchedule>,
+    pub schedule_error: Option<String>,
+    pub schedule_fetched_at: Option<DateTime<Utc>>,
+    pub release_dates: HashMap<String, NaiveDate>,
+    /// Binaries published per version, from the index of `release_files_mirror`.
+    pub release_files: HashMap<String, Vec<String>>,
+    pub release_files_mirror: Option<String>,
+}
+
+impl VersionCache {
+    pub fn new(active: RemoteSource) -> Self {
+        Self {
+            lists: HashMap::from([(active.clone(), RemoteList::default())]),
+            active,
+            schedule: None,
+            schedule_error: None,
+            schedule_fetched_at: None,
+            release_dates: HashMap::new(),
+            release_files: HashMap::new(),
+            release_files_mirror: None,
+        }
+    }
+
+    /// The list of the active environment's source.
+    pub fn remote(&self) -> &RemoteList {
+        &self.lists[&self.active]
+    }
+
+    pub fn remote_mut(&mut self) -> &mut RemoteList {
+        self.lists.entry(self.active.clone()).or_default()
+    }
+
+    pub fn list(&self, source: &RemoteSource) -> Option<&RemoteList> {
+        self.lists.get(source)
+    }
+
+    pub fn list_mut(&mut self, source: &RemoteSource) -> &mut RemoteList {
+        self.lists.entry(source.clone()).or_default()
+    }
+
+    pub fn active_source(&self) -> &RemoteSource {
+        &self.active
+    }
+
+    /// Shows the list of `source`, starting an empty one the first time.
+    pub fn set_active(&mut self, source: RemoteSource) {
+        self.lists.entry(source.clone()).or_default();
+        self.active = source;
+    }
+
+    /// Whether the schedule was fetched recently enough to skip refetching.
+    pub fn schedule_is_fresh(&self, now: DateTime<Utc>) -> bool {
+        self.schedule.is_some()
+            && self.schedule_fetched_at.is_some_and(|fetched_at| {
+                (now - fetched_at)
+                    .to_std()
+                    .is_ok_and(|age| age < versi_core::SCHEDULE_TTL)
+            })
+    }
+
+    /// Whether a cached schedule is in use because refreshing it failed.
+    pub fn schedule_is_stale(&self) -> bool {
+        self.schedule.is_some() && self.schedule_error.is_some()
+    }
+
+    pub fn network_status(&self) -> NetworkStatus {
+        self.remote().network_status()
+    }
+}
+
 pub enum NetworkStatus {
     Online,
     Fetching,
@@ -247,27 +411,48 @@ pub enum NetworkStatus {
     Stale,
 }
 
-fn resolve_alias_query<'a>(
-    versions: &'a [RemoteVersion],
-    query_lower: &str,
-) -> Option<&'a RemoteVersion> {
-    match query_lower {
-        "latest" | "stable" | "current" => versions.iter().max_by_key(|v| &v.version),
-        "lts/*" => versions
-            .iter()
-            .filter(|v| v.lts_codename.is_some())
-            .max_by_key(|v| &v.version),
-        q if q.starts_with("lts/") => {
-            let codename = &q[4..];
-            versions
-                .iter()
-                .filter(|v| {
-                    v.lts_codename
-                        .as_ref()
-                        .is_some_and(|c| c.to_lowercase() == codename)
-                })
-                .max_by_key(|v| &v.version)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(mirror: Option<&str>) -> RemoteSource {
+        RemoteSource {
+            backend: "fnm",
+            mirror: mirror.map(str::to_string),
+        }
+    }
+
+    fn remote(version: &str) -> RemoteVersion {
+        RemoteVersion {
+            version: version.parse().unwrap(),
+            lts_codename: None,
+            is_latest: false,
         }
-        _ => None,
+    }
+
+    #[test]
+    fn sources_keep_their_own_remote_list() {
+        let official = source(None);
+        let unofficial = source(Some(
+            "https://unofficial-builds.nodejs.org/download/release",
+        ));
+        let mut cache = VersionCache::new(official.clone());
+        cache
+            .remote_mut()
+            .set_versions(vec![remote("22.1.0"), remote("22.3.0")]);
+
+        cache.set_active(unofficial.clone());
+        assert!(cache.remote().is_unloaded());
+        cache
+            .list_mut(&unofficial)
+            .set_versions(vec![remote("22.2.0")]);
+
+        cache.set_active(official);
+        assert_eq!(cache.remote().versions.len(), 2);
+        assert_eq!(
+            cache.remote().latest_by_major[&22],
+            "22.3.0".parse::<NodeVersion>().unwrap()
+        );
+        assert_eq!(cache.list(&unofficial).unwrap().versions.len(), 1);
     }
 }
@@ -13,7 +13,7 @@ pub use ui::*;
 #[derive(Debug)]
 pub enum AppState {
     Loading,
-    Onboarding(OnboardingState),
+    Onboarding(Box<OnboardingState>),
     Main(Box<MainState>),
 }
 
@@ -22,5 +22,7 @@ pub enum MainViewKind {
     #[default]
     Versions,
     Settings,
+    /// The log viewer, opened from settings.
+    Logs,
     About,
 }
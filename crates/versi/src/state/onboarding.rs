@@ -1,5 +1,7 @@
 use std::path::PathBuf;
 
+use versi_fnm::FnmInstallMethod;
+use versi_nvm::WindowsPackageManager;
 use versi_shell::ShellType;
 
 #[derive(Debug)]
@@ -7,9 +9,16 @@ pub struct OnboardingState {
     pub step: OnboardingStep,
     pub backend_installing: bool,
     pub install_error: Option<String>,
+    /// Last line printed by a package manager installing the backend.
+    pub install_progress: Option<String>,
+    /// Ways of installing each backend that work on this system.
+    pub install_methods: Vec<InstallMethod>,
     pub detected_shells: Vec<ShellConfigStatus>,
     pub available_backends: Vec<BackendOption>,
     pub selected_backend: Option<String>,
+    /// Looking for a backend installed since Versi started.
+    pub detecting: bool,
+    pub existing: Option<ExistingSetup>,
 }
 
 impl OnboardingState {
@@ -18,9 +27,13 @@ impl OnboardingState {
             step: OnboardingStep::Welcome,
             backend_installing: false,
             install_error: None,
+            install_progress: None,
+            install_methods: Vec::new(),
             detected_shells: Vec::new(),
             available_backends: Vec::new(),
             selected_backend: None,
+            detecting: false,
+            existing: None,
         }
     }
 }
@@ -31,6 +44,56 @@ pub enum OnboardingStep {
     SelectBackend,
     InstallBackend,
     ConfigureShell,
+    /// A backend turned out to be installed already; shows what was found
+    /// instead of the install and shell steps.
+    Ready,
+}
+
+/// A way of installing a backend that shows its output while it runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallMethod {
+    Fnm(FnmInstallMethod),
+    Nvm(WindowsPackageManager),
+}
+
+impl InstallMethod {
+    pub fn available() -> Vec<Self> {
+        FnmInstallMethod::available()
+            .into_iter()
+            .map(Self::Fnm)
+            .chain(
+                WindowsPackageManager::available()
+                    .into_iter()
+                    .map(Self::Nvm),
+            )
+            .collect()
+    }
+
+    pub fn backend(&self) -> &'static str {
+        match self {
+            Self::Fnm(_) => "fnm",
+            Self::Nvm(_) => "nvm",
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Self::Fnm(method) => format!("Install with {}", method.display_name()),
+            Self::Nvm(package_manager) => {
+                format!("Install with {}", package_manager.display_name())
+            }
+        }
+    }
+}
+
+/// A backend found installed while onboarding, and which shells load it.
+#[derive(Debug, Clone)]
+pub struct ExistingSetup {
+    pub backend: &'static str,
+    pub display_name: &'static str,
+    pub version: Option<String>,
+    pub source: Option<String>,
+    pub configured_shells: Vec<ShellType>,
 }
 
 #[derive(Debug, Clone)]
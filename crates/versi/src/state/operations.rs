@@ -1,4 +1,12 @@
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::time::{Instant, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use versi_backend::InstallProgress;
+use versi_platform::{BinaryArch, EnvironmentId};
+
+use crate::transfer::InstallTransfer;
 
 #[derive(Debug, Clone)]
 pub enum Operation {
@@ -7,7 +15,8 @@ pub enum Operation {
     SetDefault { version: String },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
 pub enum OperationRequest {
     Install { version: String },
     Uninstall { version: String },
@@ -22,6 +31,91 @@ impl OperationRequest {
             Self::SetDefault { version } => version,
         }
     }
+
+    /// Tags the commands run for this request in `env`, so their
+    /// transcripts can be attached to a bug report.
+    pub fn log_key(&self, env: &EnvironmentId) -> String {
+        let action = match self {
+            Self::Install { .. } => "install",
+            Self::Uninstall { .. } => "uninstall",
+            Self::SetDefault { .. } => "set default",
+        };
+        format!("{} {} in {}", action, self.version(), env.display_name())
+    }
+}
+
+/// An operation that failed, kept for the error details dialog.
+#[derive(Debug, Clone)]
+pub struct OperationFailure {
+    pub environment: EnvironmentId,
+    pub request: OperationRequest,
+    pub error: String,
+    pub failed_at: SystemTime,
+}
+
+impl OperationFailure {
+    pub fn new(environment: EnvironmentId, request: OperationRequest, error: String) -> Self {
+        Self {
+            environment,
+            request,
+            error,
+            failed_at: SystemTime::now(),
+        }
+    }
+}
+
+/// Outcome of every operation in a batch that ran without the queue going idle.
+#[derive(Debug, Clone, Default)]
+pub struct BatchSummary {
+    pub results: Vec<(OperationRequest, Option<String>)>,
+}
+
+impl BatchSummary {
+    pub fn failed(&self) -> usize {
+        self.results
+            .iter()
+            .filter(|(_, error)| error.is_some())
+            .count()
+    }
+
+    /// One-line description such as "Installed 3, failed 1".
+    pub fn headline(&self) -> String {
+        let succeeded = |matches: fn(&OperationRequest) -> bool| {
+            self.results
+                .iter()
+                .filter(|(request, error)| error.is_none() && matches(request))
+                .count()
+        };
+        let counts = [
+            (
+                "Installed",
+                succeeded(|r| matches!(r, OperationRequest::Install { .. })),
+            ),
+            (
+                "Uninstalled",
+                succeeded(|r| matches!(r, OperationRequest::Uninstall { .. })),
+            ),
+            (
+                "Set default",
+                succeeded(|r| matches!(r, OperationRequest::SetDefault { .. })),
+            ),
+        ];
+
+        let mut parts: Vec<String> = counts
+            .iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(label, count)| format!("{} {}", label, count))
+            .collect();
+        if self.failed() > 0 {
+            parts.push(format!("failed {}", self.failed()));
+        }
+        let headline = parts.join(", ");
+        let mut chars = headline.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => headline,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -34,6 +128,10 @@ pub struct OperationQueue {
     pub active_installs: Vec<Operation>,
     pub exclusive_op: Option<Operation>,
     pub pending: VecDeque<QueuedOperation>,
+    completed_in_batch: usize,
+    batch_results: Vec<(OperationRequest, Option<String>)>,
+    /// Download progress of active installs that report it.
+    transfers: HashMap<String, InstallTransfer>,
 }
 
 impl std::fmt::Debug for OperationQueue {
@@ -42,6 +140,8 @@ impl std::fmt::Debug for OperationQueue {
             .field("active_installs", &self.active_installs.len())
             .field("exclusive_op", &self.exclusive_op)
             .field("pending", &self.pending.len())
+            .field("completed_in_batch", &self.completed_in_batch)
+            .field("batch_results", &self.batch_results.len())
             .finish()
     }
 }
@@ -58,6 +158,9 @@ impl OperationQueue {
             active_installs: Vec::new(),
             exclusive_op: None,
             pending: VecDeque::new(),
+            completed_in_batch: 0,
+            batch_results: Vec::new(),
+            transfers: HashMap::new(),
         }
     }
 
@@ -69,6 +172,87 @@ impl OperationQueue {
         !self.active_installs.is_empty() || self.exclusive_op.is_some()
     }
 
+    pub fn is_idle(&self) -> bool {
+        self.active_installs.is_empty() && self.exclusive_op.is_none() && self.pending.is_empty()
+    }
+
+    /// Operations that have started, leaving out those still queued.
+    pub fn running_count(&self) -> usize {
+        self.active_installs.len() + usize::from(self.exclusive_op.is_some())
+    }
+
+    pub fn active_count(&self) -> usize {
+        self.active_installs.len() + usize::from(self.exclusive_op.is_some()) + self.pending.len()
+    }
+
+    /// Completed and total operations since the queue was last idle, or `None`
+    /// when nothing is running.
+    pub fn progress(&self) -> Option<(usize, usize)> {
+        (!self.is_idle()).then(|| {
+            (
+                self.completed_in_batch,
+                self.completed_in_batch + self.active_count(),
+            )
+        })
+    }
+
+    /// Whether more than one operation has run or is queued since the queue
+    /// was last idle, so results are reported together once it drains.
+    pub fn in_batch(&self) -> bool {
+        self.completed_in_batch + self.active_count() > 1
+    }
+
+    pub fn record_result(&mut self, request: OperationRequest, error: Option<String>) {
+        self.batch_results.push((request, error));
+    }
+
+    /// Hands out the results of a finished multi-operation batch. Single
+    /// operations report their own outcome, so they yield `None`.
+    pub fn take_batch_summary(&mut self) -> Option<BatchSummary> {
+        self.take_summary(2)
+    }
+
+    /// Like [`Self::take_batch_summary`], but also reports a lone operation.
+    pub fn take_any_summary(&mut self) -> Option<BatchSummary> {
+        self.take_summary(1)
+    }
+
+    fn take_summary(&mut self, min_results: usize) -> Option<BatchSummary> {
+        if !self.is_idle() {
+            return None;
+        }
+        let results = std::mem::take(&mut self.batch_results);
+        (results.len() >= min_results).then_some(BatchSummary { results })
+    }
+
+    fn start_batch_if_idle(&mut self) {
+        if self.is_idle() {
+            self.completed_in_batch = 0;
+            self.batch_results.clear();
+        }
+    }
+
+    pub fn snapshot(&self) -> Vec<OperationRequest> {
+        let active = self
+            .active_installs
+            .iter()
+            .chain(self.exclusive_op.iter())
+            .map(|op| match op {
+                Operation::Install { version } => OperationRequest::Install {
+                    version: version.clone(),
+                },
+                Operation::Uninstall { version } => OperationRequest::Uninstall {
+                    version: version.clone(),
+                },
+                Operation::SetDefault { version } => OperationRequest::SetDefault {
+                    version: version.clone(),
+                },
+            });
+        active
+            .chain(self.pending.iter().map(|queued| queued.request.clone()))
+            .collect()
+    }
+
     pub fn has_pending_for_version(&self, version: &str) -> bool {
         self.pending
             .iter()
@@ -114,27 +298,55 @@ impl OperationQueue {
             .any(|op| matches!(op, Operation::Install { version: v, .. } if v == version))
     }
 
+    /// Drops the queued operations on `version`, returning how many there were.
+    pub fn cancel_pending_for(&mut self, version: &str) -> usize {
+        let before = self.pending.len();
+        self.pending.retain(|op| op.request.version() != version);
+        before - self.pending.len()
+    }
+
     pub fn enqueue(&mut self, request: OperationRequest) {
+        self.start_batch_if_idle();
         self.pending.push_back(QueuedOperation { request });
     }
 
     pub fn start_install(&mut self, version: String) {
+        self.start_batch_if_idle();
         self.active_installs.push(Operation::Install { version });
     }
 
     pub fn start_exclusive(&mut self, op: Operation) {
+        self.start_batch_if_idle();
         self.exclusive_op = Some(op);
     }
 
     pub fn complete_exclusive(&mut self) {
-        self.exclusive_op = None;
+        if self.exclusive_op.take().is_some() {
+            self.completed_in_batch += 1;
+        }
+    }
+
+    pub fn record_transfer(&mut self, version: &str, progress: InstallProgress) {
+        if self.has_active_install(version) {
+            self.transfers
+                .entry(version.to_string())
+                .or_default()
+                .record(progress, Instant::now());
+        }
+    }
+
+    pub fn transfer_for(&self, version: &str) -> Option<&InstallTransfer> {
+        self.transfers.get(version)
     }
 
     pub fn remove_completed_install(&mut self, version: &str) {
+        self.transfers.remove(version);
+        let before = self.active_installs.len();
         self.active_installs.retain(|op| match op {
             Operation::Install { version: v, .. } => v != version,
             _ => true,
         });
+        self.completed_in_batch += before - self.active_installs.len();
     }
 
     pub fn drain_next(&mut self) -> (Vec<String>, Option<OperationRequest>) {
@@ -186,10 +398,107 @@ pub enum Modal {
         versions: Vec<String>,
         keeping: String,
     },
+    ConfirmBulkUninstallSelected {
+        versions: Vec<String>,
+        /// The selected default version, left out so it keeps its own
+        /// confirmation.
+        kept_default: Option<String>,
+    },
     ConfirmUninstallDefault {
         version: String,
     },
+    ConfirmReinstall {
+        version: String,
+        is_default: bool,
+        keep_globals: bool,
+    },
+    ConfirmUninstallProtected {
+        version: String,
+        is_default: bool,
+    },
+    ConfirmInstallWithoutBinary {
+        version: String,
+        platform: String,
+        /// Whether the unofficial builds are offered for the platform.
+        unofficial_builds: bool,
+    },
+    ConfirmResolvedInstall {
+        spec: String,
+        candidates: Vec<String>,
+        selected: String,
+    },
+    ConfirmSetDefaultForeignArch {
+        version: String,
+        arch: BinaryArch,
+    },
+    ConfirmQuit {
+        active: usize,
+    },
+    ConfirmClose {
+        can_minimize: bool,
+        remember: bool,
+    },
+    ResumeOperations {
+        environment: String,
+        operations: Vec<OperationRequest>,
+    },
+    SetupSnippet {
+        version: String,
+        target: crate::snippets::SnippetTarget,
+        snippet: String,
+    },
+    CiMatrix {
+        majors: Vec<u32>,
+        selected: Vec<u32>,
+        include_engines: bool,
+    },
+    TestMatrix {
+        versions: Vec<String>,
+        project: Option<PathBuf>,
+        command: String,
+        runs: Vec<crate::test_matrix::MatrixRun>,
+        toolchain: crate::toolchain::ProjectToolchain,
+        /// Asking whether the project is trusted before its command runs.
+        confirming_trust: bool,
+    },
+    BatchSummary {
+        summary: BatchSummary,
+    },
+    OperationFailure {
+        failure: OperationFailure,
+    },
+    EditMajorNote {
+        major: u32,
+        draft: String,
+    },
+    ConfirmJetBrainsUpdate {
+        changes: Vec<crate::integrations::jetbrains::InterpreterChange>,
+    },
     KeyboardShortcuts,
+    BackendUpgrades {
+        backend: &'static str,
+        version: String,
+        upgrades: Vec<BackendUpgrade>,
+    },
+}
+
+/// Progress of the backend upgrade in one environment.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BackendUpgradeOutcome {
+    Running,
+    /// Done, with how it was upgraded.
+    Upgraded(String),
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct BackendUpgrade {
+    pub env_id: EnvironmentId,
+    pub name: String,
+    pub from: Option<String>,
+    pub outcome: BackendUpgradeOutcome,
+    /// Last line of output while the upgrade runs.
+    pub progress: Option<String>,
 }
 
 #[cfg(test)]
@@ -636,4 +945,137 @@ mod tests {
         assert!(q.active_installs.is_empty());
         assert!(!q.is_busy_for_exclusive());
     }
+
+    #[test]
+    fn is_idle_tracks_all_operation_sources() {
+        let mut q = OperationQueue::new();
+        assert!(q.is_idle());
+        assert_eq!(q.active_count(), 0);
+
+        q.enqueue(OperationRequest::Uninstall {
+            version: "18.0.0".to_string(),
+        });
+        assert!(!q.is_idle());
+
+        q.start_install("20.0.0".to_string());
+        q.start_exclusive(Operation::SetDefault {
+            version: "22.0.0".to_string(),
+        });
+        assert_eq!(q.active_count(), 3);
+
+        q.pending.clear();
+        q.remove_completed_install("20.0.0");
+        q.complete_exclusive();
+        assert!(q.is_idle());
+    }
+
+    #[test]
+    fn snapshot_includes_active_and_pending_in_order() {
+        let mut q = OperationQueue::new();
+        q.start_install("20.0.0".to_string());
+        q.enqueue(OperationRequest::SetDefault {
+            version: "20.0.0".to_string(),
+        });
+        q.enqueue(OperationRequest::Uninstall {
+            version: "18.0.0".to_string(),
+        });
+
+        assert_eq!(
+            q.snapshot(),
+            vec![
+                OperationRequest::Install {
+                    version: "20.0.0".to_string()
+                },
+                OperationRequest::SetDefault {
+                    version: "20.0.0".to_string()
+                },
+                OperationRequest::Uninstall {
+                    version: "18.0.0".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn progress_counts_completed_operations_in_batch() {
+        let mut q = OperationQueue::new();
+        assert_eq!(q.progress(), None);
+
+        q.start_install("20.0.0".to_string());
+        q.start_install("22.0.0".to_string());
+        q.enqueue(OperationRequest::SetDefault {
+            version: "22.0.0".to_string(),
+        });
+        assert_eq!(q.progress(), Some((0, 3)));
+
+        q.remove_completed_install("20.0.0");
+        assert_eq!(q.progress(), Some((1, 3)));
+
+        q.remove_completed_install("22.0.0");
+        q.pending.clear();
+        assert_eq!(q.progress(), None);
+
+        q.start_exclusive(Operation::Uninstall {
+            version: "18.0.0".to_string(),
+        });
+        assert_eq!(q.progress(), Some((0, 1)));
+    }
+
+    #[test]
+    fn batch_summary_only_for_drained_multi_operation_batches() {
+        let mut q = OperationQueue::new();
+        q.start_install("20.0.0".to_string());
+        q.start_install("22.0.0".to_string());
+
+        q.remove_completed_install("20.0.0");
+        q.record_result(
+            OperationRequest::Install {
+                version: "20.0.0".to_string(),
+            },
+            None,
+        );
+        assert!(q.in_batch());
+        assert!(q.take_batch_summary().is_none());
+
+        q.remove_completed_install("22.0.0");
+        q.record_result(
+            OperationRequest::Install {
+                version: "22.0.0".to_string(),
+            },
+            Some("network error".to_string()),
+        );
+        let summary = q.take_batch_summary().unwrap();
+        assert_eq!(summary.headline(), "Installed 1, failed 1");
+
+        q.start_exclusive(Operation::Uninstall {
+            version: "18.0.0".to_string(),
+        });
+        q.complete_exclusive();
+        q.record_result(
+            OperationRequest::Uninstall {
+                version: "18.0.0".to_string(),
+            },
+            None,
+        );
+        assert!(!q.in_batch());
+        assert!(q.take_batch_summary().is_none());
+    }
+
+    #[test]
+    fn cancel_pending_for_leaves_other_versions() {
+        let mut q = OperationQueue::new();
+        q.enqueue(OperationRequest::Install {
+            version: "20.0.0".to_string(),
+        });
+        q.enqueue(OperationRequest::Install {
+            version: "22.0.0".to_string(),
+        });
+        q.enqueue(OperationRequest::SetDefault {
+            version: "20.0.0".to_string(),
+        });
+
+        assert_eq!(q.cancel_pending_for("20.0.0"), 2);
+        assert!(!q.has_pending_for_version("20.0.0"));
+        assert!(q.has_pending_for_version("22.0.0"));
+    }
 }
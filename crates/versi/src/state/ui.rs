@@ -1,21 +1,65 @@
+use std::path::PathBuf;
 use std::time::Instant;
 
+use versi_platform::EnvironmentId;
+
+use crate::backend_removal::{RemovalPlan, RemovalStep};
+use crate::integrations::jetbrains::InterpreterChange;
+use crate::scheduler::ScheduleDraft;
+use crate::settings::{EnvVarOverride, NotificationCategory};
+use crate::state::{BatchSummary, OperationFailure};
+
 #[derive(Debug, Clone)]
 pub struct Toast {
     pub id: usize,
     pub message: String,
     pub created_at: Instant,
+    pub summary: Option<BatchSummary>,
+    pub failure: Option<OperationFailure>,
+    pub category: NotificationCategory,
 }
 
 impl Toast {
     pub fn error(id: usize, message: String) -> Self {
+        Self::notice(id, message, NotificationCategory::OperationFailures)
+    }
+
+    /// An informational toast, such as an available update.
+    pub fn notice(id: usize, message: String, category: NotificationCategory) -> Self {
         Self {
             id,
             message,
             created_at: Instant::now(),
+            summary: None,
+            failure: None,
+            category,
+        }
+    }
+
+    pub fn operation_failed(id: usize, message: String, failure: OperationFailure) -> Self {
+        Self {
+            failure: Some(failure),
+            ..Self::error(id, message)
         }
     }
 
+    pub fn batch_summary(id: usize, summary: BatchSummary) -> Self {
+        let category = if summary.failed() == 0 {
+            NotificationCategory::Completions
+        } else {
+            NotificationCategory::OperationFailures
+        };
+        let message = summary.headline();
+        Self {
+            summary: Some(summary),
+            ..Self::notice(id, message, category)
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.summary.as_ref().is_some_and(|s| s.failed() == 0)
+    }
+
     pub fn is_expired(&self, timeout_secs: u64) -> bool {
         self.created_at.elapsed().as_secs() > timeout_secs
     }
@@ -26,6 +70,25 @@ pub struct SettingsModalState {
     pub shell_statuses: Vec<ShellSetupStatus>,
     pub checking_shells: bool,
     pub log_file_size: Option<u64>,
+    pub testing_connection: bool,
+    pub connection_test: Option<Result<(), String>>,
+    /// The detected proxy, or why detection failed; `None` while detecting.
+    pub proxy_status: Option<Result<String, String>>,
+    pub checking_npm: bool,
+    pub npm_checks: Vec<crate::npm_doctor::NpmCheck>,
+    pub resolving_shells: bool,
+    pub resolved_versions: Vec<(versi_shell::ShellType, versi_shell::NodeResolution)>,
+    pub troubleshooting: bool,
+    pub troubleshoot_report: Option<versi_shell::TroubleshootReport>,
+    /// A profile fix waiting for the user to look over the change.
+    pub terminal_fix: Option<TerminalFixPreview>,
+    /// Where the profile was backed up before the last applied fix.
+    pub terminal_fix_backup: Option<PathBuf>,
+    pub checking_jetbrains: bool,
+    pub jetbrains_plan: Option<Result<Vec<InterpreterChange>, String>>,
+    pub backend_removal: Option<BackendRemoval>,
+    pub schedule_draft: ScheduleDraft,
+    pub env_var_draft: Option<EnvVarDraft>,
 }
 
 impl SettingsModalState {
@@ -34,16 +97,70 @@ impl SettingsModalState {
             shell_statuses: Vec::new(),
             checking_shells: false,
             log_file_size: None,
+            testing_connection: false,
+            connection_test: None,
+            proxy_status: None,
+            checking_npm: false,
+            npm_checks: Vec::new(),
+            resolving_shells: false,
+            resolved_versions: Vec::new(),
+            troubleshooting: false,
+            troubleshoot_report: None,
+            terminal_fix: None,
+            terminal_fix_backup: None,
+            checking_jetbrains: false,
+            jetbrains_plan: None,
+            backend_removal: None,
+            schedule_draft: ScheduleDraft::default(),
+            env_var_draft: None,
         }
     }
 }
 
+/// Environment variables being edited, saved once a field is submitted.
+#[derive(Debug, Clone)]
+pub struct EnvVarDraft {
+    pub env_id: EnvironmentId,
+    pub vars: Vec<EnvVarOverride>,
+}
+
+impl EnvVarDraft {
+    /// Whether every named row can be exported. Unnamed rows are ignored.
+    pub fn is_valid(&self) -> bool {
+        self.vars.iter().all(|var| {
+            let key = var.key.trim();
+            key.is_empty() || versi_backend::is_valid_env_key(key)
+        })
+    }
+}
+
+/// A terminal troubleshooter fix worked out but not yet written.
+#[derive(Debug, Clone)]
+pub struct TerminalFixPreview {
+    pub path: PathBuf,
+    pub shell_type: versi_shell::ShellType,
+    pub edit: versi_shell::ShellConfigEdit,
+}
+
+/// Progress of the assisted flow that uninstalls the active version manager.
+#[derive(Debug, Clone)]
+pub enum BackendRemoval {
+    Planning,
+    Review(RemovalPlan),
+    Confirm(RemovalPlan),
+    Running,
+    Done(Vec<RemovalStep>),
+}
+
 #[derive(Debug, Clone)]
 pub struct ShellSetupStatus {
+    pub environment: EnvironmentId,
     pub shell_type: versi_shell::ShellType,
     pub shell_name: String,
     pub status: ShellVerificationStatus,
     pub configuring: bool,
+    /// What configuring the shell would change, once previewed.
+    pub preview: Option<Result<Vec<String>, String>>,
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use versi_backend::NodeVersion;
+use versi_platform::AppPaths;
+
+use crate::state::{EnvironmentState, MainState, OperationRequest};
+
+/// Machine-readable summary of the app written to the data directory, so
+/// status-bar tools can show the default Node version without talking to
+/// the backend themselves. It is left in place on exit, since the default
+/// version stays valid; `pid` tells readers whether the app is running.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PublicStatus {
+    pub version: &'static str,
+    pub pid: u32,
+    pub active_environment: String,
+    pub environments: Vec<EnvironmentStatus>,
+    pub operations: OperationsStatus,
+    pub updates: UpdatesStatus,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EnvironmentStatus {
+    pub id: String,
+    pub name: String,
+    pub backend: &'static str,
+    pub available: bool,
+    pub default: Option<String>,
+    /// Version the most recently used shell runs, when the backend can tell.
+    pub active: Option<String>,
+    pub installed: usize,
+    /// Newest release of each installed major that is ahead of what is
+    /// installed.
+    pub node_updates: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OperationsStatus {
+    pub running: Vec<OperationRequest>,
+    pub pending: Vec<OperationRequest>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UpdatesStatus {
+    pub app: Option<String>,
+    pub backend: Option<String>,
+}
+
+impl PublicStatus {
+    pub fn capture(state: &MainState) -> Self {
+        let latest_by_major = &state.available_versions.remote().latest_by_major;
+        let mut operations = state.operation_queue.snapshot();
+        let pending = operations.split_off(state.operation_queue.running_count());
+
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            pid: std::process::id(),
+            active_environment: state.active_environment().id.settings_key(),
+            environments: state
+                .environments
+                .iter()
+                .map(|env| EnvironmentStatus::capture(env, latest_by_major))
+                .collect(),
+            operations: OperationsStatus {
+                running: operations,
+                pending,
+            },
+            updates: UpdatesStatus {
+                app: state.app_update.as_ref().map(|u| u.latest_version.clone()),
+                backend: state
+                    .backend_update
+                    .as_ref()
+                    .map(|u| u.latest_version.clone()),
+            },
+        }
+    }
+
+    /// Replaces the file in one step so readers never see a partial write.
+    pub fn save(&self) {
+        let Ok(paths) = AppPaths::new() else {
+            return;
+        };
+        let _ = paths.ensure_dirs();
+        let Ok(data) = serde_json::to_string_pretty(self) else {
+            return;
+        };
+        let path = paths.status_file();
+        let tmp = path.with_extension("json.tmp");
+        if let Err(e) = std::fs::write(&tmp, data).and_then(|()| std::fs::rename(&tmp, &path)) {
+            log::debug!("Failed to write status file: {}", e);
+        }
+    }
+}
+
+impl EnvironmentStatus {
+    fn capture(env: &EnvironmentState, latest_by_major: &HashMap<u32, NodeVersion>) -> Self {
+        Self {
+            id: env.id.settings_key(),
+            name: env.name.clone(),
+            backend: env.backend_name,
+            available: env.available,
+            default: env.default_version.as_ref().map(|v| v.to_string()),
+            active: env.active_version.as_ref().map(|v| v.to_string()),
+            installed: env.installed_versions.len(),
+            node_updates: node_updates(env, latest_by_major),
+        }
+    }
+}
+
+fn node_updates(
+    env: &EnvironmentState,
+    latest_by_major: &HashMap<u32, NodeVersion>,
+) -> Vec<String> {
+    env.version_groups
+        .iter()
+        .filter_map(|group| {
+            let installed_latest = group.versions.iter().map(|v| &v.version).max()?;
+            latest_by_major
+                .get(&group.major)
+                .filter(|latest| *latest > installed_latest)
+                .map(|latest| latest.to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use versi_backend::InstalledVersion;
+    use versi_platform::EnvironmentId;
+
+    fn environment(installed: &[&str], default: Option<&str>) -> EnvironmentState {
+        let mut env = EnvironmentState::new(EnvironmentId::Native, "fnm", None);
+        env.update_versions(
+            installed
+                .iter()
+                .map(|v| InstalledVersion {
+                    version: v.parse().unwrap(),
+                    is_default: Some(*v) == default,
+                    lts_codename: None,
+                    install_date: None,
+                    disk_size: None,
+                })
+                .collect(),
+        );
+        env
+    }
+
+    #[test]
+    fn reports_default_and_outdated_majors() {
+        let env = environment(&["v20.10.0", "v20.11.0", "v22.1.0"], Some("v22.1.0"));
+        let latest_by_major = HashMap::from([
+            (20, "v20.12.2".parse().unwrap()),
+            (22, "v22.1.0".parse().unwrap()),
+            (24, "v24.0.0".parse().unwrap()),
+        ]);
+
+        let status = EnvironmentStatus::capture(&env, &latest_by_major);
+        assert_eq!(status.id, "native");
+        assert_eq!(status.default.as_deref(), Some("v22.1.0"));
+        assert_eq!(status.installed, 3);
+        assert_eq!(status.node_updates, vec!["v20.12.2"]);
+    }
+}
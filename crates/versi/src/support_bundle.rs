@@ -0,0 +1,277 @@
+use std::io::{Cursor, Write};
+
+use chrono::{DateTime, Local};
+use serde_json::Value;
+use versi_backend::BackendInfo;
+use zip::write::SimpleFileOptions;
+
+use crate::settings::AppSettings;
+use crate::state::EnvironmentState;
+
+/// Files in a support bundle and what each holds, shown before exporting.
+pub const CONTENTS: [(&str, &str); 4] = [
+    ("system.txt", "Versi, OS and backend versions"),
+    (
+        "environments.txt",
+        "Each environment's backend, installed versions and default",
+    ),
+    (
+        "settings.json",
+        "Settings, without tokens, mirrors, notes, project paths or environment variables",
+    ),
+    ("versi.log", "The last 500 lines of the app log"),
+];
+
+/// Settings left out of a bundle because they may hold credentials or say
+/// more about the user than about the problem.
+const REDACTED_SETTINGS: &[&str] = &[
+    "github_token",
+    "pac_url",
+    "node_dist_mirror",
+    "environment_variables",
+    "search_history",
+    "saved_filters",
+    "major_notes",
+    "followed_directory",
+    "project_trust",
+];
+
+const REDACTED: &str = "<redacted>";
+
+/// Replaces the home directory and user name in text bound for a bundle.
+pub struct Anonymizer {
+    home: Option<String>,
+    user: Option<String>,
+}
+
+impl Anonymizer {
+    pub fn new(home: Option<String>, user: Option<String>) -> Self {
+        Self {
+            home: home.filter(|home| home.len() > 1),
+            // Very short names would match inside unrelated words.
+            user: user.filter(|user| user.len() >= 3),
+        }
+    }
+
+    pub fn current() -> Self {
+        Self::new(
+            dirs::home_dir().map(|home| home.display().to_string()),
+            std::env::var("USER")
+                .or_else(|_| std::env::var("USERNAME"))
+                .ok(),
+        )
+    }
+
+    pub fn scrub(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        if let Some(home) = &self.home {
+            text = text.replace(home.as_str(), "~");
+        }
+        if let Some(user) = &self.user {
+            text = text.replace(user.as_str(), "<user>");
+        }
+        text
+    }
+
+    fn scrub_value(&self, value: Value) -> Value {
+        match value {
+            Value::String(s) => Value::String(self.scrub(&s)),
+            Value::Array(items) => {
+                Value::Array(items.into_iter().map(|v| self.scrub_value(v)).collect())
+            }
+            Value::Object(map) => Value::Object(
+                map.into_iter()
+                    .map(|(key, v)| (self.scrub(&key), self.scrub_value(v)))
+                    .collect(),
+            ),
+            other => other,
+        }
+    }
+}
+
+/// App state gathered for a bug report, with personal details taken out.
+pub struct SupportBundle<'a> {
+    pub settings: &'a AppSettings,
+    pub environments: &'a [EnvironmentState],
+    pub backend: &'a BackendInfo,
+    pub app_log: String,
+    pub created_at: DateTime<Local>,
+    pub anonymizer: Anonymizer,
+}
+
+impl SupportBundle<'_> {
+    pub fn file_name(&self) -> String {
+        format!(
+            "versi-support-{}.zip",
+            self.created_at.format("%Y-%m-%d-%H%M%S")
+        )
+    }
+
+    pub fn to_zip(&self) -> Result<Vec<u8>, String> {
+        let files = [
+            self.system(),
+            self.environments(),
+            self.settings()?,
+            self.anonymizer.scrub(&self.app_log),
+        ];
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for ((name, _), content) in CONTENTS.iter().zip(files) {
+            zip.start_file(*name, SimpleFileOptions::default())
+                .map_err(|e| e.to_string())?;
+            zip.write_all(content.as_bytes())
+                .map_err(|e| e.to_string())?;
+        }
+        let cursor = zip.finish().map_err(|e| e.to_string())?;
+        Ok(cursor.into_inner())
+    }
+
+    fn system(&self) -> String {
+        let backend = self.backend;
+        let lines = [
+            format!("Versi: {}", env!("CARGO_PKG_VERSION")),
+            format!("OS: {} ({})", std::env::consts::OS, std::env::consts::ARCH),
+            format!("Created at: {}", self.created_at.to_rfc3339()),
+            format!(
+                "Renderer: {}",
+                crate::renderer::current()
+                    .map(|selection| selection.describe())
+                    .unwrap_or_else(|| "unknown".to_string())
+            ),
+            String::new(),
+            format!("Backend: {}", backend.name),
+            format!(
+                "Backend version: {}",
+                backend.version.as_deref().unwrap_or("unknown")
+            ),
+            format!("Backend path: {}", backend.path.display()),
+            format!(
+                "Found via: {}",
+                backend.source.as_deref().unwrap_or("unknown")
+            ),
+        ];
+        self.anonymizer.scrub(&(lines.join("\n") + "\n"))
+    }
+
+    fn environments(&self) -> String {
+        let sections: Vec<String> = self
+            .environments
+            .iter()
+            .map(|env| {
+                let mut lines = vec![
+                    format!("Environment: {}", env.name),
+                    format!(
+                        "Backend: {} {}",
+                        env.backend_name,
+                        env.backend_version.as_deref().unwrap_or("unknown")
+                    ),
+                    format!(
+                        "Target: {}",
+                        env.target
+                            .map(|target| target.dist_file_key())
+                            .unwrap_or_else(|| "unknown".to_string())
+                    ),
+                    format!(
+                        "Installed versions: {}",
+                        env.installed_versions
+                            .iter()
+                            .map(|v| v.version.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    format!(
+                        "Default: {}",
+                        env.default_version
+                            .as_ref()
+                            .map(|v| v.to_string())
+                            .unwrap_or_else(|| "none".to_string())
+                    ),
+                ];
+                if let Some(error) = &env.error {
+                    lines.push(format!("Error: {}", error.summary()));
+                }
+                lines.join("\n")
+            })
+            .collect();
+        self.anonymizer.scrub(&(sections.join("\n\n") + "\n"))
+    }
+
+    fn settings(&self) -> Result<String, String> {
+        let mut value = serde_json::to_value(self.settings).map_err(|e| e.to_string())?;
+        if let Value::Object(map) = &mut value {
+            for key in REDACTED_SETTINGS {
+                if let Some(setting) = map.get_mut(*key)
+                    && !is_unset(setting)
+                {
+                    *setting = Value::String(REDACTED.to_string());
+                }
+            }
+        }
+        serde_json::to_string_pretty(&self.anonymizer.scrub_value(value)).map_err(|e| e.to_string())
+    }
+}
+
+/// Whether a setting holds nothing worth hiding, so the bundle can still
+/// tell that it was never set.
+fn is_unset(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Array(items) => items.is_empty(),
+        Value::Object(map) => map.is_empty(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+    use std::path::PathBuf;
+
+    use versi_platform::EnvironmentId;
+
+    use super::*;
+
+    #[test]
+    fn bundle_leaves_out_personal_details() {
+        let mut settings = AppSettings::default();
+        settings.github_token = Some("ghp_secret".to_string());
+        settings.fnm_dir = Some(PathBuf::from("/home/alex/.fnm"));
+        let backend = BackendInfo {
+            name: "fnm",
+            path: PathBuf::from("/home/alex/.local/bin/fnm"),
+            version: Some("1.38.1".to_string()),
+            data_dir: None,
+            in_path: true,
+            source: Some("PATH".to_string()),
+        };
+        let environments = [EnvironmentState::new(EnvironmentId::Native, "fnm", None)];
+        let bundle = SupportBundle {
+            settings: &settings,
+            environments: &environments,
+            backend: &backend,
+            app_log: "[INFO] Signed in as alex\n".to_string(),
+            created_at: Local::now(),
+            anonymizer: Anonymizer::new(Some("/home/alex".to_string()), Some("alex".to_string())),
+        };
+
+        let bytes = bundle.to_zip().unwrap();
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let mut read = |name: &str| {
+            let mut content = String::new();
+            archive
+                .by_name(name)
+                .unwrap()
+                .read_to_string(&mut content)
+                .unwrap();
+            content
+        };
+
+        let settings = read("settings.json");
+        assert!(!settings.contains("ghp_secret"));
+        assert!(settings.contains("\"github_token\": \"<redacted>\""));
+        assert!(settings.contains("~/.fnm"));
+        assert!(settings.contains("\"major_notes\": {}"));
+        assert!(read("system.txt").contains("Backend path: ~/.local/bin/fnm"));
+        assert!(read("environments.txt").contains("Default: none"));
+        assert_eq!(read("versi.log"), "[INFO] Signed in as <user>\n");
+    }
+}
@@ -0,0 +1,129 @@
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use versi_backend::{BackendError, VersionManager};
+
+/// How long one version may run the test command before it counts as failed.
+pub const RUN_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Lines from the end of a failed run shown in the results table.
+const FAILURE_TAIL_LINES: usize = 3;
+
+/// Progress of the test command under one version.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MatrixOutcome {
+    Pending,
+    Running,
+    Passed,
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct MatrixRun {
+    pub version: String,
+    pub outcome: MatrixOutcome,
+    pub duration: Option<Duration>,
+}
+
+impl MatrixRun {
+    pub fn pending(version: String) -> Self {
+        Self {
+            version,
+            outcome: MatrixOutcome::Pending,
+            duration: None,
+        }
+    }
+}
+
+/// Splits a command line into arguments on whitespace, keeping single- or
+/// double-quoted parts together.
+pub fn split_command(command: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut quote = None;
+
+    for c in command.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => current.push(c),
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                in_arg = true;
+            }
+            None if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            None => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    args
+}
+
+/// Runs `command` from `project` with `version` on `PATH` and reports
+/// whether it exited successfully, along with how long it took.
+pub async fn run_version(
+    backend: &dyn VersionManager,
+    version: &str,
+    command: &[String],
+    project: &Path,
+    timeout: Duration,
+) -> (Result<(), String>, Duration) {
+    let args: Vec<&str> = command.iter().map(String::as_str).collect();
+    let started = Instant::now();
+    let result = match tokio::time::timeout(timeout, backend.exec_in(version, &args, project)).await
+    {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(BackendError::CommandFailed { stderr })) => Err(failure_summary(&stderr)),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(format!("Timed out after {}s", timeout.as_secs())),
+    };
+    (result, started.elapsed())
+}
+
+fn failure_summary(stderr: &str) -> String {
+    let lines: Vec<&str> = stderr
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    if lines.is_empty() {
+        return "Exited with an error".to_string();
+    }
+    let start = lines.len().saturating_sub(FAILURE_TAIL_LINES);
+    lines[start..].join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_command_keeping_quoted_arguments() {
+        assert_eq!(split_command("npm test"), vec!["npm", "test"]);
+        assert_eq!(
+            split_command("  npx  jest --testNamePattern \"parses dates\" "),
+            vec!["npx", "jest", "--testNamePattern", "parses dates"]
+        );
+        assert_eq!(split_command("node -e ''"), vec!["node", "-e", ""]);
+        assert!(split_command("   ").is_empty());
+    }
+
+    #[test]
+    fn failure_summary_keeps_last_lines() {
+        assert_eq!(
+            failure_summary("npm ERR! one\n\nnpm ERR! two\nnpm ERR! three\nnpm ERR! four\n"),
+            "npm ERR! two\nnpm ERR! three\nnpm ERR! four"
+        );
+        assert_eq!(failure_summary("  \n"), "Exited with an error");
+    }
+}
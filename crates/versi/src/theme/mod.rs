@@ -1,5 +1,7 @@
 pub mod styles;
 
+use std::time::Duration;
+
 use iced::theme::Palette;
 use iced::{Theme, color};
 
@@ -17,6 +19,12 @@ pub mod tahoe {
     }
 }
 
+/// Opacity of the window background while native vibrancy shows behind it.
+pub const VIBRANCY_BACKGROUND_ALPHA: f32 = 0.6;
+
+/// How often the scheduled theme checks the clock.
+pub const THEME_SCHEDULE_INTERVAL: Duration = Duration::from_secs(60);
+
 pub fn light_theme() -> Theme {
     Theme::custom(
         "Versi Light".to_string(),
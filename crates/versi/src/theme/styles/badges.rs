@@ -54,3 +54,21 @@ pub fn badge_eol(_theme: &Theme) -> container::Style {
         ..Default::default()
     }
 }
+
+pub fn badge_error(_theme: &Theme) -> container::Style {
+    let error_color = Color::from_rgb8(255, 59, 48);
+
+    container::Style {
+        background: Some(Background::Color(Color {
+            a: 0.15,
+            ..error_color
+        })),
+        text_color: Some(error_color),
+        border: Border {
+            radius: crate::theme::tahoe::RADIUS_SM.into(),
+            width: 0.0,
+            color: Color::TRANSPARENT,
+        },
+        ..Default::default()
+    }
+}
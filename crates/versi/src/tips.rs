@@ -0,0 +1,124 @@
+#[cfg(target_os = "macos")]
+const MOD_KEY: &str = "\u{2318}";
+#[cfg(not(target_os = "macos"))]
+const MOD_KEY: &str = "Ctrl+";
+
+/// A feature pointed out to new users after onboarding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tip {
+    Search,
+    BulkCleanup,
+    EnvironmentTabs,
+    KeyboardShortcuts,
+}
+
+impl Tip {
+    pub const ALL: [Tip; 4] = [
+        Tip::Search,
+        Tip::BulkCleanup,
+        Tip::EnvironmentTabs,
+        Tip::KeyboardShortcuts,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::Search => "Search understands versions and aliases",
+            Self::BulkCleanup => "Clean up in bulk",
+            Self::EnvironmentTabs => "One tab per environment",
+            Self::KeyboardShortcuts => "Keyboard shortcuts",
+        }
+    }
+
+    pub fn body(self) -> String {
+        match self {
+            Self::Search => format!(
+                "Type 22 or 22.11 to match a release line, or an alias such as lts, latest or lts/iron to find the version it points to. {}K jumps to the search field.",
+                MOD_KEY
+            ),
+            Self::BulkCleanup => format!(
+                "Keep Latest on a major removes all but its newest version, and Clean Up in the end-of-life banner removes versions that no longer get fixes. {}Click selects several versions to uninstall at once.",
+                MOD_KEY
+            ),
+            Self::EnvironmentTabs => format!(
+                "WSL distros and Git Bash get their own tab above the list, each with its own versions and default. {}Tab switches between them.",
+                MOD_KEY
+            ),
+            Self::KeyboardShortcuts => {
+                "Press ? at any time for the full list of shortcuts. These tips are in About whenever you want them again.".to_string()
+            }
+        }
+    }
+}
+
+/// Steps through [`Tip::ALL`] one tip at a time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TipsTour {
+    #[default]
+    Hidden,
+    Showing(usize),
+}
+
+impl TipsTour {
+    pub fn start() -> Self {
+        Self::Showing(0)
+    }
+
+    pub fn current(self) -> Option<Tip> {
+        match self {
+            Self::Hidden => None,
+            Self::Showing(idx) => Tip::ALL.get(idx).copied(),
+        }
+    }
+
+    /// The next tip, or hidden after the last one.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Showing(idx) if idx + 1 < Tip::ALL.len() => Self::Showing(idx + 1),
+            _ => Self::Hidden,
+        }
+    }
+
+    pub fn previous(self) -> Self {
+        match self {
+            Self::Showing(idx) => Self::Showing(idx.saturating_sub(1)),
+            Self::Hidden => Self::Hidden,
+        }
+    }
+
+    /// The current tip's 1-based position and the number of tips.
+    pub fn step(self) -> Option<(usize, usize)> {
+        match self {
+            Self::Hidden => None,
+            Self::Showing(idx) => Some((idx + 1, Tip::ALL.len())),
+        }
+    }
+
+    pub fn is_last(self) -> bool {
+        matches!(self, Self::Showing(idx) if idx + 1 == Tip::ALL.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tour_walks_every_tip_then_hides() {
+        let mut tour = TipsTour::start();
+        let mut seen = Vec::new();
+        while let Some(tip) = tour.current() {
+            seen.push(tip);
+            tour = tour.next();
+        }
+        assert_eq!(seen, Tip::ALL);
+        assert_eq!(tour, TipsTour::Hidden);
+    }
+
+    #[test]
+    fn previous_stops_at_the_first_tip() {
+        let tour = TipsTour::start().next().previous().previous();
+        assert_eq!(tour.current(), Some(Tip::Search));
+        assert_eq!(tour.step(), Some((1, Tip::ALL.len())));
+        assert!(TipsTour::start().next().next().next().is_last());
+    }
+}
@@ -0,0 +1,319 @@
+use std::path::Path;
+
+use log::warn;
+use serde_json::Value;
+use versi_backend::{NodeVersion, RemoteVersion};
+
+/// Node requirements a project declares in `package.json` and its package
+/// manager's workspace configuration.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProjectToolchain {
+    pub requirements: Vec<Requirement>,
+    /// Package manager whose workspace the project is the root of.
+    pub workspace: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Requirement {
+    /// Where the requirement comes from, e.g. `engines.node` or `pnpm@9.1.0`.
+    pub source: String,
+    pub range: String,
+}
+
+impl ProjectToolchain {
+    /// Reads the toolchain of the project in `dir`. Missing or unreadable
+    /// files leave the matching requirements out.
+    pub fn read(dir: &Path) -> Self {
+        let package = std::fs::read_to_string(dir.join("package.json"))
+            .ok()
+            .and_then(|content| match serde_json::from_str::<Value>(&content) {
+                Ok(package) => Some(package),
+                Err(e) => {
+                    warn!("Failed to parse {}/package.json: {}", dir.display(), e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+        let pnpm_workspace = std::fs::read_to_string(dir.join("pnpm-workspace.yaml")).ok();
+        Self::from_manifests(&package, pnpm_workspace.as_deref())
+    }
+
+    fn from_manifests(package: &Value, pnpm_workspace: Option<&str>) -> Self {
+        let mut requirements = Vec::new();
+
+        if let Some(range) = package.pointer("/engines/node").and_then(Value::as_str) {
+            requirements.push(Requirement {
+                source: "engines.node".to_string(),
+                range: range.trim().to_string(),
+            });
+        }
+
+        // `pnpm@9.1.0+sha512.abc...`
+        let package_manager = package
+            .get("packageManager")
+            .and_then(Value::as_str)
+            .and_then(|spec| spec.split('+').next()?.split_once('@'));
+        if let Some((name, version)) = package_manager
+            && let Some(range) = version
+                .split('.')
+                .next()
+                .and_then(|major| major.parse().ok())
+                .and_then(|major| package_manager_range(name, major))
+        {
+            requirements.push(Requirement {
+                source: format!("{}@{}", name, version),
+                range: range.to_string(),
+            });
+        }
+
+        if let Some(version) =
+            pnpm_workspace.and_then(|yaml| top_level_value(yaml, "useNodeVersion"))
+        {
+            requirements.push(Requirement {
+                source: "pnpm-workspace.yaml useNodeVersion".to_string(),
+                range: version,
+            });
+        }
+
+        let workspace = if pnpm_workspace.is_some() {
+            Some("pnpm".to_string())
+        } else if package.get("workspaces").is_some() {
+            Some(
+                package_manager
+                    .map(|(name, _)| name.to_string())
+                    .unwrap_or_else(|| "npm".to_string()),
+            )
+        } else {
+            None
+        };
+
+        Self {
+            requirements,
+            workspace,
+        }
+    }
+
+    /// Requirements that `version` does not meet. Ranges that cannot be
+    /// parsed are left out rather than reported.
+    pub fn unmet(&self, version: &NodeVersion) -> Vec<&Requirement> {
+        self.requirements
+            .iter()
+            .filter(|r| NodeRange::parse(&r.range).is_some_and(|range| !range.matches(version)))
+            .collect()
+    }
+
+    /// Newest release that meets every requirement, preferring LTS lines.
+    pub fn suggested_install<'a>(
+        &self,
+        versions: &'a [RemoteVersion],
+    ) -> Option<&'a RemoteVersion> {
+        let meets_all = |v: &&RemoteVersion| self.unmet(&v.version).is_empty();
+        let newest = |lts: bool| {
+            versions
+                .iter()
+                .filter(|v| !lts || v.lts_codename.is_some())
+                .filter(meets_all)
+                .max_by(|a, b| a.version.cmp(&b.version))
+        };
+        newest(true).or_else(|| newest(false))
+    }
+}
+
+/// Node versions each package manager major supports, from its `engines`.
+fn package_manager_range(name: &str, major: u32) -> Option<&'static str> {
+    match (name, major) {
+        ("pnpm", 9 | 10) => Some(">=18.12"),
+        ("pnpm", 8) => Some(">=16.14"),
+        ("pnpm", 7) => Some(">=14.6"),
+        ("yarn", 4) => Some(">=18.12"),
+        ("yarn", 2 | 3) => Some(">=12"),
+        ("npm", 11) => Some("^20.17.0 || >=22.9.0"),
+        ("npm", 10) => Some("^18.17.0 || >=20.5.0"),
+        _ => None,
+    }
+}
+
+/// Value of an unindented `key: value` line, without quotes or comments.
+fn top_level_value(yaml: &str, key: &str) -> Option<String> {
+    yaml.lines()
+        .find_map(|line| line.strip_prefix(key)?.strip_prefix(':'))
+        .map(|value| value.split(" #").next().unwrap_or_default())
+        .map(|value| value.trim().trim_matches(['"', '\'']).to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// An npm-style version range such as `^20.11 || >=22`.
+#[derive(Debug, Clone, PartialEq)]
+struct NodeRange(Vec<Vec<Comparator>>);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Comparator {
+    AtLeast(NodeVersion),
+    Below(NodeVersion),
+}
+
+impl Comparator {
+    fn matches(&self, version: &NodeVersion) -> bool {
+        match self {
+            Self::AtLeast(bound) => version >= bound,
+            Self::Below(bound) => version < bound,
+        }
+    }
+}
+
+impl NodeRange {
+    fn parse(input: &str) -> Option<Self> {
+        let alternatives = input
+            .split("||")
+            .map(|set| match set.split_once(" - ") {
+                Some((from, to)) => {
+                    let (from, to) = (Partial::parse(from)?, Partial::parse(to)?);
+                    Some(vec![Comparator::AtLeast(from.lowest()), to.upper_bound()])
+                }
+                None => set
+                    .split_whitespace()
+                    .map(parse_comparator)
+                    .collect::<Option<Vec<_>>>()
+                    .map(|sets| sets.into_iter().flatten().collect()),
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self(alternatives))
+    }
+
+    fn matches(&self, version: &NodeVersion) -> bool {
+        self.0
+            .iter()
+            .any(|set| set.iter().all(|c| c.matches(version)))
+    }
+}
+
+fn parse_comparator(token: &str) -> Option<Vec<Comparator>> {
+    let (op, rest) = ["^", "~", ">=", "<=", ">", "<", "="]
+        .into_iter()
+        .find_map(|op| token.strip_prefix(op).map(|rest| (op, rest)))
+        .unwrap_or(("", token));
+    let partial = Partial::parse(rest)?;
+
+    let comparators = match op {
+        "^" if partial.parts.first().is_some_and(|&major| major > 0) => vec![
+            Comparator::AtLeast(partial.lowest()),
+            Comparator::Below(NodeVersion::new(partial.parts[0] + 1, 0, 0)),
+        ],
+        "~" | "^" => vec![
+            Comparator::AtLeast(partial.lowest()),
+            Partial {
+                parts: partial.parts.iter().copied().take(2).collect(),
+            }
+            .upper_bound(),
+        ],
+        ">=" => vec![Comparator::AtLeast(partial.lowest())],
+        "<" => vec![Comparator::Below(partial.lowest())],
+        ">" => match partial.upper_bound() {
+            Comparator::Below(bound) => vec![Comparator::AtLeast(bound)],
+            at_least => vec![at_least],
+        },
+        "<=" => vec![partial.upper_bound()],
+        _ => vec![Comparator::AtLeast(partial.lowest()), partial.upper_bound()],
+    };
+    Some(comparators)
+}
+
+/// A version with any trailing parts left out or written as `x`/`*`.
+#[derive(Debug)]
+struct Partial {
+    parts: Vec<u32>,
+}
+
+impl Partial {
+    fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        let input = input.strip_prefix('v').unwrap_or(input);
+        let mut parts = Vec::new();
+        for part in input.split('.').take(3) {
+            if matches!(part, "x" | "X" | "*" | "") {
+                break;
+            }
+            parts.push(part.parse().ok()?);
+        }
+        Some(Self { parts })
+    }
+
+    fn lowest(&self) -> NodeVersion {
+        let part = |i: usize| self.parts.get(i).copied().unwrap_or(0);
+        NodeVersion::new(part(0), part(1), part(2))
+    }
+
+    /// Comparator that excludes everything past the versions this matches.
+    fn upper_bound(&self) -> Comparator {
+        match self.parts[..] {
+            [] => Comparator::AtLeast(NodeVersion::new(0, 0, 0)),
+            [major] => Comparator::Below(NodeVersion::new(major + 1, 0, 0)),
+            [major, minor] => Comparator::Below(NodeVersion::new(major, minor + 1, 0)),
+            [major, minor, patch, ..] => {
+                Comparator::Below(NodeVersion::new(major, minor, patch + 1))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn matches(range: &str, version: &str) -> bool {
+        NodeRange::parse(range)
+            .unwrap()
+            .matches(&version.parse().unwrap())
+    }
+
+    #[test]
+    fn evaluates_npm_ranges() {
+        assert!(matches(">=18.12", "v18.12.0"));
+        assert!(!matches(">=18.12", "v18.11.9"));
+        assert!(matches("^20.17.0 || >=22.9.0", "v22.9.0"));
+        assert!(!matches("^20.17.0 || >=22.9.0", "v21.0.0"));
+        assert!(matches("20.x", "v20.19.1"));
+        assert!(!matches("~20.11.0", "v20.12.0"));
+        assert!(matches(">18 <23", "v22.0.0"));
+        assert!(!matches(">18 <23", "v18.20.0"));
+        assert!(matches("18 - 20", "v20.5.0"));
+        assert!(matches("22.1.0", "v22.1.0"));
+        assert!(!matches("22.1.0", "v22.1.1"));
+        assert!(NodeRange::parse("latest").is_none());
+    }
+
+    #[test]
+    fn reads_engines_package_manager_and_pnpm_workspace() {
+        let package = json!({
+            "engines": { "node": ">=20" },
+            "packageManager": "pnpm@9.1.0+sha512.abc",
+        });
+        let toolchain = ProjectToolchain::from_manifests(
+            &package,
+            Some("packages:\n  - 'apps/*'\nuseNodeVersion: \"22.1.0\" # pinned\n"),
+        );
+
+        assert_eq!(toolchain.workspace.as_deref(), Some("pnpm"));
+        let requirements: Vec<(&str, &str)> = toolchain
+            .requirements
+            .iter()
+            .map(|r| (r.source.as_str(), r.range.as_str()))
+            .collect();
+        assert_eq!(
+            requirements,
+            vec![
+                ("engines.node", ">=20"),
+                ("pnpm@9.1.0", ">=18.12"),
+                ("pnpm-workspace.yaml useNodeVersion", "22.1.0"),
+            ]
+        );
+
+        let unmet: Vec<&str> = toolchain
+            .unmet(&"v20.11.0".parse().unwrap())
+            .iter()
+            .map(|r| r.source.as_str())
+            .collect();
+        assert_eq!(unmet, vec!["pnpm-workspace.yaml useNodeVersion"]);
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use versi_backend::InstallProgress;
+
+use crate::widgets::helpers::format_bytes;
+
+/// Samples older than this no longer count towards the speed, so it follows
+/// changes in the connection without jumping on every redraw.
+const SPEED_WINDOW: Duration = Duration::from_secs(5);
+
+/// Download progress of one install, with its speed averaged over the last
+/// few seconds.
+#[derive(Debug, Clone, Default)]
+pub struct InstallTransfer {
+    samples: VecDeque<(Instant, u64)>,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+}
+
+impl InstallTransfer {
+    pub fn record(&mut self, progress: InstallProgress, at: Instant) {
+        self.downloaded = progress.downloaded;
+        self.total = progress.total.or(self.total);
+        self.samples.push_back((at, progress.downloaded));
+        while self
+            .samples
+            .front()
+            .is_some_and(|&(sampled, _)| at.duration_since(sampled) > SPEED_WINDOW)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Bytes per second over the samples in the window.
+    pub fn speed(&self) -> Option<f64> {
+        let (first_at, first) = *self.samples.front()?;
+        let (last_at, last) = *self.samples.back()?;
+        let elapsed = last_at.duration_since(first_at).as_secs_f64();
+        (elapsed > 0.0 && last >= first).then(|| (last - first) as f64 / elapsed)
+    }
+
+    pub fn eta(&self) -> Option<Duration> {
+        let remaining = self.total?.saturating_sub(self.downloaded);
+        let speed = self.speed().filter(|&speed| speed > 0.0)?;
+        Some(Duration::from_secs_f64(remaining as f64 / speed))
+    }
+
+    pub fn percent(&self) -> Option<u64> {
+        let total = self.total.filter(|&total| total > 0)?;
+        Some((self.downloaded.min(total) * 100) / total)
+    }
+
+    /// Speed and time left, e.g. `4.2 MB/s, 12s left`.
+    pub fn rate_summary(&self) -> Option<String> {
+        let speed = self.speed()?;
+        let mut summary = format!("{}/s", format_bytes(speed as u64));
+        if let Some(eta) = self.eta() {
+            summary.push_str(&format!(", {} left", format_eta(eta)));
+        }
+        Some(summary)
+    }
+
+    /// Progress line for a busy button, e.g. `Installing 45% · 4.2 MB/s, 12s left`.
+    pub fn status(&self, verb: &str) -> String {
+        let mut status = match self.percent() {
+            Some(percent) => format!("{verb} {percent}%"),
+            None => format!("{verb} {}", format_bytes(self.downloaded)),
+        };
+        if let Some(summary) = self.rate_summary() {
+            status.push_str(&format!(" · {summary}"));
+        }
+        status
+    }
+}
+
+fn format_eta(eta: Duration) -> String {
+    let secs = eta.as_secs();
+    if secs >= 60 {
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs.max(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(downloaded: u64) -> InstallProgress {
+        InstallProgress {
+            downloaded,
+            total: Some(10_000_000),
+        }
+    }
+
+    #[test]
+    fn averages_speed_over_the_window() {
+        let start = Instant::now();
+        let mut transfer = InstallTransfer::default();
+        transfer.record(progress(0), start);
+        assert_eq!(transfer.speed(), None);
+
+        transfer.record(progress(1_000_000), start + Duration::from_secs(1));
+        transfer.record(progress(2_000_000), start + Duration::from_secs(2));
+        assert_eq!(transfer.speed(), Some(1_000_000.0));
+        assert_eq!(transfer.eta(), Some(Duration::from_secs(8)));
+        assert_eq!(transfer.percent(), Some(20));
+        assert!(
+            transfer
+                .status("Installing")
+                .starts_with("Installing 20% · ")
+        );
+
+        // The first samples fall out of the window.
+        transfer.record(progress(8_000_000), start + Duration::from_secs(6));
+        assert_eq!(transfer.speed(), Some(1_400_000.0));
+    }
+
+    #[test]
+    fn formats_time_left() {
+        assert_eq!(format_eta(Duration::from_millis(300)), "1s");
+        assert_eq!(format_eta(Duration::from_secs(95)), "1m 35s");
+    }
+}
@@ -25,6 +25,7 @@ pub enum TrayMessage {
 pub struct TrayMenuData {
     pub environments: Vec<EnvironmentData>,
     pub window_visible: bool,
+    pub read_only: bool,
 }
 
 pub struct EnvironmentData {
@@ -39,9 +40,14 @@ pub struct VersionData {
 }
 
 impl TrayMenuData {
-    pub fn from_environments(environments: &[EnvironmentState], window_visible: bool) -> Self {
+    pub fn from_environments(
+        environments: &[EnvironmentState],
+        window_visible: bool,
+        read_only: bool,
+    ) -> Self {
         Self {
             window_visible,
+            read_only,
             environments: environments
                 .iter()
                 .enumerate()
@@ -77,6 +83,7 @@ pub fn init_tray(behavior: &TrayBehavior) -> Result<(), Box<dyn std::error::Erro
     let menu = build_menu(&TrayMenuData {
         environments: vec![],
         window_visible: true,
+        read_only: false,
     });
 
     let tray_icon = TrayIconBuilder::new()
@@ -150,7 +157,7 @@ fn build_menu(data: &TrayMenuData) -> Menu {
             let _ = menu.append(&MenuItem::with_id(
                 MenuId::new(format!("set:{}:{}", env.env_index, ver.version)),
                 label,
-                true,
+                !data.read_only,
                 None,
             ));
         }
@@ -0,0 +1,99 @@
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+use versi_core::AppUpdate;
+
+/// How long "Later" hides an app update.
+pub const REMIND_LATER_DAYS: u64 = 1;
+
+/// App updates the user put off, and where this install falls in staged
+/// rollouts.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UpdateDeferral {
+    /// Release the user chose to skip. Newer releases are still offered.
+    #[serde(default)]
+    pub skipped_version: Option<String>,
+    #[serde(default)]
+    pub remind_after: Option<NaiveDate>,
+    /// 0 to 99, picked once so a growing rollout keeps including this
+    /// install.
+    #[serde(default)]
+    pub rollout_bucket: Option<u8>,
+}
+
+impl UpdateDeferral {
+    pub fn remind_later(&mut self, today: NaiveDate) {
+        self.remind_after = today.checked_add_days(Days::new(REMIND_LATER_DAYS));
+    }
+
+    pub fn skip(&mut self, version: &str) {
+        self.skipped_version = Some(version.to_string());
+        self.remind_after = None;
+    }
+
+    /// The rollout bucket, picking one the first time. Returns whether it
+    /// was newly picked and needs saving.
+    pub fn ensure_rollout_bucket(&mut self) -> bool {
+        if self.rollout_bucket.is_some() {
+            return false;
+        }
+        self.rollout_bucket = Some((RandomState::new().hash_one(0u8) % 100) as u8);
+        true
+    }
+
+    /// Whether `update` should be offered on `today`.
+    pub fn allows(&self, update: &AppUpdate, today: NaiveDate) -> bool {
+        if self.skipped_version.as_deref() == Some(update.latest_version.as_str()) {
+            return false;
+        }
+        if self.remind_after.is_some_and(|after| today < after) {
+            return false;
+        }
+        match (update.rollout_percentage, self.rollout_bucket) {
+            (Some(percentage), Some(bucket)) => bucket < percentage,
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(version: &str, rollout_percentage: Option<u8>) -> AppUpdate {
+        AppUpdate {
+            current_version: "1.0.0".to_string(),
+            latest_version: version.to_string(),
+            release_url: String::new(),
+            release_notes: None,
+            download_url: None,
+            download_size: None,
+            download_sha256: None,
+            rollout_percentage,
+            channel: None,
+        }
+    }
+
+    #[test]
+    fn honors_skips_reminders_and_rollouts() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        let tomorrow = today.succ_opt().unwrap();
+        let mut deferral = UpdateDeferral {
+            rollout_bucket: Some(40),
+            ..Default::default()
+        };
+        assert!(deferral.allows(&update("1.1.0", None), today));
+        assert!(!deferral.allows(&update("1.1.0", Some(25)), today));
+        assert!(deferral.allows(&update("1.1.0", Some(50)), today));
+
+        deferral.remind_later(today);
+        assert!(!deferral.allows(&update("1.1.0", None), today));
+        assert!(deferral.allows(&update("1.1.0", None), tomorrow));
+
+        deferral.skip("1.1.0");
+        assert!(!deferral.allows(&update("1.1.0", None), today));
+        assert!(deferral.allows(&update("1.2.0", None), today));
+    }
+}
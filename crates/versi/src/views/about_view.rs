@@ -3,11 +3,18 @@ use iced::{Alignment, Element, Length};
 
 use crate::icon;
 use crate::message::Message;
+use crate::metrics;
+use crate::settings::AppSettings;
 use crate::state::MainState;
+use crate::support_bundle;
 use crate::theme::styles;
 use crate::widgets::helpers::nav_icons;
 
-pub fn view<'a>(state: &'a MainState, has_tabs: bool) -> Element<'a, Message> {
+pub fn view<'a>(
+    state: &'a MainState,
+    settings: &'a AppSettings,
+    has_tabs: bool,
+) -> Element<'a, Message> {
     let header = row![
         text("About").size(14),
         Space::new().width(Length::Fill),
@@ -22,6 +29,17 @@ pub fn view<'a>(state: &'a MainState, has_tabs: bool) -> Element<'a, Message> {
         text("A native GUI for managing Node.js versions")
             .size(12)
             .color(iced::Color::from_rgb8(142, 142, 147)),
+        text(format!(
+            "Renderer: {}",
+            crate::renderer::current()
+                .map(|selection| selection.describe())
+                .unwrap_or_else(|| "unknown".to_string())
+        ))
+        .size(12)
+        .color(iced::Color::from_rgb8(142, 142, 147)),
+        text(backend_summary(state, settings))
+            .size(12)
+            .color(iced::Color::from_rgb8(142, 142, 147)),
         Space::new().height(12),
         row![
             button(
@@ -46,6 +64,12 @@ pub fn view<'a>(state: &'a MainState, has_tabs: bool) -> Element<'a, Message> {
             .padding([6, 12]),
         ]
         .spacing(8),
+        Space::new().height(20),
+        timings_section(),
+        Space::new().height(20),
+        support_bundle_section(),
+        Space::new().height(20),
+        tips_section(),
     ]
     .spacing(4)
     .width(Length::Fill);
@@ -65,3 +89,106 @@ pub fn view<'a>(state: &'a MainState, has_tabs: bool) -> Element<'a, Message> {
     .height(Length::Fill)
     .into()
 }
+
+/// How long installs, refreshes and detection took in each environment
+/// this session.
+fn timings_section<'a>() -> Element<'a, Message> {
+    let muted = iced::Color::from_rgb8(142, 142, 147);
+    let summaries = metrics::summaries();
+
+    let mut section = column![text("Operation Timings").size(14)].spacing(4);
+    if summaries.is_empty() {
+        return section
+            .push(text("Nothing has been timed yet").size(12).color(muted))
+            .into();
+    }
+    for summary in summaries {
+        section = section.push(
+            row![
+                text(format!(
+                    "{} \u{2014} {}",
+                    summary.env.display_name(),
+                    summary.kind.label()
+                ))
+                .size(12)
+                .width(Length::FillPortion(2)),
+                text(format!(
+                    "last {}, median {}, slowest {} ({} timed)",
+                    metrics::format_duration(summary.last),
+                    metrics::format_duration(summary.median),
+                    metrics::format_duration(summary.slowest),
+                    summary.count
+                ))
+                .size(12)
+                .color(muted)
+                .width(Length::FillPortion(3)),
+            ]
+            .spacing(8),
+        );
+    }
+    section.into()
+}
+
+/// What goes into a support bundle, listed next to the button that saves one.
+fn support_bundle_section<'a>() -> Element<'a, Message> {
+    let muted = iced::Color::from_rgb8(142, 142, 147);
+    let mut section = column![
+        text("Support Bundle").size(14),
+        text("A zip to attach to bug reports. Your home directory and user name are replaced.")
+            .size(12)
+            .color(muted),
+    ]
+    .spacing(4);
+    for (file, description) in support_bundle::CONTENTS {
+        section = section.push(
+            row![
+                text(file).size(12).width(Length::Fixed(120.0)),
+                text(description).size(12).color(muted),
+            ]
+            .spacing(8),
+        );
+    }
+    section
+        .push(Space::new().height(4))
+        .push(
+            button(text("Export Support Bundle...").size(12))
+                .on_press(Message::ExportSupportBundle)
+                .style(styles::secondary_button)
+                .padding([6, 12]),
+        )
+        .into()
+}
+
+fn tips_section<'a>() -> Element<'a, Message> {
+    let muted = iced::Color::from_rgb8(142, 142, 147);
+    column![
+        text("Tips").size(14),
+        text("The short tour of search, cleanup, environments and shortcuts shown after setup.")
+            .size(12)
+            .color(muted),
+        Space::new().height(4),
+        button(text("Show Tips").size(12))
+            .on_press(Message::ShowTips)
+            .style(styles::secondary_button)
+            .padding([6, 12]),
+    ]
+    .spacing(4)
+    .into()
+}
+
+fn backend_summary(state: &MainState, settings: &AppSettings) -> String {
+    let info = state.backend.backend_info();
+    let mut summary = format!("Backend: {}", info.name);
+    if let Some(version) = &info.version {
+        summary.push_str(&format!(" {}", version));
+    }
+    summary.push_str(&format!(" at {}", info.path.display()));
+    if let Some(source) = &info.source {
+        summary.push_str(&format!(" (found via {})", source));
+    }
+    summary.push_str(&format!(
+        "; updates: {}",
+        settings.update_channel_for(info.name).describe()
+    ));
+    summary
+}
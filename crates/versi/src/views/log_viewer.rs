@@ -0,0 +1,187 @@
+use iced::widget::{
+    Space, button, column, container, pick_list, row, scrollable, text, text_input,
+};
+use iced::{Alignment, Element, Font, Length};
+use log::Level;
+
+use crate::log_viewer::LogEntry;
+use crate::message::Message;
+use crate::state::MainState;
+use crate::theme::styles;
+use crate::widgets::helpers::nav_icons;
+use crate::widgets::status::Status;
+
+/// Matching entries rendered at once, counted from the newest.
+const RENDERED_ENTRIES: usize = 1000;
+
+const ALL_MODULES: &str = "All modules";
+
+pub fn view<'a>(state: &'a MainState, has_tabs: bool) -> Element<'a, Message> {
+    let viewer = &state.log_viewer;
+    let muted = iced::Color::from_rgb8(142, 142, 147);
+
+    let header = row![
+        button(text("Settings").size(12))
+            .on_press(Message::NavigateToSettings)
+            .style(styles::ghost_button)
+            .padding([4, 8]),
+        text("/").size(12).color(muted),
+        text("Logs").size(14),
+        Space::new().width(Length::Fill),
+        nav_icons(&state.view, state.refresh_rotation),
+    ]
+    .spacing(4)
+    .align_y(Alignment::Center);
+
+    let level_button = |label: &'static str, level: Option<Level>| {
+        button(text(label).size(11))
+            .on_press(Message::LogViewerLevelChanged(level))
+            .style(if viewer.level == level {
+                styles::ghost_button_active
+            } else {
+                styles::ghost_button
+            })
+            .padding([4, 8])
+    };
+
+    let mut modules = vec![ALL_MODULES.to_string()];
+    modules.extend(viewer.modules());
+    let selected_module = Some(
+        viewer
+            .module
+            .clone()
+            .unwrap_or_else(|| ALL_MODULES.to_string()),
+    );
+
+    let filters = row![
+        text_input("Search logs...", &viewer.query)
+            .on_input(Message::LogViewerSearchChanged)
+            .padding([6, 10])
+            .size(12)
+            .style(styles::search_input)
+            .width(Length::Fill),
+        level_button("Error", Some(Level::Error)),
+        level_button("Warn", Some(Level::Warn)),
+        level_button("Info", Some(Level::Info)),
+        level_button("All", None),
+        pick_list(modules, selected_module, |module| {
+            Message::LogViewerModuleChanged((module != ALL_MODULES).then_some(module))
+        })
+        .text_size(11)
+        .padding([4, 8]),
+    ]
+    .spacing(4)
+    .align_y(Alignment::Center);
+
+    let visible: Vec<&LogEntry> = viewer.visible().collect();
+    let shown = &visible[visible.len().saturating_sub(RENDERED_ENTRIES)..];
+    let lines = shown.iter().fold(column![].spacing(1), |lines, entry| {
+        let color = match entry.level {
+            Some(Level::Error) => Some(Status::Error.color()),
+            Some(Level::Warn) => Some(Status::Warning.color()),
+            Some(Level::Debug | Level::Trace) => Some(muted),
+            _ => None,
+        };
+        lines.push(
+            button(
+                text(&entry.text)
+                    .size(11)
+                    .font(Font::MONOSPACE)
+                    .style(move |_theme: &iced::Theme| text::Style { color }),
+            )
+            .on_press(Message::LogViewerLineClicked(entry.id))
+            .style(if viewer.is_selected(entry.id) {
+                styles::ghost_button_active
+            } else {
+                styles::ghost_button
+            })
+            .padding([1, 4])
+            .width(Length::Fill),
+        )
+    });
+
+    let body: Element<Message> = if let Some(error) = &viewer.error {
+        text(format!("Cannot read the log file: {}", error))
+            .size(12)
+            .color(Status::Error.color())
+            .into()
+    } else if shown.is_empty() {
+        text(if viewer.total() == 0 {
+            "The log is empty"
+        } else {
+            "No lines match the filters"
+        })
+        .size(12)
+        .color(muted)
+        .into()
+    } else {
+        scrollable(lines.padding(iced::Padding::default().right(12.0)))
+            .anchor_bottom()
+            .height(Length::Fill)
+            .into()
+    };
+
+    let count = if shown.len() < visible.len() {
+        format!(
+            "Showing the last {} of {} matching lines",
+            shown.len(),
+            visible.len()
+        )
+    } else {
+        format!("{} of {} lines", visible.len(), viewer.total())
+    };
+    let footer = row![
+        text(count).size(11).color(muted),
+        text(if viewer.selection.is_some() {
+            "Click another line to extend the selection"
+        } else {
+            "Click lines to select a range"
+        })
+        .size(11)
+        .color(muted),
+        Space::new().width(Length::Fill),
+        button(
+            text(if viewer.paused_at.is_some() {
+                "Resume"
+            } else {
+                "Pause"
+            })
+            .size(11)
+        )
+        .on_press(Message::LogViewerPauseToggled)
+        .style(styles::secondary_button)
+        .padding([4, 10]),
+        button(
+            text(if viewer.selection.is_some() {
+                "Copy Selection"
+            } else {
+                "Copy Visible"
+            })
+            .size(11)
+        )
+        .on_press(Message::LogViewerCopy)
+        .style(styles::secondary_button)
+        .padding([4, 10]),
+    ]
+    .spacing(12)
+    .align_y(Alignment::Center);
+
+    column![
+        container(header).padding(iced::Padding::new(0.0).right(24.0)),
+        Space::new().height(12),
+        container(filters).padding(iced::Padding::new(0.0).right(24.0)),
+        Space::new().height(12),
+        container(body).height(Length::Fill),
+        Space::new().height(8),
+        container(footer).padding(iced::Padding::new(0.0).right(24.0)),
+    ]
+    .spacing(0)
+    .padding(if has_tabs {
+        iced::Padding::new(24.0).right(0.0)
+    } else {
+        iced::Padding::new(24.0).top(12.0).right(0.0)
+    })
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .into()
+}
@@ -1,26 +1,185 @@
 use chrono::{DateTime, Utc};
-use iced::widget::{Space, button, column, row, text};
+use iced::widget::{Space, button, column, row, svg, text};
 use iced::{Alignment, Element, Length};
 
+use crate::banner_state::{BannerId, BannerSnooze};
+use crate::eol::{self, SupportPhase};
 use crate::message::Message;
-use crate::state::{MainState, NetworkStatus};
+use crate::metrics::{self, SlowOperation};
+use crate::safe_mode::{SAFE_MODE_FLAG, SafeMode};
+use crate::settings::AppSettings;
+use crate::state::{EnvironmentError, MainState, NetworkStatus};
 use crate::theme::styles;
+use crate::widgets::status::Status;
 
-pub(super) fn contextual_banners<'a>(state: &'a MainState) -> Option<Element<'a, Message>> {
+pub(super) fn contextual_banners<'a>(
+    state: &'a MainState,
+    settings: &AppSettings,
+) -> Option<Element<'a, Message>> {
+    let read_only = settings.is_read_only();
     let env = state.active_environment();
     let schedule = state.available_versions.schedule.as_ref();
+    let today = chrono::Local::now().date_naive();
+    let shown = |id| !settings.banner_states.is_hidden(id, today);
 
     let mut banners: Vec<Element<Message>> = Vec::new();
 
+    if let Some(safe_mode) = &state.safe_mode {
+        banners.push(safe_mode_banner(safe_mode));
+    }
+
+    if read_only {
+        banners.push(
+            button(
+                row![
+                    banner_icon(Status::Neutral),
+                    text("Read-only mode \u{2014} installs, uninstalls, and default changes are disabled")
+                        .size(13),
+                    Space::new().width(Length::Fill),
+                    text("Settings").size(13),
+                ]
+                .spacing(8)
+                .align_y(Alignment::Center),
+            )
+            .on_press(Message::NavigateToSettings)
+            .style(styles::banner_button_info)
+            .padding([12, 16])
+            .width(Length::Fill)
+            .into(),
+        );
+    }
+
+    if let Some(missing) = &env.missing_default {
+        let replace: Element<Message> = match env.suggested_default() {
+            Some(suggested) => button(text(format!("Use {}", suggested)).size(12))
+                .on_press_maybe((!read_only).then(|| Message::SetDefault(suggested.to_string())))
+                .style(styles::ghost_button)
+                .padding([2, 6])
+                .into(),
+            None => text("Install a version to set a new default")
+                .size(12)
+                .into(),
+        };
+        banners.push(
+            button(
+                row![
+                    banner_icon(Status::Warning),
+                    text(format!(
+                        "Default version Node {} was removed outside Versi",
+                        missing
+                    ))
+                    .size(13),
+                    Space::new().width(Length::Fill),
+                    replace,
+                    button(text("Dismiss").size(12))
+                        .on_press(Message::DismissMissingDefault)
+                        .style(styles::ghost_button)
+                        .padding([2, 6]),
+                ]
+                .spacing(8)
+                .align_y(Alignment::Center),
+            )
+            .style(styles::banner_button_warning)
+            .padding([12, 16])
+            .width(Length::Fill)
+            .into(),
+        );
+    }
+
+    if let Some(slow) = metrics::slow_operation() {
+        banners.push(slow_operation_banner(&slow));
+    }
+
+    if let Some(pin) = &state.project_pin {
+        let versions = &state.available_versions.remote().versions;
+        let default_fits = env
+            .default_version
+            .as_ref()
+            .is_some_and(|default| pin.accepts(default, versions, schedule, today));
+        if !default_fits {
+            let action =
+                match pin.installed_match(&env.installed_versions, versions, schedule, today) {
+                    Some(version) => button(text(format!("Set {} as default", version)).size(12))
+                        .on_press_maybe(
+                            (!read_only).then(|| Message::SetDefault(version.to_string())),
+                        ),
+                    None => {
+                        let target = pin.install_target(versions, schedule, today);
+                        button(text(format!("Install {}", target)).size(12))
+                            .on_press_maybe((!read_only).then(|| Message::StartInstall(target)))
+                    }
+                };
+            banners.push(
+                button(
+                    row![
+                        banner_icon(Status::Neutral),
+                        text(format!(
+                            "{} needs Node {} ({})",
+                            pin.name(),
+                            pin.spec,
+                            pin.file
+                        ))
+                        .size(13),
+                        Space::new().width(Length::Fill),
+                        action.style(styles::ghost_button).padding([2, 6]),
+                        button(text("Stop Following").size(12))
+                            .on_press(Message::ClearFollowedDirectory)
+                            .style(styles::ghost_button)
+                            .padding([2, 6]),
+                    ]
+                    .spacing(8)
+                    .align_y(Alignment::Center),
+                )
+                .style(styles::banner_button_info)
+                .padding([12, 16])
+                .width(Length::Fill)
+                .into(),
+            );
+        }
+    }
+
+    if let Some(EnvironmentError::ListFailed(error)) = &env.error
+        && !env.installed_versions.is_empty()
+    {
+        let retry_note = env
+            .retry_in
+            .map(|delay| format!(", retrying in {}s", delay.as_secs()))
+            .unwrap_or_default();
+        banners.push(
+            button(
+                row![
+                    banner_icon(Status::Warning),
+                    text(format!(
+                        "Could not refresh {}{} \u{2014} {}",
+                        env.name, retry_note, error
+                    ))
+                    .size(13),
+                    Space::new().width(Length::Fill),
+                    text("Retry").size(13),
+                ]
+                .spacing(8)
+                .align_y(Alignment::Center),
+            )
+            .on_press(Message::RefreshEnvironment)
+            .style(styles::banner_button_warning)
+            .padding([12, 16])
+            .width(Length::Fill)
+            .into(),
+        );
+    }
+
     match state.available_versions.network_status() {
-        NetworkStatus::Offline => {
+        NetworkStatus::Offline if shown(BannerId::Offline) => {
             banners.push(
                 button(
                     row![
+                        banner_icon(Status::Warning),
                         text("Could not load available versions").size(13),
                         Space::new().width(Length::Fill),
+                        snooze_buttons(BannerId::Offline),
                         text("Retry").size(13),
                     ]
+                    .spacing(8)
                     .align_y(Alignment::Center),
                 )
                 .on_press(Message::FetchRemoteVersions)
@@ -30,23 +189,27 @@ pub(super) fn contextual_banners<'a>(state: &'a MainState) -> Option<Element<'a,
                 .into(),
             );
         }
-        NetworkStatus::Stale => {
+        NetworkStatus::Stale if shown(BannerId::StaleVersions) => {
             let age_text = state
                 .available_versions
+                .remote()
                 .disk_cached_at
                 .map(|t| format!(" (cached {})", format_relative_time(t)))
                 .unwrap_or_default();
             banners.push(
                 button(
                     row![
+                        banner_icon(Status::Warning),
                         text(format!(
                             "Using cached data{} \u{2014} could not refresh from network",
                             age_text
                         ))
                         .size(13),
                         Space::new().width(Length::Fill),
+                        snooze_buttons(BannerId::StaleVersions),
                         text("Retry").size(13),
                     ]
+                    .spacing(8)
                     .align_y(Alignment::Center),
                 )
                 .on_press(Message::FetchRemoteVersions)
@@ -59,15 +222,51 @@ pub(super) fn contextual_banners<'a>(state: &'a MainState) -> Option<Element<'a,
         _ => {}
     }
 
-    if state.available_versions.schedule_error.is_some() && schedule.is_none() {
+    if state.available_versions.schedule_is_stale() && shown(BannerId::StaleSchedule) {
+        let age_text = state
+            .available_versions
+            .schedule_fetched_at
+            .map(|t| format!(" (fetched {})", format_relative_time(t)))
+            .unwrap_or_default();
+        banners.push(
+            button(
+                row![
+                    banner_icon(Status::Warning),
+                    text(format!(
+                        "Using the cached release schedule{} \u{2014} could not refresh it",
+                        age_text
+                    ))
+                    .size(13),
+                    Space::new().width(Length::Fill),
+                    snooze_buttons(BannerId::StaleSchedule),
+                    text("Retry").size(13),
+                ]
+                .spacing(8)
+                .align_y(Alignment::Center),
+            )
+            .on_press(Message::FetchReleaseSchedule)
+            .style(styles::banner_button_warning)
+            .padding([12, 16])
+            .width(Length::Fill)
+            .into(),
+        );
+    }
+
+    if state.available_versions.schedule_error.is_some()
+        && schedule.is_none()
+        && shown(BannerId::ScheduleUnavailable)
+    {
         banners.push(
             button(
                 row![
+                    banner_icon(Status::Warning),
                     text("Release schedule unavailable \u{2014} EOL detection may be inaccurate")
                         .size(13),
                     Space::new().width(Length::Fill),
+                    snooze_buttons(BannerId::ScheduleUnavailable),
                     text("Retry").size(13),
                 ]
+                .spacing(8)
                 .align_y(Alignment::Center),
             )
             .on_press(Message::FetchReleaseSchedule)
@@ -78,25 +277,15 @@ pub(super) fn contextual_banners<'a>(state: &'a MainState) -> Option<Element<'a,
         );
     }
 
-    let latest_by_major = &state.available_versions.latest_by_major;
+    let update_count = env.majors_with_updates(&state.available_versions.remote().latest_by_major);
 
-    let update_count = env
-        .version_groups
-        .iter()
-        .filter(|group| {
-            let installed_latest = group.versions.iter().map(|v| &v.version).max();
-            latest_by_major
-                .get(&group.major)
-                .is_some_and(|latest| installed_latest.is_some_and(|installed| latest > installed))
-        })
-        .count();
-
-    if update_count > 0 {
+    if update_count > 0 && shown(BannerId::MajorUpdates) {
         let has_active_ops = !state.operation_queue.active_installs.is_empty()
             || !state.operation_queue.pending.is_empty();
 
         let btn = button(
             row![
+                banner_icon(Status::Neutral),
                 text(format!(
                     "{} major {} with updates available",
                     update_count,
@@ -108,6 +297,7 @@ pub(super) fn contextual_banners<'a>(state: &'a MainState) -> Option<Element<'a,
                 ))
                 .size(13),
                 Space::new().width(Length::Fill),
+                snooze_buttons(BannerId::MajorUpdates),
                 text(if has_active_ops {
                     "Updating..."
                 } else {
@@ -115,13 +305,14 @@ pub(super) fn contextual_banners<'a>(state: &'a MainState) -> Option<Element<'a,
                 })
                 .size(13),
             ]
+            .spacing(8)
             .align_y(Alignment::Center),
         )
         .style(styles::banner_button_info)
         .padding([12, 16])
         .width(Length::Fill);
 
-        let btn = if has_active_ops {
+        let btn = if has_active_ops || read_only {
             btn
         } else {
             btn.on_press(Message::RequestBulkUpdateMajors)
@@ -130,6 +321,26 @@ pub(super) fn contextual_banners<'a>(state: &'a MainState) -> Option<Element<'a,
         banners.push(btn.into());
     }
 
+    let warnings = schedule
+        .map(|s| {
+            eol::visible_warnings(
+                s,
+                env.version_groups.iter().map(|g| g.major),
+                settings,
+                today,
+            )
+        })
+        .unwrap_or_default();
+    let majors_in = |phase: SupportPhase| -> Vec<u32> {
+        warnings
+            .iter()
+            .filter(|w| w.phase == phase)
+            .map(|w| w.major)
+            .collect()
+    };
+
+    // Counts every end-of-life version, as Clean Up offers them all, but
+    // only shows while some major's warning is not snoozed or dismissed.
     let eol_count = schedule
         .map(|s| {
             env.version_groups
@@ -140,10 +351,11 @@ pub(super) fn contextual_banners<'a>(state: &'a MainState) -> Option<Element<'a,
         })
         .unwrap_or(0);
 
-    if eol_count > 0 {
+    if !majors_in(SupportPhase::EndOfLife).is_empty() && eol_count > 0 {
         banners.push(
             button(
                 row![
+                    banner_icon(Status::Warning),
                     text(format!(
                         "{} end-of-life {} installed",
                         eol_count,
@@ -155,11 +367,38 @@ pub(super) fn contextual_banners<'a>(state: &'a MainState) -> Option<Element<'a,
                     ))
                     .size(13),
                     Space::new().width(Length::Fill),
+                    acknowledge_buttons(SupportPhase::EndOfLife),
                     text("Clean Up").size(13),
                 ]
+                .spacing(8)
+                .align_y(Alignment::Center),
+            )
+            .on_press_maybe((!read_only).then_some(Message::RequestBulkUninstallEOL))
+            .style(styles::banner_button_warning)
+            .padding([12, 16])
+            .width(Length::Fill)
+            .into(),
+        );
+    }
+
+    let maintenance_majors = majors_in(SupportPhase::Maintenance);
+    if !maintenance_majors.is_empty() {
+        let list = maintenance_majors
+            .iter()
+            .map(|major| format!("Node {}", major))
+            .collect::<Vec<_>>()
+            .join(", ");
+        banners.push(
+            button(
+                row![
+                    banner_icon(Status::Warning),
+                    text(format!("{} only receive critical fixes now", list)).size(13),
+                    Space::new().width(Length::Fill),
+                    acknowledge_buttons(SupportPhase::Maintenance),
+                ]
+                .spacing(8)
                 .align_y(Alignment::Center),
             )
-            .on_press(Message::RequestBulkUninstallEOL)
             .style(styles::banner_button_warning)
             .padding([12, 16])
             .width(Length::Fill)
@@ -174,6 +413,160 @@ pub(super) fn contextual_banners<'a>(state: &'a MainState) -> Option<Element<'a,
     }
 }
 
+/// Snooze and dismiss actions for a banner, which the header lists while
+/// it is hidden.
+fn snooze_buttons<'a>(id: BannerId) -> Element<'a, Message> {
+    row(BannerSnooze::ALL.map(|snooze| {
+        button(text(snooze.label()).size(12))
+            .on_press(Message::SuppressBanner(id, snooze))
+            .style(styles::ghost_button)
+            .padding([2, 6])
+            .into()
+    }))
+    .spacing(4)
+    .into()
+}
+
+/// Snooze and dismiss actions for the majors listed in a support banner.
+fn acknowledge_buttons<'a>(phase: SupportPhase) -> Element<'a, Message> {
+    row![
+        button(text("Snooze").size(12))
+            .on_press(Message::SnoozeSupportWarnings(phase))
+            .style(styles::ghost_button)
+            .padding([2, 6]),
+        button(text("Dismiss").size(12))
+            .on_press(Message::DismissSupportWarnings(phase))
+            .style(styles::ghost_button)
+            .padding([2, 6]),
+    ]
+    .spacing(4)
+    .into()
+}
+
+fn safe_mode_banner(safe_mode: &SafeMode) -> Element<'_, Message> {
+    let restart = button(text("Restart Normally").size(12))
+        .on_press(Message::RestartApp)
+        .style(styles::ghost_button)
+        .padding([2, 6]);
+
+    let (status, summary, action): (_, String, Element<Message>) = if !safe_mode.corrupt.is_empty()
+    {
+        (
+            Status::Warning,
+            "Safe mode \u{2014} saved data could not be read, so Versi started with default settings and no cache".to_string(),
+            button(text("Back Up and Reset").size(12))
+                .on_press(Message::ResetCorruptFiles)
+                .style(styles::ghost_button)
+                .padding([2, 6])
+                .into(),
+        )
+    } else if !safe_mode.backups.is_empty() {
+        (
+            Status::Neutral,
+            "Safe mode \u{2014} the unreadable files were backed up and settings are saved again"
+                .to_string(),
+            restart.into(),
+        )
+    } else {
+        (
+            Status::Neutral,
+            format!(
+                "Safe mode ({}) \u{2014} using default settings without the cache, changes are not saved",
+                safe_mode.requested.unwrap_or(SAFE_MODE_FLAG)
+            ),
+            restart.into(),
+        )
+    };
+
+    let mut content = column![
+        row![
+            banner_icon(status),
+            text(summary).size(13),
+            Space::new().width(Length::Fill),
+            action,
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+    ]
+    .spacing(4);
+    for file in &safe_mode.corrupt {
+        content = content.push(
+            text(format!(
+                "{} ({}): {}",
+                file.name,
+                file.path.display(),
+                file.error.lines().next().unwrap_or_default()
+            ))
+            .size(12),
+        );
+    }
+    for backup in &safe_mode.backups {
+        content = content.push(text(format!("Backed up to {}", backup.display())).size(12));
+    }
+
+    button(content)
+        .style(if status == Status::Warning {
+            styles::banner_button_warning
+        } else {
+            styles::banner_button_info
+        })
+        .padding([12, 16])
+        .width(Length::Fill)
+        .into()
+}
+
+fn slow_operation_banner<'a>(slow: &SlowOperation) -> Element<'a, Message> {
+    let usual = slow
+        .typical
+        .map(|typical| format!(", usually {}", metrics::format_duration(typical)))
+        .unwrap_or_default();
+    button(
+        column![
+            row![
+                banner_icon(Status::Warning),
+                text(format!(
+                    "{} of {} in {} took {}{}",
+                    slow.kind.label(),
+                    slow.subject,
+                    slow.env.display_name(),
+                    metrics::format_duration(slow.duration),
+                    usual
+                ))
+                .size(13),
+                Space::new().width(Length::Fill),
+                button(text("Dismiss").size(12))
+                    .on_press(Message::DismissSlowOperation)
+                    .style(styles::ghost_button)
+                    .padding([2, 6]),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center),
+            text(slow.hint()).size(12),
+        ]
+        .spacing(4),
+    )
+    .style(styles::banner_button_warning)
+    .padding([12, 16])
+    .width(Length::Fill)
+    .into()
+}
+
+/// Banner icons follow the theme palette like the banner text does.
+fn banner_icon<'a>(status: Status) -> Element<'a, Message> {
+    status
+        .icon(14.0)
+        .style(move |theme: &iced::Theme, _status| {
+            let palette = theme.palette();
+            svg::Style {
+                color: Some(match status {
+                    Status::Warning => palette.warning,
+                    _ => palette.primary,
+                }),
+            }
+        })
+        .into()
+}
+
 fn format_relative_time(timestamp: DateTime<Utc>) -> String {
     let delta = Utc::now().signed_duration_since(timestamp);
     let minutes = delta.num_minutes();
@@ -0,0 +1,112 @@
+use iced::widget::{Space, button, column, container, row, scrollable, text};
+use iced::{Alignment, Element, Length};
+
+use crate::message::Message;
+use crate::state::{EnvironmentState, MainState};
+use crate::theme::styles;
+use crate::widgets::version_list::filter_version;
+
+/// Installed versions matching the search in every environment, grouped by
+/// environment.
+pub(super) fn view(state: &MainState, read_only: bool) -> Element<'_, Message> {
+    let query = state.search_query.trim();
+    let cards: Vec<Element<Message>> = state
+        .environments
+        .iter()
+        .enumerate()
+        .map(|(idx, env)| environment_card(idx, env, query, read_only))
+        .collect();
+
+    scrollable(
+        column(cards)
+            .spacing(12)
+            .padding(iced::Padding::new(0.0).right(32.0)),
+    )
+    .height(Length::Fill)
+    .into()
+}
+
+fn environment_card<'a>(
+    idx: usize,
+    env: &'a EnvironmentState,
+    query: &str,
+    read_only: bool,
+) -> Element<'a, Message> {
+    let muted = iced::Color::from_rgb8(142, 142, 147);
+    let matches: Vec<_> = env
+        .installed_versions
+        .iter()
+        .filter(|v| filter_version(v, query))
+        .collect();
+
+    let status = if !env.available {
+        env.error
+            .as_ref()
+            .map(|error| error.summary().to_string())
+            .unwrap_or_else(|| "Unavailable".to_string())
+    } else if env.loading && env.installed_versions.is_empty() {
+        "Loading versions...".to_string()
+    } else if let Some(error) = env
+        .error
+        .as_ref()
+        .filter(|_| env.installed_versions.is_empty())
+    {
+        error.summary().to_string()
+    } else if matches.is_empty() {
+        format!("No installed versions match '{}'", query)
+    } else if matches.len() == 1 {
+        "1 match".to_string()
+    } else {
+        format!("{} matches", matches.len())
+    };
+
+    let mut card = column![
+        row![
+            text(&env.name).size(14),
+            Space::new().width(Length::Fill),
+            text(status).size(12).color(muted),
+        ]
+        .align_y(Alignment::Center),
+    ]
+    .spacing(4);
+
+    for installed in matches {
+        let version = installed.version.to_string();
+        let is_default = env.default_version.as_ref() == Some(&installed.version);
+        let mut line = row![text(format!("Node {}", version)).size(13)]
+            .spacing(8)
+            .align_y(Alignment::Center);
+        if is_default {
+            line = line.push(
+                container(text("default").size(10))
+                    .style(styles::badge_default)
+                    .padding([2, 6]),
+            );
+        }
+        line = line.push(Space::new().width(Length::Fill)).push(
+            button(text("Show").size(12))
+                .on_press(Message::ShowInEnvironment {
+                    env_idx: idx,
+                    version: version.clone(),
+                })
+                .style(styles::ghost_button)
+                .padding([2, 6]),
+        );
+        line = line.push(
+            button(text("Uninstall").size(12))
+                .on_press_maybe((!read_only).then(|| Message::UninstallFromEnvironment {
+                    env_idx: idx,
+                    version,
+                }))
+                .style(styles::ghost_button)
+                .padding([2, 6]),
+        );
+        card = card.push(line);
+    }
+
+    container(card)
+        .style(styles::card_container)
+        .padding(12)
+        .width(Length::Fill)
+        .into()
+}
@@ -1,13 +1,22 @@
-use iced::widget::{Space, button, container, row, text, tooltip};
+use iced::widget::{Space, button, column, container, row, text, tooltip};
 use iced::{Alignment, Element, Length};
+use versi_backend::NodeVersion;
 
 use crate::icon;
 use crate::message::Message;
-use crate::state::{AppUpdateState, MainState};
+use crate::settings::{VersionGrouping, VersionsLayout};
+use crate::state::{AppUpdateState, MainState, NetworkStatus};
 use crate::theme::styles;
-use crate::widgets::helpers::nav_icons;
+use crate::widgets::helpers::{format_bytes, nav_icons, styled_tooltip};
+use crate::widgets::status::{self, Status};
 
-pub(super) fn header_view<'a>(state: &'a MainState) -> Element<'a, Message> {
+pub(super) fn header_view<'a>(
+    state: &'a MainState,
+    layout: VersionsLayout,
+    update_hint: Option<&'static str>,
+    hidden_notifications: usize,
+    read_only: bool,
+) -> Element<'a, Message> {
     let env = state.active_environment();
 
     let subtitle = match &env.backend_version {
@@ -19,38 +28,243 @@ pub(super) fn header_view<'a>(state: &'a MainState) -> Element<'a, Message> {
         .spacing(8)
         .align_y(Alignment::Center);
 
+    if let Some(default) = &env.default_version {
+        left = left.push(versions_label(default, env.active_version.as_ref()));
+    }
+
+    if let Some(dir) = state.backend.system_wide_dir() {
+        left = left.push(
+            tooltip(
+                status::label(Status::Neutral, "System-wide", 11.0),
+                container(
+                    text(format!(
+                        "Versions are installed in {} for every user",
+                        dir.display()
+                    ))
+                    .size(12),
+                )
+                .padding([4, 8])
+                .style(styles::tooltip_container),
+                tooltip::Position::Bottom,
+            )
+            .gap(4.0),
+        );
+    }
+
     if let Some(update) = &state.app_update {
         left = left.push(app_update_badge(update, &state.app_update_state));
     }
 
     if let Some(update) = &state.backend_update {
+        let badge = button(
+            container(
+                row![
+                    text(format!(
+                        "{} {} available",
+                        state.backend_name, update.latest_version
+                    ))
+                    .size(11),
+                    icon::arrow_up_right(11.0),
+                ]
+                .spacing(2)
+                .align_y(Alignment::Center),
+            )
+            .padding([2, 8]),
+        )
+        .on_press(Message::OpenBackendUpdate)
+        .style(styles::app_update_button)
+        .padding(0);
+        left = left.push(match update_hint {
+            Some(hint) => styled_tooltip(badge, hint, tooltip::Position::Bottom),
+            None => badge.into(),
+        });
+        let environments = state
+            .environments
+            .iter()
+            .filter(|e| e.available && e.backend_name == state.backend_name)
+            .count();
         left = left.push(
             button(
-                container(
-                    row![
-                        text(format!(
-                            "{} {} available",
-                            state.backend_name, update.latest_version
-                        ))
-                        .size(11),
-                        icon::arrow_up_right(11.0),
-                    ]
-                    .spacing(2)
-                    .align_y(Alignment::Center),
-                )
-                .padding([2, 8]),
+                text(if environments > 1 {
+                    "Upgrade All"
+                } else {
+                    "Upgrade"
+                })
+                .size(11),
             )
-            .on_press(Message::OpenBackendUpdate)
-            .style(styles::app_update_button)
-            .padding(0),
+            .on_press_maybe((!read_only).then_some(Message::UpgradeAllBackends))
+            .style(styles::ghost_button)
+            .padding([2, 6]),
         );
     }
 
+    let mut right = row![].spacing(8).align_y(Alignment::Center);
+    if hidden_notifications > 0 || state.notifications_open {
+        right = right.push(styled_tooltip(
+            button(text(format!("{} hidden", hidden_notifications)).size(11))
+                .on_press(Message::NotificationsToggled)
+                .style(if state.notifications_open {
+                    styles::ghost_button_active
+                } else {
+                    styles::ghost_button
+                })
+                .padding([2, 6]),
+            "Snoozed and dismissed notifications",
+            tooltip::Position::Bottom,
+        ));
+    }
+
+    column![
+        row![
+            left,
+            Space::new().width(Length::Fill),
+            right,
+            layout_toggle(layout),
+            nav_icons(&state.view, state.refresh_rotation),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+        quick_stats(state, read_only),
+    ]
+    .spacing(4)
+    .into()
+}
+
+/// Counts for the active environment, each a shortcut to where it can be
+/// acted on.
+fn quick_stats<'a>(state: &'a MainState, read_only: bool) -> Element<'a, Message> {
+    let env = state.active_environment();
+    let plural = |count: usize, one: &str, many: &str| {
+        format!("{} {}", count, if count == 1 { one } else { many })
+    };
+    let stat = |label: String, hint: &'a str, on_press: Option<Message>| {
+        styled_tooltip(
+            button(
+                text(label)
+                    .size(11)
+                    .color(iced::Color::from_rgb8(142, 142, 147)),
+            )
+            .on_press_maybe(on_press)
+            .style(styles::ghost_button)
+            .padding([2, 6]),
+            hint,
+            tooltip::Position::Bottom,
+        )
+    };
+
+    let updates = env.majors_with_updates(&state.available_versions.remote().latest_by_major);
+    let mut stats = row![
+        stat(
+            plural(env.installed_versions.len(), "version", "versions"),
+            "Show every installed version",
+            Some(Message::SearchChanged(String::new())),
+        ),
+        stat(
+            plural(env.version_groups.len(), "major", "majors"),
+            "Group versions by major",
+            Some(Message::VersionGroupingChanged(VersionGrouping::Major)),
+        ),
+        stat(
+            plural(updates, "update", "updates"),
+            "Update every major with a newer release",
+            (updates > 0 && !read_only).then_some(Message::RequestBulkUpdateMajors),
+        ),
+    ]
+    .spacing(4)
+    .align_y(Alignment::Center);
+
+    if let Some(used) = env.disk_used() {
+        stats = stats.push(stat(
+            format!("{} on disk", format_bytes(used)),
+            "Storage settings",
+            Some(Message::NavigateToSettings),
+        ));
+    }
+    stats = stats.push(stat(
+        "Copy report".to_string(),
+        "Copy installed versions and updates as text for chat",
+        Some(Message::CopyEnvironmentReport(state.active_environment_idx)),
+    ));
+
+    let (network, label, hint) = match state.available_versions.network_status() {
+        NetworkStatus::Online => (
+            Status::Success,
+            "Online",
+            "Available versions are up to date",
+        ),
+        NetworkStatus::Fetching => (Status::Neutral, "Refreshing", "Loading available versions"),
+        NetworkStatus::Offline => (Status::Error, "Offline", "Retry loading available versions"),
+        NetworkStatus::Stale => (
+            Status::Warning,
+            "Cached",
+            "Retry loading available versions",
+        ),
+    };
+    let refresh = !matches!(
+        state.available_versions.network_status(),
+        NetworkStatus::Fetching
+    );
+    stats
+        .push(styled_tooltip(
+            button(status::label(network, label, 11.0))
+                .on_press_maybe(refresh.then_some(Message::FetchRemoteVersions))
+                .style(styles::ghost_button)
+                .padding([2, 6]),
+            hint,
+            tooltip::Position::Bottom,
+        ))
+        .into()
+}
+
+/// The default version, and the one the last shell runs when that differs,
+/// which is what `node --version` prints after `use` or use-on-cd.
+fn versions_label<'a>(default: &NodeVersion, active: Option<&NodeVersion>) -> Element<'a, Message> {
+    let muted = iced::Color::from_rgb8(142, 142, 147);
+    let mut label = row![text(format!("Default {}", default)).size(11).color(muted)]
+        .spacing(8)
+        .align_y(Alignment::Center);
+    let explanation = match active {
+        Some(active) if active != default => {
+            label = label.push(status::label(
+                Status::Neutral,
+                format!("Active {}", active),
+                11.0,
+            ));
+            format!(
+                "New shells start with {}. The last shell you used switched to {} with `use` or a version file; it changes back in a new shell.",
+                default, active
+            )
+        }
+        Some(_) => format!(
+            "New shells start with {}, and the last shell you used is running it too.",
+            default
+        ),
+        None => format!(
+            "New shells start with {}. Shells can switch with `use` or a version file, which this backend does not report.",
+            default
+        ),
+    };
+
+    styled_tooltip(label, explanation, tooltip::Position::Bottom)
+}
+
+fn layout_toggle<'a>(layout: VersionsLayout) -> Element<'a, Message> {
+    let layout_button = |label: &'static str, value: VersionsLayout| {
+        button(text(label).size(11))
+            .on_press(Message::VersionsLayoutChanged(value))
+            .style(if layout == value {
+                styles::ghost_button_active
+            } else {
+                styles::ghost_button
+            })
+            .padding([4, 8])
+    };
+
     row![
-        left,
-        Space::new().width(Length::Fill),
-        nav_icons(&state.view, state.refresh_rotation),
+        layout_button("List", VersionsLayout::List),
+        layout_button("Overview", VersionsLayout::Overview),
     ]
+    .spacing(2)
     .align_y(Alignment::Center)
     .into()
 }
@@ -80,7 +294,35 @@ fn app_update_badge<'a>(
                 main_btn.on_press(Message::OpenAppUpdate)
             };
 
-            badge_row = badge_row.push(main_btn);
+            let channel = update.channel;
+            match (
+                channel.and_then(versi_core::InstallChannel::package_manager),
+                channel.and_then(versi_core::InstallChannel::update_command),
+            ) {
+                (Some(manager), Some(command)) => {
+                    badge_row = badge_row.push(styled_tooltip(
+                        main_btn,
+                        format!("Installed with {}. Update with: {}", manager, command),
+                        tooltip::Position::Bottom,
+                    ));
+                    badge_row = badge_row.push(styled_tooltip(
+                        button(container(text("Copy Command").size(11)).padding([2, 8]))
+                            .on_press(Message::CopyToClipboard(command.to_string()))
+                            .style(styles::app_update_button)
+                            .padding(0),
+                        command,
+                        tooltip::Position::Bottom,
+                    ));
+                }
+                (Some(manager), None) => {
+                    badge_row = badge_row.push(styled_tooltip(
+                        main_btn,
+                        format!("Updates are installed by {}", manager),
+                        tooltip::Position::Bottom,
+                    ));
+                }
+                _ => badge_row = badge_row.push(main_btn),
+            }
 
             if update.download_url.is_some() {
                 badge_row = badge_row.push(
@@ -90,6 +332,23 @@ fn app_update_badge<'a>(
                         .padding(0),
                 );
             }
+
+            badge_row = badge_row.push(styled_tooltip(
+                button(container(text("Later").size(11)).padding([2, 8]))
+                    .on_press(Message::RemindAppUpdateLater)
+                    .style(styles::ghost_button)
+                    .padding(0),
+                "Remind me tomorrow",
+                tooltip::Position::Bottom,
+            ));
+            badge_row = badge_row.push(styled_tooltip(
+                button(container(text("Skip").size(11)).padding([2, 8]))
+                    .on_press(Message::SkipAppUpdate)
+                    .style(styles::ghost_button)
+                    .padding(0),
+                format!("Don't offer v{} again", update.latest_version),
+                tooltip::Position::Bottom,
+            ));
         }
         AppUpdateState::Downloading { downloaded, total } => {
             let label = if *total > 0 {
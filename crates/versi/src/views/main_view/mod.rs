@@ -1,7 +1,10 @@
 mod banners;
+mod global_search;
 mod header;
 mod modals;
+mod notifications;
 pub mod search;
+mod selection;
 pub mod tabs;
 
 use iced::Element;
@@ -17,23 +20,58 @@ pub fn view<'a>(
     settings: &'a AppSettings,
     has_tabs: bool,
 ) -> Element<'a, Message> {
-    let header = header::header_view(state);
-    let search_bar = search::search_bar_view(state);
+    let update_hint = settings
+        .fnm_install_method
+        .filter(|_| state.backend_name == "fnm")
+        .map(|method| method.update_hint());
+    let today = chrono::Local::now().date_naive();
+    let header = header::header_view(
+        state,
+        settings.versions_layout,
+        update_hint,
+        notifications::hidden_count(settings, today),
+        settings.is_read_only(),
+    );
+    let search_bar = search::search_bar_view(state, settings);
     let hovered = if state.modal.is_some() {
         &None
     } else {
         &state.hovered_version
     };
-    let version_list = version_list::view(
-        state.active_environment(),
-        &state.search_query,
-        &state.available_versions.versions,
-        &state.available_versions.latest_by_major,
-        state.available_versions.schedule.as_ref(),
-        &state.operation_queue,
-        hovered,
-        settings.search_results_limit,
-    );
+    let env = state.active_environment();
+    let project = state.project_pin.as_ref().and_then(|pin| {
+        let version = pin.installed_match(
+            &env.installed_versions,
+            &state.available_versions.remote().versions,
+            state.available_versions.schedule.as_ref(),
+            chrono::Local::now().date_naive(),
+        )?;
+        Some((version, pin))
+    });
+    let version_list = if state.search_all_environments && !state.search_query.is_empty() {
+        global_search::view(state, settings.is_read_only())
+    } else {
+        version_list::view(
+            state.active_environment(),
+            &state.search_query,
+            &state.available_versions.remote().versions,
+            &state.available_versions.release_dates,
+            &state.available_versions.remote().latest_by_major,
+            state.available_versions.schedule.as_ref(),
+            &state.operation_queue,
+            hovered,
+            &state.selected_versions,
+            settings.protected_versions_for(&state.active_environment().id),
+            project,
+            &settings.major_notes,
+            settings.search_results_limit,
+            settings.versions_layout,
+            settings.version_grouping,
+            settings.list_density,
+            settings.eol_countdown_days,
+            settings.is_read_only(),
+        )
+    };
 
     let right_inset = iced::Padding::new(0.0).right(24.0);
     let mut content_column = column![
@@ -43,11 +81,15 @@ pub fn view<'a>(
     .spacing(12);
 
     if state.search_query.is_empty()
-        && let Some(banner_content) = banners::contextual_banners(state)
+        && let Some(banner_content) = banners::contextual_banners(state, settings)
     {
         content_column = content_column.push(container(banner_content).padding(right_inset));
     }
 
+    if let Some(selection_bar) = selection::selection_bar_view(state, settings.is_read_only()) {
+        content_column = content_column.push(container(selection_bar).padding(right_inset));
+    }
+
     content_column = content_column.push(version_list);
 
     let content_padding = if has_tabs {
@@ -58,11 +100,16 @@ pub fn view<'a>(
     let main_content = content_column.padding(content_padding);
 
     let main_column = column![main_content].spacing(0);
+    let main_column: Element<Message> = if state.notifications_open {
+        notifications::overlay(main_column.into(), settings, today)
+    } else {
+        main_column.into()
+    };
 
     let with_modal: Element<Message> = if let Some(modal) = &state.modal {
-        modals::modal_overlay(main_column.into(), modal, state, settings)
+        modals::modal_overlay(main_column, modal, state, settings)
     } else {
-        main_column.into()
+        main_column
     };
 
     toast_container::view(with_modal, &state.toasts, settings.max_visible_toasts)
@@ -1,27 +1,46 @@
-use iced::widget::{Space, button, column, container, mouse_area, row, text};
-use iced::{Element, Length};
+use std::collections::{BTreeMap, BTreeSet};
 
+use iced::widget::{
+    Space, button, checkbox, column, container, mouse_area, pick_list, row, scrollable, text,
+    text_input, toggler,
+};
+use iced::{Alignment, Element, Length};
+
+use versi_backend::NodeVersion;
+use versi_core::format_release_age;
+use versi_platform::{BinaryArch, EnvironmentId};
+
+use crate::integrations::jetbrains::InterpreterChange;
 use crate::message::Message;
-use crate::settings::AppSettings;
-use crate::state::{MainState, Modal};
+use crate::settings::{AppSettings, CloseBehavior};
+use crate::snapshots::{self, EnvironmentSnapshot};
+use crate::snippets::{self, SnippetTarget};
+use crate::state::{
+    BackendUpgrade, BackendUpgradeOutcome, BatchSummary, MainState, Modal, OperationFailure,
+    OperationRequest,
+};
+use crate::test_matrix::{MatrixOutcome, MatrixRun};
 use crate::theme::styles;
+use crate::toolchain::ProjectToolchain;
+use crate::widgets::status::{self, Status};
 
 pub(super) fn modal_overlay<'a>(
     content: Element<'a, Message>,
     modal: &'a Modal,
-    _state: &'a MainState,
+    state: &'a MainState,
     settings: &'a AppSettings,
 ) -> Element<'a, Message> {
     let preview_limit = settings.modal_preview_limit;
+    let notes = &settings.major_notes;
     let modal_content: Element<Message> = match modal {
         Modal::ConfirmBulkUpdateMajors { versions } => {
-            confirm_bulk_update_view(versions, preview_limit)
+            confirm_bulk_update_view(versions, state, preview_limit)
         }
         Modal::ConfirmBulkUninstallEOL { versions } => {
-            confirm_bulk_uninstall_eol_view(versions, preview_limit)
+            confirm_bulk_uninstall_eol_view(versions, notes, preview_limit)
         }
         Modal::ConfirmBulkUninstallMajor { major, versions } => {
-            confirm_bulk_uninstall_major_view(*major, versions, preview_limit)
+            confirm_bulk_uninstall_major_view(*major, versions, notes, preview_limit)
         }
         Modal::ConfirmBulkUninstallMajorExceptLatest {
             major,
@@ -31,10 +50,127 @@ pub(super) fn modal_overlay<'a>(
             *major,
             versions,
             keeping,
+            notes,
+            preview_limit,
+        ),
+        Modal::ConfirmBulkUninstallSelected {
+            versions,
+            kept_default,
+        } => confirm_bulk_uninstall_selected_view(
+            versions,
+            kept_default.as_deref(),
+            notes,
+            preview_limit,
+        ),
+        Modal::ConfirmUninstallDefault { version } => {
+            confirm_uninstall_default_view(version, notes)
+        }
+        Modal::ConfirmReinstall {
+            version,
+            is_default,
+            keep_globals,
+        } => confirm_reinstall_view(version, *is_default, *keep_globals),
+        Modal::ConfirmUninstallProtected {
+            version,
+            is_default,
+        } => confirm_uninstall_protected_view(version, *is_default, notes),
+        Modal::ConfirmInstallWithoutBinary {
+            version,
+            platform,
+            unofficial_builds,
+        } => confirm_install_without_binary_view(version, platform, *unofficial_builds),
+        Modal::ConfirmResolvedInstall {
+            spec,
+            candidates,
+            selected,
+        } => confirm_resolved_install_view(
+            spec,
+            candidates,
+            selected,
+            state.is_version_installed(selected),
+            settings.is_read_only(),
+        ),
+        Modal::ConfirmSetDefaultForeignArch { version, arch } => {
+            confirm_set_default_foreign_arch_view(version, *arch)
+        }
+        Modal::ConfirmQuit { active } => confirm_quit_view(*active),
+        Modal::ConfirmClose {
+            can_minimize,
+            remember,
+        } => confirm_close_view(*can_minimize, *remember),
+        Modal::ResumeOperations {
+            environment,
+            operations,
+        } => resume_operations_view(
+            environment,
+            operations,
             preview_limit,
+            settings.is_read_only(),
+        ),
+        Modal::SetupSnippet {
+            version,
+            target,
+            snippet,
+        } => setup_snippet_view(version, *target, snippet),
+        Modal::CiMatrix {
+            majors,
+            selected,
+            include_engines,
+        } => ci_matrix_view(majors, selected, *include_engines),
+        Modal::TestMatrix {
+            versions,
+            project,
+            command,
+            runs,
+            toolchain,
+            confirming_trust,
+        } => test_matrix_view(
+            versions,
+            project.as_deref(),
+            command,
+            runs,
+            toolchain_warning_view(toolchain, state, settings.is_read_only()),
+            trust_view(
+                project.as_deref(),
+                project
+                    .as_deref()
+                    .and_then(|project| settings.project_trust_for(project)),
+                *confirming_trust,
+            ),
         ),
-        Modal::ConfirmUninstallDefault { version } => confirm_uninstall_default_view(version),
+        Modal::BatchSummary { summary } => {
+            let env = state.active_environment();
+            let snapshot = state.snapshots.latest(&env.id).map(|snapshot| {
+                // Matches the plan `RollBackEnvironment` queues, which keeps
+                // protected versions.
+                let pending = snapshots::plan_rollback(snapshot, env)
+                    .iter()
+                    .any(|request| match request {
+                        OperationRequest::Uninstall { version } => {
+                            !settings.is_protected(&env.id, version)
+                        }
+                        _ => true,
+                    });
+                (snapshot, pending)
+            });
+            batch_summary_view(summary, &env.id, snapshot, settings.is_read_only())
+        }
+        Modal::OperationFailure { failure } => operation_failure_view(failure),
+        Modal::EditMajorNote { major, draft } => edit_major_note_view(*major, draft),
+        Modal::ConfirmJetBrainsUpdate { changes } => {
+            confirm_jetbrains_update_view(changes, preview_limit)
+        }
         Modal::KeyboardShortcuts => keyboard_shortcuts_view(),
+        Modal::BackendUpgrades {
+            backend,
+            version,
+            upgrades,
+        } => backend_upgrades_view(
+            backend,
+            version,
+            upgrades,
+            state.backend_update.is_some() && !settings.is_read_only(),
+        ),
     };
 
     let backdrop = mouse_area(
@@ -70,15 +206,21 @@ pub(super) fn modal_overlay<'a>(
     iced::widget::stack![content, backdrop, modal_layer].into()
 }
 
-fn confirm_bulk_update_view(
-    versions: &[(String, String)],
+fn confirm_bulk_update_view<'a>(
+    versions: &'a [(String, String)],
+    state: &'a MainState,
     preview_limit: usize,
-) -> Element<'_, Message> {
+) -> Element<'a, Message> {
     let mut version_list = column![].spacing(4);
+    let today = chrono::Utc::now().date_naive();
 
     for (from, to) in versions.iter().take(preview_limit) {
+        let label = match state.available_versions.release_dates.get(to) {
+            Some(date) => format!("{} → {} ({})", from, to, format_release_age(*date, today)),
+            None => format!("{} → {}", from, to),
+        };
         version_list = version_list.push(
-            text(format!("{} → {}", from, to))
+            text(label)
                 .size(12)
                 .color(iced::Color::from_rgb8(142, 142, 147)),
         );
@@ -121,10 +263,11 @@ fn confirm_bulk_update_view(
     .into()
 }
 
-fn confirm_bulk_uninstall_eol_view(
+fn confirm_bulk_uninstall_eol_view<'a>(
     versions: &[String],
+    notes: &'a BTreeMap<u32, String>,
     preview_limit: usize,
-) -> Element<'_, Message> {
+) -> Element<'a, Message> {
     let mut version_list = column![].spacing(4);
 
     for version in versions.iter().take(preview_limit) {
@@ -143,6 +286,10 @@ fn confirm_bulk_uninstall_eol_view(
         );
     }
 
+    if let Some(notes) = major_notes_view(versions, notes) {
+        version_list = version_list.push(Space::new().height(8)).push(notes);
+    }
+
     column![
         text("Remove All EOL Versions?").size(20),
         Space::new().height(12),
@@ -154,9 +301,11 @@ fn confirm_bulk_uninstall_eol_view(
         Space::new().height(8),
         version_list,
         Space::new().height(8),
-        text("These versions no longer receive security updates.")
-            .size(12)
-            .color(iced::Color::from_rgb8(255, 149, 0)),
+        status::label(
+            Status::Warning,
+            "These versions no longer receive security updates.",
+            12.0
+        ),
         Space::new().height(24),
         row![
             button(text("Cancel").size(13))
@@ -176,11 +325,79 @@ fn confirm_bulk_uninstall_eol_view(
     .into()
 }
 
-fn confirm_bulk_uninstall_major_view(
+fn confirm_bulk_uninstall_selected_view<'a>(
+    versions: &[String],
+    kept_default: Option<&str>,
+    notes: &'a BTreeMap<u32, String>,
+    preview_limit: usize,
+) -> Element<'a, Message> {
+    let mut version_list = column![].spacing(4);
+
+    for version in versions.iter().take(preview_limit) {
+        version_list = version_list.push(
+            text(format!("Node {}", version))
+                .size(12)
+                .color(iced::Color::from_rgb8(142, 142, 147)),
+        );
+    }
+
+    if versions.len() > preview_limit {
+        version_list = version_list.push(
+            text(format!("...and {} more", versions.len() - preview_limit))
+                .size(11)
+                .color(iced::Color::from_rgb8(142, 142, 147)),
+        );
+    }
+
+    if let Some(notes) = major_notes_view(versions, notes) {
+        version_list = version_list.push(Space::new().height(8)).push(notes);
+    }
+
+    if let Some(default) = kept_default {
+        version_list = version_list.push(Space::new().height(8)).push(
+            text(format!(
+                "Node {} is the default version and stays installed; uninstall it on its own to remove it",
+                default
+            ))
+            .size(12),
+        );
+    }
+
+    column![
+        text("Remove Selected Versions?").size(20),
+        Space::new().height(12),
+        text(format!(
+            "This will uninstall {} selected version(s):",
+            versions.len()
+        ))
+        .size(14),
+        Space::new().height(8),
+        version_list,
+        Space::new().height(24),
+        row![
+            button(text("Cancel").size(13))
+                .on_press(Message::CancelBulkOperation)
+                .style(styles::secondary_button)
+                .padding([10, 20]),
+            Space::new().width(Length::Fill),
+            button(text("Remove Selected").size(13))
+                .on_press(Message::ConfirmBulkUninstallSelected)
+                .style(styles::danger_button)
+                .padding([10, 20]),
+        ]
+        .spacing(16),
+    ]
+    .spacing(4)
+    .width(Length::Fill)
+    .into()
+}
+
+fn confirm_bulk_uninstall_major_view<'a>(
     major: u32,
     versions: &[String],
+    notes: &'a BTreeMap<u32, String>,
     preview_limit: usize,
-) -> Element<'_, Message> {
+) -> Element<'a, Message> {
     let mut version_list = column![].spacing(4);
 
     for version in versions.iter().take(preview_limit) {
@@ -199,6 +416,10 @@ fn confirm_bulk_uninstall_major_view(
         );
     }
 
+    if let Some(notes) = major_notes_view(versions, notes) {
+        version_list = version_list.push(Space::new().height(8)).push(notes);
+    }
+
     column![
         text(format!("Remove All Node {}.x Versions?", major)).size(20),
         Space::new().height(12),
@@ -232,6 +453,7 @@ fn confirm_bulk_uninstall_major_except_latest_view<'a>(
     major: u32,
     versions: &'a [String],
     keeping: &'a str,
+    notes: &'a BTreeMap<u32, String>,
     preview_limit: usize,
 ) -> Element<'a, Message> {
     let mut version_list = column![].spacing(4);
@@ -252,6 +474,10 @@ fn confirm_bulk_uninstall_major_except_latest_view<'a>(
         );
     }
 
+    if let Some(notes) = major_notes_view(versions, notes) {
+        version_list = version_list.push(Space::new().height(8)).push(notes);
+    }
+
     column![
         text(format!("Clean Up Node {}.x Versions?", major)).size(20),
         Space::new().height(12),
@@ -263,9 +489,11 @@ fn confirm_bulk_uninstall_major_except_latest_view<'a>(
         Space::new().height(8),
         version_list,
         Space::new().height(8),
-        text(format!("Node {} will be kept.", keeping))
-            .size(12)
-            .color(iced::Color::from_rgb8(52, 199, 89)),
+        status::label(
+            Status::Success,
+            format!("Node {} will be kept.", keeping),
+            12.0
+        ),
         Space::new().height(24),
         row![
             button(text("Cancel").size(13))
@@ -285,15 +513,26 @@ fn confirm_bulk_uninstall_major_except_latest_view<'a>(
     .into()
 }
 
-fn confirm_uninstall_default_view(version: &str) -> Element<'_, Message> {
-    column![
-        text("Uninstall Default Version?").size(20),
-        Space::new().height(12),
+fn confirm_uninstall_default_view<'a>(
+    version: &'a str,
+    notes: &'a BTreeMap<u32, String>,
+) -> Element<'a, Message> {
+    let mut message = column![
         text(format!(
             "Node {} is your current default version. Uninstalling it will leave no default set.",
             version
         ))
         .size(14),
+    ]
+    .spacing(8);
+    if let Some(notes) = major_notes_view(&[version.to_string()], notes) {
+        message = message.push(notes);
+    }
+
+    column![
+        text("Uninstall Default Version?").size(20),
+        Space::new().height(12),
+        message,
         Space::new().height(24),
         row![
             button(text("Cancel").size(13))
@@ -313,51 +552,1135 @@ fn confirm_uninstall_default_view(version: &str) -> Element<'_, Message> {
     .into()
 }
 
-fn keyboard_shortcuts_view() -> Element<'static, Message> {
-    #[cfg(target_os = "macos")]
-    let mod_key = "\u{2318}";
-    #[cfg(not(target_os = "macos"))]
-    let mod_key = "Ctrl+";
-
-    let shortcuts = [
-        (format!("{}K", mod_key), "Search versions"),
-        (format!("{}R", mod_key), "Refresh"),
-        (format!("{},", mod_key), "Settings"),
-        (format!("{}W", mod_key), "Close window"),
-        (format!("{}Tab", mod_key), "Next environment"),
-        (format!("{}Shift+Tab", mod_key), "Previous environment"),
-        ("\u{2191}/\u{2193}".to_string(), "Navigate versions"),
-        ("Enter".to_string(), "Install / set default"),
-        ("Esc".to_string(), "Close modal"),
-        ("?".to_string(), "This help"),
-    ];
+fn confirm_reinstall_view<'a>(
+    version: &'a str,
+    is_default: bool,
+    keep_globals: bool,
+) -> Element<'a, Message> {
+    let mut message = column![
+        text(format!(
+            "Node {} will be removed and installed again from scratch.",
+            version
+        ))
+        .size(14),
+    ]
+    .spacing(8);
+    if is_default {
+        message = message.push(text("It will be set as the default again afterwards.").size(14));
+    }
+    message = message.push(
+        checkbox(keep_globals)
+            .label("Reinstall its global npm packages")
+            .on_toggle(Message::ReinstallGlobalsToggled)
+            .size(14)
+            .text_size(13),
+    );
 
-    let muted = iced::Color::from_rgb8(142, 142, 147);
+    column![
+        text("Reinstall Version?").size(20),
+        Space::new().height(12),
+        message,
+        Space::new().height(24),
+        row![
+            button(text("Cancel").size(13))
+                .on_press(Message::CloseModal)
+                .style(styles::secondary_button)
+                .padding([10, 20]),
+            Space::new().width(Length::Fill),
+            button(text("Reinstall").size(13))
+                .on_press(Message::ConfirmReinstall)
+                .style(styles::primary_button)
+                .padding([10, 20]),
+        ]
+        .spacing(16),
+    ]
+    .spacing(4)
+    .width(Length::Fill)
+    .into()
+}
 
-    let mut rows = column![].spacing(8);
-    for (key, desc) in shortcuts {
-        rows = rows.push(
-            row![
-                container(text(key).size(12))
-                    .style(styles::kbd_container)
-                    .padding([2, 8])
-                    .width(Length::Fixed(80.0)),
-                text(desc).size(13).color(muted),
-            ]
-            .spacing(12)
-            .align_y(iced::Alignment::Center),
+fn confirm_uninstall_protected_view<'a>(
+    version: &'a str,
+    is_default: bool,
+    notes: &'a BTreeMap<u32, String>,
+) -> Element<'a, Message> {
+    let mut message = column![
+        text(format!(
+            "Node {} is protected from removal. Uninstall it anyway?",
+            version
+        ))
+        .size(14),
+    ]
+    .spacing(8);
+    if is_default {
+        message = message.push(
+            text("It is also your default version, so no default will be left set.").size(14),
         );
     }
+    if let Some(notes) = major_notes_view(&[version.to_string()], notes) {
+        message = message.push(notes);
+    }
 
     column![
-        text("Keyboard Shortcuts").size(20),
-        Space::new().height(16),
-        rows,
+        text("Uninstall Protected Version?").size(20),
+        Space::new().height(12),
+        message,
         Space::new().height(24),
-        button(text("Close").size(13))
+        row![
+            button(text("Cancel").size(13))
+                .on_press(Message::CloseModal)
+                .style(styles::secondary_button)
+                .padding([10, 20]),
+            Space::new().width(Length::Fill),
+            button(text("Uninstall").size(13))
+                .on_press(Message::ConfirmUninstallProtected(version.to_string()))
+                .style(styles::danger_button)
+                .padding([10, 20]),
+        ]
+        .spacing(16),
+    ]
+    .spacing(4)
+    .width(Length::Fill)
+    .into()
+}
+
+fn confirm_install_without_binary_view<'a>(
+    version: &'a str,
+    platform: &str,
+    unofficial_builds: bool,
+) -> Element<'a, Message> {
+    let mut content = column![
+        text("No Prebuilt Binary").size(20),
+        Space::new().height(12),
+        text(format!(
+            "Node {} has no prebuilt binary for {}. The install will likely fail, or build from source if your version manager supports it.",
+            version, platform
+        ))
+        .size(14),
+    ]
+    .spacing(4)
+    .width(Length::Fill);
+    if unofficial_builds {
+        content = content.push(
+            text("The unofficial builds at unofficial-builds.nodejs.org may have one. They are community builds for platforms nodejs.org does not release binaries for.")
+                .size(12)
+                .color(iced::Color::from_rgb8(142, 142, 147)),
+        );
+    }
+
+    let mut actions = row![
+        button(text("Cancel").size(13))
             .on_press(Message::CloseModal)
             .style(styles::secondary_button)
             .padding([10, 20]),
+        Space::new().width(Length::Fill),
+    ]
+    .spacing(16);
+    if unofficial_builds {
+        actions = actions.push(
+            button(text("Install Anyway").size(13))
+                .on_press(Message::ConfirmInstallWithoutBinary(version.to_string()))
+                .style(styles::secondary_button)
+                .padding([10, 20]),
+        );
+        actions = actions.push(
+            button(text("Use Unofficial Builds").size(13))
+                .on_press(Message::InstallFromUnofficialBuilds(version.to_string()))
+                .style(styles::primary_button)
+                .padding([10, 20]),
+        );
+    } else {
+        actions = actions.push(
+            button(text("Install Anyway").size(13))
+                .on_press(Message::ConfirmInstallWithoutBinary(version.to_string()))
+                .style(styles::primary_button)
+                .padding([10, 20]),
+        );
+    }
+
+    content.push(Space::new().height(24)).push(actions).into()
+}
+
+fn confirm_resolved_install_view<'a>(
+    spec: &'a str,
+    candidates: &'a [String],
+    selected: &'a str,
+    installed: bool,
+    read_only: bool,
+) -> Element<'a, Message> {
+    let muted = iced::Color::from_rgb8(142, 142, 147);
+    let newest = candidates.first().map(String::as_str).unwrap_or_default();
+    let note = if installed {
+        format!("Node {} is already installed", selected)
+    } else if selected == newest {
+        format!("The newest of {} matching releases", candidates.len())
+    } else {
+        format!("Not the newest match, {} is", newest)
+    };
+
+    column![
+        text(format!("Install Node {}", spec)).size(20),
+        Space::new().height(12),
+        text(format!(
+            "'{}' installs Node {}. Versi passes the exact version to the backend, so the same release is installed every time.",
+            spec, newest
+        ))
+        .size(14),
+        Space::new().height(12),
+        row![
+            text("Version:").size(13),
+            pick_list(candidates, Some(selected.to_string()), Message::InstallCandidatePicked)
+                .text_size(13)
+                .padding([6, 10]),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+        text(note).size(12).color(muted),
+        Space::new().height(24),
+        row![
+            button(text("Cancel").size(13))
+                .on_press(Message::CloseModal)
+                .style(styles::secondary_button)
+                .padding([10, 20]),
+            Space::new().width(Length::Fill),
+            button(text(format!("Install {}", selected)).size(13))
+                .on_press_maybe(
+                    (!installed && !read_only).then(|| Message::StartInstall(selected.to_string())),
+                )
+                .style(styles::primary_button)
+                .padding([10, 20]),
+        ]
+        .spacing(16),
+    ]
+    .spacing(4)
+    .width(Length::Fill)
+    .into()
+}
+
+fn confirm_set_default_foreign_arch_view(version: &str, arch: BinaryArch) -> Element<'_, Message> {
+    column![
+        text("Different Architecture").size(20),
+        Space::new().height(12),
+        text(format!(
+            "Node {} is an {} build. On this Mac it runs under Rosetta 2, which is slower, and native modules built with it will not load in arm64 processes.",
+            version,
+            arch.label()
+        ))
+        .size(14),
+        Space::new().height(24),
+        row![
+            button(text("Cancel").size(13))
+                .on_press(Message::CloseModal)
+                .style(styles::secondary_button)
+                .padding([10, 20]),
+            Space::new().width(Length::Fill),
+            button(text("Set Default Anyway").size(13))
+                .on_press(Message::ConfirmSetDefaultForeignArch(version.to_string()))
+                .style(styles::primary_button)
+                .padding([10, 20]),
+        ]
+        .spacing(16),
+    ]
+    .spacing(4)
+    .width(Length::Fill)
+    .into()
+}
+
+fn setup_snippet_view<'a>(
+    version: &'a str,
+    selected: SnippetTarget,
+    snippet: &'a str,
+) -> Element<'a, Message> {
+    let mut tabs = row![].spacing(8);
+    for target in SnippetTarget::ALL {
+        tabs = tabs.push(
+            button(text(target.label()).size(12))
+                .on_press(Message::SetupSnippetTargetSelected(target))
+                .style(if target == selected {
+                    styles::primary_button
+                } else {
+                    styles::secondary_button
+                })
+                .padding([6, 12]),
+        );
+    }
+
+    column![
+        text(format!("Setup Snippet for {}", version)).size(20),
+        Space::new().height(12),
+        tabs,
+        Space::new().height(8),
+        container(text(snippet).size(12).font(iced::Font::MONOSPACE))
+            .style(styles::kbd_container)
+            .padding(12)
+            .width(Length::Fill),
+        Space::new().height(24),
+        row![
+            button(text("Close").size(13))
+                .on_press(Message::CloseModal)
+                .style(styles::secondary_button)
+                .padding([10, 20]),
+            Space::new().width(Length::Fill),
+            button(text("Copy").size(13))
+                .on_press(Message::CopyToClipboard(snippet.to_string()))
+                .style(styles::primary_button)
+                .padding([10, 20]),
+        ]
+        .spacing(16),
+    ]
+    .spacing(4)
+    .width(Length::Fill)
+    .into()
+}
+
+fn batch_summary_view<'a>(
+    summary: &'a BatchSummary,
+    environment: &EnvironmentId,
+    snapshot: Option<(&'a EnvironmentSnapshot, bool)>,
+    read_only: bool,
+) -> Element<'a, Message> {
+    let mut results = column![].spacing(4);
+    for (request, error) in &summary.results {
+        let action = match request {
+            OperationRequest::Install { .. } => "Install",
+            OperationRequest::Uninstall { .. } => "Uninstall",
+            OperationRequest::SetDefault { .. } => "Set default to",
+        };
+        let line = match error {
+            None => status::label(
+                Status::Success,
+                format!("{} Node {}", action, request.version()),
+                13.0,
+            ),
+            Some(error) => row![
+                status::label(
+                    Status::Error,
+                    format!("{} Node {}: {}", action, request.version(), error),
+                    13.0,
+                ),
+                Space::new().width(Length::Fill),
+                button(text("Details").size(11))
+                    .on_press(Message::ShowOperationFailure(OperationFailure::new(
+                        environment.clone(),
+                        request.clone(),
+                        error.clone(),
+                    )))
+                    .style(styles::ghost_button)
+                    .padding([2, 8]),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center)
+            .into(),
+        };
+        results = results.push(line);
+    }
+
+    let mut buttons = row![].spacing(16).align_y(Alignment::Center);
+    if let Some((snapshot, pending)) = snapshot {
+        let taken_at = chrono::DateTime::<chrono::Local>::from(snapshot.taken_at);
+        let label = if pending {
+            format!("Snapshot from {}", taken_at.format("%H:%M"))
+        } else {
+            format!(
+                "Nothing to roll back, already matches the snapshot from {}",
+                taken_at.format("%H:%M")
+            )
+        };
+        buttons = buttons
+            .push(
+                text(label)
+                    .size(12)
+                    .color(iced::Color::from_rgb8(142, 142, 147)),
+            )
+            .push(Space::new().width(Length::Fill))
+            .push(
+                button(text("Roll Back").size(13))
+                    .on_press_maybe((pending && !read_only).then_some(Message::RollBackEnvironment))
+                    .style(styles::secondary_button)
+                    .padding([10, 20]),
+            );
+    } else {
+        buttons = buttons.push(Space::new().width(Length::Fill));
+    }
+    buttons = buttons.push(
+        button(text("Close").size(13))
+            .on_press(Message::CloseModal)
+            .style(styles::secondary_button)
+            .padding([10, 20]),
+    );
+
+    column![
+        text("Operations Finished").size(20),
+        Space::new().height(12),
+        text(summary.headline()).size(14),
+        Space::new().height(8),
+        scrollable(results).height(Length::Shrink),
+        Space::new().height(24),
+        buttons,
+    ]
+    .spacing(4)
+    .width(Length::Fill)
+    .into()
+}
+
+fn operation_failure_view(failure: &OperationFailure) -> Element<'_, Message> {
+    let action = match failure.request {
+        OperationRequest::Install { .. } => "Installing",
+        OperationRequest::Uninstall { .. } => "Uninstalling",
+        OperationRequest::SetDefault { .. } => "Setting the default to",
+    };
+
+    column![
+        text("Operation Failed").size(20),
+        Space::new().height(12),
+        text(format!(
+            "{} Node {} failed in {}.",
+            action,
+            failure.request.version(),
+            failure.environment.display_name()
+        ))
+        .size(14),
+        Space::new().height(8),
+        container(
+            scrollable(text(&failure.error).size(12).font(iced::Font::MONOSPACE))
+                .height(Length::Shrink)
+        )
+        .style(styles::kbd_container)
+        .padding(12)
+        .max_height(240)
+        .width(Length::Fill),
+        Space::new().height(8),
+        text("The operation log bundles the commands that ran, the app log, and backend details for a bug report.")
+            .size(12)
+            .color(iced::Color::from_rgb8(142, 142, 147)),
+        Space::new().height(24),
+        row![
+            Space::new().width(Length::Fill),
+            button(text("Export Operation Log").size(13))
+                .on_press(Message::ExportOperationLog)
+                .style(styles::secondary_button)
+                .padding([10, 20]),
+            button(text("Close").size(13))
+                .on_press(Message::CloseModal)
+                .style(styles::primary_button)
+                .padding([10, 20]),
+        ]
+        .spacing(16),
+    ]
+    .spacing(4)
+    .width(Length::Fill)
+    .into()
+}
+
+fn ci_matrix_view<'a>(
+    majors: &'a [u32],
+    selected: &'a [u32],
+    include_engines: bool,
+) -> Element<'a, Message> {
+    let mut major_row = row![].spacing(12).align_y(Alignment::Center);
+    for &major in majors {
+        major_row = major_row.push(
+            checkbox(selected.contains(&major))
+                .label(format!("Node {}", major))
+                .on_toggle(move |_| Message::CiMatrixMajorToggled(major))
+                .size(14)
+                .text_size(12),
+        );
+    }
+
+    let snippet = snippets::ci_matrix(selected, include_engines);
+
+    column![
+        text("GitHub Actions Matrix").size(20),
+        Space::new().height(12),
+        text("Majors to test:").size(14),
+        Space::new().height(4),
+        major_row.wrap(),
+        Space::new().height(8),
+        row![
+            toggler(include_engines)
+                .on_toggle(Message::CiMatrixEnginesToggled)
+                .size(18),
+            text("Include package.json engines field").size(12),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+        Space::new().height(8),
+        container(text(snippet.clone()).size(12).font(iced::Font::MONOSPACE))
+            .style(styles::kbd_container)
+            .padding(12)
+            .width(Length::Fill),
+        Space::new().height(24),
+        row![
+            button(text("Close").size(13))
+                .on_press(Message::CloseModal)
+                .style(styles::secondary_button)
+                .padding([10, 20]),
+            Space::new().width(Length::Fill),
+            button(text("Copy").size(13))
+                .on_press_maybe((!selected.is_empty()).then_some(Message::CopyToClipboard(snippet)))
+                .style(styles::primary_button)
+                .padding([10, 20]),
+        ]
+        .spacing(16),
+    ]
+    .spacing(4)
+    .width(Length::Fill)
+    .into()
+}
+
+fn test_matrix_view<'a>(
+    versions: &'a [String],
+    project: Option<&'a std::path::Path>,
+    command: &'a str,
+    runs: &'a [MatrixRun],
+    toolchain_warning: Option<Element<'a, Message>>,
+    trust: Option<Element<'a, Message>>,
+) -> Element<'a, Message> {
+    let running = runs
+        .iter()
+        .any(|r| matches!(r.outcome, MatrixOutcome::Pending | MatrixOutcome::Running));
+
+    let project_label = match project {
+        Some(path) => text(path.display().to_string()).size(12),
+        None => text("No project selected")
+            .size(12)
+            .color(iced::Color::from_rgb8(142, 142, 147)),
+    };
+    let command_input = text_input("npm test", command)
+        .size(13)
+        .padding([8, 12])
+        .style(styles::search_input);
+    let command_input = if running {
+        command_input
+    } else {
+        command_input
+            .on_input(Message::TestMatrixCommandChanged)
+            .on_submit(Message::RunTestMatrix)
+    };
+
+    let mut results = column![].spacing(6);
+    if runs.is_empty() {
+        results = results.push(
+            text(format!(
+                "Will run under {}",
+                versions
+                    .iter()
+                    .map(|v| format!("Node {}", v))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))
+            .size(12)
+            .color(iced::Color::from_rgb8(142, 142, 147)),
+        );
+    }
+    for run in runs {
+        let (outcome, label) = match &run.outcome {
+            MatrixOutcome::Pending => (Status::Neutral, "Waiting"),
+            MatrixOutcome::Running => (Status::Neutral, "Running..."),
+            MatrixOutcome::Passed => (Status::Success, "Passed"),
+            MatrixOutcome::Failed(_) => (Status::Error, "Failed"),
+        };
+        let duration = run
+            .duration
+            .map(|d| format!("{:.1}s", d.as_secs_f64()))
+            .unwrap_or_default();
+        let mut entry = column![
+            row![
+                text(format!("Node {}", run.version))
+                    .size(13)
+                    .width(Length::Fixed(140.0)),
+                status::label(outcome, label, 13.0),
+                Space::new().width(Length::Fill),
+                text(duration)
+                    .size(12)
+                    .color(iced::Color::from_rgb8(142, 142, 147)),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center)
+        ]
+        .spacing(2);
+        if let MatrixOutcome::Failed(output) = &run.outcome {
+            entry = entry.push(
+                text(output.as_str())
+                    .size(11)
+                    .font(iced::Font::MONOSPACE)
+                    .color(iced::Color::from_rgb8(142, 142, 147)),
+            );
+        }
+        results = results.push(entry);
+    }
+
+    let can_run = project.is_some() && !command.trim().is_empty() && !running && trust.is_none();
+
+    let toolchain_warning: Element<Message> = toolchain_warning
+        .map(|warning| column![Space::new().height(8), warning].into())
+        .unwrap_or_else(|| Space::new().into());
+    let trust: Element<Message> = trust
+        .map(|trust| column![Space::new().height(8), trust].into())
+        .unwrap_or_else(|| Space::new().into());
+
+    column![
+        text("Test Matrix").size(20),
+        Space::new().height(12),
+        text("Runs a command in a project under each selected version, one at a time.")
+            .size(12)
+            .color(iced::Color::from_rgb8(142, 142, 147)),
+        Space::new().height(8),
+        row![
+            project_label,
+            Space::new().width(Length::Fill),
+            button(text("Choose Project...").size(11))
+                .on_press_maybe((!running).then_some(Message::PickTestMatrixProject))
+                .style(styles::secondary_button)
+                .padding([4, 10]),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+        toolchain_warning,
+        trust,
+        Space::new().height(8),
+        command_input,
+        Space::new().height(8),
+        scrollable(results).height(Length::Shrink),
+        Space::new().height(24),
+        row![
+            button(text("Close").size(13))
+                .on_press(Message::CloseModal)
+                .style(styles::secondary_button)
+                .padding([10, 20]),
+            Space::new().width(Length::Fill),
+            button(text(if runs.is_empty() { "Run" } else { "Run Again" }).size(13))
+                .on_press_maybe(can_run.then_some(Message::RunTestMatrix))
+                .style(styles::primary_button)
+                .padding([10, 20]),
+        ]
+        .spacing(16),
+    ]
+    .spacing(4)
+    .width(Length::Fill)
+    .into()
+}
+
+/// The trust prompt, or a note that the project was not trusted. `None`
+/// once the project is trusted and its command may run.
+fn trust_view<'a>(
+    project: Option<&std::path::Path>,
+    trusted: Option<bool>,
+    confirming: bool,
+) -> Option<Element<'a, Message>> {
+    let project = project?;
+    let decide = |trusted| Message::ProjectTrustDecided {
+        project: project.to_path_buf(),
+        trusted,
+    };
+
+    let content = match trusted {
+        Some(true) => return None,
+        Some(false) => row![
+            status::label(
+                Status::Warning,
+                "Not trusted; its commands won't run",
+                12.0
+            ),
+            Space::new().width(Length::Fill),
+            button(text("Trust and Run").size(11))
+                .on_press(decide(true))
+                .style(styles::secondary_button)
+                .padding([4, 10]),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+        None if confirming => row![
+            text("Do you trust this project? Its scripts run with your permissions, and a cloned repository can make them do anything.")
+                .size(12)
+                .width(Length::Fill),
+            button(text("Don't Trust").size(11))
+                .on_press(decide(false))
+                .style(styles::ghost_button)
+                .padding([4, 10]),
+            button(text("Trust and Run").size(11))
+                .on_press(decide(true))
+                .style(styles::primary_button)
+                .padding([4, 10]),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+        None => return None,
+    };
+
+    Some(
+        container(content)
+            .style(styles::card_container)
+            .padding(12)
+            .width(Length::Fill)
+            .into(),
+    )
+}
+
+fn backend_upgrades_view<'a>(
+    backend: &'a str,
+    version: &'a str,
+    upgrades: &'a [BackendUpgrade],
+    can_retry: bool,
+) -> Element<'a, Message> {
+    let muted = iced::Color::from_rgb8(142, 142, 147);
+    let running = upgrades
+        .iter()
+        .any(|u| u.outcome == BackendUpgradeOutcome::Running);
+    let failed = upgrades
+        .iter()
+        .any(|u| matches!(u.outcome, BackendUpgradeOutcome::Failed(_)));
+
+    let mut results = column![].spacing(8);
+    if upgrades.is_empty() {
+        results = results.push(
+            text(format!(
+                "Every environment already runs {} {}",
+                backend, version
+            ))
+            .size(12)
+            .color(muted),
+        );
+    }
+    for upgrade in upgrades {
+        let (outcome, label) = match &upgrade.outcome {
+            BackendUpgradeOutcome::Running => (Status::Neutral, "Upgrading..."),
+            BackendUpgradeOutcome::Upgraded(_) => (Status::Success, "Upgraded"),
+            BackendUpgradeOutcome::Failed(_) => (Status::Error, "Failed"),
+        };
+        let from = upgrade
+            .from
+            .as_deref()
+            .map(|v| format!("from {}", v))
+            .unwrap_or_default();
+        let mut entry = column![
+            row![
+                text(upgrade.name.as_str())
+                    .size(13)
+                    .width(Length::Fixed(180.0)),
+                status::label(outcome, label, 13.0),
+                Space::new().width(Length::Fill),
+                text(from).size(12).color(muted),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center)
+        ]
+        .spacing(2);
+        match &upgrade.outcome {
+            BackendUpgradeOutcome::Upgraded(how) => {
+                entry = entry.push(text(how.as_str()).size(11).color(muted));
+            }
+            BackendUpgradeOutcome::Failed(error) => {
+                entry = entry.push(
+                    text(error.as_str())
+                        .size(11)
+                        .font(iced::Font::MONOSPACE)
+                        .color(muted),
+                );
+            }
+            BackendUpgradeOutcome::Running => {
+                if let Some(line) = &upgrade.progress {
+                    entry = entry.push(
+                        text(line.as_str())
+                            .size(11)
+                            .font(iced::Font::MONOSPACE)
+                            .color(muted),
+                    );
+                }
+            }
+        }
+        results = results.push(entry);
+    }
+
+    column![
+        text(format!("Upgrade {} to {}", backend, version)).size(20),
+        Space::new().height(12),
+        text("Each environment is upgraded the way its backend was installed.")
+            .size(12)
+            .color(muted),
+        Space::new().height(8),
+        scrollable(results).height(Length::Shrink),
+        Space::new().height(24),
+        row![
+            button(text("Close").size(13))
+                .on_press(Message::CloseModal)
+                .style(styles::secondary_button)
+                .padding([10, 20]),
+            Space::new().width(Length::Fill),
+            button(text("Retry Failed").size(13))
+                .on_press_maybe(
+                    (failed && !running && can_retry).then_some(Message::UpgradeAllBackends)
+                )
+                .style(styles::primary_button)
+                .padding([10, 20]),
+        ]
+        .spacing(16),
+    ]
+    .spacing(4)
+    .width(Length::Fill)
+    .into()
+}
+
+/// Warns when the default version does not meet the project's declared
+/// toolchain, offering the newest release that does.
+fn toolchain_warning_view<'a>(
+    toolchain: &'a ProjectToolchain,
+    state: &'a MainState,
+    read_only: bool,
+) -> Option<Element<'a, Message>> {
+    let env = state.active_environment();
+    let default = env.default_version.as_ref()?;
+    let unmet = toolchain.unmet(default);
+    if unmet.is_empty() {
+        return None;
+    }
+
+    let mut warning = column![].spacing(4);
+    for requirement in unmet {
+        warning = warning.push(status::label(
+            Status::Warning,
+            format!(
+                "Default Node {} does not meet {} ({})",
+                default, requirement.source, requirement.range
+            ),
+            12.0,
+        ));
+    }
+    if let Some(workspace) = &toolchain.workspace {
+        warning = warning.push(
+            text(format!(
+                "Applies to every package in this {} workspace.",
+                workspace
+            ))
+            .size(11)
+            .color(iced::Color::from_rgb8(142, 142, 147)),
+        );
+    }
+    if let Some(suggested) =
+        toolchain.suggested_install(&state.available_versions.remote().versions)
+    {
+        let version = suggested.version.to_string();
+        if !env.installed_set.contains(&version) {
+            warning = warning.push(
+                button(text(format!("Install {}", version)).size(11))
+                    .on_press_maybe((!read_only).then(|| Message::StartInstall(version.clone())))
+                    .style(styles::secondary_button)
+                    .padding([4, 10]),
+            );
+        }
+    }
+    Some(warning.into())
+}
+
+fn confirm_jetbrains_update_view(
+    changes: &[InterpreterChange],
+    preview_limit: usize,
+) -> Element<'_, Message> {
+    let mut change_list = column![].spacing(4);
+    for change in changes.iter().take(preview_limit) {
+        change_list = change_list.push(
+            text(format!("{}: {}", change.ide, change.from))
+                .size(12)
+                .color(iced::Color::from_rgb8(142, 142, 147)),
+        );
+    }
+    if changes.len() > preview_limit {
+        change_list = change_list.push(
+            text(format!("...and {} more", changes.len() - preview_limit))
+                .size(11)
+                .color(iced::Color::from_rgb8(142, 142, 147)),
+        );
+    }
+    let target = changes.first().map(|c| c.to.as_str()).unwrap_or_default();
+
+    column![
+        text("Update JetBrains IDEs?").size(20),
+        Space::new().height(12),
+        text(format!(
+            "The default version changed. Point these Node.js interpreters at {}?",
+            target
+        ))
+        .size(14),
+        Space::new().height(8),
+        change_list,
+        Space::new().height(8),
+        text("Close the IDEs first; they rewrite their settings on exit.")
+            .size(12)
+            .color(iced::Color::from_rgb8(142, 142, 147)),
+        Space::new().height(24),
+        row![
+            button(text("Not Now").size(13))
+                .on_press(Message::CloseModal)
+                .style(styles::secondary_button)
+                .padding([10, 20]),
+            Space::new().width(Length::Fill),
+            button(text("Update").size(13))
+                .on_press(Message::ApplyJetBrainsUpdate)
+                .style(styles::primary_button)
+                .padding([10, 20]),
+        ]
+        .spacing(16),
+    ]
+    .spacing(4)
+    .width(Length::Fill)
+    .into()
+}
+
+fn confirm_quit_view(active: usize) -> Element<'static, Message> {
+    column![
+        text("Quit Versi?").size(20),
+        Space::new().height(12),
+        text(format!(
+            "{} operation{} still running. Cancelling may leave a partially installed version behind.",
+            active,
+            if active == 1 { " is" } else { "s are" }
+        ))
+        .size(14),
+        Space::new().height(24),
+        row![
+            button(text("Keep Running").size(13))
+                .on_press(Message::CloseModal)
+                .style(styles::secondary_button)
+                .padding([10, 20]),
+            Space::new().width(Length::Fill),
+            button(text("Quit When Done").size(13))
+                .on_press(Message::QuitWhenIdle)
+                .style(styles::secondary_button)
+                .padding([10, 20]),
+            button(text("Cancel and Quit").size(13))
+                .on_press(Message::ConfirmQuit)
+                .style(styles::danger_button)
+                .padding([10, 20]),
+        ]
+        .spacing(16),
+    ]
+    .spacing(4)
+    .width(Length::Fill)
+    .into()
+}
+
+fn confirm_close_view(can_minimize: bool, remember: bool) -> Element<'static, Message> {
+    let description = if can_minimize {
+        "Versi can keep running in the system tray, or quit completely."
+    } else {
+        "The system tray is unavailable, so closing the window will quit Versi."
+    };
+
+    let mut actions = row![
+        button(text("Cancel").size(13))
+            .on_press(Message::CloseModal)
+            .style(styles::secondary_button)
+            .padding([10, 20]),
+        Space::new().width(Length::Fill),
+    ]
+    .spacing(16);
+
+    if can_minimize {
+        actions = actions.push(
+            button(text("Minimize to Tray").size(13))
+                .on_press(Message::CloseDialogChoice(CloseBehavior::MinimizeToTray))
+                .style(styles::primary_button)
+                .padding([10, 20]),
+        );
+    }
+
+    actions = actions.push(
+        button(text("Quit").size(13))
+            .on_press(Message::CloseDialogChoice(CloseBehavior::Quit))
+            .style(styles::danger_button)
+            .padding([10, 20]),
+    );
+
+    column![
+        text("Close Versi?").size(20),
+        Space::new().height(12),
+        text(description).size(14),
+        Space::new().height(16),
+        row![
+            toggler(remember)
+                .on_toggle(Message::CloseDialogRememberToggled)
+                .size(18),
+            text("Remember my choice").size(12),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+        text("You can change this later in Settings")
+            .size(11)
+            .color(iced::Color::from_rgb8(142, 142, 147)),
+        Space::new().height(24),
+        actions,
+    ]
+    .spacing(4)
+    .width(Length::Fill)
+    .into()
+}
+
+fn resume_operations_view<'a>(
+    environment: &'a str,
+    operations: &'a [OperationRequest],
+    preview_limit: usize,
+    read_only: bool,
+) -> Element<'a, Message> {
+    let mut operation_list = column![].spacing(4);
+
+    for request in operations.iter().take(preview_limit) {
+        let label = match request {
+            OperationRequest::Install { version } => format!("Install Node {}", version),
+            OperationRequest::Uninstall { version } => format!("Uninstall Node {}", version),
+            OperationRequest::SetDefault { version } => format!("Set Node {} as default", version),
+        };
+        operation_list = operation_list.push(text(label).size(13));
+    }
+
+    if operations.len() > preview_limit {
+        operation_list = operation_list.push(
+            text(format!("...and {} more", operations.len() - preview_limit))
+                .size(12)
+                .color(iced::Color::from_rgb8(142, 142, 147)),
+        );
+    }
+
+    column![
+        text("Resume Unfinished Operations?").size(20),
+        Space::new().height(12),
+        text(format!(
+            "Versi was closed before these operations on {} finished.",
+            environment
+        ))
+        .size(14),
+        Space::new().height(16),
+        operation_list,
+        Space::new().height(24),
+        row![
+            button(text("Discard").size(13))
+                .on_press(Message::DiscardPendingOperations)
+                .style(styles::secondary_button)
+                .padding([10, 20]),
+            Space::new().width(Length::Fill),
+            button(text("Resume").size(13))
+                .on_press_maybe((!read_only).then_some(Message::ResumePendingOperations))
+                .style(styles::primary_button)
+                .padding([10, 20]),
+        ]
+        .spacing(16),
+    ]
+    .spacing(4)
+    .width(Length::Fill)
+    .into()
+}
+
+fn keyboard_shortcuts_view() -> Element<'static, Message> {
+    #[cfg(target_os = "macos")]
+    let mod_key = "\u{2318}";
+    #[cfg(not(target_os = "macos"))]
+    let mod_key = "Ctrl+";
+    #[cfg(target_os = "macos")]
+    let alt_key = "\u{2325}";
+    #[cfg(not(target_os = "macos"))]
+    let alt_key = "Alt+";
+
+    let shortcuts = [
+        (format!("{}K", mod_key), "Search versions"),
+        (format!("{}R", mod_key), "Refresh"),
+        (format!("{},", mod_key), "Settings"),
+        (format!("{}W", mod_key), "Close window"),
+        (format!("{}Tab", mod_key), "Next environment"),
+        (format!("{}Shift+Tab", mod_key), "Previous environment"),
+        ("\u{2191}/\u{2193}".to_string(), "Navigate versions"),
+        ("Enter".to_string(), "Install / set default"),
+        (format!("{}Click", mod_key), "Select version"),
+        (format!("{}Click", alt_key), "Set as default"),
+        ("Middle-click".to_string(), "Copy version"),
+        (format!("{}Shift+D", mod_key), "Event log"),
+        ("Esc".to_string(), "Close modal"),
+        ("?".to_string(), "This help"),
+    ];
+
+    let muted = iced::Color::from_rgb8(142, 142, 147);
+
+    let mut rows = column![].spacing(8);
+    for (key, desc) in shortcuts {
+        rows = rows.push(
+            row![
+                container(text(key).size(12))
+                    .style(styles::kbd_container)
+                    .padding([2, 8])
+                    .width(Length::Fixed(80.0)),
+                text(desc).size(13).color(muted),
+            ]
+            .spacing(12)
+            .align_y(iced::Alignment::Center),
+        );
+    }
+
+    column![
+        text("Keyboard Shortcuts").size(20),
+        Space::new().height(16),
+        rows,
+        Space::new().height(24),
+        button(text("Close").size(13))
+            .on_press(Message::CloseModal)
+            .style(styles::secondary_button)
+            .padding([10, 20]),
+    ]
+    .spacing(4)
+    .width(Length::Fill)
+    .into()
+}
+
+/// Notes on the majors of `versions`, so uninstall confirmations surface
+/// anything the user wrote down about keeping them.
+fn major_notes_view<'a>(
+    versions: &[String],
+    notes: &'a BTreeMap<u32, String>,
+) -> Option<Element<'a, Message>> {
+    let majors: BTreeSet<u32> = versions
+        .iter()
+        .filter_map(|v| v.parse::<NodeVersion>().ok())
+        .map(|v| v.major)
+        .collect();
+    let lines: Vec<Element<Message>> = majors
+        .into_iter()
+        .filter_map(|major| {
+            let note = notes.get(&major)?;
+            Some(status::label(
+                Status::Warning,
+                format!("Node {}: {}", major, note),
+                12.0,
+            ))
+        })
+        .collect();
+    (!lines.is_empty()).then(|| column(lines).spacing(4).into())
+}
+
+fn edit_major_note_view(major: u32, draft: &str) -> Element<'_, Message> {
+    column![
+        text(format!("Note for Node {}.x", major)).size(20),
+        Space::new().height(12),
+        text("Shown on the version group and when uninstalling its versions.")
+            .size(12)
+            .color(iced::Color::from_rgb8(142, 142, 147)),
+        Space::new().height(8),
+        text_input("e.g. used by the legacy API service", draft)
+            .on_input(Message::MajorNoteDraftChanged)
+            .on_submit(Message::SaveMajorNote)
+            .size(13)
+            .padding([8, 12])
+            .style(styles::search_input),
+        Space::new().height(24),
+        row![
+            button(text("Cancel").size(13))
+                .on_press(Message::CloseModal)
+                .style(styles::secondary_button)
+                .padding([10, 20]),
+            Space::new().width(Length::Fill),
+            button(text("Save").size(13))
+                .on_press(Message::SaveMajorNote)
+                .style(styles::primary_button)
+                .padding([10, 20]),
+        ]
+        .spacing(16),
     ]
     .spacing(4)
     .width(Length::Fill)
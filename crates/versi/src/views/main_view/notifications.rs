@@ -0,0 +1,99 @@
+use chrono::NaiveDate;
+use iced::widget::{Space, button, column, container, row, text};
+use iced::{Alignment, Element, Length};
+
+use crate::eol::WarningAcknowledgement;
+use crate::message::Message;
+use crate::settings::AppSettings;
+use crate::theme::styles;
+
+/// Banners and support warnings hidden on `today`.
+pub(super) fn hidden_count(settings: &AppSettings, today: NaiveDate) -> usize {
+    settings.banner_states.suppressed(today).count() + support_warnings(settings, today).count()
+}
+
+fn support_warnings(
+    settings: &AppSettings,
+    today: NaiveDate,
+) -> impl Iterator<Item = (&u32, &WarningAcknowledgement)> {
+    settings
+        .eol_acknowledgements
+        .iter()
+        .filter(move |(_, ack)| ack.snoozed_until.is_none_or(|until| today < until))
+}
+
+/// Lists what is hidden over `content`, each with a way to bring it back.
+pub(super) fn overlay<'a>(
+    content: Element<'a, Message>,
+    settings: &'a AppSettings,
+    today: NaiveDate,
+) -> Element<'a, Message> {
+    let muted = iced::Color::from_rgb8(142, 142, 147);
+    let until = |snoozed_until: Option<NaiveDate>| match snoozed_until {
+        Some(until) => format!("Snoozed until {}", until.format("%Y-%m-%d")),
+        None => "Dismissed".to_string(),
+    };
+    let entry = |label: String, status: String, restore: Message| {
+        row![
+            column![text(label).size(12), text(status).size(11).color(muted)]
+                .spacing(2)
+                .width(Length::Fill),
+            button(text("Restore").size(11))
+                .on_press(restore)
+                .style(styles::secondary_button)
+                .padding([4, 10]),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center)
+    };
+
+    let mut entries = column![].spacing(8);
+    for (id, suppression) in settings.banner_states.suppressed(today) {
+        entries = entries.push(entry(
+            id.label().to_string(),
+            until(suppression.snoozed_until),
+            Message::RestoreBanner(id),
+        ));
+    }
+    for (major, ack) in support_warnings(settings, today) {
+        entries = entries.push(entry(
+            format!("Node {} support warning", major),
+            until(ack.snoozed_until),
+            Message::RestoreSupportWarning(*major),
+        ));
+    }
+    if hidden_count(settings, today) == 0 {
+        entries = entries.push(text("Nothing is hidden").size(12).color(muted));
+    }
+
+    let panel = container(
+        column![
+            row![
+                text("Hidden Notifications").size(13),
+                Space::new().width(Length::Fill),
+                button(text("Close").size(11))
+                    .on_press(Message::NotificationsToggled)
+                    .style(styles::ghost_button)
+                    .padding([4, 10]),
+            ]
+            .align_y(Alignment::Center),
+            entries,
+        ]
+        .spacing(8),
+    )
+    .style(styles::modal_container)
+    .padding(12)
+    .width(Length::Fixed(340.0));
+
+    let popover = container(panel)
+        .padding(iced::Padding::new(56.0).right(24.0))
+        .align_x(iced::alignment::Horizontal::Right)
+        .align_y(iced::alignment::Vertical::Top)
+        .width(Length::Fill)
+        .height(Length::Fill);
+
+    iced::widget::stack![content, popover]
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+}
@@ -1,21 +1,26 @@
-use iced::widget::{Space, button, container, text_input, tooltip};
-use iced::{Element, Length};
+use iced::widget::{Space, button, column, container, row, text, text_input, tooltip};
+use iced::{Alignment, Element, Length};
 
 use crate::icon;
 use crate::message::Message;
+use crate::settings::{AppSettings, VersionGrouping, VersionsLayout};
 use crate::state::MainState;
 use crate::theme::styles;
 use crate::widgets::helpers::styled_tooltip;
 
 pub const SEARCH_INPUT_ID: &str = "search-input";
 
-pub(super) fn search_bar_view<'a>(state: &'a MainState) -> Element<'a, Message> {
+pub(super) fn search_bar_view<'a>(
+    state: &'a MainState,
+    settings: &'a AppSettings,
+) -> Element<'a, Message> {
     let input = text_input(
-        "Search versions (e.g., '22', 'lts', 'lts/iron', 'latest')...",
+        "Search versions (e.g., '22', 'lts', 'lts/iron', 'lts/-1', 'latest')...",
         &state.search_query,
     )
     .id(SEARCH_INPUT_ID)
     .on_input(Message::SearchChanged)
+    .on_submit(Message::SearchSubmitted)
     .padding(14)
     .size(14)
     .style(styles::search_input);
@@ -33,7 +38,7 @@ pub(super) fn search_bar_view<'a>(state: &'a MainState) -> Element<'a, Message>
         )
     };
 
-    iced::widget::stack![
+    let search_input = iced::widget::stack![
         input,
         container(clear_btn)
             .align_x(iced::alignment::Horizontal::Right)
@@ -41,6 +46,122 @@ pub(super) fn search_bar_view<'a>(state: &'a MainState) -> Element<'a, Message>
             .width(Length::Fill)
             .height(Length::Fill)
             .padding(iced::Padding::new(0.0).right(4.0)),
+    ];
+
+    let mut search_row = row![search_input].spacing(8).align_y(Alignment::Center);
+    if state.environments.len() > 1 {
+        search_row = search_row.push(scope_toggle(state.search_all_environments));
+    }
+    if settings.versions_layout == VersionsLayout::List && !state.search_all_environments {
+        search_row = search_row.push(grouping_toggle(settings.version_grouping));
+    }
+    let search_row: Element<Message> = search_row.into();
+
+    match filter_chips_view(state, settings) {
+        Some(chips) => column![search_row, chips].spacing(8).into(),
+        None => search_row,
+    }
+}
+
+fn scope_toggle<'a>(all_environments: bool) -> Element<'a, Message> {
+    styled_tooltip(
+        button(text("All environments").size(11))
+            .on_press(Message::SearchAllEnvironmentsToggled)
+            .style(if all_environments {
+                styles::ghost_button_active
+            } else {
+                styles::ghost_button
+            })
+            .padding([4, 8]),
+        "Search installed versions in every environment",
+        tooltip::Position::Bottom,
+    )
+}
+
+fn grouping_toggle<'a>(grouping: VersionGrouping) -> Element<'a, Message> {
+    let grouping_button = |label: &'static str, value: VersionGrouping| {
+        button(text(label).size(11))
+            .on_press(Message::VersionGroupingChanged(value))
+            .style(if grouping == value {
+                styles::ghost_button_active
+            } else {
+                styles::ghost_button
+            })
+            .padding([4, 8])
+    };
+
+    row![
+        grouping_button("Major", VersionGrouping::Major),
+        grouping_button("Codename", VersionGrouping::Codename),
     ]
+    .spacing(2)
+    .align_y(Alignment::Center)
     .into()
 }
+
+fn filter_chips_view<'a>(
+    state: &'a MainState,
+    settings: &'a AppSettings,
+) -> Option<Element<'a, Message>> {
+    let query = state.search_query.trim();
+    let history = settings.search_history_for(&state.active_environment().id);
+
+    let mut chips = row![].spacing(6).align_y(Alignment::Center);
+    let mut has_chips = false;
+
+    for filter in &settings.saved_filters {
+        has_chips = true;
+        chips = chips.push(
+            container(
+                row![
+                    button(text(filter.as_str()).size(11))
+                        .on_press(Message::SearchChanged(filter.clone()))
+                        .style(styles::ghost_button)
+                        .padding([2, 4]),
+                    button(icon::close(10.0))
+                        .on_press(Message::SearchFilterUnpinned(filter.clone()))
+                        .style(styles::ghost_button)
+                        .padding([2, 4]),
+                ]
+                .align_y(Alignment::Center),
+            )
+            .style(styles::badge_default)
+            .padding([0, 4]),
+        );
+    }
+
+    if query.is_empty() && !history.is_empty() {
+        has_chips = true;
+        chips = chips.push(
+            text("Recent:")
+                .size(11)
+                .color(iced::Color::from_rgb8(142, 142, 147)),
+        );
+        for recent in history {
+            chips = chips.push(
+                button(text(recent.as_str()).size(11))
+                    .on_press(Message::SearchChanged(recent.clone()))
+                    .style(styles::ghost_button)
+                    .padding([2, 6]),
+            );
+        }
+        chips = chips.push(
+            button(text("Clear").size(11))
+                .on_press(Message::SearchHistoryCleared)
+                .style(styles::link_button)
+                .padding([2, 6]),
+        );
+    }
+
+    if !query.is_empty() && !settings.saved_filters.iter().any(|f| f == query) {
+        has_chips = true;
+        chips = chips.push(
+            button(text("Pin filter").size(11))
+                .on_press(Message::SearchFilterPinned(query.to_string()))
+                .style(styles::link_button)
+                .padding([2, 6]),
+        );
+    }
+
+    has_chips.then(|| chips.wrap().into())
+}
@@ -0,0 +1,66 @@
+use iced::widget::{Space, button, container, row, text, tooltip};
+use iced::{Alignment, Element, Length};
+
+use crate::message::Message;
+use crate::state::MainState;
+use crate::theme::styles;
+use crate::widgets::helpers::styled_tooltip;
+
+pub(super) fn selection_bar_view(
+    state: &MainState,
+    read_only: bool,
+) -> Option<Element<'_, Message>> {
+    let count = state.selected_versions.len();
+    if count == 0 {
+        return None;
+    }
+
+    let compare_btn = button(text("Compare").size(11))
+        .on_press_maybe((count == 2).then_some(Message::CompareSelectedVersions))
+        .style(styles::secondary_button)
+        .padding([4, 10]);
+    let compare: Element<Message> = if count == 2 {
+        compare_btn.into()
+    } else {
+        styled_tooltip(
+            compare_btn,
+            "Select exactly two versions to compare",
+            tooltip::Position::Bottom,
+        )
+    };
+
+    let bar = row![
+        text(format!("{} selected", count)).size(12),
+        Space::new().width(Length::Fill),
+        compare,
+        button(text("Copy List").size(11))
+            .on_press(Message::CopySelectedVersions)
+            .style(styles::secondary_button)
+            .padding([4, 10]),
+        button(text("CI Matrix").size(11))
+            .on_press(Message::ShowCiMatrix)
+            .style(styles::secondary_button)
+            .padding([4, 10]),
+        button(text("Run Tests").size(11))
+            .on_press(Message::ShowTestMatrix)
+            .style(styles::secondary_button)
+            .padding([4, 10]),
+        button(text("Uninstall Selected").size(11))
+            .on_press_maybe((!read_only).then_some(Message::RequestBulkUninstallSelected))
+            .style(styles::danger_button)
+            .padding([4, 10]),
+        button(text("Clear").size(11))
+            .on_press(Message::ClearVersionSelection)
+            .style(styles::ghost_button)
+            .padding([4, 10]),
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center);
+
+    Some(
+        container(bar)
+            .style(styles::card_container)
+            .padding([8, 12])
+            .into(),
+    )
+}
@@ -1,5 +1,5 @@
-use iced::Element;
-use iced::widget::{button, row, text};
+use iced::widget::{button, mouse_area, row, text};
+use iced::{Alignment, Element};
 
 use crate::message::Message;
 use crate::state::MainState;
@@ -18,13 +18,18 @@ pub fn environment_tabs_view<'a>(state: &'a MainState) -> Option<Element<'a, Mes
             let is_active = idx == state.active_environment_idx;
 
             if !env.available {
-                let label = if let Some(reason) = &env.error {
-                    format!("{} ({})", env.name, reason)
+                let label = match &env.error {
+                    Some(error) => format!("{} ({})", env.name, error.summary()),
+                    None => format!("{} (Unavailable)", env.name),
+                };
+                let style = if is_active {
+                    styles::active_tab_button
                 } else {
-                    format!("{} (Unavailable)", env.name)
+                    styles::disabled_tab_button
                 };
                 return button(text(label).size(13))
-                    .style(styles::disabled_tab_button)
+                    .on_press(Message::EnvironmentSelected(idx))
+                    .style(style)
                     .padding([8, 16])
                     .into();
             }
@@ -35,11 +40,30 @@ pub fn environment_tabs_view<'a>(state: &'a MainState) -> Option<Element<'a, Mes
                 styles::inactive_tab_button
             };
 
-            button(text(&env.name).size(13))
-                .on_press(Message::EnvironmentSelected(idx))
-                .style(style)
-                .padding([8, 16])
+            let label: Element<'a, Message> = if env.loading {
+                row![
+                    text(&env.name).size(13),
+                    text("Loading...")
+                        .size(11)
+                        .color(iced::Color::from_rgb8(142, 142, 147)),
+                ]
+                .spacing(6)
+                .align_y(Alignment::Center)
                 .into()
+            } else {
+                text(&env.name).size(13).into()
+            };
+
+            // Right-click copies the tab's report, like "Copy report" does
+            // for the selected one.
+            mouse_area(
+                button(label)
+                    .on_press(Message::EnvironmentSelected(idx))
+                    .style(style)
+                    .padding([8, 16]),
+            )
+            .on_right_press(Message::CopyEnvironmentReport(idx))
+            .into()
         })
         .collect();
 
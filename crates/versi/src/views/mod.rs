@@ -1,5 +1,6 @@
 pub mod about_view;
 pub mod loading;
+pub mod log_viewer;
 pub mod main_view;
 pub mod onboarding;
 pub mod settings_view;
@@ -2,8 +2,9 @@ use iced::widget::{Space, button, column, container, row, text};
 use iced::{Alignment, Element, Length};
 
 use crate::message::Message;
-use crate::state::{OnboardingState, OnboardingStep};
+use crate::state::{InstallMethod, OnboardingState, OnboardingStep};
 use crate::theme::styles;
+use crate::widgets::status::{self, Status};
 
 pub fn view<'a>(state: &'a OnboardingState, backend_name: &'a str) -> Element<'a, Message> {
     let content = match state.step {
@@ -11,6 +12,7 @@ pub fn view<'a>(state: &'a OnboardingState, backend_name: &'a str) -> Element<'a
         OnboardingStep::SelectBackend => select_backend_step(state),
         OnboardingStep::InstallBackend => install_backend_step(state, backend_name),
         OnboardingStep::ConfigureShell => configure_shell_step(state, backend_name),
+        OnboardingStep::Ready => ready_step(state),
     };
 
     let progress = step_indicator(state);
@@ -101,6 +103,13 @@ fn full_step_index(step: &OnboardingStep, has_select: bool) -> usize {
                 2
             }
         }
+        OnboardingStep::Ready => {
+            if has_select {
+                4
+            } else {
+                3
+            }
+        }
     }
 }
 
@@ -175,45 +184,93 @@ fn install_backend_step<'a>(
     ]
     .spacing(8);
 
+    let methods: Vec<InstallMethod> = state
+        .install_methods
+        .iter()
+        .copied()
+        .filter(|method| method.backend() == backend_name)
+        .collect();
+
     if state.backend_installing {
         content = content.push(
             row![text(format!("Installing {}...", backend_name)).size(16),]
                 .spacing(8)
                 .align_y(Alignment::Center),
         );
+        if let Some(line) = &state.install_progress {
+            content = content.push(
+                text(line)
+                    .size(12)
+                    .color(iced::Color::from_rgb8(142, 142, 147)),
+            );
+        }
+    } else if state.detecting {
+        content = content.push(text(format!("Looking for {}...", backend_name)).size(16));
     } else if let Some(error) = &state.install_error {
-        content = content.push(
-            column![
-                text("Installation failed:").size(16),
-                text(error).size(14),
-                Space::new().height(16),
+        let mut failure = column![
+            text("Installation failed:").size(16),
+            text(error).size(14),
+            Space::new().height(16),
+        ]
+        .spacing(8);
+        if methods.is_empty() {
+            failure = failure.push(
                 button(text("Retry"))
                     .on_press(Message::OnboardingInstallBackend)
                     .style(styles::primary_button),
-            ]
-            .spacing(8),
-        );
+            );
+        } else {
+            failure = failure.push(install_method_buttons(&methods));
+        }
+        content = content.push(failure);
     } else {
-        content = content.push(
-            column![
-                Space::new().height(24),
+        let mut buttons = column![Space::new().height(24)].spacing(8);
+        if methods.is_empty() {
+            buttons = buttons.push(
                 button(text(format!("Install {}", backend_name)).size(16))
                     .on_press(Message::OnboardingInstallBackend)
                     .style(styles::primary_button)
                     .padding([12, 24]),
-            ]
-            .spacing(8),
-        );
+            );
+        } else {
+            buttons = buttons.push(install_method_buttons(&methods));
+        }
+        if backend_name == "fnm" {
+            buttons = buttons.push(
+                button(text("Locate Existing fnm...").size(14))
+                    .on_press(Message::PickFnmBinary)
+                    .style(styles::secondary_button)
+                    .padding([8, 16]),
+            );
+        }
+        content = content.push(buttons);
     }
 
     content.into()
 }
 
+fn install_method_buttons<'a>(methods: &[InstallMethod]) -> Element<'a, Message> {
+    let mut buttons = column![].spacing(8);
+    for (index, method) in methods.iter().enumerate() {
+        buttons = buttons.push(
+            button(text(method.label()).size(16))
+                .on_press(Message::OnboardingInstallWith(*method))
+                .style(if index == 0 {
+                    styles::primary_button
+                } else {
+                    styles::secondary_button
+                })
+                .padding([12, 24]),
+        );
+    }
+    buttons.into()
+}
+
 fn configure_shell_step<'a>(
     state: &'a OnboardingState,
     backend_name: &str,
 ) -> Element<'a, Message> {
-    let mut content = column![
+    column![
         text("Configure Shell").size(28),
         Space::new().height(16),
         text(format!(
@@ -222,8 +279,62 @@ fn configure_shell_step<'a>(
         ))
         .size(16),
         Space::new().height(24),
+        shell_rows(state),
     ]
-    .spacing(8);
+    .spacing(8)
+    .into()
+}
+
+fn ready_step(state: &OnboardingState) -> Element<'_, Message> {
+    let Some(setup) = &state.existing else {
+        return Space::new().into();
+    };
+    let mut found = setup.display_name.to_string();
+    if let Some(version) = &setup.version {
+        found = format!("{} {}", found, version);
+    }
+    let mut summary = format!("{} detected", found);
+    if let Some(source) = &setup.source {
+        summary = format!("{} ({})", summary, source);
+    }
+    let configured: Vec<&str> = state
+        .detected_shells
+        .iter()
+        .filter(|shell| shell.configured)
+        .map(|shell| shell.shell_name.as_str())
+        .collect();
+    let shells = if configured.is_empty() {
+        "No shell loads it yet".to_string()
+    } else {
+        format!("{} configured", configured.join(", "))
+    };
+
+    column![
+        text("You're All Set").size(28),
+        Space::new().height(16),
+        status::label(Status::Success, summary, 16.0),
+        status::label(
+            if configured.is_empty() {
+                Status::Warning
+            } else {
+                Status::Success
+            },
+            shells,
+            16.0,
+        ),
+        Space::new().height(24),
+        text("Other shells can be set up now, or later in Settings.")
+            .size(14)
+            .color(iced::Color::from_rgb8(142, 142, 147)),
+        Space::new().height(8),
+        shell_rows(state),
+    ]
+    .spacing(8)
+    .into()
+}
+
+fn shell_rows(state: &OnboardingState) -> Element<'_, Message> {
+    let mut content = column![].spacing(8);
 
     for shell in &state.detected_shells {
         let shell_row = row![
@@ -274,18 +385,24 @@ fn navigation_buttons<'a>(state: &'a OnboardingState) -> Element<'a, Message> {
     };
 
     let next_label = match state.step {
+        _ if state.detecting => "Checking...",
         OnboardingStep::ConfigureShell => "Finish",
+        OnboardingStep::Ready => "Open Versi",
         _ => "Next",
     };
 
-    let can_proceed = match state.step {
-        OnboardingStep::SelectBackend => state.selected_backend.is_some(),
-        OnboardingStep::InstallBackend => !state.backend_installing,
-        OnboardingStep::ConfigureShell => state.detected_shells.iter().any(|s| s.configured),
-        _ => true,
-    };
+    let can_proceed = !state.detecting
+        && match state.step {
+            OnboardingStep::SelectBackend => state.selected_backend.is_some(),
+            OnboardingStep::InstallBackend => !state.backend_installing,
+            OnboardingStep::ConfigureShell => state.detected_shells.iter().any(|s| s.configured),
+            _ => true,
+        };
 
-    let next_message = if state.step == OnboardingStep::ConfigureShell {
+    let next_message = if matches!(
+        state.step,
+        OnboardingStep::ConfigureShell | OnboardingStep::Ready
+    ) {
         Message::OnboardingComplete
     } else {
         Message::OnboardingNext
@@ -1,12 +1,34 @@
-use iced::widget::{Space, button, column, container, row, scrollable, text, toggler, tooltip};
+use iced::widget::{
+    Space, button, column, container, pick_list, row, scrollable, text, text_input, toggler,
+    tooltip,
+};
 use iced::{Alignment, Element, Length};
 
+use chrono::NaiveTime;
+
+use std::path::{Path, PathBuf};
+
+use crate::eol::{COUNTDOWN_THRESHOLDS, GRACE_PERIODS};
 use crate::icon;
 use crate::message::Message;
-use crate::settings::{AppSettings, ThemeSetting, TrayBehavior};
-use crate::state::{MainState, SettingsModalState, ShellVerificationStatus};
+use crate::report::ReportFormat;
+use crate::scheduler::ScheduledTask;
+use crate::settings::{
+    AppSettings, CloseBehavior, DataDir, ListDensity, NotificationCategory, NotificationDelivery,
+    ProxyMode, RendererSetting, SettingsSection, StartupView, ThemeSchedule, ThemeSetting,
+    TrayBehavior,
+};
+use crate::settings_journal;
+use crate::state::{
+    BackendRemoval, MainState, SettingsModalState, ShellVerificationStatus, TerminalFixPreview,
+};
 use crate::theme::styles;
-use crate::widgets::helpers::nav_icons;
+use crate::widgets::helpers::{format_bytes, nav_icons};
+use crate::widgets::status::{self, Status};
+use versi_backend::BackendUpdateChannel;
+use versi_core::{BackoffStrategy, RetryPolicy};
+use versi_platform::EnvironmentId;
+use versi_shell::{DiffLine, NodeResolution, ShellType, StepOutcome, TroubleshootStep};
 
 pub fn view<'a>(
     settings_state: &'a SettingsModalState,
@@ -27,7 +49,7 @@ pub fn view<'a>(
     let shell_opts = settings.shell_options_for(state.backend_name);
 
     let mut content = column![
-        text("Appearance").size(14),
+        section_title(SettingsSection::Appearance),
         Space::new().height(8),
         row![
             button(
@@ -61,8 +83,20 @@ pub fn view<'a>(
                     styles::secondary_button
                 })
                 .padding([10, 16]),
+            button(text("Scheduled").size(13))
+                .on_press(Message::ThemeChanged(ThemeSetting::Scheduled))
+                .style(if settings.theme == ThemeSetting::Scheduled {
+                    styles::primary_button
+                } else {
+                    styles::secondary_button
+                })
+                .padding([10, 16]),
         ]
         .spacing(8),
+        theme_schedule_row(settings),
+        Space::new().height(12),
+        text("List Density").size(12),
+        density_selector(settings),
         Space::new().height(28),
         text("Preferred Engine").size(14),
         Space::new().height(8),
@@ -74,7 +108,7 @@ pub fn view<'a>(
             .size(11)
             .color(iced::Color::from_rgb8(142, 142, 147)),
         Space::new().height(28),
-        text("System Tray").size(14),
+        section_title(SettingsSection::Tray),
         Space::new().height(8),
         row![
             button(text("When Open").size(13))
@@ -116,7 +150,82 @@ pub fn view<'a>(
             .size(11)
             .color(iced::Color::from_rgb8(142, 142, 147)),
         Space::new().height(28),
-        text("Shell Options").size(14),
+        text("Window").size(14),
+        Space::new().height(8),
+        row![
+            toggler(settings.always_on_top)
+                .on_toggle(Message::AlwaysOnTopToggled)
+                .size(18),
+            text("Keep on top of other windows").size(12),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+        Space::new().height(8),
+        row![
+            toggler(settings.remember_workspace)
+                .on_toggle_maybe(
+                    versi_platform::workspaces_supported()
+                        .then_some(Message::RememberWorkspaceToggled)
+                )
+                .size(18),
+            text("Reopen on the same workspace").size(12),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+        text(
+            "Workspaces are restored on X11 desktops only. Wayland and macOS Spaces \
+             don't let apps choose their workspace"
+        )
+        .size(11)
+        .color(iced::Color::from_rgb8(142, 142, 147)),
+        Space::new().height(8),
+        row![
+            toggler(settings.window_vibrancy)
+                .on_toggle(Message::WindowVibrancyToggled)
+                .size(18),
+            text("Translucent window background").size(12),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+        text(
+            "Blurs the desktop behind the window on macOS, Windows 11 and KDE on X11. \
+             Enabling it takes effect after restarting Versi",
+        )
+        .size(11)
+        .color(iced::Color::from_rgb8(142, 142, 147)),
+        Space::new().height(28),
+        notifications_section(settings),
+        Space::new().height(28),
+        startup_section(settings, state),
+        Space::new().height(28),
+        text("Close Button").size(14),
+        Space::new().height(8),
+        close_behavior_selector(settings),
+        text(if settings.tray_behavior == TrayBehavior::AlwaysRunning {
+            "Closing while operations are running always asks before quitting"
+        } else {
+            "Minimize to Tray needs the tray icon set to always running. \
+             Closing while operations are running always asks before quitting"
+        })
+        .size(11)
+        .color(iced::Color::from_rgb8(142, 142, 147)),
+        Space::new().height(28),
+        text("Renderer").size(14),
+        Space::new().height(8),
+        renderer_selector(settings),
+        text(format!(
+            "Currently using: {}",
+            crate::renderer::current()
+                .map(|selection| selection.describe())
+                .unwrap_or_else(|| settings.renderer.label().to_string())
+        ))
+        .size(11)
+        .color(iced::Color::from_rgb8(142, 142, 147)),
+        text("Use Software if the window stays blank. Takes effect after restarting Versi")
+            .size(11)
+            .color(iced::Color::from_rgb8(142, 142, 147)),
+        Space::new().height(28),
+        section_title(SettingsSection::ShellOptions),
         Space::new().height(8),
     ]
     .spacing(4)
@@ -187,7 +296,26 @@ pub fn view<'a>(
     } else if settings_state.shell_statuses.is_empty() {
         content = content.push(text("No shells detected").size(12));
     } else {
+        let several_environments = settings_state
+            .shell_statuses
+            .iter()
+            .any(|shell| shell.environment != settings_state.shell_statuses[0].environment);
+        let mut current_environment = None;
         for shell in &settings_state.shell_statuses {
+            if several_environments && current_environment != Some(&shell.environment) {
+                current_environment = Some(&shell.environment);
+                let name = state
+                    .environments
+                    .iter()
+                    .find(|env| env.id == shell.environment)
+                    .map_or_else(|| shell.environment.display_name(), |env| env.name.clone());
+                content = content.push(
+                    text(name)
+                        .size(12)
+                        .color(iced::Color::from_rgb8(142, 142, 147)),
+                );
+            }
+
             let is_configured_check = matches!(shell.status, ShellVerificationStatus::Configured);
 
             let status_text = match &shell.status {
@@ -212,49 +340,124 @@ pub fn view<'a>(
                     text("Configuring...").size(12),
                 ]
             } else if is_configured {
-                let mut r = row![
+                let status = if is_configured_check {
+                    Status::Success
+                } else {
+                    Status::Warning
+                };
+                row![
                     text(&shell.shell_name).size(13).width(Length::Fixed(100.0)),
-                    text(status_text)
-                        .size(12)
-                        .color(iced::Color::from_rgb8(52, 199, 89)),
+                    status::label(status, status_text, 12.0),
                 ]
-                .spacing(8)
-                .align_y(Alignment::Center);
-                if is_configured_check {
-                    let check_icon: Element<'_, Message> = icon::check(12.0)
-                        .style(|_theme: &iced::Theme, _status| iced::widget::svg::Style {
-                            color: Some(iced::Color::from_rgb8(52, 199, 89)),
-                        })
-                        .into();
-                    r = r.push(check_icon);
-                }
-                r
             } else if has_no_config_file {
                 row![
                     text(&shell.shell_name).size(13).width(Length::Fixed(100.0)),
-                    text(status_text)
-                        .size(12)
-                        .color(iced::Color::from_rgb8(142, 142, 147)),
+                    status::label(Status::Neutral, status_text, 12.0),
                 ]
             } else {
                 let shell_type = shell.shell_type.clone();
+                let status = if matches!(shell.status, ShellVerificationStatus::Error) {
+                    Status::Error
+                } else {
+                    Status::Warning
+                };
                 row![
                     text(&shell.shell_name).size(13).width(Length::Fixed(100.0)),
-                    text(status_text)
-                        .size(12)
-                        .color(iced::Color::from_rgb8(255, 149, 0)),
+                    status::label(status, status_text, 12.0),
                     Space::new().width(Length::Fill),
+                    button(text("Preview").size(11))
+                        .on_press(Message::PreviewShellConfig(
+                            shell.environment.clone(),
+                            shell_type.clone(),
+                        ))
+                        .style(styles::ghost_button)
+                        .padding([4, 10]),
                     button(text("Configure").size(11))
-                        .on_press(Message::ConfigureShell(shell_type))
+                        .on_press(Message::ConfigureShell(
+                            shell.environment.clone(),
+                            shell_type
+                        ))
                         .style(styles::secondary_button)
                         .padding([4, 10]),
                 ]
             };
 
             content = content.push(shell_row.spacing(8).align_y(Alignment::Center));
+
+            match &shell.preview {
+                Some(Ok(changes)) if changes.is_empty() => {
+                    content = content.push(
+                        text("No changes needed")
+                            .size(11)
+                            .color(iced::Color::from_rgb8(142, 142, 147)),
+                    );
+                }
+                Some(Ok(changes)) => {
+                    for change in changes {
+                        content = content.push(
+                            text(change)
+                                .size(11)
+                                .color(iced::Color::from_rgb8(142, 142, 147)),
+                        );
+                    }
+                }
+                Some(Err(e)) => {
+                    content = content.push(status::label(
+                        Status::Error,
+                        format!("Preview failed: {}", e),
+                        11.0,
+                    ));
+                }
+                None => {}
+            }
         }
     }
 
+    content = content.push(Space::new().height(28));
+    content = content.push(resolved_versions_section(settings_state, state));
+
+    content = content.push(Space::new().height(28));
+    content = content.push(troubleshooter_section(settings_state, state));
+
+    content = content.push(Space::new().height(28));
+    content = content.push(npm_section(settings_state, settings.is_read_only()));
+
+    content = content.push(Space::new().height(28));
+    content = content.push(integrations_section(settings_state, settings));
+
+    content = content.push(Space::new().height(28));
+    content = content.push(network_section(settings_state, settings));
+
+    content = content.push(Space::new().height(28));
+    content = content.push(scheduled_operations_section(
+        settings_state,
+        settings,
+        state,
+    ));
+
+    content = content.push(Space::new().height(28));
+    content = content.push(text("Environment Report").size(14));
+    content = content.push(Space::new().height(8));
+    let mut report_buttons = row![].spacing(8);
+    for format in [
+        ReportFormat::Json,
+        ReportFormat::Csv,
+        ReportFormat::Markdown,
+    ] {
+        report_buttons = report_buttons.push(
+            button(text(format.label()).size(11))
+                .on_press(Message::ExportReport(format))
+                .style(styles::secondary_button)
+                .padding([4, 10]),
+        );
+    }
+    content = content.push(report_buttons);
+    content = content.push(
+        text("Export installed versions, defaults, support status, and disk usage for every environment")
+            .size(11)
+            .color(iced::Color::from_rgb8(142, 142, 147)),
+    );
+
     content = content.push(Space::new().height(28));
     content = content.push(text("Settings Data").size(14));
     content = content.push(Space::new().height(8));
@@ -282,7 +485,135 @@ pub fn view<'a>(
     );
 
     content = content.push(Space::new().height(28));
-    content = content.push(text("Advanced").size(14));
+    content = content.push(sync_section(settings));
+
+    content = content.push(Space::new().height(28));
+    content = content.push(recent_changes_section());
+
+    content = content.push(Space::new().height(28));
+    content = content.push(backend_removal_section(
+        settings_state,
+        state,
+        settings.is_read_only(),
+    ));
+
+    content = content.push(Space::new().height(28));
+    content = content.push(backend_paths_section(settings));
+
+    content = content.push(Space::new().height(28));
+    content = content.push(backend_updates_section(settings, state));
+
+    if capabilities.supports_system_wide || settings.system_wide {
+        content = content.push(Space::new().height(28));
+        content = content.push(system_wide_section(settings, state));
+    }
+
+    content = content.push(Space::new().height(28));
+    content = content.push(follow_project_section(settings, state));
+
+    if !settings.project_trust.is_empty() {
+        content = content.push(Space::new().height(28));
+        content = content.push(project_trust_section(settings));
+    }
+
+    content = content.push(Space::new().height(28));
+    content = content.push(data_dirs_section(settings));
+
+    content = content.push(Space::new().height(28));
+    content = content.push(storage_section(state));
+
+    content = content.push(Space::new().height(28));
+    content = content.push(section_title(SettingsSection::Advanced));
+    content = content.push(Space::new().height(8));
+    let read_only_enforced = AppSettings::read_only_enforced();
+    content = content.push(
+        row![
+            toggler(settings.is_read_only())
+                .on_toggle_maybe((!read_only_enforced).then_some(Message::ReadOnlyToggled))
+                .size(18),
+            text("Read-only mode").size(12),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+    );
+    content = content.push(
+        text(if read_only_enforced {
+            "Enforced by the VERSI_READ_ONLY environment variable"
+        } else {
+            "Inspect environments without allowing installs, uninstalls, or default changes"
+        })
+        .size(11)
+        .color(iced::Color::from_rgb8(142, 142, 147)),
+    );
+    content = content.push(Space::new().height(8));
+    content = content.push(
+        row![
+            toggler(settings.prevent_sleep)
+                .on_toggle(Message::PreventSleepToggled)
+                .size(18),
+            text("Keep the computer awake during operations").size(12),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+    );
+    content = content.push(
+        text("Sleep is allowed again once the operation queue is empty")
+            .size(11)
+            .color(iced::Color::from_rgb8(142, 142, 147)),
+    );
+    if let Some(version) = &settings.update_deferral.skipped_version {
+        content = content.push(Space::new().height(8));
+        content = content.push(
+            row![
+                text(format!("Skipping the Versi {} update", version)).size(12),
+                button(text("Offer It Again").size(12))
+                    .on_press(Message::ClearSkippedAppUpdate)
+                    .style(styles::link_button)
+                    .padding(0),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center),
+        );
+    }
+    content = content.push(Space::new().height(8));
+    content = content.push(
+        row![
+            toggler(settings.sanitize_backend_env)
+                .on_toggle(Message::SanitizeBackendEnvToggled)
+                .size(18),
+            text("Hide other version managers' variables from backend commands").size(12),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+    );
+    let conflicting = versi_backend::conflicting_env_vars(state.backend_name);
+    content = content.push(
+        text(if conflicting.is_empty() {
+            "None are set in the environment Versi was started from".to_string()
+        } else if settings.sanitize_backend_env {
+            format!("Leaving out {}", conflicting.join(", "))
+        } else {
+            format!("Passing on {}", conflicting.join(", "))
+        })
+        .size(11)
+        .color(iced::Color::from_rgb8(142, 142, 147)),
+    );
+    content = content.push(Space::new().height(8));
+    content = content.push(
+        row![
+            toggler(settings.ipc_server)
+                .on_toggle(Message::IpcServerToggled)
+                .size(18),
+            text("Allow control from scripts and editors").size(12),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+    );
+    content = content.push(
+        text(ipc_endpoint_note())
+            .size(11)
+            .color(iced::Color::from_rgb8(142, 142, 147)),
+    );
     content = content.push(Space::new().height(8));
     content = content.push(
         row![
@@ -322,6 +653,10 @@ pub fn view<'a>(
     content = content.push(Space::new().height(8));
     content = content.push(
         row![
+            button(text("View Logs").size(11))
+                .on_press(Message::ShowLogViewer)
+                .style(styles::secondary_button)
+                .padding([4, 10]),
             button(text("Show in Folder").size(11))
                 .on_press(Message::RevealLogFile)
                 .style(styles::secondary_button)
@@ -333,6 +668,32 @@ pub fn view<'a>(
         ]
         .spacing(8),
     );
+    content = content.push(Space::new().height(8));
+    content = content.push(
+        row![
+            toggler(settings.show_event_log)
+                .on_toggle(|_| Message::EventLogToggled)
+                .size(18),
+            text("Show event log overlay").size(12),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+    );
+    content = content.push(
+        text("Recent UI events, for reporting stuck dialogs or lost operations")
+            .size(11)
+            .color(iced::Color::from_rgb8(142, 142, 147)),
+    );
+
+    content = content.push(Space::new().height(28));
+    content = content.push(env_vars_section(settings, settings_state, state));
+
+    content = content.push(Space::new().height(28));
+    content = content.push(support_warnings_section(settings));
+
+    content = content.push(Space::new().height(28));
+    content = content.push(major_notes_section(settings));
+
     column![
         container(header).padding(iced::Padding::new(0.0).right(24.0)),
         Space::new().height(12),
@@ -349,44 +710,1790 @@ pub fn view<'a>(
     .into()
 }
 
-fn engine_button<'a>(
-    name: &'static str,
-    is_selected: bool,
-    is_detected: bool,
+/// A section title with a link that puts the section back to its defaults.
+fn section_title<'a>(section: SettingsSection) -> Element<'a, Message> {
+    row![
+        text(section.label()).size(14),
+        Space::new().width(Length::Fill),
+        button(text("Reset to Defaults").size(11))
+            .on_press(Message::ResetSettingsSection(section))
+            .style(styles::link_button)
+            .padding(0),
+    ]
+    .align_y(Alignment::Center)
+    .into()
+}
+
+fn recent_changes_section<'a>() -> Element<'a, Message> {
+    let breadcrumbs = settings_journal::breadcrumbs();
+    let mut section = column![
+        row![
+            text("Recent Changes").size(14),
+            Space::new().width(Length::Fill),
+            button(text("Revert Last Change").size(11))
+                .on_press_maybe((!breadcrumbs.is_empty()).then_some(Message::RevertSettingsChange))
+                .style(styles::secondary_button)
+                .padding([4, 10]),
+        ]
+        .align_y(Alignment::Center),
+        Space::new().height(8),
+    ]
+    .spacing(4);
+
+    if breadcrumbs.is_empty() {
+        return section
+            .push(
+                text("Settings changed since Versi started can be reverted here")
+                    .size(11)
+                    .color(iced::Color::from_rgb8(142, 142, 147)),
+            )
+            .into();
+    }
+    for breadcrumb in breadcrumbs.iter().take(5) {
+        section = section.push(
+            row![
+                text(breadcrumb.at.format("%H:%M").to_string())
+                    .size(12)
+                    .color(iced::Color::from_rgb8(142, 142, 147))
+                    .width(Length::Fixed(48.0)),
+                text(breadcrumb.labels.join(", ")).size(12),
+            ]
+            .spacing(8),
+        );
+    }
+    section.into()
+}
+
+fn ipc_endpoint_note() -> String {
+    #[cfg(windows)]
+    let endpoint = crate::ipc::pipe_name().unwrap_or_default();
+    #[cfg(not(windows))]
+    let endpoint = versi_platform::AppPaths::new()
+        .map(|p| p.ipc_socket().display().to_string())
+        .unwrap_or_default();
+    format!(
+        "JSON-RPC on {} with get-status, list-installed, install and set-default",
+        endpoint
+    )
+}
+
+fn resolved_versions_section<'a>(
+    settings_state: &'a SettingsModalState,
+    state: &'a MainState,
 ) -> Element<'a, Message> {
-    let btn = button(text(name).size(13))
-        .style(if is_selected {
-            styles::primary_button
-        } else {
-            styles::secondary_button
-        })
-        .padding([10, 16]);
+    let default_version = state
+        .active_environment()
+        .default_version
+        .as_ref()
+        .map(|v| v.to_string());
 
-    if is_detected {
-        btn.on_press(Message::PreferredBackendChanged(name.to_string()))
-            .into()
-    } else {
-        tooltip(
-            btn,
-            container(text(format!("{} is not installed", name)).size(12))
-                .padding([4, 8])
-                .style(styles::tooltip_container),
-            tooltip::Position::Bottom,
-        )
-        .gap(4.0)
-        .into()
+    let mut section = column![
+        text("Terminal Node Versions").size(14),
+        Space::new().height(8),
+        row![
+            button(text("Check").size(11))
+                .on_press_maybe(
+                    (!settings_state.resolving_shells).then_some(Message::CheckResolvedVersions)
+                )
+                .style(styles::secondary_button)
+                .padding([4, 10]),
+            text(if settings_state.resolving_shells {
+                "Starting each shell..."
+            } else {
+                "Runs node --version in each shell with its profile loaded"
+            })
+            .size(11)
+            .color(iced::Color::from_rgb8(142, 142, 147)),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+    ]
+    .spacing(4);
+
+    for (shell_type, resolution) in &settings_state.resolved_versions {
+        let (label, status) = match resolution {
+            NodeResolution::Version(version) => match &default_version {
+                Some(default) if default != version => (
+                    format!("{} (default is {})", version, default),
+                    Status::Warning,
+                ),
+                _ => (version.clone(), Status::Success),
+            },
+            NodeResolution::NotFound => ("node not found".to_string(), Status::Warning),
+            NodeResolution::Error(e) => (e.clone(), Status::Error),
+        };
+        section = section.push(
+            row![
+                text(shell_type.name()).size(13).width(Length::Fixed(100.0)),
+                status::label(status, label, 12.0),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center),
+        );
     }
+
+    section.into()
 }
 
-fn engine_selector<'a>(settings: &'a AppSettings, state: &'a MainState) -> Element<'a, Message> {
-    let preferred = settings.preferred_backend.as_deref().unwrap_or("fnm");
-    let fnm_detected = state.detected_backends.contains(&"fnm");
-    let nvm_detected = state.detected_backends.contains(&"nvm");
+fn troubleshooter_section<'a>(
+    settings_state: &'a SettingsModalState,
+    state: &'a MainState,
+) -> Element<'a, Message> {
+    let muted = iced::Color::from_rgb8(142, 142, 147);
+    let mut section = column![
+        text("Troubleshoot Terminal").size(14),
+        Space::new().height(8),
+    ]
+    .spacing(4);
 
-    row![
-        engine_button("fnm", preferred == "fnm", fnm_detected),
+    if !matches!(state.active_environment().id, EnvironmentId::Native) {
+        return section
+            .push(
+                text("Troubleshooting checks shell profiles on this machine; switch to the native environment to run them")
+                    .size(11)
+                    .color(muted),
+            )
+            .into();
+    }
+
+    let mut actions = row![
+        button(
+            text(if settings_state.troubleshoot_report.is_some() {
+                "Run Again"
+            } else {
+                "Run Checks"
+            })
+            .size(11)
+        )
+        .on_press_maybe(
+            (!settings_state.troubleshooting).then_some(Message::RunTerminalTroubleshooter)
+        )
+        .style(styles::secondary_button)
+        .padding([4, 10]),
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center);
+    if settings_state.troubleshoot_report.is_some() {
+        actions = actions.push(
+            button(text("Close").size(11))
+                .on_press(Message::CloseTerminalTroubleshooter)
+                .style(styles::secondary_button)
+                .padding([4, 10]),
+        );
+    }
+    section = section
+        .push(
+            text("Works in Versi but not in your terminal? Checks the usual causes and fixes them where it is safe to")
+                .size(11)
+                .color(muted),
+        )
+        .push(actions);
+    if let Some(preview) = &settings_state.terminal_fix {
+        section = section.push(terminal_fix_preview(preview));
+    }
+    if let Some(backup) = &settings_state.terminal_fix_backup {
+        section = section.push(
+            text(format!(
+                "Backed up the previous profile to {}",
+                backup.display()
+            ))
+            .size(11)
+            .color(muted),
+        );
+    }
+
+    let Some(report) = &settings_state.troubleshoot_report else {
+        return section.into();
+    };
+
+    for (shell_type, steps) in &report.shells {
+        section = section.push(Space::new().height(8));
+        section = section.push(text(shell_type.name()).size(13));
+        for (index, step) in steps.iter().enumerate() {
+            section = section.push(troubleshoot_step_row(index + 1, step, Some(shell_type)));
+        }
+    }
+    section = section.push(Space::new().height(8));
+    section = section.push(text("Editors").size(13));
+    section = section.push(troubleshoot_step_row(1, &report.editor, None));
+    section = section.push(Space::new().height(4));
+    section = section.push(
+        text("Open a new terminal after applying fixes")
+            .size(11)
+            .color(muted),
+    );
+
+    section.into()
+}
+
+/// Lines around each change shown in a fix preview.
+const DIFF_CONTEXT: usize = 2;
+
+fn terminal_fix_preview(preview: &TerminalFixPreview) -> Element<'_, Message> {
+    let muted = iced::Color::from_rgb8(142, 142, 147);
+    let diff = preview.edit.line_diff();
+    let near_change = |index: usize| {
+        let from = index.saturating_sub(DIFF_CONTEXT);
+        let to = (index + DIFF_CONTEXT + 1).min(diff.len());
+        diff[from..to]
+            .iter()
+            .any(|line| !matches!(line, DiffLine::Same(_)))
+    };
+
+    let mut lines = column![].spacing(0);
+    let mut skipped = false;
+    for (index, line) in diff.iter().enumerate() {
+        let (prefix, content, color) = match line {
+            DiffLine::Same(content) => ("  ", content, muted),
+            DiffLine::Added(content) => ("+ ", content, Status::Success.color()),
+            DiffLine::Removed(content) => ("- ", content, Status::Error.color()),
+        };
+        if matches!(line, DiffLine::Same(_)) && !near_change(index) {
+            if !skipped {
+                lines = lines.push(text("  …").size(11).color(muted));
+            }
+            skipped = true;
+            continue;
+        }
+        skipped = false;
+        lines = lines.push(
+            text(format!("{}{}", prefix, content))
+                .size(11)
+                .font(iced::Font::MONOSPACE)
+                .color(color),
+        );
+    }
+
+    column![
+        text(format!("Changes to {}", preview.path.display())).size(12),
+        container(scrollable(lines).height(Length::Shrink))
+            .max_height(240)
+            .padding(8)
+            .style(styles::card_container),
+        row![
+            button(text("Cancel").size(11))
+                .on_press(Message::CancelTerminalFix)
+                .style(styles::secondary_button)
+                .padding([4, 10]),
+            button(text("Apply").size(11))
+                .on_press(Message::ApplyTerminalFix)
+                .style(styles::primary_button)
+                .padding([4, 10]),
+            text("A backup is saved next to the file first")
+                .size(11)
+                .color(muted),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+    ]
+    .spacing(6)
+    .into()
+}
+
+fn troubleshoot_step_row<'a>(
+    number: usize,
+    step: &'a TroubleshootStep,
+    shell_type: Option<&'a ShellType>,
+) -> Element<'a, Message> {
+    let (detail, status) = match &step.outcome {
+        StepOutcome::Passed(detail) => (detail, Status::Success),
+        StepOutcome::Issue(detail) => (detail, Status::Warning),
+        StepOutcome::Skipped(detail) => (detail, Status::Neutral),
+    };
+
+    let mut step_row = row![
+        text(format!("{}.", number))
+            .size(12)
+            .width(Length::Fixed(20.0)),
+        column![
+            text(step.kind.title()).size(12),
+            status::label(status, detail.as_str(), 11.0),
+        ]
+        .spacing(2)
+        .width(Length::Fill),
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center);
+
+    if let (Some(fix), Some(shell_type)) = (&step.fix, shell_type) {
+        step_row = step_row.push(
+            button(text(fix.label()).size(11))
+                .on_press(Message::PreviewTerminalFix(shell_type.clone(), fix.clone()))
+                .style(styles::secondary_button)
+                .padding([4, 10]),
+        );
+    }
+
+    step_row.into()
+}
+
+fn integrations_section<'a>(
+    settings_state: &'a SettingsModalState,
+    settings: &'a AppSettings,
+) -> Element<'a, Message> {
+    let muted = iced::Color::from_rgb8(142, 142, 147);
+    let mut section = column![
+        text("IDE Integrations").size(14),
+        Space::new().height(8),
+        row![
+            toggler(settings.jetbrains_sync)
+                .on_toggle(Message::JetBrainsSyncToggled)
+                .size(18),
+            text("Offer to update JetBrains IDEs when the default version changes").size(12),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+        text("Updates the Node.js interpreter in WebStorm, IntelliJ IDEA, and other JetBrains IDEs when it points at a managed version")
+            .size(11)
+            .color(muted),
+        Space::new().height(8),
+        row![
+            button(text("Preview Update").size(11))
+                .on_press_maybe(
+                    (!settings_state.checking_jetbrains).then_some(Message::PreviewJetBrainsUpdate)
+                )
+                .style(styles::secondary_button)
+                .padding([4, 10]),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+    ]
+    .spacing(4);
+
+    match &settings_state.jetbrains_plan {
+        Some(Ok(changes)) if changes.is_empty() => {
+            section = section.push(
+                text("No JetBrains interpreter settings need updating")
+                    .size(11)
+                    .color(muted),
+            );
+        }
+        Some(Ok(changes)) => {
+            for change in changes {
+                section = section.push(
+                    text(format!("{}: {} → {}", change.ide, change.from, change.to))
+                        .size(11)
+                        .color(muted),
+                );
+            }
+            section = section.push(
+                row![
+                    button(text("Apply").size(11))
+                        .on_press(Message::ApplyJetBrainsUpdate)
+                        .style(styles::secondary_button)
+                        .padding([4, 10]),
+                    text("Close the IDEs first; they rewrite their settings on exit")
+                        .size(11)
+                        .color(muted),
+                ]
+                .spacing(8)
+                .align_y(Alignment::Center),
+            );
+        }
+        Some(Err(e)) => {
+            section = section.push(status::label(
+                Status::Warning,
+                format!("Failed: {}", e),
+                11.0,
+            ));
+        }
+        None => {}
+    }
+
+    section.into()
+}
+
+fn backend_removal_section<'a>(
+    settings_state: &'a SettingsModalState,
+    state: &'a MainState,
+    read_only: bool,
+) -> Element<'a, Message> {
+    let muted = iced::Color::from_rgb8(142, 142, 147);
+    let backend = state.backend_name;
+    let mut section = column![
+        text(format!("Uninstall {}", backend)).size(14),
+        Space::new().height(8)
+    ]
+    .spacing(4);
+
+    let cancel = || {
+        button(text("Cancel").size(11))
+            .on_press(Message::CancelBackendRemoval)
+            .style(styles::secondary_button)
+            .padding([4, 10])
+    };
+
+    match &settings_state.backend_removal {
+        None if state.active_environment().id != EnvironmentId::Native => {
+            section = section.push(
+                text("Switch to the native environment to uninstall its engine")
+                    .size(11)
+                    .color(muted),
+            );
+        }
+        None => {
+            section = section.push(
+                button(text(format!("Uninstall {}...", backend)).size(11))
+                    .on_press(Message::PlanBackendRemoval)
+                    .style(styles::secondary_button)
+                    .padding([4, 10]),
+            );
+            section = section.push(
+                text("Removes every Node version, shell integration, and data managed by this engine, for consolidating on another one")
+                    .size(11)
+                    .color(muted),
+            );
+        }
+        Some(BackendRemoval::Planning) => {
+            section = section.push(text("Looking for files to remove...").size(12));
+        }
+        Some(BackendRemoval::Review(plan)) => {
+            section = section.push(
+                text(format!(
+                    "{} Node version(s) will be uninstalled",
+                    plan.versions.len()
+                ))
+                .size(12),
+            );
+            if plan.shell_configs.is_empty() {
+                section = section.push(
+                    text("No shell integration lines found")
+                        .size(12)
+                        .color(muted),
+                );
+            }
+            for (_, path) in &plan.shell_configs {
+                section = section.push(
+                    text(format!("Remove integration lines from {}", path.display())).size(12),
+                );
+            }
+            for (path, issue) in &plan.shell_config_issues {
+                section = section.push(status::label(
+                    Status::Warning,
+                    format!("Leaves {} unchanged: {}", path.display(), issue),
+                    12.0,
+                ));
+            }
+            match (&plan.data_dir, &plan.kept_dir) {
+                (Some(dir), _) => {
+                    section = section.push(
+                        text(format!(
+                            "Delete {} ({})",
+                            dir.display(),
+                            format_bytes(plan.data_size)
+                        ))
+                        .size(12),
+                    );
+                }
+                (None, Some(dir)) => {
+                    section = section.push(status::label(
+                        Status::Warning,
+                        format!(
+                            "Leaves {} in place: it does not look like a {} directory; delete it by hand if it only holds {}",
+                            dir.display(),
+                            backend,
+                            backend
+                        ),
+                        12.0,
+                    ));
+                }
+                (None, None) => {
+                    section = section.push(
+                        text("Data directory not found; it will be left alone")
+                            .size(12)
+                            .color(muted),
+                    );
+                }
+            }
+            for entry in &plan.path_entries {
+                section = section.push(text(format!("Remove {} from PATH", entry)).size(12));
+            }
+            for name in &plan.env_vars {
+                section =
+                    section.push(text(format!("Remove environment variable {}", name)).size(12));
+            }
+            section = section.push(Space::new().height(4));
+            section = section.push(
+                row![
+                    cancel(),
+                    button(text("Continue").size(11))
+                        .on_press(Message::ContinueBackendRemoval)
+                        .style(styles::secondary_button)
+                        .padding([4, 10]),
+                ]
+                .spacing(8),
+            );
+        }
+        Some(BackendRemoval::Confirm(plan)) => {
+            let size = plan
+                .data_dir
+                .as_ref()
+                .map(|_| format!(" and {} of data", format_bytes(plan.data_size)))
+                .unwrap_or_default();
+            section = section.push(status::label(
+                Status::Error,
+                format!(
+                    "This permanently removes {} with {} Node version(s){}. This cannot be undone.",
+                    backend,
+                    plan.versions.len(),
+                    size
+                ),
+                12.0,
+            ));
+            section = section.push(Space::new().height(4));
+            section = section.push(
+                row![
+                    cancel(),
+                    button(text(format!("Uninstall {}", backend)).size(11))
+                        .on_press_maybe((!read_only).then_some(Message::ConfirmBackendRemoval))
+                        .style(styles::danger_button)
+                        .padding([4, 10]),
+                ]
+                .spacing(8),
+            );
+        }
+        Some(BackendRemoval::Running) => {
+            section = section.push(text(format!("Uninstalling {}...", backend)).size(12));
+        }
+        Some(BackendRemoval::Done(steps)) => {
+            for step in steps {
+                let line = match &step.error {
+                    None => status::label(Status::Success, step.label.as_str(), 11.0),
+                    Some(error) => {
+                        status::label(Status::Warning, format!("{}: {}", step.label, error), 11.0)
+                    }
+                };
+                section = section.push(line);
+            }
+            section = section.push(
+                text("Restart Versi and pick another engine under Preferred Engine")
+                    .size(11)
+                    .color(muted),
+            );
+            section = section.push(
+                button(text("Done").size(11))
+                    .on_press(Message::CancelBackendRemoval)
+                    .style(styles::secondary_button)
+                    .padding([4, 10]),
+            );
+        }
+    }
+
+    section.into()
+}
+
+fn npm_section(settings_state: &SettingsModalState, read_only: bool) -> Element<'_, Message> {
+    let mut section = column![
+        text("npm Configuration").size(14),
+        Space::new().height(8),
+        row![
+            button(text("Check npm").size(11))
+                .on_press_maybe((!settings_state.checking_npm).then_some(Message::CheckNpmConfig))
+                .style(styles::secondary_button)
+                .padding([4, 10]),
+            text(if settings_state.checking_npm {
+                "Checking installed versions..."
+            } else {
+                "Runs npm config get registry and npm whoami with each installed version"
+            })
+            .size(11)
+            .color(iced::Color::from_rgb8(142, 142, 147)),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+    ]
+    .spacing(4);
+
+    for check in &settings_state.npm_checks {
+        let (label, status) = if let Some(error) = &check.error {
+            (
+                error.lines().next().unwrap_or_default().to_string(),
+                Status::Error,
+            )
+        } else if !check.issues.is_empty() {
+            let issues: Vec<String> = check.issues.iter().map(|i| i.label()).collect();
+            (issues.join(", "), Status::Warning)
+        } else {
+            let registry = check.registry.clone().unwrap_or_default();
+            match &check.user {
+                Some(user) => (format!("{} as {}", registry, user), Status::Success),
+                None => (registry, Status::Success),
+            }
+        };
+
+        let mut check_row = row![
+            text(&check.version).size(13).width(Length::Fixed(100.0)),
+            status::label(status, label, 12.0),
+            Space::new().width(Length::Fill),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center);
+
+        if let Some(from) = &check.fix_from {
+            check_row = check_row.push(
+                button(text(format!("Copy .npmrc from {}", from)).size(11))
+                    .on_press_maybe((!read_only).then(|| Message::CopyNpmConfig {
+                        from: from.clone(),
+                        to: check.version.clone(),
+                    }))
+                    .style(styles::secondary_button)
+                    .padding([4, 10]),
+            );
+        }
+        section = section.push(check_row);
+    }
+
+    section.into()
+}
+
+fn backend_paths_section(settings: &AppSettings) -> Element<'_, Message> {
+    let mut section = column![text("Backend Location").size(14), Space::new().height(8)].spacing(4);
+
+    section = section.push(path_override_row(
+        "fnm binary: ",
+        settings.fnm_binary.as_deref(),
+        "Auto-detected",
+        Message::PickFnmBinary,
+        Message::ClearFnmBinary,
+    ));
+    // nvm-windows is located through its executable, not NVM_DIR.
+    if !cfg!(windows) {
+        section = section.push(path_override_row(
+            "NVM_DIR: ",
+            settings.nvm_dir.as_deref(),
+            "Auto-detected",
+            Message::PickNvmDir,
+            Message::ClearNvmDir,
+        ));
+    }
+
+    section
+        .push(
+            text("Only needed when a backend is installed somewhere Versi does not look, such as a custom prefix")
+                .size(11)
+                .color(iced::Color::from_rgb8(142, 142, 147)),
+        )
+        .into()
+}
+
+/// Which releases of the active backend the update banner offers.
+fn backend_updates_section<'a>(
+    settings: &'a AppSettings,
+    state: &'a MainState,
+) -> Element<'a, Message> {
+    let backend = state.backend_name;
+    let channel = settings.update_channel_for(backend);
+    let current = state.active_environment().backend_version.clone();
+    let option = |label: &'static str, selected: bool, target: Option<BackendUpdateChannel>| {
+        button(text(label).size(11))
+            .on_press_maybe(target.map(Message::BackendUpdateChannelChanged))
+            .style(if selected {
+                styles::primary_button
+            } else {
+                styles::secondary_button
+            })
+            .padding([4, 10])
+    };
+
+    let hint = match &channel {
+        BackendUpdateChannel::Stable => format!("Offering new stable {} releases", backend),
+        BackendUpdateChannel::Prerelease => {
+            format!(
+                "Offering every new {} release, prereleases included",
+                backend
+            )
+        }
+        BackendUpdateChannel::Pinned(version) => format!(
+            "Pinned to {} {}; newer releases are not offered",
+            backend,
+            version.trim_start_matches('v')
+        ),
+    };
+
+    column![
+        text(format!("{} Updates", backend)).size(14),
+        Space::new().height(8),
+        row![
+            option(
+                "Stable",
+                channel == BackendUpdateChannel::Stable,
+                Some(BackendUpdateChannel::Stable)
+            ),
+            option(
+                "Prereleases",
+                channel == BackendUpdateChannel::Prerelease,
+                Some(BackendUpdateChannel::Prerelease)
+            ),
+            option(
+                "Pin Current Version",
+                matches!(channel, BackendUpdateChannel::Pinned(_)),
+                current.map(BackendUpdateChannel::Pinned)
+            ),
+        ]
+        .spacing(8),
+        text(hint)
+            .size(11)
+            .color(iced::Color::from_rgb8(142, 142, 147)),
+    ]
+    .spacing(4)
+    .into()
+}
+
+/// Times the scheduled theme switches at, in half-hour steps.
+fn theme_schedule_row(settings: &AppSettings) -> Element<'_, Message> {
+    if settings.theme != ThemeSetting::Scheduled {
+        return Space::new().into();
+    }
+    let schedule = settings.theme_schedule;
+    let times: Vec<String> = (0..48)
+        .map(|slot| format!("{:02}:{:02}", slot / 2, slot % 2 * 30))
+        .collect();
+    let parse = |time: String| NaiveTime::parse_from_str(&time, "%H:%M").ok();
+
+    column![
+        Space::new().height(8),
+        row![
+            text("Light from").size(12),
+            pick_list(
+                times.clone(),
+                Some(schedule.light_from.format("%H:%M").to_string()),
+                move |time| match parse(time) {
+                    Some(light_from) => Message::ThemeScheduleChanged(ThemeSchedule {
+                        light_from,
+                        ..schedule
+                    }),
+                    None => Message::NoOp,
+                },
+            )
+            .text_size(12),
+            text("Dark from").size(12),
+            pick_list(
+                times,
+                Some(schedule.dark_from.format("%H:%M").to_string()),
+                move |time| match parse(time) {
+                    Some(dark_from) => Message::ThemeScheduleChanged(ThemeSchedule {
+                        dark_from,
+                        ..schedule
+                    }),
+                    None => Message::NoOp,
+                },
+            )
+            .text_size(12),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+    ]
+    .into()
+}
+
+fn system_wide_section<'a>(
+    settings: &'a AppSettings,
+    state: &'a MainState,
+) -> Element<'a, Message> {
+    let muted = iced::Color::from_rgb8(142, 142, 147);
+    let mut section = column![
+        text("System-wide Install").size(14),
+        Space::new().height(8),
+        row![
+            toggler(settings.system_wide)
+                .on_toggle(Message::SystemWideToggled)
+                .size(18),
+            text("Install versions for every user of this machine").size(12),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+    ]
+    .spacing(4);
+
+    let dir = state
+        .backend
+        .system_wide_dir()
+        .map(Path::to_path_buf)
+        .or_else(|| settings.system_wide_fnm_dir());
+    if let Some(dir) = dir.filter(|_| settings.system_wide) {
+        let (status, label) = if !state.backend.capabilities().supports_system_wide {
+            (
+                Status::Warning,
+                format!(
+                    "{} does not support system-wide installs",
+                    state.backend_name
+                ),
+            )
+        } else if !state.backend.needs_elevation() {
+            (Status::Success, format!("{} is writable", dir.display()))
+        } else if let Some(elevation) = versi_platform::Elevation::available() {
+            (
+                Status::Neutral,
+                format!(
+                    "{} is owned by an administrator; changes ask for your password through {}",
+                    dir.display(),
+                    elevation.label()
+                ),
+            )
+        } else {
+            (
+                Status::Warning,
+                format!(
+                    "{} is not writable; start Versi as an administrator to change versions",
+                    dir.display()
+                ),
+            )
+        };
+        section = section.push(status::label(status, label, 11.0));
+    }
+
+    section
+        .push(
+            text("Versions go in a shared directory instead of your own, and shell setup points at it. Versions installed before are left where they are")
+                .size(11)
+                .color(muted),
+        )
+        .into()
+}
+
+fn follow_project_section<'a>(
+    settings: &'a AppSettings,
+    state: &'a MainState,
+) -> Element<'a, Message> {
+    let mut section = column![
+        text("Follow Project").size(14),
+        Space::new().height(8),
+        path_override_row(
+            "Directory: ",
+            settings.followed_directory.as_deref(),
+            "Not following",
+            Message::PickFollowedDirectory,
+            Message::ClearFollowedDirectory,
+        ),
+    ]
+    .spacing(4);
+
+    if settings.followed_directory.is_some() {
+        section = section.push(match &state.project_pin {
+            Some(pin) => status::label(
+                Status::Success,
+                format!(
+                    "{} in {} asks for Node {}",
+                    pin.file,
+                    pin.root.display(),
+                    pin.spec
+                ),
+                11.0,
+            ),
+            None => status::label(
+                Status::Warning,
+                "No .nvmrc or .node-version found in the directory or its parents",
+                11.0,
+            ),
+        });
+    }
+
+    section
+        .push(
+            text("The version the project asks for is highlighted and offered as the default. Shell hooks can switch projects with the control socket's follow-directory method")
+                .size(11)
+                .color(iced::Color::from_rgb8(142, 142, 147)),
+        )
+        .into()
+}
+
+fn project_trust_section(settings: &AppSettings) -> Element<'_, Message> {
+    let mut section = column![text("Project Trust").size(14), Space::new().height(8)].spacing(4);
+
+    for (project, trusted) in &settings.project_trust {
+        let (status, label) = if *trusted {
+            (Status::Success, "Trusted")
+        } else {
+            (Status::Warning, "Not trusted")
+        };
+        section = section.push(
+            row![
+                text(project.display().to_string())
+                    .size(12)
+                    .width(Length::Fill),
+                status::label(status, label, 12.0),
+                button(text("Forget").size(11))
+                    .on_press(Message::ForgetProjectTrust(project.clone()))
+                    .style(styles::ghost_button)
+                    .padding([4, 10]),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center),
+        );
+    }
+
+    section
+        .push(
+            text("Projects are asked about once before Versi runs commands they define, such as a test matrix command")
+                .size(11)
+                .color(iced::Color::from_rgb8(142, 142, 147)),
+        )
+        .into()
+}
+
+fn storage_section(state: &MainState) -> Element<'_, Message> {
+    let env = state.active_environment();
+    let measured = match &env.npm_cache {
+        _ if env.npm_cache_busy => text("Working...")
+            .size(12)
+            .color(iced::Color::from_rgb8(142, 142, 147))
+            .into(),
+        None => text("Not measured yet")
+            .size(12)
+            .color(iced::Color::from_rgb8(142, 142, 147))
+            .into(),
+        Some(Ok(cache)) => status::label(
+            Status::Success,
+            format!("{} in {}", format_bytes(cache.size), cache.path),
+            12.0,
+        ),
+        Some(Err(e)) => status::label(
+            Status::Error,
+            e.lines().next().unwrap_or_default().to_string(),
+            12.0,
+        ),
+    };
+    let can_clear =
+        !env.npm_cache_busy && matches!(&env.npm_cache, Some(Ok(cache)) if cache.size > 0);
+
+    column![
+        text("Storage").size(14),
+        Space::new().height(8),
+        row![
+            text("npm cache: ").size(13),
+            measured,
+            Space::new().width(Length::Fill),
+            button(text("Measure").size(11))
+                .on_press_maybe((!env.npm_cache_busy).then_some(Message::CheckNpmCache))
+                .style(styles::secondary_button)
+                .padding([4, 10]),
+            button(text("Clear npm Cache").size(11))
+                .on_press_maybe(can_clear.then_some(Message::ClearNpmCache))
+                .style(styles::secondary_button)
+                .padding([4, 10]),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+        text(format!(
+            "Shared by every Node version in {}. Removing versions leaves their packages here",
+            env.name
+        ))
+        .size(11)
+        .color(iced::Color::from_rgb8(142, 142, 147)),
+    ]
+    .spacing(4)
+    .into()
+}
+
+fn data_dirs_section(settings: &AppSettings) -> Element<'_, Message> {
+    let mut section = column![text("Data Directories").size(14), Space::new().height(8)].spacing(4);
+
+    for (label, dir) in [("Cache: ", DataDir::Cache), ("Logs: ", DataDir::Logs)] {
+        let path = settings.data_dir(dir);
+        section = section.push(path_override_row(
+            label,
+            path.map(PathBuf::as_path),
+            "Default",
+            Message::PickDataDir(dir),
+            Message::ResetDataDir(dir),
+        ));
+        if let Some(path) = path.filter(|path| !path.is_dir()) {
+            section = section.push(status::label(
+                Status::Warning,
+                format!(
+                    "{} is unavailable, using the default location",
+                    path.display()
+                ),
+                11.0,
+            ));
+        }
+    }
+
+    section
+        .push(
+            text("Existing files are moved to the new location")
+                .size(11)
+                .color(iced::Color::from_rgb8(142, 142, 147)),
+        )
+        .into()
+}
+
+fn sync_section(settings: &AppSettings) -> Element<'_, Message> {
+    column![
+        text("Sync Between Machines").size(14),
+        Space::new().height(8),
+        path_override_row(
+            "File: ",
+            settings.sync_file.as_deref(),
+            "Not syncing",
+            Message::PickSyncFile,
+            Message::ClearSyncFile,
+        ),
+        text("Choose a file in a Dropbox or OneDrive folder to share preferences, saved filters, protected versions and notes. Paths, environment variables and window layout stay on each machine")
+            .size(11)
+            .color(iced::Color::from_rgb8(142, 142, 147)),
+    ]
+    .spacing(4)
+    .into()
+}
+
+fn path_override_row<'a>(
+    label: &'a str,
+    path: Option<&'a std::path::Path>,
+    unset_label: &'a str,
+    pick: Message,
+    clear: Message,
+) -> Element<'a, Message> {
+    let path_label = path
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|| unset_label.to_string());
+
+    let mut path_row = row![
+        text(label)
+            .size(11)
+            .color(iced::Color::from_rgb8(142, 142, 147)),
+        text(path_label).size(11),
+        Space::new().width(Length::Fill),
+        button(text("Choose...").size(11))
+            .on_press(pick)
+            .style(styles::secondary_button)
+            .padding([4, 10]),
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center);
+
+    if path.is_some() {
+        path_row = path_row.push(
+            button(text("Clear").size(11))
+                .on_press(clear)
+                .style(styles::secondary_button)
+                .padding([4, 10]),
+        );
+    }
+
+    path_row.into()
+}
+
+fn network_section<'a>(
+    settings_state: &'a SettingsModalState,
+    settings: &'a AppSettings,
+) -> Element<'a, Message> {
+    let ca_label = settings
+        .ca_bundle_path
+        .as_ref()
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|| "System default".to_string());
+
+    let mut ca_row = row![
+        text("CA certificate: ")
+            .size(11)
+            .color(iced::Color::from_rgb8(142, 142, 147)),
+        text(ca_label).size(11),
+        Space::new().width(Length::Fill),
+        button(text("Choose...").size(11))
+            .on_press(Message::PickCaBundle)
+            .style(styles::secondary_button)
+            .padding([4, 10]),
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center);
+
+    if settings.ca_bundle_path.is_some() {
+        ca_row = ca_row.push(
+            button(text("Clear").size(11))
+                .on_press(Message::ClearCaBundle)
+                .style(styles::secondary_button)
+                .padding([4, 10]),
+        );
+    }
+
+    let test_status: Element<'a, Message> = if settings_state.testing_connection {
+        text("Testing...").size(11).into()
+    } else {
+        match &settings_state.connection_test {
+            Some(Ok(())) => status::label(Status::Success, "Connected", 11.0),
+            Some(Err(e)) => status::label(Status::Warning, format!("Failed: {}", e), 11.0),
+            None => Space::new().into(),
+        }
+    };
+
+    column![
+        text("Network").size(14),
+        Space::new().height(8),
+        proxy_rows(settings_state, settings),
+        Space::new().height(8),
+        ca_row,
+        text("Trusted alongside the system certificates for update checks, and exported to Node as NODE_EXTRA_CA_CERTS")
+            .size(11)
+            .color(iced::Color::from_rgb8(142, 142, 147)),
+        Space::new().height(8),
+        row![
+            button(text("Test Connection").size(11))
+                .on_press_maybe(
+                    (!settings_state.testing_connection).then_some(Message::TestConnection)
+                )
+                .style(styles::secondary_button)
+                .padding([4, 10]),
+            test_status,
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+        Space::new().height(8),
+        row![
+            text("GitHub token").size(12).width(Length::Fixed(100.0)),
+            text_input("Optional", settings.github_token.as_deref().unwrap_or_default())
+                .on_input(Message::GitHubTokenChanged)
+                .secure(true)
+                .size(12)
+                .padding([4, 8]),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+        text("Raises GitHub's rate limit for update checks. Not included in settings exports")
+            .size(11)
+            .color(iced::Color::from_rgb8(142, 142, 147)),
+        Space::new().height(8),
+        retry_policy_rows(&settings.retry_policy),
+    ]
+    .spacing(4)
+    .into()
+}
+
+fn proxy_rows<'a>(
+    settings_state: &'a SettingsModalState,
+    settings: &'a AppSettings,
+) -> Element<'a, Message> {
+    let muted = iced::Color::from_rgb8(142, 142, 147);
+    let option = |label: &'static str, mode: ProxyMode| {
+        button(text(label).size(11))
+            .on_press(Message::ProxyModeChanged(mode))
+            .style(if settings.proxy_mode == mode {
+                styles::primary_button
+            } else {
+                styles::secondary_button
+            })
+            .padding([4, 10])
+    };
+
+    let status: Element<'a, Message> = match &settings_state.proxy_status {
+        None => text("Detecting...").size(11).color(muted).into(),
+        Some(Ok(summary)) => text(summary).size(11).color(muted).into(),
+        Some(Err(e)) => status::label(Status::Warning, e, 11.0),
+    };
+
+    let mut rows = column![
+        row![
+            text("Proxy").size(12).width(Length::Fixed(100.0)),
+            option("System", ProxyMode::System),
+            option("PAC file", ProxyMode::Pac),
+            option("Direct", ProxyMode::Direct),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+    ]
+    .spacing(4);
+
+    if settings.proxy_mode == ProxyMode::Pac {
+        rows = rows.push(
+            row![
+                text("PAC URL").size(12).width(Length::Fixed(100.0)),
+                text_input(
+                    "The system's PAC file",
+                    settings.pac_url.as_deref().unwrap_or_default()
+                )
+                .on_input(Message::PacUrlChanged)
+                .on_submit(Message::DetectProxy)
+                .size(12)
+                .padding([4, 8]),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center),
+        );
+    }
+
+    rows.push(
+        row![
+            status,
+            Space::new().width(Length::Fill),
+            button(text("Detect Again").size(11))
+                .on_press_maybe(
+                    settings_state
+                        .proxy_status
+                        .is_some()
+                        .then_some(Message::DetectProxy)
+                )
+                .style(styles::secondary_button)
+                .padding([4, 10]),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+    )
+    .push(
+        text("Applied to update checks and to the backends' downloads")
+            .size(11)
+            .color(muted),
+    )
+    .into()
+}
+
+fn retry_policy_rows(policy: &RetryPolicy) -> Element<'_, Message> {
+    let choice = |label: String, selected: bool, message: Message| {
+        button(text(label).size(11))
+            .on_press(message)
+            .style(if selected {
+                styles::primary_button
+            } else {
+                styles::secondary_button
+            })
+            .padding([4, 10])
+    };
+    let label = |name: &'static str| text(name).size(12).width(Length::Fixed(100.0));
+
+    let mut retries = row![label("Retries")].spacing(8).align_y(Alignment::Center);
+    for count in [0, 1, 3, 5] {
+        retries = retries.push(choice(
+            count.to_string(),
+            policy.retries == count,
+            Message::RetryCountChanged(count),
+        ));
+    }
+
+    let mut backoff = row![label("Backoff")].spacing(8).align_y(Alignment::Center);
+    for strategy in BackoffStrategy::ALL {
+        backoff = backoff.push(choice(
+            strategy.label().to_string(),
+            policy.backoff == strategy,
+            Message::RetryBackoffChanged(strategy),
+        ));
+    }
+
+    let mut timeout = row![label("Timeout")].spacing(8).align_y(Alignment::Center);
+    for secs in [10, 30, 60, 120] {
+        timeout = timeout.push(choice(
+            format!("{}s", secs),
+            policy.timeout_secs == secs,
+            Message::RetryTimeoutChanged(secs),
+        ));
+    }
+
+    column![
+        retries,
+        backoff,
+        timeout,
+        text("Applies to version lists, the release schedule, and update checks")
+            .size(11)
+            .color(iced::Color::from_rgb8(142, 142, 147)),
+    ]
+    .spacing(4)
+    .into()
+}
+
+fn scheduled_operations_section<'a>(
+    settings_state: &'a SettingsModalState,
+    settings: &'a AppSettings,
+    state: &'a MainState,
+) -> Element<'a, Message> {
+    let draft = &settings_state.schedule_draft;
+    let mut tasks = row![].spacing(8);
+    for task in ScheduledTask::ALL {
+        tasks = tasks.push(
+            button(text(task.label()).size(13))
+                .on_press(Message::ScheduleDraftTaskChanged(task))
+                .style(if draft.task == task {
+                    styles::primary_button
+                } else {
+                    styles::secondary_button
+                })
+                .padding([10, 16]),
+        );
+    }
+
+    let mut when = row![
+        toggler(draft.when_idle)
+            .on_toggle(Message::ScheduleDraftWhenIdleToggled)
+            .size(18),
+        text("When idle on AC power").size(12),
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center);
+    if !draft.when_idle {
+        when = when.push(text("or at").size(12)).push(
+            text_input("HH:MM", &draft.time)
+                .on_input(Message::ScheduleDraftTimeChanged)
+                .on_submit_maybe((!settings.is_read_only()).then_some(Message::AddScheduledJob))
+                .size(12)
+                .padding([6, 10])
+                .style(styles::search_input)
+                .width(Length::Fixed(80.0)),
+        );
+    }
+
+    let mut section = column![
+        text("Scheduled Operations").size(14),
+        Space::new().height(8),
+        tasks.wrap(),
+        Space::new().height(4),
+        when,
+        Space::new().height(4),
+        button(text("Schedule").size(11))
+            .on_press_maybe((!settings.is_read_only()).then_some(Message::AddScheduledJob))
+            .style(styles::secondary_button)
+            .padding([4, 10]),
+    ]
+    .spacing(4);
+
+    for job in &settings.scheduled_jobs {
+        let environment = state
+            .environments
+            .iter()
+            .find(|env| env.id.settings_key() == job.environment)
+            .map_or(job.environment.as_str(), |env| env.name.as_str());
+        section = section.push(
+            row![
+                text(format!(
+                    "{} in {} {}",
+                    job.task.label(),
+                    environment,
+                    job.trigger.describe()
+                ))
+                .size(12)
+                .width(Length::Fill),
+                button(icon::close(12.0))
+                    .on_press(Message::RemoveScheduledJob(job.id))
+                    .style(styles::ghost_button)
+                    .padding([4, 6]),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center),
+        );
+    }
+
+    section = section.push(
+        text(
+            "Jobs run once, switching to their environment's tab when due, and \
+             report the results in a notification. Removals never touch the default version",
+        )
+        .size(11)
+        .color(iced::Color::from_rgb8(142, 142, 147)),
+    );
+    if settings.tray_behavior != TrayBehavior::AlwaysRunning {
+        section = section.push(
+            text("Set the system tray to Always so jobs still run after closing the window")
+                .size(11)
+                .color(iced::Color::from_rgb8(142, 142, 147)),
+        );
+    }
+
+    section.into()
+}
+
+fn support_warnings_section(settings: &AppSettings) -> Element<'_, Message> {
+    let mut grace_periods = row![].spacing(8);
+    for days in GRACE_PERIODS {
+        grace_periods = grace_periods.push(
+            button(
+                text(match days {
+                    0 => "None".to_string(),
+                    days => format!("{} days", days),
+                })
+                .size(13),
+            )
+            .on_press(Message::EolGraceDaysChanged(days))
+            .style(if settings.eol_grace_days == days {
+                styles::primary_button
+            } else {
+                styles::secondary_button
+            })
+            .padding([10, 16]),
+        );
+    }
+
+    let mut countdowns = row![].spacing(8);
+    for days in COUNTDOWN_THRESHOLDS {
+        countdowns = countdowns.push(
+            button(
+                text(match days {
+                    30 => "1 month".to_string(),
+                    365 => "1 year".to_string(),
+                    days => format!("{} months", days / 30),
+                })
+                .size(13),
+            )
+            .on_press(Message::EolCountdownDaysChanged(days))
+            .style(if settings.eol_countdown_days == days {
+                styles::primary_button
+            } else {
+                styles::secondary_button
+            })
+            .padding([10, 16]),
+        );
+    }
+
+    let mut section = column![
+        text("End-of-Life Warnings").size(14),
+        Space::new().height(8),
+        row![
+            toggler(settings.eol_strict)
+                .on_toggle(Message::EolStrictToggled)
+                .size(18),
+            text("Also warn when a major enters maintenance").size(12),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+        Space::new().height(8),
+        text("Grace period").size(12),
+        grace_periods,
+        text("Time after a major changes phase before it is flagged")
+            .size(11)
+            .color(iced::Color::from_rgb8(142, 142, 147)),
+        Space::new().height(8),
+        text("Countdown").size(12),
+        countdowns,
+        text("How long before end-of-life the version badges start counting down")
+            .size(11)
+            .color(iced::Color::from_rgb8(142, 142, 147)),
+    ]
+    .spacing(4);
+
+    for (major, acknowledgement) in &settings.eol_acknowledgements {
+        let status = match acknowledgement.snoozed_until {
+            Some(until) => format!("Snoozed until {}", until.format("%Y-%m-%d")),
+            None => "Dismissed".to_string(),
+        };
+        section = section.push(
+            row![
+                text(format!("Node {}", major))
+                    .size(13)
+                    .width(Length::Fixed(100.0)),
+                text(status).size(12).width(Length::Fill),
+                button(text("Restore").size(11))
+                    .on_press(Message::RestoreSupportWarning(*major))
+                    .style(styles::secondary_button)
+                    .padding([4, 10]),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center),
+        );
+    }
+
+    section.into()
+}
+
+fn major_notes_section(settings: &AppSettings) -> Element<'_, Message> {
+    let mut section = column![text("Version Notes").size(14), Space::new().height(8)].spacing(4);
+
+    if settings.major_notes.is_empty() {
+        return section
+            .push(
+                text("Add notes to a major version from its group header")
+                    .size(11)
+                    .color(iced::Color::from_rgb8(142, 142, 147)),
+            )
+            .into();
+    }
+
+    for (major, note) in &settings.major_notes {
+        section = section.push(
+            row![
+                text(format!("Node {}", major))
+                    .size(13)
+                    .width(Length::Fixed(100.0)),
+                text(note).size(12).width(Length::Fill),
+                button(icon::close(12.0))
+                    .on_press(Message::RemoveMajorNote(*major))
+                    .style(styles::ghost_button)
+                    .padding([4, 6]),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center),
+        );
+    }
+
+    section.into()
+}
+
+fn env_vars_section<'a>(
+    settings: &'a AppSettings,
+    settings_state: &'a SettingsModalState,
+    state: &'a MainState,
+) -> Element<'a, Message> {
+    let env = state.active_environment();
+    let draft = settings_state
+        .env_var_draft
+        .as_ref()
+        .filter(|draft| draft.env_id == env.id);
+    let vars = match draft {
+        Some(draft) => draft.vars.as_slice(),
+        None => settings
+            .environment_variables
+            .get(&env.id.settings_key())
+            .map(Vec::as_slice)
+            .unwrap_or_default(),
+    };
+
+    let mut section = column![
+        text("Environment Variables").size(14),
+        Space::new().height(8),
+        text(format!(
+            "Passed to every {} command in {}",
+            state.backend_name, env.name
+        ))
+        .size(11)
+        .color(iced::Color::from_rgb8(142, 142, 147)),
+    ]
+    .spacing(4);
+
+    for (index, var) in vars.iter().enumerate() {
+        section = section.push(
+            row![
+                text_input("NAME", &var.key)
+                    .on_input(move |key| Message::EnvVarKeyChanged(index, key))
+                    .on_submit(Message::EnvVarsSubmitted)
+                    .size(12)
+                    .padding([6, 10])
+                    .style(styles::search_input)
+                    .width(Length::FillPortion(2)),
+                text_input("value", &var.value)
+                    .on_input(move |value| Message::EnvVarValueChanged(index, value))
+                    .on_submit(Message::EnvVarsSubmitted)
+                    .size(12)
+                    .padding([6, 10])
+                    .style(styles::search_input)
+                    .width(Length::FillPortion(3)),
+                button(icon::close(12.0))
+                    .on_press(Message::EnvVarRemoved(index))
+                    .style(styles::ghost_button)
+                    .padding([4, 6]),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center),
+        );
+        let key = var.key.trim();
+        if !key.is_empty() && !versi_backend::is_valid_env_key(key) {
+            section = section.push(status::label(
+                Status::Error,
+                "Names may only hold letters, digits and underscores, and can't start with a digit",
+                11.0,
+            ));
+        }
+    }
+
+    if let Some(draft) = draft
+        && draft.is_valid()
+    {
+        section = section.push(
+            text("Press Enter to save")
+                .size(11)
+                .color(iced::Color::from_rgb8(142, 142, 147)),
+        );
+    }
+
+    section = section.push(Space::new().height(4));
+    section = section.push(
+        button(text("Add Variable").size(11))
+            .on_press(Message::EnvVarAdded)
+            .style(styles::secondary_button)
+            .padding([4, 10]),
+    );
+
+    section.into()
+}
+
+fn engine_button<'a>(
+    name: &'static str,
+    is_selected: bool,
+    is_detected: bool,
+) -> Element<'a, Message> {
+    let btn = button(text(name).size(13))
+        .style(if is_selected {
+            styles::primary_button
+        } else {
+            styles::secondary_button
+        })
+        .padding([10, 16]);
+
+    if is_detected {
+        btn.on_press(Message::PreferredBackendChanged(name.to_string()))
+            .into()
+    } else {
+        tooltip(
+            btn,
+            container(text(format!("{} is not installed", name)).size(12))
+                .padding([4, 8])
+                .style(styles::tooltip_container),
+            tooltip::Position::Bottom,
+        )
+        .gap(4.0)
+        .into()
+    }
+}
+
+fn engine_selector<'a>(settings: &'a AppSettings, state: &'a MainState) -> Element<'a, Message> {
+    let preferred = settings.preferred_backend.as_deref().unwrap_or("fnm");
+    let fnm_detected = state.detected_backends.contains(&"fnm");
+    let nvm_detected = state.detected_backends.contains(&"nvm");
+
+    row![
+        engine_button("fnm", preferred == "fnm", fnm_detected),
         engine_button("nvm", preferred == "nvm", nvm_detected),
     ]
     .spacing(8)
     .into()
 }
+
+fn density_selector<'a>(settings: &'a AppSettings) -> Element<'a, Message> {
+    let density_option = |density: ListDensity| {
+        button(text(density.label()).size(13))
+            .on_press(Message::ListDensityChanged(density))
+            .style(if settings.list_density == density {
+                styles::primary_button
+            } else {
+                styles::secondary_button
+            })
+            .padding([10, 16])
+    };
+
+    row![
+        density_option(ListDensity::Comfortable),
+        density_option(ListDensity::Compact),
+    ]
+    .spacing(8)
+    .into()
+}
+
+/// How each category of notification is delivered.
+fn notifications_section(settings: &AppSettings) -> Element<'_, Message> {
+    let mut section = column![text("Notifications").size(14), Space::new().height(8)].spacing(4);
+    for category in NotificationCategory::ALL {
+        let current = settings.notification_delivery.get(category);
+        let mut options = row![text(category.label()).size(12).width(Length::Fixed(160.0))]
+            .spacing(8)
+            .align_y(Alignment::Center);
+        for delivery in NotificationDelivery::ALL {
+            options = options.push(
+                button(text(delivery.label()).size(12))
+                    .on_press(Message::NotificationDeliveryChanged(category, delivery))
+                    .style(if current == delivery {
+                        styles::primary_button
+                    } else {
+                        styles::secondary_button
+                    })
+                    .padding([6, 12]),
+            );
+        }
+        section = section.push(options);
+    }
+    section
+        .push(
+            text("Silent ones are only written to the log. Banners are not affected")
+                .size(11)
+                .color(iced::Color::from_rgb8(142, 142, 147)),
+        )
+        .into()
+}
+
+fn startup_section<'a>(settings: &'a AppSettings, state: &'a MainState) -> Element<'a, Message> {
+    let view_option = |view: StartupView| {
+        button(text(view.label()).size(13))
+            .on_press(Message::StartupViewChanged(view))
+            .style(if settings.startup_view == view {
+                styles::primary_button
+            } else {
+                styles::secondary_button
+            })
+            .padding([10, 16])
+    };
+
+    let mut section = column![
+        text("On Startup").size(14),
+        Space::new().height(8),
+        row![
+            view_option(StartupView::LastUsed),
+            view_option(StartupView::List),
+            view_option(StartupView::Overview),
+        ]
+        .spacing(8),
+    ]
+    .spacing(4);
+
+    if state.environments.len() > 1 {
+        let startup_key = settings.startup_environment.as_deref().unwrap_or("native");
+        let mut environments = row![].spacing(8);
+        for env in &state.environments {
+            let key = env.id.settings_key();
+            let selected = key == startup_key;
+            environments = environments.push(
+                button(text(&env.name).size(13))
+                    .on_press(Message::StartupEnvironmentChanged(key))
+                    .style(if selected {
+                        styles::primary_button
+                    } else {
+                        styles::secondary_button
+                    })
+                    .padding([10, 16]),
+            );
+        }
+        section = section
+            .push(Space::new().height(8))
+            .push(text("Environment tab").size(12))
+            .push(environments.wrap())
+            .push(
+                text("WSL environments are selected as soon as they are detected")
+                    .size(11)
+                    .color(iced::Color::from_rgb8(142, 142, 147)),
+            );
+    }
+
+    section.into()
+}
+
+fn renderer_selector(settings: &AppSettings) -> Element<'_, Message> {
+    let option = |renderer: RendererSetting| {
+        button(text(renderer.label()).size(13))
+            .on_press(Message::RendererChanged(renderer))
+            .style(if settings.renderer == renderer {
+                styles::primary_button
+            } else {
+                styles::secondary_button
+            })
+            .padding([10, 16])
+    };
+
+    row![
+        option(RendererSetting::Auto),
+        option(RendererSetting::Gpu),
+        option(RendererSetting::Software),
+    ]
+    .spacing(8)
+    .into()
+}
+
+fn close_behavior_selector(settings: &AppSettings) -> Element<'_, Message> {
+    let current = settings.close_behavior();
+    // With the tray shown only while the window is open, a hidden window
+    // would leave no way back.
+    let tray_available = settings.tray_behavior == TrayBehavior::AlwaysRunning;
+
+    let option = |label: &'static str, behavior: CloseBehavior, enabled: bool| {
+        button(text(label).size(13))
+            .on_press_maybe(enabled.then_some(Message::CloseBehaviorChanged(behavior)))
+            .style(if current == behavior {
+                styles::primary_button
+            } else {
+                styles::secondary_button
+            })
+            .padding([10, 16])
+    };
+
+    row![
+        option(
+            "Minimize to Tray",
+            CloseBehavior::MinimizeToTray,
+            tray_available
+        ),
+        option("Quit", CloseBehavior::Quit, true),
+        option("Ask", CloseBehavior::Ask, true),
+    ]
+    .spacing(8)
+    .into()
+}
@@ -0,0 +1,67 @@
+use iced::widget::{Space, button, column, container, row, scrollable, text};
+use iced::{Alignment, Element, Font, Length};
+
+use crate::event_log::EventLog;
+use crate::message::Message;
+use crate::theme::styles;
+
+const VISIBLE_ENTRIES: usize = 100;
+
+pub fn view<'a>(content: Element<'a, Message>, log: &'a EventLog) -> Element<'a, Message> {
+    let muted = iced::Color::from_rgb8(142, 142, 147);
+
+    let entries = log.recent(VISIBLE_ENTRIES).fold(column![], |col, entry| {
+        col.push(
+            row![
+                text(entry.at.format("%H:%M:%S%.3f").to_string())
+                    .size(10)
+                    .font(Font::MONOSPACE)
+                    .color(muted),
+                text(&entry.summary).size(10).font(Font::MONOSPACE),
+            ]
+            .spacing(8),
+        )
+    });
+
+    let header = row![
+        text(format!("Event Log ({})", log.len())).size(13),
+        Space::new().width(Length::Fill),
+        button(text("Save").size(11))
+            .on_press(Message::SaveEventLog)
+            .style(styles::secondary_button)
+            .padding([4, 10]),
+        button(text("Clear").size(11))
+            .on_press(Message::ClearEventLog)
+            .style(styles::secondary_button)
+            .padding([4, 10]),
+        button(text("Hide").size(11))
+            .on_press(Message::EventLogToggled)
+            .style(styles::ghost_button)
+            .padding([4, 10]),
+    ]
+    .spacing(4)
+    .align_y(Alignment::Center);
+
+    let panel = container(
+        column![
+            header,
+            scrollable(entries.spacing(2)).height(Length::Fixed(240.0)),
+        ]
+        .spacing(8),
+    )
+    .style(styles::modal_container)
+    .padding(12)
+    .width(Length::Fixed(480.0));
+
+    let overlay = container(panel)
+        .padding(16)
+        .align_x(iced::alignment::Horizontal::Left)
+        .align_y(iced::alignment::Vertical::Bottom)
+        .width(Length::Fill)
+        .height(Length::Fill);
+
+    iced::widget::stack![content, overlay]
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+}
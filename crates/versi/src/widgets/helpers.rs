@@ -8,7 +8,7 @@ use crate::theme::styles;
 
 pub fn styled_tooltip<'a>(
     content: impl Into<Element<'a, Message>>,
-    label: &'a str,
+    label: impl text::IntoFragment<'a>,
     position: tooltip::Position,
 ) -> Element<'a, Message> {
     tooltip(
@@ -29,7 +29,7 @@ pub fn nav_icons<'a>(active_view: &MainViewKind, refresh_rotation: f32) -> Eleme
         icon::refresh(16.0)
     };
 
-    let settings_style = if *active_view == MainViewKind::Settings {
+    let settings_style = if matches!(active_view, MainViewKind::Settings | MainViewKind::Logs) {
         styles::ghost_button_active as fn(&iced::Theme, button::Status) -> button::Style
     } else {
         styles::ghost_button
@@ -85,3 +85,19 @@ pub fn nav_icons<'a>(active_view: &MainViewKind, refresh_rotation: f32) -> Eleme
     .align_y(Alignment::Center)
     .into()
 }
+
+pub fn format_bytes(bytes: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = KB * 1024;
+    const GB: u64 = MB * 1024;
+
+    if bytes >= GB {
+        format!("{:.1} GB", bytes as f64 / GB as f64)
+    } else if bytes >= MB {
+        format!("{:.1} MB", bytes as f64 / MB as f64)
+    } else if bytes >= KB {
+        format!("{:.1} KB", bytes as f64 / KB as f64)
+    } else {
+        format!("{} B", bytes)
+    }
+}
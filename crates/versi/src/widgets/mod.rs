@@ -1,3 +1,6 @@
+pub mod event_log_overlay;
 pub mod helpers;
+pub mod status;
+pub mod tips_overlay;
 pub mod toast_container;
 pub mod version_list;
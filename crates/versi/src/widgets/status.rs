@@ -0,0 +1,56 @@
+use iced::widget::{row, svg, text};
+use iced::{Alignment, Color, Element, Theme};
+
+use crate::icon;
+use crate::message::Message;
+
+/// A state shown to the user. Each one pairs its color with an icon, so it
+/// can be told apart without relying on color alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Success,
+    Warning,
+    Error,
+    Neutral,
+}
+
+impl Status {
+    pub fn color(self) -> Color {
+        match self {
+            Self::Success => Color::from_rgb8(52, 199, 89),
+            Self::Warning => Color::from_rgb8(255, 149, 0),
+            Self::Error => Color::from_rgb8(255, 59, 48),
+            Self::Neutral => Color::from_rgb8(142, 142, 147),
+        }
+    }
+
+    pub fn icon(self, size: f32) -> svg::Svg<'static, Theme> {
+        self.icon_on(size, self.color())
+    }
+
+    /// The status icon drawn in `color`, for use on colored backgrounds.
+    pub fn icon_on(self, size: f32, color: Color) -> svg::Svg<'static, Theme> {
+        let icon = match self {
+            Self::Success => icon::check(size),
+            Self::Warning => icon::warning(size),
+            Self::Error => icon::close(size),
+            Self::Neutral => icon::info(size),
+        };
+        icon.style(move |_theme: &Theme, _status| svg::Style { color: Some(color) })
+    }
+}
+
+/// The status icon followed by `content` in the status color.
+pub fn label<'a>(
+    status: Status,
+    content: impl text::IntoFragment<'a>,
+    size: f32,
+) -> Element<'a, Message> {
+    row![
+        status.icon(size),
+        text(content).size(size).color(status.color()),
+    ]
+    .spacing(4)
+    .align_y(Alignment::Center)
+    .into()
+}
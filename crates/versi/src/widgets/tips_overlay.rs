@@ -0,0 +1,86 @@
+use iced::alignment::{Horizontal, Vertical};
+use iced::widget::{Space, button, column, container, row, text};
+use iced::{Alignment, Element, Length, Padding};
+
+use crate::message::Message;
+use crate::theme::styles;
+use crate::tips::{Tip, TipsTour};
+
+/// Where each tip's card sits, next to the feature it describes.
+fn placement(tip: Tip) -> (Horizontal, Vertical, Padding) {
+    match tip {
+        // Under the search bar.
+        Tip::Search => (
+            Horizontal::Center,
+            Vertical::Top,
+            Padding::new(24.0).top(150.0),
+        ),
+        // Beside the version groups and their actions.
+        Tip::BulkCleanup => (
+            Horizontal::Right,
+            Vertical::Top,
+            Padding::new(24.0).top(220.0).right(48.0),
+        ),
+        // Under the environment tabs.
+        Tip::EnvironmentTabs => (
+            Horizontal::Left,
+            Vertical::Top,
+            Padding::new(24.0).top(56.0),
+        ),
+        Tip::KeyboardShortcuts => (Horizontal::Center, Vertical::Center, Padding::new(24.0)),
+    }
+}
+
+pub fn view<'a>(content: Element<'a, Message>, tour: TipsTour) -> Element<'a, Message> {
+    let (Some(tip), Some((step, total))) = (tour.current(), tour.step()) else {
+        return content;
+    };
+    let muted = iced::Color::from_rgb8(142, 142, 147);
+
+    let actions = row![
+        button(text("Skip Tips").size(12))
+            .on_press(Message::DismissTips)
+            .style(styles::ghost_button)
+            .padding([6, 12]),
+        Space::new().width(Length::Fill),
+        button(text("Back").size(12))
+            .on_press_maybe((step > 1).then_some(Message::PreviousTip))
+            .style(styles::secondary_button)
+            .padding([6, 12]),
+        button(text(if tour.is_last() { "Done" } else { "Next" }).size(12))
+            .on_press(Message::NextTip)
+            .style(styles::primary_button)
+            .padding([6, 12]),
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center);
+
+    let card = container(
+        column![
+            text(format!("Tip {} of {}", step, total))
+                .size(11)
+                .color(muted),
+            text(tip.title()).size(15),
+            text(tip.body()).size(12),
+            Space::new().height(4),
+            actions,
+        ]
+        .spacing(6),
+    )
+    .style(styles::modal_container)
+    .padding(16)
+    .width(Length::Fixed(360.0));
+
+    let (horizontal, vertical, padding) = placement(tip);
+    let overlay = container(card)
+        .padding(padding)
+        .align_x(horizontal)
+        .align_y(vertical)
+        .width(Length::Fill)
+        .height(Length::Fill);
+
+    iced::widget::stack![content, overlay]
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+}
@@ -3,7 +3,9 @@ use iced::{Alignment, Element, Length};
 
 use crate::icon;
 use crate::message::Message;
+use crate::settings::NotificationCategory;
 use crate::state::Toast;
+use crate::widgets::status::Status;
 
 pub fn view<'a>(
     content: Element<'a, Message>,
@@ -46,8 +48,43 @@ fn toast_view<'a>(toast: &'a Toast) -> Element<'a, Message> {
         })
         .into();
 
-    let content = row![
-        text(&toast.message).size(14),
+    let status = match toast.category {
+        _ if toast.is_success() => Status::Success,
+        NotificationCategory::Completions => Status::Success,
+        NotificationCategory::Updates => Status::Neutral,
+        NotificationCategory::EolWarnings => Status::Warning,
+        NotificationCategory::OperationFailures => Status::Error,
+    };
+    let mut content = row![
+        status.icon_on(14.0, iced::Color::WHITE),
+        text(&toast.message).size(14)
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center);
+    let details = if toast.summary.is_some() {
+        Some(Message::ShowBatchSummary(toast.id))
+    } else {
+        toast.failure.clone().map(Message::ShowOperationFailure)
+    };
+    if let Some(details) = details {
+        content = content.push(
+            button(text("View details").size(12))
+                .on_press(details)
+                .style(|_theme, _status| iced::widget::button::Style {
+                    background: Some(iced::Background::Color(iced::Color::TRANSPARENT)),
+                    text_color: iced::Color::WHITE,
+                    border: iced::Border {
+                        color: iced::Color::WHITE,
+                        width: 1.0,
+                        radius: 4.0.into(),
+                    },
+                    shadow: iced::Shadow::default(),
+                    snap: false,
+                })
+                .padding([2, 8]),
+        );
+    }
+    content = content.push(
         button(close_icon)
             .on_press(Message::ToastDismiss(toast.id))
             .style(|_theme, _status| iced::widget::button::Style {
@@ -58,13 +95,13 @@ fn toast_view<'a>(toast: &'a Toast) -> Element<'a, Message> {
                 snap: false,
             })
             .padding([0, 4]),
-    ]
-    .spacing(8)
-    .align_y(Alignment::Center);
+    );
+
+    let background = status.color();
 
     container(content)
-        .style(|_theme| container::Style {
-            background: Some(iced::Background::Color(iced::Color::from_rgb8(255, 59, 48))),
+        .style(move |_theme| container::Style {
+            background: Some(iced::Background::Color(background)),
             text_color: Some(iced::Color::WHITE),
             border: iced::Border {
                 radius: 8.0.into(),
@@ -1,30 +1,47 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+
+use chrono::NaiveDate;
 
 use iced::widget::{Space, button, container, mouse_area, row, text};
 use iced::{Alignment, Element, Length};
 
 use versi_backend::RemoteVersion;
-use versi_core::ReleaseSchedule;
+use versi_core::{ReleaseSchedule, format_release_age};
 
 use crate::icon;
 use crate::message::Message;
 use crate::state::OperationQueue;
 use crate::theme::styles;
+use crate::widgets::status::Status;
+
+use super::{Metrics, eol_badge};
 
+#[allow(clippy::too_many_arguments)]
 pub(super) fn available_version_row<'a>(
     version: &'a RemoteVersion,
     schedule: Option<&ReleaseSchedule>,
+    eol_countdown_days: u32,
     operation_queue: &'a OperationQueue,
     installed_set: &HashSet<String>,
     hovered_version: &'a Option<String>,
+    release_dates: &HashMap<String, NaiveDate>,
+    read_only: bool,
+    metrics: Metrics,
 ) -> Element<'a, Message> {
     let version_str = version.version.to_string();
-    let is_eol = schedule
-        .map(|s| !s.is_active(version.version.major))
-        .unwrap_or(false);
+    let today = chrono::Utc::now().date_naive();
+    let countdown =
+        schedule.and_then(|s| s.eol_countdown(version.version.major, today, eol_countdown_days));
+    let eol_without_date = schedule.is_some_and(|s| {
+        s.end_date(version.version.major).is_none() && !s.is_active(version.version.major)
+    });
+    let release_age = release_dates
+        .get(&version_str)
+        .map(|date| format_release_age(*date, today));
     let version_display = version_str.clone();
     let version_for_changelog = version_str.clone();
     let version_for_hover = version_str.clone();
+    let version_for_copy = version_str.clone();
     let is_installed = installed_set.contains(&version_str);
 
     let is_active = operation_queue.is_current_version(&version_str);
@@ -32,25 +49,28 @@ pub(super) fn available_version_row<'a>(
     let is_button_hovered = hovered_version.as_ref().is_some_and(|h| h == &version_str);
 
     let action_button: Element<Message> = if is_active {
-        button(text("Installing...").size(12))
+        let label = operation_queue
+            .transfer_for(&version_str)
+            .map_or_else(|| "Installing...".to_string(), |t| t.status("Installing"));
+        button(text(label).size(12))
             .style(styles::primary_button)
-            .padding([6, 12])
+            .padding(metrics.action_padding)
             .into()
     } else if is_pending {
         button(text("Queued").size(12))
             .style(styles::secondary_button)
-            .padding([6, 12])
+            .padding(metrics.action_padding)
             .into()
     } else if is_installed {
-        let btn = if is_button_hovered {
+        let btn = if is_button_hovered && !read_only {
             button(text("Uninstall").size(12))
                 .on_press(Message::RequestUninstall(version_str))
                 .style(styles::danger_button)
-                .padding([6, 12])
+                .padding(metrics.action_padding)
         } else {
             button(text("Installed").size(12))
                 .style(styles::secondary_button)
-                .padding([6, 12])
+                .padding(metrics.action_padding)
         };
         mouse_area(btn)
             .on_enter(Message::VersionRowHovered(Some(version_for_hover)))
@@ -58,13 +78,13 @@ pub(super) fn available_version_row<'a>(
             .into()
     } else {
         button(text("Install").size(12))
-            .on_press(Message::StartInstall(version_str))
+            .on_press_maybe((!read_only).then_some(Message::StartInstall(version_str)))
             .style(styles::primary_button)
-            .padding([6, 12])
+            .padding(metrics.action_padding)
             .into()
     };
 
-    row![
+    let row = row![
         text(version_display).size(14).width(Length::Fixed(120.0)),
         if let Some(lts) = &version.lts_codename {
             container(text(format!("LTS: {}", lts)).size(11))
@@ -73,14 +93,29 @@ pub(super) fn available_version_row<'a>(
         } else {
             container(Space::new())
         },
-        if is_eol {
-            container(text("End-of-Life").size(11))
-                .padding([2, 6])
-                .style(styles::badge_eol)
+        if let Some(countdown) = countdown {
+            container(eol_badge(countdown, 11))
+        } else if eol_without_date {
+            container(
+                row![Status::Warning.icon(11.0), text("End-of-Life").size(11)]
+                    .spacing(4)
+                    .align_y(Alignment::Center),
+            )
+            .padding([2, 6])
+            .style(styles::badge_eol)
         } else {
             container(Space::new())
         },
         Space::new().width(Length::Fill),
+        if let Some(age) = release_age {
+            container(
+                text(age)
+                    .size(11)
+                    .color(iced::Color::from_rgb8(142, 142, 147)),
+            )
+        } else {
+            container(Space::new())
+        },
         button(
             row![text("Changelog").size(11), icon::arrow_up_right(11.0),]
                 .spacing(2)
@@ -93,6 +128,9 @@ pub(super) fn available_version_row<'a>(
     ]
     .spacing(8)
     .align_y(Alignment::Center)
-    .padding([4, 8])
-    .into()
+    .padding(metrics.row_padding);
+
+    mouse_area(row)
+        .on_middle_press(Message::CopyToClipboard(version_for_copy))
+        .into()
 }
@@ -2,48 +2,70 @@ use std::collections::HashMap;
 
 use versi_backend::RemoteVersion;
 
-pub(super) fn resolve_alias<'a>(
-    versions: &'a [RemoteVersion],
-    query: &str,
-) -> Option<&'a RemoteVersion> {
-    let query_lower = query.to_lowercase();
+use crate::aliases::AliasMatch;
 
-    match query_lower.as_str() {
-        "latest" | "stable" | "current" => versions.iter().max_by_key(|v| &v.version),
-        "lts/*" => versions
-            .iter()
-            .filter(|v| v.lts_codename.is_some())
-            .max_by_key(|v| &v.version),
-        q if q.starts_with("lts/") => {
-            let codename = &q[4..];
-            versions
-                .iter()
-                .filter(|v| {
-                    v.lts_codename
-                        .as_ref()
-                        .is_some_and(|c| c.to_lowercase() == codename)
-                })
-                .max_by_key(|v| &v.version)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum MajorRange {
+    AtLeast(u32),
+    Above(u32),
+    AtMost(u32),
+    Below(u32),
+}
+
+impl MajorRange {
+    pub(super) fn parse(query: &str) -> Option<Self> {
+        let query = query.trim();
+        let (ctor, rest): (fn(u32) -> Self, &str) = if let Some(rest) = query.strip_prefix(">=") {
+            (Self::AtLeast, rest)
+        } else if let Some(rest) = query.strip_prefix("<=") {
+            (Self::AtMost, rest)
+        } else if let Some(rest) = query.strip_prefix('>') {
+            (Self::Above, rest)
+        } else if let Some(rest) = query.strip_prefix('<') {
+            (Self::Below, rest)
+        } else {
+            return None;
+        };
+
+        let rest = rest.trim();
+        let major = rest.strip_prefix('v').unwrap_or(rest).parse().ok()?;
+        Some(ctor(major))
+    }
+
+    pub(super) fn matches(&self, major: u32) -> bool {
+        match *self {
+            Self::AtLeast(m) => major >= m,
+            Self::Above(m) => major > m,
+            Self::AtMost(m) => major <= m,
+            Self::Below(m) => major < m,
         }
-        _ => None,
     }
 }
 
 pub(super) fn filter_available_versions<'a>(
     versions: &'a [RemoteVersion],
+    alias: Option<&AliasMatch<'a>>,
     query: &str,
     limit: usize,
 ) -> Vec<&'a RemoteVersion> {
     let query_lower = query.to_lowercase();
 
-    if let Some(resolved) = resolve_alias(versions, query) {
-        return vec![resolved];
+    // A bare "lts" lists the newest of each LTS line instead.
+    if let Some(alias) = alias
+        && query_lower != "lts"
+    {
+        return vec![alias.version];
     }
 
-    if query_lower == "lts" {
+    let range = MajorRange::parse(&query_lower);
+
+    if query_lower == "lts" || range.is_some() {
         let mut filtered: Vec<&RemoteVersion> = versions
             .iter()
-            .filter(|v| v.lts_codename.is_some())
+            .filter(|v| match range {
+                Some(range) => range.matches(v.version.major),
+                None => v.lts_codename.is_some(),
+            })
             .collect();
         filtered.sort_by(|a, b| b.version.cmp(&a.version));
 
@@ -98,3 +120,33 @@ pub(super) fn filter_available_versions<'a>(
     result.truncate(20);
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn major_range_parses_comparators() {
+        assert_eq!(MajorRange::parse(">=20"), Some(MajorRange::AtLeast(20)));
+        assert_eq!(MajorRange::parse("> 18"), Some(MajorRange::Above(18)));
+        assert_eq!(MajorRange::parse("<=v22"), Some(MajorRange::AtMost(22)));
+        assert_eq!(MajorRange::parse("<16"), Some(MajorRange::Below(16)));
+    }
+
+    #[test]
+    fn major_range_rejects_non_ranges() {
+        assert_eq!(MajorRange::parse("20"), None);
+        assert_eq!(MajorRange::parse("lts"), None);
+        assert_eq!(MajorRange::parse(">=abc"), None);
+        assert_eq!(MajorRange::parse(">="), None);
+    }
+
+    #[test]
+    fn major_range_matches() {
+        assert!(MajorRange::AtLeast(20).matches(20));
+        assert!(!MajorRange::Above(20).matches(20));
+        assert!(MajorRange::AtMost(20).matches(20));
+        assert!(!MajorRange::Below(20).matches(20));
+        assert!(MajorRange::Below(20).matches(18));
+    }
+}
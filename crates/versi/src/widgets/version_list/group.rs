@@ -1,32 +1,47 @@
+use std::collections::{BTreeSet, HashSet};
+
 use iced::widget::{Space, button, column, container, row, text};
 use iced::{Alignment, Element, Length};
 
-use versi_backend::{InstalledVersion, VersionGroup};
+use versi_backend::{InstalledVersion, NodeVersion, VersionGroup};
 use versi_core::ReleaseSchedule;
 
 use crate::icon;
 use crate::message::Message;
-use crate::state::OperationQueue;
+use crate::project_watch::ProjectPin;
+use crate::state::{EnvironmentState, OperationQueue};
 use crate::theme::styles;
+use crate::widgets::status::Status;
 
-use super::filter_version;
 use super::item::version_item_view;
+use super::{Metrics, eol_badge, filter_version};
 
+#[allow(clippy::too_many_arguments)]
 pub(super) fn version_group_view<'a>(
     group: &'a VersionGroup,
-    default: &'a Option<versi_backend::NodeVersion>,
+    env: &'a EnvironmentState,
     search_query: &'a str,
     update_available: Option<String>,
+    note: Option<&'a str>,
     schedule: Option<&ReleaseSchedule>,
+    eol_countdown_days: u32,
     operation_queue: &'a OperationQueue,
     hovered_version: &'a Option<String>,
+    selected_versions: &'a HashSet<String>,
+    protected: &'a BTreeSet<String>,
+    project: Option<(&'a NodeVersion, &'a ProjectPin)>,
+    read_only: bool,
+    metrics: Metrics,
 ) -> Element<'a, Message> {
+    let default = &env.default_version;
     let has_lts = group.versions.iter().any(|v| v.lts_codename.is_some());
     let has_default = group
         .versions
         .iter()
         .any(|v| default.as_ref().map(|d| d == &v.version).unwrap_or(false));
     let is_eol = schedule.map(|s| !s.is_active(group.major)).unwrap_or(false);
+    let today = chrono::Utc::now().date_naive();
+    let countdown = schedule.and_then(|s| s.eol_countdown(group.major, today, eol_countdown_days));
 
     let chevron = if group.is_expanded {
         icon::chevron_down(12.0)
@@ -50,11 +65,17 @@ pub(super) fn version_group_view<'a>(
         );
     }
 
-    if is_eol {
+    if let Some(countdown) = countdown {
+        header_row = header_row.push(eol_badge(countdown, 10));
+    } else if is_eol {
         header_row = header_row.push(
-            container(text("End-of-Life").size(10))
-                .padding([2, 6])
-                .style(styles::badge_eol),
+            container(
+                row![Status::Warning.icon(10.0), text("End-of-Life").size(10)]
+                    .spacing(4)
+                    .align_y(Alignment::Center),
+            )
+            .padding([2, 6])
+            .style(styles::badge_eol),
         );
     }
 
@@ -77,11 +98,25 @@ pub(super) fn version_group_view<'a>(
 
     let mut header_actions = row![].spacing(8).align_y(Alignment::Center);
 
+    header_actions = header_actions.push(
+        button(
+            text(if note.is_some() {
+                "Edit Note"
+            } else {
+                "Add Note"
+            })
+            .size(10),
+        )
+        .on_press(Message::EditMajorNote(group.major))
+        .style(styles::ghost_button)
+        .padding([4, 8]),
+    );
+
     if let Some(new_version) = update_available {
         let version_to_install = new_version.clone();
         header_actions = header_actions.push(
             button(container(text(format!("{} available", new_version)).size(10)).padding([2, 6]))
-                .on_press(Message::StartInstall(version_to_install))
+                .on_press_maybe((!read_only).then_some(Message::StartInstall(version_to_install)))
                 .style(styles::update_badge_button)
                 .padding([0, 4]),
         );
@@ -90,19 +125,24 @@ pub(super) fn version_group_view<'a>(
     if group.is_expanded && group.versions.len() > 1 {
         header_actions = header_actions.push(
             button(text("Keep Latest").size(10))
-                .on_press(Message::RequestBulkUninstallMajorExceptLatest { major: group.major })
+                .on_press_maybe((!read_only).then_some(
+                    Message::RequestBulkUninstallMajorExceptLatest { major: group.major },
+                ))
                 .style(styles::ghost_button)
                 .padding([4, 8]),
         );
         header_actions = header_actions.push(
             button(text("Uninstall All").size(10))
-                .on_press(Message::RequestBulkUninstallMajor { major: group.major })
+                .on_press_maybe(
+                    (!read_only)
+                        .then_some(Message::RequestBulkUninstallMajor { major: group.major }),
+                )
                 .style(styles::ghost_button)
                 .padding([4, 8]),
         );
     }
 
-    let header: Element<Message> = row![
+    let header_row: Element<Message> = row![
         header_button,
         Space::new().width(Length::Fill),
         header_actions,
@@ -110,6 +150,26 @@ pub(super) fn version_group_view<'a>(
     .align_y(Alignment::Center)
     .into();
 
+    let header: Element<Message> = match note {
+        Some(note) => column![
+            header_row,
+            container(
+                row![
+                    Status::Neutral.icon(11.0),
+                    text(note)
+                        .size(11)
+                        .color(iced::Color::from_rgb8(142, 142, 147)),
+                ]
+                .spacing(4)
+                .align_y(Alignment::Center),
+            )
+            .padding([0, 12]),
+        ]
+        .spacing(2)
+        .into(),
+        None => header_row,
+    };
+
     if group.is_expanded {
         let filtered_versions: Vec<&InstalledVersion> = group
             .versions
@@ -119,13 +179,32 @@ pub(super) fn version_group_view<'a>(
 
         let items: Vec<Element<Message>> = filtered_versions
             .iter()
-            .map(|v| version_item_view(v, default, operation_queue, hovered_version))
+            .map(|v| {
+                let version = v.version.to_string();
+                version_item_view(
+                    v,
+                    default,
+                    env.integrity.get(&version),
+                    env.architectures.get(&version).copied(),
+                    env.foreign_arch(&version).is_some(),
+                    countdown,
+                    protected.contains(&version),
+                    project
+                        .filter(|(matched, _)| **matched == v.version)
+                        .map(|(_, pin)| pin),
+                    operation_queue,
+                    hovered_version,
+                    selected_versions,
+                    read_only,
+                    metrics,
+                )
+            })
             .collect();
 
         container(
             column![
                 header,
-                container(column(items).spacing(2)).padding(iced::Padding {
+                container(column(items).spacing(metrics.row_spacing)).padding(iced::Padding {
                     top: 0.0,
                     right: 0.0,
                     bottom: 0.0,
@@ -135,12 +214,12 @@ pub(super) fn version_group_view<'a>(
             .spacing(4),
         )
         .style(styles::card_container)
-        .padding(12)
+        .padding(metrics.card_padding)
         .into()
     } else {
         container(header)
             .style(styles::card_container)
-            .padding(12)
+            .padding(metrics.card_padding)
             .width(Length::Fill)
             .into()
     }
@@ -0,0 +1,163 @@
+use chrono::NaiveDate;
+
+use versi_backend::VersionGroup;
+use versi_core::ReleaseSchedule;
+
+use crate::settings::VersionGrouping;
+
+const CURRENT_SECTION: &str = "Current";
+
+/// A heading in the installed list and the major groups shown under it.
+/// Grouping by major uses a single section without a title.
+#[derive(Debug)]
+pub(super) struct Section<'a> {
+    pub title: Option<String>,
+    pub groups: Vec<&'a VersionGroup>,
+}
+
+/// Splits the major groups into sections for `grouping`, keeping their
+/// order. Sections appear in the order their first group does.
+pub(super) fn sections<'a>(
+    groups: &[&'a VersionGroup],
+    grouping: VersionGrouping,
+    schedule: Option<&ReleaseSchedule>,
+    today: NaiveDate,
+) -> Vec<Section<'a>> {
+    match grouping {
+        VersionGrouping::Major => vec![Section {
+            title: None,
+            groups: groups.to_vec(),
+        }],
+        VersionGrouping::Codename => {
+            let mut sections: Vec<Section<'a>> = Vec::new();
+            for group in groups {
+                let title =
+                    codename(group, schedule, today).unwrap_or_else(|| CURRENT_SECTION.to_string());
+                match sections
+                    .iter_mut()
+                    .find(|s| s.title.as_deref() == Some(title.as_str()))
+                {
+                    Some(section) => section.groups.push(group),
+                    None => sections.push(Section {
+                        title: Some(title),
+                        groups: vec![group],
+                    }),
+                }
+            }
+            sections
+        }
+    }
+}
+
+/// The LTS codename of a major line. The schedule names lines before they
+/// enter LTS, so its codename only counts once the LTS date has passed.
+fn codename(
+    group: &VersionGroup,
+    schedule: Option<&ReleaseSchedule>,
+    today: NaiveDate,
+) -> Option<String> {
+    if let Some(codename) = group.versions.iter().find_map(|v| v.lts_codename.as_ref()) {
+        return Some(capitalize(codename));
+    }
+    let schedule = schedule?;
+    schedule
+        .lts_date(group.major)
+        .filter(|date| *date <= today)
+        .and_then(|_| schedule.codename(group.major))
+        .map(capitalize)
+}
+
+/// Backends report codenames in lowercase (`iron`), the schedule does not.
+fn capitalize(codename: &str) -> String {
+    let mut chars = codename.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use versi_backend::InstalledVersion;
+    use versi_core::VersionSchedule;
+
+    fn group(major: u32, lts_codename: Option<&str>) -> VersionGroup {
+        VersionGroup {
+            major,
+            versions: vec![InstalledVersion {
+                version: format!("v{}.0.0", major).parse().unwrap(),
+                is_default: false,
+                lts_codename: lts_codename.map(str::to_string),
+                install_date: None,
+                disk_size: None,
+            }],
+            is_expanded: false,
+        }
+    }
+
+    fn titles<'a>(sections: &'a [Section]) -> Vec<(Option<&'a str>, Vec<u32>)> {
+        sections
+            .iter()
+            .map(|s| {
+                (
+                    s.title.as_deref(),
+                    s.groups.iter().map(|g| g.major).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn groups_by_codename_with_current_for_the_rest() {
+        let line = |lts: &str, codename: &str| VersionSchedule {
+            start: "2020-01-01".to_string(),
+            lts: Some(lts.to_string()),
+            maintenance: None,
+            end: "2030-01-01".to_string(),
+            codename: Some(codename.to_string()),
+        };
+        let schedule = ReleaseSchedule {
+            versions: HashMap::from([
+                (18, line("2022-10-25", "Hydrogen")),
+                (22, line("2024-10-29", "Jod")),
+                (24, line("2025-10-28", "Krypton")),
+            ]),
+        };
+        let groups = [
+            group(25, None),
+            group(24, None),
+            group(23, None),
+            group(22, None),
+            group(20, Some("iron")),
+            group(18, None),
+        ];
+        let groups: Vec<&VersionGroup> = groups.iter().collect();
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+
+        assert_eq!(
+            titles(&sections(
+                &groups,
+                VersionGrouping::Codename,
+                Some(&schedule),
+                today
+            )),
+            vec![
+                (Some("Current"), vec![25, 24, 23]),
+                (Some("Jod"), vec![22]),
+                (Some("Iron"), vec![20]),
+                (Some("Hydrogen"), vec![18]),
+            ]
+        );
+        assert_eq!(
+            titles(&sections(
+                &groups,
+                VersionGrouping::Major,
+                Some(&schedule),
+                today
+            )),
+            vec![(None, vec![25, 24, 23, 22, 20, 18])]
+        );
+    }
+}
@@ -1,18 +1,38 @@
-use iced::widget::{Space, button, container, mouse_area, row, text};
+use std::collections::HashSet;
+
+use iced::widget::{Space, button, checkbox, container, mouse_area, row, text, tooltip};
 use iced::{Alignment, Element, Length};
 
 use versi_backend::InstalledVersion;
+use versi_core::EolCountdown;
+use versi_platform::BinaryArch;
 
 use crate::icon;
+use crate::integrity::Integrity;
 use crate::message::Message;
+use crate::project_watch::ProjectPin;
 use crate::state::{Operation, OperationQueue};
 use crate::theme::styles;
+use crate::widgets::helpers::{format_bytes, styled_tooltip};
+use crate::widgets::status::{self, Status};
+
+use super::{Metrics, eol_badge};
 
+#[allow(clippy::too_many_arguments)]
 pub(super) fn version_item_view<'a>(
     version: &'a InstalledVersion,
     default: &'a Option<versi_backend::NodeVersion>,
+    integrity: Option<&'a Integrity>,
+    arch: Option<BinaryArch>,
+    foreign_arch: bool,
+    eol: Option<EolCountdown>,
+    protected: bool,
+    project: Option<&'a ProjectPin>,
     operation_queue: &'a OperationQueue,
     hovered_version: &'a Option<String>,
+    selected_versions: &'a HashSet<String>,
+    read_only: bool,
+    metrics: Metrics,
 ) -> Element<'a, Message> {
     let is_default = default
         .as_ref()
@@ -23,21 +43,45 @@ pub(super) fn version_item_view<'a>(
     let version_display = version_str.clone();
     let version_for_default = version_str.clone();
     let version_for_changelog = version_str.clone();
+    let version_for_snippet = version_str.clone();
     let version_for_hover = version_str.clone();
+    let version_for_select = version_str.clone();
+    let version_for_verify = version_str.clone();
+    let version_for_reinstall = version_str.clone();
+    let version_for_click = version_str.clone();
+    let version_for_copy = version_str.clone();
+    let version_for_protect = version_str.clone();
 
     let active_op = operation_queue.active_operation_for(&version_str);
     let is_pending = operation_queue.has_pending_for_version(&version_str);
     let is_busy = active_op.is_some() || is_pending;
+    let actions_disabled = is_busy || read_only;
 
     let is_uninstalling = matches!(active_op, Some(Operation::Uninstall { .. }));
     let is_setting_default = matches!(active_op, Some(Operation::SetDefault { .. }));
 
     let is_hovered = hovered_version.as_ref().is_some_and(|h| h == &version_str);
     let show_actions = is_hovered || is_default;
+    let is_selected = selected_versions.contains(&version_str);
+
+    let select_box: Element<'a, Message> =
+        if is_selected || is_hovered || !selected_versions.is_empty() {
+            checkbox(is_selected)
+                .on_toggle(move |_| Message::VersionSelectionToggled(version_for_select.clone()))
+                .size(14)
+                .into()
+        } else {
+            Space::new().width(Length::Fixed(14.0)).into()
+        };
 
-    let mut row_content = row![text(version_display).size(14).width(Length::Fixed(120.0)),]
-        .spacing(8)
-        .align_y(Alignment::Center);
+    let mut row_content = row![
+        select_box,
+        text(version_display)
+            .size(metrics.version_size)
+            .width(Length::Fixed(120.0)),
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center);
 
     if let Some(lts) = &version.lts_codename {
         row_content = row_content.push(
@@ -47,6 +91,51 @@ pub(super) fn version_item_view<'a>(
         );
     }
 
+    if let Some(eol) = eol {
+        row_content = row_content.push(eol_badge(eol, 11));
+    }
+
+    if let Some(arch) = arch {
+        if foreign_arch {
+            let badge = container(
+                row![Status::Warning.icon(11.0), text(arch.label()).size(11)]
+                    .spacing(4)
+                    .align_y(Alignment::Center),
+            )
+            .padding([2, 6])
+            .style(styles::badge_eol);
+            row_content = row_content.push(styled_tooltip(
+                badge,
+                "Built for another architecture; runs under Rosetta 2",
+                tooltip::Position::Top,
+            ));
+        } else {
+            row_content = row_content.push(
+                text(arch.label())
+                    .size(11)
+                    .color(iced::Color::from_rgb8(142, 142, 147)),
+            );
+        }
+    }
+
+    if protected {
+        row_content = row_content.push(styled_tooltip(
+            icon::lock(12.0),
+            "Protected: skipped by bulk removals",
+            tooltip::Position::Top,
+        ));
+    }
+
+    if let Some(pin) = project {
+        row_content = row_content.push(styled_tooltip(
+            container(text(pin.name()).size(11))
+                .padding([2, 6])
+                .style(styles::badge_lts),
+            format!("Picked for {} in {}", pin.file, pin.root.display()),
+            tooltip::Position::Top,
+        ));
+    }
+
     if is_default {
         row_content = row_content.push(
             container(text("default").size(11))
@@ -55,6 +144,30 @@ pub(super) fn version_item_view<'a>(
         );
     }
 
+    match integrity {
+        Some(Integrity::Checking) => {
+            row_content = row_content.push(
+                text("Verifying...")
+                    .size(11)
+                    .color(iced::Color::from_rgb8(142, 142, 147)),
+            );
+        }
+        Some(Integrity::Healthy) => {
+            row_content = row_content.push(status::label(Status::Success, "Verified", 11.0));
+        }
+        Some(Integrity::Corrupted(reason)) => {
+            let badge = container(
+                row![Status::Error.icon(11.0), text("Corrupted").size(11)]
+                    .spacing(4)
+                    .align_y(Alignment::Center),
+            )
+            .padding([2, 6])
+            .style(styles::badge_error);
+            row_content = row_content.push(styled_tooltip(badge, reason, tooltip::Position::Top));
+        }
+        None => {}
+    }
+
     row_content = row_content.push(Space::new().width(Length::Fill));
 
     if let Some(size) = version.disk_size {
@@ -72,6 +185,46 @@ pub(super) fn version_item_view<'a>(
         styles::row_action_button_hidden
     };
 
+    row_content = row_content.push(
+        button(text("Snippet").size(11))
+            .on_press_maybe(show_actions.then_some(Message::ShowSetupSnippet(version_for_snippet)))
+            .style(action_style)
+            .padding([4, 8]),
+    );
+
+    let is_verifying = matches!(integrity, Some(Integrity::Checking));
+    row_content = row_content.push(
+        button(text("Verify").size(11))
+            .on_press_maybe(
+                (show_actions && !is_busy && !is_verifying)
+                    .then_some(Message::VerifyVersion(version_for_verify)),
+            )
+            .style(action_style)
+            .padding([4, 8]),
+    );
+
+    let is_corrupted = matches!(integrity, Some(Integrity::Corrupted(_)));
+    if !is_corrupted {
+        row_content = row_content.push(
+            button(text("Reinstall").size(11))
+                .on_press_maybe(
+                    (show_actions && !actions_disabled)
+                        .then_some(Message::ReinstallVersion(version_for_reinstall)),
+                )
+                .style(action_style)
+                .padding([4, 8]),
+        );
+    }
+
+    row_content = row_content.push(
+        button(text(if protected { "Unprotect" } else { "Protect" }).size(11))
+            .on_press_maybe(
+                show_actions.then_some(Message::ToggleVersionProtected(version_for_protect)),
+            )
+            .style(action_style)
+            .padding([4, 8]),
+    );
+
     if show_actions {
         row_content = row_content.push(
             button(
@@ -95,26 +248,37 @@ pub(super) fn version_item_view<'a>(
         row_content = row_content.push(
             button(text("Default").size(12))
                 .style(action_style)
-                .padding([6, 12]),
+                .padding(metrics.action_padding),
         );
     } else if is_setting_default {
         row_content = row_content.push(
             button(text("Setting...").size(12))
                 .style(action_style)
-                .padding([6, 12]),
+                .padding(metrics.action_padding),
         );
-    } else if is_busy || !show_actions {
+    } else if actions_disabled || !show_actions {
         row_content = row_content.push(
             button(text("Set Default").size(12))
                 .style(action_style)
-                .padding([6, 12]),
+                .padding(metrics.action_padding),
         );
     } else {
         row_content = row_content.push(
             button(text("Set Default").size(12))
                 .on_press(Message::SetDefault(version_for_default))
                 .style(action_style)
-                .padding([6, 12]),
+                .padding(metrics.action_padding),
+        );
+    }
+
+    if is_corrupted && !is_busy {
+        row_content = row_content.push(
+            button(text("Reinstall").size(12))
+                .on_press_maybe(
+                    (!read_only).then(|| Message::ReinstallVersion(version_str.clone())),
+                )
+                .style(styles::primary_button)
+                .padding(metrics.action_padding),
         );
     }
 
@@ -122,20 +286,20 @@ pub(super) fn version_item_view<'a>(
         row_content = row_content.push(
             button(text("Removing...").size(12))
                 .style(danger_style)
-                .padding([6, 12]),
+                .padding(metrics.action_padding),
         );
-    } else if is_busy || !show_actions {
+    } else if actions_disabled || !show_actions {
         row_content = row_content.push(
             button(text("Uninstall").size(12))
                 .style(danger_style)
-                .padding([6, 12]),
+                .padding(metrics.action_padding),
         );
     } else {
         row_content = row_content.push(
             button(text("Uninstall").size(12))
                 .on_press(Message::RequestUninstall(version_str))
                 .style(danger_style)
-                .padding([6, 12]),
+                .padding(metrics.action_padding),
         );
     }
 
@@ -145,26 +309,12 @@ pub(super) fn version_item_view<'a>(
         |_: &_| iced::widget::container::Style::default()
     };
 
-    let row_container = container(row_content.padding([4, 8])).style(row_style);
+    let row_container = container(row_content.padding(metrics.row_padding)).style(row_style);
 
     mouse_area(row_container)
+        .on_press(Message::VersionRowClicked(version_for_click))
+        .on_middle_press(Message::CopyToClipboard(version_for_copy))
         .on_enter(Message::VersionRowHovered(Some(version_for_hover)))
         .on_exit(Message::VersionRowHovered(None))
         .into()
 }
-
-pub(super) fn format_bytes(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-
-    if bytes >= GB {
-        format!("{:.1} GB", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.1} MB", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.1} KB", bytes as f64 / KB as f64)
-    } else {
-        format!("{} B", bytes)
-    }
-}
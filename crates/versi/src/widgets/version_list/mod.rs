@@ -1,27 +1,52 @@
 mod available;
 mod filters;
 mod group;
+mod grouping;
 mod item;
+mod overview;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
-use iced::widget::{Space, button, column, container, scrollable, text};
+use iced::widget::{Space, button, column, container, row, scrollable, text};
 use iced::{Alignment, Element, Length};
 
 use versi_backend::{InstalledVersion, NodeVersion, RemoteVersion, VersionGroup};
-use versi_core::ReleaseSchedule;
+use versi_core::{EolCountdown, ReleaseSchedule};
 
+use crate::aliases::{AliasMatch, is_partial_version, matches_partial, resolve_alias};
 use crate::message::Message;
-use crate::state::{EnvironmentState, OperationQueue};
+use crate::project_watch::ProjectPin;
+use crate::settings::{ListDensity, VersionGrouping, VersionsLayout};
+use crate::state::{EnvironmentError, EnvironmentState, OperationQueue};
 use crate::theme::styles;
+use crate::widgets::status::Status;
 
-use filters::{filter_available_versions, resolve_alias};
+use filters::{MajorRange, filter_available_versions};
+
+/// Badge counting down to a major's end-of-life, or showing when it ended.
+fn eol_badge<'a>(countdown: EolCountdown, size: u32) -> Element<'a, Message> {
+    container(
+        row![
+            Status::Warning.icon(size as f32),
+            text(countdown.label()).size(size)
+        ]
+        .spacing(4)
+        .align_y(Alignment::Center),
+    )
+    .padding([2, 6])
+    .style(styles::badge_eol)
+    .into()
+}
 
 fn filter_group(group: &VersionGroup, query: &str) -> bool {
     if query.is_empty() {
         return true;
     }
 
+    if let Some(range) = MajorRange::parse(query) {
+        return range.matches(group.major);
+    }
+
     let query_lower = query.to_lowercase();
 
     if query_lower == "lts" {
@@ -38,11 +63,15 @@ fn filter_group(group: &VersionGroup, query: &str) -> bool {
     })
 }
 
-fn filter_version(version: &InstalledVersion, query: &str) -> bool {
+pub fn filter_version(version: &InstalledVersion, query: &str) -> bool {
     if query.is_empty() {
         return true;
     }
 
+    if let Some(range) = MajorRange::parse(query) {
+        return range.matches(version.version.major);
+    }
+
     let query_lower = query.to_lowercase();
 
     if query_lower == "lts" {
@@ -58,17 +87,182 @@ fn filter_version(version: &InstalledVersion, query: &str) -> bool {
             .unwrap_or(false)
 }
 
+/// Call to action for a search that names a release by alias.
+fn alias_install_row<'a>(
+    alias: &AliasMatch<'a>,
+    installed_set: &HashSet<String>,
+    operation_queue: &OperationQueue,
+    read_only: bool,
+) -> Element<'a, Message> {
+    let version = alias.version.version.to_string();
+    if installed_set.contains(&version) {
+        return text(format!("The {} ({}) is installed", alias.label, version))
+            .size(12)
+            .color(iced::Color::from_rgb8(142, 142, 147))
+            .into();
+    }
+
+    let installing = operation_queue.has_active_install(&version)
+        || operation_queue.has_pending_for_version(&version);
+    button(text(format!("Install {} ({})", alias.label, version)).size(13))
+        .on_press_maybe((!read_only && !installing).then_some(Message::StartInstall(version)))
+        .style(styles::primary_button)
+        .padding([8, 14])
+        .into()
+}
+
+/// Call to action for a search that is a partial version such as `20`,
+/// naming the release it resolves to before anything is installed.
+fn partial_install_row<'a>(
+    query: &str,
+    newest: &RemoteVersion,
+    read_only: bool,
+) -> Element<'a, Message> {
+    button(text(format!("Install {} ({})...", query.trim(), newest.version)).size(13))
+        .on_press_maybe((!read_only).then(|| Message::PreviewInstall(query.trim().to_string())))
+        .style(styles::primary_button)
+        .padding([8, 14])
+        .into()
+}
+
+fn environment_error_view<'a>(
+    env: &'a EnvironmentState,
+    error: &'a EnvironmentError,
+) -> Element<'a, Message> {
+    let muted = iced::Color::from_rgb8(142, 142, 147);
+    let mut content = column![text(format!("{}: {}", env.name, error.summary())).size(16)]
+        .spacing(8)
+        .align_x(Alignment::Center);
+
+    if let EnvironmentError::ListFailed(detail) = error {
+        content = content.push(text(detail).size(13));
+    }
+    content = content.push(text(error.remediation()).size(12).color(muted));
+
+    let mut actions = row![].spacing(8);
+    match error {
+        EnvironmentError::ListFailed(_) => {
+            actions = actions.push(
+                button(text("Retry"))
+                    .on_press(Message::RefreshEnvironment)
+                    .style(styles::primary_button)
+                    .padding([8, 16]),
+            );
+            actions = actions.push(
+                button(text("Open Settings"))
+                    .on_press(Message::NavigateToSettings)
+                    .style(styles::secondary_button)
+                    .padding([8, 16]),
+            );
+        }
+        #[cfg(windows)]
+        EnvironmentError::NotRunning => {
+            if let versi_platform::EnvironmentId::Wsl { distro, .. } = &env.id {
+                actions = actions.push(
+                    button(text("Start Distro"))
+                        .on_press(Message::StartWslDistro(distro.clone()))
+                        .style(styles::primary_button)
+                        .padding([8, 16]),
+                );
+            }
+        }
+        #[cfg(windows)]
+        EnvironmentError::BackendMissing => {
+            let detect = match &env.id {
+                versi_platform::EnvironmentId::Msys { .. } => Message::DetectMsysEnvironments,
+                _ => Message::DetectWslEnvironments,
+            };
+            actions = actions.push(
+                button(text("Detect Again"))
+                    .on_press(detect)
+                    .style(styles::primary_button)
+                    .padding([8, 16]),
+            );
+        }
+        #[cfg(not(windows))]
+        _ => {}
+    }
+    content = content.push(Space::new().height(8)).push(actions);
+
+    if let Some(delay) = env.retry_in {
+        content = content.push(
+            text(format!("Retrying automatically in {}s", delay.as_secs()))
+                .size(11)
+                .color(muted),
+        );
+    }
+
+    container(content)
+        .center_x(Length::Fill)
+        .center_y(Length::Fill)
+        .height(Length::Fill)
+        .into()
+}
+
+/// Spacing of rows and groups at a [`ListDensity`].
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Metrics {
+    /// Around each version row.
+    pub row_padding: [u16; 2],
+    /// Of the buttons at the end of a row, which set its height.
+    pub action_padding: [u16; 2],
+    pub version_size: u32,
+    /// Between the rows of a group.
+    pub row_spacing: u32,
+    pub card_padding: u16,
+    /// Between groups.
+    pub group_spacing: u32,
+}
+
+impl Metrics {
+    const COMFORTABLE: Self = Self {
+        row_padding: [4, 8],
+        action_padding: [6, 12],
+        version_size: 14,
+        row_spacing: 2,
+        card_padding: 12,
+        group_spacing: 12,
+    };
+
+    const COMPACT: Self = Self {
+        row_padding: [1, 8],
+        action_padding: [3, 10],
+        version_size: 13,
+        row_spacing: 0,
+        card_padding: 8,
+        group_spacing: 6,
+    };
+
+    pub(super) fn of(density: ListDensity) -> Self {
+        match density {
+            ListDensity::Comfortable => Self::COMFORTABLE,
+            ListDensity::Compact => Self::COMPACT,
+        }
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn view<'a>(
     env: &'a EnvironmentState,
     search_query: &'a str,
     remote_versions: &'a [RemoteVersion],
+    release_dates: &'a HashMap<String, chrono::NaiveDate>,
     latest_by_major: &'a HashMap<u32, NodeVersion>,
     schedule: Option<&'a ReleaseSchedule>,
     operation_queue: &'a OperationQueue,
     hovered_version: &'a Option<String>,
+    selected_versions: &'a HashSet<String>,
+    protected: &'a BTreeSet<String>,
+    project: Option<(&'a NodeVersion, &'a ProjectPin)>,
+    major_notes: &'a BTreeMap<u32, String>,
     search_results_limit: usize,
+    layout: VersionsLayout,
+    grouping: VersionGrouping,
+    density: ListDensity,
+    eol_countdown_days: u32,
+    read_only: bool,
 ) -> Element<'a, Message> {
+    let metrics = Metrics::of(density);
     if env.loading && env.installed_versions.is_empty() {
         return container(
             column![text("Loading versions...").size(16),]
@@ -81,24 +275,10 @@ pub fn view<'a>(
         .into();
     }
 
-    if let Some(error) = &env.error {
-        return container(
-            column![
-                text("Error loading versions").size(16),
-                text(error).size(14),
-                Space::new().height(16),
-                button(text("Retry"))
-                    .on_press(Message::RefreshEnvironment)
-                    .style(styles::primary_button)
-                    .padding([8, 16]),
-            ]
-            .spacing(8)
-            .align_x(Alignment::Center),
-        )
-        .center_x(Length::Fill)
-        .center_y(Length::Fill)
-        .height(Length::Fill)
-        .into();
+    if let Some(error) = &env.error
+        && env.installed_versions.is_empty()
+    {
+        return environment_error_view(env, error);
     }
 
     let filtered_groups: Vec<&VersionGroup> = env
@@ -107,49 +287,97 @@ pub fn view<'a>(
         .filter(|g| filter_group(g, search_query))
         .collect();
 
-    let default_version = &env.default_version;
-
     let mut content_items: Vec<Element<Message>> = Vec::new();
 
-    if !filtered_groups.is_empty() && search_query.is_empty() {
-        for g in &filtered_groups {
-            let installed_latest = g.versions.iter().map(|v| &v.version).max();
-            let update_available = latest_by_major.get(&g.major).and_then(|latest| {
-                installed_latest.and_then(|installed| {
-                    if latest > installed {
-                        Some(latest.to_string())
-                    } else {
-                        None
-                    }
-                })
-            });
-            content_items.push(group::version_group_view(
-                g,
-                default_version,
-                search_query,
-                update_available,
-                schedule,
-                operation_queue,
-                hovered_version,
-            ));
+    if !filtered_groups.is_empty() && search_query.is_empty() && layout == VersionsLayout::Overview
+    {
+        content_items.push(overview::overview_view(
+            &filtered_groups,
+            latest_by_major,
+            major_notes,
+            schedule,
+            eol_countdown_days,
+            operation_queue,
+            read_only,
+        ));
+    } else if !filtered_groups.is_empty() && search_query.is_empty() {
+        let sections = grouping::sections(
+            &filtered_groups,
+            grouping,
+            schedule,
+            chrono::Local::now().date_naive(),
+        );
+        for section in sections {
+            if let Some(title) = section.title {
+                content_items.push(
+                    container(text(title).size(14))
+                        .padding(iced::Padding::new(0.0).top(8.0).left(4.0))
+                        .into(),
+                );
+            }
+            for g in section.groups {
+                let installed_latest = g.versions.iter().map(|v| &v.version).max();
+                let update_available = latest_by_major.get(&g.major).and_then(|latest| {
+                    installed_latest.and_then(|installed| {
+                        if latest > installed {
+                            Some(latest.to_string())
+                        } else {
+                            None
+                        }
+                    })
+                });
+                content_items.push(group::version_group_view(
+                    g,
+                    env,
+                    search_query,
+                    update_available,
+                    major_notes.get(&g.major).map(String::as_str),
+                    schedule,
+                    eol_countdown_days,
+                    operation_queue,
+                    hovered_version,
+                    selected_versions,
+                    protected,
+                    project,
+                    read_only,
+                    metrics,
+                ));
+            }
         }
     }
 
     if !search_query.is_empty() {
-        let alias_resolved = resolve_alias(remote_versions, search_query);
-        let available_list =
-            filter_available_versions(remote_versions, search_query, search_results_limit);
+        let alias = resolve_alias(
+            remote_versions,
+            schedule,
+            search_query,
+            chrono::Local::now().date_naive(),
+        );
+        let available_list = filter_available_versions(
+            remote_versions,
+            alias.as_ref(),
+            search_query,
+            search_results_limit,
+        );
 
         if !available_list.is_empty() {
             let mut card_items: Vec<Element<Message>> = Vec::new();
 
-            if alias_resolved.is_some() {
-                card_items.push(
-                    text(format!("\"{}\" resolves to:", search_query))
-                        .size(12)
-                        .color(iced::Color::from_rgb8(142, 142, 147))
-                        .into(),
-                );
+            if let Some(alias) = &alias {
+                card_items.push(alias_install_row(
+                    alias,
+                    &env.installed_set,
+                    operation_queue,
+                    read_only,
+                ));
+                card_items.push(Space::new().height(4).into());
+            } else if is_partial_version(search_query)
+                && let Some(newest) = remote_versions
+                    .iter()
+                    .filter(|v| matches_partial(search_query, &v.version))
+                    .max_by_key(|v| &v.version)
+            {
+                card_items.push(partial_install_row(search_query, newest, read_only));
                 card_items.push(Space::new().height(4).into());
             }
 
@@ -157,16 +385,20 @@ pub fn view<'a>(
                 card_items.push(available::available_version_row(
                     v,
                     schedule,
+                    eol_countdown_days,
                     operation_queue,
                     &env.installed_set,
                     hovered_version,
+                    release_dates,
+                    read_only,
+                    metrics,
                 ));
             }
 
             content_items.push(
                 container(column(card_items).spacing(4))
                     .style(styles::card_container)
-                    .padding(12)
+                    .padding(metrics.card_padding)
                     .into(),
             );
         }
@@ -193,7 +425,7 @@ pub fn view<'a>(
 
     scrollable(
         column(content_items)
-            .spacing(12)
+            .spacing(metrics.group_spacing)
             .padding(iced::Padding::new(0.0).right(32.0)),
     )
     .height(Length::Fill)
@@ -0,0 +1,180 @@
+use std::collections::{BTreeMap, HashMap};
+
+use iced::widget::{Space, button, column, container, row, text};
+use iced::{Alignment, Element, Length};
+
+use versi_backend::{NodeVersion, VersionGroup};
+use versi_core::{EolCountdown, ReleaseSchedule};
+
+use crate::message::Message;
+use crate::state::OperationQueue;
+use crate::theme::styles;
+
+use crate::widgets::helpers::format_bytes;
+
+use super::eol_badge;
+
+pub(super) fn overview_view<'a>(
+    groups: &[&'a VersionGroup],
+    latest_by_major: &'a HashMap<u32, NodeVersion>,
+    major_notes: &'a BTreeMap<u32, String>,
+    schedule: Option<&'a ReleaseSchedule>,
+    eol_countdown_days: u32,
+    operation_queue: &'a OperationQueue,
+    read_only: bool,
+) -> Element<'a, Message> {
+    let mut cards = row![].spacing(12);
+
+    for group in groups {
+        cards = cards.push(major_card(
+            group,
+            latest_by_major.get(&group.major),
+            major_notes.get(&group.major).map(String::as_str),
+            schedule,
+            eol_countdown_days,
+            operation_queue,
+            read_only,
+        ));
+    }
+
+    cards.wrap().vertical_spacing(12).into()
+}
+
+fn major_card<'a>(
+    group: &'a VersionGroup,
+    latest_available: Option<&'a NodeVersion>,
+    note: Option<&'a str>,
+    schedule: Option<&'a ReleaseSchedule>,
+    eol_countdown_days: u32,
+    operation_queue: &'a OperationQueue,
+    read_only: bool,
+) -> Element<'a, Message> {
+    let muted = iced::Color::from_rgb8(142, 142, 147);
+    let latest_installed = group.versions.iter().map(|v| &v.version).max();
+
+    let mut title = row![text(format!("Node {}", group.major)).size(16)]
+        .spacing(8)
+        .align_y(Alignment::Center);
+
+    let codename = schedule
+        .and_then(|s| s.codename(group.major))
+        .map(str::to_string)
+        .or_else(|| group.versions.iter().find_map(|v| v.lts_codename.clone()));
+    if let Some(codename) = codename {
+        title = title.push(
+            container(text(format!("LTS: {}", codename)).size(10))
+                .padding([2, 6])
+                .style(styles::badge_lts),
+        );
+    }
+
+    let today = chrono::Utc::now().date_naive();
+    if let Some(countdown) =
+        schedule.and_then(|s| s.eol_countdown(group.major, today, eol_countdown_days))
+    {
+        title = title.push(eol_badge(countdown, 10));
+    }
+    let support_text = match schedule.and_then(|s| s.end_date(group.major)) {
+        Some(end) => EolCountdown::new(end, today).label(),
+        None => "EOL date unknown".to_string(),
+    };
+
+    let disk_usage: u64 = group.versions.iter().filter_map(|v| v.disk_size).sum();
+
+    let stat = |label: &'static str, value: String| {
+        row![
+            text(label)
+                .size(12)
+                .color(muted)
+                .width(Length::Fixed(110.0)),
+            text(value).size(12),
+        ]
+        .align_y(Alignment::Center)
+    };
+
+    let mut stats = column![
+        stat(
+            "Latest installed",
+            latest_installed
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+        ),
+        stat(
+            "Latest available",
+            latest_available
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+        ),
+        stat("Installed", group.versions.len().to_string()),
+        stat("Support", support_text),
+    ]
+    .spacing(4);
+
+    if disk_usage > 0 {
+        stats = stats.push(stat("Disk usage", format_bytes(disk_usage)));
+    }
+    if let Some(note) = note {
+        stats = stats.push(stat("Note", note.to_string()));
+    }
+
+    let mut actions = row![].spacing(8).align_y(Alignment::Center);
+
+    let update_target = latest_available
+        .filter(|latest| latest_installed.is_some_and(|installed| *latest > installed));
+    if let Some(latest) = update_target {
+        let version = latest.to_string();
+        let is_busy = read_only
+            || operation_queue.has_active_install(&version)
+            || operation_queue.has_pending_for_version(&version);
+        let label = operation_queue.transfer_for(&version).map_or_else(
+            || format!("Update to {}", version),
+            |t| t.status("Updating"),
+        );
+        actions = actions.push(
+            button(text(label).size(11))
+                .on_press_maybe((!is_busy).then(|| Message::StartInstall(version.clone())))
+                .style(styles::primary_button)
+                .padding([4, 10]),
+        );
+    }
+
+    if group.versions.len() > 1 {
+        actions = actions.push(
+            button(text("Clean Old Patches").size(11))
+                .on_press_maybe((!read_only).then_some(
+                    Message::RequestBulkUninstallMajorExceptLatest { major: group.major },
+                ))
+                .style(styles::secondary_button)
+                .padding([4, 10]),
+        );
+    }
+
+    actions = actions.push(
+        button(
+            text(if note.is_some() {
+                "Edit Note"
+            } else {
+                "Add Note"
+            })
+            .size(11),
+        )
+        .on_press(Message::EditMajorNote(group.major))
+        .style(styles::secondary_button)
+        .padding([4, 10]),
+    );
+
+    container(
+        column![
+            title,
+            Space::new().height(4),
+            stats,
+            Space::new().height(4),
+            actions
+        ]
+        .spacing(4),
+    )
+    .style(styles::card_container)
+    .padding(16)
+    .width(Length::Fixed(300.0))
+    .into()
+}
@@ -0,0 +1,55 @@
+#!/bin/bash
+# Pins the fnm release Versi's onboarding installs, with the SHA-256 of
+# each asset. The version must be given, so that bumping it is a reviewed
+# change.
+#
+#   ./scripts/pin-fnm.sh 1.38.1          downloads the assets and writes the pins
+#   ./scripts/pin-fnm.sh --check 1.38.1  fails unless the committed pins match
+
+set -euo pipefail
+
+CHECK=false
+if [ "${1:-}" = "--check" ]; then
+    CHECK=true
+    shift
+fi
+if [ $# -ne 1 ]; then
+    echo "usage: $0 [--check] <fnm version>" >&2
+    exit 2
+fi
+
+ASSETS=(fnm-linux.zip fnm-arm64.zip fnm-arm32.zip fnm-macos.zip fnm-windows.zip)
+OUT="$(cd "$(dirname "$0")/.." && pwd)/crates/versi-fnm/fnm-release.json"
+VERSION="${1#v}"
+
+if $CHECK && [ "$(jq -r .version "$OUT")" != "$VERSION" ]; then
+    echo "$OUT pins fnm $(jq -r .version "$OUT"), not $VERSION" >&2
+    exit 1
+fi
+
+TMP="$(mktemp -d)"
+trap 'rm -rf "$TMP"' EXIT
+
+CHECKSUMS='{}'
+for ASSET in "${ASSETS[@]}"; do
+    echo "Downloading $ASSET of fnm $VERSION..."
+    curl -fsSL -o "$TMP/$ASSET" "https://github.com/Schniz/fnm/releases/download/v$VERSION/$ASSET"
+    SUM="$(sha256sum "$TMP/$ASSET" | cut -d' ' -f1)"
+    if $CHECK; then
+        PINNED="$(jq -r --arg asset "$ASSET" '.assets[$asset] // empty' "$OUT")"
+        if [ "$PINNED" != "$SUM" ]; then
+            echo "$ASSET of fnm $VERSION is $SUM, but ${PINNED:-nothing} is pinned" >&2
+            exit 1
+        fi
+    fi
+    CHECKSUMS="$(jq --arg asset "$ASSET" --arg sum "$SUM" '. + {($asset): $sum}' <<< "$CHECKSUMS")"
+done
+
+if $CHECK; then
+    echo "Pinned checksums of fnm $VERSION match the release"
+    exit 0
+fi
+
+jq -n --arg version "$VERSION" --argjson assets "$CHECKSUMS" \
+    '{version: $version, assets: $assets}' > "$OUT"
+echo "Pinned fnm $VERSION in $OUT"